@@ -3,7 +3,7 @@ use gitai::architectural_impact::{
     git_state_analyzer::GitStateAnalyzer, ArchitecturalImpactAnalysis, BreakingChange,
     BreakingChangeType, ImpactLevel,
 };
-use gitai::tree_sitter::{ClassInfo, FunctionInfo, StructuralSummary};
+use gitai::tree_sitter::{ClassInfo, FunctionInfo, Param, StructuralSummary};
 /// 创建一个大型的测试 StructuralSummary
 fn create_large_summary(function_count: usize, class_count: usize) -> StructuralSummary {
     let mut functions = Vec::new();
@@ -12,10 +12,16 @@ fn create_large_summary(function_count: usize, class_count: usize) -> Structural
             name: format!("function_{i}"),
             visibility: Some(if i % 2 == 0 { "public" } else { "private" }.to_string()),
             is_async: i % 3 == 0,
-            parameters: vec!["String".to_string(); i % 5],
+            parameters: vec!["arg: String".to_string(); i % 5],
+            params: vec![Param::typed("arg", "String"); i % 5],
             return_type: Some(format!("Type{}", i % 10)),
             line_start: i * 10,
             line_end: i * 10 + 8,
+            generics: vec![],
+            annotations: Vec::new(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
         });
     }
 
@@ -30,6 +36,9 @@ fn create_large_summary(function_count: usize, class_count: usize) -> Structural
             line_end: i * 50 + 45,
             extends: None,
             implements: vec![],
+            visibility: Some("public".to_string()),
+            generics: vec![],
+            annotations: Vec::new(),
         });
     }
 
@@ -43,6 +52,7 @@ fn create_large_summary(function_count: usize, class_count: usize) -> Structural
         exports: vec![],
         complexity_hints: vec![],
         calls: vec![],
+        truncated: false,
     }
 }
 
@@ -82,7 +92,9 @@ fn benchmark_ast_comparison(c: &mut Criterion) {
 
     // 修改一些函数和类
     for i in 0..10 {
-        after.functions[i].parameters.push("NewParam".to_string());
+        let func = &mut after.functions[i];
+        func.parameters.push("extra: NewParam".to_string());
+        func.params.push(Param::typed("extra", "NewParam"));
     }
     for i in 0..5 {
         after.classes[i].methods.push("new_method".to_string());
@@ -0,0 +1,47 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use gitai::evaluation::{analyze_duplicates_with, DuplicateOptions};
+use std::path::Path;
+
+/// 生成 families 组文件，每组 variants 个仅有一行差异的变体
+fn create_synthetic_tree(root: &Path, families: usize, variants: usize) {
+    for f in 0..families {
+        let dir = root.join(format!("m{}", f % 32));
+        std::fs::create_dir_all(&dir).unwrap();
+        let lines = 20 + f % 200;
+        for v in 0..variants {
+            let body: Vec<String> = (0..lines)
+                .map(|k| {
+                    if k == 0 {
+                        format!("// family {f} variant {v}")
+                    } else {
+                        format!("let f{f}_{k} = {k};")
+                    }
+                })
+                .collect();
+            std::fs::write(dir.join(format!("f{f}_v{v}.rs")), body.join("\n")).unwrap();
+        }
+    }
+}
+
+fn benchmark_duplicates(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    create_synthetic_tree(dir.path(), 2000, 5);
+
+    let mut group = c.benchmark_group("duplicates_10k_files");
+    group.sample_size(10);
+    group.bench_function("default_budget", |b| {
+        let options = DuplicateOptions::default();
+        b.iter(|| analyze_duplicates_with(black_box(dir.path()), &[], &options))
+    });
+    group.bench_function("1mb_budget", |b| {
+        let options = DuplicateOptions {
+            memory_budget: 1024 * 1024,
+            ..Default::default()
+        };
+        b.iter(|| analyze_duplicates_with(black_box(dir.path()), &[], &options))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_duplicates);
+criterion_main!(benches);
@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::project_insights::InsightsGenerator;
+use crate::project_metadata::ProjectMetadata;
 use crate::prompts::{PromptContext, PromptManager};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -10,6 +11,28 @@ struct AiRequest {
     model: String,
     messages: Vec<AiMessage>,
     temperature: f32,
+    // 未设置的采样参数不发送，交给服务端默认值；不认识 seed 的服务通常会忽略该字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+impl AiRequest {
+    /// 按配置中的模型与采样参数构建请求
+    fn new(config: &Config, messages: Vec<AiMessage>) -> Self {
+        let sampling = config.ai.sampling();
+        Self {
+            model: config.ai.model.clone(),
+            messages,
+            temperature: sampling.temperature,
+            top_p: sampling.top_p,
+            max_tokens: sampling.max_tokens,
+            seed: sampling.seed,
+        }
+    }
 }
 
 /// AI消息
@@ -23,11 +46,11 @@ pub async fn call_ai(
     config: &Config,
     prompt: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = crate::http::client()?;
 
-    let request = AiRequest {
-        model: config.ai.model.clone(),
-        messages: vec![
+    let request = AiRequest::new(
+        config,
+        vec![
             AiMessage {
                 role: "system".to_string(),
                 content: "You are a helpful assistant for Git operations.".to_string(),
@@ -37,8 +60,7 @@ pub async fn call_ai(
                 content: prompt.to_string(),
             },
         ],
-        temperature: config.ai.temperature,
-    };
+    );
 
     let mut req = client.post(&config.ai.api_url).json(&request);
     if let Some(ref key) = config.ai.api_key {
@@ -115,16 +137,15 @@ pub async fn call_ai_with_template(
             .unwrap_or(rendered_prompt.len())]
     );
 
-    let client = reqwest::Client::new();
+    let client = crate::http::client()?;
 
-    let request = AiRequest {
-        model: config.ai.model.clone(),
-        messages: vec![AiMessage {
+    let request = AiRequest::new(
+        config,
+        vec![AiMessage {
             role: "user".to_string(),
             content: rendered_prompt,
         }],
-        temperature: config.ai.temperature,
-    };
+    );
 
     let mut req = client.post(&config.ai.api_url).json(&request);
     if let Some(ref key) = config.ai.api_key {
@@ -217,6 +238,7 @@ fn extract_content_from_ai_response(v: &Value) -> Option<String> {
 
 pub async fn review_code_with_template(
     config: &Config,
+    repo_dir: &std::path::Path,
     diff: &str,
     tree_sitter_summary: Option<&str>,
     security_scan_results: &str,
@@ -229,12 +251,20 @@ pub async fn review_code_with_template(
         .with_variable("devops_issue_context", devops_issue_context)
         .with_variable("dependency_insights", dependency_insights);
 
+    let structural = tree_sitter_summary
+        .and_then(|s| serde_json::from_str::<crate::tree_sitter::StructuralSummary>(s).ok());
+    context = context.with_project_metadata(&ProjectMetadata::for_repo(
+        diff,
+        structural.as_ref(),
+        repo_dir,
+    ));
+
     // 使用增强的架构洞察替代简单的统计
     if let Some(summary) = tree_sitter_summary {
         // 尝试解析为 StructuralSummary 并生成架构洞察
-        match serde_json::from_str::<crate::tree_sitter::StructuralSummary>(summary) {
-            Ok(structural_summary) => {
-                let insights = InsightsGenerator::generate(&structural_summary, None);
+        match &structural {
+            Some(structural_summary) => {
+                let insights = InsightsGenerator::generate(structural_summary, None);
 
                 // 使用 ProjectInsights 的 to_ai_context 方法
                 let ai_context = insights.to_ai_context();
@@ -271,7 +301,7 @@ pub async fn review_code_with_template(
 
                 context = context.with_variable("tree_sitter_summary", &architecture_context);
             }
-            Err(_) => {
+            None => {
                 // 如果解析失败，使用原始摘要
                 context = context.with_variable("tree_sitter_summary", summary);
             }
@@ -283,39 +313,84 @@ pub async fn review_code_with_template(
     call_ai_with_template(config, "review", &context).await
 }
 
+/// 使用提交信息模板生成提交信息；`template` 为模板名（`commit` 或 `commit-<风格>`）
 pub async fn generate_commit_message_with_template(
     config: &Config,
+    repo_dir: &std::path::Path,
+    template: &str,
     diff: &str,
     tree_sitter_summary: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let mut context = PromptContext::new().with_variable("diff", diff);
+    let structural = tree_sitter_summary
+        .and_then(|s| serde_json::from_str::<crate::tree_sitter::StructuralSummary>(s).ok());
+    let mut context = PromptContext::new()
+        .with_variable("diff", diff)
+        .with_project_metadata(&ProjectMetadata::for_repo(
+            diff,
+            structural.as_ref(),
+            repo_dir,
+        ));
 
     // 如果有结构分析，添加架构影响信息
-    if let Some(summary) = tree_sitter_summary {
-        if let Ok(structural_summary) =
-            serde_json::from_str::<crate::tree_sitter::StructuralSummary>(summary)
-        {
-            let insights = InsightsGenerator::generate(&structural_summary, None);
-
-            // 为提交信息提供关键架构影响信息
-            let impact_summary = format!(
-                "架构影响: 函数变更{}个, 类变更{}个, 复杂度热点{}个, API影响{}",
-                structural_summary.functions.len(),
-                structural_summary.classes.len(),
-                insights.quality_hotspots.complexity_hotspots.len(),
-                if !insights.impact_analysis.breaking_changes.is_empty() {
-                    format!(
-                        "有{}个破坏性变更",
-                        insights.impact_analysis.breaking_changes.len()
-                    )
-                } else {
-                    "兼容".to_string()
-                }
-            );
+    if let Some(structural_summary) = &structural {
+        let insights = InsightsGenerator::generate(structural_summary, None);
+
+        // 为提交信息提供关键架构影响信息
+        let impact_summary = format!(
+            "架构影响: 函数变更{}个, 类变更{}个, 复杂度热点{}个, API影响{}",
+            structural_summary.functions.len(),
+            structural_summary.classes.len(),
+            insights.quality_hotspots.complexity_hotspots.len(),
+            if !insights.impact_analysis.breaking_changes.is_empty() {
+                format!(
+                    "有{}个破坏性变更",
+                    insights.impact_analysis.breaking_changes.len()
+                )
+            } else {
+                "兼容".to_string()
+            }
+        );
 
-            context = context.with_variable("architecture_impact", &impact_summary);
-        }
+        context = context.with_variable("architecture_impact", &impact_summary);
+    }
+
+    call_ai_with_template(config, template, &context).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_json(config: &Config) -> Value {
+        let messages = vec![AiMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }];
+        serde_json::to_value(AiRequest::new(config, messages)).unwrap()
     }
 
-    call_ai_with_template(config, "commit", &context).await
+    #[test]
+    fn test_request_sampling_parameters() {
+        let mut config = Config::default();
+        let v = request_json(&config);
+        assert!(v.get("top_p").is_none());
+        assert!(v.get("max_tokens").is_none());
+        assert!(v.get("seed").is_none());
+
+        config.ai.top_p = Some(0.9);
+        config.ai.max_tokens = Some(512);
+        let v = request_json(&config);
+        assert_eq!(v["max_tokens"], 512);
+        assert!((v["top_p"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+
+        // 确定性模式：温度为 0、不发送 top_p，并带上固定种子
+        config.ai.deterministic = true;
+        let v = request_json(&config);
+        assert_eq!(v["temperature"], 0.0);
+        assert!(v.get("top_p").is_none());
+        assert_eq!(v["max_tokens"], 512);
+        assert_eq!(v["seed"], crate::config::DETERMINISTIC_SEED);
+        config.ai.seed = Some(7);
+        assert_eq!(request_json(&config)["seed"], 7);
+    }
 }
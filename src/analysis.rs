@@ -25,6 +25,8 @@ pub struct OperationContext {
     pub impact_scope: Option<ImpactScope>,
     /// 级联效应
     pub cascade_effects: Vec<CascadeEffect>,
+    /// 被分析的仓库目录，默认为当前目录
+    pub repo_dir: PathBuf,
     /// 操作特定的选项
     pub options: OperationOptions,
 }
@@ -63,10 +65,17 @@ impl OperationContext {
             dependency_graph: None,
             impact_scope: None,
             cascade_effects: Vec::new(),
+            repo_dir: PathBuf::from("."),
             options: OperationOptions::default(),
         }
     }
 
+    /// 设置被分析的仓库目录
+    pub fn with_repo_dir(mut self, repo_dir: PathBuf) -> Self {
+        self.repo_dir = repo_dir;
+        self
+    }
+
     /// 设置代码变更
     pub fn with_diff(mut self, diff: String) -> Self {
         self.diff = diff;
@@ -276,6 +285,7 @@ impl Analyzer {
             // 依赖洞察：在 Analyzer 流程中暂无依赖图，传入空文本（留给 review --full 路径）
             match crate::ai::review_code_with_template(
                 &context.config,
+                &context.repo_dir,
                 &context.diff,
                 Some(&tree_sitter_info),
                 &security_scan_results,
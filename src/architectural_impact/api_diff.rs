@@ -0,0 +1,378 @@
+// 公共 API 差异检测模块
+// 对比两个 Git 引用下的公共函数/类型，列出 API 变化并标记破坏性变更
+
+use super::git_state_analyzer::infer_language_from_path;
+use crate::tree_sitter::{ClassInfo, FunctionInfo, TreeSitterManager};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// API 条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiItemKind {
+    Function,
+    Class,
+}
+
+/// 单个公共 API 条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiItem {
+    pub kind: ApiItemKind,
+    pub name: String,
+    pub file_path: String,
+    /// 归一化后的签名文本，用于比较
+    pub signature: String,
+    pub line: usize,
+}
+
+impl ApiItem {
+    pub fn from_function(func: &FunctionInfo, file_path: &str) -> Self {
+        let mut signature = String::new();
+        if func.is_async {
+            signature.push_str("async ");
+        }
+        signature.push_str(&func.name);
+        if !func.generics.is_empty() {
+            signature.push_str(&format!("<{}>", func.generics.join(", ")));
+        }
+        signature.push_str(&format!("({})", func.parameters.join(", ")));
+        if let Some(ret) = &func.return_type {
+            signature.push_str(&format!(" -> {ret}"));
+        }
+
+        Self {
+            kind: ApiItemKind::Function,
+            name: func.name.clone(),
+            file_path: file_path.to_string(),
+            signature,
+            line: func.line_start,
+        }
+    }
+
+    pub fn from_class(class: &ClassInfo, file_path: &str) -> Self {
+        let mut signature = class.name.clone();
+        if !class.generics.is_empty() {
+            signature.push_str(&format!("<{}>", class.generics.join(", ")));
+        }
+        if let Some(base) = &class.extends {
+            signature.push_str(&format!(" extends {base}"));
+        }
+        if !class.implements.is_empty() {
+            signature.push_str(&format!(" implements {}", class.implements.join(", ")));
+        }
+
+        Self {
+            kind: ApiItemKind::Class,
+            name: class.name.clone(),
+            file_path: file_path.to_string(),
+            signature,
+            line: class.line_start,
+        }
+    }
+}
+
+/// API 变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    Added,
+    Removed,
+    SignatureChanged,
+}
+
+/// 单条 API 变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiChange {
+    pub change: ApiChangeKind,
+    pub kind: ApiItemKind,
+    pub name: String,
+    pub file_path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// 是否为破坏性变更（删除或签名变化）
+    pub breaking: bool,
+}
+
+/// API 差异报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiDiffReport {
+    pub base: String,
+    pub head: String,
+    pub base_api_count: usize,
+    pub head_api_count: usize,
+    pub changes: Vec<ApiChange>,
+}
+
+impl ApiDiffReport {
+    pub fn count(&self, kind: ApiChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.change == kind).count()
+    }
+
+    pub fn breaking_count(&self) -> usize {
+        self.changes.iter().filter(|c| c.breaking).count()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.breaking)
+    }
+
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "🔍 公共 API 差异: {} → {}\n   公共 API: {} → {}\n   新增 {} / 删除 {} / 签名变化 {}（破坏性 {}）\n",
+            self.base,
+            self.head,
+            self.base_api_count,
+            self.head_api_count,
+            self.count(ApiChangeKind::Added),
+            self.count(ApiChangeKind::Removed),
+            self.count(ApiChangeKind::SignatureChanged),
+            self.breaking_count()
+        );
+
+        if self.changes.is_empty() {
+            out.push_str("\n✅ 公共 API 无变化\n");
+            return out;
+        }
+
+        out.push('\n');
+        for change in &self.changes {
+            let marker = if change.breaking { "⚠️ " } else { "" };
+            let line = match change.change {
+                ApiChangeKind::Added => format!(
+                    "  + {} {}",
+                    change.file_path,
+                    change.after.as_deref().unwrap_or(&change.name)
+                ),
+                ApiChangeKind::Removed => format!(
+                    "  - {}{} {}",
+                    marker,
+                    change.file_path,
+                    change.before.as_deref().unwrap_or(&change.name)
+                ),
+                ApiChangeKind::SignatureChanged => format!(
+                    "  ~ {}{} {} => {}",
+                    marker,
+                    change.file_path,
+                    change.before.as_deref().unwrap_or_default(),
+                    change.after.as_deref().unwrap_or_default()
+                ),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+
+        if self.has_breaking_changes() {
+            out.push_str(&format!(
+                "\n⚠️  检测到 {} 个破坏性 API 变更\n",
+                self.breaking_count()
+            ));
+        }
+        out
+    }
+}
+
+type ApiKey = (ApiItemKind, String, String);
+
+fn group_signatures(items: &[ApiItem]) -> BTreeMap<ApiKey, BTreeSet<String>> {
+    let mut grouped: BTreeMap<ApiKey, BTreeSet<String>> = BTreeMap::new();
+    for item in items {
+        grouped
+            .entry((item.kind, item.file_path.clone(), item.name.clone()))
+            .or_default()
+            .insert(item.signature.clone());
+    }
+    grouped
+}
+
+/// 对比两组公共 API，返回按文件与名称排序的变化列表
+///
+/// 同一文件同名条目的签名不一致时视为签名变化；删除与签名变化均为破坏性变更。
+pub fn diff_api_items(before: &[ApiItem], after: &[ApiItem]) -> Vec<ApiChange> {
+    let before = group_signatures(before);
+    let after = group_signatures(after);
+    let empty = BTreeSet::new();
+
+    let keys: BTreeSet<&ApiKey> = before.keys().chain(after.keys()).collect();
+    let mut changes = Vec::new();
+
+    for key in keys {
+        let (kind, file_path, name) = key;
+        let old = before.get(key).unwrap_or(&empty);
+        let new = after.get(key).unwrap_or(&empty);
+        let removed: Vec<&String> = old.difference(new).collect();
+        let added: Vec<&String> = new.difference(old).collect();
+
+        let change =
+            |change: ApiChangeKind, before: Option<&String>, after: Option<&String>| ApiChange {
+                change,
+                kind: *kind,
+                name: name.clone(),
+                file_path: file_path.clone(),
+                before: before.cloned(),
+                after: after.cloned(),
+                breaking: change != ApiChangeKind::Added,
+            };
+
+        // 签名一一配对，多出的部分视为新增或删除（如重载）
+        let paired = removed.len().min(added.len());
+        for (old_sig, new_sig) in removed.iter().zip(added.iter()) {
+            changes.push(change(
+                ApiChangeKind::SignatureChanged,
+                Some(old_sig),
+                Some(new_sig),
+            ));
+        }
+        for old_sig in &removed[paired..] {
+            changes.push(change(ApiChangeKind::Removed, Some(old_sig), None));
+        }
+        for new_sig in &added[paired..] {
+            changes.push(change(ApiChangeKind::Added, None, Some(new_sig)));
+        }
+    }
+
+    changes
+}
+
+/// 收集指定 Git 引用下所有受支持源文件中的公共 API（visibility == "public"）
+pub async fn collect_public_api(
+    git_ref: &str,
+    manager: &mut TreeSitterManager,
+) -> Result<Vec<ApiItem>, DynError> {
+    let listing = crate::git::run_git(&[
+        "ls-tree".to_string(),
+        "-r".to_string(),
+        "--name-only".to_string(),
+        git_ref.to_string(),
+    ])?;
+
+    let is_public = |v: &Option<String>| v.as_deref() == Some("public");
+    let mut items = Vec::new();
+
+    for path in listing.lines().map(str::trim).filter(|p| !p.is_empty()) {
+        let Ok(language) = infer_language_from_path(path) else {
+            continue;
+        };
+        let content = match crate::git::run_git(&["show".to_string(), format!("{git_ref}:{path}")])
+        {
+            Ok(content) => content,
+            Err(e) => {
+                log::debug!("跳过无法读取的文件 {git_ref}:{path}: {e}");
+                continue;
+            }
+        };
+        let summary = match manager.analyze_structure(&content, language) {
+            Ok(summary) => summary,
+            Err(e) => {
+                log::debug!("跳过无法分析的文件 {path}: {e}");
+                continue;
+            }
+        };
+
+        items.extend(
+            summary
+                .functions
+                .iter()
+                .filter(|f| is_public(&f.visibility))
+                .map(|f| ApiItem::from_function(f, path)),
+        );
+        items.extend(
+            summary
+                .classes
+                .iter()
+                .filter(|c| is_public(&c.visibility))
+                .map(|c| ApiItem::from_class(c, path)),
+        );
+    }
+
+    Ok(items)
+}
+
+/// 对比两个 Git 引用之间的公共 API
+pub async fn analyze_api_diff(base: &str, head: &str) -> Result<ApiDiffReport, DynError> {
+    let mut manager = TreeSitterManager::new().await?;
+    let before = collect_public_api(base, &mut manager).await?;
+    let after = collect_public_api(head, &mut manager).await?;
+
+    Ok(ApiDiffReport {
+        base: base.to_string(),
+        head: head.to_string(),
+        base_api_count: before.len(),
+        head_api_count: after.len(),
+        changes: diff_api_items(&before, &after),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(file: &str, name: &str, params: &[&str]) -> ApiItem {
+        let info = FunctionInfo {
+            name: name.to_string(),
+            parameters: params.iter().map(|p| p.to_string()).collect(),
+            params: Vec::new(),
+            return_type: None,
+            line_start: 1,
+            line_end: 2,
+            is_async: false,
+            visibility: Some("public".to_string()),
+            generics: Vec::new(),
+            annotations: Vec::new(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
+        };
+        ApiItem::from_function(&info, file)
+    }
+
+    #[test]
+    fn test_diff_detects_added_removed_and_changed() {
+        let before = vec![
+            func("src/lib.rs", "keep", &["a: i32"]),
+            func("src/lib.rs", "gone", &[]),
+            func("src/lib.rs", "reshaped", &["a: i32"]),
+        ];
+        let after = vec![
+            func("src/lib.rs", "keep", &["a: i32"]),
+            func("src/lib.rs", "reshaped", &["a: i32", "b: i32"]),
+            func("src/new.rs", "fresh", &[]),
+        ];
+
+        let changes = diff_api_items(&before, &after);
+        assert_eq!(changes.len(), 3);
+
+        let by_name = |name: &str| changes.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(by_name("gone").change, ApiChangeKind::Removed);
+        assert!(by_name("gone").breaking);
+        assert_eq!(by_name("reshaped").change, ApiChangeKind::SignatureChanged);
+        assert_eq!(
+            by_name("reshaped").after.as_deref(),
+            Some("reshaped(a: i32, b: i32)")
+        );
+        assert_eq!(by_name("fresh").change, ApiChangeKind::Added);
+        assert!(!by_name("fresh").breaking);
+    }
+
+    #[test]
+    fn test_report_counts_and_json() {
+        let before = vec![func("a.rs", "x", &[])];
+        let report = ApiDiffReport {
+            base: "v1".to_string(),
+            head: "HEAD".to_string(),
+            base_api_count: 1,
+            head_api_count: 0,
+            changes: diff_api_items(&before, &[]),
+        };
+
+        assert!(report.has_breaking_changes());
+        assert_eq!(report.count(ApiChangeKind::Removed), 1);
+        assert!(report.to_text().contains("破坏性"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["changes"][0]["change"], "removed");
+        assert_eq!(json["changes"][0]["kind"], "function");
+    }
+}
@@ -183,13 +183,71 @@ fn compare_single_function(
         });
     }
 
-    // 检查参数类型变化（简化检测）
-    if before.parameters != after.parameters {
+    // 检查泛型参数变化（新增/收紧约束会影响调用方）
+    if before.generics != after.generics {
         changes.push(BreakingChange {
             change_type: BreakingChangeType::FunctionSignatureChanged,
             component: before.name.clone(),
-            description: format!("函数 '{}' 的签名发生变化", before.name),
+            description: format!(
+                "函数 '{}' 的泛型参数从 <{}> 变为 <{}>",
+                before.name,
+                before.generics.join(", "),
+                after.generics.join(", ")
+            ),
             impact_level: ImpactLevel::Module,
+            suggestions: vec!["确认新的泛型约束不会破坏现有调用方".to_string()],
+            before: Some(format_function_signature(before)),
+            after: Some(format_function_signature(after)),
+            file_path: "unknown".to_string(),
+        });
+    }
+
+    // 检查参数变化：区分类型变化与仅重命名
+    if before.parameters != after.parameters {
+        // 旧缓存中没有结构化参数时无法区分类型变化与重命名，按签名变化处理
+        let structured = before.parameters.len() == after.parameters.len()
+            && before.params.len() == before.parameters.len()
+            && after.params.len() == after.parameters.len();
+        let pairs = || before.params.iter().zip(after.params.iter());
+        let type_changes: Vec<String> = pairs()
+            .filter(|(b, a)| b.ty != a.ty)
+            .map(|(b, a)| format!("{b} → {a}"))
+            .collect();
+        let renames: Vec<String> = pairs()
+            .filter(|(b, a)| b.ty == a.ty && b.name != a.name)
+            .map(|(b, a)| format!("{} → {}", b.name, a.name))
+            .collect();
+
+        let (description, impact_level) = if structured && type_changes.is_empty() {
+            (
+                format!(
+                    "函数 '{}' 的参数重命名（类型未变）: {}",
+                    before.name,
+                    renames.join(", ")
+                ),
+                ImpactLevel::Minimal,
+            )
+        } else if structured {
+            (
+                format!(
+                    "函数 '{}' 的参数类型变化: {}",
+                    before.name,
+                    type_changes.join(", ")
+                ),
+                ImpactLevel::Module,
+            )
+        } else {
+            (
+                format!("函数 '{}' 的签名发生变化", before.name),
+                ImpactLevel::Module,
+            )
+        };
+
+        changes.push(BreakingChange {
+            change_type: BreakingChangeType::FunctionSignatureChanged,
+            component: before.name.clone(),
+            description,
+            impact_level,
             suggestions: vec![
                 format!("为 '{}' 保留向后兼容的重载版本", before.name),
                 "使用渐进式迁移策略".to_string(),
@@ -307,16 +365,22 @@ fn compare_imports(before_imports: &[String], after_imports: &[String]) -> Vec<B
 /// 格式化函数签名
 fn format_function_signature(func: &crate::tree_sitter::FunctionInfo) -> String {
     let params = func.parameters.join(", ");
+    let generics = if func.generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", func.generics.join(", "))
+    };
     let return_type = func.return_type.as_deref().unwrap_or("void");
     let visibility = func.visibility.as_deref().unwrap_or("");
     let async_str = if func.is_async { "async " } else { "" };
 
     format!(
-        "{}{}{} {}({}) -> {}",
+        "{}{}{} {}{}({}) -> {}",
         visibility,
         if visibility.is_empty() { "" } else { " " },
         async_str,
         func.name,
+        generics,
         params,
         return_type
     )
@@ -325,22 +389,32 @@ fn format_function_signature(func: &crate::tree_sitter::FunctionInfo) -> String
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tree_sitter::{FunctionInfo, StructuralSummary};
+    use crate::tree_sitter::{FunctionInfo, StructuralSummary, SupportedLanguage};
 
     fn create_test_summary_with_function(
         name: &str,
         params: Vec<&str>,
         return_type: Option<&str>,
     ) -> StructuralSummary {
-        let function = FunctionInfo {
+        let mut function = FunctionInfo {
             name: name.to_string(),
-            parameters: params.into_iter().map(|p| p.to_string()).collect(),
+            parameters: Vec::new(),
+            params: Vec::new(),
             return_type: return_type.map(|rt| rt.to_string()),
             line_start: 1,
             line_end: 5,
             is_async: false,
             visibility: Some("public".to_string()),
+            generics: Vec::new(),
+            annotations: Vec::new(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
         };
+        function.set_parameters(
+            params.into_iter().map(str::to_string).collect(),
+            SupportedLanguage::Rust,
+        );
 
         StructuralSummary {
             language: "rust".to_string(),
@@ -352,6 +426,7 @@ mod tests {
             comments: vec![],
             complexity_hints: vec![],
             calls: vec![],
+            truncated: false,
         }
     }
 
@@ -408,6 +483,36 @@ mod tests {
         assert!(param_change.is_some());
     }
 
+    #[test]
+    fn test_parameter_type_change_vs_rename() {
+        let before = create_test_summary_with_function("f", vec!["a: i32"], None);
+        let retyped = create_test_summary_with_function("f", vec!["a: i64"], None);
+        let renamed = create_test_summary_with_function("f", vec!["count: i32"], None);
+
+        let signature_change = |after: &StructuralSummary| {
+            compare_structural_summaries(&before, after)
+                .breaking_changes
+                .into_iter()
+                .find(|c| c.change_type == BreakingChangeType::FunctionSignatureChanged)
+                .unwrap()
+        };
+
+        let change = signature_change(&retyped);
+        assert!(change.description.contains("参数类型变化"));
+        assert_eq!(change.impact_level, ImpactLevel::Module);
+
+        let change = signature_change(&renamed);
+        assert!(change.description.contains("参数重命名"));
+        assert_eq!(change.impact_level, ImpactLevel::Minimal);
+
+        // 旧缓存只有参数原始文本时按签名变化处理
+        let mut legacy = renamed.clone();
+        legacy.functions[0].params.clear();
+        let change = signature_change(&legacy);
+        assert!(change.description.contains("签名发生变化"));
+        assert_eq!(change.impact_level, ImpactLevel::Module);
+    }
+
     #[test]
     fn test_function_return_type_change_detection() {
         let before = create_test_summary_with_function("test_func", vec![], Some("i32"));
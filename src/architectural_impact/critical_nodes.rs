@@ -0,0 +1,342 @@
+// 关键节点分析模块
+// 将依赖图视为无向图，找出割点（移除后会使图断开的节点）并计算介数中心性，
+// 按影响分数排序，用于评估修改某个节点的波及范围
+
+use super::dependency_graph::{DependencyGraph, NodeType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// 单个关键节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalNode {
+    pub id: String,
+    /// 展示用标签（如 `fn main()`）
+    pub label: String,
+    pub file_path: String,
+    /// 是否为割点
+    pub is_articulation_point: bool,
+    /// 移除该节点后，与所在连通分量最大剩余部分断开的节点数
+    pub disconnected_nodes: usize,
+    /// 归一化介数中心性（0-1）
+    pub centrality: f32,
+    /// 影响分数（0-1）：断开比例与中心性的平均值
+    pub impact_score: f32,
+}
+
+/// 关键节点报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalNodeReport {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// 割点总数
+    pub articulation_points: usize,
+    /// 按影响分数降序排列的节点（截断到 top_k）
+    pub nodes: Vec<CriticalNode>,
+}
+
+impl CriticalNodeReport {
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "🎯 关键节点: {} 个节点 / {} 条边，割点 {} 个\n",
+            self.node_count, self.edge_count, self.articulation_points
+        );
+        if self.nodes.is_empty() {
+            out.push_str("\n✅ 未发现关键节点\n");
+            return out;
+        }
+        out.push('\n');
+        for (rank, node) in self.nodes.iter().enumerate() {
+            let marker = if node.is_articulation_point {
+                " [割点]"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "{:>3}. {}{marker}  影响 {:.3}（断开 {} 个节点，中心性 {:.3}）\n",
+                rank + 1,
+                node.label,
+                node.impact_score,
+                node.disconnected_nodes,
+                node.centrality,
+            ));
+            // 文件节点的标签已包含路径
+            if !node.label.ends_with(&node.file_path) {
+                out.push_str(&format!("     {}\n", node.file_path));
+            }
+        }
+        out
+    }
+}
+
+/// 计算割点与介数中心性，返回影响分数最高的 top_k 个节点
+///
+/// 只考虑两端都在图中的边，边方向与重复边被忽略。没有断开作用且中心性为 0 的节点不列出。
+pub fn analyze_critical_nodes(graph: &DependencyGraph, top_k: usize) -> CriticalNodeReport {
+    let mut ids: Vec<&String> = graph.nodes.keys().collect();
+    ids.sort();
+    let index: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    let mut edge_count = 0;
+    for edge in &graph.edges {
+        let (Some(&a), Some(&b)) = (index.get(edge.from.as_str()), index.get(edge.to.as_str()))
+        else {
+            continue;
+        };
+        if a == b || adjacency[a].contains(&b) {
+            continue;
+        }
+        adjacency[a].push(b);
+        adjacency[b].push(a);
+        edge_count += 1;
+    }
+
+    let disconnected = articulation_points(&adjacency);
+    let centrality = betweenness(&adjacency);
+    let n = ids.len();
+
+    let mut nodes: Vec<CriticalNode> = ids
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| disconnected[i].is_some() || centrality[i] > 0.0)
+        .map(|(i, id)| {
+            let node = &graph.nodes[*id];
+            let cut = disconnected[i].unwrap_or(0);
+            let cut_ratio = if n > 1 {
+                cut as f32 / (n - 1) as f32
+            } else {
+                0.0
+            };
+            CriticalNode {
+                id: (*id).clone(),
+                label: node_label(&node.node_type),
+                file_path: node.metadata.file_path.clone(),
+                is_articulation_point: disconnected[i].is_some(),
+                disconnected_nodes: cut,
+                centrality: centrality[i],
+                impact_score: (cut_ratio + centrality[i]) / 2.0,
+            }
+        })
+        .collect();
+    let articulation_points = nodes.iter().filter(|n| n.is_articulation_point).count();
+    nodes.sort_by(|a, b| {
+        b.impact_score
+            .partial_cmp(&a.impact_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    nodes.truncate(top_k);
+
+    CriticalNodeReport {
+        node_count: n,
+        edge_count,
+        articulation_points,
+        nodes,
+    }
+}
+
+fn node_label(node_type: &NodeType) -> String {
+    match node_type {
+        NodeType::Function(f) => format!("fn {}()", f.name),
+        NodeType::Class(c) => format!("class {}", c.name),
+        NodeType::Module(m) => format!("mod {}", m.name),
+        NodeType::File(f) => format!("file {}", f.path),
+    }
+}
+
+/// 迭代式 Tarjan 割点算法
+///
+/// 对每个割点返回移除后与最大剩余部分断开的节点数，非割点为 None
+fn articulation_points(adjacency: &[Vec<usize>]) -> Vec<Option<usize>> {
+    let n = adjacency.len();
+    let mut disc = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut subtree = vec![1usize; n];
+    // 每个节点被割开的子树大小
+    let mut separated: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut result = vec![None; n];
+    let mut timer = 0;
+
+    for root in 0..n {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        disc[root] = timer;
+        low[root] = timer;
+        timer += 1;
+        let mut members = vec![root];
+        // (节点, 父节点, 下一个待访问邻居下标)
+        let mut stack: Vec<(usize, usize, usize)> = vec![(root, usize::MAX, 0)];
+        while let Some(&mut (v, parent, ref mut next)) = stack.last_mut() {
+            if let Some(&w) = adjacency[v].get(*next) {
+                *next += 1;
+                if disc[w] == usize::MAX {
+                    disc[w] = timer;
+                    low[w] = timer;
+                    timer += 1;
+                    members.push(w);
+                    stack.push((w, v, 0));
+                } else if w != parent {
+                    low[v] = low[v].min(disc[w]);
+                }
+                continue;
+            }
+            stack.pop();
+            if parent != usize::MAX {
+                low[parent] = low[parent].min(low[v]);
+                subtree[parent] += subtree[v];
+                if low[v] >= disc[parent] {
+                    separated[parent].push(subtree[v]);
+                }
+            }
+        }
+
+        // 根节点需要至少两个被割开的子树，非根节点至少一个
+        let component = subtree[root];
+        for v in members {
+            let pieces = &separated[v];
+            if pieces.is_empty() {
+                continue;
+            }
+            if v == root && pieces.len() < 2 {
+                continue;
+            }
+            // 剩余部分：割开的各子树，以及（非根时）包含父节点的其余部分
+            let rest = component - 1 - pieces.iter().sum::<usize>();
+            let largest = pieces.iter().copied().max().unwrap_or(0).max(rest);
+            result[v] = Some(component - 1 - largest);
+        }
+    }
+    result
+}
+
+/// Brandes 算法计算无向图的归一化介数中心性
+fn betweenness(adjacency: &[Vec<usize>]) -> Vec<f32> {
+    let n = adjacency.len();
+    let mut centrality = vec![0.0f64; n];
+    for s in 0..n {
+        let mut order = Vec::with_capacity(n);
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0f64; n];
+        let mut distance = vec![usize::MAX; n];
+        paths[s] = 1.0;
+        distance[s] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &w in &adjacency[v] {
+                if distance[w] == usize::MAX {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        let mut dependency = vec![0.0f64; n];
+        for &w in order.iter().rev() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    // 无向图每条最短路径被两端各统计一次；再按 (n-1)(n-2) 归一化到 0-1
+    let scale = if n > 2 {
+        ((n - 1) * (n - 2)) as f64
+    } else {
+        1.0
+    };
+    centrality.into_iter().map(|c| (c / scale) as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architectural_impact::dependency_graph::{
+        Edge, EdgeType, FunctionNode, Node, NodeMetadata,
+    };
+
+    fn function_graph(edges: &[(&str, &str)]) -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        for (from, to) in edges {
+            for id in [from, to] {
+                graph.add_node(Node {
+                    id: id.to_string(),
+                    node_type: NodeType::Function(FunctionNode {
+                        name: id.to_string(),
+                        visibility: None,
+                        parameters: vec![],
+                        return_type: None,
+                        is_async: false,
+                    }),
+                    metadata: NodeMetadata {
+                        file_path: "a.rs".to_string(),
+                        start_line: 1,
+                        end_line: 1,
+                        complexity: 1,
+                        created_at: 0,
+                    },
+                    importance_score: 0.0,
+                });
+            }
+            graph.add_edge(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                edge_type: EdgeType::Calls,
+                weight: 1.0,
+                metadata: None,
+            });
+        }
+        graph.rebuild_adjacency_lists();
+        graph
+    }
+
+    #[test]
+    fn test_articulation_points_rank_bridge_nodes_first() {
+        // 三角形 a-b-c 通过 c-d 连接到链 d-e-f
+        let graph = function_graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "f"),
+        ]);
+        let report = analyze_critical_nodes(&graph, 10);
+        assert_eq!(report.node_count, 6);
+        assert_eq!(report.edge_count, 6);
+        assert_eq!(report.articulation_points, 3);
+
+        let ranked: Vec<(&str, usize)> = report
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.disconnected_nodes))
+            .collect();
+        assert_eq!(ranked, vec![("c", 2), ("d", 2), ("e", 1)]);
+        // c 位于 {a,b} 与 {d,e,f} 之间全部 6 对节点的最短路径上：2 * 6 / (5 * 4) = 0.6
+        assert!((report.nodes[0].centrality - 0.6).abs() < 1e-6);
+        assert!(report.nodes.iter().all(|n| n.is_articulation_point));
+
+        let report = analyze_critical_nodes(&graph, 1);
+        assert_eq!(report.nodes.len(), 1);
+        assert_eq!(report.articulation_points, 3);
+    }
+
+    #[test]
+    fn test_cycle_has_no_articulation_points() {
+        let graph = function_graph(&[("a", "b"), ("b", "c"), ("c", "a")]);
+        let report = analyze_critical_nodes(&graph, 10);
+        assert_eq!(report.articulation_points, 0);
+        assert!(report.nodes.is_empty());
+    }
+}
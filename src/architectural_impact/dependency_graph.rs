@@ -1,5 +1,6 @@
 // 依赖图模块 - 用于构建和分析代码间的依赖关系
-use crate::tree_sitter::{ClassInfo, FunctionInfo, StructuralSummary};
+use crate::tree_sitter::imports::{ImportClassifier, ImportKind};
+use crate::tree_sitter::{ClassInfo, FunctionInfo, StructuralSummary, SupportedLanguage};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 
@@ -70,6 +71,9 @@ pub struct ModuleNode {
     pub name: String,
     pub path: String,
     pub exports: Vec<String>,
+    /// 导入类别（标准库 / 第三方 / 本地）
+    #[serde(default)]
+    pub kind: Option<ImportKind>,
 }
 
 /// 文件节点信息
@@ -168,6 +172,127 @@ impl Visibility {
     }
 }
 
+impl NodeType {
+    /// 可用于过滤的节点类型名称
+    pub const NAMES: &'static [&'static str] = &["function", "class", "module", "file"];
+
+    /// 节点类型名称（小写）
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            NodeType::Function(_) => "function",
+            NodeType::Class(_) => "class",
+            NodeType::Module(_) => "module",
+            NodeType::File(_) => "file",
+        }
+    }
+}
+
+impl EdgeType {
+    /// 全部边类型
+    pub const ALL: [EdgeType; 9] = [
+        EdgeType::Calls,
+        EdgeType::Imports,
+        EdgeType::Exports,
+        EdgeType::Inherits,
+        EdgeType::Implements,
+        EdgeType::Uses,
+        EdgeType::References,
+        EdgeType::Contains,
+        EdgeType::DependsOn,
+    ];
+
+    /// 边类型名称（snake_case）
+    pub fn name(&self) -> &'static str {
+        match self {
+            EdgeType::Calls => "calls",
+            EdgeType::Imports => "imports",
+            EdgeType::Exports => "exports",
+            EdgeType::Inherits => "inherits",
+            EdgeType::Implements => "implements",
+            EdgeType::Uses => "uses",
+            EdgeType::References => "references",
+            EdgeType::Contains => "contains",
+            EdgeType::DependsOn => "depends_on",
+        }
+    }
+
+    /// 按名称解析边类型（忽略大小写，同时接受 `DependsOn` 形式）
+    pub fn from_name(name: &str) -> Option<EdgeType> {
+        let normalized = name.trim().to_lowercase().replace(['_', '-'], "");
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name().replace('_', "") == normalized)
+    }
+}
+
+/// 按节点类型与边类型裁剪依赖图（为空表示不限制该维度）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphFilter {
+    pub node_types: Vec<String>,
+    pub edge_types: Vec<EdgeType>,
+}
+
+impl GraphFilter {
+    /// 由类型名称构造过滤器，名称无效时返回列出可用名称的错误
+    pub fn from_names(node_types: &[String], edge_types: &[String]) -> Result<Self, String> {
+        let node_types = node_types
+            .iter()
+            .map(|name| parse_node_type_name(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let edge_types = edge_types
+            .iter()
+            .map(|name| {
+                parse_edge_type_name(name)
+                    .map(|n| EdgeType::from_name(&n).expect("已校验的边类型名称"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            node_types,
+            edge_types,
+        })
+    }
+
+    /// 是否不做任何裁剪
+    pub fn is_empty(&self) -> bool {
+        self.node_types.is_empty() && self.edge_types.is_empty()
+    }
+
+    fn keeps_node(&self, node: &Node) -> bool {
+        self.node_types.is_empty()
+            || self
+                .node_types
+                .iter()
+                .any(|t| t == node.node_type.kind_name())
+    }
+
+    fn keeps_edge(&self, edge: &Edge) -> bool {
+        self.edge_types.is_empty() || self.edge_types.contains(&edge.edge_type)
+    }
+}
+
+/// 校验节点类型名称并规范为小写，无效时列出可用名称
+pub fn parse_node_type_name(name: &str) -> Result<String, String> {
+    let normalized = name.trim().to_lowercase();
+    if NodeType::NAMES.contains(&normalized.as_str()) {
+        Ok(normalized)
+    } else {
+        Err(format!(
+            "无效的节点类型: {name}（可用: {}）",
+            NodeType::NAMES.join(", ")
+        ))
+    }
+}
+
+/// 校验边类型名称并规范为 snake_case，无效时列出可用名称
+pub fn parse_edge_type_name(name: &str) -> Result<String, String> {
+    EdgeType::from_name(name)
+        .map(|t| t.name().to_string())
+        .ok_or_else(|| {
+            let names: Vec<&str> = EdgeType::ALL.iter().map(|t| t.name()).collect();
+            format!("无效的边类型: {name}（可用: {}）", names.join(", "))
+        })
+}
+
 impl Default for DependencyGraph {
     fn default() -> Self {
         Self::new()
@@ -224,9 +349,12 @@ impl DependencyGraph {
         }
 
         // 处理导入关系（为每个导入创建模块节点）
+        let language = SupportedLanguage::from_name(&summary.language);
+        let classifier = ImportClassifier::new();
         for import in &summary.imports {
             let module_id = format!("module:{import}");
-            graph.add_module_node(&module_id, import);
+            let kind = language.map(|lang| classifier.classify(import, lang));
+            graph.add_module_node(&module_id, import, kind);
             graph.add_edge(Edge {
                 from: file_id.clone(),
                 to: module_id,
@@ -292,17 +420,7 @@ impl DependencyGraph {
                 {
                     // 仅在 callee 存在于同文件函数映射中时创建 Calls 边
                     if let Some(callee_id) = name_to_id.get(call.callee.as_str()) {
-                        graph.add_edge(Edge {
-                            from: caller_id,
-                            to: callee_id.clone(),
-                            edge_type: EdgeType::Calls,
-                            weight: 1.0,
-                            metadata: Some(EdgeMetadata {
-                                call_count: Some(1),
-                                is_strong_dependency: true,
-                                notes: None,
-                            }),
-                        });
+                        graph.add_call_edge(&caller_id, callee_id);
                     }
                 }
             }
@@ -392,9 +510,41 @@ impl DependencyGraph {
         self.edges.push(edge);
     }
 
+    /// 添加调用边：同一对 caller/callee 只保留一条 Calls 边，重复调用累加
+    /// `call_count`，权重等于调用次数
+    pub fn add_call_edge(&mut self, from: &str, to: &str) {
+        if let Some(edge) = self
+            .edges
+            .iter_mut()
+            .find(|e| e.edge_type == EdgeType::Calls && e.from == from && e.to == to)
+        {
+            let metadata = edge.metadata.get_or_insert(EdgeMetadata {
+                call_count: Some(1),
+                is_strong_dependency: true,
+                notes: None,
+            });
+            let count = metadata.call_count.unwrap_or(1) + 1;
+            metadata.call_count = Some(count);
+            edge.weight = count as f32;
+            return;
+        }
+        self.add_edge(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type: EdgeType::Calls,
+            weight: 1.0,
+            metadata: Some(EdgeMetadata {
+                call_count: Some(1),
+                is_strong_dependency: true,
+                notes: None,
+            }),
+        });
+    }
+
     /// 基于现有图节点尝试解析并添加跨文件调用边
     /// 规则：
     /// - 通过 caller_file_path 和 call_line 定位调用发生的函数（作为 caller）
+    /// - callee 在 caller 所在文件中有同名函数时跳过（已由 `from_structural_summary` 计入）
     /// - 在全图中按函数名唯一匹配 callee（若有多个同名函数则跳过，以避免歧义）
     pub fn add_resolved_call(
         &mut self,
@@ -423,6 +573,15 @@ impl DependencyGraph {
         }
         let caller_id = caller_id_opt.unwrap();
 
+        // 同文件调用已在构建单文件子图时计入，避免重复累加调用次数
+        let same_file = self.nodes.values().any(|node| {
+            matches!(&node.node_type, NodeType::Function(f) if f.name == callee_name)
+                && node.metadata.file_path == caller_file_path
+        });
+        if same_file {
+            return;
+        }
+
         // 唯一定位 callee（同名函数必须唯一）
         let mut matches: Vec<String> = self
             .nodes
@@ -438,21 +597,11 @@ impl DependencyGraph {
         }
         let callee_id = matches.pop().unwrap();
 
-        self.add_edge(Edge {
-            from: caller_id,
-            to: callee_id,
-            edge_type: EdgeType::Calls,
-            weight: 1.0,
-            metadata: Some(EdgeMetadata {
-                call_count: Some(1),
-                is_strong_dependency: true,
-                notes: None,
-            }),
-        });
+        self.add_call_edge(&caller_id, &callee_id);
     }
 
     /// 添加模块节点（用于 imports 等场景）
-    fn add_module_node(&mut self, id: &str, import_path: &str) {
+    fn add_module_node(&mut self, id: &str, import_path: &str, kind: Option<ImportKind>) {
         let name = import_path
             .rsplit(['.', '/', ':'])
             .next()
@@ -464,6 +613,7 @@ impl DependencyGraph {
                 name,
                 path: import_path.to_string(),
                 exports: vec![],
+                kind,
             }),
             metadata: NodeMetadata {
                 file_path: import_path.to_string(),
@@ -484,6 +634,43 @@ impl DependencyGraph {
         }
     }
 
+    /// 移除弱调用边：权重低于最大调用边权重 × ratio 的 Calls 边被丢弃，返回移除数量
+    ///
+    /// 仅调用边携带调用次数权重，其它类型的边保持不变；所有调用边权重相同时不会移除任何边
+    pub fn prune_weak_edges(&mut self, ratio: f32) -> usize {
+        let max_weight = self
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Calls)
+            .map(|e| e.weight)
+            .fold(0.0_f32, f32::max);
+        let cutoff = max_weight * ratio;
+        let before = self.edges.len();
+        self.edges
+            .retain(|e| e.edge_type != EdgeType::Calls || e.weight >= cutoff);
+        let removed = before - self.edges.len();
+        if removed > 0 {
+            self.rebuild_adjacency_lists();
+        }
+        removed
+    }
+
+    /// 按过滤器裁剪：只保留指定类型的节点，以及两端节点均被保留且类型匹配的边
+    pub fn filtered(mut self, filter: &GraphFilter) -> Self {
+        if filter.is_empty() {
+            return self;
+        }
+        self.nodes.retain(|_, node| filter.keeps_node(node));
+        let nodes = &self.nodes;
+        self.edges.retain(|edge| {
+            filter.keeps_edge(edge)
+                && nodes.contains_key(&edge.from)
+                && nodes.contains_key(&edge.to)
+        });
+        self.rebuild_adjacency_lists();
+        self
+    }
+
     /// 重建邻接表（在添加完所有节点和边后调用）
     pub fn rebuild_adjacency_lists(&mut self) {
         self.adjacency_list.clear();
@@ -568,15 +755,15 @@ impl DependencyGraph {
             pagerank.insert(node_id.clone(), 1.0 / n);
         }
 
-        // 预计算每个节点的出度
-        let mut out_degree: HashMap<String, usize> = HashMap::new();
-        for node_id in self.nodes.keys() {
-            let degree = self
-                .adjacency_list
-                .get(node_id)
-                .map(|neighbors| neighbors.len())
-                .unwrap_or(0);
-            out_degree.insert(node_id.clone(), degree);
+        // 预计算每个节点的加权出度与入边（权重为调用次数等边权，全部为 1 时即经典 PageRank）
+        let mut out_weight: HashMap<&str, f32> = HashMap::new();
+        let mut incoming: HashMap<&str, Vec<(&str, f32)>> = HashMap::new();
+        for edge in &self.edges {
+            *out_weight.entry(edge.from.as_str()).or_default() += edge.weight;
+            incoming
+                .entry(edge.to.as_str())
+                .or_default()
+                .push((edge.from.as_str(), edge.weight));
         }
 
         // 迭代计算 PageRank
@@ -587,18 +774,13 @@ impl DependencyGraph {
             for node_id in self.nodes.keys() {
                 let mut rank = (1.0 - damping_factor) / n;
 
-                // 从所有指向该节点的节点收集 PageRank 贡献
-                if let Some(incoming) = self.reverse_adjacency_list.get(node_id) {
-                    for source in incoming {
+                // 从所有指向该节点的节点收集 PageRank 贡献，按边权占源节点总出权重的比例分配
+                if let Some(sources) = incoming.get(node_id.as_str()) {
+                    for &(source, edge_weight) in sources {
                         if let Some(&source_rank) = pagerank.get(source) {
-                            if let Some(&out_deg) = out_degree.get(source) {
-                                if out_deg > 0 {
-                                    // 考虑边的权重
-                                    let edge_weight =
-                                        self.get_edge_weight(source, node_id).unwrap_or(1.0);
-                                    rank +=
-                                        damping_factor * source_rank * edge_weight / out_deg as f32;
-                                }
+                            let total = out_weight.get(source).copied().unwrap_or(0.0);
+                            if total > 0.0 {
+                                rank += damping_factor * source_rank * edge_weight / total;
                             }
                         }
                     }
@@ -606,10 +788,8 @@ impl DependencyGraph {
 
                 // 处理没有出边的节点（dangling nodes）
                 for (source_id, &source_rank) in &pagerank {
-                    if let Some(&out_deg) = out_degree.get(source_id) {
-                        if out_deg == 0 {
-                            rank += damping_factor * source_rank / n;
-                        }
+                    if out_weight.get(source_id.as_str()).copied().unwrap_or(0.0) <= 0.0 {
+                        rank += damping_factor * source_rank / n;
                     }
                 }
 
@@ -991,13 +1171,17 @@ impl DependencyGraph {
                 EdgeType::DependsOn => "#e76f51",
             };
             let mut label = label_type.to_string();
+            let mut extra = String::new();
             if include_weights {
                 label.push_str(&format!(" (w={:.2})", e.weight));
+                // 线宽随权重对数增长，热点调用路径更醒目
+                let penwidth = (1.0 + e.weight.max(1.0).log2()).min(6.0);
+                extra.push_str(&format!(", penwidth={penwidth:.2}"));
             }
             let safe_label = label.replace("\\", "\\\\").replace("\"", "\\\"");
             s.push_str(&format!(
-                "  \"{}\" -> \"{}\" [color=\"{}\", label=\"{}\"];\n",
-                e.from, e.to, color, safe_label
+                "  \"{}\" -> \"{}\" [color=\"{}\", label=\"{}\"{}];\n",
+                e.from, e.to, color, safe_label, extra
             ));
         }
 
@@ -1275,6 +1459,17 @@ mod tests {
             .filter(|e| e.edge_type == EdgeType::Imports);
         assert!(imports_edges2
             .any(|e| e.from == "file:src/lib.rs" && e.to == "module:serde::Serialize"));
+
+        // 模块节点应带有导入类别
+        let kind_of = |id: &str| match &graph.nodes[id].node_type {
+            NodeType::Module(m) => m.kind,
+            _ => None,
+        };
+        assert_eq!(kind_of("module:std::fmt"), Some(ImportKind::Std));
+        assert_eq!(
+            kind_of("module:serde::Serialize"),
+            Some(ImportKind::ThirdParty)
+        );
     }
 
     #[test]
@@ -1285,11 +1480,17 @@ mod tests {
         let func = FunctionInfo {
             name: "foo".to_string(),
             parameters: vec![],
+            params: vec![],
             return_type: None,
             line_start: 1,
             line_end: 5,
             is_async: false,
             visibility: None,
+            generics: Vec::new(),
+            annotations: Vec::new(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
         };
         let summary = StructuralSummary {
             functions: vec![func],
@@ -1308,4 +1509,74 @@ mod tests {
             .iter()
             .any(|e| e.edge_type == EdgeType::Exports && e.from == file_id && e.to == func_id));
     }
+
+    #[test]
+    fn test_filtered_keeps_requested_node_and_edge_types() {
+        let mut graph = DependencyGraph::new();
+        let metadata = NodeMetadata {
+            file_path: "a.rs".to_string(),
+            start_line: 1,
+            end_line: 10,
+            complexity: 1,
+            created_at: 0,
+        };
+        graph.add_node(Node {
+            id: "file:a.rs".to_string(),
+            node_type: NodeType::File(FileNode {
+                path: "a.rs".to_string(),
+                language: "rust".to_string(),
+                size: 0,
+            }),
+            metadata: metadata.clone(),
+            importance_score: 0.0,
+        });
+        for id in ["f", "g"] {
+            graph.add_node(Node {
+                id: id.to_string(),
+                node_type: NodeType::Function(FunctionNode {
+                    name: id.to_string(),
+                    visibility: None,
+                    parameters: vec![],
+                    return_type: None,
+                    is_async: false,
+                }),
+                metadata: metadata.clone(),
+                importance_score: 0.0,
+            });
+        }
+        for (from, to, edge_type) in [
+            ("f", "g", EdgeType::Calls),
+            ("f", "g", EdgeType::References),
+            ("file:a.rs", "f", EdgeType::Contains),
+        ] {
+            graph.add_edge(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+                edge_type,
+                weight: 1.0,
+                metadata: None,
+            });
+        }
+
+        let filter =
+            GraphFilter::from_names(&["Function".to_string()], &["calls".to_string()]).unwrap();
+        let filtered = graph.clone().filtered(&filter);
+        assert_eq!(filtered.nodes.len(), 2);
+        assert_eq!(filtered.edges.len(), 1);
+        assert_eq!(filtered.edges[0].edge_type, EdgeType::Calls);
+        assert_eq!(filtered.get_dependencies("f"), vec![&"g".to_string()]);
+
+        // 只限定节点类型时，端点被裁掉的边一并移除
+        let filter = GraphFilter::from_names(&["function".to_string()], &[]).unwrap();
+        assert_eq!(graph.clone().filtered(&filter).edges.len(), 2);
+
+        let filter = GraphFilter::from_names(&[], &["DependsOn".to_string()]).unwrap();
+        assert_eq!(filter.edge_types, vec![EdgeType::DependsOn]);
+        assert!(graph.clone().filtered(&filter).edges.is_empty());
+
+        let err = GraphFilter::from_names(&[], &["call".to_string()]).unwrap_err();
+        assert!(err.contains("depends_on"));
+        let err = GraphFilter::from_names(&["method".to_string()], &[]).unwrap_err();
+        assert!(err.contains("function, class, module, file"));
+    }
 }
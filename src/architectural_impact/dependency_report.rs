@@ -0,0 +1,466 @@
+// 外部依赖报告模块
+// 按文件归类导入（标准库 / 第三方 / 本地），并按语言汇总项目使用的外部依赖
+
+use super::graph_export::collect_files;
+use crate::tree_sitter::imports::{package_name, ImportClassifier, ImportKind};
+use crate::tree_sitter::{SupportedLanguage, TreeSitterManager};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 已分类的单条导入
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassifiedImport {
+    /// 原始导入路径
+    pub import: String,
+    /// 所属包名
+    pub package: String,
+    pub kind: ImportKind,
+}
+
+/// 单个文件的导入情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDependencies {
+    pub path: String,
+    pub language: String,
+    pub imports: Vec<ClassifiedImport>,
+}
+
+impl FileDependencies {
+    /// 该文件使用的第三方包（去重排序）
+    pub fn external_packages(&self) -> BTreeSet<&str> {
+        self.imports
+            .iter()
+            .filter(|i| i.kind == ImportKind::ThirdParty)
+            .map(|i| i.package.as_str())
+            .collect()
+    }
+}
+
+/// 一个外部依赖及其使用位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalDependency {
+    pub package: String,
+    pub language: String,
+    /// 使用该依赖的文件
+    pub files: Vec<String>,
+}
+
+/// 项目依赖报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyReport {
+    pub root: String,
+    pub files: Vec<FileDependencies>,
+    /// 按语言、包名排序的外部依赖
+    pub external: Vec<ExternalDependency>,
+}
+
+impl DependencyReport {
+    /// 由文件级结果汇总外部依赖
+    pub fn from_files(root: impl Into<String>, files: Vec<FileDependencies>) -> Self {
+        let mut grouped: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        for file in &files {
+            for package in file.external_packages() {
+                grouped
+                    .entry((file.language.clone(), package.to_string()))
+                    .or_default()
+                    .insert(file.path.clone());
+            }
+        }
+
+        let external = grouped
+            .into_iter()
+            .map(|((language, package), files)| ExternalDependency {
+                package,
+                language,
+                files: files.into_iter().collect(),
+            })
+            .collect();
+
+        Self {
+            root: root.into(),
+            files,
+            external,
+        }
+    }
+
+    /// 按语言分组的外部依赖
+    pub fn external_by_language(&self) -> BTreeMap<&str, Vec<&ExternalDependency>> {
+        let mut by_language: BTreeMap<&str, Vec<&ExternalDependency>> = BTreeMap::new();
+        for dep in &self.external {
+            by_language
+                .entry(dep.language.as_str())
+                .or_default()
+                .push(dep);
+        }
+        by_language
+    }
+
+    /// 指定类别的导入总数
+    pub fn count(&self, kind: ImportKind) -> usize {
+        self.files
+            .iter()
+            .flat_map(|f| &f.imports)
+            .filter(|i| i.kind == kind)
+            .count()
+    }
+
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "📦 外部依赖: {} 个（扫描 {} 个文件，导入 std {} / 第三方 {} / 本地 {}）\n",
+            self.external.len(),
+            self.files.len(),
+            self.count(ImportKind::Std),
+            self.count(ImportKind::ThirdParty),
+            self.count(ImportKind::Local)
+        );
+
+        if self.external.is_empty() {
+            out.push_str("\n✅ 未发现第三方依赖\n");
+            return out;
+        }
+
+        for (language, deps) in self.external_by_language() {
+            out.push_str(&format!("\n[{language}] {} 个\n", deps.len()));
+            for dep in deps {
+                out.push_str(&format!(
+                    "  {} （{} 个文件）\n",
+                    dep.package,
+                    dep.files.len()
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// 变更中新引入的第三方依赖
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDependency {
+    pub package: String,
+    pub language: String,
+    /// 引入该依赖的文件
+    pub files: Vec<String>,
+}
+
+impl std::fmt::Display for NewDependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.package, self.language)
+    }
+}
+
+/// 对比变更前后的文件导入，找出变更后才出现的第三方包
+///
+/// 以 (语言, 包名) 为单位比较：只要变更前任一文件已使用该包，就不视为新依赖。
+pub fn find_new_dependencies(
+    before: &[FileDependencies],
+    after: &[FileDependencies],
+) -> Vec<NewDependency> {
+    let existing: BTreeSet<(&str, &str)> = before
+        .iter()
+        .flat_map(|f| {
+            f.external_packages()
+                .into_iter()
+                .map(move |p| (f.language.as_str(), p))
+        })
+        .collect();
+
+    let mut introduced: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    for file in after {
+        for package in file.external_packages() {
+            if !existing.contains(&(file.language.as_str(), package)) {
+                introduced
+                    .entry((file.language.clone(), package.to_string()))
+                    .or_default()
+                    .insert(file.path.clone());
+            }
+        }
+    }
+
+    introduced
+        .into_iter()
+        .map(|((language, package), files)| NewDependency {
+            package,
+            language,
+            files: files.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// 从 diff 头部提取变更后的文件路径（删除的文件取原路径）
+fn changed_files_in_diff(diff: &str) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            if let Some(b_part) = rest.split_whitespace().nth(1) {
+                files.insert(b_part.strip_prefix("b/").unwrap_or(b_part).to_string());
+            }
+        }
+    }
+    files
+}
+
+fn file_dependencies(
+    path: &str,
+    content: &str,
+    language: SupportedLanguage,
+    manager: &mut TreeSitterManager,
+    classifier: &ImportClassifier,
+) -> Option<FileDependencies> {
+    match manager.analyze_structure(content, language) {
+        Ok(summary) => Some(FileDependencies {
+            path: path.to_string(),
+            language: language.name().to_string(),
+            imports: classify_imports(&summary.imports, language, classifier),
+        }),
+        Err(e) => {
+            log::debug!("跳过无法分析的文件 {path}: {e}");
+            None
+        }
+    }
+}
+
+/// 检测 diff 中新引入的第三方依赖
+///
+/// 以 `base_ref` 中的文件内容为变更前状态、工作区文件为变更后状态比较导入；
+/// 候选依赖还会与基准版本中其余同语言文件核对，项目中已在使用的包不会被标记。
+pub async fn detect_new_dependencies(
+    diff: &str,
+    base_ref: &str,
+) -> Result<Vec<NewDependency>, DynError> {
+    detect_new_dependencies_in(Path::new("."), diff, base_ref).await
+}
+
+/// 在 dir 所在仓库中检测 diff 新引入的第三方依赖
+pub async fn detect_new_dependencies_in(
+    dir: &Path,
+    diff: &str,
+    base_ref: &str,
+) -> Result<Vec<NewDependency>, DynError> {
+    let changed: Vec<(String, SupportedLanguage)> = changed_files_in_diff(diff)
+        .into_iter()
+        .filter_map(|path| {
+            let ext = Path::new(&path).extension()?.to_str()?;
+            let language = SupportedLanguage::from_extension(ext)?;
+            Some((path, language))
+        })
+        .collect();
+    if changed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let root = crate::git::run_git_in(
+        dir,
+        &["rev-parse".to_string(), "--show-toplevel".to_string()],
+    )?;
+    let root = Path::new(root.trim());
+    let classifier = ImportClassifier::for_project(root);
+    let mut manager = TreeSitterManager::new().await?;
+    let show = |path: &str| {
+        crate::git::run_git_in(dir, &["show".to_string(), format!("{base_ref}:{path}")]).ok()
+    };
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for (path, language) in &changed {
+        // 新增文件在基准中不存在，删除的文件在工作区中不存在
+        if let Some(content) = show(path) {
+            before.extend(file_dependencies(
+                path,
+                &content,
+                *language,
+                &mut manager,
+                &classifier,
+            ));
+        }
+        if let Ok(content) = std::fs::read_to_string(root.join(path)) {
+            after.extend(file_dependencies(
+                path,
+                &content,
+                *language,
+                &mut manager,
+                &classifier,
+            ));
+        }
+    }
+
+    let mut candidates = find_new_dependencies(&before, &after);
+    if candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    // 与基准版本中未变更的同语言文件核对，排除项目中已有的依赖
+    let languages: BTreeSet<&str> = candidates.iter().map(|c| c.language.as_str()).collect();
+    let changed_paths: BTreeSet<&str> = changed.iter().map(|(p, _)| p.as_str()).collect();
+    let listing = crate::git::run_git_in(
+        dir,
+        &[
+            "ls-tree".to_string(),
+            "-r".to_string(),
+            "--name-only".to_string(),
+            base_ref.to_string(),
+        ],
+    )?;
+    let mut existing = Vec::new();
+    for path in listing.lines().map(str::trim) {
+        if changed_paths.contains(path) {
+            continue;
+        }
+        let Some(language) = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(SupportedLanguage::from_extension)
+            .filter(|l| languages.contains(l.name()))
+        else {
+            continue;
+        };
+        if let Some(content) = show(path) {
+            existing.extend(file_dependencies(
+                path,
+                &content,
+                language,
+                &mut manager,
+                &classifier,
+            ));
+        }
+    }
+    if !existing.is_empty() {
+        let known: BTreeSet<(String, String)> = existing
+            .iter()
+            .flat_map(|f| {
+                f.external_packages()
+                    .into_iter()
+                    .map(|p| (f.language.clone(), p.to_string()))
+            })
+            .collect();
+        candidates.retain(|c| !known.contains(&(c.language.clone(), c.package.clone())));
+    }
+
+    Ok(candidates)
+}
+
+/// 对一组导入分类
+pub fn classify_imports(
+    imports: &[String],
+    language: SupportedLanguage,
+    classifier: &ImportClassifier,
+) -> Vec<ClassifiedImport> {
+    imports
+        .iter()
+        .map(|import| ClassifiedImport {
+            import: import.clone(),
+            package: package_name(import, language),
+            kind: classifier.classify(import, language),
+        })
+        .collect()
+}
+
+/// 扫描目录，生成外部依赖报告
+pub async fn build_dependency_report(root: &Path) -> Result<DependencyReport, DynError> {
+    let mut paths = Vec::new();
+    collect_files(root, &mut paths);
+    paths.sort();
+
+    let classifier = ImportClassifier::for_project(root);
+    let mut manager = TreeSitterManager::new().await?;
+    let mut files = Vec::new();
+
+    for path in paths {
+        let result = match manager.analyze_path(&path) {
+            Ok(Some(result)) => result,
+            Ok(None) => continue,
+            Err(e) => {
+                log::debug!("跳过无法分析的文件 {}: {e}", path.display());
+                continue;
+            }
+        };
+
+        files.push(FileDependencies {
+            path: crate::utils::paths::relative_slash(root, &path),
+            language: result.language.name().to_string(),
+            imports: classify_imports(&result.summary.imports, result.language, &classifier),
+        });
+    }
+
+    Ok(DependencyReport::from_files(
+        root.to_string_lossy().to_string(),
+        files,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, imports: &[&str]) -> FileDependencies {
+        let imports: Vec<String> = imports.iter().map(|s| s.to_string()).collect();
+        FileDependencies {
+            path: path.to_string(),
+            language: "rust".to_string(),
+            imports: classify_imports(&imports, SupportedLanguage::Rust, &ImportClassifier::new()),
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates_external_packages() {
+        let report = DependencyReport::from_files(
+            ".",
+            vec![
+                file("a.rs", &["serde::Serialize", "std::fmt", "crate::x"]),
+                file("b.rs", &["serde::Deserialize", "tokio::fs"]),
+            ],
+        );
+
+        let packages: Vec<&str> = report.external.iter().map(|d| d.package.as_str()).collect();
+        assert_eq!(packages, vec!["serde", "tokio"]);
+        assert_eq!(report.external[0].files, vec!["a.rs", "b.rs"]);
+        assert_eq!(report.count(ImportKind::Std), 1);
+        assert_eq!(report.count(ImportKind::Local), 1);
+
+        let text = report.to_text();
+        assert!(text.contains("[rust] 2 个"));
+        assert!(text.contains("serde （2 个文件）"));
+    }
+
+    #[test]
+    fn test_find_new_dependencies() {
+        let before = vec![file("a.rs", &["serde::Serialize", "std::fmt"])];
+        let after = vec![
+            file("a.rs", &["serde::Serialize", "reqwest::Client"]),
+            file("b.rs", &["serde::Deserialize", "reqwest::Url", "crate::x"]),
+        ];
+
+        let introduced = find_new_dependencies(&before, &after);
+        assert_eq!(introduced.len(), 1);
+        assert_eq!(introduced[0].package, "reqwest");
+        assert_eq!(introduced[0].files, vec!["a.rs", "b.rs"]);
+        assert_eq!(introduced[0].to_string(), "reqwest (rust)");
+        assert!(find_new_dependencies(&after, &before).is_empty());
+    }
+
+    #[test]
+    fn test_changed_files_in_diff() {
+        let diff = "## 已暂存的变更 (Staged Changes):\ndiff --git a/src/a.rs b/src/a.rs\n+use x;\ndiff --git a/old.py b/new.py\n";
+        let files: Vec<String> = changed_files_in_diff(diff).into_iter().collect();
+        assert_eq!(files, vec!["new.py", "src/a.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_report_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("main.py"),
+            "import os\nimport requests\nfrom .util import helper\n",
+        )
+        .unwrap();
+
+        let report = build_dependency_report(dir.path()).await.unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.external.len(), 1);
+        assert_eq!(report.external[0].package, "requests");
+        assert_eq!(report.external[0].language, "python");
+    }
+}
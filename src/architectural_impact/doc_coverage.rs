@@ -0,0 +1,456 @@
+// 文档覆盖率模块
+// 结合注释信息与公共函数/类型，统计每个公共 API 是否带有文档注释
+
+use super::api_diff::ApiItemKind;
+use super::graph_export::collect_files;
+use crate::tree_sitter::{CommentInfo, StructuralSummary, SupportedLanguage, TreeSitterManager};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 文档注释的惯用位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocPlacement {
+    /// 紧贴在条目上方（Rust `///`、Java/JS `/** */`、Go/C 的普通注释）
+    Above,
+    /// 条目体内的首个字符串（Python docstring）
+    Docstring,
+}
+
+impl DocPlacement {
+    /// 语言惯用的文档注释位置
+    pub fn for_language(language: SupportedLanguage) -> Self {
+        match language {
+            SupportedLanguage::Python => DocPlacement::Docstring,
+            _ => DocPlacement::Above,
+        }
+    }
+}
+
+/// 单个公共 API 的文档情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocItem {
+    pub kind: ApiItemKind,
+    pub name: String,
+    pub file_path: String,
+    pub line: usize,
+    pub language: String,
+    pub documented: bool,
+}
+
+/// 单一语言的覆盖率
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageCoverage {
+    pub language: String,
+    pub placement: DocPlacement,
+    pub total: usize,
+    pub documented: usize,
+}
+
+impl LanguageCoverage {
+    /// 覆盖率百分比（没有公共 API 时视为 100%）
+    pub fn percent(&self) -> f64 {
+        percent(self.documented, self.total)
+    }
+}
+
+/// 文档覆盖率报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocCoverageReport {
+    pub root: String,
+    pub files_scanned: usize,
+    pub total: usize,
+    pub documented: usize,
+    /// 按语言名排序的分语言统计
+    pub languages: Vec<LanguageCoverage>,
+    /// 全部公共 API（按文件与行号排序）
+    pub items: Vec<DocItem>,
+}
+
+impl DocCoverageReport {
+    /// 由条目汇总报告
+    pub fn from_items(
+        root: impl Into<String>,
+        files_scanned: usize,
+        mut items: Vec<DocItem>,
+    ) -> Self {
+        items.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+        let mut grouped: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for item in &items {
+            let entry = grouped.entry(item.language.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(item.documented);
+        }
+        let languages = grouped
+            .into_iter()
+            .map(|(language, (total, documented))| LanguageCoverage {
+                language: language.to_string(),
+                placement: SupportedLanguage::from_name(language)
+                    .map(DocPlacement::for_language)
+                    .unwrap_or(DocPlacement::Above),
+                total,
+                documented,
+            })
+            .collect();
+        Self {
+            root: root.into(),
+            files_scanned,
+            total: items.len(),
+            documented: items.iter().filter(|i| i.documented).count(),
+            languages,
+            items,
+        }
+    }
+
+    /// 总体覆盖率百分比（没有公共 API 时视为 100%）
+    pub fn percent(&self) -> f64 {
+        percent(self.documented, self.total)
+    }
+
+    /// 缺少文档的公共 API
+    pub fn undocumented(&self) -> impl Iterator<Item = &DocItem> {
+        self.items.iter().filter(|i| !i.documented)
+    }
+
+    /// 按 `--fail-under` 门禁检查，返回未通过的原因（为空表示通过）
+    pub fn violations(&self, fail_under: Option<f64>) -> Vec<String> {
+        match fail_under {
+            Some(min) if self.percent() < min => vec![format!(
+                "文档覆盖率 {:.1}% 低于要求的 {min:.1}%（{} 个公共 API 缺少文档）",
+                self.percent(),
+                self.total - self.documented
+            )],
+            _ => Vec::new(),
+        }
+    }
+
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "📝 文档覆盖率: {:.1}%（公共 API {} 个，已文档化 {} 个，扫描 {} 个文件）\n",
+            self.percent(),
+            self.total,
+            self.documented,
+            self.files_scanned
+        );
+        for lang in &self.languages {
+            out.push_str(&format!(
+                "  [{}] {:.1}%（{}/{}）\n",
+                lang.language,
+                lang.percent(),
+                lang.documented,
+                lang.total
+            ));
+        }
+
+        let undocumented: Vec<&DocItem> = self.undocumented().collect();
+        if undocumented.is_empty() {
+            out.push_str("\n✅ 所有公共 API 均有文档\n");
+            return out;
+        }
+        out.push_str(&format!(
+            "\n缺少文档的公共 API（{} 个）:\n",
+            undocumented.len()
+        ));
+        for item in undocumented {
+            let kind = match item.kind {
+                ApiItemKind::Function => "fn",
+                ApiItemKind::Class => "type",
+            };
+            out.push_str(&format!(
+                "  {}:{}  {kind} {}\n",
+                item.file_path, item.line, item.name
+            ));
+        }
+        out
+    }
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+/// 从单个文件的结构摘要中提取公共 API 及其文档情况
+pub fn document_items(
+    summary: &StructuralSummary,
+    language: SupportedLanguage,
+    file_path: &str,
+) -> Vec<DocItem> {
+    let placement = DocPlacement::for_language(language);
+    let is_public = |v: &Option<String>| v.as_deref() == Some("public");
+    // 条目起始行，用于判断 docstring 是否属于嵌套条目
+    let starts: Vec<usize> = summary
+        .functions
+        .iter()
+        .map(|f| f.line_start)
+        .chain(summary.classes.iter().map(|c| c.line_start))
+        .collect();
+    let item = |kind, name: &str, line_start, line_end, annotations: usize| DocItem {
+        kind,
+        name: name.to_string(),
+        file_path: file_path.to_string(),
+        line: line_start,
+        language: language.name().to_string(),
+        documented: match placement {
+            DocPlacement::Above => {
+                has_doc_above(&summary.comments, language, line_start, annotations)
+            }
+            DocPlacement::Docstring => {
+                has_docstring(&summary.comments, &starts, line_start, line_end)
+            }
+        },
+    };
+
+    let mut items: Vec<DocItem> = summary
+        .functions
+        .iter()
+        .filter(|f| is_public(&f.visibility))
+        .map(|f| {
+            item(
+                ApiItemKind::Function,
+                &f.name,
+                f.line_start,
+                f.line_end,
+                f.annotations.len(),
+            )
+        })
+        .collect();
+    items.extend(
+        summary
+            .classes
+            .iter()
+            .filter(|c| is_public(&c.visibility))
+            .map(|c| {
+                item(
+                    ApiItemKind::Class,
+                    &c.name,
+                    c.line_start,
+                    c.line_end,
+                    c.annotations.len(),
+                )
+            }),
+    );
+    // 分析器可能对同一方法报告多次（如 impl 块内的方法），按位置去重
+    items.sort_by(|a, b| (a.line, &a.name).cmp(&(b.line, &b.name)));
+    items.dedup_by(|a, b| a.line == b.line && a.name == b.name && a.kind == b.kind);
+    items
+}
+
+/// 条目上方是否紧贴文档注释，中间允许隔着注解/属性行
+///
+/// Go 与 C/C++ 没有专门的文档注释语法，按惯例紧贴上方的普通注释即为文档。
+fn has_doc_above(
+    comments: &[CommentInfo],
+    language: SupportedLanguage,
+    line_start: usize,
+    annotations: usize,
+) -> bool {
+    let plain_is_doc = matches!(
+        language,
+        SupportedLanguage::Go | SupportedLanguage::C | SupportedLanguage::Cpp
+    );
+    let earliest_end = line_start.saturating_sub(1 + annotations);
+    comments
+        .iter()
+        .filter(|c| c.is_doc_comment || plain_is_doc)
+        .any(|c| {
+            let end = c.line + c.text.lines().count().max(1) - 1;
+            end < line_start && end >= earliest_end
+        })
+}
+
+/// 条目体内是否有 docstring：范围内第一个文档字符串之前不能有嵌套条目开始
+fn has_docstring(
+    comments: &[CommentInfo],
+    starts: &[usize],
+    line_start: usize,
+    line_end: usize,
+) -> bool {
+    comments
+        .iter()
+        .filter(|c| c.is_doc_comment && c.line > line_start && c.line <= line_end)
+        .map(|c| c.line)
+        .min()
+        .is_some_and(|doc_line| {
+            !starts
+                .iter()
+                .any(|&start| start > line_start && start < doc_line)
+        })
+}
+
+/// 扫描目录，生成公共 API 文档覆盖率报告
+pub async fn build_doc_coverage(root: &Path) -> Result<DocCoverageReport, DynError> {
+    let mut paths = Vec::new();
+    collect_files(root, &mut paths);
+    paths.sort();
+
+    let mut manager = TreeSitterManager::new().await?;
+    let mut files_scanned = 0;
+    let mut items = Vec::new();
+
+    for path in paths {
+        let result = match manager.analyze_path(&path) {
+            Ok(Some(result)) => result,
+            Ok(None) => continue,
+            Err(e) => {
+                log::debug!("跳过无法分析的文件 {}: {e}", path.display());
+                continue;
+            }
+        };
+        files_scanned += 1;
+        items.extend(document_items(
+            &result.summary,
+            result.language,
+            &crate::utils::paths::relative_slash(root, &path),
+        ));
+    }
+
+    Ok(DocCoverageReport::from_items(
+        root.to_string_lossy().to_string(),
+        files_scanned,
+        items,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree_sitter::{ClassInfo, FunctionInfo};
+
+    fn function(
+        name: &str,
+        lines: (usize, usize),
+        public: bool,
+        annotations: &[&str],
+    ) -> FunctionInfo {
+        FunctionInfo {
+            name: name.to_string(),
+            parameters: vec![],
+            params: vec![],
+            return_type: None,
+            line_start: lines.0,
+            line_end: lines.1,
+            is_async: false,
+            visibility: Some(if public { "public" } else { "private" }.to_string()),
+            generics: vec![],
+            annotations: annotations.iter().map(|a| a.to_string()).collect(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
+        }
+    }
+
+    fn class(name: &str, lines: (usize, usize)) -> ClassInfo {
+        ClassInfo {
+            name: name.to_string(),
+            methods: vec![],
+            fields: vec![],
+            line_start: lines.0,
+            line_end: lines.1,
+            is_abstract: false,
+            extends: None,
+            implements: vec![],
+            visibility: Some("public".to_string()),
+            generics: vec![],
+            annotations: vec![],
+        }
+    }
+
+    fn comment(text: &str, line: usize, is_doc_comment: bool) -> CommentInfo {
+        CommentInfo {
+            text: text.to_string(),
+            line,
+            is_doc_comment,
+        }
+    }
+
+    fn documented(items: &[DocItem]) -> Vec<(&str, bool)> {
+        items
+            .iter()
+            .map(|i| (i.name.as_str(), i.documented))
+            .collect()
+    }
+
+    #[test]
+    fn test_doc_comments_above_items() {
+        // 1: /// doc f   2: #[inline]   3: pub fn f
+        // 5: // plain    6: pub fn g
+        // 8: /// doc h   9: (空行)     10: pub fn h
+        // 11: fn private
+        // 12..14: /** S */   15..17: pub struct S
+        let summary = StructuralSummary {
+            functions: vec![
+                function("f", (3, 3), true, &["inline"]),
+                function("g", (6, 6), true, &[]),
+                // 重复报告的条目只统计一次
+                function("g", (6, 6), true, &[]),
+                function("h", (10, 10), true, &[]),
+                function("private", (11, 11), false, &[]),
+            ],
+            classes: vec![class("S", (15, 17))],
+            comments: vec![
+                comment("/// doc f", 1, true),
+                comment("// plain", 5, false),
+                comment("/// doc h", 8, true),
+                comment("/**\n * S\n */", 12, true),
+            ],
+            ..Default::default()
+        };
+
+        let items = document_items(&summary, SupportedLanguage::Rust, "src/lib.rs");
+        assert_eq!(
+            documented(&items),
+            vec![("f", true), ("g", false), ("h", false), ("S", true)]
+        );
+
+        // Go：紧贴上方的普通注释即文档
+        let items = document_items(&summary, SupportedLanguage::Go, "main.go");
+        assert_eq!(documented(&items)[1], ("g", true));
+    }
+
+    #[test]
+    fn test_python_docstrings_inside_items() {
+        // 1: def f():  2: """Doc."""
+        // 5: class C:  6: def m():  7: """m doc"""
+        // 9: # plain   10: def g():  11: pass
+        let summary = StructuralSummary {
+            functions: vec![
+                function("f", (1, 3), true, &[]),
+                function("m", (6, 7), true, &[]),
+                function("g", (10, 11), true, &[]),
+            ],
+            classes: vec![class("C", (5, 7))],
+            comments: vec![
+                comment("\"\"\"Doc.\"\"\"", 2, true),
+                comment("\"\"\"m doc\"\"\"", 7, true),
+                comment("# plain", 9, false),
+            ],
+            ..Default::default()
+        };
+
+        let items = document_items(&summary, SupportedLanguage::Python, "pkg/mod.py");
+        assert_eq!(
+            documented(&items),
+            vec![("f", true), ("C", false), ("m", true), ("g", false)]
+        );
+
+        let report = DocCoverageReport::from_items(".", 1, items);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.documented, 2);
+        assert_eq!(report.percent(), 50.0);
+        assert_eq!(report.languages[0].placement, DocPlacement::Docstring);
+        assert_eq!(report.items[0].name, "f");
+        assert_eq!(report.undocumented().count(), 2);
+        assert!(report.violations(Some(50.0)).is_empty());
+        assert_eq!(report.violations(Some(80.0)).len(), 1);
+        assert!(report.violations(None).is_empty());
+        assert!(report.to_text().contains("pkg/mod.py:10  fn g"));
+    }
+}
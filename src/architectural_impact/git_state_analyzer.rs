@@ -5,6 +5,7 @@ use crate::error::{GitAIError, GitError};
 use crate::tree_sitter::{StructuralSummary, SupportedLanguage, TreeSitterManager};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// 架构影响分析结果（简化版本）
@@ -62,6 +63,8 @@ pub struct ImpactSummary {
 /// Git 状态分析器
 pub struct GitStateAnalyzer {
     tree_sitter_manager: Option<TreeSitterManager>,
+    /// 被分析的仓库目录；git 命令与工作区文件都相对于该目录
+    repo_dir: PathBuf,
 }
 
 impl Default for GitStateAnalyzer {
@@ -71,18 +74,22 @@ impl Default for GitStateAnalyzer {
 }
 
 impl GitStateAnalyzer {
-    /// 创建新的 Git 状态分析器（同步版本）
+    /// 创建新的 Git 状态分析器（同步版本），分析当前目录所在的仓库
     pub fn new() -> Self {
+        Self::in_repo(Path::new("."))
+    }
+
+    /// 创建分析指定仓库目录的 Git 状态分析器
+    pub fn in_repo(repo_dir: &Path) -> Self {
         Self {
             tree_sitter_manager: None,
+            repo_dir: repo_dir.to_path_buf(),
         }
     }
 
     /// 创建新的 Git 状态分析器（异步版本）
     pub async fn new_async() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        Ok(Self {
-            tree_sitter_manager: None,
-        })
+        Ok(Self::new())
     }
 
     /// 懒加载 TreeSitter 管理器
@@ -105,6 +112,8 @@ impl GitStateAnalyzer {
         commit_ref: &str,
     ) -> Result<String, GitAIError> {
         let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_dir)
             .args(["show", &format!("{commit_ref}:{file_path}")])
             .output()
             .map_err(|e| {
@@ -130,6 +139,8 @@ impl GitStateAnalyzer {
     /// 获取当前工作目录相对于 git 根目录的文件列表
     pub fn get_changed_files(&self) -> Result<Vec<String>, GitAIError> {
         let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_dir)
             .args(["diff", "--name-only", "HEAD~1..HEAD"])
             .output()
             .map_err(|e| {
@@ -181,8 +192,8 @@ impl GitStateAnalyzer {
         &mut self,
         file_path: &str,
     ) -> Result<StructuralSummary, Box<dyn std::error::Error + Send + Sync>> {
-        // 读取当前文件内容
-        let current_content = std::fs::read_to_string(file_path)
+        // 读取当前文件内容（路径相对于仓库目录）
+        let current_content = std::fs::read_to_string(self.repo_dir.join(file_path))
             .map_err(|e| format!("无法读取文件 {file_path}: {e}"))?;
 
         // 推断语言类型
@@ -243,75 +254,8 @@ impl GitStateAnalyzer {
 
     /// 获取当前 git 仓库信息
     pub fn get_git_info(&self) -> Result<crate::architectural_impact::GitInfo, GitAIError> {
-        let current_commit = self.get_current_commit()?;
-        let base_commit = self.get_base_commit("HEAD~1")?;
-        let branch = self.get_current_branch().ok(); // 分支信息是可选的
-
-        Ok(crate::architectural_impact::GitInfo {
-            current_commit,
-            base_commit,
-            branch,
-        })
-    }
-
-    /// 获取当前提交 hash
-    fn get_current_commit(&self) -> Result<String, GitAIError> {
-        let output = Command::new("git")
-            .args(["rev-parse", "HEAD"])
-            .output()
-            .map_err(|e| {
-                GitAIError::Git(GitError::CommandFailed(format!(
-                    "无法执行 git rev-parse: {e}"
-                )))
-            })?;
-
-        if !output.status.success() {
-            return Err(GitAIError::Git(GitError::CommandFailed(
-                "无法获取当前提交hash".to_string(),
-            )));
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    }
-
-    /// 获取基准提交 hash
-    fn get_base_commit(&self, commit_ref: &str) -> Result<String, GitAIError> {
-        let output = Command::new("git")
-            .args(["rev-parse", commit_ref])
-            .output()
-            .map_err(|e| {
-                GitAIError::Git(GitError::CommandFailed(format!(
-                    "无法执行 git rev-parse: {e}"
-                )))
-            })?;
-
-        if !output.status.success() {
-            return Err(GitAIError::Git(GitError::CommandFailed(format!(
-                "无法获取提交hash: {commit_ref}"
-            ))));
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    }
-
-    /// 获取当前分支名
-    fn get_current_branch(&self) -> Result<String, GitAIError> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--abbrev-ref", "HEAD"])
-            .output()
-            .map_err(|e| {
-                GitAIError::Git(GitError::CommandFailed(format!(
-                    "无法执行 git rev-parse: {e}"
-                )))
-            })?;
-
-        if !output.status.success() {
-            return Err(GitAIError::Git(GitError::CommandFailed(
-                "无法获取当前分支名".to_string(),
-            )));
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        crate::git::git_info_in(&self.repo_dir, "HEAD~1")
+            .map_err(|e| GitAIError::Git(GitError::CommandFailed(e.to_string())))
     }
 
     /// 分析 git diff 并返回架构影响
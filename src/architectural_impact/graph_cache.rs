@@ -0,0 +1,204 @@
+// 依赖图增量构建
+// 把逐文件的结构分析结果连同文件大小、修改时间与内容哈希保存为快照，
+// 再次构建时只重新解析新增或变化的文件，其余文件直接复用快照中的分析结果。
+// 图由全部文件的分析结果按路径顺序重新组装，因此与完整重建得到的图一致
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::architectural_impact::dependency_graph::DependencyGraph;
+use crate::tree_sitter::{FileAnalysisOutcome, FileAnalysisResult, StructuralSummary};
+
+/// 快照格式版本；结构发生变化时递增，使旧快照失效
+const SNAPSHOT_VERSION: u32 = 1;
+
+static INCREMENTAL: AtomicBool = AtomicBool::new(false);
+
+/// 开启或关闭增量构建（`gitai graph --incremental`）
+pub fn set_incremental(enabled: bool) {
+    INCREMENTAL.store(enabled, Ordering::Relaxed);
+}
+
+/// 是否增量构建依赖图
+pub fn incremental() -> bool {
+    INCREMENTAL.load(Ordering::Relaxed)
+}
+
+/// 扫描目录对应的快照文件：`~/.cache/gitai/graph/<目录哈希>.json`
+pub fn snapshot_path(scan_dir: &Path) -> PathBuf {
+    let root = std::fs::canonicalize(scan_dir).unwrap_or_else(|_| scan_dir.to_path_buf());
+    let key = format!(
+        "{:x}",
+        md5::compute(crate::utils::paths::to_slash(&root).as_bytes())
+    );
+    crate::utils::paths::graph_cache_dir().join(format!("{key}.json"))
+}
+
+/// 增量构建的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct IncrementalStats {
+    /// 复用快照的文件数
+    pub reused: usize,
+    /// 重新解析的文件数
+    pub reparsed: usize,
+    /// 快照中已不存在的文件数
+    pub removed: usize,
+}
+
+/// 快照中单个文件的分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotEntry {
+    len: u64,
+    modified_ns: u128,
+    hash: String,
+    language: String,
+    summary: StructuralSummary,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GraphSnapshot {
+    version: u32,
+    /// 结构分析缓存的格式版本，分析器输出变化时快照一并失效
+    analysis_version: u32,
+    files: BTreeMap<String, SnapshotEntry>,
+}
+
+impl GraphSnapshot {
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<GraphSnapshot>(&content).ok())
+            .filter(|snapshot| {
+                snapshot.version == SNAPSHOT_VERSION
+                    && snapshot.analysis_version == crate::tree_sitter::cache::CACHE_VERSION
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// 文件的大小与修改时间（纳秒）
+fn file_stamp(path: &Path) -> Option<(u64, u128)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some((meta.len(), modified))
+}
+
+fn content_hash(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("{:x}", md5::compute(bytes)))
+}
+
+/// 快照中的结果是否仍可用：大小与修改时间一致，或内容哈希一致（仅被 touch 过）
+fn reusable(entry: &SnapshotEntry, path: &Path) -> bool {
+    match file_stamp(path) {
+        Some((len, modified)) if len == entry.len && modified == entry.modified_ns => true,
+        Some((len, _)) if len == entry.len => content_hash(path).is_some_and(|h| h == entry.hash),
+        _ => false,
+    }
+}
+
+/// 增量构建依赖图：复用 `snapshot` 中未变化文件的分析结果，只重新解析变化的文件，
+/// 构建完成后更新快照
+pub async fn build_incremental_dependency_graph(
+    scan_dir: &Path,
+    max_concurrent: Option<usize>,
+    snapshot: &Path,
+) -> Result<(DependencyGraph, IncrementalStats), Box<dyn std::error::Error + Send + Sync>> {
+    let mut paths = Vec::new();
+    let generated = super::graph_export::collect_files(scan_dir, &mut paths);
+    paths.sort();
+    if generated > 0 {
+        crate::progress!(
+            "{}",
+            crate::tr!("analysis.skipped_generated", count = generated)
+        );
+    }
+
+    let mut previous = GraphSnapshot::load(snapshot);
+    let mut stats = IncrementalStats::default();
+    let mut analyses: Vec<Option<FileAnalysisResult>> = Vec::with_capacity(paths.len());
+    let mut changed: Vec<(usize, PathBuf)> = Vec::new();
+    let mut next = GraphSnapshot {
+        version: SNAPSHOT_VERSION,
+        analysis_version: crate::tree_sitter::cache::CACHE_VERSION,
+        files: BTreeMap::new(),
+    };
+
+    for path in &paths {
+        let key = crate::utils::paths::to_slash(path);
+        let cached = previous
+            .files
+            .remove(&key)
+            .filter(|entry| reusable(entry, path));
+        let language = cached
+            .as_ref()
+            .and_then(|entry| crate::tree_sitter::SupportedLanguage::from_name(&entry.language));
+        match (cached, language) {
+            (Some(entry), Some(language)) => {
+                stats.reused += 1;
+                analyses.push(Some(FileAnalysisResult {
+                    path: path.clone(),
+                    language,
+                    summary: entry.summary.clone(),
+                }));
+                next.files.insert(key, entry);
+            }
+            _ => {
+                changed.push((analyses.len(), path.clone()));
+                analyses.push(None);
+            }
+        }
+    }
+    stats.removed = previous.files.len();
+
+    let changed_paths: Vec<PathBuf> = changed.iter().map(|(_, p)| p.clone()).collect();
+    let outcomes = crate::tree_sitter::analyze_paths_with_outcomes(
+        &changed_paths,
+        max_concurrent,
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+    .await?;
+    let mut failed = 0;
+    for ((slot, path), outcome) in changed.into_iter().zip(outcomes) {
+        match outcome {
+            FileAnalysisOutcome::Analyzed(analysis) => {
+                stats.reparsed += 1;
+                if let (Some((len, modified_ns)), Some(hash)) =
+                    (file_stamp(&path), content_hash(&path))
+                {
+                    next.files.insert(
+                        crate::utils::paths::to_slash(&path),
+                        SnapshotEntry {
+                            len,
+                            modified_ns,
+                            hash,
+                            language: analysis.language.name().to_string(),
+                            summary: analysis.summary.clone(),
+                        },
+                    );
+                }
+                analyses[slot] = Some(*analysis);
+            }
+            FileAnalysisOutcome::Unsupported => {}
+            FileAnalysisOutcome::Failed(_) => failed += 1,
+        }
+    }
+    if failed > 0 {
+        crate::progress!("{}", crate::tr!("analysis.failed_files", count = failed));
+    }
+
+    if let Some(parent) = snapshot.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write::write_atomic(snapshot, serde_json::to_vec(&next)?)?;
+
+    Ok((super::graph_export::graph_from_analyses(analyses), stats))
+}
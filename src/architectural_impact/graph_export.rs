@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::architectural_impact::dependency_graph::{DependencyGraph, DotOptions, NodeType};
+use crate::architectural_impact::dependency_graph::{
+    DependencyGraph, DotOptions, GraphFilter, NodeType,
+};
 use crate::git;
-use crate::tree_sitter::{SupportedLanguage, TreeSitterManager};
 
 fn is_code_file(path: &Path) -> bool {
     matches!(
@@ -24,14 +25,54 @@ fn is_code_file(path: &Path) -> bool {
     )
 }
 
-fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
-    if !dir.exists() {
-        return;
+/// 递归收集代码文件，命中生成文件模式的文件被跳过；返回跳过的生成文件数。
+/// 遍历深度与符号链接策略使用全局遍历选项（见 [`crate::utils::walk`]）
+pub(crate) fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> usize {
+    collect_files_with(dir, out, &crate::utils::walk::active())
+}
+
+/// 同 [`collect_files`]，使用指定的遍历选项
+pub(crate) fn collect_files_with(
+    dir: &Path,
+    out: &mut Vec<PathBuf>,
+    options: &crate::utils::walk::WalkOptions,
+) -> usize {
+    let mut visited = HashSet::new();
+    if let Ok(root) = fs::canonicalize(dir) {
+        visited.insert(root);
+    }
+    collect_files_at(dir, out, options, 1, &mut visited)
+}
+
+/// depth 为 dir 中条目的深度；visited 记录已进入目录的规范路径，
+/// 跟随符号链接时用于检测环路（同一目录只遍历一次）
+fn collect_files_at(
+    dir: &Path,
+    out: &mut Vec<PathBuf>,
+    options: &crate::utils::walk::WalkOptions,
+    depth: usize,
+    visited: &mut HashSet<PathBuf>,
+) -> usize {
+    if !dir.exists() || !options.allows_depth(depth) {
+        return 0;
     }
+    let mut generated = 0;
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_dir() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // 默认不跟随符号链接，避免环路与扫描到仓库之外
+            let is_dir = if file_type.is_symlink() {
+                if !options.follow_symlinks {
+                    continue;
+                }
+                path.is_dir()
+            } else {
+                file_type.is_dir()
+            };
+            if is_dir {
                 // 跳过常见的无关目录
                 if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
                     if [
@@ -49,23 +90,80 @@ fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
                         continue;
                     }
                 }
-                collect_files(&path, out);
+                if options.follow_symlinks
+                    && !fs::canonicalize(&path).is_ok_and(|real| visited.insert(real))
+                {
+                    continue;
+                }
+                generated += collect_files_at(&path, out, options, depth + 1, visited);
             } else if is_code_file(&path) {
-                out.push(path);
+                if crate::utils::generated::is_generated_path(&path) {
+                    generated += 1;
+                } else {
+                    out.push(path);
+                }
             }
         }
     }
+    generated
 }
 
 /// 从给定目录构建全局依赖图（跨文件调用会在后处理阶段尽力解析）
 pub async fn build_global_dependency_graph(
     scan_dir: &Path,
 ) -> Result<DependencyGraph, Box<dyn std::error::Error + Send + Sync>> {
-    let mut files = Vec::new();
-    collect_files(scan_dir, &mut files);
-    files.sort();
+    build_global_dependency_graph_with(scan_dir, None).await
+}
+
+/// 同 [`build_global_dependency_graph`]，max_concurrent 指定并发分析的工作线程数
+/// （None 时使用 `GITAI_ANALYSIS_CONCURRENCY` 或 CPU 可用并行度）；
+/// 开启增量构建（[`graph_cache::set_incremental`](super::graph_cache::set_incremental)）时只重新解析变化的文件
+pub async fn build_global_dependency_graph_with(
+    scan_dir: &Path,
+    max_concurrent: Option<usize>,
+) -> Result<DependencyGraph, Box<dyn std::error::Error + Send + Sync>> {
+    if super::graph_cache::incremental() {
+        let snapshot = super::graph_cache::snapshot_path(scan_dir);
+        let (graph, stats) = super::graph_cache::build_incremental_dependency_graph(
+            scan_dir,
+            max_concurrent,
+            &snapshot,
+        )
+        .await?;
+        crate::progress!(
+            "{}",
+            crate::tr!(
+                "graph.incremental",
+                reused = stats.reused,
+                reparsed = stats.reparsed,
+                removed = stats.removed
+            )
+        );
+        return Ok(graph);
+    }
+    let analysis =
+        crate::tree_sitter::analyze_directory_concurrent(scan_dir, max_concurrent).await?;
+    if analysis.generated > 0 {
+        crate::progress!(
+            "{}",
+            crate::tr!("analysis.skipped_generated", count = analysis.generated)
+        );
+    }
+    if analysis.failed_count() > 0 {
+        crate::progress!(
+            "{}",
+            crate::tr!("analysis.failed_files", count = analysis.failed_count())
+        );
+    }
+    Ok(graph_from_analyses(
+        analysis.files.into_iter().map(Some).collect(),
+    ))
+}
 
-    let mut manager = TreeSitterManager::new().await?;
+/// 由逐文件的分析结果构建全局依赖图（无法分析的文件为 None，会被跳过）
+pub fn graph_from_analyses(
+    analyses: Vec<Option<crate::tree_sitter::FileAnalysisResult>>,
+) -> DependencyGraph {
     let mut global_graph = DependencyGraph::new();
 
     // 暂存跨文件调用以便后处理
@@ -76,43 +174,27 @@ pub async fn build_global_dependency_graph(
     }
     let mut pending_calls: Vec<PendingCall> = Vec::new();
 
-    for path in files {
-        let path_str = path.to_string_lossy().to_string();
-        let ext = path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
-        let Some(lang) = SupportedLanguage::from_extension(&ext) else {
-            continue;
-        };
-        let Ok(code) = fs::read_to_string(&path) else {
-            continue;
-        };
+    // 按文件顺序合并，保证结果与并发度无关；无法分析的文件已被跳过
+    for analysis in analyses.into_iter().flatten() {
+        let path_str = crate::utils::paths::to_slash(&analysis.path);
+        let summary = analysis.summary;
 
-        match manager.analyze_structure(&code, lang) {
-            Ok(summary) => {
-                // 收集调用信息（用于跨文件解析）
-                for call in &summary.calls {
-                    pending_calls.push(PendingCall {
-                        file_path: path_str.clone(),
-                        line: call.line,
-                        callee: call.callee.clone(),
-                    });
-                }
+        // 收集调用信息（用于跨文件解析）
+        for call in &summary.calls {
+            pending_calls.push(PendingCall {
+                file_path: path_str.clone(),
+                line: call.line,
+                callee: call.callee.clone(),
+            });
+        }
 
-                let sub_graph = DependencyGraph::from_structural_summary(&summary, &path_str);
-                // 合并节点
-                for (id, node) in sub_graph.nodes.into_iter() {
-                    global_graph.nodes.entry(id).or_insert(node);
-                }
-                // 合并边
-                global_graph.edges.extend(sub_graph.edges.into_iter());
-            }
-            Err(_e) => {
-                // 忽略单文件失败，继续其它文件
-            }
+        let sub_graph = DependencyGraph::from_structural_summary(&summary, &path_str);
+        // 合并节点
+        for (id, node) in sub_graph.nodes.into_iter() {
+            global_graph.nodes.entry(id).or_insert(node);
         }
+        // 合并边
+        global_graph.edges.extend(sub_graph.edges);
     }
 
     // 先重建邻接表以保证基础索引
@@ -126,20 +208,27 @@ pub async fn build_global_dependency_graph(
     // 变更后重建邻接表
     global_graph.rebuild_adjacency_lists();
 
-    Ok(global_graph)
+    global_graph
 }
 
-/// 导出 DOT 文本（含高亮关键节点）
+/// 导出 DOT 文本（含高亮关键节点），filter 限定导出的节点/边类型，max_concurrent 限定分析并发度
+///
+/// highlight_threshold 同时用于过滤弱调用边：调用次数低于最热调用边 × 阈值的边不输出
 pub async fn export_dot_string(
     scan_dir: &Path,
     highlight_threshold: f32,
+    filter: &GraphFilter,
+    max_concurrent: Option<usize>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let graph = build_global_dependency_graph(scan_dir).await?;
+    let mut graph = build_global_dependency_graph_with(scan_dir, max_concurrent)
+        .await?
+        .filtered(filter);
     let critical: Vec<String> = graph
         .identify_critical_nodes(highlight_threshold)
         .into_iter()
         .map(|(id, _)| id.clone())
         .collect();
+    graph.prune_weak_edges(highlight_threshold);
     let options = DotOptions {
         include_weights: true,
         highlight_nodes: critical,
@@ -147,14 +236,78 @@ pub async fn export_dot_string(
     Ok(graph.to_dot(Some(&options)))
 }
 
-/// 导出 LLM 友好的图摘要（v1：在 v0 基础上可选社区压缩，文本/JSON）
+/// 导出关键节点报告（割点与介数中心性，按影响分数排序，文本/JSON）
+pub async fn export_critical_nodes_string(
+    scan_dir: &Path,
+    top_k: usize,
+    format: &str,
+    filter: &GraphFilter,
+    max_concurrent: Option<usize>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let graph = build_global_dependency_graph_with(scan_dir, max_concurrent)
+        .await?
+        .filtered(filter);
+    let report = super::critical_nodes::analyze_critical_nodes(&graph, top_k);
+    if format == "json" {
+        Ok(serde_json::to_string_pretty(&report)?)
+    } else {
+        Ok(report.to_text())
+    }
+}
+
+/// 图分析归档包的结构版本，字段含义变化时递增
+pub const GRAPH_BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+/// 图分析归档包（`gitai graph --format bundle`），一次输出完整图、摘要与生成参数
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphBundle {
+    pub metadata: GraphBundleMetadata,
+    pub graph: GraphBundleGraph,
+    /// 与 `--summary --summary-format json` 相同的摘要对象
+    pub summary: serde_json::Value,
+}
+
+/// 归档包的生成参数
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphBundleMetadata {
+    pub schema_version: u32,
+    /// 生成时间（RFC 3339）
+    pub generated_at: String,
+    pub path: String,
+    /// 关键节点阈值
+    pub threshold: f32,
+    pub radius: usize,
+    pub top_k: usize,
+    pub budget_tokens: usize,
+    pub seeds_from_diff: bool,
+    /// 摘要使用的种子节点 ID
+    pub seeds: Vec<String>,
+    /// 限定的节点类型（为空表示不限制）
+    #[serde(default)]
+    pub node_types: Vec<String>,
+    /// 限定的边类型（为空表示不限制）
+    #[serde(default)]
+    pub edge_types: Vec<String>,
+}
+
+/// 归档包中的完整依赖图
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GraphBundleGraph {
+    /// 按 ID 排序的节点
+    pub nodes: Vec<crate::architectural_impact::dependency_graph::Node>,
+    pub edges: Vec<crate::architectural_impact::dependency_graph::Edge>,
+    /// 超过阈值的关键节点 ID（即 DOT 输出中高亮的节点）
+    pub critical_nodes: Vec<String>,
+}
+
+/// 导出图分析归档包（JSON），内部复用完整图构建与摘要导出
 #[allow(clippy::too_many_arguments)]
-pub async fn export_summary_string(
+pub async fn export_bundle_string(
     scan_dir: &Path,
+    threshold: f32,
     radius: usize,
     top_k: usize,
     seeds_from_diff: bool,
-    format: &str,
     budget_tokens: usize,
     with_communities: bool,
     comm_alg: &str,
@@ -163,36 +316,82 @@ pub async fn export_summary_string(
     with_paths: bool,
     path_samples: usize,
     path_max_hops: usize,
+    filter: &GraphFilter,
+    max_concurrent: Option<usize>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let mut graph = build_global_dependency_graph(scan_dir).await?;
-
-    // 计算 PageRank 以填充 importance_score
+    let mut graph = build_global_dependency_graph_with(scan_dir, max_concurrent)
+        .await?
+        .filtered(filter);
     let _pr = graph.calculate_pagerank(0.85, 20, 1e-6);
 
+    let mut critical_nodes: Vec<String> = graph
+        .identify_critical_nodes(threshold)
+        .into_iter()
+        .map(|(id, _)| id.clone())
+        .collect();
+    critical_nodes.sort();
+    let seeds = collect_seed_ids(&graph, seeds_from_diff);
+    let mut nodes: Vec<_> = graph.nodes.values().cloned().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let summary = export_summary_string(
+        scan_dir,
+        radius,
+        top_k,
+        seeds_from_diff,
+        "json",
+        budget_tokens,
+        with_communities,
+        comm_alg,
+        max_communities,
+        max_nodes_per_community,
+        with_paths,
+        path_samples,
+        path_max_hops,
+        filter,
+        max_concurrent,
+    )
+    .await?;
+
+    let bundle = GraphBundle {
+        metadata: GraphBundleMetadata {
+            schema_version: GRAPH_BUNDLE_SCHEMA_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            path: scan_dir.display().to_string(),
+            threshold,
+            radius,
+            top_k,
+            budget_tokens,
+            seeds_from_diff,
+            seeds,
+            node_types: filter.node_types.clone(),
+            edge_types: filter
+                .edge_types
+                .iter()
+                .map(|t| t.name().to_string())
+                .collect(),
+        },
+        graph: GraphBundleGraph {
+            nodes,
+            edges: graph.edges.clone(),
+            critical_nodes,
+        },
+        summary: serde_json::from_str(&summary)?,
+    };
+    Ok(serde_json::to_string_pretty(&bundle)?)
+}
+
+/// 摘要种子节点：从 git diff 推导变更涉及的节点，没有变更时退化为全局 Top-20 重要节点
+fn collect_seed_ids(graph: &DependencyGraph, seeds_from_diff: bool) -> Vec<String> {
     // 种子：从 git diff 推导（按文件）
     let mut seed_ids: Vec<String> = Vec::new();
     if seeds_from_diff {
-        let mut changed_files = std::collections::HashSet::new();
-        // 已暂存
-        if let Ok(staged) = git::run_git(&[
-            "diff".to_string(),
-            "--cached".to_string(),
-            "--name-only".to_string(),
-        ]) {
-            for line in staged.lines() {
-                if !line.trim().is_empty() {
-                    changed_files.insert(line.trim().to_string());
-                }
-            }
-        }
-        // 未暂存
-        if let Ok(unstaged) = git::run_git(&["diff".to_string(), "--name-only".to_string()]) {
-            for line in unstaged.lines() {
-                if !line.trim().is_empty() {
-                    changed_files.insert(line.trim().to_string());
-                }
-            }
-        }
+        // 已暂存 + 未暂存
+        let changed_files: std::collections::HashSet<String> = git::diff_changed_files()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|change| change.path)
+            .collect();
         // 从节点元数据匹配文件
         for (id, node) in &graph.nodes {
             // 仅匹配文件/函数/类的 file_path
@@ -222,6 +421,37 @@ pub async fn export_summary_string(
         }
     }
 
+    seed_ids
+}
+
+/// 导出 LLM 友好的图摘要（v1：在 v0 基础上可选社区压缩，文本/JSON），filter 限定参与摘要的节点/边类型
+#[allow(clippy::too_many_arguments)]
+pub async fn export_summary_string(
+    scan_dir: &Path,
+    radius: usize,
+    top_k: usize,
+    seeds_from_diff: bool,
+    format: &str,
+    budget_tokens: usize,
+    with_communities: bool,
+    comm_alg: &str,
+    max_communities: usize,
+    max_nodes_per_community: usize,
+    with_paths: bool,
+    path_samples: usize,
+    path_max_hops: usize,
+    filter: &GraphFilter,
+    max_concurrent: Option<usize>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut graph = build_global_dependency_graph_with(scan_dir, max_concurrent)
+        .await?
+        .filtered(filter);
+
+    // 计算 PageRank 以填充 importance_score
+    let _pr = graph.calculate_pagerank(0.85, 20, 1e-6);
+
+    let seed_ids = collect_seed_ids(&graph, seeds_from_diff);
+
     // 从种子出发做有限半径的邻域采样（双向）
     let mut kept: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut queue: std::collections::VecDeque<(String, usize)> = std::collections::VecDeque::new();
@@ -336,7 +566,7 @@ pub async fn export_summary_string(
         }
         let mut edge_vec: Vec<((String, String), (usize, f32))> =
             edge_buckets.into_iter().collect();
-        edge_vec.sort_by(|a, b| b.1 .0.cmp(&a.1 .0));
+        edge_vec.sort_by_key(|e| std::cmp::Reverse(e.1 .0));
         for ((src, dst), (cnt, wsum)) in edge_vec.into_iter().take(max_communities * 2) {
             comm_edges_out.push((src, dst, cnt, wsum));
         }
@@ -1,15 +1,21 @@
 use serde::{Deserialize, Serialize};
 
 pub mod ai_context;
+pub mod api_diff;
 pub mod ast_comparison;
 pub mod breaking_changes;
 pub mod cascade_detector;
+pub mod critical_nodes;
 pub mod dependency_graph;
+pub mod dependency_report;
+pub mod doc_coverage;
 pub mod git_state_analyzer;
+pub mod graph_cache;
 pub mod graph_export;
 pub mod impact_propagation;
 pub mod impact_report;
 pub mod risk_assessment;
+pub mod version_bump;
 
 // 重新导出git_state_analyzer模块的公共类型
 pub use git_state_analyzer::{ArchitecturalImpact, GitStateAnalyzer};
@@ -100,6 +106,18 @@ pub enum RiskLevel {
     None,
 }
 
+impl crate::domain::entities::Scorable for RiskLevel {
+    fn score(&self) -> f64 {
+        match self {
+            RiskLevel::Critical => 1.0,
+            RiskLevel::High => 0.8,
+            RiskLevel::Medium => 0.5,
+            RiskLevel::Low => 0.3,
+            RiskLevel::None => 0.0,
+        }
+    }
+}
+
 /// 影响级别枚举
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ImpactLevel {
@@ -0,0 +1,458 @@
+// 语义化版本建议模块
+// 根据公共 API 变化推断应提升的版本号（major / minor / patch），并给出理由
+
+use super::api_diff::{diff_api_items, ApiChange, ApiChangeKind, ApiItem, ApiItemKind};
+use super::git_state_analyzer::infer_language_from_path;
+use super::{BreakingChange, BreakingChangeType};
+use crate::tree_sitter::SupportedLanguage;
+use serde::{Deserialize, Serialize};
+
+/// 版本提升级别（按严重程度排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl std::fmt::Display for VersionBump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            VersionBump::Patch => "patch",
+            VersionBump::Minor => "minor",
+            VersionBump::Major => "major",
+        };
+        f.write_str(s)
+    }
+}
+
+impl From<&BreakingChangeType> for VersionBump {
+    fn from(change_type: &BreakingChangeType) -> Self {
+        match change_type {
+            BreakingChangeType::FunctionAdded => VersionBump::Minor,
+            _ => VersionBump::Major,
+        }
+    }
+}
+
+/// 单条推断理由及其对应的提升级别
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BumpReason {
+    pub level: VersionBump,
+    pub message: String,
+}
+
+impl std::fmt::Display for BumpReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} → {}", self.message, self.level)
+    }
+}
+
+/// 版本建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSuggestion {
+    pub bump: VersionBump,
+    /// 当前版本（从基准引用解析，可能为空）
+    pub current_version: Option<String>,
+    /// 建议的下一个版本
+    pub next_version: Option<String>,
+    /// 推断理由，级别高的在前；为空表示没有检测到需要提升版本的变化
+    pub reasons: Vec<BumpReason>,
+    /// 计算下一版本时的附加说明（如 0.x 版本降级、版本号无法解析）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+impl VersionSuggestion {
+    fn from_reasons(mut reasons: Vec<BumpReason>) -> Self {
+        // 稳定排序：破坏性理由在前，同级保持原顺序
+        reasons.sort_by_key(|r| std::cmp::Reverse(r.level));
+        VersionSuggestion {
+            bump: reasons
+                .iter()
+                .map(|r| r.level)
+                .max()
+                .unwrap_or(VersionBump::Patch),
+            current_version: None,
+            next_version: None,
+            reasons,
+            notes: Vec::new(),
+        }
+    }
+
+    /// 是否建议了 minor 及以上的版本提升；提交与评审流程只在此时提示
+    pub fn is_suggested(&self) -> bool {
+        self.bump > VersionBump::Patch
+    }
+
+    /// 以 `; ` 连接的理由文本，写入结果详情
+    pub fn reasons_text(&self) -> String {
+        self.reasons
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// 根据当前版本号计算建议的下一个版本
+    ///
+    /// 接受 `v1.2.3` / `1.2.3` 形式；0.x 版本遵循 Cargo 惯例，
+    /// 破坏性变更提升次版本号，新增功能提升修订号。
+    pub fn with_current_version(mut self, version: &str) -> Self {
+        let Ok(current) = ::semver::Version::parse(version.trim_start_matches('v')) else {
+            self.notes
+                .push(format!("无法从 `{version}` 解析版本号，未计算下一版本"));
+            return self;
+        };
+
+        let mut next = ::semver::Version::new(current.major, current.minor, current.patch);
+        let effective = if current.major == 0 && self.bump > VersionBump::Patch {
+            self.notes
+                .push("当前为 0.x 版本，按惯例将级别整体下调一级".to_string());
+            if self.bump == VersionBump::Major {
+                VersionBump::Minor
+            } else {
+                VersionBump::Patch
+            }
+        } else {
+            self.bump
+        };
+
+        match effective {
+            VersionBump::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            VersionBump::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            VersionBump::Patch => next.patch += 1,
+        }
+
+        self.current_version = Some(current.to_string());
+        self.next_version = Some(next.to_string());
+        self
+    }
+
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        const MAX_REASONS: usize = 10;
+
+        let mut out = format!("🏷️  版本建议: {}", self.bump);
+        if let (Some(current), Some(next)) = (&self.current_version, &self.next_version) {
+            out.push_str(&format!("（{current} → {next}）"));
+        }
+        out.push('\n');
+
+        if self.reasons.is_empty() {
+            out.push_str("  • 未检测到公共 API 变化\n");
+        }
+        for reason in self.reasons.iter().take(MAX_REASONS) {
+            out.push_str(&format!("  • {reason}\n"));
+        }
+        if self.reasons.len() > MAX_REASONS {
+            out.push_str(&format!(
+                "  … 另有 {} 条\n",
+                self.reasons.len() - MAX_REASONS
+            ));
+        }
+        for note in &self.notes {
+            out.push_str(&format!("  • {note}\n"));
+        }
+        out
+    }
+}
+
+/// 根据 API 变化列表推断版本提升
+///
+/// 删除与签名变化 → major；仅新增 → minor；否则 → patch。
+pub fn suggest_from_changes(changes: &[ApiChange]) -> VersionSuggestion {
+    let reasons = changes
+        .iter()
+        .map(|change| {
+            let (level, message) = match change.change {
+                ApiChangeKind::Removed => (
+                    VersionBump::Major,
+                    format!("删除公共 API `{}`（{}）", change.name, change.file_path),
+                ),
+                ApiChangeKind::SignatureChanged => (
+                    VersionBump::Major,
+                    format!(
+                        "公共 API `{}` 签名变化（{}）",
+                        change.name, change.file_path
+                    ),
+                ),
+                ApiChangeKind::Added => (
+                    VersionBump::Minor,
+                    format!("新增公共 API `{}`（{}）", change.name, change.file_path),
+                ),
+            };
+            BumpReason { level, message }
+        })
+        .collect();
+    VersionSuggestion::from_reasons(reasons)
+}
+
+/// 根据架构影响分析的破坏性变更推断版本提升
+pub fn suggest_from_breaking_changes(changes: &[BreakingChange]) -> VersionSuggestion {
+    let reasons = changes
+        .iter()
+        .map(|change| BumpReason {
+            level: VersionBump::from(&change.change_type),
+            message: format!(
+                "{:?} `{}`（{}）",
+                change.change_type, change.component, change.file_path
+            ),
+        })
+        .collect();
+    VersionSuggestion::from_reasons(reasons)
+}
+
+/// 直接基于 unified diff 推断版本提升
+///
+/// 仅检查增删行中的公共声明（Rust `pub`、Java `public`、JS/TS `export`、
+/// Go 首字母大写、Python 非下划线定义），适合提交/评审流程中的快速提示。
+pub fn suggest_from_diff(diff: &str) -> VersionSuggestion {
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let mut current: Option<(String, SupportedLanguage)> = None;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git") {
+            current = line
+                .split_whitespace()
+                .last()
+                .map(|p| p.trim_start_matches("b/").to_string())
+                .and_then(|p| infer_language_from_path(&p).ok().map(|lang| (p, lang)));
+            continue;
+        }
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        let Some((ref file, language)) = current else {
+            continue;
+        };
+
+        let (target, content) = if let Some(content) = line.strip_prefix('+') {
+            (&mut after, content)
+        } else if let Some(content) = line.strip_prefix('-') {
+            (&mut before, content)
+        } else {
+            continue;
+        };
+
+        if let Some((kind, name)) = public_declaration(language, content) {
+            target.push(ApiItem {
+                kind,
+                name,
+                file_path: file.clone(),
+                signature: normalize_signature(content),
+                line: 0,
+            });
+        }
+    }
+
+    suggest_from_changes(&diff_api_items(&before, &after))
+}
+
+fn normalize_signature(line: &str) -> String {
+    let joined = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    joined
+        .trim_end_matches(['{', ':', ';'])
+        .trim_end()
+        .to_string()
+}
+
+fn leading_ident(text: &str) -> Option<String> {
+    let ident: String = text
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    (!ident.is_empty()).then_some(ident)
+}
+
+/// 识别一行源码是否为公共声明，返回 (类型, 名称)
+fn public_declaration(language: SupportedLanguage, line: &str) -> Option<(ApiItemKind, String)> {
+    let trimmed = line.trim();
+    let after_keyword = |text: &str, keywords: &[(&str, ApiItemKind)]| {
+        keywords.iter().find_map(|(kw, kind)| {
+            text.strip_prefix(kw)
+                .and_then(leading_ident)
+                .map(|name| (*kind, name))
+        })
+    };
+
+    match language {
+        SupportedLanguage::Rust => {
+            let mut rest = trimmed.strip_prefix("pub ")?;
+            for qualifier in ["const ", "async ", "unsafe ", "extern \"C\" "] {
+                rest = rest.strip_prefix(qualifier).unwrap_or(rest);
+            }
+            after_keyword(
+                rest,
+                &[
+                    ("fn ", ApiItemKind::Function),
+                    ("struct ", ApiItemKind::Class),
+                    ("enum ", ApiItemKind::Class),
+                    ("trait ", ApiItemKind::Class),
+                    ("type ", ApiItemKind::Class),
+                    ("union ", ApiItemKind::Class),
+                ],
+            )
+        }
+        SupportedLanguage::Java => {
+            if !trimmed.split_whitespace().any(|w| w == "public") {
+                return None;
+            }
+            for kw in ["class ", "interface ", "enum ", "record "] {
+                if let Some(pos) = trimmed.find(kw) {
+                    return leading_ident(&trimmed[pos + kw.len()..])
+                        .map(|name| (ApiItemKind::Class, name));
+                }
+            }
+            let head = &trimmed[..trimmed.find('(')?];
+            let name = head.split_whitespace().last()?;
+            Some((ApiItemKind::Function, name.to_string()))
+        }
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+            let mut rest = trimmed.strip_prefix("export ")?;
+            for qualifier in ["default ", "async ", "abstract "] {
+                rest = rest.strip_prefix(qualifier).unwrap_or(rest);
+            }
+            after_keyword(
+                rest,
+                &[
+                    ("function ", ApiItemKind::Function),
+                    ("class ", ApiItemKind::Class),
+                    ("interface ", ApiItemKind::Class),
+                ],
+            )
+        }
+        SupportedLanguage::Go => {
+            let (kind, name) = if let Some(rest) = trimmed.strip_prefix("func ") {
+                // 跳过方法接收者
+                let rest = if rest.starts_with('(') {
+                    &rest[rest.find(')')? + 1..]
+                } else {
+                    rest
+                };
+                (ApiItemKind::Function, leading_ident(rest)?)
+            } else {
+                let rest = trimmed.strip_prefix("type ")?;
+                (ApiItemKind::Class, leading_ident(rest)?)
+            };
+            name.chars()
+                .next()
+                .is_some_and(char::is_uppercase)
+                .then_some((kind, name))
+        }
+        SupportedLanguage::Python => {
+            let rest = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+            let (kind, name) = after_keyword(
+                rest,
+                &[
+                    ("def ", ApiItemKind::Function),
+                    ("class ", ApiItemKind::Class),
+                ],
+            )?;
+            let dunder = name.starts_with("__") && name.ends_with("__");
+            (!name.starts_with('_') || dunder).then_some((kind, name))
+        }
+        SupportedLanguage::C | SupportedLanguage::Cpp => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(kind: ApiChangeKind) -> ApiChange {
+        ApiChange {
+            change: kind,
+            kind: ApiItemKind::Function,
+            name: "foo".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            before: None,
+            after: None,
+            breaking: kind != ApiChangeKind::Added,
+        }
+    }
+
+    #[test]
+    fn test_suggest_from_changes_levels() {
+        let none = suggest_from_changes(&[]);
+        assert_eq!(none.bump, VersionBump::Patch);
+        assert!(!none.is_suggested());
+        assert!(none.reasons.is_empty());
+        assert_eq!(
+            suggest_from_changes(&[change(ApiChangeKind::Added)]).bump,
+            VersionBump::Minor
+        );
+        let major = suggest_from_changes(&[
+            change(ApiChangeKind::Added),
+            change(ApiChangeKind::SignatureChanged),
+        ]);
+        assert_eq!(major.bump, VersionBump::Major);
+        assert!(major.is_suggested());
+        let levels: Vec<_> = major.reasons.iter().map(|r| r.level).collect();
+        assert_eq!(levels, vec![VersionBump::Major, VersionBump::Minor]);
+        assert!(major.reasons[0].message.contains("签名变化"));
+    }
+
+    #[test]
+    fn test_with_current_version() {
+        let major = suggest_from_changes(&[change(ApiChangeKind::Removed)]);
+        let next = major.clone().with_current_version("v1.4.2");
+        assert_eq!(next.next_version.as_deref(), Some("2.0.0"));
+
+        let pre_one = major.with_current_version("0.3.1");
+        assert_eq!(pre_one.next_version.as_deref(), Some("0.4.0"));
+        assert_eq!(pre_one.notes.len(), 1);
+
+        let unknown = suggest_from_changes(&[]).with_current_version("main");
+        assert!(unknown.next_version.is_none());
+        assert!(unknown.reasons.is_empty());
+        assert_eq!(unknown.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_from_rust_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+-pub fn parse(input: &str) -> Value {
++pub fn parse(input: &str, strict: bool) -> Value {
++pub struct Options {
+ fn private_helper() {}
+-fn internal() {}
+";
+        let suggestion = suggest_from_diff(diff);
+        assert_eq!(suggestion.bump, VersionBump::Major);
+        assert_eq!(suggestion.reasons.len(), 2);
+
+        let additive = "\
+diff --git a/src/lib.rs b/src/lib.rs
++pub(crate) fn hidden() {}
++pub async fn fetch() {}
+";
+        assert_eq!(suggest_from_diff(additive).bump, VersionBump::Minor);
+    }
+
+    #[test]
+    fn test_breaking_change_type_mapping() {
+        assert_eq!(
+            VersionBump::from(&BreakingChangeType::FunctionRemoved),
+            VersionBump::Major
+        );
+        assert_eq!(
+            VersionBump::from(&BreakingChangeType::FunctionAdded),
+            VersionBump::Minor
+        );
+    }
+}
@@ -25,6 +25,38 @@ pub struct Args {
     /// 自定义配置URL
     #[arg(long, global = true)]
     pub config_url: Option<String>,
+
+    /// 以 JSON 格式向 stderr 输出错误（{error_type, message, hint}），便于脚本处理
+    #[arg(long, global = true)]
+    pub json_errors: bool,
+
+    /// 不输出 ANSI 颜色（也可设置环境变量 NO_COLOR）
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// 去掉日志与错误信息中的 emoji
+    #[arg(long, global = true)]
+    pub no_emoji: bool,
+
+    /// 静默模式：不输出进度与状态信息，只输出最终结果（--format json 时自动启用）
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// 不读取也不写入结构分析缓存与按提交保存的分析结果
+    #[arg(long, global = true)]
+    pub no_analysis_cache: bool,
+
+    /// 不跳过生成文件（忽略配置中的 generated_patterns）
+    #[arg(long, global = true)]
+    pub include_generated: bool,
+
+    /// 目录遍历的最大深度（起始目录下的文件为 1；覆盖配置中的 max_depth）
+    #[arg(long, global = true, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// 输出语言（en|zh，需放在子命令之前；默认按 LANG 环境变量检测，无法识别时为中文）
+    #[arg(long = "lang", id = "ui_lang", value_name = "LANG")]
+    pub ui_lang: Option<crate::i18n::Lang>,
 }
 
 #[derive(Parser, Debug)]
@@ -34,12 +66,12 @@ pub enum Command {
         /// 语言
         #[arg(long)]
         language: Option<String>,
-        /// 输出格式
-        #[arg(long, default_value = "text")]
-        format: String,
-        /// 输出文件
+        /// 输出格式（text|json|markdown，默认 text）
         #[arg(long)]
-        output: Option<PathBuf>,
+        format: Option<String>,
+        /// 输出文件（可重复指定，格式按扩展名推断：.json、.md；其他扩展名使用 --format，文本格式写入 Markdown）
+        #[arg(long)]
+        output: Vec<PathBuf>,
         /// 启用Tree-sitter
         #[arg(long)]
         tree_sitter: bool,
@@ -49,9 +81,12 @@ pub enum Command {
         /// 扫描工具
         #[arg(long)]
         scan_tool: Option<String>,
-        /// 阻止严重问题
+        /// 阻止严重问题（等同于 --fail-on critical）
         #[arg(long)]
         block_on_critical: bool,
+        /// 存在达到该严重程度的问题时以退出码 10 失败（critical|high|medium|low）
+        #[arg(long, value_parser = ["critical", "high", "medium", "low"])]
+        fail_on: Option<String>,
         /// 关联的Issue ID（逗号分隔，Coding 为 issue 编号 code，例如 5；可带 # 前缀）
         #[arg(long)]
         issue_id: Option<String>,
@@ -61,15 +96,52 @@ pub enum Command {
         /// 启用完整深度分析（架构影响、危险改动、依赖分析等）
         #[arg(long)]
         full: bool,
+        /// 评审远程 PR/MR（GitHub/GitLab 链接），无需本地检出
+        #[arg(long)]
+        pr: Option<String>,
+        /// 将评审结果作为评论回帖到 PR/MR（需配合 --pr）
+        #[arg(long, requires = "pr")]
+        post_comment: bool,
+        /// 只评审已暂存的变更（适用于 pre-commit 钩子）
+        #[arg(long, conflicts_with_all = ["working", "pr"])]
+        staged: bool,
+        /// 评审所有未提交的变更（已暂存 + 未暂存 + 未跟踪），不包含未推送的提交
+        #[arg(long, conflicts_with = "pr")]
+        working: bool,
+        /// 评审第 n 个贮藏（stash@{n}）中的变更，含贮藏的未跟踪文件
+        #[arg(long, value_name = "N", conflicts_with_all = ["staged", "working", "pr"])]
+        stash: Option<usize>,
+        /// 评审指定版本的单个文件（与 git show <rev>:<path> 相同），例如 HEAD~1:src/main.rs
+        #[arg(
+            long,
+            value_name = "REV:PATH",
+            conflicts_with_all = ["staged", "working", "pr", "stash"]
+        )]
+        blob: Option<String>,
+        /// 只输出执行计划（将执行的阶段、变更规模、是否需要网络），不实际评审；--format json 输出 JSON
+        #[arg(long)]
+        plan: bool,
+        /// 单次发送给 AI 的 diff token 上限，超过时按文件分段评审后汇总（覆盖配置 ai.max_diff_tokens，0 表示不限制）
+        #[arg(long)]
+        max_diff_tokens: Option<usize>,
+        /// 使用配置中的命名配置组 [profiles.<名称>]，命令行参数优先
+        #[arg(long)]
+        profile: Option<String>,
     },
     /// 代码安全扫描（基于OpenGrep）
     Scan {
-        /// 扫描路径
-        #[arg(short, long, default_value = ".")]
-        path: PathBuf,
-        /// 扫描工具 (opengrep|auto)
-        #[arg(long, default_value = "auto")]
-        tool: String,
+        /// 扫描路径（可指定多个，并发扫描后按指纹去重合并；默认当前目录）
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+        /// 扫描路径（与位置参数等价，可重复指定）
+        #[arg(short, long = "path", value_name = "PATH")]
+        path: Vec<PathBuf>,
+        /// 多个路径时按路径分别报告：文本输出列出每个路径的问题数与耗时，JSON 输出为每个路径一项的数组
+        #[arg(long)]
+        per_path: bool,
+        /// 扫描工具 (opengrep|auto，默认 auto)
+        #[arg(long)]
+        tool: Option<String>,
         /// 全量扫描
         #[arg(long)]
         full: bool,
@@ -79,12 +151,12 @@ pub enum Command {
         /// 更新规则
         #[arg(long)]
         update_rules: bool,
-        /// 输出格式
-        #[arg(long, default_value = "text")]
-        format: String,
-        /// 输出文件
+        /// 输出格式（text|json|markdown|html，默认 text）
         #[arg(long)]
-        output: Option<PathBuf>,
+        format: Option<String>,
+        /// 输出文件（可重复指定，格式按扩展名推断：.json、.md、.html、.txt；其他扩展名使用 --format）
+        #[arg(long)]
+        output: Vec<PathBuf>,
         /// 启用翻译
         #[arg(long)]
         translate: bool,
@@ -94,6 +166,12 @@ pub enum Command {
         /// 指定规则语言（例如：java、python），将直接使用对应子目录，跳过自动检测
         #[arg(long)]
         lang: Option<String>,
+        /// 本地自定义规则目录，与托管规则一起使用（覆盖配置 [scan] custom_rules_dir）
+        #[arg(long, value_name = "DIR")]
+        rules: Option<PathBuf>,
+        /// 仅使用自定义规则，不加载托管规则
+        #[arg(long)]
+        rules_only: bool,
         /// 不保存扫描历史（用于基准测试/提高性能）
         #[arg(long)]
         no_history: bool,
@@ -103,6 +181,21 @@ pub enum Command {
         /// 基准模式：禁用历史、跳过版本查询等非必要逻辑
         #[arg(long)]
         benchmark: bool,
+        /// 发现严重程度不低于该级别（error|warning|info）的问题时以退出码 10 失败
+        #[arg(long, value_parser = ["error", "warning", "info"])]
+        fail_on: Option<String>,
+        /// 用 AI 解释单个问题并给出修复建议（按扫描结果中的编号或指纹选择，解释按指纹缓存）
+        #[arg(long, value_name = "INDEX|FINGERPRINT")]
+        explain: Option<String>,
+        /// 基线文件（JSON）：抑制其中记录的已接受问题，加入基线超过 [scan] baseline_max_age_days 天的问题重新报告为逾期
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+        /// 用本次扫描的问题更新基线文件（已有条目保留加入时间，已修复的问题被移除）
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+        /// 使用配置中的命名配置组 [profiles.<名称>]，命令行参数优先
+        #[arg(long)]
+        profile: Option<String>,
     },
     /// 查看历史扫描记录
     ScanHistory {
@@ -141,6 +234,15 @@ pub enum Command {
         /// 测试运行，不实际提交
         #[arg(long)]
         dry_run: bool,
+        /// 生成提交信息时发送给 AI 的 diff token 上限，超过时按文件优先级裁剪（覆盖配置 ai.max_diff_tokens，0 表示不限制）
+        #[arg(long)]
+        max_diff_tokens: Option<usize>,
+        /// 提交信息风格模板（内置 angular|gitmoji|plain，默认为约定式提交；对应提示词目录中的 commit-<名称>.md，覆盖配置 commit.template）
+        #[arg(long)]
+        template: Option<String>,
+        /// 使用配置中的命名配置组 [profiles.<名称>]，命令行参数优先
+        #[arg(long)]
+        profile: Option<String>,
     },
     /// 更新安全扫描规则库
     Update {
@@ -162,6 +264,9 @@ pub enum Command {
         /// 监听地址 (tcp/sse)
         #[arg(long, default_value = "127.0.0.1:8080")]
         addr: String,
+        /// 列出已启用服务提供的全部工具及其所属服务与版本，不启动服务器
+        #[arg(long)]
+        list_tools: bool,
     },
     /// 初始化GitAI配置
     Init {
@@ -186,6 +291,11 @@ pub enum Command {
         #[command(subcommand)]
         action: ConfigAction,
     },
+    /// 缓存管理
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
     /// 架构质量趋势追踪
     Metrics {
         #[command(subcommand)]
@@ -199,12 +309,24 @@ pub enum Command {
         /// 输出 DOT 文件路径（默认输出到 stdout）
         #[arg(long)]
         output: Option<PathBuf>,
-        /// 关键节点高亮阈值（0-1）
+        /// 输出格式（dot|bundle）；bundle 为包含完整图、摘要与元数据的单个 JSON
+        #[arg(long, default_value = "dot", value_parser = ["dot", "bundle"])]
+        format: String,
+        /// 关键节点高亮阈值（0-1）；同时过滤权重低于最热调用边 × 阈值的弱调用边
         #[arg(long, default_value_t = 0.15)]
         threshold: f32,
+        /// 仅保留指定类型的节点（逗号分隔：function,class,module,file）
+        #[arg(long, value_delimiter = ',', value_parser = parse_node_type)]
+        node_types: Vec<String>,
+        /// 仅保留指定类型的边（逗号分隔：calls,imports,exports,inherits,implements,uses,references,contains,depends_on）
+        #[arg(long, value_delimiter = ',', value_parser = parse_edge_type)]
+        edge_types: Vec<String>,
         /// 生成摘要（LLM 友好）而不是完整 DOT 图
         #[arg(long)]
         summary: bool,
+        /// 输出关键节点报告：割点与介数中心性按影响分数排序（数量受 --top-k 限制，格式同 --summary-format）
+        #[arg(long, conflicts_with = "summary")]
+        critical_nodes: bool,
         /// 摘要半径（基于变更种子）
         #[arg(long, default_value_t = 1)]
         radius: usize,
@@ -214,6 +336,9 @@ pub enum Command {
         /// 从 git diff 推导变更种子
         #[arg(long)]
         seeds_from_diff: bool,
+        /// 增量构建：复用上次缓存的逐文件分析结果，只重新解析变化的文件（快照位于 ~/.cache/gitai/graph）
+        #[arg(long)]
+        incremental: bool,
         /// 摘要输出格式（text|json）
         #[arg(long, default_value = "text")]
         summary_format: String,
@@ -241,6 +366,160 @@ pub enum Command {
         /// 单条路径最大跳数（调用链长度上限）
         #[arg(long, default_value_t = 5)]
         path_max_hops: usize,
+        /// 并发分析文件的最大工作线程数（默认取 GITAI_ANALYSIS_CONCURRENCY，否则为 CPU 可用并行度）
+        #[arg(long)]
+        max_concurrent: Option<usize>,
+    },
+    /// 对比两个提交之间的公共 API 变化（删除/签名变化视为破坏性）
+    ApiDiff {
+        /// 基准引用（提交/分支/标签）
+        #[arg(long)]
+        base: String,
+        /// 目标引用
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 根据公共 API 变化建议语义化版本提升（major/minor/patch）
+    SemverSuggest {
+        /// 基准引用（通常为上一个发布标签，如 v1.2.0）
+        #[arg(long)]
+        base: String,
+        /// 目标引用
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 综合风险报告：汇总安全扫描、质量指标与公共 API 影响，给出整体风险级别
+    Report {
+        /// 扫描与度量的路径
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// API 影响的基准引用
+        #[arg(long, default_value = "HEAD~1")]
+        base: String,
+        /// API 影响的目标引用
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+        /// 输出格式（markdown|html|json）
+        #[arg(long, default_value = "markdown", value_parser = ["markdown", "html", "json"])]
+        format: String,
+        /// 输出文件（可重复指定，格式按扩展名推断：.json、.md、.html；其他扩展名使用 --format）
+        #[arg(long)]
+        output: Vec<PathBuf>,
+        /// 不运行安全扫描
+        #[arg(long)]
+        no_scan: bool,
+        /// 不计算质量指标
+        #[arg(long)]
+        no_metrics: bool,
+        /// 不分析公共 API 影响
+        #[arg(long)]
+        no_impact: bool,
+    },
+    /// 列出项目使用的外部依赖（按语言分组，基于源码导入分析）
+    Deps {
+        /// 扫描路径（目录）
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 统计公共 API 的文档覆盖率，列出缺少文档注释的函数/类型
+    DocsCoverage {
+        /// 扫描路径（目录）
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 覆盖率低于该百分比（0-100）时以非零状态退出，用于 CI 门禁
+        #[arg(long)]
+        fail_under: Option<f64>,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 代码结构分析（Tree-sitter），以 JSON 输出结构摘要
+    #[command(group(clap::ArgGroup::new("input").required(true).args(["stdin", "files_from"])))]
+    Analyze {
+        /// 从标准输入读取代码（适用于编辑器中未保存的缓冲区）
+        #[arg(long, requires = "lang")]
+        stdin: bool,
+        /// 从清单文件读取待分析的文件列表（每行一个路径，`#` 开头为注释），不存在的文件会被跳过
+        #[arg(long)]
+        files_from: Option<PathBuf>,
+        /// --files-from 时同时分析的文件数（默认取 GITAI_ANALYSIS_CONCURRENCY，否则为 CPU 可用并行度）
+        #[arg(long, requires = "files_from")]
+        jobs: Option<usize>,
+        /// 代码语言（rust|python|javascript|typescript|java|go|c|cpp），--stdin 时必填
+        #[arg(long, value_parser = parse_language)]
+        lang: Option<String>,
+    },
+    /// 工程一致性评估（可作为 CI 门禁，未通过时以退出码 10 失败）
+    Eval {
+        #[command(subcommand)]
+        action: EvalAction,
+    },
+    /// 需求偏离度分析：对比代码变更与 Issue 需求（已覆盖/未覆盖的需求、无关变更），可作为合并门禁
+    Deviation {
+        /// 关联的Issue ID（逗号分隔，Coding 为 issue 编号 code，例如 5；可带 # 前缀）
+        #[arg(long)]
+        issue_id: String,
+        /// 指定 Coding 空间（项目）ID，覆盖配置中的 space_id
+        #[arg(long)]
+        space_id: Option<u64>,
+        /// 只分析已暂存的变更
+        #[arg(long, conflicts_with = "working")]
+        staged: bool,
+        /// 分析所有未提交的变更（已暂存 + 未暂存 + 未跟踪）
+        #[arg(long)]
+        working: bool,
+        /// 输出格式（text|json）；json 的字段结构稳定，可供机器人判断是否允许合并
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        format: String,
+        /// 得分（0-100）低于该值时以退出码 10 失败
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        fail_below: Option<u8>,
+    },
+    /// 管理调用 GitAI 的 Git 钩子
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// 查看扫描规则的说明、严重程度与示例（从本地规则缓存中查找）
+    Explain {
+        /// 规则 ID（可带扫描结果中的目录前缀，如 `python.lang.security.audit.eval-detected`）
+        rule_id: String,
+        /// 将规则说明翻译为当前输出语言（需要 AI 服务）
+        #[arg(long)]
+        translate: bool,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 端到端性能基准：测量文件发现、结构分析、依赖图构建与安全扫描各阶段的耗时与吞吐量
+    Bench {
+        /// 基准目录
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 输出格式（text|json）；json 便于跨版本追踪性能变化
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        format: String,
+        /// 将报告写入文件（默认输出到 stdout）
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// 并发分析文件的最大工作线程数（默认取 GITAI_ANALYSIS_CONCURRENCY，否则为 CPU 可用并行度）
+        #[arg(long)]
+        max_concurrent: Option<usize>,
+        /// 保留结构分析缓存（默认关闭以测量冷启动耗时）
+        #[arg(long)]
+        warm: bool,
+        /// 跳过安全扫描阶段
+        #[arg(long)]
+        no_scan: bool,
     },
     /// 显示本构建启用的功能
     Features {
@@ -248,6 +527,12 @@ pub enum Command {
         #[arg(long, default_value = "text")]
         format: String,
     },
+    /// 自检：检查 Git、OpenGrep、ast-grep、Tree-sitter 查询、规则缓存、AI 服务与配置，存在致命问题时以非零退出码结束
+    Doctor {
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        format: String,
+    },
 }
 
 /// 提示词操作
@@ -263,10 +548,16 @@ pub enum PromptAction {
         #[arg(long)]
         language: Option<String>,
     },
-    /// 更新所有提示词
-    Update,
+    /// 更新所有提示词（保留用户修改过的模板）
+    Update {
+        /// 覆盖用户修改过的模板（原文件备份为 .md.bak）
+        #[arg(long)]
+        force: bool,
+    },
     /// 初始化提示词目录
     Init,
+    /// 校验提示词模板的占位符
+    Validate,
 }
 
 /// 质量指标操作
@@ -280,6 +571,9 @@ pub enum MetricsAction {
         /// 强制记录（即使没有代码变化）
         #[arg(long)]
         force: bool,
+        /// 并发分析文件的最大工作线程数（默认取 GITAI_ANALYSIS_CONCURRENCY，否则为 CPU 可用并行度）
+        #[arg(long)]
+        max_concurrent: Option<usize>,
     },
     /// 分析质量趋势
     Analyze {
@@ -289,18 +583,18 @@ pub enum MetricsAction {
         /// 输出格式 (text|json|markdown|html)
         #[arg(long, default_value = "text")]
         format: String,
-        /// 输出文件
+        /// 输出文件（可重复指定，格式按扩展名推断：.json、.md、.html、.txt；其他扩展名使用 --format）
         #[arg(long)]
-        output: Option<PathBuf>,
+        output: Vec<PathBuf>,
     },
     /// 生成趋势报告
     Report {
         /// 报告类型 (summary|detailed|full)
         #[arg(long, default_value = "summary")]
         report_type: String,
-        /// 输出文件
+        /// 输出文件（可重复指定；.html 输出 HTML 报告，其他扩展名输出 Markdown 报告）
         #[arg(long)]
-        output: Option<PathBuf>,
+        output: Vec<PathBuf>,
         /// 生成HTML格式
         #[arg(long)]
         html: bool,
@@ -349,7 +643,104 @@ pub enum MetricsAction {
     },
 }
 
+/// 评估操作
+#[derive(Parser, Debug)]
+pub enum EvalAction {
+    /// 错误处理一致性：统计统一错误类型（GitAIError）的采用率与混用文件
+    ErrorPatterns {
+        /// 扫描路径（目录）
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 最低采用率（0.0-1.0）
+        #[arg(long, default_value = "0.9")]
+        min_adoption: f64,
+        /// 只评估相对 HEAD 变更的文件（含未跟踪文件）
+        #[arg(long)]
+        changed_only: bool,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 重复代码：完全重复的源文件分组与近似重复文件对
+    Duplicates {
+        /// 扫描路径（目录）
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 允许的最大重复数（完全重复组计 组大小-1，近似重复对计 1）
+        #[arg(long, default_value = "0")]
+        max_duplicates: usize,
+        /// 近似重复的最低相似度（0.0-1.0）
+        #[arg(long, default_value = "0.9")]
+        min_similarity: f64,
+        /// 忽略的路径、目录或通配符模式（相对扫描路径，可重复或用逗号分隔）
+        #[arg(long, value_delimiter = ',')]
+        ignore: Vec<String>,
+        /// 近似重复比较阶段的内存预算（MB）
+        #[arg(long, default_value = "256")]
+        memory_budget_mb: usize,
+        /// 只评估相对 HEAD 变更的文件（含未跟踪文件）
+        #[arg(long)]
+        changed_only: bool,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// 配置模块扩散：白名单之外的 config.rs 超过上限即失败
+    ConfigCheck {
+        /// 扫描路径（目录）
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+        /// 白名单之外允许的 config.rs 数量（默认取配置 analysis.evaluation.max_config_files）
+        #[arg(long)]
+        max_files: Option<usize>,
+        /// 额外允许的路径、目录或通配符模式（与配置中的白名单合并，可重复或用逗号分隔）
+        #[arg(long, value_delimiter = ',')]
+        allow: Vec<String>,
+        /// 输出格式（text|json）
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+}
+
 /// 配置管理操作
+#[derive(Parser, Debug)]
+pub enum HooksAction {
+    /// 安装钩子（未指定类型时安装全部）
+    Install {
+        /// pre-commit：评审已暂存的变更，发现严重问题时阻止提交
+        #[arg(long)]
+        pre_commit: bool,
+        /// commit-msg：校验提交信息格式
+        #[arg(long)]
+        commit_msg: bool,
+        /// 备份并覆盖已存在的非 GitAI 钩子
+        #[arg(long)]
+        force: bool,
+    },
+    /// 移除 GitAI 安装的钩子并恢复备份（未指定类型时移除全部）
+    Uninstall {
+        #[arg(long)]
+        pre_commit: bool,
+        #[arg(long)]
+        commit_msg: bool,
+    },
+    /// 校验提交信息文件（commit-msg 钩子调用），不通过时以退出码 10 失败
+    CheckMessage {
+        /// 提交信息文件
+        file: PathBuf,
+    },
+}
+
+#[derive(Parser, Debug)]
+pub enum CacheAction {
+    /// 按类别统计缓存目录占用的磁盘空间
+    Du {
+        /// 输出格式 (text|json)
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        format: String,
+    },
+}
+
 #[derive(Parser, Debug)]
 pub enum ConfigAction {
     /// 检查配置状态
@@ -365,6 +756,9 @@ pub enum ConfigAction {
         /// 强制更新，即使未过期
         #[arg(long)]
         force: bool,
+        /// 只列出将要下载/更新的资源（含已知大小），不做任何修改
+        #[arg(long)]
+        dry_run: bool,
     },
     /// 重置配置到默认值
     Reset {
@@ -373,7 +767,38 @@ pub enum ConfigAction {
         no_backup: bool,
     },
     /// 清理过期缓存
-    Clean,
+    Clean {
+        /// 只列出将要删除的缓存目录及大小，不做任何修改
+        #[arg(long)]
+        dry_run: bool,
+        /// 只清空指定类别的缓存（不论是否过期，可重复或用逗号分隔）；不指定时清理所有过期缓存
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = ["rules", "tree-sitter", "scan-history", "reviews", "ai-cache", "graph"]
+        )]
+        only: Vec<String>,
+    },
+    /// 读取单个配置项的生效值（点分键，如 ai.model）：合并默认值、用户配置文件、环境变量与命令行参数后的值
+    Get {
+        /// 配置项
+        key: String,
+    },
+    /// 修改配置文件中的单个配置项，保留注释与格式；拒绝未知的配置项
+    Set {
+        /// 配置项（点分键，如 ai.model）
+        key: String,
+        /// 新值（TOML 字面量，如 0.5、true、["a", "b"]；其他内容按字符串处理）
+        value: String,
+    },
+    /// 列出每个配置项的生效值及其来源（default|user-file|env|flag）
+    Explain {
+        /// 只显示该配置项或配置段（点分键，如 ai 或 scan.timeout）
+        key: Option<String>,
+        /// 输出格式 (text|json)
+        #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+        format: String,
+    },
 }
 
 impl Args {
@@ -381,4 +806,46 @@ impl Args {
     pub fn parse() -> Self {
         <Self as clap::Parser>::parse()
     }
+
+    /// 解析命令行参数，解析失败时返回错误而不是直接退出
+    ///
+    /// 帮助与错误信息在解析前就要输出，因此 `--no-color` 直接从原始参数中检测
+    pub fn try_parse() -> Result<Self, clap::Error> {
+        use clap::{CommandFactory, FromArgMatches};
+        let mut command = Self::command();
+        if std::env::args().any(|arg| arg == "--no-color") || crate::utils::output::no_color_env() {
+            command = command.color(clap::ColorChoice::Never);
+        }
+        let mut matches = command.try_get_matches()?;
+        Self::from_arg_matches_mut(&mut matches).map_err(|e| e.format(&mut Self::command()))
+    }
+}
+
+impl Command {
+    /// 子命令通过 `--profile` 选用的命名配置组
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            Command::Review { profile, .. }
+            | Command::Scan { profile, .. }
+            | Command::Commit { profile, .. } => profile.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// 校验 `--lang` 并规范为语言名称
+fn parse_language(value: &str) -> Result<String, String> {
+    crate::tree_sitter::SupportedLanguage::from_name(value)
+        .map(|language| language.name().to_string())
+        .ok_or_else(|| format!("不支持的语言: {value}"))
+}
+
+/// 校验 `--node-types` 中的节点类型名称
+fn parse_node_type(value: &str) -> Result<String, String> {
+    crate::architectural_impact::dependency_graph::parse_node_type_name(value)
+}
+
+/// 校验 `--edge-types` 中的边类型名称
+fn parse_edge_type(value: &str) -> Result<String, String> {
+    crate::architectural_impact::dependency_graph::parse_edge_type_name(value)
 }
@@ -0,0 +1,347 @@
+// 端到端性能基准模块
+// 对目录依次执行文件发现、解析器初始化、Tree-sitter 结构分析、依赖图构建与安全扫描，记录各阶段耗时与吞吐量
+
+use crate::config::Config;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 基准选项
+#[derive(Debug, Clone, Default)]
+pub struct BenchOptions {
+    /// 并发分析的工作线程数，None 时与 `graph` 命令一致
+    pub max_concurrent: Option<usize>,
+    /// 保留 Tree-sitter 分析缓存与按提交保存的分析结果（默认关闭以测量冷启动耗时）
+    pub warm_cache: bool,
+    /// 跳过安全扫描阶段
+    pub skip_scan: bool,
+}
+
+/// 阶段状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+/// 单个阶段的测量结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StageReport {
+    pub name: String,
+    pub status: StageStatus,
+    pub duration_ms: f64,
+    /// 该阶段处理的文件数与行数
+    pub files: usize,
+    pub lines: usize,
+    pub files_per_sec: f64,
+    pub lines_per_sec: f64,
+    /// 补充说明（图规模、跳过/失败原因等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl StageReport {
+    fn measured(name: &str, started: Instant, files: usize, lines: usize) -> Self {
+        let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let per_sec = |count: usize| {
+            if duration_ms > 0.0 {
+                count as f64 * 1000.0 / duration_ms
+            } else {
+                0.0
+            }
+        };
+        Self {
+            name: name.to_string(),
+            status: StageStatus::Ok,
+            duration_ms,
+            files,
+            lines,
+            files_per_sec: per_sec(files),
+            lines_per_sec: per_sec(lines),
+            note: None,
+        }
+    }
+
+    fn not_run(name: &str, status: StageStatus, note: String) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            duration_ms: 0.0,
+            files: 0,
+            lines: 0,
+            files_per_sec: 0.0,
+            lines_per_sec: 0.0,
+            note: Some(note),
+        }
+    }
+
+    fn with_note(mut self, note: String) -> Self {
+        self.note = Some(note);
+        self
+    }
+}
+
+/// 基准报告（`gitai bench --format json` 的输出，便于跨版本对比）
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub version: String,
+    pub timestamp: String,
+    pub path: String,
+    /// 并发分析的工作线程数
+    pub workers: usize,
+    pub warm_cache: bool,
+    /// 参与基准的代码文件数与总行数
+    pub files: usize,
+    pub lines: usize,
+    pub stages: Vec<StageReport>,
+    pub total_ms: f64,
+}
+
+impl BenchReport {
+    /// 文本报告
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "⏱️ GitAI 性能基准 v{}（{}）\n文件: {}，行数: {}，工作线程: {}，分析缓存: {}\n\n",
+            self.version,
+            self.path,
+            self.files,
+            self.lines,
+            self.workers,
+            if self.warm_cache { "保留" } else { "关闭" }
+        );
+        out.push_str(&format!(
+            "{:<12} {:>8} {:>12} {:>12} {:>14}\n",
+            "stage", "status", "time(ms)", "files/s", "lines/s"
+        ));
+        for stage in &self.stages {
+            let status = match stage.status {
+                StageStatus::Ok => "ok",
+                StageStatus::Skipped => "skipped",
+                StageStatus::Failed => "failed",
+            };
+            out.push_str(&format!(
+                "{:<12} {:>8} {:>12.1} {:>12.1} {:>14.1}",
+                stage.name, status, stage.duration_ms, stage.files_per_sec, stage.lines_per_sec
+            ));
+            if let Some(note) = &stage.note {
+                out.push_str(&format!("  {note}"));
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!("\n总耗时: {:.1} ms\n", self.total_ms));
+        out
+    }
+}
+
+/// 对目录执行一次完整基准
+pub async fn run(
+    config: &Config,
+    root: &Path,
+    options: &BenchOptions,
+) -> Result<BenchReport, DynError> {
+    if !root.is_dir() {
+        return Err(format!("基准路径不是目录: {}", root.display()).into());
+    }
+    let started = Instant::now();
+    let previous_cache = crate::tree_sitter::cache_enabled();
+    // `--no-analysis-cache` 优先于 `--warm`
+    let warm_cache = options.warm_cache && previous_cache;
+    crate::tree_sitter::set_cache_enabled(warm_cache);
+    let result = run_stages(config, root, options).await;
+    crate::tree_sitter::set_cache_enabled(previous_cache);
+    let (files, lines, stages) = result?;
+
+    Ok(BenchReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        path: root.display().to_string(),
+        workers: crate::tree_sitter::analysis_pool_size(
+            files.len(),
+            options
+                .max_concurrent
+                .or_else(crate::tree_sitter::analysis_concurrency_from_env),
+        ),
+        warm_cache,
+        files: files.len(),
+        lines,
+        stages,
+        total_ms: started.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+async fn run_stages(
+    config: &Config,
+    root: &Path,
+    options: &BenchOptions,
+) -> Result<(Vec<PathBuf>, usize, Vec<StageReport>), DynError> {
+    let mut stages = Vec::new();
+
+    // 1. 文件发现：与依赖图使用同一套目录遍历规则
+    let started = Instant::now();
+    let mut files = Vec::new();
+    let generated = crate::architectural_impact::graph_export::collect_files(root, &mut files);
+    files.sort();
+    let line_counts: Vec<usize> = files.iter().map(|path| count_lines(path)).collect();
+    let total_lines: usize = line_counts.iter().sum();
+    let discover = StageReport::measured("discover", started, files.len(), total_lines);
+    stages.push(if generated > 0 {
+        discover.with_note(format!("跳过 {generated} 个生成文件"))
+    } else {
+        discover
+    });
+
+    // 2. 解析器与查询初始化（首次运行可能需要下载查询文件），单独计时以免计入分析吞吐量
+    let started = Instant::now();
+    crate::tree_sitter::TreeSitterManager::new().await?;
+    stages.push(StageReport::measured("init", started, 0, 0));
+
+    // 3. Tree-sitter 结构分析
+    let started = Instant::now();
+    let outcomes = crate::tree_sitter::analyze_paths_with_outcomes(
+        &files,
+        options.max_concurrent,
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+    .await?;
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, crate::tree_sitter::FileAnalysisOutcome::Failed(_)))
+        .count();
+    let analyses: Vec<_> = outcomes
+        .into_iter()
+        .map(crate::tree_sitter::FileAnalysisOutcome::into_analyzed)
+        .collect();
+    let analyzed_lines: usize = analyses
+        .iter()
+        .zip(&line_counts)
+        .filter(|(analysis, _)| analysis.is_some())
+        .map(|(_, lines)| lines)
+        .sum();
+    let analyzed = analyses.iter().filter(|a| a.is_some()).count();
+    stages.push(
+        StageReport::measured("tree_sitter", started, analyzed, analyzed_lines).with_note(format!(
+            "跳过 {} 个文件，其中分析失败 {failed} 个",
+            files.len() - analyzed
+        )),
+    );
+
+    // 4. 依赖图构建（复用上一阶段的分析结果，仅测量建图本身）
+    let started = Instant::now();
+    let graph = crate::architectural_impact::graph_export::graph_from_analyses(analyses);
+    stages.push(
+        StageReport::measured("graph", started, analyzed, analyzed_lines).with_note(format!(
+            "{} 个节点，{} 条边",
+            graph.nodes.len(),
+            graph.edges.len()
+        )),
+    );
+
+    // 5. 安全扫描
+    stages.push(scan_stage(config, root, options, files.len(), total_lines));
+
+    Ok((files, total_lines, stages))
+}
+
+#[cfg(feature = "security")]
+fn scan_stage(
+    config: &Config,
+    root: &Path,
+    options: &BenchOptions,
+    files: usize,
+    lines: usize,
+) -> StageReport {
+    if options.skip_scan {
+        return StageReport::not_run("scan", StageStatus::Skipped, "--no-scan".to_string());
+    }
+    if !crate::scan::is_opengrep_installed() {
+        return StageReport::not_run("scan", StageStatus::Skipped, "未安装 OpenGrep".to_string());
+    }
+    let started = Instant::now();
+    match crate::scan::run_opengrep_scan(config, root, None, None, false) {
+        Ok(result) => StageReport::measured("scan", started, files, lines)
+            .with_note(format!("{} 个问题", result.findings.len())),
+        Err(e) => StageReport::not_run("scan", StageStatus::Failed, e.to_string()),
+    }
+}
+
+#[cfg(not(feature = "security"))]
+fn scan_stage(
+    _config: &Config,
+    _root: &Path,
+    _options: &BenchOptions,
+    _files: usize,
+    _lines: usize,
+) -> StageReport {
+    StageReport::not_run(
+        "scan",
+        StageStatus::Skipped,
+        "本构建未启用 security 功能".to_string(),
+    )
+}
+
+fn count_lines(path: &Path) -> usize {
+    std::fs::read(path)
+        .map(|bytes| bytecount_lines(&bytes))
+        .unwrap_or(0)
+}
+
+fn bytecount_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    // 末行没有换行符时也计为一行
+    if bytes.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bench_reports_each_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "pub fn a() -> u8 {\n    b()\n}\n\nfn b() -> u8 {\n    1\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("util.py"), "def f():\n    return 1").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not code\n").unwrap();
+
+        let options = BenchOptions {
+            skip_scan: true,
+            max_concurrent: Some(1),
+            ..Default::default()
+        };
+        let report = run(&Config::default(), dir.path(), &options).await.unwrap();
+        assert_eq!(report.files, 2);
+        assert_eq!(report.lines, 9);
+        assert_eq!(report.workers, 1);
+        let names: Vec<&str> = report.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["discover", "init", "tree_sitter", "graph", "scan"]
+        );
+        assert_eq!(report.stages[4].status, StageStatus::Skipped);
+        // 基准结束后恢复缓存设置
+        assert!(crate::tree_sitter::cache_enabled());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["stages"][2]["status"], "ok");
+        assert_eq!(json["stages"][2]["files"], 2);
+        assert!(json["stages"][2]["files_per_sec"].is_number());
+        assert!(report.to_text().contains("tree_sitter"));
+
+        assert!(
+            run(&Config::default(), &dir.path().join("lib.rs"), &options)
+                .await
+                .is_err()
+        );
+    }
+}
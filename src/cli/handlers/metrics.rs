@@ -34,8 +34,7 @@ async fn handle_metrics(_config: &Config, action: &MetricsAction) -> Result<()>
 
             // 检查是否有代码变化（除非强制记录）
             if !force {
-                let status = git::run_git(&["status".to_string(), "--porcelain".to_string()])?;
-                if status.trim().is_empty() {
+                if git::status()?.is_clean() {
                     println!("ℹ️  没有检测到代码变化");
                     println!("💡 使用 --force 强制记录快照");
                     return Ok(());
@@ -54,17 +53,11 @@ async fn handle_metrics(_config: &Config, action: &MetricsAction) -> Result<()>
             let code_files = find_code_files(".")?;
 
             for file_path in &code_files {
-                if let Ok(content) = std::fs::read_to_string(file_path) {
-                    if let Some(ext) = file_path.extension().and_then(|s| s.to_str()) {
-                        if let Some(lang) = gitai::tree_sitter::SupportedLanguage::from_extension(ext) {
-                            if let Ok(file_summary) = manager.analyze_structure(&content, lang) {
-                                // 合并结果
-                                summary.functions.extend(file_summary.functions);
-                                summary.classes.extend(file_summary.classes);
-                                summary.comments.extend(file_summary.comments);
-                            }
-                        }
-                    }
+                if let Ok(Some(result)) = manager.analyze_path(file_path) {
+                    // 合并结果
+                    summary.functions.extend(result.summary.functions);
+                    summary.classes.extend(result.summary.classes);
+                    summary.comments.extend(result.summary.comments);
                 }
             }
 
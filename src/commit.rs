@@ -65,6 +65,10 @@ pub struct CommitConfig {
     pub review: bool,
     pub tree_sitter: bool,
     pub dry_run: bool,
+    /// 生成提交信息时发送给 AI 的 diff token 上限，覆盖配置中的 `ai.max_diff_tokens`（0 表示不限制）
+    pub max_diff_tokens: Option<usize>,
+    /// 提交信息风格模板，覆盖配置中的 `commit.template`
+    pub template: Option<String>,
 }
 
 impl CommitConfig {
@@ -85,14 +89,138 @@ impl CommitConfig {
             review,
             tree_sitter,
             dry_run,
+            max_diff_tokens: None,
+            template: None,
         }
     }
 
+    /// 覆盖生成提交信息时的 diff token 上限
+    pub fn with_max_diff_tokens(mut self, max_diff_tokens: Option<usize>) -> Self {
+        self.max_diff_tokens = max_diff_tokens;
+        self
+    }
+
+    /// 覆盖提交信息风格模板
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// 以编程方式构建提交配置（供库调用方与服务层使用，不依赖命令行参数顺序）
+    ///
+    /// # Examples
+    /// ```
+    /// use gitai::commit::CommitConfig;
+    ///
+    /// let config = CommitConfig::builder()
+    ///     .message("fix: handle empty diff")
+    ///     .issue_ids(["12", "#12", "34"])
+    ///     .add_all(true)
+    ///     .dry_run(true)
+    ///     .build();
+    /// // Issue ID 统一补全 `#` 前缀并去重
+    /// assert_eq!(config.issue_ids, vec!["#12", "#34"]);
+    /// assert!(config.add_all && config.dry_run && !config.review);
+    /// ```
+    pub fn builder() -> CommitConfigBuilder {
+        CommitConfigBuilder::default()
+    }
+
     pub fn needs_issue_context(&self) -> bool {
         !self.issue_ids.is_empty()
     }
 }
 
+/// [`CommitConfig`] 构建器，未设置的选项取默认值（由 AI 生成提交信息、不附加评审）
+#[derive(Debug, Clone, Default)]
+pub struct CommitConfigBuilder {
+    message: Option<String>,
+    issue_ids: Vec<String>,
+    space_id: Option<u64>,
+    add_all: bool,
+    review: bool,
+    tree_sitter: bool,
+    dry_run: bool,
+    max_diff_tokens: Option<usize>,
+    template: Option<String>,
+}
+
+impl CommitConfigBuilder {
+    /// 指定提交信息（不再由 AI 生成）
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// 关联的 Issue（缺少 `#` 前缀时自动补全）
+    pub fn issue_ids<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.issue_ids = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Coding 空间（项目）ID
+    pub fn space_id(mut self, space_id: u64) -> Self {
+        self.space_id = Some(space_id);
+        self
+    }
+
+    /// 提交前暂存全部变更
+    pub fn add_all(mut self, enabled: bool) -> Self {
+        self.add_all = enabled;
+        self
+    }
+
+    /// 提交前执行代码评审
+    pub fn review(mut self, enabled: bool) -> Self {
+        self.review = enabled;
+        self
+    }
+
+    /// 启用 Tree-sitter 结构分析
+    pub fn tree_sitter(mut self, enabled: bool) -> Self {
+        self.tree_sitter = enabled;
+        self
+    }
+
+    /// 只生成提交信息，不执行提交
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// 生成提交信息时的 diff token 上限（0 表示不限制）
+    pub fn max_diff_tokens(mut self, max_diff_tokens: usize) -> Self {
+        self.max_diff_tokens = Some(max_diff_tokens);
+        self
+    }
+
+    /// 提交信息风格模板（如 `gitmoji`，对应 `commit-gitmoji.md`）
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// 生成配置
+    pub fn build(self) -> CommitConfig {
+        let issue_ids = (!self.issue_ids.is_empty()).then(|| self.issue_ids.join(","));
+        CommitConfig::from_args(
+            self.message,
+            issue_ids,
+            self.space_id,
+            self.add_all,
+            self.review,
+            self.tree_sitter,
+            self.dry_run,
+        )
+        .with_max_diff_tokens(self.max_diff_tokens)
+        .with_template(self.template)
+    }
+}
+
 // === 触发结构化函数识别的示例改动 ===
 // 该函数仅用于验证 Tree-sitter 与架构影响分析能正确识别函数级变更
 pub fn sample_util_add(x: i32, y: i32) -> i32 {
@@ -120,6 +248,12 @@ pub async fn execute_commit_with_result(
     config: &Config,
     commit_config: CommitConfig,
 ) -> Result<CommitResult, Box<dyn std::error::Error + Send + Sync>> {
+    // 需要生成提交信息时先确认模板可用，避免拉取 Issue、调用 AI 之后才报错
+    let template = match commit_config.message {
+        Some(_) => None,
+        None => Some(resolve_template(config, &commit_config)?),
+    };
+
     let diff = get_changes()?;
     if diff.is_empty() {
         return Ok(CommitResult {
@@ -134,7 +268,20 @@ pub async fn execute_commit_with_result(
 
     let issues =
         get_issue_context(config, &commit_config.issue_ids, commit_config.space_id).await?;
-    let commit_message = generate_commit_message(config, &diff, &issues, &commit_config).await?;
+    let commit_message = generate_commit_message(
+        config,
+        &diff,
+        &issues,
+        &commit_config,
+        template.as_deref().unwrap_or("commit"),
+    )
+    .await?;
+
+    // 基于公共 API 变化提示语义化版本提升
+    let version_suggestion = crate::architectural_impact::version_bump::suggest_from_diff(&diff);
+    if version_suggestion.is_suggested() && !crate::utils::output::quiet() {
+        print!("{}", version_suggestion.to_text());
+    }
 
     let mut review_results = None;
     if commit_config.review {
@@ -155,6 +302,10 @@ pub async fn execute_commit_with_result(
     );
     details.insert("add_all".to_string(), commit_config.add_all.to_string());
     details.insert("dry_run".to_string(), commit_config.dry_run.to_string());
+    details.insert(
+        "semver_bump".to_string(),
+        version_suggestion.bump.to_string(),
+    );
 
     if !commit_config.issue_ids.is_empty() {
         details.insert("issue_ids".to_string(), commit_config.issue_ids.join(", "));
@@ -174,6 +325,19 @@ pub async fn execute_commit_with_result(
     })
 }
 
+/// 解析提交信息风格模板：命令行优先，其次为配置中的 `commit.template`，默认为 `commit`
+fn resolve_template(
+    config: &Config,
+    commit_config: &CommitConfig,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let style = commit_config
+        .template
+        .as_deref()
+        .or(config.commit.template.as_deref())
+        .unwrap_or("commit");
+    crate::prompts::PromptManager::new(config.clone()).resolve_commit_template(style)
+}
+
 /// 获取代码变更
 fn get_changes() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     crate::git::get_all_diff()
@@ -203,7 +367,7 @@ async fn get_issue_context(
         }
     }
 
-    eprintln!("⚠️ DevOps功能未启用或未配置，无法获取Issue信息");
+    eprintln!("{}", crate::tr!("commit.devops_unavailable"));
     Ok(Vec::new())
 }
 
@@ -213,10 +377,12 @@ async fn generate_commit_message(
     diff: &str,
     issues: &[Issue],
     commit_config: &CommitConfig,
+    template: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     if let Some(ref message) = commit_config.message {
-        let final_message = format_commit_message(message, &commit_config.issue_ids);
-        println!("📝 提交信息: {final_message}");
+        let final_message =
+            format_commit_message(message, &commit_config.issue_ids, &config.commit);
+        crate::progress!("{}", crate::tr!("commit.message", message = final_message));
         return Ok(final_message);
     }
 
@@ -231,25 +397,68 @@ async fn generate_commit_message(
 
     // 尝试直接使用模板生成提交信息（传递 tree_sitter 信息）
     #[cfg(feature = "ai")]
-    let ai_message = match crate::ai::generate_commit_message_with_template(
-        config,
-        diff,
-        tree_sitter_summary.as_deref(),
-    )
-    .await
-    {
-        Ok(message) => message,
-        Err(template_error) => {
-            log::warn!("使用模板生成提交信息失败，降级为传统方式: {template_error}");
+    let ai_message = {
+        // 超出 token 预算时按文件优先级裁剪 diff，其余文件只保留增删行数摘要
+        let max_diff_tokens = commit_config
+            .max_diff_tokens
+            .unwrap_or(config.ai.max_diff_tokens);
+        let budgeted = crate::review::chunking::fit_to_budget(
+            diff,
+            max_diff_tokens,
+            &crate::utils::generated::active(),
+        );
+        if budgeted.is_truncated() {
+            crate::progress!(
+                "{}",
+                crate::tr!(
+                    "commit.diff_truncated",
+                    limit = max_diff_tokens,
+                    kept = budgeted.full_files(),
+                    total = budgeted.total_files
+                )
+            );
+        }
 
-            // 降级为传统方式：构建prompt然后调用AI
-            let prompt = build_commit_prompt_fallback(config, diff, issues, commit_config).await?;
-            crate::ai::call_ai(config, &prompt).await?
+        let message = match crate::ai::generate_commit_message_with_template(
+            config,
+            std::path::Path::new("."),
+            template,
+            &budgeted.diff,
+            tree_sitter_summary.as_deref(),
+        )
+        .await
+        {
+            Ok(message) => message,
+            Err(template_error) => {
+                log::warn!("使用模板生成提交信息失败，降级为传统方式: {template_error}");
+
+                // 降级为传统方式：构建prompt然后调用AI
+                let prompt =
+                    build_commit_prompt_fallback(config, &budgeted.diff, issues, commit_config)
+                        .await?;
+                crate::ai::call_ai(config, &prompt).await?
+            }
+        };
+
+        // 在提交信息正文中注明裁剪，避免读者误以为信息覆盖了全部变更
+        if budgeted.is_truncated() {
+            format!(
+                "{}\n\n{}",
+                message.trim(),
+                crate::tr!(
+                    "commit.truncated_note",
+                    kept = budgeted.full_files(),
+                    total = budgeted.total_files
+                )
+            )
+        } else {
+            message
         }
     };
 
     #[cfg(not(feature = "ai"))]
     let ai_message = {
+        let _ = template;
         // 未启用 AI 时的简易提交信息
         let changes = count_changes(diff).unwrap_or(0);
         let mut msg = if changes > 0 {
@@ -263,20 +472,75 @@ async fn generate_commit_message(
         msg
     };
 
-    let final_message = format_commit_message(ai_message.trim(), &commit_config.issue_ids);
-    println!("📝 提交信息: {final_message}");
+    let final_message =
+        format_commit_message(ai_message.trim(), &commit_config.issue_ids, &config.commit);
+    crate::progress!("{}", crate::tr!("commit.message", message = final_message));
     Ok(final_message)
 }
 
-/// 格式化提交信息（添加issue前缀）
-fn format_commit_message(message: &str, issue_ids: &[String]) -> String {
-    if issue_ids.is_empty() {
-        message.to_string()
+/// 按配置把 Issue ID 写入提交信息（标题前缀或 Git trailer），已在信息中出现的 ID 不重复添加
+fn format_commit_message(
+    message: &str,
+    issue_ids: &[String],
+    settings: &crate::config::CommitMessageConfig,
+) -> String {
+    use crate::config::IssuePlacement;
+
+    let missing: Vec<String> = issue_ids
+        .iter()
+        .map(|id| display_issue_id(id))
+        .filter(|id| !mentions_issue(message, id))
+        .collect();
+    if missing.is_empty() {
+        return message.to_string();
+    }
+    match settings.issue_placement {
+        IssuePlacement::None => message.to_string(),
+        IssuePlacement::Subject => format!("{} {message}", missing.join(",")),
+        IssuePlacement::Trailer => {
+            let key = settings.issue_trailer.trim();
+            let trailers: Vec<String> = missing.iter().map(|id| format!("{key}: {id}")).collect();
+            let message = message.trim_end();
+            // 末段已是 trailer 块时直接追加，否则空一行另起一段
+            let last_paragraph = message.rsplit("\n\n").next().unwrap_or_default();
+            let separator = if message.contains("\n\n") && is_trailer_block(last_paragraph) {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            format!("{message}{separator}{}", trailers.join("\n"))
+        }
+    }
+}
+
+/// 展示用的 Issue ID：数字编号保留 `#` 前缀（`#12`），带项目键的 ID 去掉前缀（`PROJ-123`）
+fn display_issue_id(id: &str) -> String {
+    let core = id.trim_start_matches('#');
+    if core.chars().all(|c| c.is_ascii_digit()) {
+        format!("#{core}")
     } else {
-        format!("{} {}", issue_ids.join(","), message)
+        core.to_string()
     }
 }
 
+/// 提交信息中是否已包含该 Issue ID（`#12` 需带 `#`，`PROJ-123` 不区分大小写）
+fn mentions_issue(message: &str, display_id: &str) -> bool {
+    let pattern = match display_id.strip_prefix('#') {
+        Some(number) => format!(r"#{number}\b"),
+        None => format!(r"(?i)\b{}\b", regex::escape(display_id)),
+    };
+    regex::Regex::new(&pattern).is_ok_and(|re| re.is_match(message))
+}
+
+/// 段落是否全部由 `Key: value` 形式的 trailer 行组成
+fn is_trailer_block(paragraph: &str) -> bool {
+    paragraph.lines().all(|line| {
+        line.split_once(": ").is_some_and(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    })
+}
+
 // 需要的支撑函数
 
 /// 传统方式构建AI提示词（作为模板失败的降级方案）
@@ -323,40 +587,55 @@ fn build_issue_context(issues: &[Issue]) -> String {
 async fn perform_structural_analysis(
     diff: &str,
 ) -> Result<Option<StructuralSummary>, Box<dyn std::error::Error + Send + Sync>> {
-    println!("🌳 正在进行Tree-sitter结构分析...");
+    crate::progress!("{}", crate::tr!("commit.tree_sitter"));
 
     // 从diff中提取代码内容
     let code_content = extract_code_from_diff(diff);
     if code_content.is_empty() {
-        println!("⚠️ 未能从diff中提取到代码内容");
+        crate::progress!("{}", crate::tr!("commit.no_code"));
         return Ok(None);
     }
 
     // 推断语言
     let language = infer_language_from_diff(diff);
     let Some(supported_lang) = language else {
-        println!("⚠️ 不支持的语言或无法推断语言类型");
+        crate::progress!("{}", crate::tr!("commit.unsupported_language"));
         return Ok(None);
     };
 
-    println!("  检测到语言: {supported_lang:?}");
+    crate::progress!(
+        "{}",
+        crate::tr!(
+            "commit.detected_language",
+            lang = format!("{supported_lang:?}")
+        )
+    );
 
     // 创建Tree-sitter管理器并分析
     match TreeSitterManager::new().await {
         Ok(mut manager) => match manager.analyze_structure(&code_content, supported_lang) {
             Ok(summary) => {
-                println!("  ✅ 结构分析完成");
-                println!("     函数数量: {}", summary.functions.len());
-                println!("     类数量: {}", summary.classes.len());
+                crate::progress!("{}", crate::tr!("structure.done"));
+                crate::progress!(
+                    "{}",
+                    crate::tr!("commit.function_count", count = summary.functions.len())
+                );
+                crate::progress!(
+                    "{}",
+                    crate::tr!("commit.class_count", count = summary.classes.len())
+                );
                 Ok(Some(summary))
             }
             Err(e) => {
-                println!("  ⚠️ 结构分析失败: {e}");
+                crate::progress!("{}", crate::tr!("structure.failed", error = e));
                 Ok(None)
             }
         },
         Err(e) => {
-            println!("  ⚠️ Tree-sitter管理器初始化失败: {e}");
+            crate::progress!(
+                "{}",
+                crate::tr!("commit.tree_sitter_init_failed", error = e)
+            );
             Ok(None)
         }
     }
@@ -453,19 +732,14 @@ async fn perform_review_with_result(
     issues: &[Issue],
 ) -> Result<Option<ReviewResults>, Box<dyn std::error::Error + Send + Sync>> {
     // 创建评审配置
-    let review_config = crate::review::ReviewConfig {
-        language: None,
-        format: "text".to_string(),
-        output: None,
-        tree_sitter: true,
-        security_scan: true,
-        scan_tool: None,
-        block_on_critical: false,
-        issue_ids: issues.iter().map(|i| i.id.clone()).collect(),
-        space_id: config.devops.as_ref().and_then(|d| d.space_id),
-        full: false,
-        deviation_analysis: !issues.is_empty(),
-    };
+    let mut builder = crate::review::ReviewConfig::builder()
+        .tree_sitter(true)
+        .security_scan(true)
+        .issue_ids(issues.iter().map(|i| i.id.clone()));
+    if let Some(space_id) = config.devops.as_ref().and_then(|d| d.space_id) {
+        builder = builder.space_id(space_id);
+    }
+    let review_config = builder.build();
 
     // 执行评审 - 现在使用静态函数！
     match crate::review::execute_review_with_result(config, review_config).await {
@@ -496,25 +770,36 @@ async fn execute_git_operations_with_result(
     commit_config: &CommitConfig,
 ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
     if commit_config.dry_run {
-        println!("🔍 干运行模式 - 不会实际提交");
+        crate::progress!("{}", crate::tr!("commit.dry_run"));
+        // 静默模式下只输出生成的提交信息，便于脚本捕获
+        if crate::utils::output::quiet() {
+            println!("{commit_message}");
+        }
         return Ok(None);
     }
 
     // 添加文件到暂存区
     if commit_config.add_all {
-        println!("📝 添加所有变更到暂存区...");
+        crate::progress!("{}", crate::tr!("commit.adding_all"));
         crate::git::git_add_all()?;
     }
 
     // 执行提交
-    println!("📝 执行提交: {commit_message}");
+    crate::progress!(
+        "{}",
+        crate::tr!("commit.committing", message = commit_message)
+    );
     match crate::git::git_commit(commit_message) {
         Ok(hash) => {
-            println!("✅ 提交成功: {hash}");
+            if crate::utils::output::quiet() {
+                println!("{hash}");
+            } else {
+                println!("{}", crate::tr!("commit.success", hash = hash));
+            }
             Ok(Some(hash))
         }
         Err(e) => {
-            eprintln!("❌ 提交失败: {e}");
+            eprintln!("{}", crate::tr!("commit.failed", error = e));
             Err(e)
         }
     }
@@ -535,3 +820,77 @@ fn count_changes(diff: &str) -> Result<usize, Box<dyn std::error::Error + Send +
 
     Ok(added_lines + removed_lines)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CommitMessageConfig, IssuePlacement};
+
+    fn trailer_settings() -> CommitMessageConfig {
+        CommitMessageConfig {
+            issue_placement: IssuePlacement::Trailer,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_issue_ids_in_subject_are_not_duplicated() {
+        let subject = CommitMessageConfig::default();
+        let ids = vec!["#12".to_string(), "#PROJ-7".to_string()];
+        assert_eq!(
+            format_commit_message("feat: add login", &ids, &subject),
+            "#12,PROJ-7 feat: add login"
+        );
+        // 模型已写入的 ID 不再重复（带项目键的 ID 不区分大小写）
+        assert_eq!(
+            format_commit_message("feat: add login (#12, proj-7)", &ids, &subject),
+            "feat: add login (#12, proj-7)"
+        );
+        // 只补充缺少的 ID；`#120` 不算提到了 `#12`
+        assert_eq!(
+            format_commit_message("fix: retry #120", &ids, &subject),
+            "#12,PROJ-7 fix: retry #120"
+        );
+        assert_eq!(
+            format_commit_message("chore: tidy", &[], &subject),
+            "chore: tidy"
+        );
+    }
+
+    #[test]
+    fn test_issue_ids_as_trailer() {
+        let settings = trailer_settings();
+        let ids = vec!["#12".to_string(), "PROJ-7".to_string()];
+        assert_eq!(
+            format_commit_message("feat: add login\n\n- form validation", &ids, &settings),
+            "feat: add login\n\n- form validation\n\nRefs: #12\nRefs: PROJ-7"
+        );
+        // 追加到已有的 trailer 块，已存在的 ID 不重复
+        assert_eq!(
+            format_commit_message(
+                "feat: add login\n\nRefs: PROJ-7\nSigned-off-by: Dev <dev@example.com>\n",
+                &ids,
+                &settings
+            ),
+            "feat: add login\n\nRefs: PROJ-7\nSigned-off-by: Dev <dev@example.com>\nRefs: #12"
+        );
+
+        let closes = CommitMessageConfig {
+            issue_trailer: "Closes".to_string(),
+            ..trailer_settings()
+        };
+        assert_eq!(
+            format_commit_message("fix: crash", &["#3".to_string()], &closes),
+            "fix: crash\n\nCloses: #3"
+        );
+
+        let none = CommitMessageConfig {
+            issue_placement: IssuePlacement::None,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_commit_message("fix: crash", &ids, &none),
+            "fix: crash"
+        );
+    }
+}
@@ -1,9 +1,13 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// 应用配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
+    /// 配置文件结构版本，旧版本在加载时迁移到 `config_migrate::CURRENT_VERSION`
+    #[serde(default = "default_config_version")]
+    pub version: u32,
     /// AI配置
     pub ai: AiConfig,
     /// 扫描配置
@@ -14,18 +18,332 @@ pub struct Config {
     pub language: Option<String>,
     /// MCP配置
     pub mcp: Option<McpConfig>,
+    /// 代码分析配置
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    /// HTTP 客户端配置（超时、代理、自定义 CA 证书）
+    #[serde(default)]
+    pub http: crate::http::HttpConfig,
+    /// 提交信息生成配置
+    #[serde(default)]
+    pub commit: CommitMessageConfig,
+    /// 从分支名推断 Issue ID 的配置
+    #[serde(default)]
+    pub branch_issue: BranchIssueConfig,
+    /// 代码评审配置
+    #[serde(default)]
+    pub review: ReviewSettings,
+    /// 命名配置组（`[profiles.<名称>]`），通过 `--profile <名称>` 选用
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+fn default_config_version() -> u32 {
+    crate::config_migrate::CURRENT_VERSION
+}
+
+/// 命名配置组：把 scan/review/commit 常用的参数组合集中在配置里，
+/// 通过 `--profile <名称>` 选用；命令行参数优先于配置组中的值
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// 额外排除的文件模式（通配符），并入 `analysis.generated_patterns`
+    pub exclude: Vec<String>,
+    /// `gitai scan` 的参数
+    pub scan: ScanProfile,
+    /// `gitai review` 的参数
+    pub review: ReviewProfile,
+    /// `gitai commit` 的参数
+    pub commit: CommitProfile,
+}
+
+/// 配置组中的 `gitai scan` 参数
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanProfile {
+    pub tool: Option<String>,
+    pub format: Option<String>,
+    pub output: Vec<PathBuf>,
+    pub fail_on: Option<String>,
+    pub lang: Option<String>,
+    pub timeout: Option<u64>,
+    pub rules: Option<String>,
+    pub rules_only: bool,
+    pub no_history: bool,
+}
+
+/// 配置组中的 `gitai review` 参数
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReviewProfile {
+    pub format: Option<String>,
+    pub output: Vec<PathBuf>,
+    pub fail_on: Option<String>,
+    pub language: Option<String>,
+    pub tree_sitter: bool,
+    pub security_scan: bool,
+    pub scan_tool: Option<String>,
+    pub full: bool,
+    pub max_diff_tokens: Option<usize>,
+}
+
+/// 配置组中的 `gitai commit` 参数
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitProfile {
+    pub all: bool,
+    pub review: bool,
+    pub tree_sitter: bool,
+    pub template: Option<String>,
+    pub max_diff_tokens: Option<usize>,
+}
+
+impl ProfileConfig {
+    /// 验证配置组中的取值（命令行参数由 clap 校验，配置组中的值在这里校验）
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        fn check(
+            name: &str,
+            key: &str,
+            value: Option<&str>,
+            allowed: &[&str],
+        ) -> Result<(), String> {
+            match value {
+                Some(v) if !allowed.contains(&v) => Err(format!(
+                    "配置组 {name} 的 {key} 取值无效: {v}（可选: {}）",
+                    allowed.join(", ")
+                )),
+                _ => Ok(()),
+            }
+        }
+        check(
+            name,
+            "scan.tool",
+            self.scan.tool.as_deref(),
+            &["auto", "opengrep", "security"],
+        )?;
+        check(
+            name,
+            "scan.format",
+            self.scan.format.as_deref(),
+            &["text", "json", "markdown", "html"],
+        )?;
+        check(
+            name,
+            "scan.fail_on",
+            self.scan.fail_on.as_deref(),
+            &["error", "warning", "info"],
+        )?;
+        check(
+            name,
+            "review.format",
+            self.review.format.as_deref(),
+            &["text", "json", "markdown"],
+        )?;
+        check(
+            name,
+            "review.fail_on",
+            self.review.fail_on.as_deref(),
+            &["critical", "high", "medium", "low"],
+        )?;
+        if self.exclude.iter().any(|p| p.trim().is_empty()) {
+            return Err(format!("配置组 {name} 的 exclude 不能包含空模式"));
+        }
+        Ok(())
+    }
+}
+
+/// 默认的分支名 Issue ID 模式：匹配 `feature/PROJ-123-thing` 中的 `PROJ-123`
+/// 与 `fix/42-login` 中的 `42`（需位于分支名开头或 `/` 之后）
+pub const DEFAULT_BRANCH_ISSUE_PATTERN: &str = r"(?:^|/)([A-Z][A-Z0-9]+-\d+|\d+)(?:[-_/]|$)";
+
+/// 从分支名推断 Issue ID 的配置（`[branch_issue]`）
+///
+/// `commit` 与 `review` 未指定 `--issue-id` 时按 `pattern` 匹配当前分支名，未匹配时不关联 Issue
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BranchIssueConfig {
+    /// 是否启用推断
+    pub enabled: bool,
+    /// 匹配分支名的正则表达式；有捕获组时取第一个捕获组，否则取整个匹配
+    pub pattern: String,
+}
+
+impl Default for BranchIssueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pattern: DEFAULT_BRANCH_ISSUE_PATTERN.to_string(),
+        }
+    }
+}
+
+impl BranchIssueConfig {
+    /// 从分支名中提取 Issue ID；未启用、模式无效或未匹配时返回 None
+    pub fn extract(&self, branch: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let captures = regex::Regex::new(&self.pattern).ok()?.captures(branch)?;
+        let matched = captures.get(1).or_else(|| captures.get(0))?;
+        Some(matched.as_str().to_string()).filter(|id| !id.is_empty())
+    }
+
+    /// 验证正则表达式
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if self.enabled {
+            regex::Regex::new(&self.pattern)
+                .map_err(|e| format!("branch_issue.pattern 不是有效的正则表达式: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// 代码评审配置（`[review]`）
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReviewSettings {
+    /// 严重程度评级标准
+    pub severity_rubric: SeverityRubric,
+}
+
+/// 严重程度评级标准（`[review.severity_rubric]`）
+///
+/// 注入评审提示词，要求 AI 按统一口径把问题标注为 critical/major/minor，
+/// 使不同模型、不同次运行的严重程度一致，`--fail-on` 等门禁才有意义
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeverityRubric {
+    /// 何种问题属于 critical（映射为 Critical）
+    pub critical: String,
+    /// 何种问题属于 major（映射为 High）
+    pub major: String,
+    /// 何种问题属于 minor（映射为 Low）
+    pub minor: String,
+}
+
+impl Default for SeverityRubric {
+    fn default() -> Self {
+        Self {
+            critical: "会导致安全漏洞、数据丢失或损坏、生产环境崩溃，或破坏公共 API 兼容性的问题"
+                .to_string(),
+            major: "功能错误、未处理的错误路径、并发或资源泄漏问题，以及明显的性能退化".to_string(),
+            minor: "可读性、命名、重复代码、缺少注释或测试等不影响正确性的问题".to_string(),
+        }
+    }
+}
+
+impl SeverityRubric {
+    /// 验证各级别的说明均已填写
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        for (level, text) in [
+            ("critical", &self.critical),
+            ("major", &self.major),
+            ("minor", &self.minor),
+        ] {
+            if text.trim().is_empty() {
+                return Err(format!("review.severity_rubric.{level} 不能为空").into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 提交信息生成配置（`[commit]`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CommitMessageConfig {
+    /// 提交信息风格模板（如 `angular`、`gitmoji`、`plain`，对应提示词目录中的 `commit-<风格>.md`）；
+    /// 未设置时使用默认的约定式提交模板 `commit.md`，可用 `gitai commit --template` 临时覆盖
+    pub template: Option<String>,
+    /// 关联的 Issue ID 写入提交信息的位置
+    pub issue_placement: IssuePlacement,
+    /// `issue_placement = "trailer"` 时使用的 Git trailer 键
+    pub issue_trailer: String,
+}
+
+impl Default for CommitMessageConfig {
+    fn default() -> Self {
+        Self {
+            template: None,
+            issue_placement: IssuePlacement::default(),
+            issue_trailer: "Refs".to_string(),
+        }
+    }
+}
+
+/// Issue ID 在提交信息中的位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssuePlacement {
+    /// 作为标题前缀：`#12 feat: ...`
+    #[default]
+    Subject,
+    /// 作为 Git trailer 追加在末尾：`Refs: #12`
+    Trailer,
+    /// 不写入提交信息
+    None,
 }
 
 /// AI配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AiConfig {
     pub api_url: String,
     pub model: String,
     pub api_key: Option<String>,
     pub temperature: f32,
+    /// 核采样参数（0.0-1.0]，未设置时使用服务端默认值
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    /// 单次回复的最大 token 数，未设置时使用服务端默认值
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// 确定性模式：温度固定为 0 并发送固定的随机种子，便于 CI 与快照测试复现输出
+    #[serde(default)]
+    pub deterministic: bool,
+    /// 随机种子（服务端支持时生效）；确定性模式下未设置时使用 [`DETERMINISTIC_SEED`]
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 代码评审时单次发送给 AI 的 diff token 上限（约 4 字符/token），超过时按文件分段评审；0 表示不限制
+    #[serde(default = "default_max_diff_tokens")]
+    pub max_diff_tokens: usize,
+}
+
+/// 确定性模式下未指定 seed 时使用的随机种子
+pub const DETERMINISTIC_SEED: u64 = 42;
+
+fn default_max_diff_tokens() -> usize {
+    20_000
+}
+
+/// 发送给 AI 服务的采样参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplingParams {
+    pub temperature: f32,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub seed: Option<u64>,
 }
 
 impl AiConfig {
+    /// 生效的采样参数；确定性模式下温度为 0、不发送 top_p，并带上固定种子
+    pub fn sampling(&self) -> SamplingParams {
+        if self.deterministic {
+            SamplingParams {
+                temperature: 0.0,
+                top_p: None,
+                max_tokens: self.max_tokens,
+                seed: Some(self.seed.unwrap_or(DETERMINISTIC_SEED)),
+            }
+        } else {
+            SamplingParams {
+                temperature: self.temperature,
+                top_p: self.top_p,
+                max_tokens: self.max_tokens,
+                seed: self.seed,
+            }
+        }
+    }
+
     /// 验证 AI 配置
     pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
         // 验证 API URL
@@ -52,21 +370,81 @@ impl AiConfig {
             .into());
         }
 
+        if let Some(top_p) = self.top_p {
+            if top_p <= 0.0 || top_p > 1.0 {
+                return Err(format!("AI top_p 必须在 (0.0, 1.0] 之间，当前值: {top_p}").into());
+            }
+        }
+
+        if self.max_tokens == Some(0) {
+            return Err("AI max_tokens 不能为 0".into());
+        }
+
         Ok(())
     }
 }
 
 /// 扫描配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ScanConfig {
     /// 默认扫描路径
     pub default_path: Option<String>,
     /// 超时时间（秒）
     pub timeout: u64,
-    /// 并发数
+    /// 并发数（0 表示使用 OpenGrep 默认值）
     pub jobs: usize,
     /// 规则目录（可选）。未设置时默认使用 ~/.cache/gitai/rules
     pub rules_dir: Option<String>,
+    /// 本地自定义规则目录（可选），与托管规则一起使用；可用 `gitai scan --rules` 临时指定
+    #[serde(default)]
+    pub custom_rules_dir: Option<String>,
+    /// 仅使用自定义规则，不加载托管规则
+    #[serde(default)]
+    pub rules_only: bool,
+    /// 规则来源列表（`[[scan.sources]]`），按顺序合并，后面的来源按规则 ID 覆盖前面的；
+    /// 为空时只使用托管规则（及 `custom_rules_dir`）
+    #[serde(default)]
+    pub sources: Vec<RuleSourceConfig>,
+    /// 基线条目的老化期限（天）：加入基线超过该天数的问题重新报告为逾期；未设置时永不过期
+    #[serde(default)]
+    pub baseline_max_age_days: Option<u32>,
+}
+
+/// 一个规则来源（`[[scan.sources]]`）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RuleSourceConfig {
+    /// 来源名称，用于报告与缓存目录名（仅字母、数字、`-`、`_`）
+    pub name: String,
+    /// 来源类型及位置
+    #[serde(flatten)]
+    pub kind: RuleSourceKind,
+}
+
+/// 规则来源类型
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RuleSourceKind {
+    /// 托管规则缓存（`gitai scan --update-rules` 下载的规则）
+    Managed,
+    /// 本地规则目录
+    Local { path: String },
+    /// 额外的 Git 规则仓库，`rev` 为分支或标签（未设置时使用默认分支）
+    Git {
+        url: String,
+        #[serde(default)]
+        rev: Option<String>,
+    },
+}
+
+impl RuleSourceKind {
+    /// 类型名（与配置中的 `kind` 一致）
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuleSourceKind::Managed => "managed",
+            RuleSourceKind::Local { .. } => "local",
+            RuleSourceKind::Git { .. } => "git",
+        }
+    }
 }
 
 impl ScanConfig {
@@ -81,15 +459,15 @@ impl ScanConfig {
             return Err("扫描超时时间不能超过 3600 秒（1小时）".into());
         }
 
-        // 验证并发数
-        if self.jobs == 0 {
-            return Err("扫描并发数不能为 0".into());
-        }
-
+        // 验证并发数（0 表示使用 OpenGrep 默认值）
         if self.jobs > 32 {
             return Err("扫描并发数不能超过 32".into());
         }
 
+        if self.baseline_max_age_days == Some(0) {
+            return Err("基线老化期限至少为 1 天（不需要老化时不设置该项）".into());
+        }
+
         // 验证默认路径（如果存在）
         if let Some(ref path) = self.default_path {
             if path.trim().is_empty() {
@@ -104,12 +482,175 @@ impl ScanConfig {
             }
         }
 
+        if let Some(ref custom) = self.custom_rules_dir {
+            if custom.trim().is_empty() {
+                return Err("自定义规则目录不能为空字符串".into());
+            }
+        }
+
+        // 验证规则来源
+        let mut names = std::collections::HashSet::new();
+        for source in &self.sources {
+            let valid_name = !source.name.is_empty()
+                && source
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name {
+                return Err(format!(
+                    "规则来源名称只能包含字母、数字、`-` 与 `_`: {:?}",
+                    source.name
+                )
+                .into());
+            }
+            if !names.insert(source.name.as_str()) {
+                return Err(format!("规则来源名称重复: {}", source.name).into());
+            }
+            match &source.kind {
+                RuleSourceKind::Local { path } if path.trim().is_empty() => {
+                    return Err(format!("规则来源 {} 的 path 不能为空", source.name).into());
+                }
+                RuleSourceKind::Git { url, .. } if url.trim().is_empty() => {
+                    return Err(format!("规则来源 {} 的 url 不能为空", source.name).into());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 代码分析配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AnalysisConfig {
+    /// 内置 lint 配置
+    #[serde(default)]
+    pub lints: LintsConfig,
+    /// 工程一致性评估配置
+    #[serde(default)]
+    pub evaluation: EvaluationConfig,
+    /// 生成文件的通配符模式，命中的文件不参与结构分析、质量指标、安全扫描与评审；
+    /// 未设置时使用内置列表（protobuf 生成代码、`*_generated.rs`、锁文件、压缩产物等）
+    #[serde(default = "crate::utils::generated::default_patterns")]
+    pub generated_patterns: Vec<String>,
+    /// 即使命中 `generated_patterns` 也保留的文件模式
+    #[serde(default)]
+    pub include_generated: Vec<String>,
+    /// 目录遍历的最大深度（起始目录下的文件为 1），未设置时不限制
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// 目录遍历是否跟随符号链接（默认不跟随；跟随时会检测环路）
+    #[serde(default)]
+    pub follow_symlinks: bool,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            lints: LintsConfig::default(),
+            evaluation: EvaluationConfig::default(),
+            generated_patterns: crate::utils::generated::default_patterns(),
+            include_generated: Vec::new(),
+            max_depth: None,
+            follow_symlinks: false,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// 由配置构建生成文件过滤器
+    pub fn generated_filter(&self) -> crate::utils::generated::GeneratedFilter {
+        crate::utils::generated::GeneratedFilter::new(
+            self.generated_patterns.clone(),
+            self.include_generated.clone(),
+        )
+    }
+
+    /// 由配置构建目录遍历选项
+    pub fn walk_options(&self) -> crate::utils::walk::WalkOptions {
+        crate::utils::walk::WalkOptions {
+            max_depth: self.max_depth,
+            follow_symlinks: self.follow_symlinks,
+        }
+    }
+}
+
+/// 工程一致性评估配置（`gitai eval`）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EvaluationConfig {
+    /// 允许存在的 config.rs 路径、目录或通配符模式（相对扫描路径），不计入数量限制
+    #[serde(default)]
+    pub config_allowlist: Vec<String>,
+    /// 白名单之外允许存在的 config.rs 数量上限
+    #[serde(default = "default_max_config_files")]
+    pub max_config_files: usize,
+}
+
+fn default_max_config_files() -> usize {
+    1
+}
+
+impl Default for EvaluationConfig {
+    fn default() -> Self {
+        Self {
+            config_allowlist: Vec::new(),
+            max_config_files: default_max_config_files(),
+        }
+    }
+}
+
+/// 内置 lint 阈值配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LintsConfig {
+    /// 是否启用内置 lint
+    #[serde(default = "default_lints_enabled")]
+    pub enabled: bool,
+    /// 函数最大行数，超过即报告
+    #[serde(default = "default_max_function_lines")]
+    pub max_function_lines: usize,
+    /// 函数最大参数个数，超过即报告
+    #[serde(default = "default_max_parameters")]
+    pub max_parameters: usize,
+}
+
+fn default_lints_enabled() -> bool {
+    true
+}
+
+fn default_max_function_lines() -> usize {
+    80
+}
+
+fn default_max_parameters() -> usize {
+    6
+}
+
+impl Default for LintsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_lints_enabled(),
+            max_function_lines: default_max_function_lines(),
+            max_parameters: default_max_parameters(),
+        }
+    }
+}
+
+impl LintsConfig {
+    /// 验证 lint 配置
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if self.max_function_lines == 0 {
+            return Err("analysis.lints.max_function_lines 不能为 0".into());
+        }
+        if self.max_parameters == 0 {
+            return Err("analysis.lints.max_parameters 不能为 0".into());
+        }
         Ok(())
     }
 }
 
 /// DevOps配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DevOpsConfig {
     /// 平台类型 (coding, github, gitlab)
     pub platform: String,
@@ -128,7 +669,7 @@ pub struct DevOpsConfig {
 }
 
 /// MCP配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpConfig {
     /// 是否启用MCP服务
     pub enabled: bool,
@@ -136,6 +677,37 @@ pub struct McpConfig {
     pub server: McpServerConfig,
     /// 服务配置
     pub services: McpServicesConfig,
+    /// 工具调用日志配置
+    #[serde(default)]
+    pub logging: McpLoggingConfig,
+}
+
+/// MCP 工具调用日志配置（`[mcp.logging]`）
+///
+/// 默认关闭，避免把调用参数中的敏感信息写入日志；也可通过环境变量
+/// `GITAI_MCP_LOG_CALLS=true` 临时开启
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct McpLoggingConfig {
+    /// 是否记录工具调用的参数与响应
+    pub enabled: bool,
+    /// 响应内容记录的最大字符数，超出部分截断
+    pub max_response_chars: usize,
+    /// 需要脱敏的字段名（不区分大小写，嵌套对象中同样生效）
+    pub redact_fields: Vec<String>,
+}
+
+impl Default for McpLoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_response_chars: 2000,
+            redact_fields: ["api_key", "token", "password", "secret", "authorization"]
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        }
+    }
 }
 
 impl McpConfig {
@@ -152,7 +724,7 @@ impl McpConfig {
 }
 
 /// MCP服务器配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpServerConfig {
     /// 传输协议 (stdio, tcp, sse)
     pub transport: String,
@@ -162,6 +734,9 @@ pub struct McpServerConfig {
     pub name: String,
     /// 服务版本
     pub version: String,
+    /// 工具调用允许访问的目录（`repo_path` 与 `path` 必须位于其中之一）；为空时仅允许服务启动时的仓库根目录
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
 }
 
 impl McpServerConfig {
@@ -215,7 +790,7 @@ impl McpServerConfig {
 }
 
 /// MCP服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpServicesConfig {
     /// 启用的服务列表
     pub enabled: Vec<String>,
@@ -274,7 +849,7 @@ impl McpServicesConfig {
 }
 
 /// MCP Review服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpReviewConfig {
     /// 默认启用tree-sitter分析
     pub default_tree_sitter: bool,
@@ -328,7 +903,7 @@ impl McpReviewConfig {
 }
 
 /// MCP Commit服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpCommitConfig {
     /// 默认启用代码评审
     pub default_review: bool,
@@ -347,7 +922,7 @@ impl McpCommitConfig {
 }
 
 /// MCP Scan服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpScanConfig {
     /// 默认扫描工具
     pub default_tool: String,
@@ -377,7 +952,7 @@ impl McpScanConfig {
 }
 
 /// MCP Analysis服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpAnalysisConfig {
     /// 默认输出详细程度
     pub verbosity: u32,
@@ -387,6 +962,9 @@ pub struct McpAnalysisConfig {
     pub supported_languages: Option<Vec<String>>,
     /// 多语言项目的最大文件数量
     pub max_files_per_analysis: u32,
+    /// 并发分析的最大工作数（未设置时取 CPU 可用并行度，可用 GITAI_ANALYSIS_CONCURRENCY 覆盖）
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
 }
 
 impl McpAnalysisConfig {
@@ -443,7 +1021,7 @@ impl McpAnalysisConfig {
 }
 
 /// MCP Dependency服务配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpDependencyConfig {
     /// 默认输出格式
     pub default_format: String,
@@ -488,17 +1066,27 @@ impl McpDependencyConfig {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: default_config_version(),
             ai: AiConfig {
                 api_url: "http://localhost:11434/v1/chat/completions".to_string(),
                 model: "qwen2.5:32b".to_string(),
                 api_key: None,
                 temperature: 0.3,
+                top_p: None,
+                max_tokens: None,
+                deterministic: false,
+                seed: None,
+                max_diff_tokens: default_max_diff_tokens(),
             },
             scan: ScanConfig {
                 default_path: None,
                 timeout: 300,
                 jobs: 0, // 0 表示不强制设置并发，使用 OpenGrep 默认
                 rules_dir: None,
+                custom_rules_dir: None,
+                rules_only: false,
+                sources: Vec::new(),
+                baseline_max_age_days: None,
             },
             devops: None,
             language: None,
@@ -509,6 +1097,7 @@ impl Default for Config {
                     listen_addr: Some("127.0.0.1:8080".to_string()),
                     name: "gitai".to_string(),
                     version: "0.1.0".to_string(),
+                    allowed_paths: Vec::new(),
                 },
                 services: McpServicesConfig {
                     enabled: vec![
@@ -539,6 +1128,7 @@ impl Default for Config {
                         default_format: "json".to_string(),
                         supported_languages: None, // 支持所有语言
                         max_files_per_analysis: 1000,
+                        max_concurrency: None,
                     }),
                     dependency: Some(McpDependencyConfig {
                         default_format: "ascii".to_string(),
@@ -548,27 +1138,84 @@ impl Default for Config {
                         exclude_test_code: true,
                     }),
                 },
+                logging: McpLoggingConfig::default(),
             }),
+            analysis: AnalysisConfig::default(),
+            http: crate::http::HttpConfig::default(),
+            commit: CommitMessageConfig::default(),
+            branch_issue: BranchIssueConfig::default(),
+            review: ReviewSettings::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
 
 impl Config {
-    /// 加载配置
-    pub fn load() -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let config_path = dirs::home_dir()
+    /// 配置文件路径（`~/.config/gitai/config.toml`）
+    pub fn path() -> PathBuf {
+        dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".config")
             .join("gitai")
-            .join("config.toml");
+            .join("config.toml")
+    }
 
+    /// 加载配置：用户配置文件与环境变量逐层覆盖默认值
+    pub fn load() -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(Self::load_layered(Vec::new())?.config)
+    }
+
+    /// 加载配置并记录每个配置项的来源；flags 为命令行参数对应的配置层，优先级最高
+    pub fn load_layered(
+        flags: Vec<crate::config_layers::Layer>,
+    ) -> Result<
+        crate::config_layers::LayeredConfig,
+        Box<dyn std::error::Error + Send + Sync + 'static>,
+    > {
+        use crate::config_layers::{self, Layer, Source};
+
+        let config_path = Self::path();
+        let mut layers = Vec::new();
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
-            config.validate()?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+            let mut content = std::fs::read_to_string(&config_path)?;
+            if let Some(migration) = crate::config_migrate::migrate(&content)? {
+                Self::report_migration(&config_path, &content, &migration);
+                content = migration.content;
+            }
+            // 用户配置文件本身须是完整的配置，单独解析一次以便错误信息带上行号
+            toml::from_str::<Config>(&content)?;
+            layers.push(Layer::from_content(
+                Source::UserFile,
+                config_path.display().to_string(),
+                &content,
+            )?);
+        }
+        layers.extend(config_layers::env_layers(std::env::vars()));
+        layers.extend(flags);
+        config_layers::resolve(&layers)
+    }
+
+    /// 写回迁移后的配置文件并报告改动；写入失败时仅提示，本次仍使用迁移后的内容
+    fn report_migration(
+        config_path: &std::path::Path,
+        original: &str,
+        migration: &crate::config_migrate::Migration,
+    ) {
+        let (from, to) = (migration.from, migration.to);
+        log::debug!("配置文件从版本 {from} 迁移到 {to}: {:?}", migration.changes);
+        match crate::config_migrate::write_migration(config_path, original, migration) {
+            Ok(backup) => {
+                if !crate::utils::output::quiet() {
+                    eprintln!(
+                        "🔄 配置文件已从版本 {from} 迁移到 {to}，原文件备份为 {}",
+                        backup.display()
+                    );
+                    for change in &migration.changes {
+                        eprintln!("  - {change}");
+                    }
+                }
+            }
+            Err(e) => log::warn!("无法写回迁移后的配置文件 {}: {e}", config_path.display()),
         }
     }
 
@@ -585,6 +1232,159 @@ impl Config {
             mcp.validate()?;
         }
 
+        // 验证分析配置
+        self.analysis.lints.validate()?;
+
+        self.branch_issue.validate()?;
+        self.review.severity_rubric.validate()?;
+
+        // 验证命名配置组
+        for (name, profile) in &self.profiles {
+            profile.validate(name)?;
+        }
+
         Ok(())
     }
+
+    /// 按名称取命名配置组；不存在时的错误信息列出全部可用的配置组
+    pub fn profile(&self, name: &str) -> Result<&ProfileConfig, String> {
+        self.profiles.get(name).ok_or_else(|| {
+            if self.profiles.is_empty() {
+                format!("未找到配置组 '{name}'：配置文件中没有定义任何 [profiles.<名称>]")
+            } else {
+                let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                format!(
+                    "未找到配置组 '{name}'，可用的配置组: {}",
+                    available.join(", ")
+                )
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_lookup_and_validation() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(default)]
+            profiles: BTreeMap<String, ProfileConfig>,
+        }
+        let parsed: Wrapper = toml::from_str(
+            r#"
+            [profiles.ci]
+            exclude = ["vendor/**"]
+            [profiles.ci.scan]
+            tool = "opengrep"
+            fail_on = "error"
+            format = "json"
+            [profiles.ci.review]
+            fail_on = "high"
+            security_scan = true
+            [profiles.local.commit]
+            template = "gitmoji"
+            "#,
+        )
+        .unwrap();
+        let mut config = Config {
+            profiles: parsed.profiles,
+            ..Config::default()
+        };
+
+        let ci = config.profile("ci").unwrap();
+        assert_eq!(ci.scan.fail_on.as_deref(), Some("error"));
+        assert!(ci.review.security_scan);
+        assert_eq!(ci.exclude, vec!["vendor/**".to_string()]);
+        assert!(config.profiles.iter().all(|(n, p)| p.validate(n).is_ok()));
+
+        let err = config.profile("nightly").unwrap_err();
+        assert!(err.contains("ci, local"), "{err}");
+
+        let ci = config.profiles.get_mut("ci").unwrap();
+        ci.scan.fail_on = Some("critical".to_string());
+        assert!(ci.validate("ci").unwrap_err().contains("scan.fail_on"));
+
+        // 拼写错误的键直接报错，而不是被静默忽略
+        let typo: Result<Wrapper, _> = toml::from_str("[profiles.ci.scan]\nfail-on = \"error\"\n");
+        assert!(typo.is_err());
+    }
+
+    #[test]
+    fn test_rule_sources_parse_and_validate() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            scan: ScanConfig,
+        }
+        let parsed: Wrapper = toml::from_str(
+            r#"
+            [scan]
+            timeout = 300
+            jobs = 4
+            [[scan.sources]]
+            name = "baseline"
+            kind = "managed"
+            [[scan.sources]]
+            name = "org"
+            kind = "git"
+            url = "https://example.com/org/rules.git"
+            rev = "v2"
+            [[scan.sources]]
+            name = "team"
+            kind = "local"
+            path = "./team-rules"
+            "#,
+        )
+        .unwrap();
+        let mut scan = parsed.scan;
+        assert_eq!(scan.sources.len(), 3);
+        assert_eq!(scan.sources[0].kind, RuleSourceKind::Managed);
+        assert_eq!(
+            scan.sources[1].kind,
+            RuleSourceKind::Git {
+                url: "https://example.com/org/rules.git".to_string(),
+                rev: Some("v2".to_string()),
+            }
+        );
+        assert!(scan.validate().is_ok());
+
+        scan.sources[2].name = "baseline".to_string();
+        assert!(scan.validate().is_err());
+        scan.sources[2].name = "team/rules".to_string();
+        assert!(scan.validate().is_err());
+    }
+
+    #[test]
+    fn test_branch_issue_extraction() {
+        let config = BranchIssueConfig::default();
+        assert_eq!(
+            config.extract("feature/PROJ-123-thing").as_deref(),
+            Some("PROJ-123")
+        );
+        assert_eq!(config.extract("fix/42-login").as_deref(), Some("42"));
+        assert_eq!(config.extract("1024").as_deref(), Some("1024"));
+        assert_eq!(config.extract("main"), None);
+        assert_eq!(config.extract("release/v1.2.0"), None);
+        assert_eq!(config.extract("release/1.2.0"), None);
+        assert_eq!(config.extract("feature/oauth2-login"), None);
+
+        let custom: BranchIssueConfig = toml::from_str(r#"pattern = "issue-(\\d+)""#).unwrap();
+        assert!(custom.enabled);
+        assert_eq!(custom.extract("dev/issue-77").as_deref(), Some("77"));
+
+        let disabled = BranchIssueConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.extract("feature/PROJ-123-thing"), None);
+
+        let invalid = BranchIssueConfig {
+            enabled: true,
+            pattern: "(".to_string(),
+        };
+        assert!(invalid.validate().is_err());
+        assert_eq!(invalid.extract("feature/PROJ-1"), None);
+    }
 }
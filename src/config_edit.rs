@@ -0,0 +1,216 @@
+// 单项配置读写
+// `gitai config get/set` 按点分键（如 `ai.model`）读取生效值或修改配置文件中的单个值；
+// 修改通过 toml_edit 进行，保留文件中的注释与格式，写回前用完整的配置校验
+
+use crate::config::Config;
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item, Table};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 读取配置项在 config 中的值；传入 [`Config::load_layered`] 合并后的配置即为各命令实际使用的生效值，
+/// 未设置的可选项返回 Null
+pub fn get(config: &Config, key: &str) -> Result<JsonValue> {
+    let parts = split_key(key)?;
+    if let Some(value) = lookup(&serde_json::to_value(config)?, &parts) {
+        return Ok(value.clone());
+    }
+    // 所在的可选配置段（如 `[mcp]`）未设置
+    match lookup(&serde_json::to_value(Config::default())?, &parts) {
+        Some(_) => Ok(JsonValue::Null),
+        None => Err(unknown_key(key)),
+    }
+}
+
+/// 把配置项设置为 raw 并返回新的文件内容；未知的键、配置段或校验失败的值都会被拒绝
+///
+/// raw 按 TOML 字面量解析（`0.5`、`true`、`["a", "b"]`），不是合法字面量或类型不符时按字符串处理
+pub fn set(content: &str, key: &str, raw: &str) -> Result<String> {
+    let parts = split_key(key)?;
+    let current: Config =
+        toml::from_str(content).map_err(|e| format!("当前配置文件无法解析: {e}"))?;
+
+    // 键必须出现在默认配置或当前配置中（后者覆盖 profiles 等以名称为键的配置段）
+    let known = [
+        serde_json::to_value(Config::default())?,
+        serde_json::to_value(&current)?,
+    ];
+    let existing = known
+        .iter()
+        .find_map(|value| lookup(value, &parts))
+        .ok_or_else(|| unknown_key(key))?;
+    if existing.is_object() {
+        return Err(format!("{key} 是配置段，请指定其中的具体配置项").into());
+    }
+
+    let literal = raw.parse::<toml_edit::Value>().ok();
+    let mut candidates = Vec::new();
+    if let Some(literal) = literal.filter(|v| !v.is_str()) {
+        candidates.push(literal);
+    }
+    candidates.push(toml_edit::Value::from(raw));
+
+    let mut last_error = String::new();
+    for value in candidates {
+        let mut doc: DocumentMut = content.parse()?;
+        set_in_doc(&mut doc, &parts, value)?;
+        let updated = doc.to_string();
+        match toml::from_str::<Config>(&updated) {
+            Ok(config) => match config.validate() {
+                Ok(()) => return Ok(updated),
+                Err(e) => last_error = e.to_string(),
+            },
+            Err(e) => last_error = e.message().to_string(),
+        }
+    }
+    Err(format!("{key} 的值 '{raw}' 无效: {last_error}").into())
+}
+
+/// 在文档中写入值；缺少的上级配置段会被创建，已有值的注释与空白保留
+fn set_in_doc(doc: &mut DocumentMut, parts: &[&str], mut value: toml_edit::Value) -> Result<()> {
+    let (last, parents) = parts.split_last().ok_or("配置项不能为空")?;
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for part in parents {
+        if table.get(part).is_none() {
+            let mut new_table = Table::new();
+            new_table.set_implicit(true);
+            table.insert(part, Item::Table(new_table));
+        }
+        table = table
+            .get_mut(part)
+            .and_then(Item::as_table_like_mut)
+            .ok_or_else(|| format!("{part} 在配置文件中不是配置段"))?;
+    }
+    // 原地替换已有的值，保留键前的注释
+    match table.get_mut(last) {
+        Some(item) if item.is_value() => {
+            if let Some(old) = item.as_value() {
+                *value.decor_mut() = old.decor().clone();
+            }
+            *item = Item::Value(value);
+        }
+        _ => {
+            table.insert(last, Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+/// 拆分点分键，拒绝空段
+fn split_key(key: &str) -> Result<Vec<&str>> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|p| p.trim().is_empty()) {
+        return Err(format!("无效的配置项: '{key}'").into());
+    }
+    Ok(parts)
+}
+
+fn lookup<'a>(value: &'a JsonValue, parts: &[&str]) -> Option<&'a JsonValue> {
+    parts
+        .iter()
+        .try_fold(value, |value, part| value.as_object()?.get(*part))
+}
+
+fn unknown_key(key: &str) -> Box<dyn std::error::Error + Send + Sync> {
+    format!("未知的配置项: {key}").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"# GitAI 配置
+[ai]
+# 使用的模型
+model = "qwen2.5:32b" # 本地模型
+api_url = "http://localhost:11434/v1/chat/completions"
+temperature = 0.3
+
+[scan]
+timeout = 300
+jobs = 4
+
+[profiles.ci.scan]
+fail_on = "error"
+"#;
+
+    fn parse(content: &str) -> Config {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_get_nested_keys() {
+        let config = parse(CONFIG);
+        assert_eq!(get(&config, "ai.model").unwrap(), "qwen2.5:32b");
+        assert_eq!(get(&config, "scan.timeout").unwrap(), 300);
+        // 文件中未设置的项返回默认值，未设置的可选项为 Null
+        assert_eq!(get(&config, "analysis.lints.max_parameters").unwrap(), 6);
+        assert!(get(&config, "commit.template").unwrap().is_null());
+        assert!(get(&config, "mcp.server.transport").unwrap().is_null());
+        assert_eq!(get(&Config::default(), "ai.model").unwrap(), "qwen2.5:32b");
+        assert_eq!(get(&config, "profiles.ci.scan.fail_on").unwrap(), "error");
+        assert!(get(&config, "ai").unwrap().is_object());
+        assert!(get(&config, "ai.modle").is_err());
+        assert!(get(&config, "ai..model").is_err());
+    }
+
+    #[test]
+    fn test_get_reads_layered_value() {
+        use crate::config_layers::{resolve, Layer, Source};
+
+        let layers = vec![
+            Layer::from_content(Source::UserFile, "config.toml", CONFIG).unwrap(),
+            Layer::value(Source::Env, "GITAI__SCAN__TIMEOUT", "scan.timeout", 42),
+            Layer::value(Source::Flag, "--max-depth", "analysis.max_depth", 3),
+        ];
+        let layered = resolve(&layers).unwrap();
+        assert_eq!(get(&layered.config, "scan.timeout").unwrap(), 42);
+        assert_eq!(get(&layered.config, "analysis.max_depth").unwrap(), 3);
+        assert_eq!(get(&layered.config, "ai.model").unwrap(), "qwen2.5:32b");
+    }
+
+    #[test]
+    fn test_set_preserves_comments() {
+        let updated = set(CONFIG, "ai.model", "gpt-4o").unwrap();
+        assert!(updated.contains("# 使用的模型\nmodel = \"gpt-4o\" # 本地模型\n"));
+        assert!(updated.starts_with("# GitAI 配置\n"));
+        assert_eq!(get(&parse(&updated), "ai.model").unwrap(), "gpt-4o");
+
+        let updated = set(&updated, "ai.temperature", "0.7").unwrap();
+        assert!(updated.contains("temperature = 0.7\n"));
+    }
+
+    #[test]
+    fn test_set_nested_keys() {
+        let updated = set(CONFIG, "analysis.lints.max_function_lines", "80").unwrap();
+        assert!(updated.contains("[analysis.lints]\nmax_function_lines = 80\n"));
+        assert_eq!(
+            get(&parse(&updated), "analysis.lints.max_function_lines").unwrap(),
+            80
+        );
+        let updated = set(&updated, "analysis.include_generated", r#"["src/gen/**"]"#).unwrap();
+        assert_eq!(
+            get(&parse(&updated), "analysis.include_generated").unwrap(),
+            serde_json::json!(["src/gen/**"])
+        );
+        let updated = set(&updated, "commit.template", "gitmoji").unwrap();
+        assert_eq!(get(&parse(&updated), "commit.template").unwrap(), "gitmoji");
+        let updated = set(&updated, "profiles.ci.scan.fail_on", "warning").unwrap();
+        assert!(updated.contains("[profiles.ci.scan]\nfail_on = \"warning\""));
+        // 数字字符串写入字符串字段时按字符串处理
+        let updated = set(&updated, "ai.model", "123").unwrap();
+        assert_eq!(get(&parse(&updated), "ai.model").unwrap(), "123");
+    }
+
+    #[test]
+    fn test_set_rejects_unknown_and_invalid() {
+        assert!(set(CONFIG, "ai.modle", "x")
+            .unwrap_err()
+            .to_string()
+            .contains("未知的配置项"));
+        assert!(set(CONFIG, "profiles.nightly.scan.fail_on", "error").is_err());
+        assert!(set(CONFIG, "ai", "x").is_err());
+        assert!(set(CONFIG, "scan.timeout", "soon").is_err());
+        assert!(set(CONFIG, "profiles.ci.scan.fail_on", "critical").is_err());
+    }
+}
@@ -121,7 +121,8 @@ impl ConfigInitializer {
     async fn download_config(&self, url: &str, target: &Path) -> Result<()> {
         info!("Downloading configuration from {}", url);
 
-        let client = reqwest::Client::builder()
+        let client = crate::http::client_builder()
+            .map_err(|e| anyhow::anyhow!(e))?
             .timeout(std::time::Duration::from_secs(30))
             .build()?;
 
@@ -154,175 +155,17 @@ impl ConfigInitializer {
         self.download_config(default_url, target).await
     }
 
-    /// Check and migrate config if needed
+    /// Migrate an existing config to the current version
+    /// (same path as `Config::load`, see [`crate::config_migrate`])
     fn migrate_config_if_needed(&self, config_path: &Path) -> Result<()> {
-        let content = fs::read_to_string(config_path)?;
-
-        // Try to parse as TOML to check version
-        if let Ok(mut config) = toml::from_str::<toml::Value>(&content) {
-            // Check if version field exists
-            if config.get("version").is_none() {
-                info!("Migrating configuration to new format...");
-
-                // Add version field
-                if let Some(table) = config.as_table_mut() {
-                    table.insert(
-                        "version".to_string(),
-                        toml::Value::String("1.0.0".to_string()),
-                    );
-
-                    // Add sources section if not exists
-                    if !table.contains_key("sources") {
-                        let mut sources = toml::Table::new();
-                        sources.insert("config_url".to_string(), 
-                            toml::Value::String("https://raw.githubusercontent.com/nehcuh/gitai/main/assets/config.enhanced.toml".to_string()));
-                        sources.insert(
-                            "rules_url".to_string(),
-                            toml::Value::String(
-                                "https://github.com/nehcuh/gitai-rules.git".to_string(),
-                            ),
-                        );
-                        sources.insert(
-                            "tree_sitter_url".to_string(),
-                            toml::Value::String(
-                                "https://github.com/nehcuh/gitai-tree-sitter.git".to_string(),
-                            ),
-                        );
-
-                        table.insert("sources".to_string(), toml::Value::Table(sources));
-                    }
-
-                    // Add network section if not exists
-                    if !table.contains_key("network") {
-                        let mut network = toml::Table::new();
-                        network.insert("proxy".to_string(), toml::Value::String("".to_string()));
-                        network.insert("timeout".to_string(), toml::Value::Integer(30));
-                        network.insert("retry_times".to_string(), toml::Value::Integer(3));
-                        network.insert("offline_mode".to_string(), toml::Value::Boolean(false));
-
-                        table.insert("network".to_string(), toml::Value::Table(network));
-                    }
-
-                    // Add cache section if not exists
-                    if !table.contains_key("cache") {
-                        let mut cache = toml::Table::new();
-                        cache.insert("enabled".to_string(), toml::Value::Boolean(true));
-                        cache.insert(
-                            "path".to_string(),
-                            toml::Value::String(self.cache_dir.to_string_lossy().to_string()),
-                        );
-                        cache.insert(
-                            "max_size".to_string(),
-                            toml::Value::String("1GB".to_string()),
-                        );
-                        cache.insert("ttl".to_string(), toml::Value::Integer(604800));
-
-                        table.insert("cache".to_string(), toml::Value::Table(cache));
-                    }
-
-                    // Add MCP section if not exists
-                    if !table.contains_key("mcp") {
-                        let mut mcp = toml::Table::new();
-                        mcp.insert("enabled".to_string(), toml::Value::Boolean(true));
-
-                        // Add MCP server section
-                        let mut mcp_server = toml::Table::new();
-                        mcp_server.insert(
-                            "transport".to_string(),
-                            toml::Value::String("stdio".to_string()),
-                        );
-                        mcp_server.insert(
-                            "name".to_string(),
-                            toml::Value::String("gitai-mcp".to_string()),
-                        );
-                        mcp_server.insert(
-                            "version".to_string(),
-                            toml::Value::String("0.1.0".to_string()),
-                        );
-                        mcp.insert("server".to_string(), toml::Value::Table(mcp_server));
-
-                        // Add MCP services section
-                        let mut mcp_services = toml::Table::new();
-                        let enabled_services = vec![
-                            toml::Value::String("review".to_string()),
-                            toml::Value::String("commit".to_string()),
-                            toml::Value::String("scan".to_string()),
-                            toml::Value::String("analysis".to_string()),
-                        ];
-                        mcp_services
-                            .insert("enabled".to_string(), toml::Value::Array(enabled_services));
-
-                        // Add review service config
-                        let mut review_service = toml::Table::new();
-                        review_service.insert(
-                            "default_format".to_string(),
-                            toml::Value::String("text".to_string()),
-                        );
-                        review_service.insert(
-                            "default_tree_sitter".to_string(),
-                            toml::Value::Boolean(false),
-                        );
-                        review_service.insert(
-                            "default_security_scan".to_string(),
-                            toml::Value::Boolean(false),
-                        );
-                        mcp_services
-                            .insert("review".to_string(), toml::Value::Table(review_service));
-
-                        // Add commit service config
-                        let mut commit_service = toml::Table::new();
-                        commit_service
-                            .insert("default_add_all".to_string(), toml::Value::Boolean(false));
-                        commit_service
-                            .insert("default_review".to_string(), toml::Value::Boolean(false));
-                        commit_service.insert(
-                            "default_tree_sitter".to_string(),
-                            toml::Value::Boolean(false),
-                        );
-                        mcp_services
-                            .insert("commit".to_string(), toml::Value::Table(commit_service));
-
-                        // Add scan service config
-                        let mut scan_service = toml::Table::new();
-                        scan_service.insert(
-                            "default_tool".to_string(),
-                            toml::Value::String("opengrep".to_string()),
-                        );
-                        scan_service
-                            .insert("default_timeout".to_string(), toml::Value::Integer(300));
-                        mcp_services.insert("scan".to_string(), toml::Value::Table(scan_service));
-
-                        // Add analysis service config
-                        let mut analysis_service = toml::Table::new();
-                        analysis_service.insert("verbosity".to_string(), toml::Value::Integer(1));
-                        analysis_service.insert(
-                            "default_format".to_string(),
-                            toml::Value::String("json".to_string()),
-                        );
-                        analysis_service.insert(
-                            "max_files_per_analysis".to_string(),
-                            toml::Value::Integer(1000),
-                        );
-                        mcp_services
-                            .insert("analysis".to_string(), toml::Value::Table(analysis_service));
-
-                        mcp.insert("services".to_string(), toml::Value::Table(mcp_services));
-                        table.insert("mcp".to_string(), toml::Value::Table(mcp));
-                    }
-
-                    // Backup old config
-                    let backup_path = config_path.with_extension("toml.backup");
-                    fs::copy(config_path, &backup_path)?;
-                    info!("Backed up old configuration to {:?}", backup_path);
-
-                    // Write migrated config
-                    let migrated = toml::to_string_pretty(&config)?;
-                    fs::write(config_path, migrated)?;
-                    info!("Configuration migrated successfully");
-                }
-            }
+        if let Some((migration, backup)) =
+            crate::config_migrate::migrate_file(config_path).map_err(|e| anyhow::anyhow!(e))?
+        {
+            info!(
+                "Migrated configuration from version {} to {}, backup at {:?}",
+                migration.from, migration.to, backup
+            );
         }
-
         Ok(())
     }
 
@@ -439,4 +282,30 @@ mod tests {
         assert!(initializer.config_dir.join("prompts").exists());
         assert!(initializer.config_dir.join("prompts/commit.md").exists());
     }
+
+    #[tokio::test]
+    async fn test_initialize_migrates_existing_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let initializer = ConfigInitializer {
+            config_dir: temp_dir.path().join(".config/gitai"),
+            cache_dir: temp_dir.path().join(".cache/gitai"),
+            config_url: None,
+            offline_mode: true,
+        };
+        fs::create_dir_all(&initializer.config_dir).unwrap();
+        let old = "[scan]\nrules_path = \"/opt/rules\"\n";
+        fs::write(initializer.config_dir.join("config.toml"), old).unwrap();
+
+        let config_path = initializer.initialize().await.unwrap();
+        let migrated = fs::read_to_string(&config_path).unwrap();
+        assert!(migrated.starts_with(&format!(
+            "version = {}",
+            crate::config_migrate::CURRENT_VERSION
+        )));
+        assert!(migrated.contains("rules_dir = \"/opt/rules\""));
+        assert_eq!(
+            fs::read_to_string(initializer.config_dir.join("config.toml.v1.backup")).unwrap(),
+            old
+        );
+    }
 }
@@ -0,0 +1,371 @@
+// 分层配置与来源追踪
+// 生效配置由低到高逐层合并：内置默认值 → 用户配置文件（~/.config/gitai/config.toml）→
+// 环境变量（GITAI__<段>__<键>）→ 命令行参数；
+// 合并时记录每个配置项来自哪一层，`gitai config explain` 据此说明生效值的来源
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 覆盖配置项的环境变量前缀：`GITAI__AI__MODEL` 对应 `ai.model`
+pub const ENV_PREFIX: &str = "GITAI__";
+
+/// explain 输出中隐藏取值的配置项（按最后一段匹配）
+const SECRET_KEYS: &[&str] = &["api_key", "token"];
+
+/// 配置项的来源，按优先级由低到高排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    Default,
+    UserFile,
+    Env,
+    Flag,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Default => "default",
+            Source::UserFile => "user-file",
+            Source::Env => "env",
+            Source::Flag => "flag",
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 一层配置：来源、具体位置（文件路径、环境变量名或命令行参数）与其中设置的值
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub source: Source,
+    pub origin: String,
+    values: toml::Table,
+}
+
+impl Layer {
+    /// 解析一份配置文件的内容
+    pub fn from_content(source: Source, origin: impl Into<String>, content: &str) -> Result<Self> {
+        let origin = origin.into();
+        let values = content
+            .parse::<toml::Table>()
+            .map_err(|e| format!("{origin} 解析失败: {e}"))?;
+        Ok(Self {
+            source,
+            origin,
+            values,
+        })
+    }
+
+    /// 设置单个配置项（点分键）的一层，用于环境变量与命令行参数
+    pub fn value(
+        source: Source,
+        origin: impl Into<String>,
+        key: &str,
+        value: impl Into<toml::Value>,
+    ) -> Self {
+        let mut value = value.into();
+        for part in key.rsplit('.') {
+            let mut table = toml::Table::new();
+            table.insert(part.to_string(), value);
+            value = toml::Value::Table(table);
+        }
+        let values = match value {
+            toml::Value::Table(table) => table,
+            _ => unreachable!("rsplit 至少产生一段"),
+        };
+        Self {
+            source,
+            origin: origin.into(),
+            values,
+        }
+    }
+}
+
+/// 把 `GITAI__<段>__<键>` 形式的环境变量转换为配置层；未知的配置项被忽略并给出警告
+///
+/// 取值按 TOML 字面量解析（`0.5`、`true`、`["a", "b"]`），不是合法字面量或对应的默认值是字符串时按字符串处理
+pub fn env_layers(vars: impl IntoIterator<Item = (String, String)>) -> Vec<Layer> {
+    let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect();
+    vars.sort();
+
+    let mut layers = Vec::new();
+    for (name, raw) in vars {
+        let key = name[ENV_PREFIX.len()..]
+            .split("__")
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(".");
+        let Some(default) = lookup(&defaults, &key) else {
+            log::warn!("忽略环境变量 {name}: 未知的配置项 {key}");
+            continue;
+        };
+        let literal = toml::Value::deserialize(toml::de::ValueDeserializer::new(&raw));
+        let value = match literal {
+            Ok(value) if !value.is_str() && !default.is_string() => value,
+            _ => toml::Value::String(raw),
+        };
+        layers.push(Layer::value(Source::Env, name, &key, value));
+    }
+    layers
+}
+
+/// 合并后的配置及每个配置项的来源
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    /// 点分键 → (来源, 具体位置)，只包含各层显式设置的配置项
+    sources: BTreeMap<String, (Source, String)>,
+}
+
+/// explain 输出的一项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainEntry {
+    pub key: String,
+    pub value: JsonValue,
+    pub source: Source,
+    /// 文件路径、环境变量名或命令行参数；来自默认值时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+/// 按优先级由低到高合并各层并校验结果
+///
+/// 没有用户配置文件时以内置默认配置为底；各层中未设置的配置项取默认值
+pub fn resolve(layers: &[Layer]) -> Result<LayeredConfig> {
+    let mut merged = if layers.iter().any(|l| l.source == Source::UserFile) {
+        toml::Table::new()
+    } else {
+        toml::Table::try_from(Config::default())?
+    };
+    let mut sources = BTreeMap::new();
+
+    let mut ordered: Vec<&Layer> = layers.iter().collect();
+    ordered.sort_by_key(|layer| layer.source);
+    for layer in ordered {
+        merge_into(&mut merged, &layer.values, "", layer, &mut sources);
+    }
+
+    let config = Config::deserialize(toml::Value::Table(merged))
+        .map_err(|e| format!("合并后的配置无效: {e}"))?;
+    config.validate()?;
+    Ok(LayeredConfig { config, sources })
+}
+
+fn merge_into(
+    target: &mut toml::Table,
+    values: &toml::Table,
+    prefix: &str,
+    layer: &Layer,
+    sources: &mut BTreeMap<String, (Source, String)>,
+) {
+    for (name, value) in values {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        if let (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) =
+            (target.get_mut(name), value)
+        {
+            merge_into(existing, nested, &key, layer, sources);
+            continue;
+        }
+        // 整体替换：原先记录在该键之下的来源不再有效
+        let nested_prefix = format!("{key}.");
+        sources.retain(|k, _| k != &key && !k.starts_with(&nested_prefix));
+        record_leaves(value, &key, layer, sources);
+        target.insert(name.clone(), value.clone());
+    }
+}
+
+fn record_leaves(
+    value: &toml::Value,
+    key: &str,
+    layer: &Layer,
+    sources: &mut BTreeMap<String, (Source, String)>,
+) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (name, value) in table {
+                record_leaves(value, &format!("{key}.{name}"), layer, sources);
+            }
+        }
+        _ => {
+            sources.insert(key.to_string(), (layer.source, layer.origin.clone()));
+        }
+    }
+}
+
+impl LayeredConfig {
+    /// 列出每个生效配置项的值与来源；key 为配置项或配置段时只列出其下的配置项
+    pub fn explain(&self, key: Option<&str>) -> Result<Vec<ExplainEntry>> {
+        let mut leaves = Vec::new();
+        flatten(&serde_json::to_value(&self.config)?, "", &mut leaves);
+        if let Some(key) = key {
+            let nested_prefix = format!("{key}.");
+            leaves.retain(|(k, _)| k == key || k.starts_with(&nested_prefix));
+            if leaves.is_empty() {
+                return Err(format!("未知的配置项: {key}").into());
+            }
+        }
+
+        Ok(leaves
+            .into_iter()
+            .map(|(key, value)| {
+                let (source, origin) = match self.source_of(&key) {
+                    Some((source, origin)) => (*source, Some(origin.clone())),
+                    None => (Source::Default, None),
+                };
+                let secret = key
+                    .rsplit('.')
+                    .next()
+                    .is_some_and(|last| SECRET_KEYS.contains(&last));
+                let value = match value {
+                    JsonValue::String(s) if secret && !s.is_empty() => {
+                        JsonValue::String("***".to_string())
+                    }
+                    value => value,
+                };
+                ExplainEntry {
+                    key,
+                    value,
+                    source,
+                    origin,
+                }
+            })
+            .collect())
+    }
+
+    /// 配置项自身或最近的上级（如整体设置的内联表）的来源
+    fn source_of(&self, key: &str) -> Option<&(Source, String)> {
+        let mut key = key;
+        loop {
+            if let Some(found) = self.sources.get(key) {
+                return Some(found);
+            }
+            key = &key[..key.rfind('.')?];
+        }
+    }
+}
+
+/// 展开为 (点分键, 值)；数组与空对象视为单个值
+fn flatten(value: &JsonValue, prefix: &str, out: &mut Vec<(String, JsonValue)>) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (name, value) in map {
+                let key = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{prefix}.{name}")
+                };
+                flatten(value, &key, out);
+            }
+        }
+        _ => out.push((prefix.to_string(), value.clone())),
+    }
+}
+
+fn lookup<'a>(value: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    key.split('.')
+        .try_fold(value, |value, part| value.as_object()?.get(part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r#"
+[ai]
+api_url = "http://localhost:11434/v1/chat/completions"
+model = "qwen2.5:32b"
+api_key = "sk-user"
+temperature = 0.3
+
+[scan]
+timeout = 300
+jobs = 4
+"#;
+
+    fn entry<'a>(entries: &'a [ExplainEntry], key: &str) -> &'a ExplainEntry {
+        entries.iter().find(|e| e.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_layers_override_in_priority_order() {
+        let layers = vec![
+            Layer::value(Source::Flag, "--max-depth", "analysis.max_depth", 3),
+            Layer::from_content(Source::UserFile, "user.toml", USER).unwrap(),
+            Layer::value(Source::Env, "GITAI__SCAN__TIMEOUT", "scan.timeout", 600),
+            Layer::value(
+                Source::Env,
+                "GITAI__ANALYSIS__MAX_DEPTH",
+                "analysis.max_depth",
+                8,
+            ),
+        ];
+        let layered = resolve(&layers).unwrap();
+        assert_eq!(layered.config.scan.timeout, 600);
+        assert_eq!(layered.config.analysis.max_depth, Some(3));
+
+        let entries = layered.explain(None).unwrap();
+        let timeout = entry(&entries, "scan.timeout");
+        assert_eq!(timeout.source, Source::Env);
+        assert_eq!(timeout.origin.as_deref(), Some("GITAI__SCAN__TIMEOUT"));
+        assert_eq!(entry(&entries, "scan.jobs").source, Source::UserFile);
+        assert_eq!(entry(&entries, "analysis.max_depth").source, Source::Flag);
+        assert_eq!(
+            entry(&entries, "analysis.lints.max_parameters").source,
+            Source::Default
+        );
+        assert_eq!(entry(&entries, "ai.api_key").value, "***");
+    }
+
+    #[test]
+    fn test_env_layers() {
+        let vars = [
+            ("GITAI__AI__MODEL", "123"),
+            ("GITAI__SCAN__TIMEOUT", "42"),
+            ("GITAI__AI__MODLE", "typo"),
+            ("GITAI_CONFIG_DIR", "/tmp"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let mut layers = env_layers(vars);
+        assert_eq!(layers.len(), 2);
+        layers.push(Layer::from_content(Source::UserFile, "user.toml", USER).unwrap());
+
+        let layered = resolve(&layers).unwrap();
+        // 字符串配置项中的数字按字符串处理
+        assert_eq!(layered.config.ai.model, "123");
+        assert_eq!(layered.config.scan.timeout, 42);
+        let entries = layered.explain(Some("scan")).unwrap();
+        assert!(entries.iter().all(|e| e.key.starts_with("scan.")));
+        let timeout = entry(&entries, "scan.timeout");
+        assert_eq!(timeout.source, Source::Env);
+        assert_eq!(timeout.origin.as_deref(), Some("GITAI__SCAN__TIMEOUT"));
+    }
+
+    #[test]
+    fn test_resolve_without_user_file_and_invalid_values() {
+        let layered = resolve(&[]).unwrap();
+        let entries = layered.explain(Some("ai.model")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, Source::Default);
+        assert!(layered.explain(Some("ai.modle")).is_err());
+
+        let invalid = Layer::value(Source::Env, "GITAI__SCAN__TIMEOUT", "scan.timeout", "x");
+        assert!(resolve(&[invalid]).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+// 配置文件版本迁移
+// 配置文件顶层的 `version` 记录其结构版本；`Config::load` 与 `gitai init` 都通过这里把旧版本逐级升级到
+// CURRENT_VERSION，写回前备份原文件，并报告迁移了哪些配置项。修改通过 toml_edit 进行，保留注释
+
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Key};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 当前的配置结构版本
+pub const CURRENT_VERSION: u32 = 2;
+
+/// 一次迁移的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// 迁移前的版本
+    pub from: u32,
+    /// 迁移后的版本
+    pub to: u32,
+    /// 迁移后的文件内容
+    pub content: String,
+    /// 每一项改动的说明
+    pub changes: Vec<String>,
+}
+
+/// 单步迁移：把 version 升级到下一个版本，返回改动说明
+type Step = fn(&mut DocumentMut) -> Vec<String>;
+
+/// 第 i 项把版本 i + 1 升级到 i + 2
+const STEPS: &[Step] = &[migrate_v1_to_v2];
+
+/// 读取文件中的配置版本：未设置或旧模板中的 `"1.0.0"` 视为 1
+pub fn config_version(doc: &DocumentMut) -> Result<u32> {
+    match doc.get("version") {
+        None => Ok(1),
+        Some(item) => {
+            if let Some(version) = item.as_integer() {
+                return u32::try_from(version)
+                    .map_err(|_| format!("无效的配置版本: {version}").into());
+            }
+            match item.as_str() {
+                Some(version) if version.starts_with("1.") || version == "1" => Ok(1),
+                _ => Err(format!("无效的配置版本: {}", item.to_string().trim()).into()),
+            }
+        }
+    }
+}
+
+/// 把配置内容升级到当前版本；已是当前版本（或更新的版本）时返回 None
+pub fn migrate(content: &str) -> Result<Option<Migration>> {
+    let mut doc: DocumentMut = content.parse()?;
+    let from = config_version(&doc)?;
+    if from >= CURRENT_VERSION {
+        if from > CURRENT_VERSION {
+            log::warn!(
+                "配置文件版本 {from} 比当前 GitAI 支持的版本 {CURRENT_VERSION} 新，按原样加载"
+            );
+        }
+        return Ok(None);
+    }
+
+    let mut changes = Vec::new();
+    for step in &STEPS[(from.max(1) as usize - 1)..] {
+        changes.extend(step(&mut doc));
+    }
+    // 原地替换旧的版本号，保留其前后的注释
+    let mut version = toml_edit::Value::from(i64::from(CURRENT_VERSION));
+    match doc.get_mut("version") {
+        Some(item) => {
+            if let Some(old) = item.as_value() {
+                *version.decor_mut() = old.decor().clone();
+            }
+            *item = Item::Value(version);
+        }
+        None => {
+            doc.insert("version", Item::Value(version));
+        }
+    }
+    Ok(Some(Migration {
+        from,
+        to: CURRENT_VERSION,
+        content: doc.to_string(),
+        changes,
+    }))
+}
+
+/// 就地迁移配置文件：需要迁移时备份并写回，返回迁移结果与备份路径；已是当前版本时返回 None
+pub fn migrate_file(path: &Path) -> Result<Option<(Migration, PathBuf)>> {
+    let original = std::fs::read_to_string(path)?;
+    match migrate(&original)? {
+        Some(migration) => {
+            let backup = write_migration(path, &original, &migration)?;
+            Ok(Some((migration, backup)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 写回迁移结果：原内容备份为 `config.toml.v<旧版本>.backup`（已存在时依次使用 `.backup.1`、`.backup.2`……，
+/// 不覆盖已有备份）后原子地写入新内容，返回备份路径
+pub fn write_migration(path: &Path, original: &str, migration: &Migration) -> Result<PathBuf> {
+    let backup = write_backup(path, migration.from, original)?;
+    crate::utils::atomic_write::write_atomic(path, &migration.content)?;
+    Ok(backup)
+}
+
+/// 以独占创建的方式写入备份，名称已被占用时换用下一个序号
+fn write_backup(path: &Path, version: u32, original: &str) -> Result<PathBuf> {
+    use std::io::Write;
+
+    for n in 0.. {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".v{version}.backup"));
+        if n > 0 {
+            name.push(format!(".{n}"));
+        }
+        let backup = path.with_file_name(name);
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&backup)
+        {
+            Ok(mut file) => {
+                file.write_all(original.as_bytes())?;
+                file.sync_all()?;
+                return Ok(backup);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    unreachable!("备份序号耗尽")
+}
+
+/// v1 → v2
+/// - `scan.rules_path` 更名为 `scan.rules_dir`（旧名称会被静默忽略）
+/// - 删除空的 `ai.api_key`（空值会发送空的 Bearer 令牌）
+/// - `network.proxy` 只作用于资源下载，复制到所有出站请求共用的 `http.proxy`
+fn migrate_v1_to_v2(doc: &mut DocumentMut) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if let Some(scan) = doc.get_mut("scan").and_then(Item::as_table_mut) {
+        if let Some((key, rules_path)) = scan.remove_entry("rules_path") {
+            if scan.contains_key("rules_dir") {
+                changes.push("删除 scan.rules_path（已设置 scan.rules_dir）".to_string());
+            } else {
+                // 沿用旧键前的注释
+                let renamed = Key::new("rules_dir").with_leaf_decor(key.leaf_decor().clone());
+                scan.insert_formatted(&renamed, rules_path);
+                changes.push("scan.rules_path 更名为 scan.rules_dir".to_string());
+            }
+        }
+    }
+
+    if let Some(ai) = doc.get_mut("ai").and_then(Item::as_table_like_mut) {
+        let empty_key = ai
+            .get("api_key")
+            .and_then(Item::as_str)
+            .is_some_and(|key| key.trim().is_empty());
+        if empty_key {
+            ai.remove("api_key");
+            changes.push("删除空的 ai.api_key".to_string());
+        }
+    }
+
+    let proxy = doc
+        .get("network")
+        .and_then(|network| network.get("proxy"))
+        .and_then(Item::as_str)
+        .map(str::trim)
+        .filter(|proxy| !proxy.is_empty())
+        .map(str::to_string);
+    if let Some(proxy) = proxy {
+        let http = doc
+            .entry("http")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut();
+        if let Some(http) = http.filter(|http| !http.contains_key("proxy")) {
+            http.insert("proxy", toml_edit::value(proxy));
+            changes.push("network.proxy 复制到 http.proxy".to_string());
+        }
+    }
+
+    changes
+}
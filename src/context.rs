@@ -4,7 +4,9 @@
 use crate::architectural_impact::ArchitecturalImpactAnalysis;
 use crate::config::Config;
 use crate::tree_sitter::StructuralSummary;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 // Re-export Issue from devops when available, or define a stub
 #[cfg(feature = "devops")]
@@ -29,6 +31,9 @@ pub struct Issue {
 /// 大量参数的混乱。遵循"Fix the data structures, not the symptoms"的原则。
 #[derive(Debug, Clone)]
 pub struct OperationContext {
+    /// 请求 ID，用于关联一次调用在扫描/分析/AI 各阶段的日志
+    pub request_id: String,
+
     /// 应用配置
     pub config: Config,
 
@@ -78,8 +83,11 @@ pub struct OperationOptions {
 
 impl OperationContext {
     /// 创建新的操作上下文
+    ///
+    /// 请求 ID 沿用当前作用域的 ID（见 [`current_request_id`]），没有时生成新的
     pub fn new(config: Config) -> Self {
         Self {
+            request_id: current_request_id().unwrap_or_else(new_request_id),
             config,
             diff: String::new(),
             issues: Vec::new(),
@@ -89,6 +97,17 @@ impl OperationContext {
         }
     }
 
+    /// 指定请求 ID
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = request_id.into();
+        self
+    }
+
+    /// 在本上下文的请求 ID 作用域内执行 future，其中的日志都会带上该 ID
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        with_request_id(self.request_id.clone(), fut).await
+    }
+
     /// 设置代码变更
     pub fn with_diff(mut self, diff: String) -> Self {
         self.diff = diff;
@@ -221,8 +240,41 @@ impl OperationOptions {
     }
 }
 
-/// 解析issue ID字符串为列表
-fn parse_issue_ids(issue_id: Option<String>) -> Vec<String> {
+static PROCESS_REQUEST_ID: OnceLock<String> = OnceLock::new();
+
+tokio::task_local! {
+    static TASK_REQUEST_ID: String;
+}
+
+/// 生成新的请求 ID（12 位十六进制）
+pub fn new_request_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(12);
+    id
+}
+
+/// 设置进程级请求 ID（命令行每次调用一个），只有第一次设置生效
+pub fn set_process_request_id(request_id: impl Into<String>) -> &'static str {
+    PROCESS_REQUEST_ID.get_or_init(|| request_id.into())
+}
+
+/// 当前请求 ID：优先取 [`with_request_id`] 作用域内的 ID，其次是进程级 ID
+///
+/// `spawn_blocking` 等脱离当前任务的代码只能看到进程级 ID
+pub fn current_request_id() -> Option<String> {
+    TASK_REQUEST_ID
+        .try_with(Clone::clone)
+        .ok()
+        .or_else(|| PROCESS_REQUEST_ID.get().cloned())
+}
+
+/// 在给定请求 ID 的作用域内执行 future（如单次 MCP 工具调用）
+pub async fn with_request_id<F: Future>(request_id: String, fut: F) -> F::Output {
+    TASK_REQUEST_ID.scope(request_id, fut).await
+}
+
+/// 解析issue ID字符串为列表（逗号分隔，补全 # 前缀并去重）
+pub fn parse_issue_ids(issue_id: Option<String>) -> Vec<String> {
     use std::collections::HashSet;
     let mut seen = HashSet::new();
     let mut out = Vec::new();
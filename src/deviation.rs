@@ -0,0 +1,449 @@
+//! 需求偏离度分析
+//!
+//! 对比代码变更与 DevOps Issue 中的需求，得到需求覆盖情况与无关变更。`gitai deviation`
+//! 与 MCP 工具 `analyze_deviation` 输出同一个 [`DeviationReport`]；JSON 字段构成对外契约
+//! （机器人据此决定是否允许合并），字段的增删需提升 [`SCHEMA_VERSION`]。
+//!
+//! 分析是确定性的启发式：需求取自 Issue 描述中的列表项/复选框（没有时使用标题），
+//! 从需求中提取关键词（英文单词按驼峰/下划线拆分，中文按双字切分）后与 diff 中变更的
+//! 行和文件路径匹配；至少一半关键词出现在变更中即视为已覆盖，与任何需求都不相关的
+//! 文件记为无关变更。
+
+use crate::context::Issue;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 偏离度报告的结构版本
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// 得分低于该值时标记为需要关注
+pub const ATTENTION_THRESHOLD: u8 = 60;
+
+/// 每个需求最多列出的证据文件数
+const MAX_EVIDENCE: usize = 5;
+
+/// 需求描述中不具区分度的常见词
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "should", "must", "when", "will",
+    "can", "are", "not", "all", "add", "support", "make", "use", "new", "allow", "also", "via",
+    "each", "its", "has", "have", "been", "was", "were",
+];
+
+/// 偏离度报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviationReport {
+    /// 报告结构版本，见 [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// 分析的 Issue ID
+    pub issue_ids: Vec<String>,
+    /// 综合得分（0-100）：需求覆盖率占 80 分，相关文件占比占 20 分
+    pub score: u8,
+    /// 需求覆盖率（0.0-1.0）
+    pub requirement_coverage: f32,
+    /// 已被变更覆盖的需求
+    pub matched_requirements: Vec<RequirementMatch>,
+    /// 未被变更覆盖的需求
+    pub unmatched_requirements: Vec<RequirementMatch>,
+    /// 与所有需求都不相关的变更文件
+    pub unrelated_changes: Vec<String>,
+    /// 得分低于 [`ATTENTION_THRESHOLD`] 时为 true
+    pub needs_attention: bool,
+}
+
+/// 单条需求的匹配结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequirementMatch {
+    /// 需求所属的 Issue
+    pub issue_id: String,
+    /// 需求原文
+    pub requirement: String,
+    /// 与该需求相关的变更文件
+    pub evidence: Vec<String>,
+}
+
+impl DeviationReport {
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "🎯 需求偏离度: 得分 {}/100，需求覆盖率 {:.0}%（Issue: {}）\n",
+            self.score,
+            self.requirement_coverage * 100.0,
+            self.issue_ids.join(", ")
+        );
+        if !self.matched_requirements.is_empty() {
+            out.push_str(&format!(
+                "\n✅ 已覆盖的需求（{} 个）:\n",
+                self.matched_requirements.len()
+            ));
+            for item in &self.matched_requirements {
+                out.push_str(&format!(
+                    "  - [{}] {}（{}）\n",
+                    item.issue_id,
+                    item.requirement,
+                    item.evidence.join(", ")
+                ));
+            }
+        }
+        if !self.unmatched_requirements.is_empty() {
+            out.push_str(&format!(
+                "\n❌ 未覆盖的需求（{} 个）:\n",
+                self.unmatched_requirements.len()
+            ));
+            for item in &self.unmatched_requirements {
+                out.push_str(&format!("  - [{}] {}\n", item.issue_id, item.requirement));
+            }
+        }
+        if !self.unrelated_changes.is_empty() {
+            out.push_str(&format!(
+                "\n⚠️ 与需求无关的变更（{} 个文件）:\n",
+                self.unrelated_changes.len()
+            ));
+            for file in &self.unrelated_changes {
+                out.push_str(&format!("  - {file}\n"));
+            }
+        }
+        if self.needs_attention {
+            out.push_str("\n🔎 变更与需求偏离较大，建议人工确认\n");
+        }
+        out
+    }
+
+    /// 得分低于门槛时返回违规说明
+    pub fn violation(&self, fail_below: Option<u8>) -> Option<String> {
+        let threshold = fail_below?;
+        (self.score < threshold).then(|| {
+            format!(
+                "需求偏离度得分 {} 低于门槛 {threshold}（未覆盖需求 {} 个，无关变更 {} 个）",
+                self.score,
+                self.unmatched_requirements.len(),
+                self.unrelated_changes.len()
+            )
+        })
+    }
+}
+
+/// 对比 Issue 需求与 diff，生成偏离度报告
+pub fn analyze(issue_ids: &[String], issues: &[Issue], diff: &str) -> DeviationReport {
+    let files = changed_files(diff);
+    let changed: BTreeSet<&String> = files.values().flatten().collect();
+
+    let mut relevant = BTreeSet::new();
+    let mut matched_requirements = Vec::new();
+    let mut unmatched_requirements = Vec::new();
+    for issue in issues {
+        relevant.extend(keywords(&issue.title));
+        for requirement in requirements(issue) {
+            let words = keywords(&requirement);
+            if words.is_empty() {
+                continue;
+            }
+            let hits = words.iter().filter(|w| changed.contains(w)).count();
+            let evidence: Vec<String> = files
+                .iter()
+                .filter(|(_, tokens)| !tokens.is_disjoint(&words))
+                .map(|(path, _)| path.clone())
+                .take(MAX_EVIDENCE)
+                .collect();
+            relevant.extend(words.iter().cloned());
+            let item = RequirementMatch {
+                issue_id: issue.id.clone(),
+                requirement,
+                evidence,
+            };
+            if hits * 2 >= words.len() {
+                matched_requirements.push(item);
+            } else {
+                unmatched_requirements.push(item);
+            }
+        }
+    }
+
+    let unrelated_changes: Vec<String> = files
+        .iter()
+        .filter(|(_, tokens)| tokens.is_disjoint(&relevant))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let total = matched_requirements.len() + unmatched_requirements.len();
+    let requirement_coverage = if total == 0 {
+        0.0
+    } else {
+        matched_requirements.len() as f32 / total as f32
+    };
+    let related_ratio = if files.is_empty() {
+        1.0
+    } else {
+        (files.len() - unrelated_changes.len()) as f32 / files.len() as f32
+    };
+    let score = (requirement_coverage * 80.0 + related_ratio * 20.0).round() as u8;
+
+    DeviationReport {
+        schema_version: SCHEMA_VERSION,
+        issue_ids: issue_ids.to_vec(),
+        score,
+        requirement_coverage,
+        matched_requirements,
+        unmatched_requirements,
+        unrelated_changes,
+        needs_attention: score < ATTENTION_THRESHOLD,
+    }
+}
+
+/// 读取待分析的变更：显式选择时只读取该部分，否则为全部变更，没有时回退到最后一次提交
+pub fn read_diff(selection: crate::review::DiffSelection) -> Result<String, DynError> {
+    if let Some(diff) = selection.read_diff()? {
+        return Ok(diff);
+    }
+    match crate::git::get_all_diff() {
+        Ok(diff) if !diff.trim().is_empty() => Ok(diff),
+        _ => crate::git::get_last_commit_diff(),
+    }
+}
+
+/// 从 DevOps 平台拉取 Issue 并分析
+#[cfg(feature = "devops")]
+pub async fn analyze_issues(
+    config: &crate::config::Config,
+    issue_ids: &[String],
+    space_id: Option<u64>,
+    diff: &str,
+) -> Result<DeviationReport, DynError> {
+    if issue_ids.is_empty() {
+        return Err("缺少 Issue ID".into());
+    }
+    let devops_cfg = config
+        .devops
+        .clone()
+        .ok_or("DevOps 未配置，请在配置文件中添加 [devops] 部分")?;
+    let space_id = space_id.or(devops_cfg.space_id);
+    let client = crate::devops::DevOpsClient::new(devops_cfg);
+    let issues = client.get_issues_with_space(issue_ids, space_id).await?;
+    Ok(analyze(issue_ids, &issues, diff))
+}
+
+/// 从 Issue 中提取需求：描述中的列表项与复选框，没有时使用标题
+fn requirements(issue: &Issue) -> Vec<String> {
+    let items: Vec<String> = issue
+        .description
+        .lines()
+        .filter_map(list_item)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect();
+    if items.is_empty() && !issue.title.trim().is_empty() {
+        vec![issue.title.trim().to_string()]
+    } else {
+        items
+    }
+}
+
+/// 列表项（`- `、`* `、`+ `、`1. `，可带 `[ ]`/`[x]` 复选框）的内容
+fn list_item(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let rest = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .or_else(|| {
+            let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits > 0 {
+                line[digits..].strip_prefix(". ")
+            } else {
+                None
+            }
+        })?;
+    let rest = rest.trim();
+    Some(
+        ["[ ]", "[x]", "[X]"]
+            .iter()
+            .find_map(|box_| rest.strip_prefix(box_))
+            .unwrap_or(rest)
+            .trim(),
+    )
+}
+
+/// 按文件收集 diff 中变更的行（新增与删除）与路径的关键词
+fn changed_files(diff: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let mut files: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            // 删除的文件没有 `+++ b/...`，先取 diff 头中的新路径
+            current = rest.rsplit(" b/").next().map(str::to_string);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(path) = path.strip_prefix("b/") {
+                current = Some(path.to_string());
+            }
+        } else if line.starts_with("--- ") {
+            continue;
+        } else if let Some(path) = &current {
+            let entry = files.entry(path.clone()).or_insert_with(|| keywords(path));
+            if let Some(content) = line.strip_prefix('+').or_else(|| line.strip_prefix('-')) {
+                entry.extend(keywords(content));
+            }
+        }
+    }
+    files
+}
+
+/// 提取关键词：英文/数字按驼峰与下划线拆分为小写单词（至少 3 个字符，去除常见词），中文按双字切分
+fn keywords(text: &str) -> BTreeSet<String> {
+    let mut words = BTreeSet::new();
+    let mut ascii = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() {
+            // 小写后接大写处为驼峰边界
+            if c.is_ascii_uppercase() && ascii.ends_with(|p: char| p.is_ascii_lowercase()) {
+                push_word(&mut words, &ascii);
+                ascii.clear();
+            }
+            ascii.push(c);
+        } else {
+            push_word(&mut words, &ascii);
+            ascii.clear();
+        }
+        if is_cjk(c) {
+            cjk.push(c);
+        } else {
+            words.extend(cjk.windows(2).map(|pair| pair.iter().collect::<String>()));
+            cjk.clear();
+        }
+    }
+    words
+}
+
+fn push_word(words: &mut BTreeSet<String>, word: &str) {
+    let word = word.to_ascii_lowercase();
+    if word.len() >= 3 && !STOPWORDS.contains(&word.as_str()) {
+        words.insert(word);
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    ('\u{4e00}'..='\u{9fff}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, title: &str, description: &str) -> Issue {
+        Issue {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+            status: "open".to_string(),
+            priority: None,
+            url: String::new(),
+            ai_context: None,
+            #[cfg(feature = "devops")]
+            assignee: None,
+            #[cfg(feature = "devops")]
+            created_at: String::new(),
+            #[cfg(feature = "devops")]
+            updated_at: String::new(),
+            #[cfg(feature = "devops")]
+            labels: Vec::new(),
+        }
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/retry.rs b/src/retry.rs
+--- a/src/retry.rs
++++ b/src/retry.rs
+@@ -1,2 +1,4 @@
++pub fn retry_with_backoff(max_attempts: u32) {}
+diff --git a/docs/changelog.md b/docs/changelog.md
+--- a/docs/changelog.md
++++ b/docs/changelog.md
+@@ -1 +1,2 @@
++Bumped release notes
+";
+
+    #[test]
+    fn test_analyze_matches_requirements_and_unrelated_changes() {
+        let issues = vec![issue(
+            "#7",
+            "HTTP retry",
+            "Requests fail on flaky networks.\n- [ ] Retry with exponential backoff\n- [x] Limit max attempts\n- Report metrics to Prometheus",
+        )];
+        let report = analyze(&["#7".to_string()], &issues, DIFF);
+
+        let matched: Vec<&str> = report
+            .matched_requirements
+            .iter()
+            .map(|m| m.requirement.as_str())
+            .collect();
+        assert_eq!(
+            matched,
+            vec!["Retry with exponential backoff", "Limit max attempts"]
+        );
+        assert_eq!(
+            report.matched_requirements[0].evidence,
+            vec!["src/retry.rs"]
+        );
+        assert_eq!(
+            report.unmatched_requirements[0].requirement,
+            "Report metrics to Prometheus"
+        );
+        assert_eq!(report.unrelated_changes, vec!["docs/changelog.md"]);
+        // 覆盖 2/3 需求，1/2 文件相关
+        assert_eq!(report.score, 63);
+        assert!(!report.needs_attention);
+        assert!(report.violation(Some(70)).is_some());
+        assert!(report.violation(Some(60)).is_none());
+        assert!(report.violation(None).is_none());
+    }
+
+    #[test]
+    fn test_requirements_fall_back_to_title() {
+        let issues = vec![issue("#8", "修复登录超时", "用户反馈登录很慢")];
+        let diff =
+            "diff --git a/src/auth.rs b/src/auth.rs\n+++ b/src/auth.rs\n+// 登录超时改为 30 秒\n";
+        let report = analyze(&["#8".to_string()], &issues, diff);
+        assert_eq!(report.matched_requirements.len(), 1);
+        assert_eq!(report.matched_requirements[0].requirement, "修复登录超时");
+        assert_eq!(report.score, 100);
+    }
+
+    #[test]
+    fn test_report_json_shape_is_stable() {
+        let issues = vec![issue(
+            "#7",
+            "HTTP retry",
+            "- Retry with backoff\n- Add metrics",
+        )];
+        let report = analyze(&["#7".to_string()], &issues, DIFF);
+        let value = serde_json::to_value(&report).unwrap();
+        // 字段构成对外契约，修改时需提升 SCHEMA_VERSION 并同步更新此处
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "schema_version": 1,
+                "issue_ids": ["#7"],
+                "score": 50,
+                "requirement_coverage": 0.5,
+                "matched_requirements": [
+                    {
+                        "issue_id": "#7",
+                        "requirement": "Retry with backoff",
+                        "evidence": ["src/retry.rs"]
+                    }
+                ],
+                "unmatched_requirements": [
+                    {
+                        "issue_id": "#7",
+                        "requirement": "Add metrics",
+                        "evidence": []
+                    }
+                ],
+                "unrelated_changes": ["docs/changelog.md"],
+                "needs_attention": true
+            })
+        );
+        let parsed: DeviationReport = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
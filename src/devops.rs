@@ -19,6 +19,90 @@ pub struct Issue {
     pub ai_context: Option<String>,
 }
 
+/// 远程 PR/MR 引用（由 URL 解析得到）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequestRef {
+    /// 平台类型 (github, gitlab)
+    pub platform: String,
+    /// 主机名（例如 github.com 或自建 GitLab 域名）
+    pub host: String,
+    /// 项目路径：GitHub 为 owner/repo，GitLab 可包含多级 group
+    pub project: String,
+    /// PR 编号（GitLab 为 MR iid）
+    pub number: u64,
+}
+
+impl PullRequestRef {
+    /// 从 PR/MR 链接解析
+    ///
+    /// 支持：
+    /// - `https://github.com/<owner>/<repo>/pull/<n>`
+    /// - `https://gitlab.com/<group>/<project>/-/merge_requests/<n>`
+    pub fn parse(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let trimmed = url.trim();
+        let without_scheme = trimmed
+            .strip_prefix("https://")
+            .or_else(|| trimmed.strip_prefix("http://"))
+            .ok_or_else(|| format!("无效的 PR 链接（需要 http/https）: {url}"))?;
+        let without_query = without_scheme
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+
+        let mut parts = without_query.splitn(2, '/');
+        let host = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default();
+        if host.is_empty() || path.is_empty() {
+            return Err(format!("无效的 PR 链接: {url}").into());
+        }
+
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let parse_number = |s: &str| {
+            s.parse::<u64>()
+                .map_err(|_| format!("无法解析 PR 编号 '{s}': {url}"))
+        };
+
+        // GitLab: <project path>/-/merge_requests/<iid>
+        if let Some(pos) = segments.iter().position(|s| *s == "merge_requests") {
+            if pos + 1 < segments.len() && pos >= 2 && segments[pos - 1] == "-" {
+                return Ok(Self {
+                    platform: "gitlab".to_string(),
+                    host,
+                    project: segments[..pos - 1].join("/"),
+                    number: parse_number(segments[pos + 1])?,
+                });
+            }
+        }
+
+        // GitHub: <owner>/<repo>/pull/<n>
+        if segments.len() >= 4 && segments[2] == "pull" {
+            return Ok(Self {
+                platform: "github".to_string(),
+                host,
+                project: format!("{}/{}", segments[0], segments[1]),
+                number: parse_number(segments[3])?,
+            });
+        }
+
+        Err(format!("无法识别的 PR 链接（支持 GitHub/GitLab）: {url}").into())
+    }
+
+    /// 平台 API 根地址
+    fn api_base(&self) -> String {
+        match (self.platform.as_str(), self.host.as_str()) {
+            ("github", "github.com") => "https://api.github.com".to_string(),
+            ("github", host) => format!("https://{host}/api/v3"),
+            (_, host) => format!("https://{host}/api/v4"),
+        }
+    }
+
+    /// GitLab API 使用 URL 编码后的项目路径作为 ID
+    fn encoded_project(&self) -> String {
+        self.project.replace('/', "%2F")
+    }
+}
+
 /// DevOps客户端
 pub struct DevOpsClient {
     config: DevOpsConfig,
@@ -27,10 +111,16 @@ pub struct DevOpsClient {
 
 impl DevOpsClient {
     pub fn new(config: DevOpsConfig) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout))
-            .build()
-            .unwrap_or_default();
+        let client = crate::http::client_builder()
+            .and_then(|builder| {
+                Ok(builder
+                    .timeout(std::time::Duration::from_secs(config.timeout))
+                    .build()?)
+            })
+            .unwrap_or_else(|e| {
+                log::warn!("HTTP 客户端配置无效，使用默认客户端: {e}");
+                reqwest::Client::default()
+            });
 
         Self { config, client }
     }
@@ -278,4 +368,303 @@ impl DevOpsClient {
             ai_context: None,
         })
     }
+
+    /// 获取远程 PR/MR 的统一 diff（自动分页）
+    pub async fn get_pull_request_diff(
+        &self,
+        pr: &PullRequestRef,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        match pr.platform.as_str() {
+            "github" => self.get_github_pr_diff(pr).await,
+            "gitlab" => self.get_gitlab_mr_diff(pr).await,
+            other => Err(format!("Unsupported platform: {other}").into()),
+        }
+    }
+
+    /// 在远程 PR/MR 下发表评论
+    pub async fn post_pull_request_comment(
+        &self,
+        pr: &PullRequestRef,
+        body: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let payload = serde_json::json!({ "body": body });
+        let request = match pr.platform.as_str() {
+            "github" => self
+                .with_auth(
+                    pr,
+                    self.client.post(format!(
+                        "{}/repos/{}/issues/{}/comments",
+                        pr.api_base(),
+                        pr.project,
+                        pr.number
+                    )),
+                )
+                .json(&payload),
+            "gitlab" => self
+                .with_auth(
+                    pr,
+                    self.client.post(format!(
+                        "{}/projects/{}/merge_requests/{}/notes",
+                        pr.api_base(),
+                        pr.encoded_project(),
+                        pr.number
+                    )),
+                )
+                .json(&payload),
+            other => return Err(format!("Unsupported platform: {other}").into()),
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to post comment: {}", response.status()).into());
+        }
+        Ok(())
+    }
+
+    /// 按平台附加认证头；PR 所在主机与配置的 devops.base_url 不同时不发送令牌，以匿名方式访问
+    fn with_auth(
+        &self,
+        pr: &PullRequestRef,
+        builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        let builder = builder.header("User-Agent", "gitai");
+        if self.config.token.is_empty() {
+            return builder;
+        }
+        if !self.token_applies_to(pr) {
+            log::warn!(
+                "PR 所在主机 {} 与配置的 devops.base_url（{}）不一致，不发送令牌，以匿名方式访问",
+                pr.host,
+                self.config.base_url
+            );
+            return builder;
+        }
+        match pr.platform.as_str() {
+            "gitlab" => builder.header("PRIVATE-TOKEN", &self.config.token),
+            _ => builder
+                .header("Authorization", format!("token {}", self.config.token))
+                .header("Accept", "application/vnd.github.v3+json"),
+        }
+    }
+
+    /// 配置的令牌是否属于 PR 所在的主机：devops.base_url 的主机须为 PR 主机或其 API 主机
+    fn token_applies_to(&self, pr: &PullRequestRef) -> bool {
+        let host_of = |url: &str| {
+            reqwest::Url::parse(url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        };
+        match host_of(&self.config.base_url) {
+            Some(configured) => {
+                configured.eq_ignore_ascii_case(&pr.host)
+                    || host_of(&pr.api_base()).as_deref() == Some(configured.as_str())
+            }
+            None => false,
+        }
+    }
+
+    /// 获取 GitHub PR 的 diff（通过 files 接口分页拼接）
+    async fn get_github_pr_diff(
+        &self,
+        pr: &PullRequestRef,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        const PER_PAGE: usize = 100;
+        let mut diff = String::new();
+        let mut page = 1;
+
+        loop {
+            let url = format!(
+                "{}/repos/{}/pulls/{}/files?per_page={PER_PAGE}&page={page}",
+                pr.api_base(),
+                pr.project,
+                pr.number
+            );
+            let response = self.with_auth(pr, self.client.get(&url)).send().await?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to fetch pull request: {}", response.status()).into());
+            }
+
+            let files: Vec<serde_json::Value> = response.json().await?;
+            for file in &files {
+                let new_path = file["filename"].as_str().unwrap_or("");
+                let old_path = file["previous_filename"].as_str().unwrap_or(new_path);
+                let status = file["status"].as_str().unwrap_or("");
+                append_file_diff(
+                    &mut diff,
+                    old_path,
+                    new_path,
+                    status == "added",
+                    status == "removed",
+                    file["patch"].as_str(),
+                );
+            }
+
+            if files.len() < PER_PAGE {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(diff)
+    }
+
+    /// 获取 GitLab MR 的 diff（通过 X-Next-Page 分页）
+    async fn get_gitlab_mr_diff(
+        &self,
+        pr: &PullRequestRef,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut diff = String::new();
+        let mut page = "1".to_string();
+
+        loop {
+            let url = format!(
+                "{}/projects/{}/merge_requests/{}/diffs?per_page=100&page={page}",
+                pr.api_base(),
+                pr.encoded_project(),
+                pr.number
+            );
+            let response = self.with_auth(pr, self.client.get(&url)).send().await?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to fetch merge request: {}", response.status()).into());
+            }
+
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .trim()
+                .to_string();
+
+            let files: Vec<serde_json::Value> = response.json().await?;
+            for file in &files {
+                append_file_diff(
+                    &mut diff,
+                    file["old_path"].as_str().unwrap_or(""),
+                    file["new_path"].as_str().unwrap_or(""),
+                    file["new_file"].as_bool().unwrap_or(false),
+                    file["deleted_file"].as_bool().unwrap_or(false),
+                    file["diff"].as_str(),
+                );
+            }
+
+            if next_page.is_empty() || files.is_empty() {
+                break;
+            }
+            page = next_page;
+        }
+
+        Ok(diff)
+    }
+}
+
+/// 将单个文件的补丁拼接为统一 diff 格式
+fn append_file_diff(
+    out: &mut String,
+    old_path: &str,
+    new_path: &str,
+    is_new: bool,
+    is_deleted: bool,
+    patch: Option<&str>,
+) {
+    let old_label = if is_new {
+        "/dev/null".to_string()
+    } else {
+        format!("a/{old_path}")
+    };
+    let new_label = if is_deleted {
+        "/dev/null".to_string()
+    } else {
+        format!("b/{new_path}")
+    };
+
+    out.push_str(&format!("diff --git a/{old_path} b/{new_path}\n"));
+    out.push_str(&format!("--- {old_label}\n+++ {new_label}\n"));
+    match patch {
+        Some(p) if !p.is_empty() => {
+            out.push_str(p);
+            if !p.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        // 二进制文件或过大的补丁，平台不返回内容
+        _ => out.push_str("@@\n+ [补丁内容不可用（可能为二进制或过大的文件）]\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_pr_url() {
+        let pr = PullRequestRef::parse("https://github.com/nehcuh/gitai/pull/42/files").unwrap();
+        assert_eq!(pr.platform, "github");
+        assert_eq!(pr.host, "github.com");
+        assert_eq!(pr.project, "nehcuh/gitai");
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.api_base(), "https://api.github.com");
+    }
+
+    #[test]
+    fn test_parse_gitlab_mr_url() {
+        let pr = PullRequestRef::parse(
+            "https://gitlab.example.com/group/sub/project/-/merge_requests/7?tab=diffs",
+        )
+        .unwrap();
+        assert_eq!(pr.platform, "gitlab");
+        assert_eq!(pr.project, "group/sub/project");
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.encoded_project(), "group%2Fsub%2Fproject");
+        assert_eq!(pr.api_base(), "https://gitlab.example.com/api/v4");
+    }
+
+    #[test]
+    fn test_token_only_sent_to_configured_host() {
+        let client = |base_url: &str| {
+            DevOpsClient::new(DevOpsConfig {
+                platform: "github".to_string(),
+                base_url: base_url.to_string(),
+                token: "secret".to_string(),
+                project: None,
+                space_id: None,
+                timeout: 5,
+                retry_count: 0,
+            })
+        };
+        let github = PullRequestRef::parse("https://github.com/a/b/pull/1").unwrap();
+        let other = PullRequestRef::parse("https://evil.example/a/b/pull/1").unwrap();
+        assert!(client("https://api.github.com").token_applies_to(&github));
+        assert!(client("https://github.com").token_applies_to(&github));
+        assert!(!client("https://api.github.com").token_applies_to(&other));
+        assert!(!client("not a url").token_applies_to(&github));
+
+        let request = client("https://api.github.com")
+            .with_auth(&other, reqwest::Client::new().get("https://evil.example/x"))
+            .build()
+            .unwrap();
+        assert!(request.headers().get("Authorization").is_none());
+    }
+
+    #[test]
+    fn test_parse_invalid_pr_url() {
+        assert!(PullRequestRef::parse("github.com/a/b/pull/1").is_err());
+        assert!(PullRequestRef::parse("https://github.com/a/b/issues/1").is_err());
+        assert!(PullRequestRef::parse("https://github.com/a/b/pull/abc").is_err());
+    }
+
+    #[test]
+    fn test_append_file_diff_for_new_file() {
+        let mut out = String::new();
+        append_file_diff(
+            &mut out,
+            "a.rs",
+            "a.rs",
+            true,
+            false,
+            Some("@@ -0,0 +1 @@\n+fn a() {}"),
+        );
+        assert!(out.contains("--- /dev/null\n+++ b/a.rs"));
+        assert!(out.ends_with("+fn a() {}\n"));
+    }
 }
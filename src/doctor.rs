@@ -0,0 +1,356 @@
+// 自检模块
+// `gitai doctor` 汇总各子系统的检查：Git、OpenGrep、ast-grep、Tree-sitter 查询、规则缓存、
+// AI 服务连通性与配置，出现问题时首先运行它定位原因
+
+use crate::config::Config;
+use crate::domain::interfaces::{HealthCheckResult, HealthStatus};
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// AI 服务连通性检查的超时时间
+const AI_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 自检选项
+#[derive(Debug, Clone, Default)]
+pub struct DoctorOptions {
+    /// 离线模式：跳过 AI 服务连通性检查
+    pub offline: bool,
+}
+
+/// 单项检查
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    #[serde(flatten)]
+    pub result: HealthCheckResult,
+}
+
+/// 自检报告（`gitai doctor --format json` 的输出）
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub version: String,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// 是否存在致命问题（任一检查为 unhealthy）
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.result.status == HealthStatus::Unhealthy)
+    }
+
+    /// 各状态的检查数：(通过, 警告, 失败)
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |status| {
+            self.checks
+                .iter()
+                .filter(|check| check.result.status == status)
+                .count()
+        };
+        (
+            count(HealthStatus::Healthy),
+            count(HealthStatus::Degraded),
+            count(HealthStatus::Unhealthy),
+        )
+    }
+
+    /// 文本报告
+    pub fn to_text(&self) -> String {
+        let mut out = format!("🩺 GitAI 自检 v{}\n\n", self.version);
+        for check in &self.checks {
+            let icon = match check.result.status {
+                HealthStatus::Healthy => "✅",
+                HealthStatus::Degraded => "⚠️",
+                HealthStatus::Unhealthy => "❌",
+            };
+            out.push_str(&format!("{icon} {:<16}", check.name));
+            if let Some(message) = &check.result.message {
+                out.push_str(&format!(" {message}"));
+            }
+            out.push('\n');
+        }
+        let (pass, warn, fail) = self.counts();
+        out.push_str(&format!("\n通过 {pass}，警告 {warn}，失败 {fail}\n"));
+        out
+    }
+}
+
+/// 执行全部检查；配置加载失败时其余检查使用默认配置
+pub async fn run(options: &DoctorOptions) -> DoctorReport {
+    let (config_check, config) = match Config::load() {
+        Ok(config) => (
+            HealthCheckResult::healthy_with(
+                crate::utils::paths::default_config_file()
+                    .display()
+                    .to_string(),
+            ),
+            config,
+        ),
+        Err(e) => (
+            HealthCheckResult::unhealthy(format!("配置无效: {e}")),
+            Config::default(),
+        ),
+    };
+
+    let checks = vec![
+        DoctorCheck {
+            name: "git",
+            result: check_git(),
+        },
+        DoctorCheck {
+            name: "config",
+            result: config_check,
+        },
+        DoctorCheck {
+            name: "opengrep",
+            result: check_opengrep(),
+        },
+        DoctorCheck {
+            name: "ast-grep",
+            result: check_ast_grep(),
+        },
+        DoctorCheck {
+            name: "tree-sitter",
+            result: check_queries_dir(&crate::utils::paths::tree_sitter_queries_dir()),
+        },
+        DoctorCheck {
+            name: "rules",
+            result: check_rules_dir(&crate::utils::paths::resolve_rules_dir(
+                config.scan.rules_dir.as_deref(),
+            )),
+        },
+        DoctorCheck {
+            name: "ai",
+            result: check_ai(&config, options).await,
+        },
+    ];
+
+    DoctorReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        checks,
+    }
+}
+
+impl HealthCheckResult {
+    /// 通过并附带说明
+    fn healthy_with(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::healthy()
+        }
+    }
+}
+
+/// 执行 `<program> --version`，成功时返回首行输出
+fn tool_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    output.status.success().then(|| {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    })
+}
+
+fn check_git() -> HealthCheckResult {
+    match tool_version("git") {
+        Some(version) => HealthCheckResult::healthy_with(version),
+        None => HealthCheckResult::unhealthy("未找到 git，请安装 Git 并加入 PATH"),
+    }
+}
+
+#[cfg(feature = "security")]
+fn check_opengrep() -> HealthCheckResult {
+    if crate::scan::is_opengrep_installed() {
+        HealthCheckResult::healthy_with(
+            tool_version("opengrep").unwrap_or_else(|| "已安装".to_string()),
+        )
+    } else {
+        HealthCheckResult::degraded("未安装 OpenGrep，安全扫描不可用（gitai scan --auto-install）")
+    }
+}
+
+#[cfg(not(feature = "security"))]
+fn check_opengrep() -> HealthCheckResult {
+    HealthCheckResult::degraded("本构建未启用 security 功能")
+}
+
+/// ast-grep 为可选工具，可执行名为 `ast-grep` 或 `sg`
+fn check_ast_grep() -> HealthCheckResult {
+    match tool_version("ast-grep").or_else(|| tool_version("sg")) {
+        Some(version) => HealthCheckResult::healthy_with(version),
+        None => HealthCheckResult::degraded("未安装 ast-grep（可选）"),
+    }
+}
+
+/// Tree-sitter 查询目录是否存在且包含语言子目录
+fn check_queries_dir(dir: &Path) -> HealthCheckResult {
+    let languages = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().filter(|e| e.path().is_dir()).count())
+        .unwrap_or(0);
+    if languages > 0 {
+        HealthCheckResult::healthy_with(format!("{languages} 种语言的查询（{}）", dir.display()))
+    } else {
+        HealthCheckResult::degraded(format!(
+            "未找到查询文件（{}），首次分析时会自动下载",
+            dir.display()
+        ))
+    }
+}
+
+/// 规则目录是否存在、包含规则文件，且元数据可解析
+fn check_rules_dir(dir: &Path) -> HealthCheckResult {
+    if !dir.is_dir() {
+        return HealthCheckResult::degraded(format!(
+            "规则目录不存在（{}），请运行 gitai update",
+            dir.display()
+        ));
+    }
+    let meta_path = dir.join(".rules.meta");
+    if meta_path.exists() {
+        let meta = std::fs::read_to_string(&meta_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        return match meta {
+            Some(meta) => match meta["total_rules"].as_u64() {
+                Some(total) if total > 0 => {
+                    HealthCheckResult::healthy_with(format!("{total} 条规则（{}）", dir.display()))
+                }
+                _ => HealthCheckResult::degraded(format!(
+                    "规则元数据中没有规则（{}），请运行 gitai update",
+                    meta_path.display()
+                )),
+            },
+            None => HealthCheckResult::degraded(format!(
+                "规则元数据损坏（{}），请运行 gitai update",
+                meta_path.display()
+            )),
+        };
+    }
+    if contains_rule_file(dir) {
+        HealthCheckResult::healthy_with(dir.display().to_string())
+    } else {
+        HealthCheckResult::degraded(format!(
+            "规则目录为空（{}），请运行 gitai update",
+            dir.display()
+        ))
+    }
+}
+
+fn contains_rule_file(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            contains_rule_file(&path)
+        } else {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e == "yml" || e == "yaml")
+        }
+    })
+}
+
+/// AI 服务能返回任意 HTTP 响应即视为可达
+async fn check_ai(config: &Config, options: &DoctorOptions) -> HealthCheckResult {
+    if !cfg!(feature = "ai") {
+        return HealthCheckResult::degraded("本构建未启用 ai 功能");
+    }
+    if options.offline {
+        return HealthCheckResult::degraded("离线模式，跳过连通性检查");
+    }
+    let client = match config
+        .http
+        .client_builder()
+        .and_then(|builder| Ok(builder.timeout(AI_PROBE_TIMEOUT).build()?))
+    {
+        Ok(client) => client,
+        Err(e) => return HealthCheckResult::unhealthy(format!("无法创建 HTTP 客户端: {e}")),
+    };
+    match client.get(&config.ai.api_url).send().await {
+        Ok(response) => HealthCheckResult::healthy_with(format!(
+            "{}（HTTP {}，模型 {}）",
+            config.ai.api_url,
+            response.status().as_u16(),
+            config.ai.model
+        )),
+        Err(e) => {
+            HealthCheckResult::unhealthy(format!("无法连接 AI 服务 {}: {e}", config.ai.api_url))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_rules_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join("rules");
+        assert_eq!(check_rules_dir(&rules).status, HealthStatus::Degraded);
+
+        std::fs::create_dir_all(rules.join("python")).unwrap();
+        assert_eq!(check_rules_dir(&rules).status, HealthStatus::Degraded);
+        std::fs::write(rules.join("python").join("eval.yml"), "rules: []\n").unwrap();
+        assert_eq!(check_rules_dir(&rules).status, HealthStatus::Healthy);
+
+        std::fs::write(rules.join(".rules.meta"), "{not json").unwrap();
+        let corrupt = check_rules_dir(&rules);
+        assert_eq!(corrupt.status, HealthStatus::Degraded);
+        assert!(corrupt.message.unwrap().contains("损坏"));
+
+        std::fs::write(rules.join(".rules.meta"), r#"{"total_rules": 42}"#).unwrap();
+        let valid = check_rules_dir(&rules);
+        assert_eq!(valid.status, HealthStatus::Healthy);
+        assert!(valid.message.unwrap().contains("42"));
+    }
+
+    #[test]
+    fn test_check_queries_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_queries_dir(dir.path()).status, HealthStatus::Degraded);
+        std::fs::create_dir_all(dir.path().join("rust")).unwrap();
+        assert_eq!(check_queries_dir(dir.path()).status, HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_report_failures_and_json() {
+        let mut report = DoctorReport {
+            version: "test".to_string(),
+            checks: vec![
+                DoctorCheck {
+                    name: "git",
+                    result: HealthCheckResult::healthy_with("git version 2.43.0"),
+                },
+                DoctorCheck {
+                    name: "ast-grep",
+                    result: HealthCheckResult::degraded("未安装 ast-grep（可选）"),
+                },
+            ],
+        };
+        assert!(!report.has_failures());
+        assert_eq!(report.counts(), (1, 1, 0));
+
+        report.checks.push(DoctorCheck {
+            name: "config",
+            result: HealthCheckResult::unhealthy("配置无效"),
+        });
+        assert!(report.has_failures());
+        let text = report.to_text();
+        assert!(text.contains("❌ config"));
+        assert!(text.contains("失败 1"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["name"], "git");
+        assert_eq!(json["checks"][1]["status"], "degraded");
+        assert_eq!(json["checks"][2]["is_healthy"], false);
+    }
+}
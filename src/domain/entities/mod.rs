@@ -190,6 +190,13 @@ impl<T: Send + Sync, S: Specification<T>> Specification<T> for NotSpecification<
     }
 }
 
+/// 可评分trait
+/// 给出 0.0-1.0 的评分，越高越严重或风险越大（与 `gitai-types` 中的同名 trait 一致）
+pub trait Scorable {
+    /// 计算评分
+    fn score(&self) -> f64;
+}
+
 /// 审计信息trait
 pub trait Auditable: Send + Sync {
     /// 获取创建时间
@@ -19,6 +19,8 @@ pub enum DomainError {
     ServiceUnavailable(String),
     /// 外部依赖错误
     ExternalDependency(String),
+    /// 操作被取消
+    Cancelled,
 }
 
 impl fmt::Display for DomainError {
@@ -31,6 +33,7 @@ impl fmt::Display for DomainError {
             DomainError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             DomainError::ServiceUnavailable(msg) => write!(f, "Service unavailable: {}", msg),
             DomainError::ExternalDependency(msg) => write!(f, "External dependency error: {}", msg),
+            DomainError::Cancelled => write!(f, "Operation cancelled"),
         }
     }
 }
@@ -56,7 +56,7 @@ pub trait HealthCheckInterface {
 }
 
 /// 健康检查结果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct HealthCheckResult {
     pub is_healthy: bool,
     pub status: HealthStatus,
@@ -94,7 +94,8 @@ impl HealthCheckResult {
 }
 
 /// 健康状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HealthStatus {
     /// 健康
     Healthy,
@@ -8,10 +8,14 @@ pub mod application;
 // 配置服务
 pub mod config;
 
+// 评审服务
+pub mod review;
+
 // 工作流服务
 pub mod workflow;
 
 // 重新导出主要服务
 pub use application::ApplicationService;
 pub use config::ConfigurationService;
+pub use review::{ReviewOptions, ReviewReport, ReviewService};
 pub use workflow::WorkflowService;
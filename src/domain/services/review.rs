@@ -0,0 +1,151 @@
+//! 评审服务
+//!
+//! 为编辑器、机器人等嵌入方提供与 `gitai review` 等价的单一入口：
+//! 读取变更、结构分析、（可选）安全扫描与 AI 评审，返回结构化报告
+
+use crate::config::Config;
+use crate::domain::errors::DomainError;
+use crate::review::{DiffSelection, ReviewConfig, ReviewResult};
+use crate::utils::cancel::CancellationToken;
+use serde::Serialize;
+use std::path::PathBuf;
+
+lazy_static::lazy_static! {
+    // 评审期间关闭进度输出是进程级设置，同一时间只允许一次评审
+    static ref RUN_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// 评审选项
+#[derive(Debug, Clone, Default)]
+pub struct ReviewOptions {
+    /// 仓库目录，未指定时使用当前工作目录
+    pub repo_path: Option<PathBuf>,
+    /// 评审的变更范围
+    pub selection: DiffSelection,
+    /// 代码语言提示
+    pub language: Option<String>,
+    /// 是否执行 Tree-sitter 结构分析
+    pub tree_sitter: bool,
+    /// 是否启用完整模式（依赖图等深入分析）
+    pub full: bool,
+    /// AI 评审的 diff token 上限，未指定时使用配置中的 `ai.max_diff_tokens`
+    pub max_diff_tokens: Option<usize>,
+    /// 是否执行安全扫描
+    #[cfg(feature = "security")]
+    pub security_scan: bool,
+    /// 关联的 Issue ID，非空时启用偏离度分析
+    #[cfg(feature = "devops")]
+    pub issue_ids: Vec<String>,
+    /// 远程 PR/MR 链接；设置后从 DevOps 平台拉取 diff
+    #[cfg(feature = "devops")]
+    pub pr_url: Option<String>,
+}
+
+impl ReviewOptions {
+    fn to_review_config(&self) -> ReviewConfig {
+        #[cfg(feature = "security")]
+        let security_scan = self.security_scan;
+        #[cfg(not(feature = "security"))]
+        let security_scan = false;
+        #[cfg(feature = "devops")]
+        let (issue_ids, pr_url) = (self.issue_ids.clone(), self.pr_url.clone());
+        #[cfg(not(feature = "devops"))]
+        let (issue_ids, pr_url): (Vec<String>, Option<String>) = (Vec::new(), None);
+
+        let mut builder = ReviewConfig::builder()
+            .format("json")
+            .tree_sitter(self.tree_sitter)
+            .security_scan(security_scan)
+            .issue_ids(issue_ids)
+            .full(self.full)
+            .diff_selection(self.selection.clone());
+        if let Some(repo_path) = &self.repo_path {
+            builder = builder.repo_path(repo_path.clone());
+        }
+        if let Some(language) = &self.language {
+            builder = builder.language(language.clone());
+        }
+        if let Some(pr_url) = pr_url {
+            builder = builder.pr_url(pr_url);
+        }
+        if let Some(max_diff_tokens) = self.max_diff_tokens {
+            builder = builder.max_diff_tokens(max_diff_tokens);
+        }
+        builder.build()
+    }
+}
+
+/// 评审报告
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewReport {
+    /// 评审结果（与 `gitai review --format json` 相同）
+    pub result: ReviewResult,
+    /// 是否包含 AI 评审（未启用 `ai` 功能时为基础规则汇总）
+    pub ai_review: bool,
+    /// 是否执行了安全扫描
+    pub security_scan: bool,
+    /// 是否执行了结构分析
+    pub tree_sitter: bool,
+}
+
+/// 评审服务
+pub struct ReviewService {
+    config: Config,
+}
+
+impl ReviewService {
+    /// 创建评审服务
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// 执行一次完整评审
+    ///
+    /// 评审期间不输出进度信息；指定 `repo_path` 时在该目录中评审，不切换进程工作目录
+    pub async fn run(&self, options: ReviewOptions) -> Result<ReviewReport, DomainError> {
+        self.run_with_cancel(options, &CancellationToken::new())
+            .await
+    }
+
+    /// 可取消的评审：令牌被取消时在下一个异步等待点中止并返回 [`DomainError::Cancelled`]，
+    /// 进度输出设置照常恢复
+    pub async fn run_with_cancel(
+        &self,
+        options: ReviewOptions,
+        cancel: &CancellationToken,
+    ) -> Result<ReviewReport, DomainError> {
+        let _guard = tokio::select! {
+            guard = RUN_LOCK.lock() => guard,
+            _ = cancel.cancelled() => return Err(DomainError::Cancelled),
+        };
+        let review_config = options.to_review_config();
+        let security_scan = cfg!(feature = "security") && review_config.security_scan;
+
+        if let Some(path) = &options.repo_path {
+            if !path.is_dir() {
+                return Err(DomainError::NotFound(format!(
+                    "{}: 仓库目录不存在",
+                    path.display()
+                )));
+            }
+        }
+        let was_quiet = crate::utils::output::quiet();
+        crate::utils::output::set_quiet(true);
+
+        let result = tokio::select! {
+            result = crate::review::execute_review_with_result(&self.config, review_config) => {
+                result.map_err(|e| DomainError::ExternalDependency(e.to_string()))
+            }
+            _ = cancel.cancelled() => Err(DomainError::Cancelled),
+        };
+
+        crate::utils::output::set_quiet(was_quiet);
+
+        Ok(ReviewReport {
+            result: result?,
+            ai_review: cfg!(feature = "ai"),
+            security_scan,
+            tree_sitter: options.tree_sitter,
+        })
+    }
+}
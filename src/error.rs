@@ -32,6 +32,10 @@ pub enum GitAIError {
     Update(UpdateError),
     /// MCP 错误
     Mcp(McpError),
+    /// 扫描发现达到失败阈值的问题（`--fail-on`）
+    FindingsDetected { count: usize, threshold: String },
+    /// 评估门禁未通过（`gitai eval`）
+    GateFailed(String),
     /// 未知错误
     Unknown(String),
 }
@@ -213,6 +217,10 @@ pub enum ScanError {
     FileAccessFailed(String),
     /// 超时
     Timeout(u64),
+    /// 本地规则中未找到指定规则（消息已按输出语言本地化）
+    RuleNotFound(String),
+    /// 扫描结果中未找到指定编号或指纹的问题（消息已按输出语言本地化）
+    FindingNotFound(String),
 }
 
 impl fmt::Display for ScanError {
@@ -224,6 +232,7 @@ impl fmt::Display for ScanError {
             ScanError::ResultParseFailed(msg) => write!(f, "结果解析失败: {}", msg),
             ScanError::FileAccessFailed(msg) => write!(f, "文件访问失败: {}", msg),
             ScanError::Timeout(seconds) => write!(f, "扫描超时: {} 秒", seconds),
+            ScanError::RuleNotFound(msg) | ScanError::FindingNotFound(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -368,46 +377,129 @@ impl fmt::Display for McpError {
 impl GitAIError {
     /// 获取用户友好的错误消息
     pub fn user_message(&self) -> String {
+        match (self, self.hint()) {
+            (GitAIError::UserCancelled, _) => "⚠️ 操作已取消".to_string(),
+            (_, Some(hint)) => format!("❌ {}: {}\n💡 提示: {hint}", self.title(), self.detail()),
+            (_, None) => format!("❌ {}: {}", self.title(), self.detail()),
+        }
+    }
+
+    /// 稳定的错误类型代码（用于 `--json-errors` 输出，脚本可据此区分错误类别）
+    pub fn error_code(&self) -> &'static str {
         match self {
-            GitAIError::Config(msg) => {
-                format!("❌ 配置错误: {msg}\n💡 提示: 请检查 ~/.config/gitai/config.toml 配置文件")
-            }
-            GitAIError::Git(msg) => {
-                format!("❌ Git 操作失败: {msg}\n💡 提示: 确保您在 Git 仓库中，并且有相应的权限")
-            }
-            GitAIError::FileSystem(msg) => {
-                format!("❌ 文件系统错误: {msg}\n💡 提示: 检查文件路径和权限设置")
-            }
-            GitAIError::Network(msg) => {
-                format!("❌ 网络连接错误: {msg}\n💡 提示: 检查网络连接和代理设置")
-            }
-            GitAIError::ScanTool(msg) => {
-                format!(
-                    "❌ 扫描工具错误: {msg}\n💡 提示: 使用 'gitai scan --auto-install' 安装所需工具"
-                )
-            }
-            GitAIError::AiService(msg) => {
-                format!("❌ AI 服务错误: {msg}\n💡 提示: 检查 AI 服务配置和 API 密钥")
-            }
-            GitAIError::Parse(msg) => {
-                format!("❌ 解析错误: {msg}\n💡 提示: 数据格式可能不正确，请检查输入")
-            }
-            GitAIError::MissingDependency(dep) => {
-                format!("❌ 缺少依赖: {dep}\n💡 提示: 请先安装所需的依赖工具")
+            GitAIError::Config(_) => "config",
+            GitAIError::Git(_) => "git",
+            GitAIError::FileSystem(_) => "filesystem",
+            GitAIError::Network(_) => "network",
+            GitAIError::ScanTool(_) => "scan",
+            GitAIError::AiService(_) => "ai",
+            GitAIError::Parse(_) => "parse",
+            GitAIError::MissingDependency(_) => "missing_dependency",
+            GitAIError::UserCancelled => "cancelled",
+            GitAIError::Container(_) => "container",
+            GitAIError::Update(_) => "update",
+            GitAIError::Mcp(_) => "mcp",
+            GitAIError::FindingsDetected { .. } => "findings",
+            GitAIError::GateFailed(_) => "gate",
+            GitAIError::Unknown(_) => "unknown",
+        }
+    }
+
+    /// 错误详情（不含图标与提示）
+    pub fn detail(&self) -> String {
+        match self {
+            GitAIError::Config(e) => e.to_string(),
+            GitAIError::Git(e) => e.to_string(),
+            GitAIError::FileSystem(e) => e.to_string(),
+            GitAIError::Network(e) => e.to_string(),
+            GitAIError::ScanTool(e) => e.to_string(),
+            GitAIError::AiService(e) => e.to_string(),
+            GitAIError::Parse(e) => e.to_string(),
+            GitAIError::MissingDependency(dep) => dep.clone(),
+            GitAIError::UserCancelled => "操作已取消".to_string(),
+            GitAIError::Container(e) => e.to_string(),
+            GitAIError::Update(e) => e.to_string(),
+            GitAIError::Mcp(e) => e.to_string(),
+            GitAIError::FindingsDetected { count, threshold } => {
+                format!("发现 {count} 个严重程度不低于 {threshold} 的问题")
             }
-            GitAIError::Container(msg) => {
-                format!("❌ 容器错误: {msg}\n💡 提示: 检查服务注册和依赖注入配置")
+            GitAIError::GateFailed(reason) => reason.clone(),
+            GitAIError::Unknown(msg) => msg.clone(),
+        }
+    }
+
+    /// 针对错误类别的处理建议
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            GitAIError::Config(_) => Some("请检查 ~/.config/gitai/config.toml 配置文件"),
+            GitAIError::Git(_) => Some("确保您在 Git 仓库中，并且有相应的权限"),
+            GitAIError::FileSystem(_) => Some("检查文件路径和权限设置"),
+            GitAIError::Network(_) => Some("检查网络连接和代理设置"),
+            GitAIError::ScanTool(ScanError::RuleNotFound(_)) => {
+                Some("使用 'gitai update' 更新扫描规则")
             }
-            GitAIError::Update(msg) => {
-                format!("❌ 更新错误: {msg}\n💡 提示: 检查网络连接和更新权限")
+            GitAIError::ScanTool(ScanError::FindingNotFound(_)) => {
+                Some("先运行 'gitai scan' 查看问题编号与指纹")
             }
-            GitAIError::Mcp(msg) => {
-                format!("❌ MCP 错误: {msg}\n💡 提示: 检查 MCP 服务配置和参数")
+            GitAIError::ScanTool(_) => Some("使用 'gitai scan --auto-install' 安装所需工具"),
+            GitAIError::AiService(_) => Some("检查 AI 服务配置和 API 密钥"),
+            GitAIError::Parse(_) => Some("数据格式可能不正确，请检查输入"),
+            GitAIError::MissingDependency(_) => Some("请先安装所需的依赖工具"),
+            GitAIError::Container(_) => Some("检查服务注册和依赖注入配置"),
+            GitAIError::Update(_) => Some("检查网络连接和更新权限"),
+            GitAIError::Mcp(_) => Some("检查 MCP 服务配置和参数"),
+            GitAIError::FindingsDetected { .. } => Some("修复上述问题，或调整 --fail-on 阈值"),
+            GitAIError::GateFailed(_) => Some("修复上述问题，或调整门禁阈值"),
+            GitAIError::UserCancelled => None,
+            GitAIError::Unknown(_) => Some("请查看日志文件获取更多信息"),
+        }
+    }
+
+    /// 对应的进程退出码，见 [`exit_codes`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GitAIError::Config(_) => exit_codes::CONFIG,
+            GitAIError::Git(_) => exit_codes::GIT,
+            GitAIError::Network(_) => exit_codes::NETWORK,
+            GitAIError::AiService(_) => exit_codes::AI,
+            GitAIError::ScanTool(_) => exit_codes::SCAN_FAILED,
+            GitAIError::Parse(_) => exit_codes::ANALYSIS,
+            GitAIError::MissingDependency(_) => exit_codes::MISSING_DEPENDENCY,
+            GitAIError::FindingsDetected { .. } | GitAIError::GateFailed(_) => exit_codes::FINDINGS,
+            GitAIError::UserCancelled => exit_codes::CANCELLED,
+            GitAIError::Update(UpdateError::Network(_) | UpdateError::Download(_)) => {
+                exit_codes::NETWORK
             }
-            GitAIError::UserCancelled => "⚠️ 操作已取消".to_string(),
-            GitAIError::Unknown(msg) => {
-                format!("❌ 未知错误: {msg}\n💡 提示: 请查看日志文件获取更多信息")
+            GitAIError::Update(UpdateError::Config(_)) => exit_codes::CONFIG,
+            GitAIError::Mcp(McpError::ConfigurationError(_)) => exit_codes::CONFIG,
+            GitAIError::Mcp(McpError::NetworkError(_) | McpError::TimeoutError(_)) => {
+                exit_codes::NETWORK
             }
+            GitAIError::FileSystem(_)
+            | GitAIError::Update(_)
+            | GitAIError::Container(_)
+            | GitAIError::Mcp(_)
+            | GitAIError::Unknown(_) => exit_codes::GENERAL,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            GitAIError::Config(_) => "配置错误",
+            GitAIError::Git(_) => "Git 操作失败",
+            GitAIError::FileSystem(_) => "文件系统错误",
+            GitAIError::Network(_) => "网络连接错误",
+            GitAIError::ScanTool(_) => "扫描工具错误",
+            GitAIError::AiService(_) => "AI 服务错误",
+            GitAIError::Parse(_) => "解析错误",
+            GitAIError::MissingDependency(_) => "缺少依赖",
+            GitAIError::UserCancelled => "操作已取消",
+            GitAIError::Container(_) => "容器错误",
+            GitAIError::Update(_) => "更新错误",
+            GitAIError::Mcp(_) => "MCP 错误",
+            GitAIError::FindingsDetected { .. } => "扫描未通过",
+            GitAIError::GateFailed(_) => "评估未通过",
+            GitAIError::Unknown(_) => "未知错误",
         }
     }
 
@@ -446,6 +538,12 @@ impl From<std::io::Error> for GitAIError {
     }
 }
 
+impl From<crate::utils::cancel::Cancelled> for GitAIError {
+    fn from(_: crate::utils::cancel::Cancelled) -> Self {
+        GitAIError::UserCancelled
+    }
+}
+
 impl From<serde_json::Error> for GitAIError {
     fn from(err: serde_json::Error) -> Self {
         GitAIError::Parse(ParseError::Json(err.to_string()))
@@ -502,10 +600,13 @@ impl From<String> for GitAIError {
     }
 }
 
-// 支持从Box<dyn Error>转换
+// 支持从Box<dyn Error>转换（已是 GitAIError 时保留原类别）
 impl From<Box<dyn std::error::Error + Send + Sync>> for GitAIError {
     fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
-        GitAIError::Unknown(err.to_string())
+        match err.downcast::<GitAIError>() {
+            Ok(e) => *e,
+            Err(err) => classify_foreign(err.as_ref()),
+        }
     }
 }
 
@@ -564,6 +665,131 @@ impl From<McpError> for GitAIError {
     }
 }
 
+/// 机器可读的错误报告（`--json-errors` 模式下输出到 stderr）
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorReport {
+    /// 稳定的错误类型代码，见 [`GitAIError::error_code`]
+    pub error_type: String,
+    /// 错误详情
+    pub message: String,
+    /// 处理建议
+    pub hint: Option<String>,
+    /// 本次调用的请求 ID，与日志中的 `[req=...]` 对应
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ErrorReport {
+    /// 由顶层错误构造报告
+    pub fn from_error(err: &(dyn std::error::Error + 'static)) -> Self {
+        with_classified(err, |e| Self::from(e))
+    }
+
+    /// 命令行参数错误
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self {
+            error_type: "usage".to_string(),
+            message: message.into(),
+            hint: Some("使用 'gitai --help' 查看可用参数".to_string()),
+            request_id: None,
+        }
+    }
+
+    /// 附带请求 ID
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    /// 序列化为单行 JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            format!(
+                r#"{{"error_type":"{}","message":"","hint":null}}"#,
+                self.error_type
+            )
+        })
+    }
+}
+
+impl From<&GitAIError> for ErrorReport {
+    fn from(err: &GitAIError) -> Self {
+        Self {
+            error_type: err.error_code().to_string(),
+            message: err.detail(),
+            hint: err.hint().map(str::to_string),
+            request_id: None,
+        }
+    }
+}
+
+/// 进程退出码
+///
+/// 除 [`FINDINGS`](exit_codes::FINDINGS) 外的非零退出码都表示工具本身运行失败，
+/// CI 可据此区分“工具出错”与“扫描发现问题”。
+pub mod exit_codes {
+    /// 成功
+    pub const SUCCESS: i32 = 0;
+    /// 未分类的错误
+    pub const GENERAL: i32 = 1;
+    /// 命令行参数错误
+    pub const USAGE: i32 = 2;
+    /// 配置错误
+    pub const CONFIG: i32 = 3;
+    /// Git 操作失败
+    pub const GIT: i32 = 4;
+    /// 网络错误
+    pub const NETWORK: i32 = 5;
+    /// AI 服务错误
+    pub const AI: i32 = 6;
+    /// 扫描工具缺失或执行失败
+    pub const SCAN_FAILED: i32 = 7;
+    /// 分析或解析失败
+    pub const ANALYSIS: i32 = 8;
+    /// 缺少依赖或功能未启用
+    pub const MISSING_DEPENDENCY: i32 = 9;
+    /// 扫描发现达到 `--fail-on` 阈值的问题，或评估门禁未通过
+    pub const FINDINGS: i32 = 10;
+    /// 用户取消
+    pub const CANCELLED: i32 = 130;
+}
+
+/// 计算顶层错误对应的进程退出码
+pub fn exit_code(err: &(dyn std::error::Error + 'static)) -> i32 {
+    with_classified(err, GitAIError::exit_code)
+}
+
+/// 将顶层错误归类为 GitAIError 后交给 `f`
+fn with_classified<R>(
+    err: &(dyn std::error::Error + 'static),
+    f: impl FnOnce(&GitAIError) -> R,
+) -> R {
+    match err.downcast_ref::<GitAIError>() {
+        Some(e) => f(e),
+        None => f(&classify_foreign(err)),
+    }
+}
+
+/// 按来源归类非 GitAIError 的常见错误，其余归为 Unknown
+fn classify_foreign(err: &(dyn std::error::Error + 'static)) -> GitAIError {
+    #[cfg(feature = "mcp")]
+    if let Some(e) = err.downcast_ref::<crate::mcp::McpError>() {
+        return GitAIError::from(e.clone());
+    }
+    let msg = err.to_string();
+    if err.is::<std::io::Error>() || err.is::<walkdir::Error>() {
+        GitAIError::FileSystem(FileSystemError::Io(msg))
+    } else if err.is::<serde_json::Error>() {
+        GitAIError::Parse(ParseError::Json(msg))
+    } else if err.is::<toml::de::Error>() || err.is::<toml::ser::Error>() {
+        GitAIError::Parse(ParseError::Toml(msg))
+    } else if err.is::<reqwest::Error>() {
+        GitAIError::Network(NetworkError::Http(msg))
+    } else {
+        GitAIError::Unknown(msg)
+    }
+}
+
 /// 结果类型别名
 pub type Result<T> = std::result::Result<T, GitAIError>;
 
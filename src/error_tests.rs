@@ -159,3 +159,117 @@ fn test_display_implementations() {
         assert!(!display.is_empty());
     }
 }
+
+#[test]
+fn test_error_codes_are_stable() {
+    let cases = vec![
+        (
+            GitAIError::Config(ConfigError::Missing("api_key".to_string())),
+            "config",
+        ),
+        (
+            GitAIError::ScanTool(ScanError::ToolNotFound("opengrep".to_string())),
+            "scan",
+        ),
+        (
+            GitAIError::AiService(AiError::QuotaExceeded("quota".to_string())),
+            "ai",
+        ),
+        (GitAIError::UserCancelled, "cancelled"),
+        (GitAIError::Unknown("boom".to_string()), "unknown"),
+    ];
+
+    for (error, code) in cases {
+        assert_eq!(error.error_code(), code);
+    }
+}
+
+#[test]
+fn test_error_report_json() {
+    let error = GitAIError::Config(ConfigError::LoadFailed("bad toml".to_string()));
+    let report = ErrorReport::from(&error);
+    assert_eq!(report.error_type, "config");
+    assert_eq!(report.message, "配置加载失败: bad toml");
+    assert!(report.hint.is_some());
+    assert!(error
+        .user_message()
+        .contains(report.hint.as_deref().unwrap()));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["error_type"], "config");
+    assert_eq!(json["message"], "配置加载失败: bad toml");
+
+    let cancelled = ErrorReport::from(&GitAIError::UserCancelled);
+    assert_eq!(cancelled.hint, None);
+}
+
+#[test]
+fn test_error_report_from_boxed_errors() {
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(GitAIError::ScanTool(ScanError::Timeout(30)));
+    let report = ErrorReport::from_error(boxed.as_ref());
+    assert_eq!(report.error_type, "scan");
+    assert_eq!(report.message, "扫描超时: 30 秒");
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(io::Error::new(io::ErrorKind::NotFound, "missing"));
+    assert_eq!(
+        ErrorReport::from_error(boxed.as_ref()).error_type,
+        "filesystem"
+    );
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = "plain failure".into();
+    let report = ErrorReport::from_error(boxed.as_ref());
+    assert_eq!(report.error_type, "unknown");
+    assert_eq!(report.message, "plain failure");
+}
+
+#[test]
+fn test_exit_codes_by_category() {
+    assert_eq!(
+        GitAIError::Config(ConfigError::Missing("api_key".to_string())).exit_code(),
+        exit_codes::CONFIG
+    );
+    assert_eq!(
+        GitAIError::ScanTool(ScanError::ToolNotFound("opengrep".to_string())).exit_code(),
+        exit_codes::SCAN_FAILED
+    );
+    assert_eq!(
+        GitAIError::FindingsDetected {
+            count: 2,
+            threshold: "error".to_string(),
+        }
+        .exit_code(),
+        exit_codes::FINDINGS
+    );
+    assert_eq!(
+        GitAIError::Update(UpdateError::Download("timeout".to_string())).exit_code(),
+        exit_codes::NETWORK
+    );
+    assert_eq!(GitAIError::UserCancelled.exit_code(), exit_codes::CANCELLED);
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(GitAIError::AiService(AiError::RequestTimeout(30)));
+    assert_eq!(exit_code(boxed.as_ref()), exit_codes::AI);
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = "plain failure".into();
+    assert_eq!(exit_code(boxed.as_ref()), exit_codes::GENERAL);
+}
+
+#[test]
+fn test_boxed_error_conversion_preserves_category() {
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(GitAIError::ScanTool(ScanError::Timeout(10)));
+    let converted = GitAIError::from(boxed);
+    assert!(matches!(
+        converted,
+        GitAIError::ScanTool(ScanError::Timeout(10))
+    ));
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+    assert!(matches!(GitAIError::from(boxed), GitAIError::FileSystem(_)));
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = "plain failure".into();
+    assert!(matches!(GitAIError::from(boxed), GitAIError::Unknown(msg) if msg == "plain failure"));
+}
@@ -0,0 +1,115 @@
+// 配置模块扩散检查
+// 统计项目中的 config.rs 文件，白名单之外的数量超过上限即视为配置来源分散
+
+use super::{collect_rust_files, display_path, is_ignored};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 配置模块扩散检查报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigCheckReport {
+    pub root: String,
+    /// 找到的全部 config.rs（按路径排序）
+    pub files: Vec<String>,
+    /// 命中白名单的 config.rs
+    pub allowed: Vec<String>,
+    /// 白名单之外的 config.rs
+    pub offending: Vec<String>,
+    /// 白名单之外允许存在的数量上限
+    pub max_files: usize,
+}
+
+impl ConfigCheckReport {
+    /// 按门禁阈值检查，返回未通过的原因（为空表示通过）
+    pub fn violations(&self) -> Vec<String> {
+        if self.offending.len() <= self.max_files {
+            return Vec::new();
+        }
+        vec![format!(
+            "白名单之外存在 {} 个 config.rs（上限 {}）: {}",
+            self.offending.len(),
+            self.max_files,
+            self.offending.join(", ")
+        )]
+    }
+
+    /// 文本形式的报告
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "⚙️ 配置模块: 共 {} 个 config.rs，白名单之外 {} 个（上限 {}）\n",
+            self.files.len(),
+            self.offending.len(),
+            self.max_files
+        );
+        if !self.offending.is_empty() {
+            out.push_str("\n白名单之外的 config.rs:\n");
+            for path in &self.offending {
+                out.push_str(&format!("  {path}\n"));
+            }
+        }
+        if !self.allowed.is_empty() {
+            out.push_str("\n白名单内的 config.rs:\n");
+            for path in &self.allowed {
+                out.push_str(&format!("  {path}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// 找出 root 下的 config.rs，并按白名单（路径、目录或通配符模式，相对 root）与数量上限归类
+pub fn check_config_rs(root: &Path, allowlist: &[String], max_files: usize) -> ConfigCheckReport {
+    let files: Vec<String> = collect_rust_files(root)
+        .into_iter()
+        .filter(|p| p.file_name().and_then(|n| n.to_str()) == Some("config.rs"))
+        .map(|p| display_path(root, &p))
+        .collect();
+    let (allowed, offending) = files
+        .iter()
+        .cloned()
+        .partition(|path| is_ignored(path, allowlist));
+    ConfigCheckReport {
+        root: root.display().to_string(),
+        files,
+        allowed,
+        offending,
+        max_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_config_rs_applies_allowlist_and_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        for rel in [
+            "src/config.rs",
+            "src/cli/config.rs",
+            "crates/core/src/config.rs",
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "pub struct Config;\n").unwrap();
+        }
+        std::fs::write(dir.path().join("src/configure.rs"), "").unwrap();
+
+        let report = check_config_rs(dir.path(), &[], 1);
+        assert_eq!(report.files.len(), 3);
+        assert_eq!(report.offending.len(), 3);
+        let violations = report.violations();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("src/cli/config.rs"));
+
+        let allowlist = vec!["crates/".to_string(), "src/cli/config.rs".to_string()];
+        let report = check_config_rs(dir.path(), &allowlist, 1);
+        assert_eq!(report.offending, vec!["src/config.rs"]);
+        assert_eq!(report.allowed.len(), 2);
+        assert!(report.violations().is_empty());
+
+        let allowlist = vec!["**/src/config.rs".to_string()];
+        let report = check_config_rs(dir.path(), &allowlist, 1);
+        assert_eq!(report.offending, vec!["src/cli/config.rs"]);
+    }
+}
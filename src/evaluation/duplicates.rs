@@ -0,0 +1,784 @@
+// 重复代码评估
+// 找出内容完全相同的源文件分组，以及按行集合相似度判定的近似重复文件对
+
+use super::{collect_source_files, display_path, is_ignored};
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// 参与比较的最少有效行数，过短的文件（如只有 mod 声明）不计入
+const MIN_LINES: usize = 5;
+
+/// 重复检测参数
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// 近似重复的最低相似度（0.0-1.0）
+    pub min_similarity: f64,
+    /// 近似重复比较阶段驻留内存的行指纹总量上限（字节）
+    pub memory_budget: usize,
+    /// 近似重复比较的文件对上限
+    pub max_comparisons: usize,
+    /// 只评估变更文件时，作为比较对象读取的未变更文件数上限
+    pub max_corpus_files: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            min_similarity: 0.9,
+            memory_budget: 256 * 1024 * 1024,
+            max_comparisons: 5_000_000,
+            max_corpus_files: 2_000,
+        }
+    }
+}
+
+/// 内容完全相同的一组文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// 规范化内容的 md5
+    pub hash: String,
+    /// 有效行数
+    pub lines: usize,
+    pub files: Vec<String>,
+}
+
+/// 近似重复的文件对
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NearDuplicatePair {
+    pub left: String,
+    pub right: String,
+    /// 行集合的 Jaccard 相似度
+    pub similarity: f64,
+}
+
+/// 重复代码报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub root: String,
+    pub files_scanned: usize,
+    pub min_similarity: f64,
+    pub groups: Vec<DuplicateGroup>,
+    pub near_duplicates: Vec<NearDuplicatePair>,
+    /// 超出比较上限、比较对象上限或内存预算，结果不完整
+    #[serde(default)]
+    pub truncated: bool,
+    /// 只评估变更文件时，作为比较对象读取的未变更文件数
+    #[serde(default)]
+    pub corpus_files: usize,
+    /// 比较阶段同时驻留的行指纹峰值（字节）
+    #[serde(default)]
+    pub peak_memory_bytes: usize,
+}
+
+impl DuplicateReport {
+    /// 重复数：每组完全重复文件计 `组大小 - 1`，每个近似重复文件对计 1
+    pub fn duplicate_count(&self) -> usize {
+        self.groups.iter().map(|g| g.files.len() - 1).sum::<usize>() + self.near_duplicates.len()
+    }
+
+    /// 按门禁阈值检查，返回未通过的原因（为空表示通过）
+    pub fn violations(&self, max_duplicates: usize) -> Vec<String> {
+        let count = self.duplicate_count();
+        if count > max_duplicates {
+            vec![format!("重复文件数 {count} 超过上限 {max_duplicates}")]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// 文本形式的报告
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "🧬 重复代码: {} 组完全重复，{} 对近似重复（相似度 ≥ {:.0}%，扫描 {} 个文件）\n",
+            self.groups.len(),
+            self.near_duplicates.len(),
+            self.min_similarity * 100.0,
+            self.files_scanned
+        );
+        for group in &self.groups {
+            out.push_str(&format!("\n完全重复（{} 行）:\n", group.lines));
+            for file in &group.files {
+                out.push_str(&format!("  {file}\n"));
+            }
+        }
+        if !self.near_duplicates.is_empty() {
+            out.push_str("\n近似重复:\n");
+            for pair in &self.near_duplicates {
+                out.push_str(&format!(
+                    "  {:.1}%  {} ↔ {}\n",
+                    pair.similarity * 100.0,
+                    pair.left,
+                    pair.right
+                ));
+            }
+        }
+        if self.truncated {
+            out.push_str("\n⚠️ 超出比较上限、比较对象上限或内存预算，重复比较已截断\n");
+        }
+        out
+    }
+}
+
+/// 单个文件的摘要；预处理阶段流式读取得到，不保留文件内容
+#[derive(Debug, Clone)]
+struct FileSummary {
+    path: String,
+    /// 规范化内容的 md5
+    hash: String,
+    /// 有效行数
+    lines: usize,
+    /// 不同有效行的数量
+    unique: usize,
+    /// 行指纹的前缀（用于近似重复的候选筛选）
+    prefix: Vec<u64>,
+}
+
+/// 前缀过滤的前缀长度：Jaccard 相似度不低于 `t` 的两个集合，按同一全局顺序排序后，
+/// 各自前 `n - ⌈t·n⌉ + 1` 个元素中至少有一个相同
+fn prefix_len(n: usize, min_similarity: f64) -> usize {
+    let required = (min_similarity * n as f64 - 1e-9).ceil().max(0.0) as usize;
+    (n - required.min(n) + 1).min(n)
+}
+
+/// 流式读取有效行（去掉首尾空白并跳过空行），返回内容摘要与行指纹
+fn fingerprint(
+    path: &str,
+    reader: impl BufRead,
+    min_similarity: f64,
+) -> Option<(FileSummary, Vec<u64>)> {
+    let mut md5 = md5::Context::new();
+    let mut hashes = Vec::new();
+    for line in reader.lines() {
+        let line = line.ok()?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !hashes.is_empty() {
+            md5.consume(b"\n");
+        }
+        md5.consume(line.as_bytes());
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        line.hash(&mut hasher);
+        hashes.push(hasher.finish());
+    }
+    let lines = hashes.len();
+    hashes.sort_unstable();
+    hashes.dedup();
+    let summary = FileSummary {
+        path: path.to_string(),
+        hash: format!("{:x}", md5.compute()),
+        lines,
+        unique: hashes.len(),
+        prefix: hashes[..prefix_len(hashes.len(), min_similarity)].to_vec(),
+    };
+    Some((summary, hashes))
+}
+
+fn read_fingerprint(path: &Path) -> Option<Vec<u64>> {
+    let file = std::fs::File::open(path).ok()?;
+    fingerprint("", std::io::BufReader::new(file), 1.0).map(|(_, hashes)| hashes)
+}
+
+/// 并行地流式预处理文件，只保留摘要；结果与输入顺序一致
+fn summarize_files(files: &[(String, PathBuf)], min_similarity: f64) -> Vec<Option<FileSummary>> {
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(8);
+    let chunk = files.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(rel, path)| {
+                            let file = std::fs::File::open(path).ok()?;
+                            fingerprint(rel, std::io::BufReader::new(file), min_similarity)
+                                .map(|(s, _)| s)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+fn groups_from_summaries(summaries: &[FileSummary]) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<&str, DuplicateGroup> = BTreeMap::new();
+    for summary in summaries.iter().filter(|s| s.lines >= MIN_LINES) {
+        by_hash
+            .entry(summary.hash.as_str())
+            .or_insert_with(|| DuplicateGroup {
+                hash: summary.hash.clone(),
+                lines: summary.lines,
+                files: Vec::new(),
+            })
+            .files
+            .push(summary.path.clone());
+    }
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_values()
+        .filter(|g| g.files.len() > 1)
+        .map(|mut g| {
+            g.files.sort();
+            g
+        })
+        .collect();
+    groups.sort_by(|a, b| a.files.cmp(&b.files));
+    groups
+}
+
+/// 两个有序去重的行指纹的交集大小
+fn intersection_len(a: &[u64], b: &[u64]) -> usize {
+    let (mut i, mut j, mut n) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                n += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    n
+}
+
+struct NearScan {
+    pairs: Vec<NearDuplicatePair>,
+    truncated: bool,
+    peak_bytes: usize,
+}
+
+/// 近似重复比较
+///
+/// 1. 长度过滤：Jaccard 相似度不超过 `较小行数 / 较大行数`，文件按不同行数排序后，
+///    每个文件只需与行数落在 `[n, n / min_similarity]` 内的文件比较；
+/// 2. 前缀过滤：只有行指纹前缀有交集的文件才成为候选，前缀由预处理阶段给出；
+/// 3. 候选的完整行指纹通过 `load` 按需加载，离开窗口即释放，驻留总量超出
+///    `memory_budget` 时跳过剩余比较并标记截断。
+///
+/// 给出 `focus`（按 summaries 下标标记）时只比较至少一侧被标记的文件对。
+fn scan_near_duplicates(
+    summaries: &[FileSummary],
+    options: &DuplicateOptions,
+    focus: Option<&[bool]>,
+    mut load: impl FnMut(usize) -> Option<Vec<u64>>,
+) -> NearScan {
+    let mut order: Vec<usize> = (0..summaries.len())
+        .filter(|&i| summaries[i].unique >= MIN_LINES)
+        .collect();
+    order.sort_by(|&a, &b| {
+        summaries[a]
+            .unique
+            .cmp(&summaries[b].unique)
+            .then(a.cmp(&b))
+    });
+
+    // 前缀倒排索引：行指纹 -> 按排序位置递增的文件列表
+    let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (pos, &i) in order.iter().enumerate() {
+        for &token in &summaries[i].prefix {
+            index.entry(token).or_default().push(pos);
+        }
+    }
+
+    let bytes_of = |pos: usize| summaries[order[pos]].unique * std::mem::size_of::<u64>();
+    // 以排序位置为键，便于整体释放已离开窗口的指纹
+    let mut resident: BTreeMap<usize, Vec<u64>> = BTreeMap::new();
+    let mut resident_bytes = 0usize;
+    let mut scan = NearScan {
+        pairs: Vec::new(),
+        truncated: false,
+        peak_bytes: 0,
+    };
+    let mut comparisons = 0usize;
+
+    'outer: for pos in 0..order.len() {
+        let i = order[pos];
+        let mut candidates: Vec<usize> = summaries[i]
+            .prefix
+            .iter()
+            .filter_map(|token| index.get(token))
+            .flat_map(|positions| positions.iter().copied().filter(|&q| q > pos))
+            .filter(|&q| focus.is_none_or(|focus| focus[i] || focus[order[q]]))
+            .filter(|&q| {
+                let (small, large) = (
+                    summaries[i].unique as f64,
+                    summaries[order[q]].unique as f64,
+                );
+                small + 1e-9 >= options.min_similarity * large
+            })
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        // 释放已离开窗口的指纹
+        let kept = resident.split_off(&pos);
+        for hashes in std::mem::replace(&mut resident, kept).values() {
+            resident_bytes -= hashes.len() * std::mem::size_of::<u64>();
+        }
+        if candidates.is_empty() {
+            continue;
+        }
+        if !resident.contains_key(&pos) {
+            if resident_bytes + bytes_of(pos) > options.memory_budget {
+                resident.clear();
+                resident_bytes = 0;
+            }
+            if bytes_of(pos) > options.memory_budget {
+                scan.truncated = true;
+                continue;
+            }
+            match load(i) {
+                Some(hashes) => {
+                    resident_bytes += hashes.len() * std::mem::size_of::<u64>();
+                    resident.insert(pos, hashes);
+                }
+                None => continue,
+            }
+        }
+        scan.peak_bytes = scan.peak_bytes.max(resident_bytes);
+
+        for q in candidates {
+            let j = order[q];
+            if comparisons >= options.max_comparisons {
+                scan.truncated = true;
+                break 'outer;
+            }
+            if let Entry::Vacant(slot) = resident.entry(q) {
+                if resident_bytes + bytes_of(q) > options.memory_budget {
+                    scan.truncated = true;
+                    break;
+                }
+                match load(j) {
+                    Some(hashes) => {
+                        resident_bytes += hashes.len() * std::mem::size_of::<u64>();
+                        slot.insert(hashes);
+                        scan.peak_bytes = scan.peak_bytes.max(resident_bytes);
+                    }
+                    None => continue,
+                }
+            }
+            comparisons += 1;
+
+            let (a, b) = (&resident[&pos], &resident[&q]);
+            let intersection = intersection_len(a, b);
+            let union = a.len() + b.len() - intersection;
+            if intersection == union {
+                continue;
+            }
+            let similarity = intersection as f64 / union as f64;
+            if similarity >= options.min_similarity {
+                let (left, right) = if i < j { (i, j) } else { (j, i) };
+                scan.pairs.push(NearDuplicatePair {
+                    left: summaries[left].path.clone(),
+                    right: summaries[right].path.clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    scan.pairs.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.left.cmp(&b.left))
+            .then_with(|| a.right.cmp(&b.right))
+    });
+    scan
+}
+
+/// 基于内存中的 (路径, 内容) 计算摘要与行指纹
+fn fingerprint_contents(
+    files: &[(String, String)],
+    min_similarity: f64,
+) -> (Vec<FileSummary>, Vec<Vec<u64>>) {
+    files
+        .iter()
+        .filter_map(|(path, content)| fingerprint(path, content.as_bytes(), min_similarity))
+        .unzip()
+}
+
+/// 找出内容完全相同（忽略缩进与空行）的文件分组；`files` 为 (路径, 内容)
+pub fn scan_duplicates(files: &[(String, String)]) -> Vec<DuplicateGroup> {
+    groups_from_summaries(&fingerprint_contents(files, 1.0).0)
+}
+
+/// 找出相似度不低于 `min_similarity` 的近似重复文件对（完全相同的文件不在此列）
+///
+/// 返回的布尔值表示是否因超出比较上限而被截断。
+pub fn find_near_duplicates(
+    files: &[(String, String)],
+    min_similarity: f64,
+) -> (Vec<NearDuplicatePair>, bool) {
+    let (summaries, hashes) = fingerprint_contents(files, min_similarity);
+    let options = DuplicateOptions {
+        min_similarity,
+        ..Default::default()
+    };
+    let scan = scan_near_duplicates(&summaries, &options, None, |i| Some(hashes[i].clone()));
+    (scan.pairs, scan.truncated)
+}
+
+/// 扫描 root 下的源码文件并生成重复代码报告；`ignore` 中的路径（或目录）不参与比较
+pub fn analyze_duplicates(root: &Path, min_similarity: f64, ignore: &[String]) -> DuplicateReport {
+    let options = DuplicateOptions {
+        min_similarity,
+        ..Default::default()
+    };
+    analyze_duplicates_with(root, ignore, &options)
+}
+
+/// 按给定参数生成重复代码报告
+///
+/// 文件先经并行的流式预处理只保留摘要与行指纹前缀，近似重复比较阶段再按需重新读取
+/// 候选文件的完整行指纹，驻留的行指纹受 `options.memory_budget` 约束。
+pub fn analyze_duplicates_with(
+    root: &Path,
+    ignore: &[String],
+    options: &DuplicateOptions,
+) -> DuplicateReport {
+    analyze(root, ignore, options, None)
+}
+
+/// 只针对变更文件生成重复代码报告
+///
+/// `changed` 为相对 root 的路径。只读取变更文件以及与其扩展名相同、大小最接近的至多
+/// `options.max_corpus_files` 个未变更文件作为比较对象，其余文件不会被读取；报告只包含
+/// 至少含有一个变更文件的完全重复分组与近似重复文件对，`files_scanned` 只统计变更文件，
+/// 比较对象因上限被排除时标记截断。变更文件都不在扫描范围内时直接返回空报告。
+pub fn analyze_duplicates_changed(
+    root: &Path,
+    ignore: &[String],
+    options: &DuplicateOptions,
+    changed: &[String],
+) -> DuplicateReport {
+    analyze(root, ignore, options, Some(changed))
+}
+
+fn analyze(
+    root: &Path,
+    ignore: &[String],
+    options: &DuplicateOptions,
+    changed: Option<&[String]>,
+) -> DuplicateReport {
+    let files: Vec<(String, PathBuf)> = collect_source_files(root)
+        .into_iter()
+        .filter_map(|path| {
+            let rel = display_path(root, &path);
+            (!is_ignored(&rel, ignore)).then_some((rel, path))
+        })
+        .collect();
+
+    // 只评估变更文件时，未变更文件中只有有限的比较对象会被读取
+    let (files, corpus_files, corpus_truncated) = match changed {
+        Some(changed) => {
+            let (mut selected, unchanged): (Vec<_>, Vec<_>) = files
+                .into_iter()
+                .partition(|(rel, _)| changed.contains(rel));
+            if selected.is_empty() {
+                return DuplicateReport {
+                    root: root.display().to_string(),
+                    min_similarity: options.min_similarity,
+                    ..Default::default()
+                };
+            }
+            let (corpus, truncated) =
+                comparison_corpus(&selected, unchanged, options.max_corpus_files);
+            let corpus_files = corpus.len();
+            selected.extend(corpus);
+            selected.sort();
+            (selected, corpus_files, truncated)
+        }
+        None => (files, 0, false),
+    };
+
+    let (summaries, paths): (Vec<FileSummary>, Vec<&PathBuf>) =
+        summarize_files(&files, options.min_similarity)
+            .into_iter()
+            .zip(&files)
+            .filter_map(|(summary, (_, path))| summary.map(|s| (s, path)))
+            .unzip();
+
+    let focus: Option<Vec<bool>> = changed.map(|changed| {
+        summaries
+            .iter()
+            .map(|s| changed.iter().any(|c| c == &s.path))
+            .collect()
+    });
+    let mut groups = groups_from_summaries(&summaries);
+    if let Some(changed) = changed {
+        groups.retain(|g| g.files.iter().any(|f| changed.contains(f)));
+    }
+    let scan = scan_near_duplicates(&summaries, options, focus.as_deref(), |i| {
+        read_fingerprint(paths[i])
+    });
+    let files_scanned = match &focus {
+        Some(focus) => focus.iter().filter(|&&f| f).count(),
+        None => summaries.len(),
+    };
+    DuplicateReport {
+        root: root.display().to_string(),
+        files_scanned,
+        min_similarity: options.min_similarity,
+        groups,
+        near_duplicates: scan.pairs,
+        truncated: scan.truncated || corpus_truncated,
+        corpus_files,
+        peak_memory_bytes: scan.peak_bytes,
+    }
+}
+
+/// 选出变更文件的比较对象：与某个变更文件扩展名相同的未变更文件，按文件大小与最接近的
+/// 变更文件的比值排序后取前 `max_files` 个。只读取元数据，不读取文件内容；
+/// 返回的布尔值表示是否有文件因超出上限被排除
+fn comparison_corpus(
+    changed: &[(String, PathBuf)],
+    unchanged: Vec<(String, PathBuf)>,
+    max_files: usize,
+) -> (Vec<(String, PathBuf)>, bool) {
+    let log_size = |path: &Path| {
+        let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        (len as f64 + 1.0).ln()
+    };
+    let targets: Vec<(Option<&std::ffi::OsStr>, f64)> = changed
+        .iter()
+        .map(|(_, path)| (path.extension(), log_size(path)))
+        .collect();
+    let mut ranked: Vec<(f64, (String, PathBuf))> = unchanged
+        .into_iter()
+        .filter_map(|file| {
+            let size = log_size(&file.1);
+            let distance = targets
+                .iter()
+                .filter(|(extension, _)| *extension == file.1.extension())
+                .map(|(_, target)| (target - size).abs())
+                .min_by(f64::total_cmp)?;
+            Some((distance, file))
+        })
+        .collect();
+    let truncated = ranked.len() > max_files;
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1 .0.cmp(&b.1 .0)));
+    ranked.truncate(max_files);
+    (
+        ranked.into_iter().map(|(_, file)| file).collect(),
+        truncated,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(lines: &[&str]) -> String {
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_scan_and_near_duplicates() {
+        let base = [
+            "fn a() {",
+            "    let x = 1;",
+            "    let y = 2;",
+            "    x + y",
+            "}",
+            "fn b() {",
+            "    todo!()",
+            "}",
+            "fn c() {",
+            "}",
+        ];
+        let mut tweaked = base.to_vec();
+        tweaked[6] = "    unimplemented!()";
+        let files = vec![
+            ("a.rs".to_string(), source(&base)),
+            (
+                "b.rs".to_string(),
+                format!("\n{}\n", source(&base).replace("    ", "\t")),
+            ),
+            ("c.rs".to_string(), source(&tweaked)),
+            ("short.rs".to_string(), "mod a;\n".to_string()),
+            ("short2.rs".to_string(), "mod a;\n".to_string()),
+        ];
+
+        let groups = scan_duplicates(&files);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files, vec!["a.rs", "b.rs"]);
+
+        let (pairs, truncated) = find_near_duplicates(&files, 0.75);
+        assert!(!truncated);
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs.iter().all(|p| p.right == "c.rs"));
+        assert!(find_near_duplicates(&files, 0.95).0.is_empty());
+
+        let report = DuplicateReport {
+            groups,
+            near_duplicates: pairs,
+            ..Default::default()
+        };
+        assert_eq!(report.duplicate_count(), 3);
+        assert!(report.violations(3).is_empty());
+        assert_eq!(report.violations(2).len(), 1);
+    }
+
+    #[test]
+    fn test_analyze_duplicates_respects_ignore_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "fn a() {\n    1\n}\nfn b() {\n    2\n}\n";
+        std::fs::create_dir_all(dir.path().join("src/vendored")).unwrap();
+        std::fs::write(dir.path().join("src/a.rs"), body).unwrap();
+        std::fs::write(dir.path().join("src/b.rs"), body).unwrap();
+        std::fs::write(dir.path().join("src/vendored/c.rs"), body).unwrap();
+
+        let report = analyze_duplicates(dir.path(), 0.9, &[]);
+        assert_eq!(report.groups[0].files.len(), 3);
+
+        let ignore = vec!["src/vendored/".to_string(), "src/b.rs".to_string()];
+        let report = analyze_duplicates(dir.path(), 0.9, &ignore);
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.duplicate_count(), 0);
+    }
+
+    #[test]
+    fn test_analyze_duplicates_changed_only_reports_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "fn a() {\n    1\n}\nfn b() {\n    2\n}\n";
+        let other = "fn c() {\n    3\n}\nfn d() {\n    4\n}\n";
+        for name in ["a.rs", "b.rs"] {
+            std::fs::write(dir.path().join(name), body).unwrap();
+        }
+        for name in ["c.rs", "d.rs"] {
+            std::fs::write(dir.path().join(name), other).unwrap();
+        }
+        let options = DuplicateOptions::default();
+
+        let changed = vec!["a.rs".to_string()];
+        let report = analyze_duplicates_changed(dir.path(), &[], &options, &changed);
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].files, vec!["a.rs", "b.rs"]);
+
+        let changed = vec!["missing.rs".to_string()];
+        let report = analyze_duplicates_changed(dir.path(), &[], &options, &changed);
+        assert_eq!(report.files_scanned, 0);
+        assert_eq!(report.duplicate_count(), 0);
+        assert!(report.near_duplicates.is_empty());
+        assert_eq!(report.peak_memory_bytes, 0);
+    }
+
+    #[test]
+    fn test_analyze_duplicates_changed_reads_bounded_corpus() {
+        let dir = tempfile::tempdir().unwrap();
+        let body = "fn a() {\n    1\n}\nfn b() {\n    2\n}\n";
+        std::fs::write(dir.path().join("a.rs"), body).unwrap();
+        std::fs::write(dir.path().join("b.rs"), body).unwrap();
+        // 规范化后与 a.rs 相同，但大小相差很大；被读取的话会进入完全重复分组
+        let padded = body.replace('\n', &format!("{}\n", " ".repeat(200)));
+        std::fs::write(dir.path().join("c.rs"), padded).unwrap();
+        // 扩展名不同的文件不作为比较对象
+        std::fs::write(dir.path().join("d.py"), body).unwrap();
+        for i in 0..20 {
+            let other = format!("fn f{i}() {{\n    {i}\n}}\n").repeat(i + 3);
+            std::fs::write(dir.path().join(format!("other{i}.rs")), other).unwrap();
+        }
+        let changed = vec!["a.rs".to_string()];
+
+        let bounded = DuplicateOptions {
+            max_corpus_files: 1,
+            ..Default::default()
+        };
+        let report = analyze_duplicates_changed(dir.path(), &[], &bounded, &changed);
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.corpus_files, 1);
+        assert!(report.truncated);
+        // 只有大小最接近的 b.rs 被读取，c.rs 未被读取因而不在分组中
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].files, vec!["a.rs", "b.rs"]);
+
+        let report =
+            analyze_duplicates_changed(dir.path(), &[], &DuplicateOptions::default(), &changed);
+        assert_eq!(report.corpus_files, 22);
+        assert!(!report.truncated);
+        assert_eq!(report.groups[0].files, vec!["a.rs", "b.rs", "c.rs"]);
+    }
+
+    /// 生成 families 组文件，每组 variants 个仅有一行差异的变体，各组行数不同
+    fn synthetic_tree(root: &Path, families: usize, variants: usize) {
+        for f in 0..families {
+            let dir = root.join(format!("m{}", f % 16));
+            std::fs::create_dir_all(&dir).unwrap();
+            let lines = 20 + f % 50;
+            for v in 0..variants {
+                let body: Vec<String> = (0..lines)
+                    .map(|k| {
+                        if k == 0 {
+                            format!("// family {f} variant {v}")
+                        } else {
+                            format!("let f{f}_{k} = {k};")
+                        }
+                    })
+                    .collect();
+                std::fs::write(dir.join(format!("f{f}_v{v}.rs")), body.join("\n")).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_tree_memory_stays_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        synthetic_tree(dir.path(), 300, 5);
+
+        let generous = DuplicateOptions {
+            min_similarity: 0.9,
+            memory_budget: 4 * 1024 * 1024,
+            ..Default::default()
+        };
+        let report = analyze_duplicates_with(dir.path(), &[], &generous);
+        assert_eq!(report.files_scanned, 1500);
+        assert!(!report.truncated);
+        // 每组 5 个变体两两近似重复
+        assert_eq!(report.near_duplicates.len(), 300 * 10);
+        // 窗口化比较：驻留指纹远小于全部文件的指纹总量
+        let total: usize = (0..300).map(|f| (20 + f % 50) * 5 * 8).sum();
+        assert!(report.peak_memory_bytes < total / 2);
+
+        // 与全量读入内存的结果一致
+        let files: Vec<(String, String)> = super::super::collect_source_files(dir.path())
+            .iter()
+            .map(|p| {
+                (
+                    display_path(dir.path(), p),
+                    std::fs::read_to_string(p).unwrap(),
+                )
+            })
+            .collect();
+        let (in_memory, _) = find_near_duplicates(&files, 0.9);
+        assert_eq!(report.near_duplicates, in_memory);
+
+        // 预算不足以容纳一组变体时截断，但驻留量始终不超过预算
+        let tight = DuplicateOptions {
+            memory_budget: 1024,
+            ..generous
+        };
+        let truncated = analyze_duplicates_with(dir.path(), &[], &tight);
+        assert!(truncated.truncated);
+        assert!(truncated.peak_memory_bytes <= 1024);
+        assert!(truncated
+            .near_duplicates
+            .iter()
+            .all(|p| report.near_duplicates.contains(p)));
+    }
+}
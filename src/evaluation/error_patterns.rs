@@ -0,0 +1,328 @@
+// 错误处理一致性评估
+// 统计统一错误类型（GitAIError / crate Result）与遗留写法（Box<dyn Error>、anyhow）的使用情况
+
+use super::{collect_rust_files, display_path};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 统一错误类型的使用特征
+const UNIFIED_PATTERNS: &[&str] = &["GitAIError", "crate::Result<", "error::Result<"];
+/// Box<dyn Error> 写法的使用特征
+const BOXED_PATTERNS: &[&str] = &["Box<dyn std::error::Error", "Box<dyn Error"];
+/// anyhow 的使用特征
+const ANYHOW_PATTERNS: &[&str] = &["anyhow::Result", "anyhow::Error", "anyhow!("];
+
+/// 单个文件的错误处理写法统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileErrorPatterns {
+    pub path: String,
+    /// 统一错误类型的使用次数
+    pub unified: usize,
+    /// Box<dyn Error> 的使用次数
+    pub boxed: usize,
+    /// anyhow 的使用次数
+    pub anyhow: usize,
+}
+
+impl FileErrorPatterns {
+    /// 遗留写法的使用次数
+    pub fn legacy(&self) -> usize {
+        self.boxed + self.anyhow
+    }
+
+    /// 同一文件混用统一错误类型与遗留写法
+    pub fn is_inconsistent(&self) -> bool {
+        self.unified > 0 && self.legacy() > 0
+    }
+}
+
+/// 错误处理一致性报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorPatternReport {
+    pub root: String,
+    pub files_scanned: usize,
+    /// 统一错误类型的使用总数
+    pub unified_count: usize,
+    /// Box<dyn Error> 的使用总数
+    pub boxed_count: usize,
+    /// anyhow 的使用总数
+    pub anyhow_count: usize,
+    /// 统一错误类型在全部错误类型使用中的占比（无错误处理代码时为 1.0）
+    pub adoption_rate: f64,
+    /// 混用两种写法的文件
+    pub inconsistent_files: Vec<String>,
+    /// 涉及错误类型的文件明细（按路径排序）
+    pub files: Vec<FileErrorPatterns>,
+}
+
+impl ErrorPatternReport {
+    /// 由各文件统计汇总报告
+    pub fn from_files(root: &str, files_scanned: usize, files: Vec<FileErrorPatterns>) -> Self {
+        let mut files: Vec<FileErrorPatterns> = files
+            .into_iter()
+            .filter(|f| f.unified + f.legacy() > 0)
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let unified_count: usize = files.iter().map(|f| f.unified).sum();
+        let boxed_count: usize = files.iter().map(|f| f.boxed).sum();
+        let anyhow_count: usize = files.iter().map(|f| f.anyhow).sum();
+        let total = unified_count + boxed_count + anyhow_count;
+        let adoption_rate = if total == 0 {
+            1.0
+        } else {
+            unified_count as f64 / total as f64
+        };
+        let inconsistent_files = files
+            .iter()
+            .filter(|f| f.is_inconsistent())
+            .map(|f| f.path.clone())
+            .collect();
+
+        Self {
+            root: root.to_string(),
+            files_scanned,
+            unified_count,
+            boxed_count,
+            anyhow_count,
+            adoption_rate,
+            inconsistent_files,
+            files,
+        }
+    }
+
+    /// 仍在使用遗留写法的文件，按遗留使用次数降序
+    pub fn legacy_files(&self) -> Vec<&FileErrorPatterns> {
+        let mut legacy: Vec<&FileErrorPatterns> =
+            self.files.iter().filter(|f| f.legacy() > 0).collect();
+        legacy.sort_by(|a, b| b.legacy().cmp(&a.legacy()).then(a.path.cmp(&b.path)));
+        legacy
+    }
+
+    /// 按门禁阈值检查，返回未通过的原因（为空表示通过）
+    pub fn violations(&self, min_adoption: f64) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.adoption_rate < min_adoption {
+            violations.push(format!(
+                "统一错误类型采用率 {:.1}% 低于阈值 {:.1}%",
+                self.adoption_rate * 100.0,
+                min_adoption * 100.0
+            ));
+        }
+        if !self.inconsistent_files.is_empty() {
+            violations.push(format!(
+                "{} 个文件混用统一错误类型与 Box<dyn Error>/anyhow",
+                self.inconsistent_files.len()
+            ));
+        }
+        violations
+    }
+
+    /// 文本形式的报告
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "🧪 错误处理一致性: 采用率 {:.1}%（统一 {} / Box<dyn Error> {} / anyhow {}，扫描 {} 个文件）\n",
+            self.adoption_rate * 100.0,
+            self.unified_count,
+            self.boxed_count,
+            self.anyhow_count,
+            self.files_scanned
+        );
+        if !self.inconsistent_files.is_empty() {
+            out.push_str(&format!(
+                "\n混用写法的文件 {} 个:\n",
+                self.inconsistent_files.len()
+            ));
+            for path in &self.inconsistent_files {
+                out.push_str(&format!("  {path}\n"));
+            }
+        }
+        let legacy = self.legacy_files();
+        if !legacy.is_empty() {
+            out.push_str(&format!("\n仍使用遗留写法的文件 {} 个:\n", legacy.len()));
+            for f in legacy {
+                out.push_str(&format!(
+                    "  {} （Box<dyn Error> {}，anyhow {}）\n",
+                    f.path, f.boxed, f.anyhow
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// 统计单个文件内容中的错误处理写法
+///
+/// 注释、字符串字面量、`From` 转换实现（边界处的类型转换属于预期用法）
+/// 以及文件末尾的 `#[cfg(test)]` 测试模块不计入统计。
+pub fn count_error_patterns(path: &str, content: &str) -> FileErrorPatterns {
+    let mut stats = FileErrorPatterns {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    for line in content.lines() {
+        if line.starts_with("#[cfg(test)]") {
+            break;
+        }
+        let code = strip_string_literals(line);
+        let trimmed = code.trim_start();
+        if trimmed.starts_with("//")
+            || trimmed.starts_with('*')
+            || trimmed.starts_with("impl From<")
+            || trimmed.starts_with("fn from(")
+        {
+            continue;
+        }
+        stats.unified += count_matches(trimmed, UNIFIED_PATTERNS);
+        stats.boxed += count_matches(trimmed, BOXED_PATTERNS);
+        stats.anyhow += count_matches(trimmed, ANYHOW_PATTERNS);
+    }
+    stats
+}
+
+/// 去掉单行内的字符串字面量内容
+fn strip_string_literals(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for c in line.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                out.push(c);
+            }
+        } else {
+            // '"' 是字符字面量而不是字符串的开始
+            if c == '"' && prev != '\'' {
+                in_string = true;
+            }
+            out.push(c);
+        }
+        prev = c;
+    }
+    out
+}
+
+fn count_matches(line: &str, patterns: &[&str]) -> usize {
+    patterns.iter().map(|p| line.matches(p).count()).sum()
+}
+
+/// 扫描 root 下的 Rust 源文件并生成错误处理一致性报告
+pub fn analyze_error_patterns(root: &Path) -> ErrorPatternReport {
+    analyze(root, None)
+}
+
+/// 只统计变更的 Rust 文件（`changed` 为相对 root 的路径），采用率与混用文件均只反映这些文件
+pub fn analyze_error_patterns_changed(root: &Path, changed: &[String]) -> ErrorPatternReport {
+    analyze(root, Some(changed))
+}
+
+fn analyze(root: &Path, changed: Option<&[String]>) -> ErrorPatternReport {
+    let paths: Vec<(String, std::path::PathBuf)> = collect_rust_files(root)
+        .into_iter()
+        .map(|path| (display_path(root, &path), path))
+        .filter(|(rel, _)| changed.is_none_or(|changed| changed.contains(rel)))
+        .collect();
+    let files = paths
+        .iter()
+        .filter_map(|(rel, path)| {
+            let content = std::fs::read_to_string(path).ok()?;
+            Some(count_error_patterns(rel, &content))
+        })
+        .collect();
+    ErrorPatternReport::from_files(&root.display().to_string(), paths.len(), files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_error_patterns() {
+        let content = r#"
+use crate::error::GitAIError;
+// Box<dyn std::error::Error> 只出现在注释中
+const HINT: &str = "Box<dyn Error> 只出现在字符串中";
+const QUOTE: char = '"'; fn q() -> anyhow::Result<()> { Ok(()) }
+impl From<Box<dyn std::error::Error + Send + Sync>> for Wrapper {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self { todo!() }
+}
+fn a() -> Result<(), GitAIError> { Ok(()) }
+fn b() -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+fn c() -> anyhow::Result<()> { Ok(()) }
+"#;
+        let stats = count_error_patterns("src/lib.rs", content);
+        assert_eq!(stats.unified, 2);
+        assert_eq!(stats.boxed, 1);
+        assert_eq!(stats.anyhow, 2);
+        assert!(stats.is_inconsistent());
+
+        let with_tests = "fn a() -> Result<(), GitAIError> { Ok(()) }\n#[cfg(test)]\nmod tests {\n    fn b() -> anyhow::Result<()> { Ok(()) }\n}\n";
+        let stats = count_error_patterns("src/lib.rs", with_tests);
+        assert_eq!((stats.unified, stats.anyhow), (1, 0));
+    }
+
+    #[test]
+    fn test_report_adoption_and_violations() {
+        let report = ErrorPatternReport::from_files(
+            ".",
+            3,
+            vec![
+                FileErrorPatterns {
+                    path: "src/b.rs".to_string(),
+                    unified: 3,
+                    ..Default::default()
+                },
+                FileErrorPatterns {
+                    path: "src/a.rs".to_string(),
+                    unified: 1,
+                    boxed: 1,
+                    anyhow: 0,
+                },
+                FileErrorPatterns {
+                    path: "src/empty.rs".to_string(),
+                    ..Default::default()
+                },
+            ],
+        );
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].path, "src/a.rs");
+        assert!((report.adoption_rate - 0.8).abs() < 1e-9);
+        assert_eq!(report.inconsistent_files, vec!["src/a.rs"]);
+        assert_eq!(report.violations(0.5).len(), 1);
+        assert_eq!(report.violations(0.9).len(), 2);
+
+        let clean = ErrorPatternReport::from_files(".", 0, Vec::new());
+        assert_eq!(clean.adoption_rate, 1.0);
+        assert!(clean.violations(1.0).is_empty());
+    }
+
+    #[test]
+    fn test_analyze_error_patterns_walks_rust_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn a() -> Result<(), GitAIError> { Ok(()) }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("target/gen.rs"),
+            "fn b() -> Result<(), Box<dyn Error>> { Ok(()) }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.md"), "Box<dyn Error>").unwrap();
+
+        let report = analyze_error_patterns(dir.path());
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.files[0].path, "src/lib.rs");
+        assert_eq!(report.adoption_rate, 1.0);
+    }
+}
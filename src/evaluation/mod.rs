@@ -0,0 +1,157 @@
+// 工程一致性评估模块
+// 对项目源码做轻量的静态统计，结果可作为 CI 门禁（`gitai eval ...`）
+
+pub mod config_check;
+pub mod duplicates;
+pub mod error_patterns;
+pub mod quality;
+
+pub use config_check::{check_config_rs, ConfigCheckReport};
+pub use duplicates::{
+    analyze_duplicates, analyze_duplicates_changed, analyze_duplicates_with, find_near_duplicates,
+    scan_duplicates, DuplicateGroup, DuplicateOptions, DuplicateReport, NearDuplicatePair,
+};
+pub use error_patterns::{
+    analyze_error_patterns, analyze_error_patterns_changed, ErrorPatternReport, FileErrorPatterns,
+};
+pub use quality::{ComplexityLevel, QualityMetrics, QualityMetricsOptions};
+
+use crate::architectural_impact::graph_export::collect_files;
+use crate::utils::{glob, paths};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 一次评估的汇总结果（错误处理一致性 + 重复代码）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationSummary {
+    pub root: String,
+    pub error_patterns: ErrorPatternReport,
+    pub duplicates: DuplicateReport,
+}
+
+/// 对 root 下的全部源码做评估
+pub fn evaluate(root: &Path) -> EvaluationSummary {
+    EvaluationSummary {
+        root: root.display().to_string(),
+        error_patterns: analyze_error_patterns(root),
+        duplicates: analyze_duplicates_with(root, &[], &DuplicateOptions::default()),
+    }
+}
+
+/// 只对变更文件做评估，结构与 [`evaluate`] 相同
+///
+/// 作用域语义：
+/// - 错误处理统计只包含变更的 Rust 文件，采用率按这些文件计算；
+/// - 重复代码只读取变更文件与有上限的比较对象（见 [`analyze_duplicates_changed`]），
+///   只报告包含变更文件的完全重复分组，以及至少一侧为变更文件的近似重复文件对；
+/// - 两份报告的 `files_scanned` 均只统计变更文件，已删除或不在 root 下的路径被忽略。
+///
+/// `changed_paths` 可以是相对 root 的路径，也可以是位于 root 下的绝对路径。
+pub fn evaluate_changed(root: &Path, changed_paths: &[PathBuf]) -> EvaluationSummary {
+    let changed = normalize_changed(root, changed_paths);
+    EvaluationSummary {
+        root: root.display().to_string(),
+        error_patterns: analyze_error_patterns_changed(root, &changed),
+        duplicates: analyze_duplicates_changed(root, &[], &DuplicateOptions::default(), &changed),
+    }
+}
+
+/// 将变更路径统一为相对 root、以 `/` 分隔的形式
+///
+/// 先过滤再归一化：已删除（不存在或不是文件）以及不在 root 下的路径直接丢弃。
+/// 绝对路径与 root 都按规范化后的绝对路径比较，因此 root 为相对路径（如 `.`）时也能去掉前缀。
+pub fn normalize_changed(root: &Path, changed_paths: &[PathBuf]) -> Vec<String> {
+    let root_abs = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut changed: Vec<String> = changed_paths
+        .iter()
+        .filter_map(|path| {
+            let abs = if path.is_absolute() {
+                path.clone()
+            } else {
+                root_abs.join(path)
+            };
+            let abs = abs.canonicalize().ok().filter(|p| p.is_file())?;
+            let rel = paths::to_slash(abs.strip_prefix(&root_abs).ok()?);
+            (!rel.is_empty()).then_some(rel)
+        })
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+/// 收集 root 下的源码文件（跳过 target、.git 等目录），按路径排序
+pub(crate) fn collect_source_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if root.is_file() {
+        files.push(root.to_path_buf());
+    } else {
+        collect_files(root, &mut files);
+    }
+    files.sort();
+    files
+}
+
+/// 收集 root 下的 Rust 源文件
+pub(crate) fn collect_rust_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = collect_source_files(root);
+    files.retain(|p| p.extension().and_then(|e| e.to_str()) == Some("rs"));
+    files
+}
+
+/// 路径是否命中忽略列表（完全相同、位于列出的目录下，或匹配通配符模式）
+pub(crate) fn is_ignored(path: &str, ignore: &[String]) -> bool {
+    ignore.iter().any(|entry| {
+        if glob::is_glob(entry) {
+            return glob::glob_match(entry, path);
+        }
+        let entry = entry.trim_start_matches("./").trim_end_matches('/');
+        !entry.is_empty()
+            && (path == entry
+                || path
+                    .strip_prefix(entry)
+                    .is_some_and(|rest| rest.starts_with('/')))
+    })
+}
+
+/// 相对 root 的展示路径，统一使用 `/` 分隔
+pub(crate) fn display_path(root: &Path, path: &Path) -> String {
+    paths::relative_slash(root, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_changed_filters_and_strips_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("c.rs"), "fn c() {}\n").unwrap();
+
+        // root 未规范化时也能去掉绝对路径的前缀
+        let root = dir.path().join("src").join("..");
+        let changed = vec![
+            dir.path().join("src/a.rs"),
+            PathBuf::from("./b.rs"),
+            PathBuf::from("b.rs"),
+            PathBuf::from("deleted.rs"),
+            dir.path().join("src"),
+            outside.path().join("c.rs"),
+        ];
+        assert_eq!(normalize_changed(&root, &changed), vec!["b.rs", "src/a.rs"]);
+    }
+
+    #[test]
+    fn test_evaluate_changed_ignores_deleted_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let summary = evaluate_changed(dir.path(), &[dir.path().join("gone.rs")]);
+        assert_eq!(summary.error_patterns.files_scanned, 0);
+        assert_eq!(summary.duplicates.files_scanned, 0);
+    }
+}
@@ -0,0 +1,418 @@
+// 代码质量指标汇总
+// 由结构分析结果（函数级圈复杂度/认知复杂度）与重复代码评估统一推导质量指标，
+// 质量快照等使用方不再各自估算
+
+use super::DuplicateReport;
+use crate::tree_sitter::{FunctionInfo, StructuralSummary};
+use serde::{Deserialize, Serialize};
+
+/// 未统计 Halstead 体积的函数（如旧缓存结果）按每行代码的估计值计算（约 6 个词法单元 × log2(32)）
+const ESTIMATED_VOLUME_PER_LINE: f64 = 30.0;
+
+/// 质量指标推导选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QualityMetricsOptions<'a> {
+    /// 代码总行数；None 时按函数行数之和计算
+    pub lines_of_code: Option<usize>,
+    /// 重复代码评估结果；None 时重复率记为 0
+    pub duplicates: Option<&'a DuplicateReport>,
+    /// 逐文件的结构分析结果；非空时可维护性指数按文件计算后加权汇总，否则把 summary 视为一个文件
+    pub file_summaries: &'a [StructuralSummary],
+}
+
+/// 代码复杂度级别，由圈复杂度或认知复杂度按分段得到
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityLevel {
+    /// 非常复杂
+    VeryHigh,
+    /// 复杂
+    High,
+    /// 中等
+    Medium,
+    /// 简单
+    Low,
+    /// 非常简单
+    VeryLow,
+}
+
+impl ComplexityLevel {
+    /// 从圈复杂度值创建
+    pub fn from_cyclomatic(value: usize) -> Self {
+        match value {
+            0..=5 => ComplexityLevel::VeryLow,
+            6..=10 => ComplexityLevel::Low,
+            11..=20 => ComplexityLevel::Medium,
+            21..=50 => ComplexityLevel::High,
+            _ => ComplexityLevel::VeryHigh,
+        }
+    }
+
+    /// 从认知复杂度值创建
+    ///
+    /// 认知复杂度对嵌套逐层加权，同一函数的取值通常高于圈复杂度，因此分段更紧：
+    /// - 0..=4：非常简单
+    /// - 5..=9：简单
+    /// - 10..=15：中等（15 为常见的单函数上限）
+    /// - 16..=25：复杂
+    /// - 26 及以上：非常复杂
+    pub fn from_cognitive(value: usize) -> Self {
+        match value {
+            0..=4 => ComplexityLevel::VeryLow,
+            5..=9 => ComplexityLevel::Low,
+            10..=15 => ComplexityLevel::Medium,
+            16..=25 => ComplexityLevel::High,
+            _ => ComplexityLevel::VeryHigh,
+        }
+    }
+
+    /// 序列化名称（如 `very_low`），用于评审结果的 details
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplexityLevel::VeryHigh => "very_high",
+            ComplexityLevel::High => "high",
+            ComplexityLevel::Medium => "medium",
+            ComplexityLevel::Low => "low",
+            ComplexityLevel::VeryLow => "very_low",
+        }
+    }
+
+    /// 由 [`as_str`](Self::as_str) 的名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            ComplexityLevel::VeryHigh,
+            ComplexityLevel::High,
+            ComplexityLevel::Medium,
+            ComplexityLevel::Low,
+            ComplexityLevel::VeryLow,
+        ]
+        .into_iter()
+        .find(|level| level.as_str() == name)
+    }
+
+    /// 当前语言的级别名称
+    pub fn label(&self) -> &'static str {
+        crate::i18n::t(match self {
+            ComplexityLevel::VeryHigh => "complexity.very_high",
+            ComplexityLevel::High => "complexity.high",
+            ComplexityLevel::Medium => "complexity.medium",
+            ComplexityLevel::Low => "complexity.low",
+            ComplexityLevel::VeryLow => "complexity.very_low",
+        })
+    }
+}
+
+impl crate::domain::entities::Scorable for ComplexityLevel {
+    fn score(&self) -> f64 {
+        match self {
+            ComplexityLevel::VeryHigh => 1.0,
+            ComplexityLevel::High => 0.8,
+            ComplexityLevel::Medium => 0.5,
+            ComplexityLevel::Low => 0.3,
+            ComplexityLevel::VeryLow => 0.1,
+        }
+    }
+}
+
+/// 代码质量指标
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityMetrics {
+    /// 函数数量
+    pub function_count: usize,
+    /// 圈复杂度（所有函数之和）
+    pub cyclomatic_complexity: usize,
+    /// 平均圈复杂度
+    pub avg_cyclomatic_complexity: f64,
+    /// 最大圈复杂度
+    pub max_cyclomatic_complexity: usize,
+    /// 认知复杂度（所有函数之和）
+    pub cognitive_complexity: usize,
+    /// 最大认知复杂度
+    pub max_cognitive_complexity: usize,
+    /// 代码行数
+    pub lines_of_code: usize,
+    /// 重复代码百分比（重复文件数 / 扫描文件数）
+    pub duplication_percentage: f64,
+    /// 可维护性指数（0-100，越高越好）
+    pub maintainability_index: f64,
+}
+
+impl QualityMetrics {
+    /// 按最大圈复杂度划分的复杂度级别
+    pub fn cyclomatic_level(&self) -> ComplexityLevel {
+        ComplexityLevel::from_cyclomatic(self.max_cyclomatic_complexity)
+    }
+
+    /// 按最大认知复杂度划分的复杂度级别
+    pub fn cognitive_level(&self) -> ComplexityLevel {
+        ComplexityLevel::from_cognitive(self.max_cognitive_complexity)
+    }
+
+    /// 从结构分析结果推导质量指标
+    pub fn from_summary(summary: &StructuralSummary, options: &QualityMetricsOptions) -> Self {
+        let functions = &summary.functions;
+        let function_count = functions.len();
+        let cyclomatic: Vec<usize> = functions.iter().map(cyclomatic_of).collect();
+        let cyclomatic_complexity = cyclomatic.iter().sum();
+        let cognitive_complexity = functions.iter().map(|f| f.cognitive_complexity).sum();
+
+        let lines_of_code = options
+            .lines_of_code
+            .unwrap_or_else(|| functions.iter().map(function_lines).sum());
+
+        let duplication_percentage = options
+            .duplicates
+            .filter(|report| report.files_scanned > 0)
+            .map(|report| {
+                (report.duplicate_count() as f64 * 100.0 / report.files_scanned as f64).min(100.0)
+            })
+            .unwrap_or(0.0);
+
+        let maintainability_index = if options.file_summaries.is_empty() {
+            aggregate_maintainability(file_maintainability(summary))
+        } else {
+            aggregate_maintainability(
+                options
+                    .file_summaries
+                    .iter()
+                    .filter_map(file_maintainability),
+            )
+        };
+
+        Self {
+            function_count,
+            cyclomatic_complexity,
+            avg_cyclomatic_complexity: if function_count == 0 {
+                0.0
+            } else {
+                cyclomatic_complexity as f64 / function_count as f64
+            },
+            max_cyclomatic_complexity: cyclomatic.iter().max().copied().unwrap_or(0),
+            cognitive_complexity,
+            max_cognitive_complexity: functions
+                .iter()
+                .map(|f| f.cognitive_complexity)
+                .max()
+                .unwrap_or(0),
+            lines_of_code,
+            duplication_percentage,
+            maintainability_index,
+        }
+    }
+}
+
+/// 可维护性指数（经典公式，归一化到 0-100）：
+///
+/// `MI = max(0, (171 - 5.2·ln(V) - 0.23·CC - 16.2·ln(LOC)) × 100 / 171)`
+///
+/// - V：Halstead 体积，由结构分析按语法树词法单元统计（见 [`crate::tree_sitter::complexity`]）
+/// - CC：圈复杂度
+/// - LOC：代码行数
+///
+/// 一般认为 85 以上易于维护，65 以下难以维护
+pub fn maintainability_index(halstead_volume: f64, cyclomatic: usize, lines: usize) -> f64 {
+    let volume = halstead_volume.max(1.0);
+    let lines = lines.max(1) as f64;
+    let raw = 171.0 - 5.2 * volume.ln() - 0.23 * cyclomatic as f64 - 16.2 * lines.ln();
+    (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+/// 单个文件的可维护性指数与行数：V、CC、LOC 取文件内全部函数之和；没有函数时为 None
+fn file_maintainability(summary: &StructuralSummary) -> Option<(f64, usize)> {
+    if summary.functions.is_empty() {
+        return None;
+    }
+    let mut volume = 0.0;
+    let mut cyclomatic = 0;
+    let mut lines = 0;
+    for function in &summary.functions {
+        let function_lines = function_lines(function);
+        volume += if function.halstead_volume > 0.0 {
+            function.halstead_volume
+        } else {
+            function_lines as f64 * ESTIMATED_VOLUME_PER_LINE
+        };
+        cyclomatic += cyclomatic_of(function);
+        lines += function_lines;
+    }
+    Some((maintainability_index(volume, cyclomatic, lines), lines))
+}
+
+/// 按行数加权汇总各文件的可维护性指数；没有可计算的文件时视为完全可维护
+fn aggregate_maintainability(files: impl IntoIterator<Item = (f64, usize)>) -> f64 {
+    let (weighted, lines) = files
+        .into_iter()
+        .fold((0.0, 0usize), |(weighted, total), (index, lines)| {
+            (weighted + index * lines as f64, total + lines)
+        });
+    if lines == 0 {
+        100.0
+    } else {
+        weighted / lines as f64
+    }
+}
+
+/// 未计算复杂度的函数（如旧缓存结果）按最低值 1 计
+fn cyclomatic_of(function: &FunctionInfo) -> usize {
+    function.cyclomatic_complexity.max(1)
+}
+
+fn function_lines(function: &FunctionInfo) -> usize {
+    function.line_end.saturating_sub(function.line_start) + 1
+}
+
+#[cfg(all(test, feature = "tree-sitter-rust"))]
+mod tests {
+    use super::*;
+    use crate::evaluation::DuplicateGroup;
+    use crate::tree_sitter::{SupportedLanguage, TreeSitterManager};
+
+    const FIXTURE: &str = r#"
+pub fn parse(input: &str) -> Option<u32> {
+    if input.is_empty() || input.len() > 10 {
+        return None;
+    }
+    let mut value = 0;
+    for c in input.chars() {
+        if let Some(d) = c.to_digit(10) {
+            value = value * 10 + d;
+        } else {
+            return None;
+        }
+    }
+    Some(value)
+}
+
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+"#;
+
+    #[tokio::test]
+    async fn test_from_summary_fixture() {
+        let mut manager = TreeSitterManager::new().await.unwrap();
+        let summary = manager
+            .analyze_structure(FIXTURE, SupportedLanguage::Rust)
+            .unwrap();
+        let parse = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "parse")
+            .unwrap();
+        // 1 + if + || + for + if let
+        assert_eq!(parse.cyclomatic_complexity, 5);
+        // if 1 + || 1 + for 1 + if let 2（嵌套 1）+ else 1
+        assert_eq!(parse.cognitive_complexity, 6);
+
+        let duplicates = DuplicateReport {
+            files_scanned: 4,
+            groups: vec![DuplicateGroup {
+                hash: "h".to_string(),
+                lines: 10,
+                files: vec!["a.rs".to_string(), "b.rs".to_string()],
+            }],
+            ..Default::default()
+        };
+        let options = QualityMetricsOptions {
+            lines_of_code: Some(120),
+            duplicates: Some(&duplicates),
+            ..Default::default()
+        };
+        let metrics = QualityMetrics::from_summary(&summary, &options);
+        assert_eq!(metrics.function_count, 2);
+        assert_eq!(metrics.cyclomatic_complexity, 6);
+        assert_eq!(metrics.max_cyclomatic_complexity, 5);
+        assert!((metrics.avg_cyclomatic_complexity - 3.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.cognitive_complexity, 6);
+        assert_eq!(metrics.max_cognitive_complexity, 6);
+        assert_eq!(metrics.cognitive_level(), ComplexityLevel::Low);
+        assert_eq!(metrics.cyclomatic_level(), ComplexityLevel::VeryLow);
+        assert_eq!(metrics.lines_of_code, 120);
+        assert!((metrics.duplication_percentage - 25.0).abs() < f64::EPSILON);
+        // 文件级 V、CC、LOC 均取两个函数之和
+        assert!(parse.halstead_volume > 0.0);
+        let volume: f64 = summary.functions.iter().map(|f| f.halstead_volume).sum();
+        assert!(
+            (metrics.maintainability_index - maintainability_index(volume, 6, 17)).abs() < 1e-9
+        );
+
+        // 未提供行数与重复报告时按函数行数计算，重复率为 0
+        let metrics = QualityMetrics::from_summary(&summary, &QualityMetricsOptions::default());
+        assert_eq!(metrics.lines_of_code, 14 + 3);
+        assert_eq!(metrics.duplication_percentage, 0.0);
+
+        let empty = QualityMetrics::from_summary(
+            &StructuralSummary::default(),
+            &QualityMetricsOptions::default(),
+        );
+        assert_eq!(empty.maintainability_index, 100.0);
+        assert_eq!(empty.avg_cyclomatic_complexity, 0.0);
+    }
+
+    #[test]
+    fn test_complexity_level_bands() {
+        for (value, level) in [
+            (0, ComplexityLevel::VeryLow),
+            (4, ComplexityLevel::VeryLow),
+            (5, ComplexityLevel::Low),
+            (9, ComplexityLevel::Low),
+            (10, ComplexityLevel::Medium),
+            (15, ComplexityLevel::Medium),
+            (16, ComplexityLevel::High),
+            (25, ComplexityLevel::High),
+            (26, ComplexityLevel::VeryHigh),
+        ] {
+            assert_eq!(
+                ComplexityLevel::from_cognitive(value),
+                level,
+                "value {value}"
+            );
+            assert_eq!(ComplexityLevel::parse(level.as_str()), Some(level));
+        }
+        assert_eq!(ComplexityLevel::from_cyclomatic(10), ComplexityLevel::Low);
+        assert_eq!(
+            ComplexityLevel::from_cyclomatic(11),
+            ComplexityLevel::Medium
+        );
+        assert_eq!(ComplexityLevel::parse("unknown"), None);
+    }
+
+    #[tokio::test]
+    async fn test_maintainability_aggregates_files_by_lines() {
+        let mut manager = TreeSitterManager::new().await.unwrap();
+        let parser_file = manager
+            .analyze_structure(FIXTURE, SupportedLanguage::Rust)
+            .unwrap();
+        let tiny_file = manager
+            .analyze_structure("fn one() -> u8 { 1 }", SupportedLanguage::Rust)
+            .unwrap();
+        let (parser_index, parser_lines) = file_maintainability(&parser_file).unwrap();
+        let (tiny_index, tiny_lines) = file_maintainability(&tiny_file).unwrap();
+        assert_eq!((parser_lines, tiny_lines), (17, 1));
+        assert!(tiny_index > parser_index);
+
+        let mut merged = parser_file.clone();
+        merged.functions.extend(tiny_file.functions.clone());
+        let files = [parser_file, tiny_file];
+        let options = QualityMetricsOptions {
+            file_summaries: &files,
+            ..Default::default()
+        };
+        let metrics = QualityMetrics::from_summary(&merged, &options);
+        let expected = (parser_index * 17.0 + tiny_index) / 18.0;
+        assert!((metrics.maintainability_index - expected).abs() < 1e-9);
+
+        // 没有函数的文件不参与汇总
+        assert!(file_maintainability(&StructuralSummary::default()).is_none());
+        assert_eq!(aggregate_maintainability(Vec::new()), 100.0);
+    }
+
+    #[test]
+    fn test_maintainability_index_bounds() {
+        // 单行、无分支的函数接近满分
+        assert!(maintainability_index(1.0, 1, 1) > 99.0);
+        // 复杂度与体积越大，指数越低
+        assert!(maintainability_index(3000.0, 20, 100) < maintainability_index(300.0, 2, 10));
+        assert_eq!(maintainability_index(1e30, 500, 100_000), 0.0);
+    }
+}
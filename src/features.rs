@@ -1,10 +1,11 @@
 // 功能检测模块
 // 提供运行时功能检测和报告
 
+use serde::Serialize;
 use std::collections::HashMap;
 
 /// 功能信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FeatureInfo {
     pub name: &'static str,
     pub enabled: bool,
@@ -147,13 +148,80 @@ pub struct FeatureSummary {
     pub by_category: HashMap<String, Vec<FeatureInfo>>,
 }
 
+/// 构建信息：版本、构建目标与编译进来的可选功能
+/// （`gitai features --format json` 的输出，反馈问题时可附带精确的构建配置）
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// 构建目标（架构-系统[-环境]），如 `x86_64-linux-gnu`
+    pub target: String,
+    /// `debug` 或 `release`
+    pub profile: &'static str,
+    /// 已启用的功能名
+    pub enabled_features: Vec<&'static str>,
+    pub features: Vec<FeatureInfo>,
+    pub summary: FeatureCounts,
+}
+
+/// 功能数量统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeatureCounts {
+    pub total: usize,
+    pub enabled: usize,
+    pub disabled: usize,
+}
+
+/// 获取当前二进制的构建信息
+pub fn build_info() -> BuildInfo {
+    let features = get_features();
+    let enabled = features.iter().filter(|f| f.enabled).count();
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        target: build_target(),
+        profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        },
+        enabled_features: features
+            .iter()
+            .filter(|f| f.enabled)
+            .map(|f| f.name)
+            .collect(),
+        summary: FeatureCounts {
+            total: features.len(),
+            enabled,
+            disabled: features.len() - enabled,
+        },
+        features,
+    }
+}
+
+fn build_target() -> String {
+    let env = if cfg!(target_env = "gnu") {
+        Some("gnu")
+    } else if cfg!(target_env = "musl") {
+        Some("musl")
+    } else if cfg!(target_env = "msvc") {
+        Some("msvc")
+    } else {
+        None
+    };
+    let mut target = format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+    if let Some(env) = env {
+        target.push('-');
+        target.push_str(env);
+    }
+    target
+}
+
 /// 显示功能报告
 pub fn display_features(format: &str) {
     let features = get_features();
     let summary = get_feature_summary();
 
     match format {
-        "json" => display_json(&features),
+        "json" => display_json(),
         "table" => display_table(&features, &summary),
         _ => display_default(&features, &summary),
     }
@@ -229,14 +297,13 @@ fn display_table(features: &[FeatureInfo], summary: &FeatureSummary) {
         } else {
             "\x1b[31m"
         };
-        let reset = "\x1b[0m";
+        // 先按宽度补齐再着色，避免转义序列影响对齐
+        let status = format!("{status:<7}");
 
         println!(
-            "│ {:<19} │ {}{:<7}{} │ {:<8} │ {:<34} │",
+            "│ {:<19} │ {} │ {:<8} │ {:<34} │",
             feature.name,
-            status_color,
-            status,
-            reset,
+            crate::utils::output::paint(&status, status_color),
             feature.category,
             truncate_string(feature.description, 34)
         );
@@ -247,31 +314,11 @@ fn display_table(features: &[FeatureInfo], summary: &FeatureSummary) {
     println!("总计: {}/{} 功能已启用", summary.enabled, summary.total);
 }
 
-fn display_json(features: &[FeatureInfo]) {
-    use serde_json::json;
-
-    let json_features: Vec<_> = features
-        .iter()
-        .map(|f| {
-            json!({
-                "name": f.name,
-                "enabled": f.enabled,
-                "description": f.description,
-                "category": f.category,
-            })
-        })
-        .collect();
-
-    let output = json!({
-        "features": json_features,
-        "summary": {
-            "total": features.len(),
-            "enabled": features.iter().filter(|f| f.enabled).count(),
-            "disabled": features.iter().filter(|f| !f.enabled).count(),
-        }
-    });
-
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+fn display_json() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&build_info()).unwrap_or_default()
+    );
 }
 
 fn truncate_string(s: &str, max_len: usize) -> String {
@@ -338,6 +385,31 @@ mod tests {
         assert_eq!(summary.total, summary.enabled + summary.disabled);
     }
 
+    #[test]
+    fn test_build_info_json() {
+        let json = serde_json::to_value(build_info()).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["target"]
+            .as_str()
+            .unwrap()
+            .starts_with(std::env::consts::ARCH));
+        let enabled: Vec<&str> = json["enabled_features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(enabled.contains(&"core"));
+        assert_eq!(enabled.contains(&"ai"), cfg!(feature = "ai"));
+        assert_eq!(
+            enabled.contains(&"tree-sitter-rust"),
+            cfg!(feature = "tree-sitter-rust")
+        );
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(json["summary"]["total"], features.len());
+        assert_eq!(json["summary"]["enabled"], enabled.len());
+    }
+
     #[test]
     fn test_version_info() {
         let version = get_version_info();
@@ -1,4 +1,6 @@
-use std::path::Path;
+use crate::error::{GitAIError, GitError};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// 简化的Git命令处理（禁用pager，保证非交互输出稳定）
@@ -18,6 +20,28 @@ pub fn run_git(args: &[String]) -> Result<String, Box<dyn std::error::Error + Se
     Ok(stdout.to_string())
 }
 
+/// 在指定目录中运行Git（`git -C dir`），不切换进程工作目录
+pub fn run_git_in(
+    dir: &Path,
+    args: &[String],
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    run_git(&with_dir(
+        dir,
+        &args.iter().map(String::as_str).collect::<Vec<_>>(),
+    ))
+}
+
+/// 在指定目录中运行Git并捕获退出码、stdout、stderr
+pub fn run_git_capture_in(
+    dir: &Path,
+    args: &[String],
+) -> std::io::Result<(Option<i32>, String, String)> {
+    run_git_capture(&with_dir(
+        dir,
+        &args.iter().map(String::as_str).collect::<Vec<_>>(),
+    ))
+}
+
 /// 运行Git并捕获退出码、stdout、stderr（不因非零退出中断），禁用pager
 pub fn run_git_capture(args: &[String]) -> std::io::Result<(Option<i32>, String, String)> {
     let output = Command::new("git")
@@ -32,21 +56,102 @@ pub fn run_git_capture(args: &[String]) -> std::io::Result<(Option<i32>, String,
 
 /// 获取Git diff
 pub fn get_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    run_git(&["diff".to_string(), "--cached".to_string()])
+    get_diff_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库已暂存的 diff
+pub fn get_diff_in(dir: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    run_git_in(dir, &["diff".to_string(), "--cached".to_string()])
 }
 
 /// 获取所有变更（包括工作区、暂存区、未跟踪文件和未推送的提交）
 pub fn get_all_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_all_diff_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库的所有变更（包括工作区、暂存区、未跟踪文件和未推送的提交）
+pub fn get_all_diff_in(dir: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_work_tree_in(dir, "获取工作区变更")?;
     // Git 的 diff 命令不会包含未跟踪文件；我们将专门收集未跟踪（且未被 .gitignore 忽略）的文件
-    let staged_diff = run_git(&["diff".to_string(), "--cached".to_string()]).unwrap_or_default();
-    let unstaged_diff = run_git(&["diff".to_string()]).unwrap_or_default();
+    let staged_diff =
+        run_git_in(dir, &["diff".to_string(), "--cached".to_string()]).unwrap_or_default();
+    let unstaged_diff = run_git_in(dir, &["diff".to_string()]).unwrap_or_default();
 
     // 检查未推送的提交
-    let unpushed_diff = get_unpushed_diff().unwrap_or_default();
+    let unpushed_diff = get_unpushed_diff_in(dir).unwrap_or_default();
 
     // 收集未跟踪文件并为其生成 diff
+    let untracked_section = untracked_files_diff_in(dir);
+
+    let mut all_diff = String::new();
+
+    // 优先级：未推送的提交 > 已暂存的变更 > 未暂存的变更 > 未跟踪文件
+    if !unpushed_diff.trim().is_empty() {
+        all_diff.push_str("## 未推送的提交变更 (Unpushed Commits):\n");
+        all_diff.push_str(&unpushed_diff);
+        all_diff.push('\n');
+    }
+
+    if !staged_diff.trim().is_empty() {
+        all_diff.push_str("## 已暂存的变更 (Staged Changes):\n");
+        all_diff.push_str(&staged_diff);
+        all_diff.push('\n');
+    }
+
+    if !unstaged_diff.trim().is_empty() {
+        all_diff.push_str("## 未暂存的变更 (Unstaged Changes):\n");
+        all_diff.push_str(&unstaged_diff);
+        if !all_diff.ends_with('\n') {
+            all_diff.push('\n');
+        }
+    }
+
+    if !untracked_section.trim().is_empty() {
+        all_diff.push_str(&untracked_section);
+    }
+
+    // 如果没有任何变更，不要自动返回最后一次提交
+    // 让调用方决定如何处理这种情况
+    if all_diff.trim().is_empty() {
+        return Err("没有检测到任何变更".into());
+    }
+
+    Ok(all_diff)
+}
+
+/// 获取工作区中全部未提交的变更（已暂存 + 未暂存 + 未跟踪），不包含未推送的提交
+pub fn get_working_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_working_diff_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库工作区中全部未提交的变更，不包含未推送的提交
+pub fn get_working_diff_in(dir: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_work_tree_in(dir, "获取工作区变更")?;
+    let staged_diff =
+        run_git_in(dir, &["diff".to_string(), "--cached".to_string()]).unwrap_or_default();
+    let unstaged_diff = run_git_in(dir, &["diff".to_string()]).unwrap_or_default();
+
+    let mut working_diff = String::new();
+    if !staged_diff.trim().is_empty() {
+        working_diff.push_str("## 已暂存的变更 (Staged Changes):\n");
+        working_diff.push_str(&staged_diff);
+        working_diff.push('\n');
+    }
+    if !unstaged_diff.trim().is_empty() {
+        working_diff.push_str("## 未暂存的变更 (Unstaged Changes):\n");
+        working_diff.push_str(&unstaged_diff);
+        if !working_diff.ends_with('\n') {
+            working_diff.push('\n');
+        }
+    }
+    working_diff.push_str(&untracked_files_diff_in(dir));
+    Ok(working_diff)
+}
+
+/// 为 dir 所在仓库中未跟踪（且未被 .gitignore 忽略）的文件生成 diff 段落，没有时返回空字符串
+fn untracked_files_diff_in(dir: &Path) -> String {
     let mut untracked_section = String::new();
-    if let Ok(untracked) = get_untracked_files() {
+    if let Ok(untracked) = get_untracked_files_in(dir) {
         if !untracked.is_empty() {
             let mut combined = String::new();
             // 过滤过大的或二进制/资产类文件，避免生成巨大的 diff
@@ -61,7 +166,7 @@ pub fn get_all_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>
                 let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
                 let mut should_skip_content = skip_exts.iter().any(|e| e.eq_ignore_ascii_case(ext));
                 let mut size_info = String::new();
-                if let Ok(meta) = std::fs::metadata(path) {
+                if let Ok(meta) = std::fs::metadata(dir.join(path)) {
                     let len = meta.len();
                     if len > MAX_INLINE_SIZE {
                         should_skip_content = true;
@@ -77,13 +182,16 @@ pub fn get_all_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>
                     continue;
                 }
 
-                if let Ok((code, stdout, _stderr)) = run_git_capture(&[
-                    "diff".to_string(),
-                    "--no-index".to_string(),
-                    "--".to_string(),
-                    "/dev/null".to_string(),
-                    p.clone(),
-                ]) {
+                if let Ok((code, stdout, _stderr)) = run_git_capture_in(
+                    dir,
+                    &[
+                        "diff".to_string(),
+                        "--no-index".to_string(),
+                        "--".to_string(),
+                        "/dev/null".to_string(),
+                        p.clone(),
+                    ],
+                ) {
                     // exit code 1 表示存在差异，这是预期情况
                     if (code.is_none() || code == Some(1) || code == Some(0))
                         && !stdout.trim().is_empty()
@@ -104,63 +212,512 @@ pub fn get_all_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>
             }
         }
     }
+    untracked_section
+}
 
-    let mut all_diff = String::new();
+/// 检查是否有未暂存的变更
+pub fn has_unstaged_changes() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    has_unstaged_changes_in(Path::new("."))
+}
 
-    // 优先级：未推送的提交 > 已暂存的变更 > 未暂存的变更 > 未跟踪文件
-    if !unpushed_diff.trim().is_empty() {
-        all_diff.push_str("## 未推送的提交变更 (Unpushed Commits):\n");
-        all_diff.push_str(&unpushed_diff);
-        all_diff.push('\n');
+/// 检查 dir 所在仓库是否有未暂存的变更
+pub fn has_unstaged_changes_in(
+    dir: &Path,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let output = run_git_in(dir, &["diff".to_string(), "--name-only".to_string()])?;
+    Ok(!output.trim().is_empty())
+}
+
+/// 检查是否有已暂存的变更
+pub fn has_staged_changes() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    has_staged_changes_in(Path::new("."))
+}
+
+/// 检查 dir 所在仓库是否有已暂存的变更
+pub fn has_staged_changes_in(dir: &Path) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let output = run_git_in(
+        dir,
+        &[
+            "diff".to_string(),
+            "--cached".to_string(),
+            "--name-only".to_string(),
+        ],
+    )?;
+    Ok(!output.trim().is_empty())
+}
+
+/// 获取Git状态
+#[allow(dead_code)]
+pub fn get_status() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    run_git(&["status".to_string(), "--porcelain".to_string()])
+}
+
+/// `git status --porcelain` 中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    /// 文件路径（相对仓库根目录）
+    pub path: String,
+    /// 重命名/复制前的路径
+    pub orig_path: Option<String>,
+    /// 暂存区状态码（X）
+    pub index: char,
+    /// 工作区状态码（Y）
+    pub worktree: char,
+}
+
+impl StatusEntry {
+    /// 是否为未跟踪文件
+    pub fn is_untracked(&self) -> bool {
+        self.index == '?'
     }
 
-    if !staged_diff.trim().is_empty() {
-        all_diff.push_str("## 已暂存的变更 (Staged Changes):\n");
-        all_diff.push_str(&staged_diff);
-        all_diff.push('\n');
+    /// 是否有已暂存的变更
+    pub fn is_staged(&self) -> bool {
+        !matches!(self.index, ' ' | '?' | '!')
     }
 
-    if !unstaged_diff.trim().is_empty() {
-        all_diff.push_str("## 未暂存的变更 (Unstaged Changes):\n");
-        all_diff.push_str(&unstaged_diff);
-        if !all_diff.ends_with('\n') {
-            all_diff.push('\n');
+    /// 是否有未暂存的变更
+    pub fn is_unstaged(&self) -> bool {
+        !matches!(self.worktree, ' ' | '?' | '!')
+    }
+}
+
+/// 工作区状态
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStatus {
+    pub entries: Vec<StatusEntry>,
+}
+
+impl GitStatus {
+    /// 工作区是否干净（没有任何变更或未跟踪文件）
+    pub fn is_clean(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 已暂存变更的文件
+    pub fn staged(&self) -> impl Iterator<Item = &StatusEntry> {
+        self.entries.iter().filter(|e| e.is_staged())
+    }
+
+    /// 未暂存变更的文件
+    pub fn unstaged(&self) -> impl Iterator<Item = &StatusEntry> {
+        self.entries.iter().filter(|e| e.is_unstaged())
+    }
+
+    /// 未跟踪的文件
+    pub fn untracked(&self) -> impl Iterator<Item = &StatusEntry> {
+        self.entries.iter().filter(|e| e.is_untracked())
+    }
+}
+
+/// 获取当前目录所在仓库的工作区状态
+pub fn status() -> Result<GitStatus, Box<dyn std::error::Error + Send + Sync>> {
+    status_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库的工作区状态
+pub fn status_in(dir: &Path) -> Result<GitStatus, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_work_tree_in(dir, "git status")?;
+    let output = run_git(&with_dir(dir, &["status", "--porcelain=v1", "-z"]))?;
+    Ok(parse_status_z(&output))
+}
+
+/// 解析 `git status --porcelain=v1 -z`：每条记录为 `XY path\0`，重命名/复制后跟 `orig\0`
+fn parse_status_z(output: &str) -> GitStatus {
+    let mut entries = Vec::new();
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    while let Some(record) = fields.next() {
+        let mut chars = record.chars();
+        let (Some(index), Some(worktree)) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        let path = record.get(3..).unwrap_or_default().to_string();
+        let orig_path = if matches!(index, 'R' | 'C') {
+            fields.next().map(|f| f.to_string())
+        } else {
+            None
+        };
+        entries.push(StatusEntry {
+            path,
+            orig_path,
+            index,
+            worktree,
+        });
+    }
+    GitStatus { entries }
+}
+
+/// 变更类型（`git diff --name-status` 的状态字母）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Other,
+}
+
+impl ChangeKind {
+    fn from_status(status: &str) -> Self {
+        match status.chars().next() {
+            Some('A') => Self::Added,
+            Some('M') => Self::Modified,
+            Some('D') => Self::Deleted,
+            Some('R') => Self::Renamed,
+            Some('C') => Self::Copied,
+            Some('T') => Self::TypeChanged,
+            _ => Self::Other,
         }
     }
+}
 
-    if !untracked_section.trim().is_empty() {
-        all_diff.push_str(&untracked_section);
+/// diff 中的一个变更文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// 变更后的路径（相对仓库根目录）
+    pub path: String,
+    /// 重命名/复制前的路径
+    pub old_path: Option<String>,
+    pub kind: ChangeKind,
+}
+
+/// 获取当前目录所在仓库相对 HEAD 的已跟踪变更（已暂存 + 未暂存，不含未跟踪文件）
+pub fn diff_changed_files() -> Result<Vec<FileChange>, Box<dyn std::error::Error + Send + Sync>> {
+    diff_changed_files_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库相对 HEAD 的已跟踪变更；仓库尚无提交时返回已暂存的变更
+pub fn diff_changed_files_in(
+    dir: &Path,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_work_tree_in(dir, "获取工作区变更文件")?;
+    let base = if rev_parse_in(dir, "HEAD").is_some() {
+        "HEAD"
+    } else {
+        "--cached"
+    };
+    let output = run_git(&with_dir(dir, &["diff", "--name-status", "-M", "-z", base]))?;
+    Ok(parse_name_status_z(&output))
+}
+
+/// 解析 `git diff --name-status -z`：`status\0path\0`，重命名/复制为 `status\0old\0new\0`
+fn parse_name_status_z(output: &str) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    while let Some(status) = fields.next() {
+        let kind = ChangeKind::from_status(status);
+        let Some(first) = fields.next() else {
+            break;
+        };
+        let change = if matches!(kind, ChangeKind::Renamed | ChangeKind::Copied) {
+            let Some(path) = fields.next() else {
+                break;
+            };
+            FileChange {
+                path: path.to_string(),
+                old_path: Some(first.to_string()),
+                kind,
+            }
+        } else {
+            FileChange {
+                path: first.to_string(),
+                old_path: None,
+                kind,
+            }
+        };
+        changes.push(change);
     }
+    changes
+}
 
-    // 如果没有任何变更，不要自动返回最后一次提交
-    // 让调用方决定如何处理这种情况
-    if all_diff.trim().is_empty() {
-        return Err("没有检测到任何变更".into());
+/// 获取当前提交的完整 hash；仓库尚无提交或不在 Git 仓库中时返回 None
+pub fn current_commit() -> Option<String> {
+    rev_parse_in(Path::new("."), "HEAD")
+}
+
+/// 获取当前目录所在仓库的提交信息，base_ref 为对比的基准提交（如 `HEAD~1`）
+pub fn git_info(
+    base_ref: &str,
+) -> Result<crate::architectural_impact::GitInfo, Box<dyn std::error::Error + Send + Sync>> {
+    git_info_in(Path::new("."), base_ref)
+}
+
+/// 获取 dir 所在仓库的提交信息，base_ref 为对比的基准提交（如 `HEAD~1`）
+pub fn git_info_in(
+    dir: &Path,
+    base_ref: &str,
+) -> Result<crate::architectural_impact::GitInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let current_commit = rev_parse_in(dir, "HEAD").ok_or("无法获取当前提交hash")?;
+    let base_commit =
+        rev_parse_in(dir, base_ref).ok_or_else(|| format!("无法获取提交hash: {base_ref}"))?;
+    let branch = run_git(&with_dir(dir, &["branch", "--show-current"]))
+        .ok()
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+    Ok(crate::architectural_impact::GitInfo {
+        current_commit,
+        base_commit,
+        branch,
+    })
+}
+
+/// 在 dir 中解析引用为完整提交 hash
+fn rev_parse_in(dir: &Path, rev: &str) -> Option<String> {
+    let rev = format!("{rev}^{{commit}}");
+    run_git(&with_dir(dir, &["rev-parse", "--verify", "--quiet", &rev]))
+        .ok()
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+}
+
+/// 变更行的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineChangeKind {
+    /// 新增行，行号属于新版本
+    Added,
+    /// 删除行，行号属于旧版本
+    Removed,
+}
+
+/// diff 中连续变更的行号区间（闭区间，行号从 1 开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineRange {
+    pub kind: LineChangeKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    /// 区间是否包含某一行
+    pub fn contains(&self, line: usize) -> bool {
+        (self.start..=self.end).contains(&line)
     }
+}
 
-    Ok(all_diff)
+/// 获取当前目录所在仓库 base 与 head 之间各文件的变更行区间；head 为 None 时对比工作区
+pub fn diff_hunks(
+    base: &str,
+    head: Option<&str>,
+) -> Result<HashMap<PathBuf, Vec<LineRange>>, Box<dyn std::error::Error + Send + Sync>> {
+    diff_hunks_in(Path::new("."), base, head)
 }
 
-/// 检查是否有未暂存的变更
-pub fn has_unstaged_changes() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let output = run_git(&["diff".to_string(), "--name-only".to_string()])?;
-    Ok(!output.trim().is_empty())
+/// 获取 dir 所在仓库 base 与 head 之间各文件的变更行区间；head 为 None 时对比工作区
+pub fn diff_hunks_in(
+    dir: &Path,
+    base: &str,
+    head: Option<&str>,
+) -> Result<HashMap<PathBuf, Vec<LineRange>>, Box<dyn std::error::Error + Send + Sync>> {
+    if head.is_none() {
+        ensure_work_tree_in(dir, "对比工作区的 diff")?;
+    }
+    let mut args = vec![
+        "-c",
+        "core.quotepath=off",
+        "diff",
+        "--no-color",
+        "--no-ext-diff",
+        "-M",
+        "-U0",
+        base,
+    ];
+    args.extend(head);
+    let diff = run_git(&with_dir(dir, &args))?;
+    Ok(parse_diff_hunks(&diff))
 }
 
-/// 检查是否有已暂存的变更
-pub fn has_staged_changes() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let output = run_git(&[
-        "diff".to_string(),
-        "--cached".to_string(),
-        "--name-only".to_string(),
-    ])?;
-    Ok(!output.trim().is_empty())
+/// 解析 unified diff 为各文件的变更行区间
+///
+/// 文件以新路径为键（删除的文件以旧路径为键）；纯重命名与二进制文件没有行区间，值为空列表
+pub fn parse_diff_hunks(diff: &str) -> HashMap<PathBuf, Vec<LineRange>> {
+    let mut files: HashMap<PathBuf, Vec<LineRange>> = HashMap::new();
+    let mut current: Option<PathBuf> = None;
+    let mut old_path: Option<String> = None;
+    // 当前 hunk 剩余的旧/新行数与下一行的行号
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+    let (mut old_line, mut new_line) = (0usize, 0usize);
+
+    for line in diff.lines() {
+        if old_left > 0 || new_left > 0 {
+            let Some(path) = &current else {
+                continue;
+            };
+            let ranges = files.entry(path.clone()).or_default();
+            match line.chars().next() {
+                Some('+') => {
+                    push_range(ranges, LineChangeKind::Added, new_line);
+                    new_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                Some('-') => {
+                    push_range(ranges, LineChangeKind::Removed, old_line);
+                    old_line += 1;
+                    old_left = old_left.saturating_sub(1);
+                }
+                Some('\\') => {}
+                _ => {
+                    old_line += 1;
+                    new_line += 1;
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            // 先用头部猜测路径，后续的 rename/---/+++ 行会给出准确值
+            old_path = None;
+            current = rest.rsplit_once(" b/").map(|(_, new)| PathBuf::from(new));
+            if let Some(path) = &current {
+                files.entry(path.clone()).or_default();
+            }
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            old_path = Some(path.to_string());
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            set_current_path(&mut files, &mut current, PathBuf::from(path));
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = strip_diff_prefix(path, "a/").or(old_path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            // 删除的文件为 `+++ /dev/null`，以旧路径为键
+            if let Some(path) = strip_diff_prefix(path, "b/").or_else(|| old_path.clone()) {
+                set_current_path(&mut files, &mut current, PathBuf::from(path));
+            }
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            if let Some((old, new)) = parse_hunk_header(header) {
+                (old_line, old_left) = old;
+                (new_line, new_left) = new;
+            }
+        }
+    }
+    files
 }
 
-/// 获取Git状态
-#[allow(dead_code)]
-pub fn get_status() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    run_git(&["status".to_string(), "--porcelain".to_string()])
+/// 切换当前文件路径，并移除按 `diff --git` 头猜测出的空记录
+fn set_current_path(
+    files: &mut HashMap<PathBuf, Vec<LineRange>>,
+    current: &mut Option<PathBuf>,
+    path: PathBuf,
+) {
+    if let Some(guessed) = current.take() {
+        if guessed != path && files.get(&guessed).is_some_and(|r| r.is_empty()) {
+            files.remove(&guessed);
+        }
+    }
+    files.entry(path.clone()).or_default();
+    *current = Some(path);
+}
+
+/// 去掉 `---`/`+++` 行路径的 `a/`、`b/` 前缀（及路径含空格时 git 追加的制表符）；`/dev/null` 返回 None
+fn strip_diff_prefix(path: &str, prefix: &str) -> Option<String> {
+    let path = path.trim_end_matches('\t');
+    path.strip_prefix(prefix).map(str::to_string)
+}
+
+/// 解析 hunk 头 `-a,b +c,d @@`，返回 ((a, b), (c, d))；省略的行数为 1
+fn parse_hunk_header(header: &str) -> Option<((usize, usize), (usize, usize))> {
+    let mut parts = header.split_whitespace();
+    let old = parse_hunk_range(parts.next()?.strip_prefix('-')?)?;
+    let new = parse_hunk_range(parts.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+fn parse_hunk_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// 追加一行到同类型的区间列表，与上一区间相邻时合并
+fn push_range(ranges: &mut Vec<LineRange>, kind: LineChangeKind, line: usize) {
+    match ranges.iter_mut().rev().find(|r| r.kind == kind) {
+        Some(last) if last.end + 1 == line => last.end = line,
+        _ => ranges.push(LineRange {
+            kind,
+            start: line,
+            end: line,
+        }),
+    }
+}
+
+/// 仓库布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoLayout {
+    /// 普通仓库的主工作区
+    WorkTree,
+    /// `git worktree add` 创建的链接工作区，`.git` 是指向主仓库的文件
+    LinkedWorktree,
+    /// 裸仓库（或位于 `.git` 目录内部），没有工作区
+    Bare,
+}
+
+impl RepoLayout {
+    /// 是否有工作区（status、工作区 diff 等操作需要）
+    pub fn has_work_tree(self) -> bool {
+        !matches!(self, Self::Bare)
+    }
+}
+
+/// 检测当前目录所在仓库的布局
+pub fn repo_layout() -> Result<RepoLayout, Box<dyn std::error::Error + Send + Sync>> {
+    repo_layout_in(Path::new("."))
+}
+
+/// 检测 dir 所在仓库的布局；不在 Git 仓库中时返回错误
+pub fn repo_layout_in(dir: &Path) -> Result<RepoLayout, Box<dyn std::error::Error + Send + Sync>> {
+    let output = run_git(&with_dir(
+        dir,
+        &[
+            "rev-parse",
+            "--is-bare-repository",
+            "--is-inside-work-tree",
+            "--git-dir",
+            "--git-common-dir",
+        ],
+    ))
+    .map_err(|e| format!("{} 不是 Git 仓库: {e}", dir.display()))?;
+    let mut lines = output.lines().map(str::trim);
+    let (Some(bare), Some(inside), Some(git_dir), Some(common_dir)) =
+        (lines.next(), lines.next(), lines.next(), lines.next())
+    else {
+        return Err(format!("无法识别 {} 的仓库布局: {output}", dir.display()).into());
+    };
+    if bare == "true" || inside != "true" {
+        return Ok(RepoLayout::Bare);
+    }
+    // 两者可能是相对 dir 的路径，规范化后比较；链接工作区的 git-dir 位于 common-dir/worktrees 下
+    let canonical = |p: &str| std::fs::canonicalize(dir.join(p)).unwrap_or_else(|_| dir.join(p));
+    if canonical(git_dir) == canonical(common_dir) {
+        Ok(RepoLayout::WorkTree)
+    } else {
+        Ok(RepoLayout::LinkedWorktree)
+    }
+}
+
+/// 需要工作区的操作在裸仓库中给出明确错误，而不是让 git 报出难懂的失败
+fn ensure_work_tree_in(
+    dir: &Path,
+    operation: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match repo_layout_in(dir) {
+        Ok(layout) if !layout.has_work_tree() => Err(format!(
+            "{operation} 需要工作区，但 {} 是裸仓库；请在工作区或 `git worktree add` 创建的工作区中运行",
+            dir.display()
+        )
+        .into()),
+        // 不在仓库中等情况交给后续的 git 命令报告
+        _ => Ok(()),
+    }
+}
+
+/// 构造 `git -C dir <args>` 参数
+fn with_dir(dir: &Path, args: &[&str]) -> Vec<String> {
+    let mut full = vec!["-C".to_string(), dir.to_string_lossy().to_string()];
+    full.extend(args.iter().map(|a| a.to_string()));
+    full
 }
 
 /// 执行Git提交
@@ -175,14 +732,21 @@ pub fn git_add_all() -> Result<String, Box<dyn std::error::Error + Send + Sync>>
 
 /// 获取未推送的提交的diff
 pub fn get_unpushed_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_unpushed_diff_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库未推送的提交的diff
+pub fn get_unpushed_diff_in(
+    dir: &Path,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // 首先检查是否有远程分支
-    let remote_branch = get_upstream_branch();
+    let remote_branch = get_upstream_branch_in(dir);
 
     match remote_branch {
         Ok(upstream) => {
             // 有远程分支，比较本地与远程的差异
             log::debug!("检查未推送的提交: 本地 vs {upstream}");
-            let diff = run_git(&["diff".to_string(), format!("{upstream}..HEAD")]);
+            let diff = run_git_in(dir, &["diff".to_string(), format!("{upstream}..HEAD")]);
 
             match diff {
                 Ok(content) => {
@@ -203,12 +767,15 @@ pub fn get_unpushed_diff() -> Result<String, Box<dyn std::error::Error + Send +
         Err(_) => {
             // 没有远程分支，检查本地是否有提交
             log::debug!("没有远程分支，检查本地提交历史");
-            match run_git(&[
-                "log".to_string(),
-                "--oneline".to_string(),
-                "-n".to_string(),
-                "1".to_string(),
-            ]) {
+            match run_git_in(
+                dir,
+                &[
+                    "log".to_string(),
+                    "--oneline".to_string(),
+                    "-n".to_string(),
+                    "1".to_string(),
+                ],
+            ) {
                 Ok(log_output) => {
                     if log_output.trim().is_empty() {
                         log::debug!("没有本地提交");
@@ -216,11 +783,14 @@ pub fn get_unpushed_diff() -> Result<String, Box<dyn std::error::Error + Send +
                     } else {
                         // 有本地提交但没有远程，显示所有提交的diff
                         log::debug!("有本地提交但没有远程分支，显示最近提交的diff");
-                        match run_git(&[
-                            "show".to_string(),
-                            "HEAD".to_string(),
-                            "--format=format:".to_string(),
-                        ]) {
+                        match run_git_in(
+                            dir,
+                            &[
+                                "show".to_string(),
+                                "HEAD".to_string(),
+                                "--format=format:".to_string(),
+                            ],
+                        ) {
                             Ok(diff) => Ok(diff),
                             Err(_) => Ok(String::new()),
                         }
@@ -237,12 +807,22 @@ pub fn get_unpushed_diff() -> Result<String, Box<dyn std::error::Error + Send +
 
 /// 获取最后一次提交的 diff
 pub fn get_last_commit_diff() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_last_commit_diff_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库最后一次提交的 diff
+pub fn get_last_commit_diff_in(
+    dir: &Path,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // 先检查是否有多个提交
-    let log_output = run_git(&[
-        "rev-list".to_string(),
-        "--count".to_string(),
-        "HEAD".to_string(),
-    ])?;
+    let log_output = run_git_in(
+        dir,
+        &[
+            "rev-list".to_string(),
+            "--count".to_string(),
+            "HEAD".to_string(),
+        ],
+    )?;
 
     let commit_count: usize = log_output.trim().parse().unwrap_or(0);
 
@@ -250,25 +830,171 @@ pub fn get_last_commit_diff() -> Result<String, Box<dyn std::error::Error + Send
         Err("仓库中没有任何提交".into())
     } else if commit_count == 1 {
         // 只有一个提交，显示第一次提交的内容
-        run_git(&[
-            "show".to_string(),
-            "HEAD".to_string(),
-            "--format=format:".to_string(),
-        ])
+        run_git_in(
+            dir,
+            &[
+                "show".to_string(),
+                "HEAD".to_string(),
+                "--format=format:".to_string(),
+            ],
+        )
     } else {
         // 有多个提交，显示最后一次提交与前一次的差异
-        run_git(&["diff".to_string(), "HEAD~1".to_string(), "HEAD".to_string()])
+        run_git_in(
+            dir,
+            &["diff".to_string(), "HEAD~1".to_string(), "HEAD".to_string()],
+        )
+    }
+}
+
+/// 获取第 `index` 个贮藏（`stash@{index}`）相对其基线提交的 diff，包含贮藏的未跟踪文件
+pub fn get_stash_diff(index: usize) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_stash_diff_in(Path::new("."), index)
+}
+
+/// 获取 dir 所在仓库第 `index` 个贮藏的 diff，包含贮藏的未跟踪文件
+pub fn get_stash_diff_in(
+    dir: &Path,
+    index: usize,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let stash = format!("stash@{{{index}}}");
+    if !rev_exists_in(dir, &format!("{stash}^{{commit}}")) {
+        return Err(Box::new(GitAIError::Git(GitError::CommitNotFound(
+            format!("{stash}（可用 `git stash list` 查看已有的贮藏）"),
+        ))));
+    }
+
+    let tracked = run_git_in(
+        dir,
+        &["diff".to_string(), format!("{stash}^1"), stash.clone()],
+    )?;
+    // `git stash -u` 把未跟踪文件保存在第三个父提交中（无父提交，show 即为全部新增）
+    let untracked = if rev_exists_in(dir, &format!("{stash}^3")) {
+        run_git_in(
+            dir,
+            &[
+                "show".to_string(),
+                "--format=".to_string(),
+                format!("{stash}^3"),
+            ],
+        )?
+    } else {
+        String::new()
+    };
+
+    let mut stash_diff = String::new();
+    if !tracked.trim().is_empty() {
+        stash_diff.push_str(&format!("## 贮藏的变更 (Stash {stash}):\n"));
+        stash_diff.push_str(&tracked);
+        if !stash_diff.ends_with('\n') {
+            stash_diff.push('\n');
+        }
+    }
+    if !untracked.trim().is_empty() {
+        stash_diff.push_str(&format!(
+            "## 贮藏的未跟踪文件 (Stash {stash} Untracked Files):\n"
+        ));
+        stash_diff.push_str(untracked.trim_start_matches('\n'));
+        if !stash_diff.ends_with('\n') {
+            stash_diff.push('\n');
+        }
     }
+    Ok(stash_diff)
+}
+
+/// 读取 `<rev>:<path>` 指定的文件内容（与 `git show <rev>:<path>` 相同），以新增文件的 diff 形式返回
+pub fn get_blob_diff(spec: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_blob_diff_in(Path::new("."), spec)
+}
+
+/// 在 dir 所在仓库中读取 `<rev>:<path>` 指定的文件内容，以新增文件的 diff 形式返回
+pub fn get_blob_diff_in(
+    dir: &Path,
+    spec: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let (rev, path) = match spec.split_once(':') {
+        Some((rev, path)) if !rev.is_empty() && !path.is_empty() => (rev, path),
+        _ => {
+            return Err(format!(
+                "无效的文件引用 `{spec}`，应为 <rev>:<path>，例如 HEAD~1:src/main.rs"
+            )
+            .into())
+        }
+    };
+    if !rev_exists_in(dir, &format!("{rev}^{{commit}}")) {
+        return Err(Box::new(GitAIError::Git(GitError::CommitNotFound(
+            rev.to_string(),
+        ))));
+    }
+    let kind = run_git_in(
+        dir,
+        &["cat-file".to_string(), "-t".to_string(), spec.to_string()],
+    )
+    .map_err(|_| format!("{rev} 中不存在文件 `{path}`"))?;
+    if kind.trim() != "blob" {
+        return Err(format!("`{spec}` 不是文件（类型为 {}）", kind.trim()).into());
+    }
+
+    let content = run_git_in(dir, &["show".to_string(), spec.to_string()])?;
+    let mut blob_diff = format!(
+        "## 指定版本的文件 (Blob {spec}):\ndiff --git a/{path} b/{path}\nnew file mode 100644\n--- /dev/null\n+++ b/{path}\n"
+    );
+    if content.contains('\0') {
+        blob_diff.push_str("@@\n+ [二进制文件已省略内容]\n");
+        return Ok(blob_diff);
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    blob_diff.push_str(&format!("@@ -0,0 +1,{} @@\n", lines.len()));
+    for line in lines {
+        blob_diff.push('+');
+        blob_diff.push_str(line);
+        blob_diff.push('\n');
+    }
+    Ok(blob_diff)
+}
+
+/// 引用能否在 dir 所在仓库中解析为对象
+fn rev_exists_in(dir: &Path, rev: &str) -> bool {
+    matches!(
+        run_git_capture_in(
+            dir,
+            &[
+                "rev-parse".to_string(),
+                "--verify".to_string(),
+                "--quiet".to_string(),
+                rev.to_string(),
+            ]
+        ),
+        Ok((Some(0), _, _))
+    )
+}
+
+/// 获取当前分支名；分离 HEAD 或不在 Git 仓库中时返回 None
+pub fn current_branch() -> Option<String> {
+    run_git(&["branch".to_string(), "--show-current".to_string()])
+        .ok()
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty())
 }
 
 /// 获取当前分支的上游分支
 pub fn get_upstream_branch() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    get_upstream_branch_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库当前分支的上游分支
+pub fn get_upstream_branch_in(
+    dir: &Path,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // 尝试获取当前分支的上游分支
-    match run_git(&[
-        "rev-parse".to_string(),
-        "--abbrev-ref".to_string(),
-        "@{upstream}".to_string(),
-    ]) {
+    match run_git_in(
+        dir,
+        &[
+            "rev-parse".to_string(),
+            "--abbrev-ref".to_string(),
+            "@{upstream}".to_string(),
+        ],
+    ) {
         Ok(upstream) => {
             let upstream = upstream.trim().to_string();
             if upstream.is_empty() {
@@ -279,21 +1005,27 @@ pub fn get_upstream_branch() -> Result<String, Box<dyn std::error::Error + Send
         }
         Err(_) => {
             // 如果没有上游分支，尝试使用 origin/当前分支名
-            match run_git(&[
-                "rev-parse".to_string(),
-                "--abbrev-ref".to_string(),
-                "HEAD".to_string(),
-            ]) {
+            match run_git_in(
+                dir,
+                &[
+                    "rev-parse".to_string(),
+                    "--abbrev-ref".to_string(),
+                    "HEAD".to_string(),
+                ],
+            ) {
                 Ok(current_branch) => {
                     let current_branch = current_branch.trim();
                     let origin_branch = format!("origin/{current_branch}");
 
                     // 检查 origin/branch 是否存在
-                    match run_git(&[
-                        "rev-parse".to_string(),
-                        "--verify".to_string(),
-                        origin_branch.clone(),
-                    ]) {
+                    match run_git_in(
+                        dir,
+                        &[
+                            "rev-parse".to_string(),
+                            "--verify".to_string(),
+                            origin_branch.clone(),
+                        ],
+                    ) {
                         Ok(_) => Ok(origin_branch),
                         Err(_) => Err("没有找到对应的远程分支".into()),
                     }
@@ -353,32 +1085,103 @@ pub fn get_tracked_files() -> Result<Vec<String>, Box<dyn std::error::Error + Se
 
 /// 获取未跟踪文件（自动排除 .gitignore 中的文件）
 pub fn get_untracked_files() -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let output = run_git(&[
+    get_untracked_files_in(Path::new("."))
+}
+
+/// 获取 dir 所在仓库的未跟踪文件（自动排除 .gitignore 中的文件）
+pub fn get_untracked_files_in(
+    dir: &Path,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let output = run_git_in(
+        dir,
+        &[
+            "ls-files".to_string(),
+            "--others".to_string(),
+            "--exclude-standard".to_string(),
+        ],
+    )?;
+    let files: Vec<String> = output
+        .lines()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    Ok(files)
+}
+
+/// 获取 dir 下相对 HEAD 的变更文件（含未跟踪文件），路径相对 dir
+pub fn get_changed_files_in(
+    dir: &Path,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_work_tree_in(dir, "获取工作区变更文件")?;
+    let dir = dir.to_string_lossy().to_string();
+    let mut diff_args = vec![
+        "-C".to_string(),
+        dir.clone(),
+        "diff".to_string(),
+        "--name-only".to_string(),
+        "--relative".to_string(),
+    ];
+    let has_head = run_git_capture(&[
+        "-C".to_string(),
+        dir.clone(),
+        "rev-parse".to_string(),
+        "--verify".to_string(),
+        "HEAD".to_string(),
+    ])
+    .is_ok_and(|(code, _, _)| code == Some(0));
+    if has_head {
+        diff_args.push("HEAD".to_string());
+    } else {
+        diff_args.push("--cached".to_string());
+    }
+    let diff = run_git(&diff_args)?;
+    let untracked = run_git(&[
+        "-C".to_string(),
+        dir,
         "ls-files".to_string(),
         "--others".to_string(),
         "--exclude-standard".to_string(),
     ])?;
-    let files: Vec<String> = output
+    let mut files: Vec<String> = diff
         .lines()
+        .chain(untracked.lines())
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
         .collect();
+    files.sort();
+    files.dedup();
     Ok(files)
 }
 
 /// 是否存在未跟踪变更（新增文件）
 pub fn has_untracked_changes() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    Ok(!get_untracked_files()?.is_empty())
+    has_untracked_changes_in(Path::new("."))
+}
+
+/// dir 所在仓库是否存在未跟踪变更（新增文件）
+pub fn has_untracked_changes_in(
+    dir: &Path,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(!get_untracked_files_in(dir)?.is_empty())
 }
 
 /// 是否存在任何提交
 pub fn has_any_commit() -> bool {
-    if let Ok((code, _out, _err)) = run_git_capture(&[
-        "rev-parse".to_string(),
-        "--verify".to_string(),
-        "HEAD".to_string(),
-    ]) {
+    has_any_commit_in(Path::new("."))
+}
+
+/// dir 所在仓库是否存在任何提交
+pub fn has_any_commit_in(dir: &Path) -> bool {
+    if let Ok((code, _out, _err)) = run_git_capture_in(
+        dir,
+        &[
+            "rev-parse".to_string(),
+            "--verify".to_string(),
+            "HEAD".to_string(),
+        ],
+    ) {
         return code == Some(0);
     }
     false
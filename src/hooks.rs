@@ -0,0 +1,243 @@
+// Git 钩子管理模块
+// `gitai hooks install/uninstall` 在 .git/hooks 中写入调用 GitAI 的钩子脚本，
+// 并提供 commit-msg 钩子使用的提交信息校验
+
+use crate::error::{FileSystemError, GitAIError, Result};
+use std::path::{Path, PathBuf};
+
+/// GitAI 生成的钩子脚本中的标记行，用于区分用户自己的钩子
+pub const HOOK_MARKER: &str = "# gitai-managed-hook";
+
+/// 覆盖非 GitAI 钩子前的备份文件后缀
+pub const BACKUP_SUFFIX: &str = ".gitai-backup";
+
+/// 提交信息标题行的最大字符数
+pub const MAX_SUBJECT_CHARS: usize = 72;
+
+/// GitAI 支持安装的钩子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// 提交前评审已暂存的变更，发现严重问题时阻止提交
+    PreCommit,
+    /// 校验提交信息格式
+    CommitMsg,
+}
+
+impl HookKind {
+    /// 按命令行开关选择，都未指定时返回全部钩子
+    pub fn selected(pre_commit: bool, commit_msg: bool) -> Vec<Self> {
+        match (pre_commit, commit_msg) {
+            (false, false) => vec![Self::PreCommit, Self::CommitMsg],
+            _ => [(pre_commit, Self::PreCommit), (commit_msg, Self::CommitMsg)]
+                .into_iter()
+                .filter_map(|(on, kind)| on.then_some(kind))
+                .collect(),
+        }
+    }
+
+    /// 钩子文件名
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::PreCommit => "pre-commit",
+            Self::CommitMsg => "commit-msg",
+        }
+    }
+
+    /// 钩子脚本内容
+    pub fn script(self) -> String {
+        let command = match self {
+            Self::PreCommit => "exec gitai review --staged --fail-on critical",
+            Self::CommitMsg => "exec gitai hooks check-message \"$1\"",
+        };
+        format!(
+            "#!/bin/sh\n{HOOK_MARKER}: {}\n# 由 `gitai hooks install` 生成，使用 `gitai hooks uninstall` 移除\n{command}\n",
+            self.file_name()
+        )
+    }
+}
+
+/// 单个钩子的处理结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutcome {
+    /// 已写入；若覆盖了用户的钩子，附带备份路径
+    Installed { backup: Option<PathBuf> },
+    /// 已删除；restored 表示同时恢复了安装前的备份
+    Removed { restored: bool },
+    /// 钩子不存在或不是 GitAI 生成的，未做改动
+    Skipped(String),
+}
+
+/// 当前仓库的钩子目录（遵循 core.hooksPath 与 worktree）
+pub fn hooks_dir() -> Result<PathBuf> {
+    let path = crate::git::run_git(&[
+        "rev-parse".to_string(),
+        "--git-path".to_string(),
+        "hooks".to_string(),
+    ])
+    .map_err(GitAIError::from)?;
+    Ok(PathBuf::from(path.trim()))
+}
+
+/// 文件是否为 GitAI 生成的钩子
+pub fn is_gitai_hook(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+/// 在 dir 中安装钩子
+///
+/// 已存在的 GitAI 钩子直接覆盖；用户自己的钩子默认拒绝覆盖，force 时先备份为
+/// `<name>.gitai-backup`（备份已存在时报错，避免丢失更早的备份）
+pub fn install_hook(dir: &Path, kind: HookKind, force: bool) -> Result<HookOutcome> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(kind.file_name());
+    let mut backup = None;
+    if path.exists() && !is_gitai_hook(&path) {
+        if !force {
+            return Err(refuse(format!(
+                "{} 已存在且不是 GitAI 生成的钩子，使用 --force 备份后覆盖",
+                path.display()
+            )));
+        }
+        let backup_path = backup_path(&path);
+        if backup_path.exists() {
+            return Err(refuse(format!(
+                "备份文件 {} 已存在，请先处理",
+                backup_path.display()
+            )));
+        }
+        std::fs::rename(&path, &backup_path)?;
+        backup = Some(backup_path);
+    }
+    std::fs::write(&path, kind.script())?;
+    make_executable(&path)?;
+    Ok(HookOutcome::Installed { backup })
+}
+
+/// 从 dir 中移除 GitAI 钩子，并恢复安装时的备份
+pub fn uninstall_hook(dir: &Path, kind: HookKind) -> Result<HookOutcome> {
+    let path = dir.join(kind.file_name());
+    if !path.exists() {
+        return Ok(HookOutcome::Skipped("未安装".to_string()));
+    }
+    if !is_gitai_hook(&path) {
+        return Ok(HookOutcome::Skipped(
+            "不是 GitAI 生成的钩子，保持不变".to_string(),
+        ));
+    }
+    std::fs::remove_file(&path)?;
+    let backup_path = backup_path(&path);
+    let restored = backup_path.exists();
+    if restored {
+        std::fs::rename(&backup_path, &path)?;
+    }
+    Ok(HookOutcome::Removed { restored })
+}
+
+fn refuse(message: String) -> GitAIError {
+    GitAIError::FileSystem(FileSystemError::PermissionDenied(message))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(BACKUP_SUFFIX);
+    path.with_file_name(name)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// 校验提交信息，返回发现的问题（为空表示通过）
+///
+/// 忽略 `#` 开头的注释行（git 编辑器模板），要求标题非空、不超过
+/// [`MAX_SUBJECT_CHARS`] 个字符，且标题与正文之间有空行
+pub fn check_commit_message(message: &str) -> Vec<String> {
+    let lines: Vec<&str> = message
+        .lines()
+        .take_while(|line| !line.starts_with("# ------------------------ >8"))
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let mut problems = Vec::new();
+    let subject = lines.first().map(|l| l.trim()).unwrap_or_default();
+    if subject.is_empty() {
+        problems.push("提交信息标题为空".to_string());
+        return problems;
+    }
+    let subject_chars = subject.chars().count();
+    if subject_chars > MAX_SUBJECT_CHARS {
+        problems.push(format!(
+            "标题过长：{subject_chars} 个字符（上限 {MAX_SUBJECT_CHARS}）"
+        ));
+    }
+    if lines.get(1).is_some_and(|l| !l.trim().is_empty()) {
+        problems.push("标题与正文之间需要空一行".to_string());
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_refuses_foreign_hook_and_restores_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dir.path();
+        let user_hook = "#!/bin/sh\necho mine\n";
+        std::fs::write(hooks.join("pre-commit"), user_hook).unwrap();
+
+        assert!(install_hook(hooks, HookKind::PreCommit, false).is_err());
+        assert_eq!(
+            std::fs::read_to_string(hooks.join("pre-commit")).unwrap(),
+            user_hook
+        );
+
+        let outcome = install_hook(hooks, HookKind::PreCommit, true).unwrap();
+        let backup = hooks.join("pre-commit.gitai-backup");
+        assert_eq!(
+            outcome,
+            HookOutcome::Installed {
+                backup: Some(backup.clone())
+            }
+        );
+        assert!(is_gitai_hook(&hooks.join("pre-commit")));
+        // 重复安装直接覆盖 GitAI 钩子，不再备份
+        assert_eq!(
+            install_hook(hooks, HookKind::PreCommit, false).unwrap(),
+            HookOutcome::Installed { backup: None }
+        );
+
+        assert_eq!(
+            uninstall_hook(hooks, HookKind::PreCommit).unwrap(),
+            HookOutcome::Removed { restored: true }
+        );
+        assert_eq!(
+            std::fs::read_to_string(hooks.join("pre-commit")).unwrap(),
+            user_hook
+        );
+        assert!(!backup.exists());
+        assert!(matches!(
+            uninstall_hook(hooks, HookKind::PreCommit).unwrap(),
+            HookOutcome::Skipped(_)
+        ));
+    }
+
+    #[test]
+    fn test_check_commit_message() {
+        assert!(check_commit_message("feat: add hooks\n\nbody\n").is_empty());
+        assert!(check_commit_message("fix: typo\n# Please enter the commit message\n").is_empty());
+        assert_eq!(check_commit_message("# only comments\n\n").len(), 1);
+        assert_eq!(check_commit_message("subject\nbody right away\n").len(), 1);
+        assert_eq!(check_commit_message(&"长".repeat(73)).len(), 1);
+        assert!(check_commit_message(&"长".repeat(72)).is_empty());
+    }
+}
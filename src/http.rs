@@ -0,0 +1,145 @@
+// HTTP 客户端配置
+// AI 服务、DevOps 平台、规则/查询/模板下载与版本检查等所有出站请求统一通过
+// `client_builder()` 创建客户端，共用配置 `[http]` 中的超时、代理与自定义 CA 证书
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 指定自定义 CA 证书（PEM，可包含多个证书）的环境变量，优先级低于配置中的 ca_cert
+pub const CA_CERT_ENV: &str = "GITAI_CA_CERT";
+
+lazy_static::lazy_static! {
+    static ref ACTIVE: RwLock<HttpConfig> = RwLock::new(HttpConfig::default());
+}
+
+/// HTTP 配置（`[http]`）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// 建立连接的超时时间（秒）
+    pub connect_timeout: u64,
+    /// 读取响应的超时时间（秒，两次读取之间的最长等待）；0 表示不限制
+    pub read_timeout: u64,
+    /// 代理地址（如 `http://proxy.company.com:8080`）；未设置时使用环境变量
+    /// `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY`（`NO_PROXY` 中的主机直连）
+    pub proxy: Option<String>,
+    /// 自定义 CA 证书路径（PEM），用于企业内网的自签名证书；也可通过 `GITAI_CA_CERT` 指定
+    pub ca_cert: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: 10,
+            read_timeout: 120,
+            proxy: None,
+            ca_cert: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// 生效的 CA 证书路径：配置优先，其次为环境变量
+    fn ca_cert_path(&self) -> Option<String> {
+        self.ca_cert
+            .clone()
+            .filter(|path| !path.trim().is_empty())
+            .or_else(|| std::env::var(CA_CERT_ENV).ok())
+            .filter(|path| !path.trim().is_empty())
+    }
+
+    /// 按配置创建客户端构建器；代理地址无效或证书无法读取时返回错误
+    pub fn client_builder(
+        &self,
+    ) -> Result<reqwest::ClientBuilder, Box<dyn std::error::Error + Send + Sync>> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(concat!("gitai/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(Duration::from_secs(self.connect_timeout));
+        if self.read_timeout > 0 {
+            builder = builder.read_timeout(Duration::from_secs(self.read_timeout));
+        }
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| format!("HTTP 代理地址无效 {proxy}: {e}"))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = self.ca_cert_path() {
+            let path = crate::utils::paths::resolve_config_path(&path);
+            for cert in load_certificates(&path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        Ok(builder)
+    }
+}
+
+/// 读取 PEM 证书文件
+fn load_certificates(
+    path: &Path,
+) -> Result<Vec<reqwest::Certificate>, Box<dyn std::error::Error + Send + Sync>> {
+    let pem =
+        std::fs::read(path).map_err(|e| format!("无法读取 CA 证书 {}: {e}", path.display()))?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("CA 证书格式无效 {}: {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("CA 证书文件中没有证书: {}", path.display()).into());
+    }
+    Ok(certs)
+}
+
+/// 设置全局 HTTP 配置（通常在加载配置后调用一次）
+pub fn configure(config: HttpConfig) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = config;
+    }
+}
+
+/// 当前的全局 HTTP 配置
+pub fn active() -> HttpConfig {
+    ACTIVE.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// 按全局配置创建客户端构建器，调用方可在此基础上调整（如设置整体超时）
+pub fn client_builder() -> Result<reqwest::ClientBuilder, Box<dyn std::error::Error + Send + Sync>>
+{
+    active().client_builder()
+}
+
+/// 按全局配置创建客户端
+pub fn client() -> Result<reqwest::Client, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(client_builder()?.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_config_parse_and_build() {
+        let config: HttpConfig =
+            toml::from_str("proxy = \"http://proxy.example.com:8080\"\nread_timeout = 0\n")
+                .unwrap();
+        assert_eq!(config.connect_timeout, 10);
+        assert_eq!(config.read_timeout, 0);
+        assert!(config.client_builder().unwrap().build().is_ok());
+
+        let invalid = HttpConfig {
+            proxy: Some("::not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.client_builder().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = HttpConfig {
+            ca_cert: Some(dir.path().join("ca.pem").display().to_string()),
+            ..Default::default()
+        };
+        let err = missing.client_builder().unwrap_err().to_string();
+        assert!(err.contains("ca.pem"));
+
+        std::fs::write(dir.path().join("ca.pem"), "not a certificate").unwrap();
+        assert!(missing.client_builder().is_err());
+    }
+}
@@ -0,0 +1,885 @@
+// 命令行输出的多语言支持
+// 文本集中在 MESSAGES 表中按键查找，通过 `--lang en|zh` 或 LC_ALL / LC_MESSAGES / LANG
+// 环境变量选择语言；未指定或无法识别时保持中文
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 输出语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    /// 中文（默认）
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "zh" | "cn" | "zh-cn" | "zh_cn" | "chinese" => Ok(Self::Zh),
+            "en" | "en-us" | "en_us" | "english" => Ok(Self::En),
+            other => Err(format!("不支持的输出语言: {other}（可选 en, zh）")),
+        }
+    }
+}
+
+impl Lang {
+    /// 语言代码（`zh` / `en`）
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Zh => "zh",
+            Self::En => "en",
+        }
+    }
+
+    /// 从 locale 值（如 `en_US.UTF-8`、`zh_CN.UTF-8`）识别语言；`C`/`POSIX` 等返回 None
+    pub fn from_locale(value: &str) -> Option<Self> {
+        let value = value.to_ascii_lowercase();
+        if value.starts_with("zh") {
+            Some(Self::Zh)
+        } else if value.starts_with("en") {
+            Some(Self::En)
+        } else {
+            None
+        }
+    }
+
+    /// 按 POSIX 优先级读取 LC_ALL、LC_MESSAGES、LANG 中第一个非空值
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_locale(&value))
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// 确定本次调用的输出语言：命令行参数优先，其次环境变量，默认中文
+pub fn init(explicit: Option<Lang>) -> Lang {
+    let lang = explicit.or_else(Lang::from_env).unwrap_or_default();
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+    lang
+}
+
+/// 当前输出语言
+pub fn current() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+/// 按键查找当前语言的文本；未登记的键原样返回，便于发现遗漏
+pub fn t(key: &'static str) -> &'static str {
+    lookup(key, current())
+}
+
+/// 查找指定语言的文本
+pub fn lookup(key: &'static str, lang: Lang) -> &'static str {
+    MESSAGES
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, zh, en)| match lang {
+            Lang::Zh => *zh,
+            Lang::En => *en,
+        })
+        .unwrap_or(key)
+}
+
+/// 用参数替换文本中的 `{name}` 占位符
+pub fn render(template: &str, args: &[(&str, String)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// 取当前语言的文本，可带命名参数：`tr!("scan.scanning", path = path.display())`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::t($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::render(
+            $crate::i18n::t($key),
+            &[$((stringify!($name), $value.to_string())),+],
+        )
+    };
+}
+
+/// 消息表：(键, 中文, 英文)
+const MESSAGES: &[(&str, &str, &str)] = &[
+    // scan
+    (
+        "scan.scanning",
+        "🔍 正在扫描: {path}",
+        "🔍 Scanning: {path}",
+    ),
+    (
+        "scan.auto_install",
+        "🔧 未检测到 OpenGrep，正在自动安装...",
+        "🔧 OpenGrep not found, installing...",
+    ),
+    (
+        "scan.updating_rules",
+        "🔄 正在更新扫描规则...",
+        "🔄 Updating scan rules...",
+    ),
+    (
+        "scan.rules_update_failed",
+        "⚠️ 规则更新失败: {error}",
+        "⚠️ Rule update failed: {error}",
+    ),
+    (
+        "scan.custom_rules",
+        "📐 使用自定义规则: {path}",
+        "📐 Using custom rules: {path}",
+    ),
+    (
+        "scan.custom_rules_only",
+        "📐 仅使用自定义规则: {path}",
+        "📐 Using only custom rules: {path}",
+    ),
+    (
+        "scan.agreement",
+        "  扫描器: {scanners}，{agreed} 个问题由全部扫描器共同报告",
+        "  Scanners: {scanners}; {agreed} finding(s) reported by all scanners",
+    ),
+    (
+        "scan.agreement_exclusive",
+        "    仅 {scanner} 报告: {count} 个",
+        "    Reported only by {scanner}: {count}",
+    ),
+    (
+        "scan.rule_source_updated",
+        "🔄 规则来源 {name} 已更新到 {version}",
+        "🔄 Rule source {name} updated to {version}",
+    ),
+    (
+        "scan.rule_source",
+        "  规则来源 {name}（{kind}，版本 {version}）: {rules} 条规则，覆盖 {overrides} 条",
+        "  Rule source {name} ({kind}, version {version}): {rules} rules, {overrides} overridden",
+    ),
+    (
+        "scan.rules_update_disabled",
+        "ℹ️  update-notifier 功能未启用，跳过规则更新。",
+        "ℹ️  update-notifier feature is disabled, skipping rule update.",
+    ),
+    (
+        "scan.history_dir_failed",
+        "⚠️ 无法创建扫描历史目录: {error}",
+        "⚠️ Failed to create scan history directory: {error}",
+    ),
+    (
+        "scan.history_write_failed",
+        "⚠️ 写入扫描历史失败: {error}",
+        "⚠️ Failed to write scan history: {error}",
+    ),
+    ("scan.result_header", "📊 扫描结果:", "📊 Scan result:"),
+    ("scan.tool", "  工具: {tool}", "  Tool: {tool}"),
+    ("scan.version", "  版本: {version}", "  Version: {version}"),
+    (
+        "scan.duration",
+        "  执行时间: {seconds}s",
+        "  Duration: {seconds}s",
+    ),
+    (
+        "scan.path_summary",
+        "  📁 {path}: {count} 个问题，耗时 {seconds}s",
+        "  📁 {path}: {count} issues in {seconds}s",
+    ),
+    ("scan.path_error", "     ⚠️ {error}", "     ⚠️ {error}"),
+    (
+        "scan.baseline_updated",
+        "📌 基线已更新: {path}（{count} 条）",
+        "📌 Baseline updated: {path} ({count} entries)",
+    ),
+    (
+        "scan.baseline_applied",
+        "  基线: 抑制 {suppressed} 个已接受的问题，{overdue} 个超过老化期限重新报告",
+        "  Baseline: {suppressed} accepted findings suppressed, {overdue} re-surfaced as overdue",
+    ),
+    ("scan.overdue_tag", "[逾期]", "[overdue]"),
+    (
+        "scan.findings",
+        "  发现问题: {count}",
+        "  Findings: {count}",
+    ),
+    (
+        "scan.risk_score",
+        "  风险分: {score}/100（{level}）",
+        "  Risk score: {score}/100 ({level})",
+    ),
+    (
+        "scan.more_findings",
+        "    ... 还有 {count} 个问题",
+        "    ... and {count} more",
+    ),
+    ("scan.clean", "  ✅ 未发现问题", "  ✅ No issues found"),
+    (
+        "scan.saved",
+        "📁 扫描结果已保存: {path}",
+        "📁 Scan results saved to: {path}",
+    ),
+    // review
+    (
+        "review.result_header",
+        "\n🤖 AI 代码评审结果:",
+        "\n🤖 AI code review result:",
+    ),
+    (
+        "review.new_dependencies",
+        "\n📦 新引入的依赖（请确认来源、许可证与必要性）:",
+        "\n📦 Newly introduced dependencies (check source, license and necessity):",
+    ),
+    ("review.findings", "\n🔒 发现的问题:", "\n🔒 Findings:"),
+    (
+        "review.recommendations",
+        "\n💡 改进建议:",
+        "\n💡 Recommendations:",
+    ),
+    (
+        "review.semver",
+        "\n🏷️  版本建议: {bump}",
+        "\n🏷️  Suggested version bump: {bump}",
+    ),
+    (
+        "review.dep_insights",
+        "\n🔗 依赖分析洞察:",
+        "\n🔗 Dependency insights:",
+    ),
+    (
+        "review.dep_graph_size",
+        "  图规模: {nodes} 节点 / {edges} 边",
+        "  Graph size: {nodes} nodes / {edges} edges",
+    ),
+    (
+        "review.dep_avg_degree",
+        "  平均度: {avg}",
+        "  Average degree: {avg}",
+    ),
+    (
+        "review.dep_critical_nodes",
+        "  关键节点数: {count}",
+        "  Critical nodes: {count}",
+    ),
+    (
+        "review.dep_mapped_nodes",
+        "  映射变更节点: {count}",
+        "  Mapped changed nodes: {count}",
+    ),
+    (
+        "review.dep_scope_nodes",
+        "  影响范围节点: {count}",
+        "  Impact scope nodes: {count}",
+    ),
+    (
+        "review.dep_top_impacted",
+        "  影响度 Top: {top}",
+        "  Top impacted: {top}",
+    ),
+    (
+        "review.saved",
+        "📁 评审结果已保存: {path}",
+        "📁 Review saved to: {path}",
+    ),
+    (
+        "review.fetching_pr",
+        "🌐 正在获取远程 PR 变更: {url}",
+        "🌐 Fetching remote PR changes: {url}",
+    ),
+    (
+        "review.cached",
+        "📦 使用缓存的评审结果",
+        "📦 Using cached review result",
+    ),
+    (
+        "review.scope_pr",
+        "   📝 GitAI将分析远程 PR 的变更",
+        "   📝 GitAI will review the remote PR changes",
+    ),
+    (
+        "review.scope_staged_only",
+        "   📝 GitAI将只分析已暂存的变更",
+        "   📝 GitAI will review staged changes only",
+    ),
+    (
+        "review.scope_uncommitted",
+        "   📝 GitAI将分析所有未提交的变更（已暂存 + 未暂存 + 未跟踪）",
+        "   📝 GitAI will review all uncommitted changes (staged + unstaged + untracked)",
+    ),
+    (
+        "review.scope_stash",
+        "   📝 GitAI将分析贮藏 {stash} 中的变更",
+        "   📝 GitAI will review the changes in {stash}",
+    ),
+    (
+        "review.scope_blob",
+        "   📝 GitAI将分析指定版本的文件 {spec}",
+        "   📝 GitAI will review the file at {spec}",
+    ),
+    (
+        "review.hint_unstaged",
+        "💡 提示：检测到未暂存的代码变更",
+        "💡 Hint: unstaged changes detected",
+    ),
+    (
+        "review.hint_git_add",
+        "   使用 `git add .` 暂存所有变更，或使用 `git add <file>` 暂存特定文件",
+        "   Use `git add .` to stage everything, or `git add <file>` for specific files",
+    ),
+    (
+        "review.hint_untracked",
+        "💡 提示：检测到未跟踪的新文件",
+        "💡 Hint: untracked files detected",
+    ),
+    (
+        "review.hint_track",
+        "   使用 `git add <file>` 开始跟踪这些文件",
+        "   Use `git add <file>` to start tracking them",
+    ),
+    (
+        "review.staged_included",
+        "   当前已暂存的变更也会被评审",
+        "   Staged changes will be reviewed as well",
+    ),
+    (
+        "review.no_commits_warning",
+        "   ⚠️ 当前仓库还没有任何提交（建议尽快 `git commit -m \"<msg>\"`）",
+        "   ⚠️ This repository has no commits yet (consider `git commit -m \"<msg>\"` soon)",
+    ),
+    (
+        "review.scope_all",
+        "   📝 GitAI将分析所有变更（已暂存 + 未暂存 + 未跟踪）",
+        "   📝 GitAI will review all changes (staged + unstaged + untracked)",
+    ),
+    (
+        "review.staged_ready",
+        "✅ 已暂存的代码准备就绪",
+        "✅ Staged changes are ready",
+    ),
+    (
+        "review.scope_staged",
+        "   📝 GitAI将分析已暂存的变更",
+        "   📝 GitAI will review the staged changes",
+    ),
+    (
+        "review.hint_no_commits",
+        "💡 提示：仓库没有任何提交。请先进行一次提交以建立基线：",
+        "💡 Hint: the repository has no commits. Make an initial commit to set a baseline:",
+    ),
+    (
+        "review.checking_unpushed",
+        "🔍 检查未推送的提交...",
+        "🔍 Checking unpushed commits...",
+    ),
+    (
+        "review.scope_last_commit",
+        "   📝 GitAI将分析最近的提交变更",
+        "   📝 GitAI will review the latest commit",
+    ),
+    (
+        "review.tree_sitter",
+        "🌳 使用 Tree-sitter 进行结构分析...",
+        "🌳 Running Tree-sitter structural analysis...",
+    ),
+    (
+        "review.multi_language_done",
+        "  ✅ 多语言结构分析完成",
+        "  ✅ Multi-language structural analysis done",
+    ),
+    (
+        "review.language_stats",
+        "{lang}: {functions} 函数, {classes} 类, {comments} 注释",
+        "{lang}: {functions} functions, {classes} classes, {comments} comments",
+    ),
+    (
+        "review.pass_findings",
+        "  🧩 分析 Pass 发现 {count} 个问题",
+        "  🧩 Analysis passes found {count} issue(s)",
+    ),
+    (
+        "review.new_dependencies_detected",
+        "📦 检测到 {count} 个新引入的第三方依赖",
+        "📦 Detected {count} newly introduced third-party dependencies",
+    ),
+    (
+        "review.dependency_graph",
+        "🔗 正在进行依赖图与 PageRank 分析...",
+        "🔗 Running dependency graph and PageRank analysis...",
+    ),
+    (
+        "review.dependency_graph_failed",
+        "  ⚠️ 依赖图构建失败: {error}",
+        "  ⚠️ Failed to build dependency graph: {error}",
+    ),
+    (
+        "review.security_scan",
+        "🔒 正在进行安全扫描...",
+        "🔒 Running security scan...",
+    ),
+    (
+        "review.security_findings",
+        "  ⚠️  发现 {count} 个安全问题",
+        "  ⚠️  Found {count} security issue(s)",
+    ),
+    (
+        "review.security_clean",
+        "  ✅ 未发现安全问题",
+        "  ✅ No security issues found",
+    ),
+    (
+        "review.calling_ai",
+        "🤖 正在调用 AI 进行代码评审...",
+        "🤖 Calling AI for code review...",
+    ),
+    (
+        "review.ai_disabled",
+        "🤖 AI 功能未启用，使用基础规则生成结果...",
+        "🤖 AI feature is disabled, generating result from basic rules...",
+    ),
+    (
+        "review.comment_posted",
+        "💬 评审结果已回帖到 PR",
+        "💬 Review posted to the PR",
+    ),
+    // 结构分析（review 与 commit 共用）
+    (
+        "structure.done",
+        "  ✅ 结构分析完成",
+        "  ✅ Structural analysis done",
+    ),
+    (
+        "structure.failed",
+        "  ⚠️ 结构分析失败: {error}",
+        "  ⚠️ Structural analysis failed: {error}",
+    ),
+    // commit
+    (
+        "commit.message",
+        "📝 提交信息: {message}",
+        "📝 Commit message: {message}",
+    ),
+    (
+        "issue.from_branch",
+        "🔗 根据分支 {branch} 关联 Issue: {id}",
+        "🔗 Linked issue {id} from branch {branch}",
+    ),
+    (
+        "commit.diff_truncated",
+        "✂️ 变更超出 token 上限 {limit}，仅完整发送 {kept}/{total} 个文件的 diff，其余文件以摘要代替",
+        "✂️ Diff exceeds the {limit}-token limit; only {kept}/{total} files are sent in full, the rest are summarized",
+    ),
+    (
+        "commit.truncated_note",
+        "注：变更较大，本提交信息基于 {kept}/{total} 个文件的完整 diff 及其余文件的摘要生成。",
+        "Note: large change; this message was generated from the full diff of {kept}/{total} files and a summary of the rest.",
+    ),
+    (
+        "commit.devops_unavailable",
+        "⚠️ DevOps功能未启用或未配置，无法获取Issue信息",
+        "⚠️ DevOps is disabled or not configured; issue details are unavailable",
+    ),
+    (
+        "commit.tree_sitter",
+        "🌳 正在进行Tree-sitter结构分析...",
+        "🌳 Running Tree-sitter structural analysis...",
+    ),
+    (
+        "commit.no_code",
+        "⚠️ 未能从diff中提取到代码内容",
+        "⚠️ No code content found in the diff",
+    ),
+    (
+        "commit.unsupported_language",
+        "⚠️ 不支持的语言或无法推断语言类型",
+        "⚠️ Unsupported or undetectable language",
+    ),
+    (
+        "commit.detected_language",
+        "  检测到语言: {lang}",
+        "  Detected language: {lang}",
+    ),
+    (
+        "commit.function_count",
+        "     函数数量: {count}",
+        "     Functions: {count}",
+    ),
+    (
+        "commit.class_count",
+        "     类数量: {count}",
+        "     Classes: {count}",
+    ),
+    (
+        "commit.tree_sitter_init_failed",
+        "  ⚠️ Tree-sitter管理器初始化失败: {error}",
+        "  ⚠️ Failed to initialize Tree-sitter manager: {error}",
+    ),
+    (
+        "commit.dry_run",
+        "🔍 干运行模式 - 不会实际提交",
+        "🔍 Dry run - nothing will be committed",
+    ),
+    (
+        "commit.adding_all",
+        "📝 添加所有变更到暂存区...",
+        "📝 Staging all changes...",
+    ),
+    (
+        "commit.committing",
+        "📝 执行提交: {message}",
+        "📝 Committing: {message}",
+    ),
+    (
+        "commit.success",
+        "✅ 提交成功: {hash}",
+        "✅ Committed: {hash}",
+    ),
+    (
+        "commit.failed",
+        "❌ 提交失败: {error}",
+        "❌ Commit failed: {error}",
+    ),
+    // config
+    (
+        "config.load_failed",
+        "❌ 配置加载失败: {error}",
+        "❌ Failed to load configuration: {error}",
+    ),
+    (
+        "config.load_hint_file",
+        "💡 提示: 请检查 ~/.config/gitai/config.toml 文件",
+        "💡 Hint: check ~/.config/gitai/config.toml",
+    ),
+    (
+        "config.load_hint_init",
+        "💡 可以使用 'gitai init' 初始化配置",
+        "💡 Run 'gitai init' to create a configuration",
+    ),
+    (
+        "config.checking",
+        "🔍 检查配置状态...",
+        "🔍 Checking configuration...",
+    ),
+    (
+        "config.file_found",
+        "✅ 配置文件: {path}",
+        "✅ Config file: {path}",
+    ),
+    (
+        "config.file_missing",
+        "❌ 配置文件不存在",
+        "❌ Config file not found",
+    ),
+    (
+        "config.cache_dir",
+        "✅ 缓存目录: {path}",
+        "✅ Cache directory: {path}",
+    ),
+    (
+        "config.rules_ready",
+        "  ✅ 规则缓存: 已就绪",
+        "  ✅ Rule cache: ready",
+    ),
+    (
+        "config.rules_missing",
+        "  ⚠️  规则缓存: 未找到",
+        "  ⚠️  Rule cache: not found",
+    ),
+    (
+        "config.tree_sitter_ready",
+        "  ✅ Tree-sitter缓存: 已就绪",
+        "  ✅ Tree-sitter cache: ready",
+    ),
+    (
+        "config.tree_sitter_missing",
+        "  ⚠️  Tree-sitter缓存: 未找到",
+        "  ⚠️  Tree-sitter cache: not found",
+    ),
+    (
+        "config.cache_missing",
+        "❌ 缓存目录不存在",
+        "❌ Cache directory not found",
+    ),
+    (
+        "config.toml_unavailable",
+        "📋 TOML 格式输出暂不可用",
+        "📋 TOML output is not available yet",
+    ),
+    (
+        "config.current",
+        "📋 当前配置:",
+        "📋 Current configuration:",
+    ),
+    (
+        "config.ai_service",
+        "  AI服务: {url}",
+        "  AI service: {url}",
+    ),
+    (
+        "config.ai_model",
+        "  AI模型: {model}",
+        "  AI model: {model}",
+    ),
+    (
+        "config.scan_path",
+        "  扫描路径: {path}",
+        "  Scan path: {path}",
+    ),
+    (
+        "config.updating",
+        "🔄 更新资源...",
+        "🔄 Updating resources...",
+    ),
+    (
+        "config.offline_update",
+        "⚠️  离线模式下无法更新资源",
+        "⚠️  Resources cannot be updated in offline mode",
+    ),
+    (
+        "config.force_update",
+        "🚀 强制更新所有资源...",
+        "🚀 Force-updating all resources...",
+    ),
+    ("config.updated", "✅ 资源更新完成", "✅ Resources updated"),
+    (
+        "config.resource_config_failed",
+        "❌ 无法加载资源配置",
+        "❌ Failed to load resource configuration",
+    ),
+    (
+        "config.resetting",
+        "🔄 重置配置...",
+        "🔄 Resetting configuration...",
+    ),
+    (
+        "config.backup",
+        "💾 已备份到: {path}",
+        "💾 Backed up to: {path}",
+    ),
+    (
+        "config.reset_done",
+        "✅ 配置已重置到默认值",
+        "✅ Configuration reset to defaults",
+    ),
+    ("config.cleaning", "🧹 清理缓存...", "🧹 Cleaning cache..."),
+    ("config.cleaned", "✅ 缓存清理完成", "✅ Cache cleaned"),
+    (
+        "config.cleaned_category",
+        "  {category}: 释放 {size}",
+        "  {category}: freed {size}",
+    ),
+    (
+        "config.dry_run_header",
+        "🔍 预演（不会修改任何文件）:",
+        "🔍 Dry run (nothing will be changed):",
+    ),
+    (
+        "config.dry_run_nothing",
+        "  无需任何操作",
+        "  Nothing to do",
+    ),
+    ("config.plan_download", "下载", "download"),
+    ("config.plan_update", "更新", "update"),
+    ("config.plan_delete", "删除", "delete"),
+    ("config.plan_size_unknown", "大小未知", "size unknown"),
+    (
+        "config.dry_run_total",
+        "  共 {count} 项，已知大小合计 {size}",
+        "  {count} item(s), {size} in known sizes",
+    ),
+    (
+        "cache.du_header",
+        "📦 缓存目录: {path}",
+        "📦 Cache directory: {path}",
+    ),
+    (
+        "cache.du_missing",
+        "  缓存目录不存在，尚未占用空间",
+        "  Cache directory does not exist; nothing is cached yet",
+    ),
+    ("cache.du_files", "{count} 个文件", "{count} file(s)"),
+    ("cache.du_other", "其他", "other"),
+    ("cache.du_total", "合计", "total"),
+    (
+        "config.get_unset",
+        "ℹ️  {key} 未设置",
+        "ℹ️  {key} is not set",
+    ),
+    (
+        "config.set_done",
+        "✅ 已设置 {key} = {value}",
+        "✅ Set {key} = {value}",
+    ),
+    (
+        "scan.finding_item",
+        "    {index}. [{fingerprint}] {title} ({location})",
+        "    {index}. [{fingerprint}] {title} ({location})",
+    ),
+    (
+        "scan.explain_hint",
+        "💡 使用 `gitai scan --explain <编号|指纹>` 查看单个问题的 AI 解释与修复建议",
+        "💡 Run `gitai scan --explain <index|fingerprint>` for an AI explanation and fix of a single finding",
+    ),
+    (
+        "scan.finding_not_found",
+        "未找到编号或指纹为 {selector} 的问题（本次扫描共 {count} 个问题）",
+        "No finding with index or fingerprint {selector} ({count} findings in this scan)",
+    ),
+    ("scan.explain_rule", "规则: {rule}", "Rule: {rule}"),
+    ("scan.explain_fingerprint", "指纹: {fingerprint}", "Fingerprint: {fingerprint}"),
+    ("scan.explain_ai", "🤖 AI 解释与修复建议:", "🤖 AI explanation and suggested fix:"),
+    (
+        "scan.explain_cached",
+        "🤖 AI 解释与修复建议（缓存）:",
+        "🤖 AI explanation and suggested fix (cached):",
+    ),
+    (
+        "scan.explain_rule_only",
+        "📝 规则说明（AI 不可用）:",
+        "📝 Rule description (AI unavailable):",
+    ),
+    ("scan.explain_rule_fix", "规则建议的修复:", "Fix suggested by the rule:"),
+    (
+        "scan.explain_calling_ai",
+        "🤖 正在调用 AI 解释问题...",
+        "🤖 Asking AI to explain the finding...",
+    ),
+    (
+        "scan.explain_ai_failed",
+        "⚠️ AI 解释失败，改用规则说明: {error}",
+        "⚠️ AI explanation failed, falling back to the rule description: {error}",
+    ),
+    (
+        "scan.explain_ai_disabled",
+        "本构建未启用 AI 功能",
+        "AI support is not enabled in this build",
+    ),
+    ("explain.severity", "严重程度: {severity}", "Severity: {severity}"),
+    ("explain.languages", "适用语言: {languages}", "Languages: {languages}"),
+    ("explain.file", "规则文件: {path}", "Rule file: {path}"),
+    ("explain.description", "📝 说明:", "📝 Description:"),
+    ("explain.localized", "🌐 本地化说明:", "🌐 Localized description:"),
+    ("explain.metadata", "🏷️ 元数据:", "🏷️ Metadata:"),
+    ("explain.example", "💡 示例:", "💡 Example:"),
+    (
+        "explain.not_found",
+        "本地规则中未找到 {rule}（规则目录: {dir}），请运行 `gitai update` 更新规则后重试",
+        "Rule {rule} not found in local rules ({dir}); run `gitai update` to refresh the rules and try again",
+    ),
+    (
+        "explain.translate_failed",
+        "⚠️ 规则说明翻译失败: {error}",
+        "⚠️ Failed to translate rule description: {error}",
+    ),
+    (
+        "review.diff_chunked",
+        "📏 变更约 {tokens} tokens，超过上限 {limit}，将按文件分 {chunks} 段评审后汇总",
+        "📏 Diff is ~{tokens} tokens, over the {limit} limit; reviewing it in {chunks} per-file chunks and summarizing",
+    ),
+    (
+        "review.diff_structural_only",
+        "📏 变更约 {tokens} tokens，远超上限 {limit}，跳过 AI 评审，仅进行结构分析",
+        "📏 Diff is ~{tokens} tokens, far over the {limit} limit; skipping AI review and running structural analysis only",
+    ),
+    (
+        "review.reviewing_chunk",
+        "🤖 正在评审第 {index}/{total} 段...",
+        "🤖 Reviewing chunk {index}/{total}...",
+    ),
+    (
+        "review.summarized_chunked",
+        "变更约 {tokens} tokens，超过评审上限，已按文件分 {chunks} 段评审后汇总",
+        "Diff was ~{tokens} tokens, over the review limit; it was reviewed in {chunks} per-file chunks and summarized",
+    ),
+    (
+        "review.summarized_structural",
+        "变更约 {tokens} tokens，超过评审上限，结果仅基于结构分析，未经 AI 评审",
+        "Diff was ~{tokens} tokens, over the review limit; the result is based on structural analysis only, without AI review",
+    ),
+    (
+        "review.quality_metrics",
+        "变更代码可维护性指数 {index}/100（最大圈复杂度 {cyclomatic}，最大认知复杂度 {cognitive}，{level}）",
+        "Maintainability index of changed code: {index}/100 (max cyclomatic complexity {cyclomatic}, max cognitive complexity {cognitive}, {level})",
+    ),
+    ("complexity.very_low", "非常简单", "very low complexity"),
+    ("complexity.low", "简单", "low complexity"),
+    ("complexity.medium", "中等复杂", "medium complexity"),
+    ("complexity.high", "复杂", "high complexity"),
+    ("complexity.very_high", "非常复杂", "very high complexity"),
+    (
+        "review.pre_existing_findings",
+        "\n🗂️ 既有问题（{count} 个，不在本次变更行上）:",
+        "\n🗂️ Pre-existing issues ({count}, not on changed lines):",
+    ),
+    (
+        "review.skipped_files",
+        "{binary} 个二进制文件、{oversized} 个超大文件、{generated} 个生成文件有变更，未参与评审",
+        "{binary} binary, {oversized} oversized and {generated} generated file(s) changed, skipped from review",
+    ),
+    (
+        "graph.incremental",
+        "♻️  增量构建依赖图：复用 {reused} 个文件，重新解析 {reparsed} 个，移除 {removed} 个",
+        "♻️  Incremental graph build: reused {reused} file(s), reparsed {reparsed}, removed {removed}",
+    ),
+    (
+        "analysis.skipped_generated",
+        "ℹ️  跳过 {count} 个生成文件（配置 analysis.generated_patterns，--include-generated 可保留）",
+        "ℹ️  Skipped {count} generated file(s) (analysis.generated_patterns; use --include-generated to keep them)",
+    ),
+    (
+        "analysis.failed_files",
+        "⚠️  {count} 个文件分析失败，未计入结果（原因见日志）",
+        "⚠️  {count} file(s) failed to analyze and were left out (see the log for details)",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_catalog_keys_unique_and_placeholders_match() {
+        let mut keys = HashSet::new();
+        for (key, zh, en) in MESSAGES {
+            assert!(keys.insert(key), "重复的消息键: {key}");
+            assert_eq!(placeholders(zh), placeholders(en), "占位符不一致: {key}");
+        }
+    }
+
+    #[test]
+    fn test_lookup_render_and_locale() {
+        assert_eq!(lookup("scan.result_header", Lang::En), "📊 Scan result:");
+        assert_eq!(lookup("scan.result_header", Lang::Zh), "📊 扫描结果:");
+        assert_eq!(lookup("missing.key", Lang::En), "missing.key");
+        assert_eq!(
+            render(
+                lookup("commit.success", Lang::En),
+                &[("hash", "abc123".to_string())]
+            ),
+            "✅ Committed: abc123"
+        );
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("zh_CN.UTF-8"), Some(Lang::Zh));
+        assert_eq!(Lang::from_locale("C.UTF-8"), None);
+        assert_eq!("EN".parse::<Lang>(), Ok(Lang::En));
+        assert!("fr".parse::<Lang>().is_err());
+    }
+}
@@ -8,19 +8,32 @@
 pub mod analysis;
 pub mod architectural_impact;
 pub mod args;
+pub mod bench;
 pub mod commit;
 pub mod config;
+pub mod config_edit;
 pub mod config_init;
+pub mod config_layers;
+pub mod config_migrate;
 pub mod context;
+pub mod deviation;
+pub mod doctor;
 pub mod error;
 #[cfg(test)]
 pub mod error_tests;
+pub mod evaluation;
 pub mod features;
 pub mod git;
+pub mod hooks;
+pub mod http;
+pub mod i18n;
 pub mod project_insights;
+pub mod project_metadata;
 pub mod prompts;
 pub mod resource_manager;
 pub mod review;
+pub mod risk_report;
+pub mod rules;
 pub mod tree_sitter;
 pub mod utils;
 
@@ -34,8 +47,14 @@ pub mod domain;
 #[cfg(feature = "ai")]
 pub mod ai;
 
+#[cfg(feature = "security")]
+pub mod rule_sources;
 #[cfg(feature = "security")]
 pub mod scan;
+#[cfg(feature = "security")]
+pub mod scan_baseline;
+#[cfg(feature = "security")]
+pub mod scan_explain;
 #[cfg(feature = "update-notifier")]
 pub mod update;
 
@@ -54,7 +73,11 @@ pub use context::{Issue, OperationContext, OperationOptions};
 pub use error::{GitAIError, Result};
 pub use project_insights::{InsightsGenerator, ProjectInsights};
 pub use prompts::{PromptContext, PromptManager};
-pub use tree_sitter::{StructuralSummary, SupportedLanguage, TreeSitterManager};
+pub use review::{register_pass, AnalysisPass};
+pub use tree_sitter::{
+    DirectoryAnalysisResult, FileAnalysisResult, StructuralSummary, SupportedLanguage,
+    TreeSitterManager,
+};
 pub use utils::error_handling::{convenience, DomainErrorHandler, SafeResult};
 
 // 导出新的基础设施组件
@@ -8,9 +8,11 @@
 
 // Use modules from the library crate
 use gitai::{
-    args::{Args, Command, ConfigAction, PromptAction},
+    args::{Args, CacheAction, Command, ConfigAction, EvalAction, HooksAction, PromptAction},
     config::{self},
+    error::{exit_codes, ConfigError, ErrorReport, GitAIError, Result},
     git,
+    utils::output,
 };
 
 #[cfg(feature = "metrics")]
@@ -21,7 +23,7 @@ use gitai::args::MetricsAction;
 use gitai::ai;
 
 #[cfg(feature = "security")]
-use gitai::scan;
+use gitai::{error::ScanError, scan};
 
 #[cfg(feature = "update-notifier")]
 use gitai::update;
@@ -37,7 +39,6 @@ use gitai::{commit, features, review};
 
 use std::fs;
 use std::path::PathBuf;
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
 fn init_logger() {
     use std::io::Write;
@@ -45,51 +46,108 @@ fn init_logger() {
     env_logger::Builder::from_default_env()
         .filter_level(log::LevelFilter::Info)
         .format(|buf, record| {
-            let level_style = match record.level() {
-                log::Level::Error => "\x1b[31m", // 红色
-                log::Level::Warn => "\x1b[33m",  // 黄色
-                log::Level::Info => "\x1b[32m",  // 绿色
-                log::Level::Debug => "\x1b[36m", // 青色
-                log::Level::Trace => "\x1b[90m", // 灰色
+            let level_style = if !output::color_enabled() {
+                ""
+            } else {
+                match record.level() {
+                    log::Level::Error => "\x1b[31m", // 红色
+                    log::Level::Warn => "\x1b[33m",  // 黄色
+                    log::Level::Info => "\x1b[32m",  // 绿色
+                    log::Level::Debug => "\x1b[36m", // 青色
+                    log::Level::Trace => "\x1b[90m", // 灰色
+                }
             };
 
+            // 带上请求 ID，便于关联同一次调用在各阶段的日志
+            let request_id = gitai::context::current_request_id().unwrap_or_default();
             writeln!(
                 buf,
-                "{}{} [{}] {}",
+                "{}{} [{}] [req={}] {}",
                 level_style,
                 chrono::Local::now().format("%H:%M:%S"),
                 record.level(),
-                record.args()
+                request_id,
+                output::plain(&record.args().to_string())
             )
         })
         .init();
 }
 
-/// 获取缓存目录
-#[allow(dead_code)]
-fn get_cache_dir() -> Result<PathBuf> {
-    let cache_dir = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".cache")
-        .join("gitai");
-
-    fs::create_dir_all(&cache_dir)?;
-    Ok(cache_dir)
-}
-
 // mod cli;  // Temporarily disabled due to compilation errors
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    let request_id =
+        gitai::context::set_process_request_id(gitai::context::new_request_id()).to_string();
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            // 帮助与版本信息不属于错误，交由 clap 正常输出
+            if e.use_stderr() && std::env::args().any(|arg| arg == "--json-errors") {
+                let rendered = e.to_string();
+                let message = rendered
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: ");
+                eprintln!(
+                    "{}",
+                    ErrorReport::usage(message)
+                        .with_request_id(Some(request_id))
+                        .to_json()
+                );
+                std::process::exit(exit_codes::USAGE);
+            }
+            e.exit();
+        }
+    };
+    let json_errors = args.json_errors;
+    output::init(args.no_color, args.no_emoji);
+    output::set_quiet(args.quiet);
+    gitai::i18n::init(args.ui_lang);
+    if args.no_analysis_cache {
+        gitai::tree_sitter::set_cache_enabled(false);
+    }
+
     // Temporarily use legacy main while CLI handlers are being developed
-    legacy_main().await
+    if let Err(e) = legacy_main(args).await {
+        if json_errors {
+            eprintln!(
+                "{}",
+                ErrorReport::from(&e)
+                    .with_request_id(Some(request_id))
+                    .to_json()
+            );
+        } else {
+            eprintln!("{}", output::plain(&e.to_string()));
+        }
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// 当前构建未包含某项功能时返回的错误
+#[allow(dead_code)]
+fn feature_disabled(json_errors: bool, name: &str, feature: &str) -> GitAIError {
+    if !json_errors {
+        eprintln!("❌ {name}功能未启用");
+        eprintln!("💡 请使用包含 '{feature}' 功能的构建版本");
+    }
+    GitAIError::MissingDependency(format!("{name}功能未启用（需要 '{feature}' 功能）"))
+}
+
+/// 将扫描过程中未分类的错误归入扫描错误，便于区分配置错误与扫描失败
+#[cfg(feature = "security")]
+fn scan_failure(e: GitAIError) -> GitAIError {
+    match e {
+        GitAIError::Unknown(msg) => GitAIError::ScanTool(ScanError::ScanExecutionFailed(msg)),
+        other => other,
+    }
 }
 
 // 保留原有的处理函数作为后备，直到完全迁移完成
 #[allow(dead_code)]
-async fn legacy_main() -> Result<()> {
+async fn legacy_main(args: Args) -> Result<()> {
     init_logger();
-    let args = Args::parse();
 
     // 处理 Init 命令（不需要配置）
     if let Command::Init {
@@ -110,17 +168,65 @@ async fn legacy_main() -> Result<()> {
         .await;
     }
 
+    // 自检自行加载配置，配置无效时也能给出报告
+    if let Command::Doctor { format } = &args.command {
+        let options = gitai::doctor::DoctorOptions {
+            offline: args.offline,
+        };
+        let report = gitai::doctor::run(&options).await;
+        if format == "json" {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print!("{}", report.to_text());
+        }
+        if report.has_failures() {
+            let (_, _, failed) = report.counts();
+            return Err(GitAIError::GateFailed(format!(
+                "自检发现 {failed} 项致命问题"
+            )));
+        }
+        return Ok(());
+    }
+
+    // 缓存统计只读取缓存目录，不依赖完整配置
+    if let Command::Cache { action } = &args.command {
+        return handle_cache(action);
+    }
+
     // 加载配置文件，提供友好错误信息
-    let config = match config::Config::load() {
-        Ok(config) => {
+    let (config, profile) = match config::Config::load() {
+        Ok(mut config) => {
             log::debug!("配置文件加载成功");
+            // 命名配置组：排除模式并入生成文件过滤，其余参数在各子命令中与命令行参数合并
+            let profile = match args.command.profile() {
+                Some(name) => config
+                    .profile(name)
+                    .map_err(|e| GitAIError::Config(ConfigError::ValidationFailed(e)))?
+                    .clone(),
+                None => config::ProfileConfig::default(),
+            };
             config
+                .analysis
+                .generated_patterns
+                .extend(profile.exclude.iter().cloned());
+            gitai::utils::generated::configure(if args.include_generated {
+                gitai::utils::generated::GeneratedFilter::disabled()
+            } else {
+                config.analysis.generated_filter()
+            });
+            let mut walk = config.analysis.walk_options();
+            walk.max_depth = args.max_depth.or(walk.max_depth);
+            gitai::utils::walk::configure(walk);
+            gitai::http::configure(config.http.clone());
+            (config, profile)
         }
         Err(e) => {
-            eprintln!("❌ 配置加载失败: {e}");
-            eprintln!("💡 提示: 请检查 ~/.config/gitai/config.toml 文件");
-            eprintln!("💡 可以使用 'gitai init' 初始化配置");
-            return Err(format!("配置加载失败: {e}").into());
+            if !args.json_errors {
+                eprintln!("{}", gitai::tr!("config.load_failed", error = e));
+                eprintln!("{}", gitai::tr!("config.load_hint_file"));
+                eprintln!("{}", gitai::tr!("config.load_hint_init"));
+            }
+            return Err(GitAIError::Config(ConfigError::LoadFailed(e.to_string())));
         }
     };
 
@@ -133,11 +239,48 @@ async fn legacy_main() -> Result<()> {
             security_scan,
             scan_tool,
             block_on_critical,
+            fail_on,
             issue_id,
             space_id,
             full,
-            ..
+            pr,
+            post_comment,
+            staged,
+            working,
+            stash,
+            blob,
+            plan,
+            max_diff_tokens,
+            profile: _,
         } => {
+            // 命令行参数优先于配置组
+            let preset = &profile.review;
+            let format = format
+                .or_else(|| preset.format.clone())
+                .unwrap_or_else(|| "text".to_string());
+            let output = if output.is_empty() {
+                preset.output.clone()
+            } else {
+                output
+            };
+            let language = language.or_else(|| preset.language.clone());
+            let scan_tool = scan_tool.or_else(|| preset.scan_tool.clone());
+            let fail_on = fail_on.or_else(|| preset.fail_on.clone());
+            let max_diff_tokens = max_diff_tokens.or(preset.max_diff_tokens);
+            let (tree_sitter, security_scan, full) = (
+                tree_sitter || preset.tree_sitter,
+                security_scan || preset.security_scan,
+                full || preset.full,
+            );
+            // JSON 输出时进度信息会混入结果，等同于 --quiet
+            if format.eq_ignore_ascii_case("json") {
+                output::set_quiet(true);
+            }
+            // 评审远程 PR 时本地分支与之无关，不做推断
+            let issue_id = match (issue_id, &pr) {
+                (None, None) => issue_id_from_branch(&config),
+                (issue_id, _) => issue_id,
+            };
             let review_config = review::ReviewConfig::from_args(
                 language,
                 format,
@@ -149,12 +292,44 @@ async fn legacy_main() -> Result<()> {
                 issue_id,
                 space_id,
                 full,
-            );
-            review::execute_review(&config, review_config).await?;
+                pr,
+                post_comment,
+            )
+            .with_diff_selection(match (stash, blob) {
+                (Some(index), _) => review::DiffSelection::Stash(index),
+                (None, Some(spec)) => review::DiffSelection::Blob(spec),
+                (None, None) => review::DiffSelection::from_flags(staged, working),
+            })
+            .with_max_diff_tokens(max_diff_tokens);
+            if plan {
+                let review_plan = review::plan_review(&config, &review_config).await?;
+                if review_config.format.eq_ignore_ascii_case("json") {
+                    println!("{}", serde_json::to_string_pretty(&review_plan)?);
+                } else {
+                    print!("{}", review_plan.to_text());
+                }
+            } else {
+                let result = review::execute_review(&config, review_config).await?;
+                // --block-on-critical 等同于 --fail-on critical
+                let fail_on = fail_on.or_else(|| block_on_critical.then(|| "critical".to_string()));
+                if let Some(threshold) = fail_on {
+                    let threshold: review::Severity =
+                        threshold.parse().unwrap_or(review::Severity::Critical);
+                    let blocking = result.findings_at_or_above(&threshold);
+                    if !blocking.is_empty() {
+                        return Err(GitAIError::GateFailed(format!(
+                            "评审发现 {} 个严重程度不低于 {threshold:?} 的问题",
+                            blocking.len()
+                        )));
+                    }
+                }
+            }
         }
         #[cfg(feature = "security")]
         Command::Scan {
+            paths,
             path,
+            per_path,
             tool,
             full,
             remote,
@@ -164,33 +339,79 @@ async fn legacy_main() -> Result<()> {
             translate,
             auto_install,
             lang,
+            rules,
+            rules_only,
             no_history,
             timeout,
             benchmark,
+            fail_on,
+            explain,
+            baseline,
+            update_baseline,
+            profile: _,
         } => {
+            // 命令行参数优先于配置组
+            let preset = &profile.scan;
+            let tool = tool
+                .or_else(|| preset.tool.clone())
+                .unwrap_or_else(|| "auto".to_string());
+            let format = format
+                .or_else(|| preset.format.clone())
+                .unwrap_or_else(|| "text".to_string());
+            let output = if output.is_empty() {
+                preset.output.clone()
+            } else {
+                output
+            };
+            let lang = lang.or_else(|| preset.lang.clone());
+            let timeout = timeout.or(preset.timeout);
+            let fail_on = fail_on.or_else(|| preset.fail_on.clone());
+            // 命令行指定的自定义规则覆盖配置
+            let mut config = config.clone();
+            if let Some(rules) = rules {
+                config.scan.custom_rules_dir = Some(rules.display().to_string());
+            } else if let Some(rules) = &preset.rules {
+                config.scan.custom_rules_dir = Some(rules.clone());
+            }
+            config.scan.rules_only |= rules_only || preset.rules_only;
+            // 位置参数与 --path 合并，重复的路径只扫描一次
+            let mut scan_paths: Vec<PathBuf> = Vec::new();
+            for path in paths.into_iter().chain(path) {
+                if !scan_paths.contains(&path) {
+                    scan_paths.push(path);
+                }
+            }
+            if scan_paths.is_empty() {
+                scan_paths.push(PathBuf::from("."));
+            }
             handle_scan(
                 &config,
-                &path,
+                &scan_paths,
+                per_path,
                 &tool,
                 full,
                 remote,
                 update_rules,
                 &format,
-                output,
+                &output,
                 translate,
                 auto_install,
                 lang.as_deref(),
-                no_history,
+                no_history || preset.no_history,
                 timeout,
                 benchmark,
+                fail_on.as_deref(),
+                explain.as_deref(),
+                baseline.as_deref(),
+                update_baseline,
+                args.offline,
             )
-            .await?;
+            .await
+            .map_err(scan_failure)?;
         }
         #[cfg(not(feature = "security"))]
         Command::Scan { .. } => {
-            eprintln!("❌ 安全扫描功能未启用");
-            eprintln!("💡 请使用包含 'security' 功能的构建版本");
-            return Err("功能未启用".into());
+            return Err(feature_disabled(args.json_errors, "安全扫描", "security"));
         }
         #[cfg(feature = "security")]
         Command::ScanHistory { limit, format: _ } => {
@@ -198,12 +419,14 @@ async fn legacy_main() -> Result<()> {
         }
         #[cfg(not(feature = "security"))]
         Command::ScanHistory { .. } => {
-            eprintln!("❌ 安全扫描历史功能未启用");
-            eprintln!("💡 请使用包含 'security' 功能的构建版本");
-            return Err("功能未启用".into());
+            return Err(feature_disabled(
+                args.json_errors,
+                "安全扫描历史",
+                "security",
+            ));
         }
         Command::Prompts { action } => {
-            handle_prompts_action(&config, &action).await?;
+            handle_prompts_action(&config, &action, args.offline).await?;
         }
         Command::Commit {
             message,
@@ -213,16 +436,24 @@ async fn legacy_main() -> Result<()> {
             review,
             tree_sitter,
             dry_run,
+            max_diff_tokens,
+            template,
+            profile: _,
         } => {
+            // 命令行参数优先于配置组
+            let preset = &profile.commit;
+            let issue_id = issue_id.or_else(|| issue_id_from_branch(&config));
             let commit_config = commit::CommitConfig::from_args(
                 message,
                 issue_id,
                 space_id,
-                all,
-                review,
-                tree_sitter,
+                all || preset.all,
+                review || preset.review,
+                tree_sitter || preset.tree_sitter,
                 dry_run,
-            );
+            )
+            .with_max_diff_tokens(max_diff_tokens.or(preset.max_diff_tokens))
+            .with_template(template.or_else(|| preset.template.clone()));
             commit::execute_commit(&config, commit_config).await?;
         }
         #[cfg(feature = "update-notifier")]
@@ -235,9 +466,11 @@ async fn legacy_main() -> Result<()> {
         }
         #[cfg(not(feature = "update-notifier"))]
         Command::Update { .. } => {
-            eprintln!("❌ 更新功能未启用");
-            eprintln!("💡 请使用包含 'update-notifier' 功能的构建版本");
-            return Err("功能未启用".into());
+            return Err(feature_disabled(
+                args.json_errors,
+                "更新",
+                "update-notifier",
+            ));
         }
         Command::Git(git_args) => {
             // 默认不启用AI解释；--ai 显式开启；--noai 可显式关闭（当外部别名强制开启时）
@@ -261,21 +494,40 @@ async fn legacy_main() -> Result<()> {
             }
         }
         #[cfg(feature = "mcp")]
-        Command::Mcp { transport, addr } => {
-            handle_mcp(&config, &transport, &addr).await?;
+        Command::Mcp {
+            transport,
+            addr,
+            list_tools,
+        } => {
+            if list_tools {
+                handle_mcp_list_tools(&config).await?;
+            } else {
+                handle_mcp(&config, &transport, &addr).await?;
+            }
         }
         #[cfg(not(feature = "mcp"))]
         Command::Mcp { .. } => {
-            eprintln!("❌ MCP 服务器功能未启用");
-            eprintln!("💡 请使用包含 'mcp' 功能的构建版本");
-            return Err("功能未启用".into());
+            return Err(feature_disabled(args.json_errors, "MCP 服务器", "mcp"));
         }
-        Command::Init { .. } => {
+        Command::Init { .. } | Command::Doctor { .. } | Command::Cache { .. } => {
             // 已在上面处理
             unreachable!()
         }
         Command::Config { action } => {
-            handle_config(&config, &action, args.offline).await?;
+            // 与配置项对应的命令行参数，供 config explain 说明来源
+            let flags = args
+                .max_depth
+                .map(|depth| {
+                    gitai::config_layers::Layer::value(
+                        gitai::config_layers::Source::Flag,
+                        "--max-depth",
+                        "analysis.max_depth",
+                        depth as i64,
+                    )
+                })
+                .into_iter()
+                .collect();
+            handle_config(&config, &action, args.offline, flags).await?;
         }
         #[cfg(feature = "metrics")]
         Command::Metrics { action } => {
@@ -283,15 +535,17 @@ async fn legacy_main() -> Result<()> {
         }
         #[cfg(not(feature = "metrics"))]
         Command::Metrics { .. } => {
-            eprintln!("❌ 度量功能未启用");
-            eprintln!("💡 请使用包含 'metrics' 功能的构建版本");
-            return Err("功能未启用".into());
+            return Err(feature_disabled(args.json_errors, "度量", "metrics"));
         }
         Command::Graph {
             path,
             output,
+            format,
             threshold,
+            node_types,
+            edge_types,
             summary,
+            critical_nodes,
             radius,
             top_k,
             seeds_from_diff,
@@ -304,8 +558,58 @@ async fn legacy_main() -> Result<()> {
             with_paths,
             path_samples,
             path_max_hops,
+            max_concurrent,
+            incremental,
         } => {
-            if summary {
+            gitai::architectural_impact::graph_cache::set_incremental(incremental);
+            let filter = gitai::architectural_impact::dependency_graph::GraphFilter::from_names(
+                &node_types,
+                &edge_types,
+            )
+            .map_err(GitAIError::Unknown)?;
+            if format == "bundle" {
+                use gitai::architectural_impact::graph_export::export_bundle_string;
+                let bundle = export_bundle_string(
+                    &path,
+                    threshold,
+                    radius,
+                    top_k,
+                    seeds_from_diff,
+                    budget_tokens,
+                    community,
+                    &comm_alg,
+                    max_communities,
+                    max_nodes_per_community,
+                    with_paths,
+                    path_samples,
+                    path_max_hops,
+                    &filter,
+                    max_concurrent,
+                )
+                .await?;
+                if let Some(out) = output {
+                    gitai::utils::atomic_write::write_atomic(&out, bundle)?;
+                    println!("📁 图分析归档已导出: {}", out.display());
+                } else {
+                    println!("{bundle}");
+                }
+            } else if critical_nodes {
+                use gitai::architectural_impact::graph_export::export_critical_nodes_string;
+                let report = export_critical_nodes_string(
+                    &path,
+                    top_k,
+                    &summary_format,
+                    &filter,
+                    max_concurrent,
+                )
+                .await?;
+                if let Some(out) = output {
+                    gitai::utils::atomic_write::write_atomic(&out, report)?;
+                    println!("📁 关键节点报告已导出: {}", out.display());
+                } else {
+                    print!("{report}");
+                }
+            } else if summary {
                 handle_graph_summary(
                     &path,
                     radius,
@@ -320,30 +624,181 @@ async fn legacy_main() -> Result<()> {
                     with_paths,
                     path_samples,
                     path_max_hops,
+                    &filter,
+                    max_concurrent,
                     output.as_ref(),
                 )
                 .await?;
             } else {
-                handle_graph_export(&path, output.as_ref(), threshold).await?;
+                handle_graph_export(&path, output.as_ref(), threshold, &filter, max_concurrent)
+                    .await?;
             }
         }
+        Command::ApiDiff { base, head, format } => {
+            handle_api_diff(&base, &head, &format).await?;
+        }
+        Command::SemverSuggest { base, head, format } => {
+            handle_semver_suggest(&base, &head, &format).await?;
+        }
+        Command::Report {
+            path,
+            base,
+            head,
+            format,
+            output,
+            no_scan,
+            no_metrics,
+            no_impact,
+        } => {
+            let sections = ReportSections {
+                scan: !no_scan,
+                metrics: !no_metrics,
+                impact: !no_impact,
+            };
+            handle_report(&config, &path, &base, &head, &format, &output, sections).await?;
+        }
+        Command::Deps { path, format } => {
+            handle_deps(&path, &format).await?;
+        }
+        Command::DocsCoverage {
+            path,
+            fail_under,
+            format,
+        } => {
+            handle_docs_coverage(&path, fail_under, &format).await?;
+        }
+        Command::Analyze {
+            stdin: _,
+            files_from: Some(list),
+            jobs,
+            lang: _,
+        } => {
+            let content = std::fs::read_to_string(&list)?;
+            let paths = gitai::tree_sitter::read_file_list(&content);
+            let batch = gitai::tree_sitter::analyze_files(&paths, jobs)
+                .await
+                .map_err(GitAIError::from)?;
+            println!("{}", serde_json::to_string_pretty(&batch)?);
+        }
+        Command::Analyze { lang, .. } => {
+            let language = lang
+                .as_deref()
+                .and_then(gitai::tree_sitter::SupportedLanguage::from_name)
+                .ok_or_else(|| GitAIError::Unknown("缺少 --lang 参数".to_string()))?;
+            if language.language().is_none() {
+                return Err(feature_disabled(
+                    args.json_errors,
+                    &format!("{} 语言分析", language.name()),
+                    &format!("tree-sitter-{}", language.name()),
+                ));
+            }
+            let summary = gitai::tree_sitter::analyze_reader(std::io::stdin().lock(), language)
+                .await
+                .map_err(GitAIError::from)?;
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        Command::Eval { action } => {
+            handle_eval(&config, &action)?;
+        }
+        #[cfg(feature = "devops")]
+        Command::Deviation {
+            issue_id,
+            space_id,
+            staged,
+            working,
+            format,
+            fail_below,
+        } => {
+            handle_deviation(
+                &config,
+                &issue_id,
+                space_id,
+                review::DiffSelection::from_flags(staged, working),
+                &format,
+                fail_below,
+            )
+            .await?;
+        }
+        #[cfg(not(feature = "devops"))]
+        Command::Deviation { .. } => {
+            return Err(feature_disabled(
+                args.json_errors,
+                "需求偏离度分析",
+                "devops",
+            ));
+        }
+        Command::Hooks { action } => {
+            handle_hooks(&action)?;
+        }
         Command::Features { format } => {
             features::display_features(&format);
         }
+        Command::Explain {
+            rule_id,
+            translate,
+            format,
+        } => {
+            handle_explain(&config, &rule_id, translate && !args.offline, &format).await?;
+        }
+        Command::Bench {
+            path,
+            format,
+            output,
+            max_concurrent,
+            warm,
+            no_scan,
+        } => {
+            let options = gitai::bench::BenchOptions {
+                max_concurrent,
+                warm_cache: warm,
+                skip_scan: no_scan,
+            };
+            // JSON 输出时进度信息会混入结果，等同于 --quiet
+            if format == "json" {
+                output::set_quiet(true);
+            }
+            gitai::progress!("⏱️ 正在对 {} 执行性能基准...", path.display());
+            let report = gitai::bench::run(&config, &path, &options).await?;
+            let text = if format == "json" {
+                serde_json::to_string_pretty(&report)?
+            } else {
+                report.to_text()
+            };
+            match output {
+                Some(output_path) => {
+                    gitai::utils::atomic_write::write_atomic(&output_path, text)?;
+                    println!("✅ 基准报告已写入: {}", output_path.display());
+                }
+                None => println!("{text}"),
+            }
+        }
     }
 
     Ok(())
 }
 
+/// 未指定 `--issue-id` 时按 `[branch_issue]` 从当前分支名推断；未匹配时静默返回 None
+fn issue_id_from_branch(config: &config::Config) -> Option<String> {
+    let branch = gitai::git::current_branch()?;
+    let issue_id = config.branch_issue.extract(&branch)?;
+    gitai::progress!(
+        "{}",
+        gitai::tr!("issue.from_branch", branch = branch, id = issue_id)
+    );
+    Some(issue_id)
+}
+
 async fn handle_graph_export(
     path: &std::path::Path,
     output: Option<&std::path::PathBuf>,
     threshold: f32,
+    filter: &gitai::architectural_impact::dependency_graph::GraphFilter,
+    max_concurrent: Option<usize>,
 ) -> Result<()> {
     use gitai::architectural_impact::graph_export::export_dot_string;
-    let dot = export_dot_string(path, threshold).await?;
+    let dot = export_dot_string(path, threshold, filter, max_concurrent).await?;
     if let Some(out) = output {
-        std::fs::write(out, dot)?;
+        gitai::utils::atomic_write::write_atomic(out, dot)?;
         println!("📁 依赖图已导出: {}", out.display());
     } else {
         println!("{dot}");
@@ -366,6 +821,8 @@ async fn handle_graph_summary(
     with_paths: bool,
     path_samples: usize,
     path_max_hops: usize,
+    filter: &gitai::architectural_impact::dependency_graph::GraphFilter,
+    max_concurrent: Option<usize>,
     output: Option<&std::path::PathBuf>,
 ) -> Result<()> {
     use gitai::architectural_impact::graph_export::export_summary_string;
@@ -383,10 +840,12 @@ async fn handle_graph_summary(
         with_paths,
         path_samples,
         path_max_hops,
+        filter,
+        max_concurrent,
     )
     .await?;
     if let Some(out) = output {
-        std::fs::write(out, &summary)?;
+        gitai::utils::atomic_write::write_atomic(out, &summary)?;
         println!("📁 图摘要已导出: {}", out.display());
     } else {
         println!("{summary}");
@@ -394,29 +853,431 @@ async fn handle_graph_summary(
     Ok(())
 }
 
+async fn handle_api_diff(base: &str, head: &str, format: &str) -> Result<()> {
+    use gitai::architectural_impact::api_diff::analyze_api_diff;
+    let report = analyze_api_diff(base, head).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+/// `gitai report` 各部分的开关
+struct ReportSections {
+    scan: bool,
+    metrics: bool,
+    impact: bool,
+}
+
+/// `gitai report`：依次生成安全扫描、质量指标与 API 影响部分；单个部分失败时记录原因并继续
+async fn handle_report(
+    config: &config::Config,
+    path: &std::path::Path,
+    base: &str,
+    head: &str,
+    format: &str,
+    output: &[PathBuf],
+    sections: ReportSections,
+) -> Result<()> {
+    use gitai::risk_report::{ImpactSection, RiskReport};
+    use gitai::utils::report_output;
+
+    let targets = report_output::resolve_targets(output, format, &["json", "markdown", "html"])?;
+    let mut report = RiskReport::new(path);
+
+    if !sections.scan {
+        report.skip("security", "已通过 --no-scan 关闭");
+    } else {
+        #[cfg(feature = "security")]
+        {
+            gitai::progress!("🛡️ 运行安全扫描...");
+            let options = scan::ScanOptions {
+                lang: None,
+                timeout: None,
+                include_version: false,
+            };
+            match scan::run_auto_scan(config, path, &options, &scan::builtin_scanners()) {
+                Ok(result) => {
+                    report.security =
+                        Some(gitai::risk_report::SecuritySection::from_scan(&result, 10))
+                }
+                Err(e) => report.skip("security", format!("扫描失败: {e}")),
+            }
+        }
+        #[cfg(not(feature = "security"))]
+        {
+            let _ = config;
+            report.skip("security", "未启用 security 功能");
+        }
+    }
+
+    if !sections.metrics {
+        report.skip("quality", "已通过 --no-metrics 关闭");
+    } else {
+        #[cfg(feature = "metrics")]
+        {
+            gitai::progress!("📊 计算质量指标...");
+            match measure_quality(path).await {
+                Ok(quality) => report.quality = Some(quality),
+                Err(e) => report.skip("quality", format!("质量度量失败: {e}")),
+            }
+        }
+        #[cfg(not(feature = "metrics"))]
+        report.skip("quality", "未启用 metrics 功能");
+    }
+
+    if !sections.impact {
+        report.skip("impact", "已通过 --no-impact 关闭");
+    } else {
+        use gitai::architectural_impact::api_diff::analyze_api_diff;
+        gitai::progress!("🔍 分析公共 API 影响...");
+        match analyze_api_diff(base, head).await {
+            Ok(diff) => report.impact = Some(ImpactSection::from_api_diff(&diff)),
+            Err(e) => report.skip("impact", format!("API 差异分析失败: {e}")),
+        }
+    }
+
+    let report = report.finalize();
+    if targets.is_empty() {
+        print!("{}", report.render(format)?);
+    } else {
+        report_output::write_targets(&targets, |format| report.render(format))?;
+        for target in &targets {
+            println!("📁 风险报告已保存到: {}", target.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// 度量 path 下代码的质量，并与最近一次记录的快照比较（不保存新快照）
+#[cfg(feature = "metrics")]
+async fn measure_quality(path: &std::path::Path) -> Result<gitai::risk_report::QualitySection> {
+    use gitai::metrics::QualityTracker;
+
+    let tracker = QualityTracker::new()?;
+    let dir = path.to_string_lossy();
+    let (summary, file_summaries) = analyze_code_structure(&dir, None).await?;
+    let insights = gitai::InsightsGenerator::generate(&summary, None);
+    let current = tracker.measure_snapshot(path, &summary, &file_summaries, &insights)?;
+    Ok(gitai::risk_report::QualitySection::from_snapshots(
+        &current,
+        tracker.get_snapshots().last(),
+    ))
+}
+
+async fn handle_semver_suggest(base: &str, head: &str, format: &str) -> Result<()> {
+    use gitai::architectural_impact::{api_diff::analyze_api_diff, version_bump};
+    let report = analyze_api_diff(base, head).await?;
+    let suggestion = version_bump::suggest_from_changes(&report.changes).with_current_version(base);
+    if format == "json" {
+        let value = serde_json::json!({
+            "suggestion": suggestion,
+            "api_diff": report,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        print!("{}", suggestion.to_text());
+    }
+    Ok(())
+}
+
+async fn handle_deps(path: &std::path::Path, format: &str) -> Result<()> {
+    use gitai::architectural_impact::dependency_report::build_dependency_report;
+    let report = build_dependency_report(path).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+async fn handle_docs_coverage(
+    path: &std::path::Path,
+    fail_under: Option<f64>,
+    format: &str,
+) -> Result<()> {
+    use gitai::architectural_impact::doc_coverage::build_doc_coverage;
+    let report = build_doc_coverage(path).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    let violations = report.violations(fail_under);
+    if !violations.is_empty() {
+        return Err(GitAIError::GateFailed(violations.join("；")));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "devops")]
+async fn handle_deviation(
+    config: &config::Config,
+    issue_id: &str,
+    space_id: Option<u64>,
+    selection: review::DiffSelection,
+    format: &str,
+    fail_below: Option<u8>,
+) -> Result<()> {
+    let issue_ids = gitai::context::parse_issue_ids(Some(issue_id.to_string()));
+    let diff = gitai::deviation::read_diff(selection)?;
+    let report = gitai::deviation::analyze_issues(config, &issue_ids, space_id, &diff).await?;
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+    if let Some(violation) = report.violation(fail_below) {
+        return Err(GitAIError::GateFailed(violation));
+    }
+    Ok(())
+}
+
+async fn handle_explain(
+    config: &config::Config,
+    rule_id: &str,
+    translate: bool,
+    format: &str,
+) -> Result<()> {
+    let rules_dir = gitai::utils::paths::resolve_rules_dir(config.scan.rules_dir.as_deref());
+    let Some(mut rule) = gitai::rules::find_rule(&rules_dir, rule_id) else {
+        return Err(GitAIError::ScanTool(gitai::error::ScanError::RuleNotFound(
+            gitai::tr!(
+                "explain.not_found",
+                rule = rule_id,
+                dir = rules_dir.display()
+            ),
+        )));
+    };
+    if translate && rule.localized_message.is_none() {
+        translate_rule(config, &mut rule).await;
+    }
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&rule)?);
+    } else {
+        print!("{}", rule.to_text());
+    }
+    Ok(())
+}
+
+/// 使用 AI 将规则说明翻译为当前输出语言；失败时仅提示，不影响展示原文
+#[cfg(feature = "ai")]
+async fn translate_rule(config: &config::Config, rule: &mut gitai::rules::RuleInfo) {
+    let language = match gitai::i18n::current() {
+        gitai::i18n::Lang::Zh => "简体中文",
+        gitai::i18n::Lang::En => "English",
+    };
+    let prompt = format!(
+        "Translate the following static analysis rule description into {language}. \
+         Reply with the translation only.\n\n{}",
+        rule.message
+    );
+    match ai::call_ai(config, &prompt).await {
+        Ok(text) => rule.localized_message = Some(text.trim().to_string()),
+        Err(e) => eprintln!("{}", gitai::tr!("explain.translate_failed", error = e)),
+    }
+}
+
+#[cfg(not(feature = "ai"))]
+async fn translate_rule(_config: &config::Config, _rule: &mut gitai::rules::RuleInfo) {}
+
+fn handle_hooks(action: &HooksAction) -> Result<()> {
+    use gitai::hooks::{self, HookKind, HookOutcome};
+    match action {
+        HooksAction::Install {
+            pre_commit,
+            commit_msg,
+            force,
+        } => {
+            let dir = hooks::hooks_dir()?;
+            for kind in HookKind::selected(*pre_commit, *commit_msg) {
+                match hooks::install_hook(&dir, kind, *force)? {
+                    HookOutcome::Installed {
+                        backup: Some(backup),
+                    } => println!(
+                        "✅ 已安装 {} 钩子（原钩子已备份到 {}）",
+                        kind.file_name(),
+                        backup.display()
+                    ),
+                    _ => println!("✅ 已安装 {} 钩子", kind.file_name()),
+                }
+            }
+        }
+        HooksAction::Uninstall {
+            pre_commit,
+            commit_msg,
+        } => {
+            let dir = hooks::hooks_dir()?;
+            for kind in HookKind::selected(*pre_commit, *commit_msg) {
+                match hooks::uninstall_hook(&dir, kind)? {
+                    HookOutcome::Removed { restored: true } => {
+                        println!("🗑️  已移除 {} 钩子并恢复原钩子", kind.file_name())
+                    }
+                    HookOutcome::Removed { restored: false } => {
+                        println!("🗑️  已移除 {} 钩子", kind.file_name())
+                    }
+                    HookOutcome::Skipped(reason) => {
+                        println!("ℹ️  跳过 {} 钩子：{reason}", kind.file_name())
+                    }
+                    HookOutcome::Installed { .. } => {}
+                }
+            }
+        }
+        HooksAction::CheckMessage { file } => {
+            let message = std::fs::read_to_string(file)?;
+            let problems = hooks::check_commit_message(&message);
+            if !problems.is_empty() {
+                for problem in &problems {
+                    eprintln!("{}", output::plain(&format!("❌ {problem}")));
+                }
+                return Err(GitAIError::GateFailed(format!(
+                    "提交信息未通过校验：{}",
+                    problems.join("；")
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_eval(config: &config::Config, action: &EvalAction) -> Result<()> {
+    match action {
+        EvalAction::ErrorPatterns {
+            path,
+            min_adoption,
+            changed_only,
+            format,
+        } => {
+            let report = if *changed_only {
+                let changed = changed_files(path)?;
+                gitai::evaluation::analyze_error_patterns_changed(path, &changed)
+            } else {
+                gitai::evaluation::analyze_error_patterns(path)
+            };
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report.to_text());
+            }
+            let violations = report.violations(*min_adoption);
+            if !violations.is_empty() {
+                return Err(GitAIError::GateFailed(violations.join("；")));
+            }
+        }
+        EvalAction::Duplicates {
+            path,
+            max_duplicates,
+            min_similarity,
+            ignore,
+            memory_budget_mb,
+            changed_only,
+            format,
+        } => {
+            let options = gitai::evaluation::DuplicateOptions {
+                min_similarity: *min_similarity,
+                memory_budget: memory_budget_mb.saturating_mul(1024 * 1024),
+                ..Default::default()
+            };
+            let report = if *changed_only {
+                let changed = changed_files(path)?;
+                gitai::evaluation::analyze_duplicates_changed(path, ignore, &options, &changed)
+            } else {
+                gitai::evaluation::analyze_duplicates_with(path, ignore, &options)
+            };
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report.to_text());
+            }
+            let violations = report.violations(*max_duplicates);
+            if !violations.is_empty() {
+                return Err(GitAIError::GateFailed(violations.join("；")));
+            }
+        }
+        EvalAction::ConfigCheck {
+            path,
+            max_files,
+            allow,
+            format,
+        } => {
+            let settings = &config.analysis.evaluation;
+            let mut allowlist = settings.config_allowlist.clone();
+            allowlist.extend(allow.iter().cloned());
+            let max_files = max_files.unwrap_or(settings.max_config_files);
+            let report = gitai::evaluation::check_config_rs(path, &allowlist, max_files);
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report.to_text());
+            }
+            let violations = report.violations();
+            if !violations.is_empty() {
+                return Err(GitAIError::GateFailed(violations.join("；")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 评估路径下相对 HEAD 的变更文件
+fn changed_files(path: &std::path::Path) -> Result<Vec<String>> {
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+    // git 输出相对 dir 的路径，统一交给 normalize_changed 过滤已删除文件并转换为相对 path 的形式
+    let changed: Vec<PathBuf> = git::get_changed_files_in(dir)?
+        .into_iter()
+        .map(|file| dir.join(file))
+        .collect();
+    Ok(gitai::evaluation::normalize_changed(path, &changed))
+}
+
 // 扫描相关处理函数
 #[cfg(feature = "security")]
 async fn handle_scan(
     config: &config::Config,
-    path: &std::path::Path,
+    paths: &[PathBuf],
+    per_path: bool,
     tool: &str,
     _full: bool,
     _remote: bool,
     update_rules: bool,
     _format: &str,
-    output: Option<PathBuf>,
+    output: &[PathBuf],
     _translate: bool,
     _auto_install: bool,
     lang: Option<&str>,
     no_history: bool,
     timeout: Option<u64>,
     benchmark: bool,
+    fail_on: Option<&str>,
+    explain: Option<&str>,
+    baseline: Option<&std::path::Path>,
+    update_baseline: bool,
+    offline: bool,
 ) -> Result<()> {
-    let show_progress = _format != "json";
-
-    if show_progress {
-        println!("🔍 正在扫描: {}", path.display());
+    // JSON 输出时进度信息会混入结果，等同于 --quiet
+    if _format == "json" {
+        output::set_quiet(true);
     }
+    // 扫描前校验输出目标，避免扫描完成后才发现格式不受支持
+    let targets = gitai::utils::report_output::resolve_targets(
+        output,
+        _format,
+        &["json", "markdown", "html", "text"],
+    )?;
+
+    let scan_target = paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    gitai::progress!("{}", gitai::tr!("scan.scanning", path = scan_target));
 
     // 确保扫描工具已安装
     // 将 'security' 映射为 'opengrep' 以保持向后兼容性
@@ -429,93 +1290,332 @@ async fn handle_scan(
         && !scan::is_opengrep_installed()
     {
         if _auto_install {
-            if show_progress {
-                println!("🔧 未检测到 OpenGrep，正在自动安装...");
-            }
+            gitai::progress!("{}", gitai::tr!("scan.auto_install"));
             if let Err(e) = scan::install_opengrep() {
-                return Err(format!("OpenGrep 安装失败: {}", e).into());
+                return Err(GitAIError::ScanTool(ScanError::ToolNotFound(format!(
+                    "OpenGrep 安装失败: {}",
+                    e
+                ))));
             }
         } else {
-            return Err("未检测到 OpenGrep，请先安装或使用 --auto-install 进行自动安装".into());
+            return Err(GitAIError::ScanTool(ScanError::ToolNotFound(
+                "未检测到 OpenGrep，请先安装或使用 --auto-install 进行自动安装".to_string(),
+            )));
         }
     }
 
     // 更新规则（如果需要）
     if update_rules {
-        if show_progress {
-            println!("🔄 正在更新扫描规则...");
-        }
+        gitai::progress!("{}", gitai::tr!("scan.updating_rules"));
         #[cfg(feature = "update-notifier")]
         {
             let updater = update::AutoUpdater::new(config.clone());
             if let Err(e) = updater.update_scan_rules().await {
-                eprintln!("⚠️ 规则更新失败: {}", e);
+                eprintln!("{}", gitai::tr!("scan.rules_update_failed", error = e));
             }
         }
         #[cfg(not(feature = "update-notifier"))]
         {
-            eprintln!("ℹ️  update-notifier 功能未启用，跳过规则更新。");
+            eprintln!("{}", gitai::tr!("scan.rules_update_disabled"));
+        }
+        // 多来源组合时一并刷新 Git 规则来源
+        if !config.scan.sources.is_empty() {
+            match gitai::rule_sources::RuleManager::new(config).refresh() {
+                Ok(updated) => {
+                    for source in updated {
+                        gitai::progress!(
+                            "{}",
+                            gitai::tr!(
+                                "scan.rule_source_updated",
+                                name = source.name,
+                                version = source.version.unwrap_or_default()
+                            )
+                        );
+                    }
+                }
+                Err(e) => eprintln!("{}", gitai::tr!("scan.rules_update_failed", error = e)),
+            }
         }
     }
 
+    if let Some(custom) = config.scan.custom_rules_dir.as_deref() {
+        gitai::progress!(
+            "{}",
+            gitai::tr!(
+                if config.scan.rules_only {
+                    "scan.custom_rules_only"
+                } else {
+                    "scan.custom_rules"
+                },
+                path = custom
+            )
+        );
+    }
+
     // 执行扫描
-    let result = if normalized_tool == "opengrep" || normalized_tool == "auto" {
-        let include_version = show_progress && !benchmark;
-        scan::run_opengrep_scan(config, path, lang, timeout, include_version)?
-    } else {
+    if normalized_tool != "auto" && normalized_tool != "opengrep" {
         return Err(format!(
             "不支持的扫描工具: {} (支持的工具: opengrep, security, auto)",
             tool
         )
         .into());
+    }
+    let include_version = !output::quiet() && !benchmark;
+    let options = scan::ScanOptions {
+        lang,
+        timeout,
+        include_version,
+    };
+    let scanners = scan::builtin_scanners();
+    let scan_one = |path: &std::path::Path, cancel: &gitai::utils::cancel::CancellationToken| {
+        if normalized_tool == "auto" {
+            // 运行所有可用的扫描器，多个可用时按指纹合并并比较结果
+            scan::run_auto_scan_cancellable(config, path, &options, &scanners, cancel)
+        } else {
+            scan::run_opengrep_scan_cancellable(
+                config,
+                path,
+                lang,
+                timeout,
+                include_version,
+                cancel,
+            )
+        }
     };
+    // 多个路径并发扫描后合并，同时保留每个路径的结果供 --per-path 使用
+    let (result, path_results) = match paths {
+        [path] => (
+            scan_one(path, &gitai::utils::cancel::CancellationToken::new())?,
+            Vec::new(),
+        ),
+        _ => {
+            let path_results = scan::scan_paths(paths, scan_one)?;
+            (scan::merge_path_results(&path_results), path_results)
+        }
+    };
+    let per_path = per_path && !path_results.is_empty();
+    let mut result = result;
+
+    // 基线：先按需用本次结果更新基线文件，再抑制其中未过期的问题
+    if let Some(baseline_path) = baseline {
+        let now = chrono::Utc::now();
+        let mut entries = gitai::scan_baseline::Baseline::load(baseline_path)?;
+        if update_baseline {
+            entries.update(&result.findings, now);
+            entries.save(baseline_path)?;
+            gitai::progress!(
+                "{}",
+                gitai::tr!(
+                    "scan.baseline_updated",
+                    path = baseline_path.display(),
+                    count = entries.entries.len()
+                )
+            );
+        }
+        entries.apply_to(
+            &mut result,
+            baseline_path,
+            config.scan.baseline_max_age_days,
+            now,
+        );
+    }
 
     // 保存扫描历史（无论输出格式）
     if !(no_history || benchmark) {
-        let cache_dir = get_cache_dir()?;
-        let history_dir = cache_dir.join("scan_history");
+        let history_dir = gitai::utils::paths::scan_history_dir();
         if let Err(e) = fs::create_dir_all(&history_dir) {
-            eprintln!("⚠️ 无法创建扫描历史目录: {}", e);
+            eprintln!("{}", gitai::tr!("scan.history_dir_failed", error = e));
         }
         let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
         let history_file = history_dir.join(format!("scan_{}_{}.json", result.tool, ts));
         if let Ok(json) = serde_json::to_string(&result) {
             if let Err(e) = fs::write(&history_file, json) {
-                eprintln!("⚠️ 写入扫描历史失败: {}", e);
+                eprintln!("{}", gitai::tr!("scan.history_write_failed", error = e));
             }
         }
     }
 
-    // 输出结果
-    if _format == "json" {
-        let json = serde_json::to_string_pretty(&result)?;
-        if let Some(output_path) = output {
-            fs::write(output_path, json)?;
+    // 只解释单个问题时不输出完整结果，也不参与 --fail-on 判定
+    if let Some(selector) = explain {
+        let finding =
+            gitai::scan_explain::select_finding(&result.findings, selector).ok_or_else(|| {
+                GitAIError::ScanTool(ScanError::FindingNotFound(gitai::tr!(
+                    "scan.finding_not_found",
+                    selector = selector,
+                    count = result.findings.len()
+                )))
+            })?;
+        // 问题路径相对于当前目录，多路径时以第一个路径作为读取上下文的备选根目录
+        let explanation =
+            gitai::scan_explain::explain_finding(config, finding, &paths[0], !offline).await;
+        if _format == "json" {
+            println!("{}", serde_json::to_string_pretty(&explanation)?);
+        } else {
+            print!("{}", explanation.to_text());
+        }
+        return Ok(());
+    }
+
+    // 输出结果：每种格式渲染一次后写入全部输出文件
+    // --per-path 的 JSON 为每个路径一项的数组，其他格式输出合并结果（附带各路径概况）
+    let render = |format: &str| -> Result<String> {
+        if per_path && format == "json" {
+            Ok(serde_json::to_string_pretty(&path_results)?)
         } else {
-            println!("{}", json);
+            Ok(result.render(format)?)
+        }
+    };
+    gitai::utils::report_output::write_targets(&targets, render)?;
+    for target in &targets {
+        gitai::progress!("{}", gitai::tr!("scan.saved", path = target.path.display()));
+    }
+    if _format == "json" {
+        if targets.is_empty() {
+            println!("{}", render("json")?);
+        }
+    } else if output::quiet() {
+        // 静默模式只列出问题（每行一个），无问题时不输出
+        for finding in &result.findings {
+            println!(
+                "{}:{}: {}",
+                finding.file_path.display(),
+                finding.line,
+                finding.title
+            );
         }
     } else {
-        if show_progress {
-            println!("📊 扫描结果:");
-            println!("  工具: {}", result.tool);
-            println!("  版本: {}", result.version);
-            println!("  执行时间: {:.2}s", result.execution_time);
-
-            if !result.findings.is_empty() {
-                println!("  发现问题: {}", result.findings.len());
-                for finding in result.findings.iter().take(5) {
-                    println!(
-                        "    - {} ({}:{})",
-                        finding.title,
-                        finding.file_path.display(),
-                        finding.line
-                    );
-                }
-                if result.findings.len() > 5 {
-                    println!("    ... 还有 {} 个问题", result.findings.len() - 5);
+        println!("{}", gitai::tr!("scan.result_header"));
+        println!("{}", gitai::tr!("scan.tool", tool = result.tool));
+        println!("{}", gitai::tr!("scan.version", version = result.version));
+        if let Some(agreement) = result.scanner_agreement() {
+            println!(
+                "{}",
+                gitai::tr!(
+                    "scan.agreement",
+                    scanners = agreement.scanners.join(", "),
+                    agreed = agreement.agreed
+                )
+            );
+            for (scanner, count) in &agreement.exclusive {
+                println!(
+                    "{}",
+                    gitai::tr!("scan.agreement_exclusive", scanner = scanner, count = count)
+                );
+            }
+        }
+        if let Some(info) = &result.rules_info {
+            for layer in &info.layers {
+                println!(
+                    "{}",
+                    gitai::tr!(
+                        "scan.rule_source",
+                        name = layer.name,
+                        kind = layer.kind,
+                        version = layer.version.as_deref().unwrap_or("-"),
+                        rules = layer.rules,
+                        overrides = layer.overrides
+                    )
+                );
+            }
+        }
+        println!(
+            "{}",
+            gitai::tr!(
+                "scan.duration",
+                seconds = format!("{:.2}", result.execution_time)
+            )
+        );
+        if let Some(summary) = &result.baseline {
+            println!(
+                "{}",
+                gitai::tr!(
+                    "scan.baseline_applied",
+                    suppressed = summary.suppressed,
+                    overdue = summary.overdue.len()
+                )
+            );
+        }
+        if per_path {
+            for summary in &result.paths {
+                println!(
+                    "{}",
+                    gitai::tr!(
+                        "scan.path_summary",
+                        path = summary.path.display(),
+                        count = summary.findings,
+                        seconds = format!("{:.2}", summary.execution_time)
+                    )
+                );
+                if let Some(error) = &summary.error {
+                    println!("{}", gitai::tr!("scan.path_error", error = error));
                 }
-            } else {
-                println!("  ✅ 未发现问题");
+            }
+        }
+
+        if !result.findings.is_empty() {
+            println!(
+                "{}",
+                gitai::tr!("scan.findings", count = result.findings.len())
+            );
+            let score = result.risk_score();
+            println!(
+                "{}",
+                gitai::tr!(
+                    "scan.risk_score",
+                    score = format!("{score:.0}"),
+                    level = format!("{:?}", gitai::risk_report::level_for_score(score))
+                )
+            );
+            for (i, finding) in result.findings.iter().take(5).enumerate() {
+                println!(
+                    "{}",
+                    gitai::tr!(
+                        "scan.finding_item",
+                        index = i + 1,
+                        fingerprint = finding.fingerprint,
+                        title = if result
+                            .baseline
+                            .as_ref()
+                            .is_some_and(|b| b.is_overdue(finding))
+                        {
+                            format!("{} {}", gitai::tr!("scan.overdue_tag"), finding.title)
+                        } else {
+                            finding.title.clone()
+                        },
+                        location = format!("{}:{}", finding.file_path.display(), finding.line)
+                    )
+                );
+            }
+            if result.findings.len() > 5 {
+                println!(
+                    "{}",
+                    gitai::tr!("scan.more_findings", count = result.findings.len() - 5)
+                );
+            }
+            println!("{}", gitai::tr!("scan.explain_hint"));
+        } else {
+            println!("{}", gitai::tr!("scan.clean"));
+        }
+        if !result.skipped_generated.is_empty() {
+            println!(
+                "{}",
+                gitai::tr!(
+                    "analysis.skipped_generated",
+                    count = result.skipped_generated.len()
+                )
+            );
+        }
+    }
+
+    // 达到 --fail-on 阈值时以专用退出码失败，便于 CI 区分“发现问题”与“工具出错”
+    if let Some(threshold) = fail_on {
+        if let Some(level) = scan::Severity::parse(threshold) {
+            let count = result.count_at_or_above(&level);
+            if count > 0 {
+                return Err(GitAIError::FindingsDetected {
+                    count,
+                    threshold: threshold.to_string(),
+                });
             }
         }
     }
@@ -525,8 +1625,7 @@ async fn handle_scan(
 
 #[cfg(feature = "security")]
 fn handle_scan_history(limit: usize) -> Result<()> {
-    let cache_dir = get_cache_dir()?;
-    let history_dir = cache_dir.join("scan_history");
+    let history_dir = gitai::utils::paths::scan_history_dir();
 
     if !history_dir.exists() {
         println!("📁 扫描历史目录不存在");
@@ -569,6 +1668,14 @@ fn handle_scan_history(limit: usize) -> Result<()> {
                     .unwrap_or_else(|| "未知时间".to_string());
 
                 println!("{}. {} - {}", i + 1, modified, result.tool);
+                if !result.paths.is_empty() {
+                    let paths: Vec<String> = result
+                        .paths
+                        .iter()
+                        .map(|p| p.path.display().to_string())
+                        .collect();
+                    println!("   扫描路径: {}", paths.join(", "));
+                }
                 println!("   执行时间: {:.2}s", result.execution_time);
                 println!("   发现问题: {}", result.findings.len());
                 if !result.findings.is_empty() {
@@ -614,7 +1721,7 @@ async fn handle_update_check(config: &config::Config, format: &str) -> Result<()
 
 #[cfg(feature = "update-notifier")]
 async fn handle_update(config: &config::Config) -> Result<()> {
-    println!("🔄 正在更新规则...");
+    gitai::progress!("🔄 正在更新规则...");
     let updater = update::AutoUpdater::new(config.clone());
     let result = updater.update_scan_rules().await?;
 
@@ -651,10 +1758,14 @@ async fn handle_git_with_ai(config: &config::Config, git_args: &[String]) -> Res
     Ok(())
 }
 
-async fn handle_prompts_action(_config: &config::Config, action: &PromptAction) -> Result<()> {
+async fn handle_prompts_action(
+    config: &config::Config,
+    action: &PromptAction,
+    offline: bool,
+) -> Result<()> {
     match action {
         PromptAction::Init => {
-            println!("🔄 正在初始化提示词目录...");
+            gitai::progress!("🔄 正在初始化提示词目录...");
             let prompts_dir = dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
                 .join(".config")
@@ -664,13 +1775,8 @@ async fn handle_prompts_action(_config: &config::Config, action: &PromptAction)
             fs::create_dir_all(&prompts_dir)?;
 
             // 创建默认模板
-            let templates = [
-                ("commit.md", include_str!("../assets/prompts/commit.md")),
-                ("review.md", include_str!("../assets/prompts/review.md")),
-            ];
-
-            for (filename, content) in &templates {
-                let file_path = prompts_dir.join(filename);
+            for (name, content) in gitai::prompts::BUILTIN_TEMPLATES {
+                let file_path = prompts_dir.join(format!("{name}.md"));
                 if !file_path.exists() {
                     fs::write(&file_path, content)?;
                 }
@@ -717,8 +1823,90 @@ async fn handle_prompts_action(_config: &config::Config, action: &PromptAction)
                 println!("❌ 未找到提示词模板: {name}");
             }
         }
-        PromptAction::Update => {
-            println!("🔄 更新提示词模板功能暂未实现");
+        PromptAction::Update { force } => {
+            use gitai::prompts::TemplateUpdate;
+            use gitai::resource_manager::load_resource_config;
+
+            gitai::progress!("🔄 正在更新提示词模板...");
+            let prompts_dir = gitai::prompts::PromptManager::new(config.clone()).get_prompts_dir();
+            let config_path = dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".config/gitai/config.toml");
+            let remote_url = load_resource_config(&config_path)
+                .ok()
+                .and_then(|resource_config| resource_config.sources.prompts_url);
+            if offline && remote_url.is_some() {
+                println!("📴 离线模式：跳过远程模板，使用内置版本");
+            }
+            let remote_url = remote_url.filter(|_| !offline);
+
+            let latest = gitai::prompts::fetch_latest_templates(remote_url.as_deref()).await;
+            let sources: std::collections::HashMap<_, _> = latest
+                .iter()
+                .map(|(name, _, source)| (name.clone(), source.clone()))
+                .collect();
+            let templates: Vec<(String, String)> = latest
+                .into_iter()
+                .map(|(name, content, _)| (name, content))
+                .collect();
+            let results = gitai::prompts::merge_templates(&prompts_dir, &templates, *force)?;
+
+            println!("📝 提示词模板更新结果（{}）:", prompts_dir.display());
+            let mut conflicts = 0;
+            for (name, status) in &results {
+                let source = &sources[name];
+                match status {
+                    TemplateUpdate::Added => println!("  ➕ {name}: 已新增（{source}）"),
+                    TemplateUpdate::Updated => println!("  ✅ {name}: 已更新（{source}）"),
+                    TemplateUpdate::UpToDate => println!("  ✔️  {name}: 已是最新"),
+                    TemplateUpdate::Overwritten(backup) => println!(
+                        "  ♻️  {name}: 已覆盖（{source}），原文件备份到 {}",
+                        backup.display()
+                    ),
+                    TemplateUpdate::Conflict(pending) => {
+                        conflicts += 1;
+                        println!(
+                            "  ⏭️  {name}: 已跳过（本地有修改），最新版本写入 {}",
+                            pending.display()
+                        );
+                    }
+                }
+            }
+            if conflicts > 0 {
+                println!(
+                    "💡 手动合并 .md.new 文件，或使用 `gitai prompts update --force` 覆盖本地修改"
+                );
+            }
+        }
+        PromptAction::Validate => {
+            let manager = gitai::prompts::PromptManager::new(config.clone());
+            let reports = manager.validate_all();
+            println!("🔍 校验提示词模板:");
+            let mut failed = 0;
+            for report in &reports {
+                let location = report
+                    .path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                match &report.result {
+                    Ok(()) if report.known => println!("  ✅ {} ({location})", report.name),
+                    Ok(()) => println!(
+                        "  ➖ {} ({location}): 未登记的模板，跳过占位符检查",
+                        report.name
+                    ),
+                    Err(e) => {
+                        failed += 1;
+                        println!("  ❌ {} ({location}): {e}", report.name);
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(GitAIError::Config(ConfigError::ValidationFailed(format!(
+                    "{failed} 个提示词模板校验失败"
+                ))));
+            }
+            println!("✅ 所有提示词模板校验通过");
         }
     }
 
@@ -756,7 +1944,7 @@ async fn handle_init(
             // 如果需要下载资源
             if download_resources && !offline {
                 println!();
-                println!("📦 正在下载资源...");
+                gitai::progress!("📦 正在下载资源...");
 
                 // 下载 Tree-sitter queries
                 println!("🌳 下载 Tree-sitter queries...");
@@ -798,12 +1986,13 @@ async fn handle_config(
     config: &config::Config,
     action: &ConfigAction,
     offline: bool,
+    flags: Vec<gitai::config_layers::Layer>,
 ) -> Result<()> {
     use gitai::resource_manager::{load_resource_config, ResourceManager};
 
     match action {
         ConfigAction::Check => {
-            println!("🔍 检查配置状态...");
+            gitai::progress!("{}", gitai::tr!("config.checking"));
 
             // 检查配置文件
             let config_dir = dirs::home_dir()
@@ -812,36 +2001,40 @@ async fn handle_config(
             let config_path = config_dir.join("config.toml");
 
             if config_path.exists() {
-                println!("✅ 配置文件: {}", config_path.display());
+                println!(
+                    "{}",
+                    gitai::tr!("config.file_found", path = config_path.display())
+                );
             } else {
-                println!("❌ 配置文件不存在");
+                println!("{}", gitai::tr!("config.file_missing"));
             }
 
             // 检查缓存目录
-            let cache_dir = dirs::home_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join(".cache/gitai");
+            let cache_dir = gitai::utils::paths::cache_dir();
 
             if cache_dir.exists() {
-                println!("✅ 缓存目录: {}", cache_dir.display());
+                println!(
+                    "{}",
+                    gitai::tr!("config.cache_dir", path = cache_dir.display())
+                );
 
                 // 检查规则
                 let rules_dir = cache_dir.join("rules");
                 if rules_dir.exists() {
-                    println!("  ✅ 规则缓存: 已就绪");
+                    println!("{}", gitai::tr!("config.rules_ready"));
                 } else {
-                    println!("  ⚠️  规则缓存: 未找到");
+                    println!("{}", gitai::tr!("config.rules_missing"));
                 }
 
                 // 检查 Tree-sitter
                 let ts_dir = cache_dir.join("tree-sitter");
                 if ts_dir.exists() {
-                    println!("  ✅ Tree-sitter缓存: 已就绪");
+                    println!("{}", gitai::tr!("config.tree_sitter_ready"));
                 } else {
-                    println!("  ⚠️  Tree-sitter缓存: 未找到");
+                    println!("{}", gitai::tr!("config.tree_sitter_missing"));
                 }
             } else {
-                println!("❌ 缓存目录不存在");
+                println!("{}", gitai::tr!("config.cache_missing"));
             }
         }
         ConfigAction::Show { format } => {
@@ -863,22 +2056,30 @@ async fn handle_config(
                 }
                 "toml" => {
                     // Config 类型可能没有实现 Serialize，暂时显示简单信息
-                    println!("📋 TOML 格式输出暂不可用");
+                    println!("{}", gitai::tr!("config.toml_unavailable"));
                 }
                 _ => {
-                    println!("📋 当前配置:");
-                    println!("  AI服务: {}", config.ai.api_url);
-                    println!("  AI模型: {}", config.ai.model);
+                    println!("{}", gitai::tr!("config.current"));
+                    println!(
+                        "{}",
+                        gitai::tr!("config.ai_service", url = config.ai.api_url)
+                    );
+                    println!("{}", gitai::tr!("config.ai_model", model = config.ai.model));
                     // config.scan 是 ScanConfig 类型，不是 Option
                     println!(
-                        "  扫描路径: {}",
-                        config.scan.default_path.as_deref().unwrap_or(".")
+                        "{}",
+                        gitai::tr!(
+                            "config.scan_path",
+                            path = config.scan.default_path.as_deref().unwrap_or(".")
+                        )
                     );
                 }
             }
         }
-        ConfigAction::Update { force } => {
-            println!("🔄 更新资源...");
+        ConfigAction::Update { force, dry_run } => {
+            if !dry_run {
+                gitai::progress!("{}", gitai::tr!("config.updating"));
+            }
 
             let config_path = dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -888,22 +2089,27 @@ async fn handle_config(
                 let manager = ResourceManager::new(resource_config)?;
 
                 if offline {
-                    eprintln!("⚠️  离线模式下无法更新资源");
+                    eprintln!("{}", gitai::tr!("config.offline_update"));
+                    return Ok(());
+                }
+
+                if *dry_run {
+                    print_resource_plan(&manager.plan_update().await?);
                     return Ok(());
                 }
 
                 if *force {
-                    println!("🚀 强制更新所有资源...");
+                    println!("{}", gitai::tr!("config.force_update"));
                 }
 
                 manager.update_all().await?;
-                println!("✅ 资源更新完成");
+                println!("{}", gitai::tr!("config.updated"));
             } else {
-                eprintln!("❌ 无法加载资源配置");
+                eprintln!("{}", gitai::tr!("config.resource_config_failed"));
             }
         }
         ConfigAction::Reset { no_backup } => {
-            println!("🔄 重置配置...");
+            gitai::progress!("{}", gitai::tr!("config.resetting"));
 
             let config_path = dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -912,16 +2118,23 @@ async fn handle_config(
             if !no_backup && config_path.exists() {
                 let backup_path = config_path.with_extension("toml.backup");
                 fs::copy(&config_path, &backup_path)?;
-                println!("💾 已备份到: {}", backup_path.display());
+                println!(
+                    "{}",
+                    gitai::tr!("config.backup", path = backup_path.display())
+                );
             }
 
             // 写入默认配置
             let default_config = include_str!("../assets/config.enhanced.toml");
             fs::write(&config_path, default_config)?;
-            println!("✅ 配置已重置到默认值");
+            println!("{}", gitai::tr!("config.reset_done"));
         }
-        ConfigAction::Clean => {
-            println!("🧹 清理缓存...");
+        ConfigAction::Clean { dry_run, only } => {
+            use gitai::resource_manager::{format_size, CacheCategory};
+
+            if !dry_run {
+                println!("{}", gitai::tr!("config.cleaning"));
+            }
 
             let config_path = dirs::home_dir()
                 .unwrap_or_else(|| PathBuf::from("."))
@@ -929,10 +2142,91 @@ async fn handle_config(
 
             if let Ok(resource_config) = load_resource_config(&config_path) {
                 let manager = ResourceManager::new(resource_config)?;
-                manager.clean_cache().await?;
-                println!("✅ 缓存清理完成");
+                // --only：清空指定类别（不论是否过期），按类别报告释放的空间
+                let mut categories: Vec<CacheCategory> = only
+                    .iter()
+                    .filter_map(|c| CacheCategory::parse(c))
+                    .collect();
+                categories.sort();
+                categories.dedup();
+
+                if *dry_run {
+                    let plan = if categories.is_empty() {
+                        manager.plan_clean().await?
+                    } else {
+                        manager.plan_clean_categories(&categories)
+                    };
+                    print_resource_plan(&plan);
+                    return Ok(());
+                }
+                if categories.is_empty() {
+                    manager.clean_cache().await?;
+                } else {
+                    for (category, freed) in manager.clean_categories(&categories)? {
+                        println!(
+                            "{}",
+                            gitai::tr!(
+                                "config.cleaned_category",
+                                category = category,
+                                size = format_size(freed)
+                            )
+                        );
+                    }
+                }
+                println!("{}", gitai::tr!("config.cleaned"));
+            } else {
+                eprintln!("{}", gitai::tr!("config.resource_config_failed"));
+            }
+        }
+        ConfigAction::Get { key } => {
+            // 与各命令相同，按默认值、用户配置文件、环境变量与命令行参数逐层合并
+            let layered = config::Config::load_layered(flags)
+                .map_err(|e| GitAIError::Config(ConfigError::LoadFailed(e.to_string())))?;
+            let value = gitai::config_edit::get(&layered.config, key)
+                .map_err(|e| GitAIError::Config(ConfigError::ValidationFailed(e.to_string())))?;
+            match value {
+                serde_json::Value::Null => {
+                    eprintln!("{}", gitai::tr!("config.get_unset", key = key))
+                }
+                serde_json::Value::String(value) => println!("{value}"),
+                value => println!("{value}"),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let config_path = config::Config::path();
+            if !config_path.exists() {
+                eprintln!("{}", gitai::tr!("config.load_hint_init"));
+                return Err(GitAIError::Config(ConfigError::FileNotFound(
+                    config_path.display().to_string(),
+                )));
+            }
+            let content = fs::read_to_string(&config_path)?;
+            let updated = gitai::config_edit::set(&content, key, value)
+                .map_err(|e| GitAIError::Config(ConfigError::ValidationFailed(e.to_string())))?;
+            // 写入中断时保留原配置文件
+            gitai::utils::atomic_write::write_atomic(&config_path, updated)?;
+            println!(
+                "{}",
+                gitai::tr!("config.set_done", key = key, value = value)
+            );
+        }
+        ConfigAction::Explain { key, format } => {
+            let layered = config::Config::load_layered(flags)
+                .map_err(|e| GitAIError::Config(ConfigError::LoadFailed(e.to_string())))?;
+            let entries = layered
+                .explain(key.as_deref())
+                .map_err(|e| GitAIError::Config(ConfigError::ValidationFailed(e.to_string())))?;
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
             } else {
-                eprintln!("❌ 无法加载资源配置");
+                let width = entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+                for entry in &entries {
+                    let source = match &entry.origin {
+                        Some(origin) => format!("{}: {origin}", entry.source),
+                        None => entry.source.to_string(),
+                    };
+                    println!("{:<width$} = {}  [{source}]", entry.key, entry.value);
+                }
             }
         }
     }
@@ -940,12 +2234,111 @@ async fn handle_config(
     Ok(())
 }
 
+/// 处理缓存管理命令
+fn handle_cache(action: &CacheAction) -> Result<()> {
+    use gitai::resource_manager::{cache_usage, format_size, load_resource_config};
+
+    match action {
+        CacheAction::Du { format } => {
+            // 与 config clean 使用同一缓存目录：优先取资源配置，缺失时回退到默认位置
+            let config_path = config::Config::path();
+            let cache_dir = match load_resource_config(&config_path) {
+                Ok(resource_config) => {
+                    gitai::utils::paths::resolve_config_path(&resource_config.cache.path)
+                }
+                Err(_) => gitai::utils::paths::cache_dir(),
+            };
+            let report = cache_usage(&cache_dir);
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            println!(
+                "{}",
+                gitai::tr!("cache.du_header", path = cache_dir.display())
+            );
+            if !report.exists {
+                println!("{}", gitai::tr!("cache.du_missing"));
+                return Ok(());
+            }
+            // 与 du 一致：大小列在前，标签宽度不影响对齐
+            let width = report
+                .groups
+                .iter()
+                .map(|g| g.name.len())
+                .max()
+                .unwrap_or(0);
+            for group in &report.groups {
+                println!(
+                    "  {:>10}  {:<width$}  {}",
+                    format_size(group.bytes),
+                    group.name,
+                    gitai::tr!("cache.du_files", count = group.files)
+                );
+            }
+            println!(
+                "  {:>10}  {}",
+                format_size(report.other_bytes),
+                gitai::tr!("cache.du_other")
+            );
+            println!(
+                "  {:>10}  {}",
+                format_size(report.total_bytes),
+                gitai::tr!("cache.du_total")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// 打印 `config update|clean --dry-run` 的计划：每项一行，附来源与已知大小
+fn print_resource_plan(plan: &[gitai::resource_manager::PlannedAction]) {
+    use gitai::resource_manager::{format_size, PlannedChange};
+
+    println!("{}", gitai::tr!("config.dry_run_header"));
+    if plan.is_empty() {
+        println!("{}", gitai::tr!("config.dry_run_nothing"));
+        return;
+    }
+    for action in plan {
+        let change = match action.change {
+            PlannedChange::Download => gitai::tr!("config.plan_download"),
+            PlannedChange::Update => gitai::tr!("config.plan_update"),
+            PlannedChange::Delete => gitai::tr!("config.plan_delete"),
+        };
+        let mut line = format!("  {change}  {}", action.path.display());
+        if let Some(category) = action.category {
+            line.push_str(&format!("  [{category}]"));
+        }
+        if let Some(source) = &action.source {
+            line.push_str(&format!("  ← {source}"));
+        }
+        let size = action
+            .size
+            .map(format_size)
+            .unwrap_or_else(|| gitai::tr!("config.plan_size_unknown").to_string());
+        println!("{line}  ({size})");
+    }
+    let known: u64 = plan.iter().filter_map(|a| a.size).sum();
+    println!(
+        "{}",
+        gitai::tr!(
+            "config.dry_run_total",
+            count = plan.len(),
+            size = format_size(known)
+        )
+    );
+}
+
 #[cfg(feature = "mcp")]
 async fn handle_mcp(config: &config::Config, transport: &str, addr: &str) -> Result<()> {
     // 检查 MCP 是否启用
     if !config.mcp.as_ref().map_or(false, |mcp| mcp.enabled) {
-        eprintln!("❌ MCP 服务未启用，请在配置文件中启用 MCP");
-        std::process::exit(1);
+        return Err(GitAIError::Config(ConfigError::ValidationFailed(
+            "MCP 服务未启用，请在配置文件中启用 MCP".to_string(),
+        )));
     }
 
     println!("🚀 启动 GitAI MCP 服务器");
@@ -954,7 +2347,9 @@ async fn handle_mcp(config: &config::Config, transport: &str, addr: &str) -> Res
     match transport {
         "stdio" => {
             println!("🔌 使用 stdio 传输");
-            mcp::bridge::start_mcp_server(config.clone()).await?;
+            mcp::bridge::start_mcp_server(config.clone())
+                .await
+                .map_err(GitAIError::from)?;
         }
         "tcp" => {
             println!("🌐 监听地址: {}", addr);
@@ -965,68 +2360,72 @@ async fn handle_mcp(config: &config::Config, transport: &str, addr: &str) -> Res
             eprintln!("⚠️  SSE 传输暂未实现");
         }
         _ => {
-            eprintln!("❌ 不支持的传输协议: {}", transport);
-            std::process::exit(1);
+            return Err(GitAIError::Mcp(gitai::error::McpError::InvalidParameters(
+                format!("不支持的传输协议: {}", transport),
+            )));
         }
     }
 
     Ok(())
 }
 
+/// `gitai mcp --list-tools`：列出工具与所属服务，排查工具路由与版本不一致
+#[cfg(feature = "mcp")]
+async fn handle_mcp_list_tools(config: &config::Config) -> Result<()> {
+    if !config.mcp.as_ref().map_or(false, |mcp| mcp.enabled) {
+        return Err(GitAIError::Config(ConfigError::ValidationFailed(
+            "MCP 服务未启用，请在配置文件中启用 MCP".to_string(),
+        )));
+    }
+
+    let manager = mcp::GitAiMcpManager::new(config.clone())
+        .await
+        .map_err(GitAIError::from)?;
+    let owners = manager.list_tool_owners().await;
+    let width = owners.iter().map(|o| o.tool.len()).max().unwrap_or(0);
+    for owner in owners {
+        println!(
+            "{:<width$}  {}@{}",
+            owner.tool, owner.service, owner.version
+        );
+    }
+    Ok(())
+}
+
 #[cfg(feature = "metrics")]
 async fn handle_metrics(_config: &config::Config, action: &MetricsAction) -> Result<()> {
     use gitai::metrics::QualityTracker;
     use gitai::project_insights::InsightsGenerator;
-    use gitai::tree_sitter::TreeSitterManager;
 
     match action {
-        MetricsAction::Record { tags, force } => {
+        MetricsAction::Record {
+            tags,
+            force,
+            max_concurrent,
+        } => {
             println!("📊 记录代码质量快照...");
 
             // 检查是否有代码变化（除非强制记录）
-            if !force {
-                let status = git::run_git(&["status".to_string(), "--porcelain".to_string()])?;
-                if status.trim().is_empty() {
-                    println!("ℹ️  没有检测到代码变化");
-                    println!("💡 使用 --force 强制记录快照");
-                    return Ok(());
-                }
+            if !force && git::status()?.is_clean() {
+                println!("ℹ️  没有检测到代码变化");
+                println!("💡 使用 --force 强制记录快照");
+                return Ok(());
             }
 
             // 创建质量追踪器
             let mut tracker = QualityTracker::new()?;
 
             // 分析当前代码
-            println!("🔍 分析代码结构...");
-            let mut manager = TreeSitterManager::new().await?;
-
-            // 获取当前目录的代码文件并分析
-            let mut summary = gitai::tree_sitter::StructuralSummary::default();
-            let code_files = find_code_files(".")?;
-
-            for file_path in &code_files {
-                if let Ok(content) = std::fs::read_to_string(file_path) {
-                    if let Some(ext) = file_path.extension().and_then(|s| s.to_str()) {
-                        if let Some(lang) =
-                            gitai::tree_sitter::SupportedLanguage::from_extension(ext)
-                        {
-                            if let Ok(file_summary) = manager.analyze_structure(&content, lang) {
-                                // 合并结果
-                                summary.functions.extend(file_summary.functions);
-                                summary.classes.extend(file_summary.classes);
-                                summary.comments.extend(file_summary.comments);
-                            }
-                        }
-                    }
-                }
-            }
+            gitai::progress!("🔍 分析代码结构...");
+
+            let (summary, file_summaries) = analyze_code_structure(".", *max_concurrent).await?;
 
             // 生成项目洞察
             println!("💡 生成项目洞察...");
             let insights = InsightsGenerator::generate(&summary, None);
 
             // 记录快照
-            let mut snapshot = tracker.record_snapshot(&summary, &insights)?;
+            let mut snapshot = tracker.record_snapshot(&summary, &file_summaries, &insights)?;
 
             // 添加标签
             if !tags.is_empty() {
@@ -1042,51 +2441,66 @@ async fn handle_metrics(_config: &config::Config, action: &MetricsAction) -> Res
                 "   复杂度: {:.1}",
                 snapshot.complexity_metrics.avg_cyclomatic_complexity
             );
+            println!(
+                "   可维护性指数: {:.1}",
+                snapshot.complexity_metrics.maintainability_index
+            );
         }
         MetricsAction::Analyze {
             days,
             format,
             output,
         } => {
+            use gitai::utils::report_output;
+
+            let targets = report_output::resolve_targets(
+                output,
+                format,
+                &["json", "markdown", "html", "text"],
+            )?;
             println!("📈 分析质量趋势...");
 
             let tracker = QualityTracker::new()?;
             let analysis = tracker.analyze_trends(*days)?;
 
-            let result = match format.as_str() {
-                "json" => serde_json::to_string_pretty(&analysis)?,
-                "markdown" | "html" => {
-                    let visualizer = metrics::visualizer::TrendVisualizer::new();
-                    if format == "html" {
-                        visualizer.generate_html_report(&analysis, tracker.get_snapshots())?
-                    } else {
-                        visualizer.generate_report(&analysis, tracker.get_snapshots())?
+            let render = |format: &str| -> Result<String> {
+                Ok(match format {
+                    "json" => serde_json::to_string_pretty(&analysis)?,
+                    "markdown" | "md" | "html" => {
+                        let visualizer = metrics::visualizer::TrendVisualizer::new();
+                        if format == "html" {
+                            visualizer.generate_html_report(&analysis, tracker.get_snapshots())?
+                        } else {
+                            visualizer.generate_report(&analysis, tracker.get_snapshots())?
+                        }
                     }
-                }
-                _ => {
-                    // 文本格式
-                    format!(
-                        "质量趋势分析\n\n\
-                        整体趋势: {:?}\n\
-                        时间范围: {} 到 {}\n\
-                        快照数量: {}\n\
-                        关键发现: {}\n\
-                        改进建议: {}\n",
-                        analysis.overall_trend,
-                        analysis.time_range.start.format("%Y-%m-%d"),
-                        analysis.time_range.end.format("%Y-%m-%d"),
-                        analysis.time_range.snapshots_count,
-                        analysis.key_findings.len(),
-                        analysis.recommendations.len()
-                    )
-                }
+                    _ => {
+                        // 文本格式
+                        format!(
+                            "质量趋势分析\n\n\
+                            整体趋势: {:?}\n\
+                            时间范围: {} 到 {}\n\
+                            快照数量: {}\n\
+                            关键发现: {}\n\
+                            改进建议: {}\n",
+                            analysis.overall_trend,
+                            analysis.time_range.start.format("%Y-%m-%d"),
+                            analysis.time_range.end.format("%Y-%m-%d"),
+                            analysis.time_range.snapshots_count,
+                            analysis.key_findings.len(),
+                            analysis.recommendations.len()
+                        )
+                    }
+                })
             };
 
-            if let Some(output_path) = output {
-                std::fs::write(output_path, result)?;
-                println!("📁 分析结果已保存到: {}", output_path.display());
+            if targets.is_empty() {
+                println!("{}", render(format)?);
             } else {
-                println!("{}", result);
+                report_output::write_targets(&targets, render)?;
+                for target in &targets {
+                    println!("📁 分析结果已保存到: {}", target.path.display());
+                }
             }
         }
         MetricsAction::Report {
@@ -1094,23 +2508,39 @@ async fn handle_metrics(_config: &config::Config, action: &MetricsAction) -> Res
             output,
             html,
         } => {
+            use gitai::utils::report_output::{self, OutputTarget};
+
             println!("📄 生成质量报告...");
 
             let tracker = QualityTracker::new()?;
-
-            let report = if *html {
-                let analysis = tracker.analyze_trends(None)?;
-                let visualizer = metrics::visualizer::TrendVisualizer::new();
-                visualizer.generate_html_report(&analysis, tracker.get_snapshots())?
-            } else {
-                tracker.generate_report(output.as_deref())?
+            let render = |format: &str| -> Result<String> {
+                if format == "html" {
+                    let analysis = tracker.analyze_trends(None)?;
+                    let visualizer = metrics::visualizer::TrendVisualizer::new();
+                    Ok(visualizer.generate_html_report(&analysis, tracker.get_snapshots())?)
+                } else {
+                    Ok(tracker.generate_report(None)?)
+                }
             };
 
-            if let Some(output_path) = output {
-                std::fs::write(output_path, report)?;
-                println!("✅ 报告已生成: {}", output_path.display());
+            // .html 文件输出 HTML 报告，其余输出 Markdown 报告
+            let targets: Vec<OutputTarget> = output
+                .iter()
+                .map(|path| OutputTarget {
+                    path: path.clone(),
+                    format: match report_output::format_for_path(path) {
+                        Some("html") => "html",
+                        _ => "markdown",
+                    },
+                })
+                .collect();
+            if targets.is_empty() {
+                println!("{}", render(if *html { "html" } else { "markdown" })?);
             } else {
-                println!("{}", report);
+                report_output::write_targets(&targets, render)?;
+                for target in &targets {
+                    println!("✅ 报告已生成: {}", target.path.display());
+                }
             }
         }
         MetricsAction::List {
@@ -1258,7 +2688,7 @@ async fn handle_metrics(_config: &config::Config, action: &MetricsAction) -> Res
                 }
                 "json" => {
                     let json = serde_json::to_string_pretty(&snapshots)?;
-                    std::fs::write(output, json)?;
+                    gitai::utils::atomic_write::write_atomic(output, json)?;
                     println!("✅ 已导出到: {}", output.display());
                 }
                 _ => {
@@ -1349,16 +2779,68 @@ async fn download_opengrep_resources(_config_path: &std::path::Path) -> Result<(
     }
 }
 
+/// 并发分析 dir 下的代码文件，返回合并后的结构摘要与逐文件摘要（按文件顺序）
+#[cfg(feature = "metrics")]
+async fn analyze_code_structure(
+    dir: &str,
+    max_concurrent: Option<usize>,
+) -> Result<(
+    gitai::tree_sitter::StructuralSummary,
+    Vec<gitai::tree_sitter::StructuralSummary>,
+)> {
+    // 获取代码文件并并发分析，按文件顺序合并结果
+    let mut summary = gitai::tree_sitter::StructuralSummary::default();
+    let code_files = find_code_files(dir)?;
+    let outcomes = gitai::tree_sitter::analyze_paths_with_outcomes(
+        &code_files,
+        max_concurrent,
+        &gitai::utils::cancel::CancellationToken::new(),
+    )
+    .await
+    .map_err(GitAIError::from)?;
+    let analysis =
+        gitai::tree_sitter::DirectoryAnalysisResult::from_outcomes(&code_files, outcomes);
+    if analysis.failed_count() > 0 {
+        gitai::progress!(
+            "{}",
+            gitai::tr!("analysis.failed_files", count = analysis.failed_count())
+        );
+    }
+
+    let file_summaries: Vec<_> = analysis
+        .files
+        .into_iter()
+        .map(|result| result.summary)
+        .collect();
+    for file_summary in &file_summaries {
+        summary
+            .functions
+            .extend(file_summary.functions.iter().cloned());
+        summary.classes.extend(file_summary.classes.iter().cloned());
+        summary
+            .comments
+            .extend(file_summary.comments.iter().cloned());
+    }
+
+    Ok((summary, file_summaries))
+}
+
 // 辅助函数：查找代码文件
 #[cfg(feature = "metrics")]
 fn find_code_files(dir: &str) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     let supported_extensions = ["rs", "java", "py", "js", "ts", "go", "c", "cpp"];
 
-    for entry in walkdir::WalkDir::new(dir)
+    let walk = gitai::utils::walk::active();
+    // 跟随符号链接时 walkdir 会把环路报告为错误条目，随其他错误一起跳过
+    let mut walker = walkdir::WalkDir::new(dir).follow_links(walk.follow_symlinks);
+    if let Some(max_depth) = walk.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+    for entry in walker
         .into_iter()
         .filter_map(|e| e.ok())
-        .filter(|e| !e.file_type().is_dir())
+        .filter(|e| !e.file_type().is_dir() && (walk.follow_symlinks || !e.path_is_symlink()))
     {
         let path = entry.path();
 
@@ -1367,7 +2849,8 @@ fn find_code_files(dir: &str) -> Result<Vec<PathBuf>> {
             c.as_os_str().to_str().is_some_and(|s| {
                 s.starts_with('.') || s == "target" || s == "node_modules" || s == "build"
             })
-        }) {
+        }) || gitai::utils::generated::is_generated_path(path)
+        {
             continue;
         }
 
@@ -0,0 +1,199 @@
+/// MCP 批量工具调用模块
+///
+/// `batch` 工具一次接收多个 `{tool, arguments}` 调用，按有限并发执行并按原顺序返回结果，
+/// 减少编排场景下的往返次数；单个调用失败只体现在对应条目上，不影响整批
+use crate::mcp::{McpError, McpResult, Tool};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 批量调用工具名称
+pub const BATCH_TOOL: &str = "batch";
+
+/// 默认并发数
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// 并发数上限
+pub const MAX_BATCH_CONCURRENCY: usize = 16;
+
+/// 单次批量调用允许的最大条目数
+pub const MAX_BATCH_CALLS: usize = 64;
+
+/// 批量调用参数
+#[derive(Debug, Deserialize)]
+pub struct BatchParams {
+    /// 依次执行的调用
+    pub calls: Vec<BatchCall>,
+    /// 最大并发数（默认 4，上限 16）
+    pub max_concurrency: Option<usize>,
+}
+
+/// 批量中的单个调用
+#[derive(Debug, Deserialize)]
+pub struct BatchCall {
+    /// 工具名称
+    pub tool: String,
+    /// 工具参数
+    #[serde(default = "empty_arguments")]
+    pub arguments: serde_json::Value,
+}
+
+fn empty_arguments() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+/// 单个调用的结果：成功时带 `result`，失败时带 `error` 与 `error_type`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchItemResult {
+    /// 工具名称
+    pub tool: String,
+    /// 是否成功
+    pub ok: bool,
+    /// 调用结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// 错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 错误类型（与 JSON-RPC 错误的 `data.error_type` 一致）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+}
+
+impl BatchItemResult {
+    /// 由单个调用的结果构建
+    pub fn from_result(tool: String, result: McpResult<serde_json::Value>) -> Self {
+        match result {
+            Ok(value) => Self {
+                tool,
+                ok: true,
+                result: Some(value),
+                error: None,
+                error_type: None,
+            },
+            Err(e) => Self {
+                tool,
+                ok: false,
+                result: None,
+                error_type: Some(error_type(&e).to_string()),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// 批量调用的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResponse {
+    /// 与请求中 `calls` 顺序一致的结果
+    pub results: Vec<BatchItemResult>,
+    /// 成功条目数
+    pub succeeded: usize,
+    /// 失败条目数
+    pub failed: usize,
+}
+
+impl BatchResponse {
+    /// 汇总各条目结果
+    pub fn new(results: Vec<BatchItemResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.ok).count();
+        let failed = results.len() - succeeded;
+        Self {
+            results,
+            succeeded,
+            failed,
+        }
+    }
+}
+
+impl BatchParams {
+    /// 解析并校验批量调用参数，返回调用列表与实际并发数
+    pub fn parse(arguments: serde_json::Value) -> McpResult<(Vec<BatchCall>, usize)> {
+        let params: BatchParams = serde_json::from_value(arguments)
+            .map_err(|e| crate::mcp::parse_error(BATCH_TOOL, e))?;
+        if params.calls.len() > MAX_BATCH_CALLS {
+            return Err(crate::mcp::invalid_parameters_error(format!(
+                "batch 最多包含 {MAX_BATCH_CALLS} 个调用，实际为 {}",
+                params.calls.len()
+            )));
+        }
+        let concurrency = params
+            .max_concurrency
+            .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+            .clamp(1, MAX_BATCH_CONCURRENCY);
+        Ok((params.calls, concurrency))
+    }
+}
+
+/// 错误类型名称
+fn error_type(e: &McpError) -> &'static str {
+    match e {
+        McpError::InvalidParameters(_) => "InvalidParameters",
+        McpError::ExecutionFailed(_) => "ExecutionFailed",
+        McpError::ConfigurationError(_) => "ConfigurationError",
+        McpError::FileOperationError(_) => "FileOperationError",
+        McpError::NetworkError(_) => "NetworkError",
+        McpError::ExternalToolError(_) => "ExternalToolError",
+        McpError::PermissionError(_) => "PermissionError",
+        McpError::TimeoutError(_) => "TimeoutError",
+        McpError::Unknown(_) => "Unknown",
+    }
+}
+
+/// `batch` 工具定义
+pub fn batch_tool() -> Tool {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), serde_json::json!("object"));
+    schema.insert(
+        "properties".to_string(),
+        serde_json::json!({
+            "calls": {
+                "type": "array",
+                "description": "要执行的调用，结果按相同顺序返回",
+                "maxItems": MAX_BATCH_CALLS,
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "tool": {"type": "string", "description": "工具名称"},
+                        "arguments": {"type": "object", "description": "工具参数"}
+                    },
+                    "required": ["tool"]
+                }
+            },
+            "max_concurrency": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": MAX_BATCH_CONCURRENCY,
+                "description": "最大并发数，默认 4"
+            }
+        }),
+    );
+    schema.insert("required".to_string(), serde_json::json!(["calls"]));
+    Tool {
+        name: BATCH_TOOL.into(),
+        description: "批量执行多个工具调用（有限并发），按请求顺序返回每个调用的结果或错误".into(),
+        input_schema: Arc::new(schema),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_params() {
+        let (calls, concurrency) = BatchParams::parse(serde_json::json!({
+            "calls": [{"tool": "a", "arguments": {"x": 1}}, {"tool": "b"}],
+            "max_concurrency": 100
+        }))
+        .unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].arguments, serde_json::json!({}));
+        assert_eq!(concurrency, MAX_BATCH_CONCURRENCY);
+
+        assert!(BatchParams::parse(serde_json::json!({"calls": "a"})).is_err());
+        let too_many: Vec<_> = (0..=MAX_BATCH_CALLS)
+            .map(|_| serde_json::json!({"tool": "a"}))
+            .collect();
+        assert!(BatchParams::parse(serde_json::json!({ "calls": too_many })).is_err());
+    }
+}
@@ -182,9 +182,16 @@ pub async fn start_mcp_server(config: Config) -> McpResult<()> {
                                     .cloned()
                                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
-                                // 使用共享的服务管理器处理工具调用
+                                // 使用共享的服务管理器处理工具调用，每次调用分配独立的请求 ID
+                                let request_id = crate::context::new_request_id();
+                                log::debug!("🔧 工具调用 {tool_name} 的请求 ID: {request_id}");
                                 let manager_read = manager.read().await;
-                                match manager_read.handle_tool_call(tool_name, arguments).await {
+                                match crate::context::with_request_id(
+                                    request_id.clone(),
+                                    manager_read.handle_tool_call(tool_name, arguments),
+                                )
+                                .await
+                                {
                                     Ok(result) => {
                                         let response = json!({
                                             "jsonrpc": "2.0",
@@ -260,7 +267,8 @@ pub async fn start_mcp_server(config: Config) -> McpResult<()> {
                                                 "code": error_code,
                                                 "message": error_message,
                                                 "data": {
-                                                    "type": error_type
+                                                    "type": error_type,
+                                                    "request_id": request_id
                                                 }
                                             }
                                         });
@@ -0,0 +1,133 @@
+/// MCP 工具调用日志模块
+///
+/// 按配置记录工具调用的参数与（截断后的）响应，便于排查 LLM 客户端传入的异常参数；
+/// 敏感字段在写入日志前脱敏。未启用时管理器不持有记录器，调用路径上只有一次判空
+use crate::config::McpLoggingConfig;
+use log::info;
+
+/// 开启调用日志的环境变量
+pub const LOG_CALLS_ENV: &str = "GITAI_MCP_LOG_CALLS";
+
+/// 脱敏后的占位值
+const REDACTED: &str = "***";
+
+/// 工具调用日志记录器
+#[derive(Debug, Clone)]
+pub struct CallLogger {
+    max_response_chars: usize,
+    /// 小写的敏感字段名
+    redact_fields: Vec<String>,
+}
+
+impl CallLogger {
+    /// 按配置与环境变量创建记录器；未启用时返回 None
+    pub fn from_config(config: Option<&McpLoggingConfig>) -> Option<Self> {
+        let env_enabled = std::env::var(LOG_CALLS_ENV)
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let default_config = McpLoggingConfig::default();
+        let config = config.unwrap_or(&default_config);
+        (config.enabled || env_enabled).then(|| Self::new(config))
+    }
+
+    /// 按配置创建记录器（不检查是否启用）
+    pub fn new(config: &McpLoggingConfig) -> Self {
+        Self {
+            max_response_chars: config.max_response_chars,
+            redact_fields: config
+                .redact_fields
+                .iter()
+                .map(|f| f.to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// 记录调用参数
+    pub fn log_request(&self, tool_name: &str, arguments: &serde_json::Value) {
+        info!(
+            "📥 MCP 工具调用 {tool_name} 参数: {}",
+            self.redact(arguments)
+        );
+    }
+
+    /// 记录调用响应（成功时为脱敏并截断后的结果，失败时为错误信息）
+    pub fn log_response<E: std::fmt::Display>(
+        &self,
+        tool_name: &str,
+        result: &Result<serde_json::Value, E>,
+        duration_ms: u64,
+    ) {
+        match result {
+            Ok(value) => info!(
+                "📤 MCP 工具调用 {tool_name} 成功 ({duration_ms}ms) 响应: {}",
+                self.truncate(&self.redact(value).to_string())
+            ),
+            Err(e) => info!("📤 MCP 工具调用 {tool_name} 失败 ({duration_ms}ms): {e}"),
+        }
+    }
+
+    /// 把敏感字段的值替换为占位符，嵌套对象与数组同样处理
+    pub fn redact(&self, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(key, v)| {
+                        let v = if self.redact_fields.contains(&key.to_lowercase()) {
+                            serde_json::Value::String(REDACTED.to_string())
+                        } else {
+                            self.redact(v)
+                        };
+                        (key.clone(), v)
+                    })
+                    .collect(),
+            ),
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(|v| self.redact(v)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// 截断到最大字符数，并注明原始长度
+    fn truncate(&self, text: &str) -> String {
+        let total = text.chars().count();
+        if total <= self.max_response_chars {
+            return text.to_string();
+        }
+        let kept: String = text.chars().take(self.max_response_chars).collect();
+        format!("{kept}…（已截断，共 {total} 个字符）")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_and_truncate() {
+        let logger = CallLogger::new(&McpLoggingConfig {
+            enabled: true,
+            max_response_chars: 5,
+            redact_fields: vec!["Token".to_string()],
+        });
+        let args = serde_json::json!({
+            "path": "/repo",
+            "token": "abc",
+            "nested": {"TOKEN": "def", "items": [{"token": "ghi", "keep": 1}]}
+        });
+        assert_eq!(
+            logger.redact(&args),
+            serde_json::json!({
+                "path": "/repo",
+                "token": "***",
+                "nested": {"TOKEN": "***", "items": [{"token": "***", "keep": 1}]}
+            })
+        );
+
+        assert_eq!(logger.truncate("短文本"), "短文本");
+        assert_eq!(
+            logger.truncate("一二三四五六七"),
+            "一二三四五…（已截断，共 7 个字符）"
+        );
+    }
+}
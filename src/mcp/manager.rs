@@ -2,11 +2,29 @@
 ///
 /// 将 MCP 服务注册表整合到 GitAiMcpManager 中
 use crate::config::Config;
-use crate::mcp::registry::{ServiceEvent, ServiceEventListener, ServiceMetadata, ServiceRegistry};
+use crate::mcp::registry::{
+    ServiceEvent, ServiceEventListener, ServiceMetadata, ServiceRegistry, ToolOwner,
+};
 use crate::mcp::{GitAiMcpService, McpResult, Tool};
 use log::{debug, error, info, warn};
 use std::sync::Arc;
 
+/// 管理器内置的工具：列出全部工具及其所属服务，便于排查工具路由与版本不一致
+pub const LIST_TOOL_OWNERS_TOOL: &str = "list_tool_owners";
+
+/// 内置工具所属的“服务”名称
+pub const BUILTIN_SERVICE: &str = "gitai";
+
+/// 由管理器直接提供（不属于任何已注册服务）的工具归属
+fn builtin_tool_owner(tool: &str) -> ToolOwner {
+    ToolOwner {
+        tool: tool.to_string(),
+        service: BUILTIN_SERVICE.to_string(),
+        version: semver::Version::parse(env!("CARGO_PKG_VERSION"))
+            .unwrap_or_else(|_| semver::Version::new(0, 0, 0)),
+    }
+}
+
 /// 整合服务注册表到 MCP 服务管理器中
 pub struct ManagedServiceRegistry {
     /// 配置
@@ -213,9 +231,27 @@ impl ManagedServiceRegistry {
             }
         }
 
+        if self.builtin_tools_enabled() {
+            tools.extend(builtin_tools());
+        }
         tools
     }
 
+    /// 是否提供内置工具：与服务一样，仅在 MCP 启用时提供
+    pub fn builtin_tools_enabled(&self) -> bool {
+        self.config.mcp.as_ref().is_some_and(|mcp| mcp.enabled)
+    }
+
+    /// 列出全部工具及其所属服务与版本（含管理器内置工具），随服务的动态注册/注销变化
+    pub async fn list_tool_owners(&self) -> Vec<ToolOwner> {
+        let mut owners = self.registry.list_tool_owners().await;
+        if self.builtin_tools_enabled() {
+            owners.extend(builtin_tools().iter().map(|t| builtin_tool_owner(&t.name)));
+        }
+        owners.sort_by(|a, b| a.tool.cmp(&b.tool).then_with(|| a.service.cmp(&b.service)));
+        owners
+    }
+
     /// 处理工具调用
     pub async fn handle_tool_call(
         &self,
@@ -228,6 +264,12 @@ impl ManagedServiceRegistry {
             serde_json::to_string_pretty(&arguments).unwrap_or_default()
         );
 
+        if tool_name == LIST_TOOL_OWNERS_TOOL && self.builtin_tools_enabled() {
+            let owners = self.list_tool_owners().await;
+            return serde_json::to_value(owners)
+                .map_err(|e| crate::mcp::serialize_error(LIST_TOOL_OWNERS_TOOL, e));
+        }
+
         // 查找处理该工具的服务
         if let Some(service) = self.registry.find_service_by_tool(tool_name).await {
             debug!("🎯 找到处理服务: {}", service.name());
@@ -276,3 +318,105 @@ impl ManagedServiceRegistry {
         self.registry.get_healthy_services().await
     }
 }
+
+/// 管理器内置的工具；`batch` 由 [`crate::mcp::GitAiMcpManager`] 处理，以便子调用计入性能统计
+fn builtin_tools() -> Vec<Tool> {
+    vec![list_tool_owners_tool(), crate::mcp::batch::batch_tool()]
+}
+
+/// 内置工具 `list_tool_owners` 的定义（无参数）
+fn list_tool_owners_tool() -> Tool {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), serde_json::json!("object"));
+    schema.insert("properties".to_string(), serde_json::json!({}));
+    Tool {
+        name: LIST_TOOL_OWNERS_TOOL.into(),
+        description:
+            "列出当前提供的全部 MCP 工具及其所属服务与服务版本，用于排查工具路由与版本不一致".into(),
+        input_schema: Arc::new(schema),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoService;
+
+    #[async_trait::async_trait]
+    impl GitAiMcpService for EchoService {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo service"
+        }
+
+        fn version(&self) -> semver::Version {
+            semver::Version::new(2, 1, 0)
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool {
+                name: "echo".into(),
+                description: "Echo arguments".into(),
+                input_schema: Arc::new(serde_json::Map::new()),
+            }]
+        }
+
+        async fn handle_tool_call(
+            &self,
+            _name: &str,
+            arguments: serde_json::Value,
+        ) -> McpResult<serde_json::Value> {
+            Ok(arguments)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_owners_after_register() {
+        let mut config = Config::default();
+        if let Some(mcp) = config.mcp.as_mut() {
+            mcp.enabled = true;
+            mcp.services.enabled.clear();
+        }
+        let manager = ManagedServiceRegistry::new(config).await.unwrap();
+        let owners = manager.list_tool_owners().await;
+        assert!(owners.iter().all(|o| o.service == BUILTIN_SERVICE));
+        assert!(owners.iter().any(|o| o.tool == LIST_TOOL_OWNERS_TOOL));
+
+        manager
+            .register_service(Arc::new(EchoService), serde_json::json!({}))
+            .await
+            .unwrap();
+        let owners = manager.list_tool_owners().await;
+        let echo = owners.iter().find(|o| o.tool == "echo").unwrap();
+        assert_eq!(echo.service, "echo");
+        assert_eq!(echo.version, semver::Version::new(2, 1, 0));
+
+        // 通过 MCP 工具调用得到相同结果
+        let value = manager
+            .handle_tool_call(LIST_TOOL_OWNERS_TOOL, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::to_value(&owners).unwrap());
+        assert!(manager
+            .get_all_tools()
+            .await
+            .iter()
+            .any(|t| t.name == LIST_TOOL_OWNERS_TOOL));
+
+        // 注销后工具随之消失
+        let id = manager.list_services().await[0].id.clone();
+        manager
+            .unregister_service(&id, "test".to_string())
+            .await
+            .unwrap();
+        assert!(manager
+            .list_tool_owners()
+            .await
+            .iter()
+            .all(|o| o.tool != "echo"));
+    }
+}
@@ -3,12 +3,14 @@
 // 该模块提供 GitAI 核心功能的 MCP (Model Context Protocol) 服务实现，
 // 使得 GitAI 可以作为 MCP 服务器被 LLM 调用
 
+pub mod batch;
 pub mod bridge;
+pub mod call_log;
 pub mod manager;
 pub mod registry;
 pub mod services;
 
-use log::info;
+use log::{debug, info};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -70,6 +72,30 @@ impl From<McpError> for ServiceError {
     }
 }
 
+impl From<McpError> for crate::error::McpError {
+    fn from(err: McpError) -> Self {
+        use crate::error::McpError as Unified;
+        match err {
+            McpError::InvalidParameters(msg) => Unified::InvalidParameters(msg),
+            McpError::ExecutionFailed(msg) => Unified::ExecutionFailed(msg),
+            McpError::ConfigurationError(msg) => Unified::ConfigurationError(msg),
+            McpError::FileOperationError(msg) => Unified::FileOperationError(msg),
+            McpError::NetworkError(msg) => Unified::NetworkError(msg),
+            McpError::ExternalToolError(msg) => Unified::ExternalToolError(msg),
+            McpError::PermissionError(msg) => Unified::PermissionError(msg),
+            McpError::TimeoutError(msg) => Unified::TimeoutError(msg),
+            McpError::Unknown(msg) => Unified::Unknown(msg),
+        }
+    }
+}
+
+/// 内嵌在主程序中运行时，MCP 错误按原有类别并入统一错误类型
+impl From<McpError> for crate::error::GitAIError {
+    fn from(err: McpError) -> Self {
+        crate::error::GitAIError::Mcp(err.into())
+    }
+}
+
 impl From<serde_json::Error> for McpError {
     fn from(err: serde_json::Error) -> Self {
         McpError::InvalidParameters(format!("JSON parsing error: {}", err))
@@ -355,6 +381,8 @@ pub struct GitAiMcpManager {
     managed_registry: manager::ManagedServiceRegistry,
     /// 性能统计收集器
     performance_collector: Arc<PerformanceCollector>,
+    /// 工具调用日志记录器（未启用时为 None）
+    call_logger: Option<call_log::CallLogger>,
 }
 
 impl GitAiMcpManager {
@@ -362,12 +390,21 @@ impl GitAiMcpManager {
     pub async fn new(config: crate::config::Config) -> McpResult<Self> {
         info!("🔧 初始化 GitAI MCP 服务管理器");
 
+        let server_root = services::init_server_root();
+        debug!("📁 MCP 默认允许的根目录: {}", server_root.display());
+
+        let call_logger =
+            call_log::CallLogger::from_config(config.mcp.as_ref().map(|mcp| &mcp.logging));
+        if call_logger.is_some() {
+            info!("📝 已启用 MCP 工具调用日志");
+        }
         let managed_registry = manager::ManagedServiceRegistry::new(config).await?;
         let performance_collector = Arc::new(PerformanceCollector::new());
 
         Ok(Self {
             managed_registry,
             performance_collector,
+            call_logger,
         })
     }
 
@@ -382,9 +419,50 @@ impl GitAiMcpManager {
         &self,
         tool_name: &str,
         arguments: serde_json::Value,
+    ) -> McpResult<serde_json::Value> {
+        if tool_name == batch::BATCH_TOOL && self.managed_registry.builtin_tools_enabled() {
+            return self.handle_batch(arguments).await;
+        }
+        self.dispatch_tool_call(tool_name, arguments).await
+    }
+
+    /// 批量调用：按有限并发执行各个调用，按请求顺序返回每个调用的结果或错误
+    async fn handle_batch(&self, arguments: serde_json::Value) -> McpResult<serde_json::Value> {
+        use futures_util::stream::{self, StreamExt};
+
+        let (calls, concurrency) = batch::BatchParams::parse(arguments)?;
+        info!(
+            "📦 批量执行 {} 个工具调用，最大并发数: {concurrency}",
+            calls.len()
+        );
+        let results: Vec<batch::BatchItemResult> = stream::iter(calls)
+            .map(|call| async move {
+                let result = if call.tool == batch::BATCH_TOOL {
+                    Err(invalid_parameters_error("batch 不能嵌套调用 batch"))
+                } else {
+                    self.dispatch_tool_call(&call.tool, call.arguments).await
+                };
+                batch::BatchItemResult::from_result(call.tool, result)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        serde_json::to_value(batch::BatchResponse::new(results))
+            .map_err(|e| serialize_error(batch::BATCH_TOOL, e))
+    }
+
+    /// 执行单个工具调用，并记录耗时、成功/失败统计与调用日志
+    async fn dispatch_tool_call(
+        &self,
+        tool_name: &str,
+        arguments: serde_json::Value,
     ) -> McpResult<serde_json::Value> {
         // 记录调用开始
         let start_time = self.performance_collector.record_call_start(tool_name);
+        if let Some(logger) = &self.call_logger {
+            logger.log_request(tool_name, &arguments);
+        }
 
         let result = self
             .managed_registry
@@ -394,6 +472,9 @@ impl GitAiMcpManager {
         // 记录调用结果
         let duration = start_time.elapsed();
         let duration_ms = duration.as_millis() as u64;
+        if let Some(logger) = &self.call_logger {
+            logger.log_response(tool_name, &result, duration_ms);
+        }
 
         match &result {
             Ok(_) => {
@@ -435,6 +516,11 @@ impl GitAiMcpManager {
         self.managed_registry.list_services().await
     }
 
+    /// 列出全部工具及其所属服务与版本
+    pub async fn list_tool_owners(&self) -> Vec<registry::ToolOwner> {
+        self.managed_registry.list_tool_owners().await
+    }
+
     /// 获取健康的服务列表
     #[allow(dead_code)]
     pub async fn get_healthy_services(&self) -> Vec<registry::ServiceMetadata> {
@@ -491,3 +577,129 @@ pub fn serialize_error(service_name: &str, e: impl std::fmt::Display) -> McpErro
         service_name, e
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{exit_codes, GitAIError};
+
+    #[test]
+    fn test_mcp_error_into_gitai_error() {
+        let cases = vec![
+            (
+                McpError::TimeoutError("slow".to_string()),
+                exit_codes::NETWORK,
+                "超时错误: slow",
+            ),
+            (
+                McpError::ConfigurationError("disabled".to_string()),
+                exit_codes::CONFIG,
+                "配置错误: disabled",
+            ),
+            (
+                McpError::ExecutionFailed("boom".to_string()),
+                exit_codes::GENERAL,
+                "服务执行错误: boom",
+            ),
+        ];
+
+        for (err, code, detail) in cases {
+            let unified = GitAIError::from(err);
+            assert!(matches!(unified, GitAIError::Mcp(_)));
+            assert_eq!(unified.error_code(), "mcp");
+            assert_eq!(unified.exit_code(), code);
+            assert_eq!(unified.detail(), detail);
+        }
+    }
+
+    struct FlakyService;
+
+    #[async_trait::async_trait]
+    impl GitAiMcpService for FlakyService {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn description(&self) -> &str {
+            "Succeeds or fails on demand"
+        }
+
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool {
+                name: "maybe_fail".into(),
+                description: "Fails when `fail` is true".into(),
+                input_schema: Arc::new(serde_json::Map::new()),
+            }]
+        }
+
+        async fn handle_tool_call(
+            &self,
+            _name: &str,
+            arguments: serde_json::Value,
+        ) -> McpResult<serde_json::Value> {
+            if arguments["fail"].as_bool().unwrap_or(false) {
+                Err(execution_failed_error("requested failure"))
+            } else {
+                Ok(arguments)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_preserves_order_and_reports_partial_failures() {
+        let mut config = crate::config::Config::default();
+        if let Some(mcp) = config.mcp.as_mut() {
+            mcp.enabled = true;
+            mcp.services.enabled.clear();
+        }
+        let manager = GitAiMcpManager::new(config).await.unwrap();
+        manager
+            .register_service(Arc::new(FlakyService), serde_json::json!({}))
+            .await
+            .unwrap();
+
+        let value = manager
+            .handle_tool_call(
+                batch::BATCH_TOOL,
+                serde_json::json!({
+                    "calls": [
+                        {"tool": "maybe_fail", "arguments": {"n": 1}},
+                        {"tool": "maybe_fail", "arguments": {"fail": true}},
+                        {"tool": "missing_tool"},
+                        {"tool": "batch", "arguments": {"calls": []}},
+                        {"tool": "maybe_fail", "arguments": {"n": 5}}
+                    ],
+                    "max_concurrency": 2
+                }),
+            )
+            .await
+            .unwrap();
+
+        let results = value["results"].as_array().unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0]["result"], serde_json::json!({"n": 1}));
+        assert_eq!(results[1]["ok"], false);
+        assert_eq!(results[1]["error_type"], "ExecutionFailed");
+        assert_eq!(results[2]["error_type"], "InvalidParameters");
+        assert_eq!(results[3]["error_type"], "InvalidParameters");
+        assert_eq!(results[4]["result"], serde_json::json!({"n": 5}));
+        assert_eq!(value["succeeded"], 2);
+        assert_eq!(value["failed"], 3);
+
+        // 子调用分别计入性能统计，嵌套的 batch 不执行
+        let stats = manager.get_performance_stats();
+        assert_eq!(stats.tool_calls, 4);
+        assert_eq!(stats.tool_stats["maybe_fail"].calls, 3);
+        assert_eq!(stats.tool_stats["maybe_fail"].failed_calls, 1);
+        assert!(!stats.tool_stats.contains_key(batch::BATCH_TOOL));
+    }
+
+    #[test]
+    fn test_boxed_mcp_error_is_classified() {
+        let boxed: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(McpError::TimeoutError("slow".to_string()));
+        let report = crate::error::ErrorReport::from_error(boxed.as_ref());
+        assert_eq!(report.error_type, "mcp");
+        assert_eq!(crate::error::exit_code(boxed.as_ref()), exit_codes::NETWORK);
+    }
+}
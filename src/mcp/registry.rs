@@ -60,6 +60,17 @@ pub struct ServiceDependency {
     pub optional: bool,
 }
 
+/// 工具与提供它的服务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolOwner {
+    /// 工具名称
+    pub tool: String,
+    /// 服务名称
+    pub service: String,
+    /// 服务版本
+    pub version: Version,
+}
+
 /// 服务注册表
 pub struct ServiceRegistry {
     /// 已注册的服务元数据
@@ -267,6 +278,23 @@ impl ServiceRegistry {
         services.values().cloned().collect()
     }
 
+    /// 列出当前已注册服务提供的全部工具及其所属服务，按工具名排序
+    pub async fn list_tool_owners(&self) -> Vec<ToolOwner> {
+        let services = self.services.read().await;
+        let mut owners: Vec<ToolOwner> = services
+            .values()
+            .flat_map(|metadata| {
+                metadata.tools.iter().map(|tool| ToolOwner {
+                    tool: tool.clone(),
+                    service: metadata.name.clone(),
+                    version: metadata.version.clone(),
+                })
+            })
+            .collect();
+        owners.sort_by(|a, b| a.tool.cmp(&b.tool).then_with(|| a.service.cmp(&b.service)));
+        owners
+    }
+
     /// 获取服务实例
     pub async fn get_service(
         &self,
@@ -762,6 +790,58 @@ mod tests {
         assert_eq!(services.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_list_tool_owners() {
+        let registry = ServiceRegistry::new();
+        registry
+            .register_service(
+                Arc::new(MockService::new("beta", "Beta service")),
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+        registry
+            .register_service(
+                Arc::new(MockService::new("alpha", "Alpha service")),
+                serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+
+        let owners = registry.list_tool_owners().await;
+        assert_eq!(
+            owners,
+            vec![
+                ToolOwner {
+                    tool: "test_alpha".to_string(),
+                    service: "alpha".to_string(),
+                    version: Version::new(1, 0, 0),
+                },
+                ToolOwner {
+                    tool: "test_beta".to_string(),
+                    service: "beta".to_string(),
+                    version: Version::new(1, 0, 0),
+                },
+            ]
+        );
+
+        // 注销后不再列出该服务的工具
+        let beta_id = registry
+            .list_services()
+            .await
+            .into_iter()
+            .find(|m| m.name == "beta")
+            .unwrap()
+            .id;
+        registry
+            .unregister_service(&beta_id, "Test removal".to_string())
+            .await
+            .unwrap();
+        let owners = registry.list_tool_owners().await;
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[0].service, "alpha");
+    }
+
     #[tokio::test]
     async fn test_find_service_by_tool() {
         let registry = ServiceRegistry::new();
@@ -16,7 +16,6 @@ use std::sync::Arc;
 
 /// Analysis 服务
 pub struct AnalysisService {
-    #[allow(dead_code)]
     config: Config,
     verbosity: u32,
 }
@@ -40,6 +39,7 @@ impl AnalysisService {
     /// 执行代码分析
     async fn execute_analysis(
         &self,
+        path: &Path,
         params: AnalysisParams,
     ) -> Result<AnalysisResult, Box<dyn std::error::Error + Send + Sync>> {
         info!("🔍 开始代码分析: {}", params.path);
@@ -48,18 +48,10 @@ impl AnalysisService {
             params.language, params.verbosity
         );
 
-        let path = match crate::utils::paths::resolve_mcp_path(&params.path, "Analysis") {
-            Ok(path) => path,
-            Err(e) => {
-                error!("❌ {}", e);
-                return Err(e.into());
-            }
-        };
-
         // 检查是否为目录
         if path.is_dir() {
             info!("📁 检测到目录路径，尝试分析目录中的文件");
-            return self.analyze_directory(&path, &params).await;
+            return self.analyze_directory(path, &params).await;
         }
 
         // 使用真实的分析逻辑 - 单个文件分析
@@ -69,11 +61,11 @@ impl AnalysisService {
                 .ok_or_else(|| format!("不支持的语言: {}", lang))?
         } else {
             debug!("🔍 自动推断语言");
-            Self::infer_language_from_path(&path).map_err(|e| format!("无法推断语言: {}", e))?
+            Self::infer_language_from_path(path).map_err(|e| format!("无法推断语言: {}", e))?
         };
 
         // 读取文件内容
-        let code_content = std::fs::read_to_string(&path).map_err(|e| {
+        let code_content = std::fs::read_to_string(path).map_err(|e| {
             error!("❌ 无法读取文件 {}: {}", path.display(), e);
             format!("无法读取文件 {}: {}", path.display(), e)
         })?;
@@ -244,7 +236,7 @@ impl AnalysisService {
         details.insert("concurrent_processing".to_string(), "enabled".to_string());
         details.insert(
             "max_concurrency".to_string(),
-            std::cmp::min(total_files, num_cpus::get() * 2).to_string(),
+            self.concurrency_for(total_files).to_string(),
         );
         details.insert(
             "language_distribution".to_string(),
@@ -286,6 +278,38 @@ impl AnalysisService {
         })
     }
 
+    /// 显式指定的分析并发数（环境变量优先于配置），均未设置时为 None
+    fn configured_concurrency(&self) -> Option<usize> {
+        let configured = self
+            .config
+            .mcp
+            .as_ref()
+            .and_then(|mcp| mcp.services.analysis.as_ref())
+            .and_then(|analysis| analysis.max_concurrency);
+        tree_sitter::analysis_concurrency_from_env().or(configured)
+    }
+
+    /// 图工具的扫描目录：`path`（默认 .）相对于可选的 `repo_path`（别名 `cwd`）解析
+    fn graph_target_path(
+        &self,
+        arguments: &serde_json::Value,
+    ) -> crate::mcp::McpResult<std::path::PathBuf> {
+        let path = arguments
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".");
+        let repo_path = arguments
+            .get("repo_path")
+            .or_else(|| arguments.get("cwd"))
+            .and_then(|v| v.as_str());
+        super::resolve_target_path(&self.config, repo_path, path, "Analysis")
+    }
+
+    /// 分析 file_count 个文件时使用的并发数
+    fn concurrency_for(&self, file_count: usize) -> usize {
+        tree_sitter::analysis_pool_size(file_count, self.configured_concurrency())
+    }
+
     /// 并发分析多个文件
     async fn analyze_files_concurrently(
         &self,
@@ -294,11 +318,11 @@ impl AnalysisService {
         use std::sync::Arc;
         use tokio::sync::Semaphore;
 
-        // 限制并发数量以避免占用过多资源
-        let max_concurrent = std::cmp::min(file_paths.len(), num_cpus::get() * 2);
+        // 每个工作任务都要构建独立的 TreeSitterManager，并发数按机器并行度确定
+        let max_concurrent = self.concurrency_for(file_paths.len());
         let semaphore = Arc::new(Semaphore::new(max_concurrent));
 
-        debug!(
+        info!(
             "🚀 开始并发分析 {} 个文件，最大并发数: {}",
             file_paths.len(),
             max_concurrent
@@ -746,7 +770,11 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     "properties": {
                         "path": {
                             "type": "string",
-                            "description": "要分析的文件路径或目录路径"
+                            "description": "要分析的文件路径或目录路径（相对路径相对于 repo_path）"
+                        },
+                        "repo_path": {
+                            "type": "string",
+                            "description": "工作目录（可选，别名 cwd；默认为 MCP 服务的运行目录）"
                         },
                         "language": {
                             "type": "string",
@@ -769,7 +797,8 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                 input_schema: Arc::new(serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "path": {"type": "string", "description": "扫描目录（默认 .）"},
+                        "path": {"type": "string", "description": "扫描目录（默认 .，相对路径相对于 repo_path）"},
+                        "repo_path": {"type": "string", "description": "工作目录（可选，别名 cwd）"},
                         "start": {"type": "string", "description": "起始函数名（必需）"},
                         "end": {"type": "string", "description": "结束函数名（可选）"},
                         "direction": {"type": "string", "enum": ["downstream", "upstream"], "description": "方向：下游(被调用方)/上游(调用方)，默认 downstream"},
@@ -785,7 +814,8 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                 input_schema: Arc::new(serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "path": {"type": "string", "description": "扫描目录（默认 .）"},
+                        "path": {"type": "string", "description": "扫描目录（默认 .，相对路径相对于 repo_path）"},
+                        "repo_path": {"type": "string", "description": "工作目录（可选，别名 cwd）"},
                         "radius": {"type": "integer", "minimum": 1, "description": "从种子出发的邻域半径（默认1）"},
                         "top_k": {"type": "integer", "minimum": 1, "description": "Top节点上限（默认200）"},
                         "seeds_from_diff": {"type": "boolean", "description": "从 git diff 推导变更种子（默认false）"},
@@ -820,8 +850,15 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     params.verbosity = Some(self.verbosity);
                 }
 
+                // 路径在分派前校验，越权访问以 PermissionError 返回
+                let path = super::resolve_target_path(
+                    &self.config,
+                    params.repo_path.as_deref(),
+                    &params.path,
+                    "Analysis",
+                )?;
                 let result = self
-                    .execute_analysis(params)
+                    .execute_analysis(&path, params)
                     .await
                     .map_err(|e| crate::mcp::execution_error("Analysis", e))?;
 
@@ -829,10 +866,7 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     .map_err(|e| crate::mcp::serialize_error("analysis", e))?)
             }
             "query_call_chain" => {
-                let path = arguments
-                    .get("path")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or(".");
+                let path = self.graph_target_path(&arguments)?;
                 let start = arguments
                     .get("start")
                     .and_then(|v| v.as_str())
@@ -851,22 +885,14 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(20) as usize;
                 let chains = crate::architectural_impact::graph_export::query_call_chain(
-                    std::path::Path::new(path),
-                    start,
-                    end,
-                    direction,
-                    max_depth,
-                    max_paths,
+                    &path, start, end, direction, max_depth, max_paths,
                 )
                 .await
                 .map_err(|e| crate::mcp::execution_error("Analysis", e))?;
                 Ok(serde_json::json!({"chains": chains, "message": "ok"}))
             }
             "summarize_graph" => {
-                let path = arguments
-                    .get("path")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or(".");
+                let path = self.graph_target_path(&arguments)?;
                 let radius = arguments
                     .get("radius")
                     .and_then(|v| v.as_u64())
@@ -917,7 +943,7 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     .unwrap_or(5) as usize;
 
                 let out = crate::architectural_impact::graph_export::export_summary_string(
-                    std::path::Path::new(path),
+                    &path,
                     radius,
                     top_k,
                     seeds_from_diff,
@@ -930,6 +956,8 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
                     with_paths,
                     path_samples,
                     path_max_hops,
+                    &Default::default(),
+                    self.configured_concurrency(),
                 )
                 .await
                 .map_err(|e| crate::mcp::execution_error("Analysis", e))?;
@@ -953,8 +981,11 @@ impl crate::mcp::GitAiMcpService for AnalysisService {
 /// Analysis 参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisParams {
-    /// 分析路径
+    /// 分析路径（相对路径相对于 repo_path）
     pub path: String,
+    /// 工作目录（可选，别名 cwd）
+    #[serde(default, alias = "cwd")]
+    pub repo_path: Option<String>,
     /// 编程语言
     pub language: Option<String>,
     /// 输出详细程度
@@ -27,6 +27,8 @@ impl CommitService {
                     review: commit_config.default_review,
                     tree_sitter: commit_config.default_tree_sitter,
                     dry_run: false,
+                    max_diff_tokens: None,
+                    template: None,
                 }
             } else {
                 Self::default_commit_config()
@@ -50,6 +52,8 @@ impl CommitService {
             review: false,
             tree_sitter: false,
             dry_run: false,
+            max_diff_tokens: None,
+            template: None,
         }
     }
 
@@ -21,8 +21,11 @@ pub struct DependencyService {
 /// 依赖图生成参数
 #[derive(Debug, Deserialize)]
 pub struct DependencyParams {
-    /// 分析路径
+    /// 分析路径（相对路径相对于 repo_path）
     pub path: String,
+    /// 工作目录（可选，别名 cwd）
+    #[serde(default, alias = "cwd")]
+    pub repo_path: Option<String>,
     /// 生成格式 (json, dot, svg, mermaid)
     pub format: Option<String>,
     /// 输出文件路径（可选）
@@ -112,6 +115,7 @@ impl DependencyService {
     /// 执行依赖图生成
     async fn execute_dependency_graph(
         &self,
+        path: &Path,
         params: DependencyParams,
     ) -> Result<DependencyResult, Box<dyn std::error::Error + Send + Sync>> {
         info!("🔗 开始生成依赖图: {path}", path = params.path);
@@ -126,22 +130,14 @@ impl DependencyService {
             return Err("未指定导出格式。请在参数中设置 format: json|dot|svg|mermaid|ascii。建议：大项目先使用 summarize_graph 获取摘要。".into());
         }
 
-        let path = match crate::utils::paths::resolve_mcp_path(&params.path, "Dependency") {
-            Ok(path) => path,
-            Err(e) => {
-                error!("❌ {}", e);
-                return Err(e.into());
-            }
-        };
-
         // 检查是否为目录
         if path.is_dir() {
             info!("📁 检测到目录路径，分析目录中的所有代码文件");
-            return self.analyze_directory_dependencies(&path, &params).await;
+            return self.analyze_directory_dependencies(path, &params).await;
         }
 
         // 分析单个文件
-        self.analyze_file_dependencies(&path, &params).await
+        self.analyze_file_dependencies(path, &params).await
     }
 
     /// 分析单个文件的依赖关系
@@ -589,16 +585,13 @@ impl DependencyService {
             }
         }
 
-        // 检查路径中是否包含 tests 目录
-        if let Some(path_str) = path.to_str() {
-            return path_str.contains("/tests/")
-                || path_str.contains("/test/")
-                || path_str.contains("/__tests__/")
-                || path_str.contains("/test_")
-                || path_str.contains("/tests_");
-        }
-
-        false
+        // 检查路径中是否包含 tests 目录（统一分隔符以兼容 Windows 路径）
+        let path_str = crate::utils::paths::to_slash(path);
+        path_str.contains("/tests/")
+            || path_str.contains("/test/")
+            || path_str.contains("/__tests__/")
+            || path_str.contains("/test_")
+            || path_str.contains("/tests_")
     }
 
     /// 检查是否为测试目录
@@ -986,7 +979,8 @@ impl GitAiMcpService for DependencyService {
             serde_json::json!({
                 "type": "object",
                 "properties": {
-                    "path": {"type": "string", "description": "要分析的文件或目录路径"},
+                    "path": {"type": "string", "description": "要分析的文件或目录路径（相对路径相对于 repo_path）"},
+                    "repo_path": {"type": "string", "description": "工作目录（可选，别名 cwd；默认为 MCP 服务的运行目录）"},
                     "format": {"type": "string", "enum": ["json", "dot", "svg", "mermaid", "ascii"], "description": "输出格式（默认 ascii）"},
                     "output": {"type": "string", "description": "输出文件路径（可选）"},
                     "depth": {"type": "integer", "description": "分析深度（可选，默认为无限制）"},
@@ -1067,8 +1061,15 @@ impl GitAiMcpService for DependencyService {
                 let params: DependencyParams =
                     serde_json::from_value(arguments).map_err(|e| parse_error("dependency", e))?;
 
+                // 路径在分派前校验，越权访问以 PermissionError 返回
+                let path = super::resolve_target_path(
+                    &self.config,
+                    params.repo_path.as_deref(),
+                    &params.path,
+                    "Dependency",
+                )?;
                 let result = self
-                    .execute_dependency_graph(params)
+                    .execute_dependency_graph(&path, params)
                     .await
                     .map_err(|e| execution_error("dependency", e))?;
 
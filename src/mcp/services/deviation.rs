@@ -1,9 +1,9 @@
 // 提供基于 DevOps Issue 的偏离度分析能力
 
+use crate::deviation::DeviationReport;
 use crate::{config::Config, mcp::*};
 use rmcp::model::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
 
 /// 偏差分析服务
@@ -24,26 +24,12 @@ impl DeviationService {
 pub struct DeviationParams {
     /// Issue ID 列表
     pub issue_ids: Vec<String>,
-    /// 差异内容
+    /// 差异内容；未提供时分析当前仓库的变更（没有时为最后一次提交）
     #[serde(default)]
     pub diff: Option<String>,
-}
-
-/// 偏差分析结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeviationResult {
-    /// 匹配度（0-100）
-    pub match_score: i32,
-    /// 偏离原因
-    pub deviation_reasons: Vec<String>,
-    /// 匹配到的议题 ID
-    pub matched_issues: Vec<String>,
-    /// 未匹配到的议题 ID
-    pub unmatched_issues: Vec<String>,
-    /// 详细信息
-    pub details: HashMap<String, String>,
-    /// 是否需要关注
-    pub needs_attention: bool,
+    /// Coding 空间（项目）ID，覆盖配置中的 space_id
+    #[serde(default)]
+    pub space_id: Option<u64>,
 }
 
 #[async_trait::async_trait]
@@ -59,7 +45,7 @@ impl GitAiMcpService for DeviationService {
     fn tools(&self) -> Vec<Tool> {
         vec![Tool {
             name: "analyze_deviation".into(),
-            description: "分析代码变更与 DevOps Issue 的偏离度".into(),
+            description: "分析代码变更与 DevOps Issue 的偏离度，返回与 `gitai deviation --format json` 相同的结构".into(),
             input_schema: Arc::new(self.get_schema()),
         }]
     }
@@ -97,6 +83,9 @@ impl DeviationService {
                 },
                 "diff": {
                     "type": "string"
+                },
+                "space_id": {
+                    "type": "integer"
                 }
             }),
         );
@@ -104,64 +93,35 @@ impl DeviationService {
         schema
     }
 
-    async fn analyze_deviation(&self, params: DeviationParams) -> McpResult<DeviationResult> {
+    async fn analyze_deviation(&self, params: DeviationParams) -> McpResult<DeviationReport> {
         info!("🔍 分析偏差度: {:?}", params.issue_ids);
 
+        if params.issue_ids.is_empty() {
+            return Err(invalid_parameters_error("缺少 issue_ids 参数"));
+        }
+
         #[cfg(feature = "devops")]
         {
-            // 这里应该是实际的偏差分析逻辑
-            let devops_cfg = self
-                .config
-                .devops
-                .clone()
-                .ok_or_else(|| configuration_error("DevOps 未启用或未配置".to_string()))?;
-
-            // 检查参数
-            if params.issue_ids.is_empty() {
-                return Err(invalid_parameters_error("缺少 issue_ids 参数"));
-            }
-
-            // 调用 DevOps API 获取 issues
-            let client = crate::devops::DevOpsClient::new(devops_cfg.clone());
-            let _issues = client.get_issues(&params.issue_ids).await?;
-
-            // 计算匹配度和偏离度
-            // 这里应该有复杂的分析逻辑
-
-            // 返回结果
-            Ok(DeviationResult {
-                match_score: 85,
-                deviation_reasons: vec!["有部分 Issue 要求未完全满足".to_string()],
-                matched_issues: params.issue_ids.clone(),
-                unmatched_issues: Vec::new(),
-                details: HashMap::new(),
-                needs_attention: false,
-            })
+            let diff = match params.diff {
+                Some(diff) => diff,
+                None => crate::deviation::read_diff(crate::review::DiffSelection::Auto)
+                    .map_err(|e| execution_error("deviation", e))?,
+            };
+            crate::deviation::analyze_issues(
+                &self.config,
+                &params.issue_ids,
+                params.space_id,
+                &diff,
+            )
+            .await
+            .map_err(|e| execution_error("deviation", e))
         }
 
         #[cfg(not(feature = "devops"))]
         {
-            info!("⚠️ DevOps 功能未启用，返回模拟结果");
-
-            // 返回模拟结果
-            let mut details = HashMap::new();
-            details.insert(
-                "note".to_string(),
-                "DevOps 功能未启用，这是一个模拟结果".to_string(),
-            );
-
-            if params.issue_ids.is_empty() {
-                return Err(invalid_parameters_error("缺少 issue_ids 参数"));
-            }
-
-            Ok(DeviationResult {
-                match_score: 50,
-                deviation_reasons: vec!["DevOps 功能未启用，无法进行实际分析".to_string()],
-                matched_issues: params.issue_ids.clone(),
-                unmatched_issues: Vec::new(),
-                details,
-                needs_attention: false,
-            })
+            Err(configuration_error(
+                "DevOps 功能未启用，无法获取 Issue 进行偏离度分析",
+            ))
         }
     }
 }
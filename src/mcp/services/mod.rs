@@ -16,3 +16,217 @@ pub use dependency::DependencyService;
 pub use deviation::DeviationService;
 pub use review::ReviewService;
 pub use scan::ScanService;
+
+use crate::config::Config;
+use crate::mcp::{invalid_parameters_error, permission_error, McpResult};
+use std::path::{Component, Path, PathBuf};
+
+lazy_static::lazy_static! {
+    /// MCP 服务启动时所在的仓库根目录（不在仓库中时为启动目录），未配置 allowed_paths 时的默认根
+    static ref SERVER_ROOT: PathBuf = {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(&cwd)
+            .args(["rev-parse", "--show-toplevel"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+            .unwrap_or(cwd)
+    };
+}
+
+/// 记录服务启动时的仓库根目录；应在任何工具调用切换工作目录之前调用
+pub(crate) fn init_server_root() -> &'static Path {
+    &SERVER_ROOT
+}
+
+/// 工具调用允许访问的根目录：`[mcp.server] allowed_paths`，未配置时为服务启动时的仓库根目录
+fn allowed_roots(config: &Config) -> Vec<String> {
+    match config.mcp.as_ref().map(|mcp| &mcp.server.allowed_paths) {
+        Some(paths) if !paths.is_empty() => paths.clone(),
+        _ => vec![SERVER_ROOT.to_string_lossy().to_string()],
+    }
+}
+
+/// 拒绝包含 `..` 的路径参数，防止跳出允许的目录
+fn reject_traversal(input: &str, service_name: &str) -> McpResult<()> {
+    if Path::new(input)
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(permission_error(format!(
+            "{service_name}: 路径不允许包含 `..`: {input}"
+        )));
+    }
+    Ok(())
+}
+
+/// 路径必须位于允许的根目录之一
+fn ensure_allowed(config: &Config, path: &Path, service_name: &str) -> McpResult<()> {
+    if crate::utils::paths::is_path_allowed(path, &allowed_roots(config)) {
+        Ok(())
+    } else {
+        Err(permission_error(format!(
+            "{service_name}: 路径不在允许的目录中: {}",
+            path.display()
+        )))
+    }
+}
+
+/// 解析工具调用指定的工作目录 `repo_path`：必须是存在的目录且位于允许的目录中，
+/// `require_git` 时还必须位于 Git 仓库内
+pub(crate) fn resolve_repo_path(
+    config: &Config,
+    repo_path: &str,
+    service_name: &str,
+    require_git: bool,
+) -> McpResult<PathBuf> {
+    reject_traversal(repo_path, service_name)?;
+    let path = crate::utils::paths::resolve_mcp_path(repo_path, service_name)
+        .map_err(invalid_parameters_error)?;
+    ensure_allowed(config, &path, service_name)?;
+    if !path.is_dir() {
+        return Err(invalid_parameters_error(format!(
+            "{service_name}: repo_path 不是目录: {}",
+            path.display()
+        )));
+    }
+    if require_git && !is_git_work_tree(&path) {
+        return Err(invalid_parameters_error(format!(
+            "{service_name}: repo_path 不是 Git 仓库: {}",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// 解析工具调用的目标路径：指定 `repo_path` 时相对路径相对于它解析，
+/// 最终路径必须位于允许的目录中
+pub(crate) fn resolve_target_path(
+    config: &Config,
+    repo_path: Option<&str>,
+    path: &str,
+    service_name: &str,
+) -> McpResult<PathBuf> {
+    reject_traversal(path, service_name)?;
+    let base = repo_path
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| resolve_repo_path(config, p, service_name, false))
+        .transpose()?;
+    let target = crate::utils::paths::resolve_mcp_path_in(base.as_deref(), path, service_name)
+        .map_err(invalid_parameters_error)?;
+    ensure_allowed(config, &target, service_name)?;
+    Ok(target)
+}
+
+fn is_git_work_tree(path: &Path) -> bool {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::McpError;
+
+    fn config_with_roots(roots: &[&Path]) -> Config {
+        let mut config = Config::default();
+        if let Some(mcp) = config.mcp.as_mut() {
+            mcp.server.allowed_paths = roots
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+        }
+        config
+    }
+
+    #[test]
+    fn test_resolve_paths_with_allowlist() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+
+        let config = config_with_roots(&[temp_dir.path()]);
+        let repo_str = repo.to_string_lossy().to_string();
+
+        let target = resolve_target_path(&config, Some(&repo_str), "src", "Test").unwrap();
+        assert_eq!(target, repo.join("src"));
+        let outside_str = outside.path().to_string_lossy().to_string();
+        assert!(matches!(
+            resolve_target_path(&config, None, &outside_str, "Test"),
+            Err(McpError::PermissionError(_))
+        ));
+        assert!(matches!(
+            resolve_repo_path(&config, &outside_str, "Test", false),
+            Err(McpError::PermissionError(_))
+        ));
+        assert!(matches!(
+            resolve_target_path(&config, Some(&repo_str), "missing", "Test"),
+            Err(McpError::InvalidParameters(_))
+        ));
+
+        // 普通目录不是 Git 仓库
+        assert!(resolve_repo_path(&config, &repo_str, "Test", false).is_ok());
+        assert!(resolve_repo_path(&config, &repo_str, "Test", true).is_err());
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let config = config_with_roots(&[&repo]);
+        let repo_str = repo.to_string_lossy().to_string();
+
+        for path in ["..", "../repo", "src/../../", "./src/../.."] {
+            assert!(
+                matches!(
+                    resolve_target_path(&config, Some(&repo_str), path, "Test"),
+                    Err(McpError::PermissionError(_))
+                ),
+                "{path} 应被拒绝"
+            );
+        }
+        let dotted = format!("{repo_str}/src/..");
+        assert!(matches!(
+            resolve_repo_path(&config, &dotted, "Test", false),
+            Err(McpError::PermissionError(_))
+        ));
+        assert!(matches!(
+            resolve_target_path(&config, Some(&dotted), "src", "Test"),
+            Err(McpError::PermissionError(_))
+        ));
+
+        // 指向允许目录之外的符号链接同样被拒绝
+        #[cfg(unix)]
+        {
+            let outside = tempfile::TempDir::new().unwrap();
+            std::os::unix::fs::symlink(outside.path(), repo.join("escape")).unwrap();
+            assert!(matches!(
+                resolve_target_path(&config, Some(&repo_str), "escape", "Test"),
+                Err(McpError::PermissionError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_defaults_to_server_root() {
+        let outside = tempfile::TempDir::new().unwrap();
+        let config = config_with_roots(&[]);
+        let outside_str = outside.path().to_string_lossy().to_string();
+        assert!(matches!(
+            resolve_target_path(&config, None, &outside_str, "Test"),
+            Err(McpError::PermissionError(_))
+        ));
+
+        let root = init_server_root().to_string_lossy().to_string();
+        assert!(resolve_target_path(&config, None, &root, "Test").is_ok());
+    }
+}
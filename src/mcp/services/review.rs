@@ -19,19 +19,11 @@ impl ReviewService {
     pub fn new(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
         let default_config = if let Some(mcp_config) = &config.mcp {
             if let Some(review_config) = &mcp_config.services.review {
-                review::ReviewConfig {
-                    language: None,
-                    format: review_config.default_format.clone(),
-                    output: None,
-                    tree_sitter: review_config.default_tree_sitter,
-                    security_scan: review_config.default_security_scan,
-                    scan_tool: None,
-                    block_on_critical: false,
-                    issue_ids: Vec::new(),
-                    space_id: None,
-                    deviation_analysis: false,
-                    full: false,
-                }
+                review::ReviewConfig::builder()
+                    .format(review_config.default_format.clone())
+                    .tree_sitter(review_config.default_tree_sitter)
+                    .security_scan(review_config.default_security_scan)
+                    .build()
             } else {
                 Self::default_review_config()
             }
@@ -46,42 +38,24 @@ impl ReviewService {
     }
 
     fn default_review_config() -> review::ReviewConfig {
-        review::ReviewConfig {
-            language: None,
-            format: "text".to_string(),
-            output: None,
-            tree_sitter: false,
-            security_scan: false,
-            scan_tool: None,
-            block_on_critical: false,
-            issue_ids: Vec::new(),
-            space_id: None,
-            deviation_analysis: false,
-            full: false,
-        }
+        review::ReviewConfig::builder().build()
     }
 
     /// 执行代码评审
     async fn execute_review(
         &self,
+        repo: Option<std::path::PathBuf>,
         params: ReviewParams,
     ) -> Result<ReviewResult, Box<dyn std::error::Error + Send + Sync>> {
-        // 如果指定了 path，则临时切换工作目录
-        let orig_dir = std::env::current_dir().ok();
-        let mut changed_dir = false;
-        if let Some(ref p) = params.path {
-            if !p.is_empty() {
-                if let Err(e) = std::env::set_current_dir(p) {
-                    log::warn!("无法切换到指定路径 '{}': {}", p, e);
-                } else {
-                    changed_dir = true;
-                }
+        // 构建评审配置；指定了仓库路径时在该目录中评审（不切换进程工作目录，并发调用互不影响）
+        let mut review_config = self.default_config.clone();
+        if let Some(repo) = repo {
+            if !repo.is_dir() {
+                return Err(format!("仓库路径 '{}' 不存在或不是目录", repo.display()).into());
             }
+            review_config.repo_path = Some(repo);
         }
 
-        // 构建评审配置
-        let mut review_config = self.default_config.clone();
-
         // 应用参数覆盖
         if let Some(tree_sitter) = params.tree_sitter {
             review_config.tree_sitter = tree_sitter;
@@ -115,16 +89,7 @@ impl ReviewService {
         let tree_sitter_enabled = review_config.tree_sitter;
 
         // 执行评审
-        let exec_res = review::execute_review_with_result(&self.config, review_config).await;
-
-        // 恢复工作目录
-        if changed_dir {
-            if let Some(orig) = orig_dir {
-                let _ = std::env::set_current_dir(orig);
-            }
-        }
-
-        let review_result = exec_res?;
+        let review_result = review::execute_review_with_result(&self.config, review_config).await?;
 
         // 转换为 MCP 使用的 ReviewResult 格式
         let mut details = review_result.details;
@@ -250,6 +215,10 @@ impl crate::mcp::GitAiMcpService for ReviewService {
                             "type": "string",
                             "description": "可选：仓库根路径（当 MCP 服务运行目录不是仓库根时需指定）"
                         },
+                        "repo_path": {
+                            "type": "string",
+                            "description": "可选：仓库路径（别名 cwd，与 path 相同，优先于 path）；必须是 Git 仓库"
+                        },
                         "tree_sitter": {
                             "type": "boolean",
                             "description": "是否启用 Tree-sitter 多语言结构分析 (可选，默认 false)。支持自动检测和分析多种编程语言"
@@ -300,8 +269,16 @@ impl crate::mcp::GitAiMcpService for ReviewService {
                 let params: ReviewParams = serde_json::from_value(arguments)
                     .map_err(|e| crate::mcp::parse_error("review", e))?;
 
+                // 路径在分派前校验，越权访问以 PermissionError 返回
+                let repo = params
+                    .repo_path
+                    .as_deref()
+                    .or(params.path.as_deref())
+                    .filter(|p| !p.trim().is_empty())
+                    .map(|p| super::resolve_repo_path(&self.config, p, "Review", true))
+                    .transpose()?;
                 let result = self
-                    .execute_review(params)
+                    .execute_review(repo, params)
                     .await
                     .map_err(|e| crate::mcp::execution_error("Review", e))?;
 
@@ -318,6 +295,9 @@ impl crate::mcp::GitAiMcpService for ReviewService {
 pub struct ReviewParams {
     /// 可选：指定仓库根路径（当 MCP 服务运行目录不是仓库根时需指定）
     pub path: Option<String>,
+    /// 可选：仓库路径（别名 cwd，与 path 相同，优先于 path）
+    #[serde(default, alias = "cwd")]
+    pub repo_path: Option<String>,
     /// 可选：Coding 空间（项目）ID；如提供将覆盖配置中的 devops.space_id
     pub space_id: Option<u64>,
     /// 是否启用 Tree-sitter 结构分析
@@ -6,7 +6,6 @@ use log::{debug, error, info};
 use rmcp::model::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -25,8 +24,11 @@ impl ScanService {
 /// 扫描服务参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanParams {
-    /// 要扫描的路径
+    /// 要扫描的路径（相对路径相对于 repo_path）
     pub path: String,
+    /// 工作目录（可选，别名 cwd）：让长期运行的 MCP 服务扫描不同的项目
+    #[serde(default, alias = "cwd")]
+    pub repo_path: Option<String>,
     /// 扫描工具 (可选，默认 opengrep；security 等同于 opengrep)
     #[serde(default)]
     pub tool: Option<String>,
@@ -147,7 +149,11 @@ impl ScanService {
             serde_json::json!({
                 "path": {
                     "type": "string",
-                    "description": "要扫描的路径"
+                    "description": "要扫描的路径（相对路径相对于 repo_path）"
+                },
+                "repo_path": {
+                    "type": "string",
+                    "description": "工作目录（可选，别名 cwd；默认为 MCP 服务的运行目录）"
                 },
                 "tool": {
                     "type": "string",
@@ -183,14 +189,20 @@ impl ScanService {
 
             // 配置扫描参数
             let config = &self.config;
-            let path = Path::new(&params.path);
+            let path = super::resolve_target_path(
+                config,
+                params.repo_path.as_deref(),
+                &params.path,
+                "Scan",
+            )?;
             let timeout = params.timeout.unwrap_or(300);
             let lang = params.lang.as_deref();
 
             // 执行扫描
             info!("🔄 运行 OpenGrep 扫描...");
-            let result = crate::scan::run_opengrep_scan(config, path, lang, Some(timeout), true)
-                .map_err(|e| execution_failed_error(format!("Failed to run scan: {}", e)))?;
+            let result =
+                crate::scan::run_opengrep_scan(config, &path, lang, Some(timeout), true)
+                    .map_err(|e| execution_failed_error(format!("Failed to run scan: {}", e)))?;
 
             // 转换扫描结果
             let result = self.convert_real_scan_result(result, &params);
@@ -10,6 +10,7 @@ pub mod storage;
 pub mod trend_analyzer;
 pub mod visualizer;
 
+use crate::evaluation::{QualityMetrics, QualityMetricsOptions};
 use crate::project_insights::ProjectInsights;
 use crate::tree_sitter::StructuralSummary;
 
@@ -68,6 +69,9 @@ pub struct ComplexityMetrics {
     pub high_complexity_functions: usize,
     /// 需要重构的函数数量
     pub functions_needing_refactor: usize,
+    /// 可维护性指数 (0-100, 越高越好)，旧快照中为 0
+    #[serde(default)]
+    pub maintainability_index: f64,
 }
 
 /// API 指标
@@ -201,7 +205,7 @@ impl QualityTracker {
 
         std::fs::create_dir_all(&storage_path)?;
 
-        let current_branch = Self::get_current_branch()?;
+        let current_branch = crate::git::current_branch().unwrap_or_default();
         let snapshots = storage::load_snapshots(&storage_path, &current_branch)?;
 
         Ok(Self {
@@ -212,13 +216,48 @@ impl QualityTracker {
     }
 
     /// 记录新的质量快照
+    ///
+    /// `file_summaries` 为逐文件的分析结果，用于按文件计算可维护性指数
     pub fn record_snapshot(
         &mut self,
         summary: &StructuralSummary,
+        file_summaries: &[StructuralSummary],
         insights: &ProjectInsights,
     ) -> Result<QualitySnapshot, Box<dyn std::error::Error + Send + Sync>> {
-        let commit_hash = Self::get_current_commit()?;
-        let lines_of_code = Self::count_lines_of_code()?;
+        let snapshot = self.measure_snapshot(Path::new("."), summary, file_summaries, insights)?;
+
+        // 保存快照
+        self.snapshots.push(snapshot.clone());
+        storage::save_snapshot(&self.storage_path, &snapshot)?;
+
+        log::info!("记录质量快照: commit {}", snapshot.commit_hash);
+
+        Ok(snapshot)
+    }
+
+    /// 计算 root 下代码的质量快照但不保存，用于与历史快照比较（如 `gitai report`）
+    pub fn measure_snapshot(
+        &self,
+        root: &Path,
+        summary: &StructuralSummary,
+        file_summaries: &[StructuralSummary],
+        insights: &ProjectInsights,
+    ) -> Result<QualitySnapshot, Box<dyn std::error::Error + Send + Sync>> {
+        let commit_hash = crate::git::current_commit().unwrap_or_default();
+        let lines_of_code = Self::count_lines_in(root);
+        let duplicates = crate::evaluation::analyze_duplicates_with(
+            root,
+            &[],
+            &crate::evaluation::DuplicateOptions::default(),
+        );
+        let quality = QualityMetrics::from_summary(
+            summary,
+            &QualityMetricsOptions {
+                lines_of_code: Some(lines_of_code),
+                duplicates: Some(&duplicates),
+                file_summaries,
+            },
+        );
 
         let snapshot = QualitySnapshot {
             timestamp: Utc::now(),
@@ -226,18 +265,11 @@ impl QualityTracker {
             branch: self.current_branch.clone(),
             lines_of_code,
             architecture_metrics: Self::calculate_architecture_metrics(insights),
-            complexity_metrics: Self::calculate_complexity_metrics(summary, insights),
+            complexity_metrics: Self::calculate_complexity_metrics(summary, insights, &quality),
             api_metrics: Self::calculate_api_metrics(insights, self.snapshots.last()),
-            technical_debt: Self::calculate_technical_debt(insights),
+            technical_debt: Self::calculate_technical_debt(insights, &quality),
             tags: Vec::new(),
         };
-
-        // 保存快照
-        self.snapshots.push(snapshot.clone());
-        storage::save_snapshot(&self.storage_path, &snapshot)?;
-
-        log::info!("记录质量快照: commit {}", snapshot.commit_hash);
-
         Ok(snapshot)
     }
 
@@ -261,55 +293,28 @@ impl QualityTracker {
         let report = visualizer.generate_report(&analysis, &self.snapshots)?;
 
         if let Some(path) = output_path {
-            std::fs::write(path, &report)?;
+            crate::utils::atomic_write::write_atomic(path, &report)?;
             log::info!("质量趋势报告已保存到: {:?}", path);
         }
 
         Ok(report)
     }
 
-    /// 获取当前分支
-    fn get_current_branch() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let output = std::process::Command::new("git")
-            .args(["branch", "--show-current"])
-            .output()?;
-
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    }
-
-    /// 获取当前 commit hash
-    fn get_current_commit() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let output = std::process::Command::new("git")
-            .args(["rev-parse", "HEAD"])
-            .output()?;
-
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    }
-
-    /// 统计代码行数
-    fn count_lines_of_code() -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
-        // 简化实现，实际可以使用 tokei 或类似工具
-        let output = std::process::Command::new("find")
-            .args([
-                ".", "-type", "f", "-name", "*.rs", "-o", "-name", "*.java", "-o", "-name", "*.py",
-                "-o", "-name", "*.js", "-o", "-name", "*.ts",
-            ])
-            .arg("-exec")
-            .arg("wc")
-            .arg("-l")
-            .arg("{}")
-            .arg("+")
-            .output()?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let lines = output_str
-            .lines()
-            .last()
-            .and_then(|l| l.split_whitespace().next())
-            .and_then(|n| n.parse().ok())
-            .unwrap_or(0);
-
-        Ok(lines)
+    /// 统计 root 下源码文件的行数（跳过 target、.git 等目录，不依赖 find/wc 等 unix 工具）
+    fn count_lines_in(root: &Path) -> usize {
+        const EXTENSIONS: &[&str] = &["rs", "java", "py", "js", "ts"];
+        let mut files = Vec::new();
+        crate::architectural_impact::graph_export::collect_files(root, &mut files);
+        files
+            .iter()
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| EXTENSIONS.contains(&e))
+            })
+            .filter_map(|p| std::fs::read(p).ok())
+            .map(|bytes| count_newlines(&bytes))
+            .sum()
     }
 
     /// 计算架构指标
@@ -332,6 +337,7 @@ impl QualityTracker {
     fn calculate_complexity_metrics(
         summary: &StructuralSummary,
         insights: &ProjectInsights,
+        quality: &QualityMetrics,
     ) -> ComplexityMetrics {
         let function_lengths: Vec<usize> = summary
             .functions
@@ -348,8 +354,8 @@ impl QualityTracker {
         let max_length = function_lengths.iter().max().copied().unwrap_or(0);
 
         ComplexityMetrics {
-            avg_cyclomatic_complexity: 5.0, // 简化实现
-            max_cyclomatic_complexity: 15,  // 简化实现
+            avg_cyclomatic_complexity: quality.avg_cyclomatic_complexity,
+            max_cyclomatic_complexity: quality.max_cyclomatic_complexity as u32,
             avg_function_length: avg_length,
             max_function_length: max_length,
             high_complexity_functions: insights.quality_hotspots.complexity_hotspots.len(),
@@ -359,6 +365,7 @@ impl QualityTracker {
                 .iter()
                 .filter(|h| h.complexity_score > 30)
                 .count(),
+            maintainability_index: quality.maintainability_index,
         }
     }
 
@@ -393,13 +400,16 @@ impl QualityTracker {
     }
 
     /// 计算技术债务
-    fn calculate_technical_debt(insights: &ProjectInsights) -> TechnicalDebtMetrics {
+    fn calculate_technical_debt(
+        insights: &ProjectInsights,
+        quality: &QualityMetrics,
+    ) -> TechnicalDebtMetrics {
         TechnicalDebtMetrics {
             debt_score: insights
                 .quality_hotspots
                 .maintenance_burden
                 .technical_debt_score,
-            duplication_rate: 0.0,       // 需要实现
+            duplication_rate: quality.duplication_percentage,
             comment_coverage: 0.0,       // 需要从 summary 计算
             test_coverage_estimate: 0.0, // 需要实现
             todo_count: 0,               // 需要扫描注释
@@ -462,6 +472,16 @@ impl QualityTracker {
     }
 }
 
+/// 与 `wc -l` 一致的行数（换行符个数，末行无换行时补计一行）
+fn count_newlines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    if bytes.last().is_some_and(|&b| b != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -496,6 +516,25 @@ mod tests {
         assert!(changes.get("complexity_change").unwrap() > &0.0); // complexity increased from 30 to 35
     }
 
+    #[test]
+    fn test_count_lines_in_skips_build_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src").join("nested")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(
+            dir.path().join("src").join("lib.rs"),
+            "fn a() {}\nfn b() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("src").join("nested").join("x.py"), "a = 1").unwrap();
+        std::fs::write(dir.path().join("src").join("notes.md"), "# a\n# b\n").unwrap();
+        std::fs::write(dir.path().join("target").join("gen.rs"), "fn c() {}\n").unwrap();
+
+        assert_eq!(QualityTracker::count_lines_in(dir.path()), 3);
+        assert_eq!(count_newlines(b""), 0);
+        assert_eq!(count_newlines(b"a\r\nb"), 2);
+    }
+
     fn create_test_snapshot(debt_score: f64, complexity: f64) -> QualitySnapshot {
         QualitySnapshot {
             timestamp: Utc::now(),
@@ -517,6 +556,7 @@ mod tests {
                 max_function_length: 100,
                 high_complexity_functions: 5,
                 functions_needing_refactor: 2,
+                maintainability_index: 80.0,
             },
             api_metrics: ApiMetrics {
                 public_api_count: 50,
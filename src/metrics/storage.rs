@@ -91,15 +91,15 @@ pub fn save_all_snapshots(
         std::fs::create_dir_all(parent)?;
     }
 
-    let file = File::create(&file_path)?;
-    let mut writer = BufWriter::new(file);
-
-    for snapshot in snapshots {
-        let json_line = serde_json::to_string(snapshot)?;
-        writeln!(writer, "{json_line}")?;
-    }
-
-    writer.flush()?;
+    // 整体重写快照文件，原子写入避免中断时丢失历史数据
+    crate::utils::atomic_write::write_atomic_with(&file_path, |file| {
+        let mut writer = BufWriter::new(file);
+        for snapshot in snapshots {
+            let json_line = serde_json::to_string(snapshot)?;
+            writeln!(writer, "{json_line}")?;
+        }
+        writer.flush()
+    })?;
 
     log::info!("保存 {} 个快照到: {:?}", snapshots.len(), file_path);
 
@@ -143,7 +143,14 @@ pub fn export_to_csv(
     snapshots: &[QualitySnapshot],
     output_path: &Path,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let mut writer = csv::Writer::from_path(output_path)?;
+    crate::utils::atomic_write::write_atomic_with(output_path, |file| write_csv(snapshots, file))?;
+    log::info!("导出 {} 个快照到 CSV: {:?}", snapshots.len(), output_path);
+
+    Ok(())
+}
+
+fn write_csv(snapshots: &[QualitySnapshot], file: &mut File) -> std::io::Result<()> {
+    let mut writer = csv::Writer::from_writer(file);
 
     // 写入标题行
     writer.write_record([
@@ -156,6 +163,7 @@ pub fn export_to_csv(
         "coupling_score",
         "avg_complexity",
         "max_complexity",
+        "maintainability_index",
         "public_api_count",
         "api_stability_score",
         "debt_score",
@@ -183,6 +191,7 @@ pub fn export_to_csv(
                 .complexity_metrics
                 .max_cyclomatic_complexity
                 .to_string(),
+            format!("{:.2}", snapshot.complexity_metrics.maintainability_index),
             snapshot.api_metrics.public_api_count.to_string(),
             format!("{:.2}", snapshot.api_metrics.stability_score),
             format!("{:.2}", snapshot.technical_debt.debt_score),
@@ -190,10 +199,7 @@ pub fn export_to_csv(
         ])?;
     }
 
-    writer.flush()?;
-    log::info!("导出 {} 个快照到 CSV: {:?}", snapshots.len(), output_path);
-
-    Ok(())
+    writer.flush()
 }
 
 /// 合并多个分支的快照
@@ -350,6 +356,7 @@ mod tests {
                 max_function_length: 100,
                 high_complexity_functions: 5,
                 functions_needing_refactor: 2,
+                maintainability_index: 80.0,
             },
             api_metrics: super::super::ApiMetrics {
                 public_api_count: 50,
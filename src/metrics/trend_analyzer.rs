@@ -509,6 +509,7 @@ mod tests {
                 max_function_length: 100,
                 high_complexity_functions: 5,
                 functions_needing_refactor: 2,
+                maintainability_index: 80.0,
             },
             api_metrics: ApiMetrics {
                 public_api_count: 50,
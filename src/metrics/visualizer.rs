@@ -430,17 +430,17 @@ impl TrendVisualizer {
 
         writeln!(
             report,
-            "| 日期 | Commit | LOC | 模块数 | 循环依赖 | 债务分 | 复杂度 | API稳定性 |"
+            "| 日期 | Commit | LOC | 模块数 | 循环依赖 | 债务分 | 复杂度 | 可维护性 | API稳定性 |"
         )?;
         writeln!(
             report,
-            "|------|--------|-----|--------|----------|--------|--------|-----------|"
+            "|------|--------|-----|--------|----------|--------|--------|----------|-----------|"
         )?;
 
         for snapshot in snapshots.iter().rev().take(20) {
             writeln!(
                 report,
-                "| {} | {} | {} | {} | {} | {:.1} | {:.1} | {:.1}% |",
+                "| {} | {} | {} | {} | {} | {:.1} | {:.1} | {:.1} | {:.1}% |",
                 snapshot.timestamp.format("%m-%d"),
                 &snapshot.commit_hash[..7],
                 snapshot.lines_of_code,
@@ -448,6 +448,7 @@ impl TrendVisualizer {
                 snapshot.architecture_metrics.circular_dependencies,
                 snapshot.technical_debt.debt_score,
                 snapshot.complexity_metrics.avg_cyclomatic_complexity,
+                snapshot.complexity_metrics.maintainability_index,
                 snapshot.api_metrics.stability_score,
             )?;
         }
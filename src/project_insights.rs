@@ -582,16 +582,67 @@ impl InsightsGenerator {
             })
             .collect();
 
+        // 依据注解识别弃用 API（#[deprecated] / @Deprecated / @deprecated）
+        let deprecation_reason = |annotations: &[String]| {
+            annotations
+                .iter()
+                .find(|a| crate::tree_sitter::is_deprecation_annotation(a))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let deprecated_functions =
+            summary
+                .functions
+                .iter()
+                .filter(|f| f.is_deprecated())
+                .map(|f| DeprecatedApi {
+                    api: PublicApi {
+                        name: f.name.clone(),
+                        api_type: ApiType::Function,
+                        signature: format!("{}({:?})", f.name, f.parameters),
+                        documentation: None,
+                        usage_count: 0,
+                    },
+                    deprecation_reason: deprecation_reason(&f.annotations),
+                    replacement: None,
+                });
+        let deprecated_classes = summary
+            .classes
+            .iter()
+            .filter(|c| c.is_deprecated())
+            .map(|c| DeprecatedApi {
+                api: PublicApi {
+                    name: c.name.clone(),
+                    api_type: ApiType::Class,
+                    signature: c.name.clone(),
+                    documentation: None,
+                    usage_count: 0,
+                },
+                deprecation_reason: deprecation_reason(&c.annotations),
+                replacement: None,
+            });
+        let deprecated_apis: Vec<DeprecatedApi> =
+            deprecated_functions.chain(deprecated_classes).collect();
+
+        let stable_apis = public_apis
+            .iter()
+            .filter(|api| !deprecated_apis.iter().any(|d| d.api.name == api.name))
+            .count();
+        let total = stable_apis + deprecated_apis.len();
         let stability = ApiStability {
-            stable_apis: public_apis.len(),
+            stable_apis,
             unstable_apis: 0,
-            deprecated_apis: 0,
-            stability_score: 1.0,
+            deprecated_apis: deprecated_apis.len(),
+            stability_score: if total == 0 {
+                1.0
+            } else {
+                stable_apis as f64 / total as f64
+            },
         };
 
         ApiSurface {
             public_apis,
-            deprecated_apis: vec![],
+            deprecated_apis,
             api_stability: stability,
         }
     }
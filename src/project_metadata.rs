@@ -0,0 +1,258 @@
+// 项目元数据模块
+// 为评审与提交信息提示词提供主要语言、使用的框架与本次变更涉及的目录
+
+use crate::tree_sitter::{StructuralSummary, SupportedLanguage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// 变更目录最多列出的数量
+const MAX_CHANGED_AREAS: usize = 5;
+
+/// 框架识别规则：(清单文件, 依赖名, 框架名)
+const FRAMEWORK_MARKERS: &[(&str, &str, &str)] = &[
+    ("Cargo.toml", "tokio", "Tokio"),
+    ("Cargo.toml", "axum", "Axum"),
+    ("Cargo.toml", "actix-web", "Actix Web"),
+    ("Cargo.toml", "rocket", "Rocket"),
+    ("Cargo.toml", "warp", "Warp"),
+    ("Cargo.toml", "tauri", "Tauri"),
+    ("Cargo.toml", "bevy", "Bevy"),
+    ("package.json", "react", "React"),
+    ("package.json", "vue", "Vue"),
+    ("package.json", "next", "Next.js"),
+    ("package.json", "@angular/core", "Angular"),
+    ("package.json", "svelte", "Svelte"),
+    ("package.json", "express", "Express"),
+    ("package.json", "@nestjs/core", "NestJS"),
+    ("go.mod", "github.com/gin-gonic/gin", "Gin"),
+    ("go.mod", "github.com/labstack/echo", "Echo"),
+    ("go.mod", "github.com/gofiber/fiber", "Fiber"),
+    ("requirements.txt", "django", "Django"),
+    ("requirements.txt", "flask", "Flask"),
+    ("requirements.txt", "fastapi", "FastAPI"),
+    ("pyproject.toml", "django", "Django"),
+    ("pyproject.toml", "flask", "Flask"),
+    ("pyproject.toml", "fastapi", "FastAPI"),
+    ("pom.xml", "spring-boot", "Spring Boot"),
+    ("build.gradle", "spring-boot", "Spring Boot"),
+];
+
+/// 项目元数据
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectMetadata {
+    /// 主要语言（如 `rust`），无法识别时为 None
+    pub primary_language: Option<String>,
+    /// 根据依赖清单识别的框架
+    pub frameworks: Vec<String>,
+    /// 本次变更涉及的目录及文件数，按文件数降序
+    pub changed_areas: Vec<(String, usize)>,
+}
+
+impl ProjectMetadata {
+    /// 从 diff、结构分析结果与仓库根目录收集元数据
+    pub fn collect(diff: &str, summary: Option<&StructuralSummary>, repo_root: &Path) -> Self {
+        let files = changed_files(diff);
+        Self {
+            primary_language: summary
+                .and_then(language_from_summary)
+                .or_else(|| language_from_files(&files)),
+            frameworks: detect_frameworks(repo_root),
+            changed_areas: changed_areas(&files),
+        }
+    }
+
+    /// 以 dir 所在 Git 仓库的根目录（不在仓库中时为 dir 本身）收集元数据
+    pub fn for_repo(diff: &str, summary: Option<&StructuralSummary>, dir: &Path) -> Self {
+        let root = crate::git::run_git_in(
+            dir,
+            &["rev-parse".to_string(), "--show-toplevel".to_string()],
+        )
+        .map(|out| PathBuf::from(out.trim()))
+        .unwrap_or_else(|_| dir.to_path_buf());
+        Self::collect(diff, summary, &root)
+    }
+
+    /// 提示词变量：(变量名, 值)
+    pub fn prompt_variables(&self) -> Vec<(&'static str, String)> {
+        let frameworks = if self.frameworks.is_empty() {
+            "未识别".to_string()
+        } else {
+            self.frameworks.join(", ")
+        };
+        let areas = if self.changed_areas.is_empty() {
+            "未知".to_string()
+        } else {
+            self.changed_areas
+                .iter()
+                .map(|(area, count)| format!("{area} ({count} 个文件)"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        vec![
+            (
+                "primary_language",
+                self.primary_language
+                    .clone()
+                    .unwrap_or_else(|| "未知".to_string()),
+            ),
+            ("frameworks", frameworks),
+            ("changed_areas", areas),
+        ]
+    }
+}
+
+/// diff 中变更的文件路径（按出现顺序去重）
+fn changed_files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in diff.lines() {
+        let Some(rest) = line.strip_prefix("diff --git ") else {
+            continue;
+        };
+        if let Some(path) = rest.split_whitespace().nth(1) {
+            let path = path.strip_prefix("b/").unwrap_or(path).to_string();
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+fn language_from_summary(summary: &StructuralSummary) -> Option<String> {
+    // 多语言模式取涉及文件最多的语言
+    let best = summary
+        .language_summaries
+        .iter()
+        .max_by(|a, b| a.1.file_count.cmp(&b.1.file_count).then(b.0.cmp(a.0)))
+        .map(|(lang, _)| lang.clone());
+    best.or_else(|| Some(summary.language.clone()))
+        .filter(|lang| !lang.is_empty() && lang != "unknown" && lang != "multi-language")
+}
+
+fn language_from_files(files: &[String]) -> Option<String> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    for file in files {
+        let language = Path::new(file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(SupportedLanguage::from_extension);
+        if let Some(language) = language {
+            *counts.entry(language.name()).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(lang, _)| lang.to_string())
+}
+
+fn detect_frameworks(repo_root: &Path) -> Vec<String> {
+    let mut manifests: HashMap<&str, Option<String>> = HashMap::new();
+    let mut frameworks: Vec<String> = Vec::new();
+    for (manifest, dependency, framework) in FRAMEWORK_MARKERS {
+        let content = manifests
+            .entry(manifest)
+            .or_insert_with(|| std::fs::read_to_string(repo_root.join(manifest)).ok());
+        let Some(content) = content else {
+            continue;
+        };
+        if declares_dependency(manifest, content, dependency)
+            && !frameworks.iter().any(|f| f == framework)
+        {
+            frameworks.push(framework.to_string());
+        }
+    }
+    frameworks
+}
+
+/// 清单是否声明了依赖；按清单格式匹配依赖名，避免子串误判
+fn declares_dependency(manifest: &str, content: &str, dependency: &str) -> bool {
+    match manifest {
+        "package.json" => content.contains(&format!("\"{dependency}\"")),
+        "Cargo.toml" | "requirements.txt" | "pyproject.toml" => content.lines().any(|line| {
+            let line = line.trim_start().trim_start_matches('"').to_lowercase();
+            line.strip_prefix(dependency).is_some_and(|rest| {
+                rest.is_empty()
+                    || rest.starts_with(|c: char| {
+                        c.is_whitespace() || matches!(c, '=' | '.' | '<' | '>' | '~' | '[' | '"')
+                    })
+            })
+        }),
+        _ => content.contains(dependency),
+    }
+}
+
+fn changed_areas(files: &[String]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        let area = match Path::new(file).parent().and_then(|p| p.to_str()) {
+            Some(parent) if !parent.is_empty() => parent.to_string(),
+            _ => "(根目录)".to_string(),
+        };
+        *counts.entry(area).or_default() += 1;
+    }
+    let mut areas: Vec<(String, usize)> = counts.into_iter().collect();
+    areas.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    areas.truncate(MAX_CHANGED_AREAS);
+    areas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/review/a.rs b/src/review/a.rs\n+x\n\
+                        diff --git a/src/review/b.rs b/src/review/b.rs\n+y\n\
+                        diff --git a/web/app.ts b/web/app.ts\n+z\n\
+                        diff --git a/README.md b/README.md\n+w\n";
+
+    #[test]
+    fn test_collect_from_diff_and_manifests() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("Cargo.toml"),
+            "[dependencies]\ntokio = { version = \"1\" }\naxum-extra = \"0.9\"\nserde = \"1\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18", "react-dom": "^18"}}"#,
+        )
+        .unwrap();
+
+        let meta = ProjectMetadata::collect(DIFF, None, root.path());
+        assert_eq!(meta.primary_language.as_deref(), Some("rust"));
+        assert_eq!(meta.frameworks, vec!["Tokio", "React"]);
+        assert_eq!(meta.changed_areas[0], ("src/review".to_string(), 2));
+        assert_eq!(meta.changed_areas.len(), 3);
+
+        let vars = meta.prompt_variables();
+        assert_eq!(vars[1], ("frameworks", "Tokio, React".to_string()));
+        assert!(vars[2].1.starts_with("src/review (2 个文件)"));
+    }
+
+    #[test]
+    fn test_summary_language_takes_precedence() {
+        let mut summary = StructuralSummary {
+            language: "multi-language".to_string(),
+            ..Default::default()
+        };
+        for (lang, count) in [("go", 3), ("python", 1)] {
+            summary.language_summaries.insert(
+                lang.to_string(),
+                crate::tree_sitter::LanguageSummary {
+                    language: lang.to_string(),
+                    file_count: count,
+                    ..Default::default()
+                },
+            );
+        }
+        let root = tempfile::tempdir().unwrap();
+        let meta = ProjectMetadata::collect(DIFF, Some(&summary), root.path());
+        assert_eq!(meta.primary_language.as_deref(), Some("go"));
+        assert!(meta.frameworks.is_empty());
+
+        let vars = ProjectMetadata::default().prompt_variables();
+        assert_eq!(vars[0], ("primary_language", "未知".to_string()));
+    }
+}
@@ -1,11 +1,192 @@
 use crate::config::Config;
-use std::collections::HashMap;
+use crate::project_metadata::ProjectMetadata;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// 内置模板的占位符约定：(模板名, 必需占位符, 可选占位符)
+///
+/// 与 `ai` 模块中构造 `PromptContext` 时设置的变量保持一致
+pub const TEMPLATE_SPECS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "review",
+        &["diff"],
+        &[
+            "tree_sitter_summary",
+            "security_scan_results",
+            "devops_issue_context",
+            "dependency_insights",
+            "primary_language",
+            "frameworks",
+            "changed_areas",
+        ],
+    ),
+    (
+        "commit",
+        &["diff"],
+        &[
+            "architecture_impact",
+            "primary_language",
+            "frameworks",
+            "changed_areas",
+        ],
+    ),
+];
+
+/// 随程序发布的内置模板：(模板名, 内容)
+pub const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("commit", include_str!("../assets/prompts/commit.md")),
+    (
+        "commit-angular",
+        include_str!("../assets/prompts/commit-angular.md"),
+    ),
+    (
+        "commit-gitmoji",
+        include_str!("../assets/prompts/commit-gitmoji.md"),
+    ),
+    (
+        "commit-plain",
+        include_str!("../assets/prompts/commit-plain.md"),
+    ),
+    ("review", include_str!("../assets/prompts/review.md")),
+];
+
+/// 提交信息风格模板的文件名前缀：`commit-<风格>.md` 与默认的 `commit.md` 使用相同的占位符约定
+pub const COMMIT_TEMPLATE_PREFIX: &str = "commit-";
+
+/// 将提交信息风格（`--template gitmoji`）解析为模板名（`commit-gitmoji`）；
+/// `commit`、`default` 与空值对应默认模板，已带前缀的名称保持不变
+pub fn commit_template_name(style: &str) -> String {
+    let style = style.trim().trim_end_matches(".md");
+    match style {
+        "" | "commit" | "default" | "conventional" => "commit".to_string(),
+        s if s.starts_with(COMMIT_TEMPLATE_PREFIX) => s.to_string(),
+        s => format!("{COMMIT_TEMPLATE_PREFIX}{s}"),
+    }
+}
+
+/// 模板名对应的占位符约定；`commit-*` 风格模板沿用 `commit` 的约定
+fn template_spec(
+    name: &str,
+) -> Option<&'static (
+    &'static str,
+    &'static [&'static str],
+    &'static [&'static str],
+)> {
+    let name = if name.starts_with(COMMIT_TEMPLATE_PREFIX) {
+        "commit"
+    } else {
+        name
+    };
+    TEMPLATE_SPECS.iter().find(|(n, _, _)| *n == name)
+}
+
+/// 用户提示词目录中记录已安装模板摘要的文件，用于区分用户修改过的模板
+const MANIFEST_FILE: &str = ".gitai-prompts.json";
+
+lazy_static::lazy_static! {
+    // 已加载且校验通过的模板，按文件路径缓存；文件修改时间变化时重新加载（长时间运行的 MCP 服务无需重启）
+    static ref TEMPLATE_CACHE: RwLock<HashMap<PathBuf, CachedTemplate>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Debug, Clone)]
+struct CachedTemplate {
+    modified: Option<SystemTime>,
+    content: String,
+}
 
 /// 提示词模板管理器
 pub struct PromptManager {
     config: Config,
+    prompts_dir: Option<PathBuf>,
+}
+
+/// 模板占位符校验发现的问题
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateProblems {
+    /// 模板中出现但上下文不会提供的占位符
+    pub unknown: Vec<String>,
+    /// 模板中缺少的必需占位符
+    pub missing: Vec<String>,
+}
+
+impl std::fmt::Display for TemplateProblems {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wrap = |names: &[String]| {
+            names
+                .iter()
+                .map(|n| format!("{{{n}}}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut parts = Vec::new();
+        if !self.missing.is_empty() {
+            parts.push(format!("缺少必需占位符 {}", wrap(&self.missing)));
+        }
+        if !self.unknown.is_empty() {
+            parts.push(format!("未知占位符 {}", wrap(&self.unknown)));
+        }
+        write!(f, "{}", parts.join("；"))
+    }
+}
+
+/// 单个模板的校验结果（`gitai prompts validate`）
+#[derive(Debug, Clone)]
+pub struct TemplateReport {
+    pub name: String,
+    /// 实际生效的模板文件，未找到时为 None
+    pub path: Option<PathBuf>,
+    /// 是否为登记了占位符约定的内置模板；未登记的模板不做占位符检查
+    pub known: bool,
+    pub result: Result<(), String>,
+}
+
+/// 提取模板中的 `{name}` 占位符（去重并排序）
+pub fn placeholders(content: &str) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = content;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        let valid_start = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+        if valid_start && rest[len..].starts_with('}') {
+            names.insert(name.to_string());
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// 按内置约定校验模板占位符（`commit-*` 按 `commit` 的约定）；未登记的模板名直接通过
+pub fn validate_template(name: &str, content: &str) -> Result<(), TemplateProblems> {
+    let Some((_, required, optional)) = template_spec(name) else {
+        return Ok(());
+    };
+    let found = placeholders(content);
+    let problems = TemplateProblems {
+        unknown: found
+            .iter()
+            .filter(|p| !required.contains(&p.as_str()) && !optional.contains(&p.as_str()))
+            .cloned()
+            .collect(),
+        missing: required
+            .iter()
+            .filter(|r| !found.iter().any(|p| p == *r))
+            .map(|r| r.to_string())
+            .collect(),
+    };
+    if problems.unknown.is_empty() && problems.missing.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
 }
 
 /// 提示词渲染上下文
@@ -29,6 +210,14 @@ impl PromptContext {
     pub fn set_variable(&mut self, key: &str, value: &str) {
         self.variables.insert(key.to_string(), value.to_string());
     }
+
+    /// 注入项目元数据变量：`primary_language`、`frameworks`、`changed_areas`
+    pub fn with_project_metadata(mut self, metadata: &ProjectMetadata) -> Self {
+        for (key, value) in metadata.prompt_variables() {
+            self.set_variable(key, &value);
+        }
+        self
+    }
 }
 
 impl Default for PromptContext {
@@ -39,11 +228,23 @@ impl Default for PromptContext {
 
 impl PromptManager {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            prompts_dir: None,
+        }
+    }
+
+    /// 指定用户提示词目录（默认 `~/.config/gitai/prompts`）
+    pub fn with_prompts_dir(mut self, dir: PathBuf) -> Self {
+        self.prompts_dir = Some(dir);
+        self
     }
 
     /// 获取提示词模板目录路径
-    fn get_prompts_dir(&self) -> PathBuf {
+    pub fn get_prompts_dir(&self) -> PathBuf {
+        if let Some(ref dir) = self.prompts_dir {
+            return dir.clone();
+        }
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".config")
@@ -77,19 +278,130 @@ impl PromptManager {
     }
 
     /// 加载提示词模板
+    ///
+    /// 首次加载时校验占位符，不合法则报错；文件修改后自动重新加载，
+    /// 若修改后的版本校验失败则记录警告并继续使用上次的有效版本
     pub fn load_template(
         &self,
         template_name: &str,
         _language: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let template_path = self
-            .get_template_path(template_name)
-            .ok_or_else(|| format!("未找到提示词模板: {template_name}"))?;
+        let Some(template_path) = self.get_template_path(template_name) else {
+            // 未执行 `gitai prompts init` 时回退到随程序发布的内置模板
+            return BUILTIN_TEMPLATES
+                .iter()
+                .find(|(name, _)| *name == template_name)
+                .map(|(_, content)| content.to_string())
+                .ok_or_else(|| format!("未找到提示词模板: {template_name}").into());
+        };
+        let modified = fs::metadata(&template_path).and_then(|m| m.modified()).ok();
+
+        let cached = TEMPLATE_CACHE
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(&template_path).cloned());
+        if let Some(ref cached) = cached {
+            if modified.is_some() && cached.modified == modified {
+                return Ok(cached.content.clone());
+            }
+        }
 
         log::debug!("加载提示词模板: {template_path:?}");
+        let content = fs::read_to_string(&template_path)
+            .map_err(|e| format!("读取提示词模板失败: {} - {e}", template_path.display()))?;
 
-        fs::read_to_string(&template_path)
-            .map_err(|e| format!("读取提示词模板失败: {} - {e}", template_path.display()).into())
+        if let Err(problems) = validate_template(template_name, &content) {
+            return match cached {
+                Some(previous) => {
+                    log::warn!(
+                        "提示词模板 {} 修改后校验失败，继续使用上次的有效版本: {problems}",
+                        template_path.display()
+                    );
+                    Ok(previous.content)
+                }
+                None => Err(format!(
+                    "提示词模板 {template_name} 无效（{}）: {problems}",
+                    template_path.display()
+                )
+                .into()),
+            };
+        }
+
+        if let Ok(mut cache) = TEMPLATE_CACHE.write() {
+            cache.insert(
+                template_path,
+                CachedTemplate {
+                    modified,
+                    content: content.clone(),
+                },
+            );
+        }
+        Ok(content)
+    }
+
+    /// 校验所有生效的模板：内置模板与用户目录中的模板
+    pub fn validate_all(&self) -> Vec<TemplateReport> {
+        let mut names: BTreeSet<String> = TEMPLATE_SPECS
+            .iter()
+            .map(|(name, _, _)| name.to_string())
+            .chain(BUILTIN_TEMPLATES.iter().map(|(name, _)| name.to_string()))
+            .collect();
+        names.extend(template_names(&self.get_prompts_dir()));
+
+        names
+            .into_iter()
+            .map(|name| {
+                let known = template_spec(&name).is_some();
+                let path = self.get_template_path(&name);
+                let builtin = BUILTIN_TEMPLATES.iter().find(|(n, _)| *n == name);
+                let result = match (&path, builtin) {
+                    (None, None) => Err("未找到模板文件".to_string()),
+                    (None, Some((_, content))) => {
+                        validate_template(&name, content).map_err(|p| p.to_string())
+                    }
+                    (Some(path), _) => fs::read_to_string(path)
+                        .map_err(|e| format!("读取失败: {e}"))
+                        .and_then(|content| {
+                            validate_template(&name, &content).map_err(|p| p.to_string())
+                        }),
+                };
+                TemplateReport {
+                    name,
+                    path,
+                    known,
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    /// 可用的提交信息模板：内置模板与用户目录中的 `commit.md`、`commit-*.md`
+    pub fn commit_templates(&self) -> Vec<String> {
+        let mut names: BTreeSet<String> = BUILTIN_TEMPLATES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(template_names(&self.get_prompts_dir()))
+            .collect();
+        names.retain(|name| name == "commit" || name.starts_with(COMMIT_TEMPLATE_PREFIX));
+        names.into_iter().collect()
+    }
+
+    /// 解析并加载提交信息风格模板，返回模板名；模板不存在或不合法时报错并列出可用模板
+    pub fn resolve_commit_template(
+        &self,
+        style: &str,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let name = commit_template_name(style);
+        match self.load_template(&name, None) {
+            Ok(_) => Ok(name),
+            Err(e) if self.get_template_path(&name).is_some() => Err(e),
+            Err(_) => Err(format!(
+                "未找到提交信息模板 {name}（可用：{}）；自定义模板请放在 {}/{name}.md",
+                self.commit_templates().join("、"),
+                self.get_prompts_dir().display()
+            )
+            .into()),
+        }
     }
 
     /// 渲染提示词模板
@@ -133,6 +445,143 @@ impl PromptManager {
     }
 }
 
+/// 单个模板的更新结果（`gitai prompts update`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateUpdate {
+    /// 用户目录中不存在，已新增
+    Added,
+    /// 用户未修改过，已更新为最新版本
+    Updated,
+    /// 已是最新版本
+    UpToDate,
+    /// 用户修改过，保留原文件，最新版本写入 `<name>.md.new`
+    Conflict(PathBuf),
+    /// 用户修改过，按 `--force` 覆盖，原文件备份为 `<name>.md.bak`
+    Overwritten(PathBuf),
+}
+
+/// 将最新模板合并到用户提示词目录，保留用户修改过的模板
+///
+/// `templates` 为 (模板名, 最新内容)。用户未修改过的模板（与上次安装时的摘要或任一内置版本一致）
+/// 直接更新；修改过的模板默认跳过并把最新版本写到旁边，`force` 时备份后覆盖
+pub fn merge_templates(
+    dir: &Path,
+    templates: &[(String, String)],
+    force: bool,
+) -> std::io::Result<Vec<(String, TemplateUpdate)>> {
+    fs::create_dir_all(dir)?;
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let mut manifest: HashMap<String, String> = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let mut results = Vec::new();
+    for (name, latest) in templates {
+        let path = dir.join(format!("{name}.md"));
+        let latest_digest = digest(latest);
+        let status = match fs::read_to_string(&path) {
+            Err(_) => {
+                fs::write(&path, latest)?;
+                TemplateUpdate::Added
+            }
+            Ok(current) if current == *latest => TemplateUpdate::UpToDate,
+            Ok(current) => {
+                let current_digest = digest(&current);
+                let unmodified = manifest.get(name) == Some(&current_digest)
+                    || BUILTIN_TEMPLATES
+                        .iter()
+                        .any(|(n, content)| n == name && *content == current);
+                if unmodified {
+                    fs::write(&path, latest)?;
+                    TemplateUpdate::Updated
+                } else if force {
+                    let backup = dir.join(format!("{name}.md.bak"));
+                    fs::write(&backup, &current)?;
+                    fs::write(&path, latest)?;
+                    TemplateUpdate::Overwritten(backup)
+                } else {
+                    let pending = dir.join(format!("{name}.md.new"));
+                    fs::write(&pending, latest)?;
+                    results.push((name.clone(), TemplateUpdate::Conflict(pending)));
+                    continue;
+                }
+            }
+        };
+        manifest.insert(name.clone(), latest_digest);
+        results.push((name.clone(), status));
+    }
+
+    let text = serde_json::to_string_pretty(&manifest).map_err(std::io::Error::other)?;
+    fs::write(&manifest_path, text)?;
+    Ok(results)
+}
+
+/// 获取最新模板：优先从 `remote_url/<name>.md` 下载，下载失败或校验不通过时使用内置版本
+///
+/// 返回 (模板名, 内容, 来源说明)
+pub async fn fetch_latest_templates(remote_url: Option<&str>) -> Vec<(String, String, String)> {
+    let client = crate::http::client_builder().ok().and_then(|builder| {
+        builder
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .ok()
+    });
+    let mut templates = Vec::new();
+    for (name, builtin) in BUILTIN_TEMPLATES {
+        let remote = match (remote_url, &client) {
+            (Some(base), Some(client)) => {
+                let url = format!("{}/{name}.md", base.trim_end_matches('/'));
+                match fetch_text(client, &url).await {
+                    Ok(text) => match validate_template(name, &text) {
+                        Ok(()) => Some((text, url)),
+                        Err(problems) => {
+                            log::warn!("远程提示词模板 {url} 校验失败，使用内置版本: {problems}");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        log::warn!("下载提示词模板 {url} 失败，使用内置版本: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let (content, source) = remote.unwrap_or_else(|| (builtin.to_string(), "内置".to_string()));
+        templates.push((name.to_string(), content, source));
+    }
+    templates
+}
+
+async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(response.text().await?)
+}
+
+fn digest(content: &str) -> String {
+    format!("{:x}", md5::compute(content.as_bytes()))
+}
+
+/// 目录中的 `*.md` 模板名
+fn template_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("md"))
+        .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +638,154 @@ mod tests {
         let result = manager.load_template("nonexistent", None);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_validate_template_placeholders() {
+        assert_eq!(
+            placeholders("{diff} {tree_sitter_summary} {diff} { x } {\"a\": 1} {1x}"),
+            vec!["diff", "tree_sitter_summary"]
+        );
+        assert!(validate_template("commit", "变更：{diff}").is_ok());
+        assert!(validate_template("custom", "{anything}").is_ok());
+
+        let problems = validate_template("review", "{diffs} {security_scan_results}").unwrap_err();
+        assert_eq!(problems.missing, vec!["diff"]);
+        assert_eq!(problems.unknown, vec!["diffs"]);
+        assert!(problems.to_string().contains("{diffs}"));
+
+        // 内置模板本身必须合法
+        for (name, _) in BUILTIN_TEMPLATES {
+            let manager = PromptManager::new(Config::default())
+                .with_prompts_dir(PathBuf::from("/nonexistent"));
+            assert!(manager.load_template(name, None).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_commit_template() {
+        assert_eq!(commit_template_name("gitmoji"), "commit-gitmoji");
+        assert_eq!(commit_template_name("commit-angular"), "commit-angular");
+        assert_eq!(commit_template_name("default"), "commit");
+        assert!(validate_template("commit-team", "{diff} {changed_areas}").is_ok());
+        assert!(validate_template("commit-team", "{diff} {security_scan_results}").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("commit-team.md"), "团队风格 {diff}").unwrap();
+        fs::write(dir.path().join("commit-broken.md"), "{diffs}").unwrap();
+        let manager =
+            PromptManager::new(Config::default()).with_prompts_dir(dir.path().to_path_buf());
+        assert_eq!(
+            manager.resolve_commit_template("gitmoji").unwrap(),
+            "commit-gitmoji"
+        );
+        assert_eq!(
+            manager.resolve_commit_template("team").unwrap(),
+            "commit-team"
+        );
+        assert!(manager
+            .commit_templates()
+            .contains(&"commit-team".to_string()));
+
+        let err = manager.resolve_commit_template("missing").unwrap_err();
+        assert!(err.to_string().contains("commit-plain"));
+        let err = manager.resolve_commit_template("broken").unwrap_err();
+        assert!(err.to_string().contains("{diffs}"));
+    }
+
+    #[test]
+    fn test_merge_templates_preserves_user_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let (_, builtin_review) = BUILTIN_TEMPLATES
+            .iter()
+            .find(|(n, _)| *n == "review")
+            .unwrap();
+        fs::write(dir.join("review.md"), builtin_review).unwrap();
+        fs::write(dir.join("commit.md"), "我的提交模板 {diff}").unwrap();
+
+        let v2 = |name: &str| (name.to_string(), format!("{name} v2 {{diff}}"));
+        let results =
+            merge_templates(dir, &[v2("review"), v2("commit"), v2("extra")], false).unwrap();
+        assert_eq!(results[0], ("review".to_string(), TemplateUpdate::Updated));
+        assert_eq!(
+            results[1],
+            (
+                "commit".to_string(),
+                TemplateUpdate::Conflict(dir.join("commit.md.new"))
+            )
+        );
+        assert_eq!(results[2], ("extra".to_string(), TemplateUpdate::Added));
+        assert_eq!(
+            fs::read_to_string(dir.join("commit.md")).unwrap(),
+            "我的提交模板 {diff}"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("commit.md.new")).unwrap(),
+            "commit v2 {diff}"
+        );
+
+        // 已安装的版本按摘要识别为未修改，可继续更新
+        let v3 = ("review".to_string(), "review v3 {diff}".to_string());
+        let results = merge_templates(dir, std::slice::from_ref(&v3), false).unwrap();
+        assert_eq!(results[0].1, TemplateUpdate::Updated);
+        assert_eq!(
+            merge_templates(dir, &[v3], false).unwrap()[0].1,
+            TemplateUpdate::UpToDate
+        );
+
+        let results = merge_templates(dir, &[v2("commit")], true).unwrap();
+        assert_eq!(
+            results[0].1,
+            TemplateUpdate::Overwritten(dir.join("commit.md.bak"))
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("commit.md")).unwrap(),
+            "commit v2 {diff}"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("commit.md.bak")).unwrap(),
+            "我的提交模板 {diff}"
+        );
+    }
+
+    #[test]
+    fn test_template_hot_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("commit.md");
+        let manager =
+            PromptManager::new(Config::default()).with_prompts_dir(dir.path().to_path_buf());
+        let write = |content: &str, secs: u64| {
+            fs::write(&path, content).unwrap();
+            let mtime = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        };
+
+        write("v1 {diff}", 1_000);
+        assert_eq!(manager.load_template("commit", None).unwrap(), "v1 {diff}");
+        write("v2 {diff}", 2_000);
+        assert_eq!(manager.load_template("commit", None).unwrap(), "v2 {diff}");
+        // 修改后的版本不合法时沿用上次的有效版本
+        write("v3 {diffs}", 3_000);
+        assert_eq!(manager.load_template("commit", None).unwrap(), "v2 {diff}");
+
+        let reports = manager.validate_all();
+        let commit = reports.iter().find(|r| r.name == "commit").unwrap();
+        assert_eq!(commit.path.as_deref(), Some(path.as_path()));
+        assert!(commit.result.as_ref().unwrap_err().contains("{diffs}"));
+        assert!(reports
+            .iter()
+            .any(|r| r.name == "review" && r.result.is_ok()));
+
+        // 首次加载即不合法时报错
+        let other = tempfile::tempdir().unwrap();
+        fs::write(other.path().join("review.md"), "{unknown_var}").unwrap();
+        let manager =
+            PromptManager::new(Config::default()).with_prompts_dir(other.path().to_path_buf());
+        assert!(manager.load_template("review", None).is_err());
+    }
 }
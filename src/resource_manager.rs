@@ -14,6 +14,178 @@ pub struct ResourceMetadata {
     pub checksum: Option<String>,
 }
 
+/// What a resource operation would do to a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlannedChange {
+    Download,
+    Update,
+    Delete,
+}
+
+/// One step of an update or clean plan, reported by `gitai config update|clean --dry-run`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedAction {
+    pub change: PlannedChange,
+    pub path: PathBuf,
+    /// Where the resource would be downloaded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Bytes to download (from `Content-Length`) or to free; `None` when unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Cache category being cleaned (`config clean --only`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<CacheCategory>,
+}
+
+/// Cache subsystem that can be cleaned on its own (`gitai config clean --only <category>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheCategory {
+    Rules,
+    TreeSitter,
+    ScanHistory,
+    Reviews,
+    AiCache,
+    Graph,
+}
+
+impl CacheCategory {
+    pub const ALL: [CacheCategory; 6] = [
+        Self::Rules,
+        Self::TreeSitter,
+        Self::ScanHistory,
+        Self::Reviews,
+        Self::AiCache,
+        Self::Graph,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rules => "rules",
+            Self::TreeSitter => "tree-sitter",
+            Self::ScanHistory => "scan-history",
+            Self::Reviews => "reviews",
+            Self::AiCache => "ai-cache",
+            Self::Graph => "graph",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == name)
+    }
+
+    /// Directories under `cache_dir` owned by this category, from the `utils::paths` helpers
+    pub fn dirs(&self, cache_dir: &Path) -> Vec<PathBuf> {
+        use crate::utils::paths;
+
+        match self {
+            Self::Rules => vec![
+                paths::rules_dir_in(cache_dir),
+                paths::rule_sources_dir_in(cache_dir),
+            ],
+            Self::TreeSitter => vec![
+                paths::tree_sitter_dir_in(cache_dir),
+                paths::tree_sitter_queries_dir_in(cache_dir),
+                paths::tree_sitter_cache_dir_in(cache_dir),
+                paths::analysis_store_dir_in(cache_dir),
+            ],
+            Self::ScanHistory => vec![paths::scan_history_dir_in(cache_dir)],
+            Self::Reviews => vec![paths::review_cache_dir_in(cache_dir)],
+            Self::AiCache => vec![paths::explain_cache_dir_in(cache_dir)],
+            Self::Graph => vec![paths::graph_cache_dir_in(cache_dir)],
+        }
+    }
+}
+
+impl std::fmt::Display for CacheCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Disk usage of one cache category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheUsage {
+    pub name: &'static str,
+    /// Category to pass to `gitai config clean --only`
+    pub category: CacheCategory,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+/// Disk usage of the whole cache directory (`gitai cache du`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheUsageReport {
+    pub cache_dir: PathBuf,
+    pub exists: bool,
+    pub groups: Vec<CacheUsage>,
+    /// Bytes in the cache directory outside the known categories
+    pub other_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Measure the cache directory by category (the same directories `config clean --only` removes);
+/// a missing directory yields an all-zero report
+pub fn cache_usage(cache_dir: &Path) -> CacheUsageReport {
+    let groups: Vec<CacheUsage> = CacheCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let (bytes, files) = category
+                .dirs(cache_dir)
+                .iter()
+                .map(|dir| dir_usage(dir))
+                .fold((0, 0), |(b, f), (bytes, files)| (b + bytes, f + files));
+            CacheUsage {
+                name: category.as_str(),
+                category,
+                bytes,
+                files,
+            }
+        })
+        .collect();
+    let total_bytes = dir_size(cache_dir);
+    let known: u64 = groups.iter().map(|g| g.bytes).sum();
+    CacheUsageReport {
+        cache_dir: cache_dir.to_path_buf(),
+        exists: cache_dir.is_dir(),
+        groups,
+        other_bytes: total_bytes.saturating_sub(known),
+        total_bytes,
+    }
+}
+
+fn dir_usage(path: &Path) -> (u64, usize) {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .fold((0, 0), |(bytes, files), m| (bytes + m.len(), files + 1))
+}
+
+/// Total size in bytes of the files under `path` (0 if it does not exist)
+pub fn dir_size(path: &Path) -> u64 {
+    dir_usage(path).0
+}
+
+/// Human-readable byte count, e.g. `1.5 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
 /// Resource manager for handling GitAI resources (rules, grammars, etc.)
 pub struct ResourceManager {
     cache_dir: PathBuf,
@@ -34,6 +206,9 @@ pub struct SourcesConfig {
     pub config_url: String,
     pub rules_url: String,
     pub tree_sitter_url: String,
+    /// Base URL for prompt templates (`<url>/<name>.md`), used by `gitai prompts update`
+    #[serde(default)]
+    pub prompts_url: Option<String>,
     pub fallback_sources: Vec<String>,
     pub update_check_interval: u64,
     pub auto_update: bool,
@@ -66,7 +241,8 @@ impl ResourceManager {
             crate::utils::paths::get_path_source(&cache_dir)
         );
 
-        let mut client_builder = reqwest::Client::builder()
+        let mut client_builder = crate::http::client_builder()
+            .map_err(|e| anyhow::anyhow!(e))?
             .timeout(std::time::Duration::from_secs(config.network.timeout));
 
         // Configure proxy if set
@@ -194,35 +370,54 @@ impl ResourceManager {
     /// Download from Git repository
     async fn download_from_git(&self, repo_url: &str, target_dir: &Path) -> Result<()> {
         // For Git repositories, we'll use git command or download archive
-        if repo_url.contains("github.com") {
-            // Convert to archive URL
-            let archive_url = repo_url.replace(".git", "/archive/refs/heads/main.zip");
-
-            self.download_archive(&archive_url, target_dir).await
-        } else {
+        match archive_url(repo_url) {
+            Some(archive_url) => self.download_archive(&archive_url, target_dir).await,
             // Try to clone with git
-            self.git_clone(repo_url, target_dir).await
+            None => self.git_clone(repo_url, target_dir).await,
         }
     }
 
+    /// Size announced by the server for `url`, if it answers a HEAD request with `Content-Length`
+    async fn remote_size(&self, url: &str) -> Option<u64> {
+        let response = self.client.head(url).send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
     /// Download and extract archive
+    ///
+    /// The download resumes from a previous partial fetch and is retried `network.retry_times`
+    /// times. When `<url>.sha256` exists the archive is verified and a mismatch is an error.
     async fn download_archive(&self, url: &str, target_dir: &Path) -> Result<()> {
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .with_context(|| format!("Failed to download from {url}"))?;
+        use crate::utils::download::{download_with_retry, ChecksumManifest};
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to download: HTTP {}", response.status());
+        let manifest = ChecksumManifest::fetch(&self.client, &format!("{url}.sha256")).await;
+        let file_name = url.rsplit('/').next().unwrap_or_default();
+        let expected = manifest.as_ref().and_then(|m| m.sha256_for(file_name));
+        if expected.is_none() {
+            info!("No checksum published for {}, skipping verification", url);
         }
 
-        let bytes = response.bytes().await?;
-
         // Save to temp file
         let temp_file = target_dir.with_extension("download.tmp");
-        fs::write(&temp_file, &bytes)?;
+        download_with_retry(
+            &self.client,
+            url,
+            &temp_file,
+            expected,
+            self.config.network.retry_times.saturating_add(1),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .with_context(|| format!("Failed to download from {url}"))?;
 
         // Extract based on extension
         if url.ends_with(".zip") {
@@ -365,6 +560,38 @@ impl ResourceManager {
             == 0)
     }
 
+    /// What `update_all` would download, without touching the cache.
+    ///
+    /// Archive sizes come from a HEAD request; sources cloned with git have no known size.
+    pub async fn plan_update(&self) -> Result<Vec<PlannedAction>> {
+        if self.offline_mode {
+            return Ok(Vec::new());
+        }
+
+        let rules_dir = self.cache_dir.join("rules");
+        let change = if rules_dir.exists() && !self.is_dir_empty(&rules_dir)? {
+            PlannedChange::Update
+        } else {
+            PlannedChange::Download
+        };
+        let repo_url = &self.config.sources.rules_url;
+        let (source, size) = match archive_url(repo_url) {
+            Some(url) => {
+                let size = self.remote_size(&url).await;
+                (url, size)
+            }
+            None => (repo_url.clone(), None),
+        };
+
+        Ok(vec![PlannedAction {
+            change,
+            path: rules_dir,
+            source: Some(source),
+            size,
+            category: None,
+        }])
+    }
+
     /// Update all resources
     pub async fn update_all(&self) -> Result<()> {
         if self.offline_mode {
@@ -387,9 +614,13 @@ impl ResourceManager {
         Ok(())
     }
 
-    /// Clean expired cache
-    pub async fn clean_cache(&self) -> Result<()> {
+    /// Cache directories `clean_cache` would remove (expired per `cache.ttl`), with their sizes
+    pub async fn plan_clean(&self) -> Result<Vec<PlannedAction>> {
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut plan = Vec::new();
+        if !self.cache_dir.exists() {
+            return Ok(plan);
+        }
 
         // Walk through cache directories
         for entry in fs::read_dir(&self.cache_dir)? {
@@ -402,17 +633,79 @@ impl ResourceManager {
                     let metadata: ResourceMetadata =
                         serde_json::from_str(&fs::read_to_string(&metadata_file)?)?;
 
-                    let age = now - metadata.last_updated;
+                    let age = now.saturating_sub(metadata.last_updated);
                     if age > self.config.cache.ttl {
-                        info!("Removing expired cache: {:?}", path);
-                        fs::remove_dir_all(&path)?;
+                        plan.push(PlannedAction {
+                            change: PlannedChange::Delete,
+                            size: Some(dir_size(&path)),
+                            path,
+                            source: None,
+                            category: None,
+                        });
                     }
                 }
             }
         }
+        plan.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(plan)
+    }
+
+    /// Clean expired cache
+    pub async fn clean_cache(&self) -> Result<()> {
+        for action in self.plan_clean().await? {
+            info!("Removing expired cache: {:?}", action.path);
+            fs::remove_dir_all(&action.path)?;
+        }
 
         Ok(())
     }
+
+    /// Existing directories of the given categories, regardless of age, with their sizes
+    pub fn plan_clean_categories(&self, categories: &[CacheCategory]) -> Vec<PlannedAction> {
+        let mut plan = Vec::new();
+        for &category in categories {
+            for path in category.dirs(&self.cache_dir) {
+                if path.is_dir() {
+                    plan.push(PlannedAction {
+                        change: PlannedChange::Delete,
+                        size: Some(dir_size(&path)),
+                        path,
+                        source: None,
+                        category: Some(category),
+                    });
+                }
+            }
+        }
+        plan
+    }
+
+    /// Remove everything cached by the given categories and report the bytes freed per category
+    pub fn clean_categories(
+        &self,
+        categories: &[CacheCategory],
+    ) -> Result<Vec<(CacheCategory, u64)>> {
+        let plan = self.plan_clean_categories(categories);
+        categories
+            .iter()
+            .map(|&category| {
+                let mut freed = 0;
+                for action in plan.iter().filter(|a| a.category == Some(category)) {
+                    info!("Removing {} cache: {:?}", category, action.path);
+                    fs::remove_dir_all(&action.path)?;
+                    freed += action.size.unwrap_or(0);
+                }
+                Ok((category, freed))
+            })
+            .collect()
+    }
+}
+
+/// Archive URL used instead of cloning for GitHub repositories
+fn archive_url(repo_url: &str) -> Option<String> {
+    repo_url
+        .contains("github.com")
+        .then(|| repo_url.replace(".git", "/archive/refs/heads/main.zip"))
 }
 
 /// Load resource configuration from file
@@ -496,15 +789,13 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
-    #[tokio::test]
-    async fn test_resource_manager() {
-        let temp_dir = TempDir::new().unwrap();
-
-        let config = ResourceConfig {
+    fn test_config(cache_path: &Path, offline_mode: bool) -> ResourceConfig {
+        ResourceConfig {
             sources: SourcesConfig {
                 config_url: "https://example.com/config.toml".to_string(),
                 rules_url: "https://example.com/rules".to_string(),
                 tree_sitter_url: "https://example.com/grammars".to_string(),
+                prompts_url: None,
                 fallback_sources: vec![],
                 update_check_interval: 86400,
                 auto_update: false,
@@ -513,19 +804,177 @@ mod tests {
                 proxy: String::new(),
                 timeout: 30,
                 retry_times: 3,
-                offline_mode: true,
+                offline_mode,
             },
             cache: CacheConfig {
                 enabled: true,
-                path: temp_dir.path().to_string_lossy().to_string(),
+                path: cache_path.to_string_lossy().to_string(),
                 max_size: "1GB".to_string(),
                 ttl: 604800,
             },
-        };
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resource_manager() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(temp_dir.path(), true);
 
         let manager = ResourceManager::new(config).unwrap();
 
         // Test that offline mode prevents downloads
         assert!(manager.get_rules().await.is_err());
+        assert!(manager.plan_update().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_clean_matches_clean_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        for (name, last_updated) in [("rules", now - 700_000), ("tree-sitter", now)] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("a.yaml"), "x".repeat(1500)).unwrap();
+            let metadata = ResourceMetadata {
+                version: "1.0.0".to_string(),
+                source_url: String::new(),
+                last_updated,
+                checksum: None,
+            };
+            fs::write(
+                dir.join(".metadata.json"),
+                serde_json::to_string(&metadata).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let manager = ResourceManager::new(test_config(temp_dir.path(), true)).unwrap();
+
+        // The plan lists only expired directories and changes nothing
+        let plan = manager.plan_clean().await.unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].change, PlannedChange::Delete);
+        assert_eq!(plan[0].path, temp_dir.path().join("rules"));
+        assert!(plan[0].size.unwrap() >= 1500);
+        assert!(temp_dir.path().join("rules").exists());
+
+        manager.clean_cache().await.unwrap();
+        assert!(!temp_dir.path().join("rules").exists());
+        assert!(temp_dir.path().join("tree-sitter").exists());
+    }
+
+    #[test]
+    fn test_clean_categories_reports_freed_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+        for (dir, bytes) in [
+            ("scan_history", 300),
+            ("explain_cache", 200),
+            ("tree-sitter-queries", 100),
+            ("rules", 50),
+        ] {
+            fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+            fs::write(temp_dir.path().join(dir).join("entry"), "x".repeat(bytes)).unwrap();
+        }
+        let manager = ResourceManager::new(test_config(temp_dir.path(), true)).unwrap();
+        let only = [
+            CacheCategory::ScanHistory,
+            CacheCategory::TreeSitter,
+            CacheCategory::Reviews,
+        ];
+
+        let plan = manager.plan_clean_categories(&only);
+        assert_eq!(plan.len(), 2);
+        assert!(temp_dir.path().join("scan_history").exists());
+
+        let freed = manager.clean_categories(&only).unwrap();
+        assert_eq!(
+            freed,
+            vec![
+                (CacheCategory::ScanHistory, 300),
+                (CacheCategory::TreeSitter, 100),
+                (CacheCategory::Reviews, 0),
+            ]
+        );
+        assert!(!temp_dir.path().join("scan_history").exists());
+        assert!(!temp_dir.path().join("tree-sitter-queries").exists());
+        // Other categories are left alone
+        assert!(temp_dir.path().join("explain_cache").exists());
+        assert!(temp_dir.path().join("rules").exists());
+        assert_eq!(
+            CacheCategory::parse("ai-cache"),
+            Some(CacheCategory::AiCache)
+        );
+    }
+
+    #[test]
+    fn test_category_dirs_cover_cache_users() {
+        use crate::utils::paths;
+
+        let cache = Path::new("/cache");
+        let owned: Vec<PathBuf> = CacheCategory::ALL
+            .iter()
+            .flat_map(|c| c.dirs(cache))
+            .collect();
+        // Every cache directory written by GitAI belongs to exactly one category
+        for dir in [
+            paths::rules_dir_in(cache),
+            paths::rule_sources_dir_in(cache),
+            paths::graph_cache_dir_in(cache),
+            paths::review_cache_dir_in(cache),
+            paths::scan_history_dir_in(cache),
+            paths::explain_cache_dir_in(cache),
+            paths::tree_sitter_dir_in(cache),
+            paths::tree_sitter_queries_dir_in(cache),
+            paths::tree_sitter_cache_dir_in(cache),
+            paths::analysis_store_dir_in(cache),
+        ] {
+            assert_eq!(owned.iter().filter(|d| **d == dir).count(), 1, "{dir:?}");
+        }
+        assert_eq!(CacheCategory::Graph.dirs(cache), vec![cache.join("graph")]);
+        assert_eq!(CacheCategory::parse("graph"), Some(CacheCategory::Graph));
+    }
+
+    #[test]
+    fn test_cache_usage_by_group() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = cache_usage(&temp_dir.path().join("missing"));
+        assert!(!missing.exists);
+        assert_eq!(missing.total_bytes, 0);
+        assert!(missing.groups.iter().all(|g| g.bytes == 0 && g.files == 0));
+
+        for (file, bytes) in [
+            ("tree-sitter-queries/java/highlights.scm", 100),
+            ("tree_sitter_cache/a.bin", 40),
+            ("analysis_store/v1/x.json", 60),
+            ("scan_history/scan_1.json", 30),
+            ("graph/g.json", 7),
+        ] {
+            let path = temp_dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x".repeat(bytes)).unwrap();
+        }
+        let report = cache_usage(temp_dir.path());
+        let group = |name: &str| report.groups.iter().find(|g| g.name == name).unwrap();
+        // One row per category, matching `config clean --only`
+        assert_eq!(report.groups.len(), CacheCategory::ALL.len());
+        assert_eq!(
+            (group("tree-sitter").bytes, group("tree-sitter").files),
+            (200, 3)
+        );
+        assert_eq!(group("scan-history").bytes, 30);
+        assert_eq!(group("rules").bytes, 0);
+        assert_eq!(group("graph").bytes, 7);
+        assert_eq!((report.other_bytes, report.total_bytes), (0, 237));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
     }
 }
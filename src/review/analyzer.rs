@@ -10,7 +10,7 @@ pub async fn perform_structural_analysis(
     diff: &str,
     language: &Option<String>,
 ) -> Result<Option<StructuralSummary>, Box<dyn std::error::Error + Send + Sync>> {
-    println!("🌳 正在进行Tree-sitter结构分析...");
+    crate::progress!("🌳 正在进行Tree-sitter结构分析...");
 
     // 检测支持的语言
     let detected_languages = if let Some(lang) = language {
@@ -18,7 +18,7 @@ pub async fn perform_structural_analysis(
         if let Some(supported_lang) = detect_supported_language(lang) {
             vec![supported_lang]
         } else {
-            println!("  ⚠️  指定的语言 '{lang}' 不支持Tree-sitter分析");
+            crate::progress!("  ⚠️  指定的语言 '{lang}' 不支持Tree-sitter分析");
             return Ok(None);
         }
     } else {
@@ -27,8 +27,8 @@ pub async fn perform_structural_analysis(
     };
 
     if detected_languages.is_empty() {
-        println!("  💡 提示：当前变更中没有支持Tree-sitter分析的语言");
-        println!("     支持的语言：Rust, Java, JavaScript, TypeScript, Python, Go, C, C++");
+        crate::progress!("  💡 提示：当前变更中没有支持Tree-sitter分析的语言");
+        crate::progress!("     支持的语言：Rust, Java, JavaScript, TypeScript, Python, Go, C, C++");
         return Ok(None);
     }
 
@@ -36,14 +36,14 @@ pub async fn perform_structural_analysis(
     let language_code_map = extract_code_by_language(diff);
 
     if language_code_map.is_empty() {
-        println!("  💡 提示：当前变更中没有可分析的代码内容");
-        println!("     这可能是文档、配置文件或二进制文件的变更");
+        crate::progress!("  💡 提示：当前变更中没有可分析的代码内容");
+        crate::progress!("     这可能是文档、配置文件或二进制文件的变更");
         return Ok(None);
     }
 
     // 检查是否为多语言项目
     if detected_languages.len() > 1 {
-        println!(
+        crate::progress!(
             "  🌐 检测到多语言项目：{:?}",
             detected_languages
                 .iter()
@@ -52,7 +52,7 @@ pub async fn perform_structural_analysis(
         );
         perform_multi_language_analysis(language_code_map, detected_languages).await
     } else {
-        println!("  📏 检测到语言: {:?}", detected_languages[0]);
+        crate::progress!("  📏 检测到语言: {:?}", detected_languages[0]);
         perform_single_language_analysis(language_code_map, detected_languages[0]).await
     }
 }
@@ -68,7 +68,7 @@ async fn perform_multi_language_analysis(
     let mut manager = match TreeSitterManager::new().await {
         Ok(manager) => manager,
         Err(e) => {
-            println!("  ⚠️  Tree-sitter初始化失败，将使用传统文本分析模式");
+            crate::progress!("  ⚠️  Tree-sitter初始化失败，将使用传统文本分析模式");
             log::debug!("Tree-sitter初始化详情: {e}");
             return Ok(None);
         }
@@ -87,7 +87,7 @@ async fn perform_multi_language_analysis(
                 continue;
             }
 
-            println!("  🔍 分析 {lang_name} 代码...");
+            crate::progress!("  🔍 分析 {lang_name} 代码...");
 
             match manager.analyze_structure(code, lang) {
                 Ok(single_summary) => {
@@ -107,7 +107,7 @@ async fn perform_multi_language_analysis(
                     total_classes += lang_summary.classes.len();
                     total_files += 1;
 
-                    println!(
+                    crate::progress!(
                         "    ✅ {lang_name}: {} 函数, {} 类, {} 注释",
                         lang_summary.functions.len(),
                         lang_summary.classes.len(),
@@ -117,7 +117,7 @@ async fn perform_multi_language_analysis(
                     language_summaries.insert(lang_name.to_string(), lang_summary);
                 }
                 Err(e) => {
-                    println!("    ⚠️  {lang_name} 分析失败: {e}");
+                    crate::progress!("    ⚠️  {lang_name} 分析失败: {e}");
                     log::debug!("{lang_name} Tree-sitter分析详情: {e}");
                 }
             }
@@ -125,12 +125,12 @@ async fn perform_multi_language_analysis(
     }
 
     if language_summaries.is_empty() {
-        println!("  ⚠️  所有语言分析均失败，将使用传统文本分析模式");
+        crate::progress!("  ⚠️  所有语言分析均失败，将使用传统文本分析模式");
         return Ok(None);
     }
 
-    println!("  ✅ 多语言结构分析完成");
-    println!(
+    crate::progress!("  ✅ 多语言结构分析完成");
+    crate::progress!(
         "     📊 总计: {} 种语言, {} 函数, {} 类, {} 文件",
         language_summaries.len(),
         total_functions,
@@ -162,7 +162,7 @@ async fn perform_single_language_analysis(
         .ok_or("No code found for the specified language")?;
 
     if code.trim().is_empty() {
-        println!("  💡 提示：{lang_name} 代码为空");
+        crate::progress!("  💡 提示：{lang_name} 代码为空");
         return Ok(None);
     }
 
@@ -171,17 +171,17 @@ async fn perform_single_language_analysis(
         Ok(mut manager) => {
             match manager.analyze_structure(code, language) {
                 Ok(summary) => {
-                    println!("  ✅ 结构分析完成");
+                    crate::progress!("  ✅ 结构分析完成");
 
                     // 生成架构洞察
                     let insights = InsightsGenerator::generate(&summary, None);
 
                     // 输出架构洞察
-                    println!(
+                    crate::progress!(
                         "     🏢️ 架构模式违规: {}",
                         insights.architecture.pattern_violations.len()
                     );
-                    println!(
+                    crate::progress!(
                         "     🔄 循环依赖: {}",
                         insights
                             .architecture
@@ -189,11 +189,11 @@ async fn perform_single_language_analysis(
                             .circular_dependencies
                             .len()
                     );
-                    println!(
+                    crate::progress!(
                         "     ⚡ 复杂度热点: {}",
                         insights.quality_hotspots.complexity_hotspots.len()
                     );
-                    println!(
+                    crate::progress!(
                         "     📊 API 接口: {}",
                         insights.api_surface.public_apis.len()
                     );
@@ -206,14 +206,14 @@ async fn perform_single_language_analysis(
                     )))
                 }
                 Err(e) => {
-                    println!("  ⚠️  结构分析失败，将使用传统文本分析模式");
+                    crate::progress!("  ⚠️  结构分析失败，将使用传统文本分析模式");
                     log::debug!("Tree-sitter分析详情: {e}");
                     Ok(None)
                 }
             }
         }
         Err(e) => {
-            println!("  ⚠️  Tree-sitter初始化失败，将使用传统文本分析模式");
+            crate::progress!("  ⚠️  Tree-sitter初始化失败，将使用传统文本分析模式");
             log::debug!("Tree-sitter初始化详情: {e}");
             Ok(None)
         }
@@ -223,33 +223,34 @@ async fn perform_single_language_analysis(
 /// 执行架构影响分析  
 pub async fn perform_architectural_impact_analysis(
     diff: &str,
+    repo_dir: &std::path::Path,
 ) -> Result<Option<ArchitecturalImpact>, Box<dyn std::error::Error + Send + Sync>> {
-    println!("🏗️ 正在进行架构影响分析...");
+    crate::progress!("🏗️ 正在进行架构影响分析...");
 
     // 创建GitStateAnalyzer并分析
-    let analyzer = GitStateAnalyzer::new();
+    let analyzer = GitStateAnalyzer::in_repo(repo_dir);
     match analyzer.analyze_git_diff(diff).await {
         Ok(impact) => {
-            println!("  ✅ 架构影响分析完成");
+            crate::progress!("  ✅ 架构影响分析完成");
 
             // 输出关键指标
             let total_changes = impact.function_changes.len()
                 + impact.struct_changes.len()
                 + impact.interface_changes.len();
-            println!("     📊 总变更数: {total_changes}");
-            println!("     🔧 函数变更: {}", impact.function_changes.len());
-            println!("     🏗️ 结构体变更: {}", impact.struct_changes.len());
-            println!("     🔌 接口变更: {}", impact.interface_changes.len());
+            crate::progress!("     📊 总变更数: {total_changes}");
+            crate::progress!("     🔧 函数变更: {}", impact.function_changes.len());
+            crate::progress!("     🏗️ 结构体变更: {}", impact.struct_changes.len());
+            crate::progress!("     🔌 接口变更: {}", impact.interface_changes.len());
 
             // 输出影响范围
             if !impact.impact_summary.affected_modules.is_empty() {
-                println!(
+                crate::progress!(
                     "     📦 影响模块: {}",
                     impact.impact_summary.affected_modules.len()
                 );
             }
             if !impact.impact_summary.breaking_changes.is_empty() {
-                println!(
+                crate::progress!(
                     "     ⚠️  破坏性变更: {}",
                     impact.impact_summary.breaking_changes.len()
                 );
@@ -258,7 +259,7 @@ pub async fn perform_architectural_impact_analysis(
             Ok(Some(impact))
         }
         Err(e) => {
-            println!("  ⚠️  架构影响分析失败: {e}");
+            crate::progress!("  ⚠️  架构影响分析失败: {e}");
             log::debug!("架构影响分析详情: {e}");
             Ok(None)
         }
@@ -8,12 +8,17 @@ pub fn build_cache_key(diff: &str, cfg: &ReviewConfig) -> String {
     let diff_hash = format!("{:x}", md5::compute(diff.as_bytes()));
     let mut ids = cfg.issue_ids.clone();
     ids.sort();
+    // 同一份 diff 在不同仓库中的扫描与依赖分析结果不同，缓存按仓库区分
+    let repo =
+        std::fs::canonicalize(cfg.repo_dir()).unwrap_or_else(|_| cfg.repo_dir().to_path_buf());
     let payload = serde_json::json!({
+        "repo": repo,
         "diff": diff_hash,
         "language": cfg.language,
         "security_scan": cfg.security_scan,
         "deviation_analysis": cfg.deviation_analysis,
         "issue_ids": ids,
+        "max_diff_tokens": cfg.max_diff_tokens,
     });
     format!("{:x}", md5::compute(payload.to_string().as_bytes()))
 }
@@ -22,11 +27,7 @@ pub fn build_cache_key(diff: &str, cfg: &ReviewConfig) -> String {
 pub fn check_cache(
     cache_key: &str,
 ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let cache_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cache")
-        .join("gitai")
-        .join("review_cache");
+    let cache_dir = crate::utils::paths::review_cache_dir();
 
     let cache_file = cache_dir.join(format!("review_{cache_key}.json"));
 
@@ -50,11 +51,7 @@ pub fn save_cache(
     result: &str,
     language: &Option<String>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let cache_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".cache")
-        .join("gitai")
-        .join("review_cache");
+    let cache_dir = crate::utils::paths::review_cache_dir();
 
     std::fs::create_dir_all(&cache_dir)?;
 
@@ -0,0 +1,189 @@
+// 变更行定位
+// 从 diff 的 hunk 头与新增行解析各文件在新版本中被修改的行号区间，
+// 用于区分落在变更行上的问题与全量扫描发现的既有问题
+
+use super::types::Finding;
+use std::collections::HashMap;
+
+/// diff 中各文件新版本被修改的行号区间（闭区间，已合并相邻行）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// 解析 unified diff；删除的文件与只有删除行的 hunk 不产生变更行
+    pub fn from_diff(diff: &str) -> Self {
+        let mut files: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        let mut current: Option<String> = None;
+        let mut next_line = 0usize;
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                current = None;
+            } else if let Some(path) = line.strip_prefix("+++ ").filter(|_| current.is_none()) {
+                // 删除的文件为 `+++ /dev/null`，没有新版本
+                current = path.strip_prefix("b/").map(str::to_string);
+                if let Some(path) = &current {
+                    files.entry(path.clone()).or_default();
+                }
+            } else if let Some(header) = line.strip_prefix("@@") {
+                next_line = hunk_new_start(header).unwrap_or(0);
+            } else if let Some(path) = &current {
+                if line.starts_with('+') {
+                    if next_line > 0 {
+                        push_line(files.entry(path.clone()).or_default(), next_line);
+                    }
+                    next_line += 1;
+                } else if !line.starts_with('-') && !line.starts_with('\\') {
+                    next_line += 1;
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// 文件是否出现在 diff 中
+    pub fn contains_file(&self, path: &str) -> bool {
+        self.ranges(path).is_some()
+    }
+
+    /// 文件的某一行是否被修改
+    pub fn is_changed(&self, path: &str, line: usize) -> bool {
+        self.ranges(path).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&line))
+        })
+    }
+
+    /// 问题是否为既有问题：不在 diff 涉及的文件中，或有行号但该行未被修改；
+    /// 没有文件路径的问题视为与本次变更相关
+    pub fn is_pre_existing(&self, finding: &Finding) -> bool {
+        let Some(path) = finding.file_path.as_deref() else {
+            return false;
+        };
+        match finding.line {
+            Some(line) => !self.is_changed(path, line),
+            None => !self.contains_file(path),
+        }
+    }
+
+    /// 按路径查找区间；扫描结果可能是 `./` 前缀或绝对路径，按路径后缀匹配
+    fn ranges(&self, path: &str) -> Option<&Vec<(usize, usize)>> {
+        let path = path.trim_start_matches("./");
+        self.files.get(path).or_else(|| {
+            self.files
+                .iter()
+                .find(|(file, _)| {
+                    path.strip_suffix(file.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('/'))
+                })
+                .map(|(_, ranges)| ranges)
+        })
+    }
+}
+
+/// 标记既有问题并排序：变更行上的问题在前，既有问题在后（各自保持原有顺序）
+pub fn prioritize_findings(findings: &mut [Finding], changed: &ChangedLines) {
+    for finding in findings.iter_mut() {
+        finding.pre_existing = changed.is_pre_existing(finding);
+    }
+    findings.sort_by_key(|finding| finding.pre_existing);
+}
+
+/// 解析 hunk 头 ` -a,b +c,d @@` 中的 c
+fn hunk_new_start(header: &str) -> Option<usize> {
+    let new_range = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    new_range[1..].split(',').next()?.parse().ok()
+}
+
+fn push_line(ranges: &mut Vec<(usize, usize)>, line: usize) {
+    match ranges.last_mut() {
+        Some((_, end)) if *end + 1 == line => *end = line,
+        _ => ranges.push((line, line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::types::Severity;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs\n\
+        --- a/src/lib.rs\n\
+        +++ b/src/lib.rs\n\
+        @@ -1,4 +1,5 @@\n \
+        fn a() {}\n\
+        -fn b() {}\n\
+        +fn b(x: u8) {}\n\
+        +fn c() {}\n \
+        fn d() {}\n \
+        fn e() {}\n\
+        @@ -20,3 +21,3 @@ impl Foo {\n \
+        let x = 1;\n\
+        -let y = 2;\n\
+        +let y = 3;\n\
+        \\ No newline at end of file\n\
+        diff --git a/old.rs b/old.rs\n\
+        deleted file mode 100644\n\
+        --- a/old.rs\n\
+        +++ /dev/null\n\
+        @@ -1 +0,0 @@\n\
+        -fn gone() {}\n";
+
+    fn finding(path: Option<&str>, line: Option<usize>) -> Finding {
+        Finding {
+            title: format!("{path:?}:{line:?}"),
+            severity: Severity::High,
+            file_path: path.map(str::to_string),
+            line,
+            column: None,
+            code_snippet: None,
+            message: String::new(),
+            rule_id: None,
+            recommendation: None,
+            pre_existing: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_hunk_ranges() {
+        let changed = ChangedLines::from_diff(DIFF);
+        assert_eq!(changed.files["src/lib.rs"], vec![(2, 3), (22, 22)]);
+        assert!(!changed.contains_file("old.rs"));
+        assert!(changed.is_changed("src/lib.rs", 3));
+        assert!(!changed.is_changed("src/lib.rs", 4));
+        assert!(changed.is_changed("./src/lib.rs", 22));
+        assert!(changed.is_changed("/work/repo/src/lib.rs", 2));
+        assert!(!changed.is_changed("/work/repo/xsrc/lib.rs", 2));
+    }
+
+    #[test]
+    fn test_prioritize_findings() {
+        let changed = ChangedLines::from_diff(DIFF);
+        let mut findings = vec![
+            finding(Some("src/other.rs"), Some(3)),
+            finding(Some("src/lib.rs"), Some(10)),
+            finding(Some("src/lib.rs"), Some(2)),
+            finding(None, None),
+            finding(Some("src/lib.rs"), None),
+        ];
+        prioritize_findings(&mut findings, &changed);
+        let order: Vec<(&str, bool)> = findings
+            .iter()
+            .map(|f| (f.title.as_str(), f.pre_existing))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("Some(\"src/lib.rs\"):Some(2)", false),
+                ("None:None", false),
+                ("Some(\"src/lib.rs\"):None", false),
+                ("Some(\"src/other.rs\"):Some(3)", true),
+                ("Some(\"src/lib.rs\"):Some(10)", true),
+            ]
+        );
+    }
+}
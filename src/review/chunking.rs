@@ -0,0 +1,465 @@
+// review 大变更分段模块
+// diff 超过 AI 评审的 token 上限时按文件分段，逐段评审后再汇总；分段过多时退化为仅结构分析。
+// 二进制文件、超大文件与命中生成文件模式的文件在此之前剔除，不发送给 AI。
+// 生成提交信息时只调用一次 AI，超出预算的 diff 按文件优先级裁剪，其余文件只保留增删行数摘要
+
+use crate::utils::generated::GeneratedFilter;
+
+/// 粗略按 1 token ≈ 4 字符估算（与依赖图摘要的 budget_tokens 估算一致）
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// 分段评审的最大段数，超过时不再调用 AI 逐段评审
+pub const MAX_CHUNKS: usize = 8;
+
+/// 单个文件的 diff 超过预算时截断的提示
+const TRUNCATED_MARKER: &str = "\n... (该文件的 diff 过长，已截断)\n";
+
+/// 裁剪 diff 时，剩余预算不足该字符数的文件不再截断保留，只列入摘要
+const MIN_PARTIAL_CHARS: usize = 400;
+
+/// 裁剪 diff 时省略文件的摘要标题
+const SUMMARY_HEADER: &str = "\n## 以下文件的 diff 超出 token 预算已省略（仅列出增删行数）:\n";
+
+/// 单个文件的 diff 超过该字节数时视为超大文件（通常是生成文件或打包产物），不参与评审
+pub const MAX_FILE_DIFF_BYTES: usize = 512 * 1024;
+
+/// 剔除不可评审文件后的 diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReviewableDiff {
+    /// 剩余可评审的 diff（保留首个文件头之前的说明文字）
+    pub diff: String,
+    /// 剩余可评审的文件数
+    pub files: usize,
+    /// 跳过的二进制文件
+    pub binary: Vec<String>,
+    /// 跳过的超大文件
+    pub oversized: Vec<String>,
+    /// 跳过的生成文件（命中 `analysis.generated_patterns`）
+    pub generated: Vec<String>,
+}
+
+impl ReviewableDiff {
+    /// 是否跳过了文件
+    pub fn has_skipped(&self) -> bool {
+        !self.binary.is_empty() || !self.oversized.is_empty() || !self.generated.is_empty()
+    }
+}
+
+/// 按 token 预算裁剪后的 diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BudgetedDiff {
+    /// 裁剪后的 diff，超出预算的文件以增删行数摘要代替
+    pub diff: String,
+    /// 原 diff 中的文件数
+    pub total_files: usize,
+    /// 被截断的文件
+    pub truncated: Vec<String>,
+    /// 只保留摘要的文件
+    pub summarized: Vec<String>,
+}
+
+impl BudgetedDiff {
+    /// 是否发生了裁剪
+    pub fn is_truncated(&self) -> bool {
+        !self.truncated.is_empty() || !self.summarized.is_empty()
+    }
+
+    /// 完整保留的文件数
+    pub fn full_files(&self) -> usize {
+        self.total_files - self.truncated.len() - self.summarized.len()
+    }
+}
+
+/// diff 的评审方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffPlan {
+    /// 未超过上限，整体评审
+    Full,
+    /// 按文件分段评审后汇总
+    Chunked(Vec<String>),
+    /// 分段过多，仅基于结构分析生成结果
+    StructuralOnly,
+}
+
+impl DiffPlan {
+    /// 写入评审结果 details 的标记，整体评审时为 None
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            Self::Full => None,
+            Self::Chunked(_) => Some("chunked"),
+            Self::StructuralOnly => Some("structural_only"),
+        }
+    }
+}
+
+/// 估算文本的 token 数
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// 按 `diff --git` 文件头拆分 diff，首个文件头之前的内容归入第一段
+pub fn split_by_file(diff: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = diff
+        .match_indices("diff --git ")
+        .map(|(i, _)| i)
+        .filter(|&i| i == 0 || diff.as_bytes()[i - 1] == b'\n')
+        .collect();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(diff.len());
+            &diff[start..end]
+        })
+        .filter(|part| !part.trim().is_empty())
+        .collect()
+}
+
+/// 剔除二进制文件（`Binary files ... differ`、`GIT binary patch` 或含 NUL 字节）与超过
+/// `max_file_bytes` 的文件，以及命中生成文件模式的文件；`max_file_bytes` 为 0 表示不限制大小
+pub fn strip_unreviewable(
+    diff: &str,
+    max_file_bytes: usize,
+    generated: &GeneratedFilter,
+) -> ReviewableDiff {
+    let mut result = ReviewableDiff::default();
+    for part in split_by_file(diff) {
+        let Some(path) = file_path(part) else {
+            result.diff.push_str(part);
+            continue;
+        };
+        if generated.is_generated(&path) {
+            result.generated.push(path);
+        } else if is_binary(part) {
+            result.binary.push(path);
+        } else if max_file_bytes > 0 && part.len() > max_file_bytes {
+            result.oversized.push(path);
+        } else {
+            result.files += 1;
+            result.diff.push_str(part);
+        }
+    }
+    result
+}
+
+/// 从 `diff --git a/<path> b/<path>` 文件头取新版本路径
+fn file_path(part: &str) -> Option<String> {
+    let header = part.lines().next()?.strip_prefix("diff --git ")?;
+    let path = header
+        .split_once(" b/")
+        .map(|(_, new)| new)
+        .unwrap_or(header);
+    Some(path.to_string())
+}
+
+fn is_binary(part: &str) -> bool {
+    part.contains('\0')
+        || part.lines().any(|line| {
+            line == "GIT binary patch"
+                || (line.starts_with("Binary files ") && line.ends_with(" differ"))
+        })
+}
+
+/// 依次把文件 diff 装入不超过 `budget_tokens` 的分段；单个文件超过预算时截断
+pub fn pack_chunks(files: &[&str], budget_tokens: usize) -> Vec<String> {
+    let budget_chars = budget_tokens.saturating_mul(CHARS_PER_TOKEN).max(1);
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for file in files {
+        let file = truncate_chars(file, budget_chars);
+        if !current.is_empty() && current.chars().count() + file.chars().count() > budget_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(&file);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(TRUNCATED_MARKER.chars().count());
+    let mut out: String = text.chars().take(keep).collect();
+    out.push_str(TRUNCATED_MARKER);
+    out
+}
+
+/// 根据 token 上限决定评审方式；`max_tokens` 为 0 表示不限制
+pub fn plan_diff(diff: &str, max_tokens: usize) -> DiffPlan {
+    if max_tokens == 0 || estimate_tokens(diff) <= max_tokens {
+        return DiffPlan::Full;
+    }
+    let chunks = pack_chunks(&split_by_file(diff), max_tokens);
+    if chunks.len() > MAX_CHUNKS {
+        DiffPlan::StructuralOnly
+    } else {
+        DiffPlan::Chunked(chunks)
+    }
+}
+
+/// 将 diff 裁剪到 `max_tokens` 以内（0 表示不限制）
+///
+/// 文件按重要性（源码优先，其次为文档与测试，命中生成文件模式的最后）与大小（小的优先，
+/// 以便完整保留更多文件）排序后依次装入；装不下的第一个文件在剩余预算足够时截断保留，
+/// 其余文件只在末尾列出增删行数。保留的文件维持原有顺序
+pub fn fit_to_budget(diff: &str, max_tokens: usize, generated: &GeneratedFilter) -> BudgetedDiff {
+    let mut preamble = String::new();
+    let mut files: Vec<(String, &str)> = Vec::new();
+    for part in split_by_file(diff) {
+        match file_path(part) {
+            Some(path) => files.push((path, part)),
+            None => preamble.push_str(part),
+        }
+    }
+    let mut result = BudgetedDiff {
+        total_files: files.len(),
+        ..Default::default()
+    };
+    if max_tokens == 0 || estimate_tokens(diff) <= max_tokens {
+        result.diff = diff.to_string();
+        return result;
+    }
+
+    let budget_chars = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+    // 为摘要预留空间（按全部文件都只保留摘要估算），最多占预算的四分之一
+    let summary_lines: Vec<String> = files
+        .iter()
+        .map(|(path, part)| {
+            let (added, removed) = count_lines(part);
+            format!("- {path} (+{added} -{removed})\n")
+        })
+        .collect();
+    let summary_reserve = (SUMMARY_HEADER.chars().count()
+        + summary_lines
+            .iter()
+            .map(|line| line.chars().count())
+            .sum::<usize>())
+    .min(budget_chars / 4);
+    let mut remaining = budget_chars
+        .saturating_sub(summary_reserve)
+        .saturating_sub(preamble.chars().count());
+
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by_key(|&i| {
+        (
+            std::cmp::Reverse(importance(&files[i].0, generated)),
+            files[i].1.len(),
+        )
+    });
+    let mut kept: Vec<Option<String>> = vec![None; files.len()];
+    for i in order {
+        let (path, part) = &files[i];
+        let chars = part.chars().count();
+        if chars <= remaining {
+            kept[i] = Some(part.to_string());
+            remaining -= chars;
+        } else if remaining >= MIN_PARTIAL_CHARS {
+            kept[i] = Some(truncate_chars(part, remaining));
+            remaining = 0;
+            result.truncated.push(path.clone());
+        }
+    }
+
+    result.diff = preamble;
+    for part in kept.iter().flatten() {
+        result.diff.push_str(part);
+    }
+    let omitted: Vec<usize> = (0..files.len()).filter(|&i| kept[i].is_none()).collect();
+    if !omitted.is_empty() {
+        let mut summary = SUMMARY_HEADER.to_string();
+        for (n, &i) in omitted.iter().enumerate() {
+            let line = &summary_lines[i];
+            if summary.chars().count() + line.chars().count() > summary_reserve {
+                summary.push_str(&format!("- ... 另有 {} 个文件\n", omitted.len() - n));
+                break;
+            }
+            summary.push_str(line);
+        }
+        result.diff.push_str(&summary);
+        result.summarized = omitted.iter().map(|&i| files[i].0.clone()).collect();
+    }
+    result
+}
+
+/// 文件的重要性：源码为 2，文档与测试为 1，生成文件为 0
+fn importance(path: &str, generated: &GeneratedFilter) -> u8 {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    if generated.is_generated(path) {
+        0
+    } else if [".md", ".txt", ".rst", ".adoc"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+        || lower.split('/').any(|dir| dir == "tests" || dir == "test")
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+    {
+        1
+    } else {
+        2
+    }
+}
+
+/// 统计文件 diff 的新增与删除行数
+fn count_lines(part: &str) -> (usize, usize) {
+    part.lines().fold((0, 0), |(added, removed), line| {
+        if line.starts_with('+') && !line.starts_with("+++") {
+            (added + 1, removed)
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            (added, removed + 1)
+        } else {
+            (added, removed)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_diff(name: &str, lines: usize) -> String {
+        let mut diff = format!("diff --git a/{name} b/{name}\n--- a/{name}\n+++ b/{name}\n");
+        for i in 0..lines {
+            diff.push_str(&format!("+let v{i} = {i};\n"));
+        }
+        diff
+    }
+
+    #[test]
+    fn test_split_and_pack_by_file() {
+        let diff = format!(
+            "## 已暂存的变更:\n{}{}{}",
+            file_diff("a.rs", 10),
+            file_diff("b.rs", 10),
+            file_diff("c.rs", 200)
+        );
+        let files = split_by_file(&diff);
+        assert_eq!(files.len(), 4);
+        assert!(files[1].starts_with("diff --git a/a.rs"));
+        assert_eq!(files.concat(), diff);
+
+        let budget = estimate_tokens(&files[..3].concat());
+        let chunks = pack_chunks(&files, budget);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].contains("a/b.rs"));
+        // 超过预算的单个文件被截断
+        assert!(chunks[1].contains("已截断"));
+        assert!(estimate_tokens(&chunks[1]) <= budget);
+    }
+
+    #[test]
+    fn test_plan_diff_thresholds() {
+        let small = file_diff("a.rs", 5);
+        assert_eq!(plan_diff(&small, 0), DiffPlan::Full);
+        assert_eq!(plan_diff(&small, 10_000), DiffPlan::Full);
+
+        let big: String = (0..3).map(|i| file_diff(&format!("f{i}.rs"), 40)).collect();
+        let per_file = estimate_tokens(&file_diff("f0.rs", 40));
+        match plan_diff(&big, per_file + 10) {
+            DiffPlan::Chunked(chunks) => assert_eq!(chunks.len(), 3),
+            other => panic!("unexpected plan: {other:?}"),
+        }
+
+        let huge: String = (0..MAX_CHUNKS + 2)
+            .map(|i| file_diff(&format!("f{i}.rs"), 40))
+            .collect();
+        assert_eq!(plan_diff(&huge, per_file + 10), DiffPlan::StructuralOnly);
+        assert_eq!(DiffPlan::StructuralOnly.label(), Some("structural_only"));
+    }
+
+    #[test]
+    fn test_strip_binary_and_oversized_files() {
+        let diff = format!(
+            "## 已暂存的变更:\n{}\
+             diff --git a/assets/logo.png b/assets/logo.png\n\
+             new file mode 100644\n\
+             index 0000000..3f4e2a1\n\
+             Binary files /dev/null and b/assets/logo.png differ\n\
+             diff --git a/fonts/a.woff b/fonts/a.woff\n\
+             index 1111111..2222222 100644\n\
+             GIT binary patch\n\
+             literal 12\n\
+             zcmZ?wbhEHbRA68\n\
+             {}{}",
+            file_diff("src/lib.rs", 3),
+            file_diff("dist/bundle.min.js", 400),
+            file_diff("src/main.rs", 2)
+        );
+        let max_bytes = file_diff("src/lib.rs", 50).len();
+        let reviewable = strip_unreviewable(&diff, max_bytes, &GeneratedFilter::disabled());
+        assert_eq!(reviewable.files, 2);
+        assert_eq!(
+            reviewable.binary,
+            vec!["assets/logo.png".to_string(), "fonts/a.woff".to_string()]
+        );
+        assert_eq!(reviewable.oversized, vec!["dist/bundle.min.js".to_string()]);
+        assert!(reviewable.has_skipped());
+        assert!(reviewable.diff.starts_with("## 已暂存的变更:\n"));
+        assert!(reviewable.diff.contains("a/src/main.rs"));
+        assert!(!reviewable.diff.contains("Binary files"));
+        assert!(!reviewable.diff.contains("bundle.min.js"));
+
+        // 不限制大小时只剔除二进制文件
+        let unlimited = strip_unreviewable(&diff, 0, &GeneratedFilter::disabled());
+        assert_eq!(unlimited.files, 3);
+        assert!(unlimited.oversized.is_empty());
+
+        // 只有二进制变更时没有可评审的文件
+        let only_binary = strip_unreviewable(
+            "diff --git a/a.bin b/a.bin\nBinary files a/a.bin and b/a.bin differ\n",
+            MAX_FILE_DIFF_BYTES,
+            &GeneratedFilter::default(),
+        );
+        assert_eq!(only_binary.files, 0);
+        assert_eq!(only_binary.binary, vec!["a.bin".to_string()]);
+        assert!(only_binary.diff.is_empty());
+
+        // 默认的生成文件模式命中压缩产物与锁文件
+        let with_lock = format!("{diff}{}", file_diff("Cargo.lock", 3));
+        let filtered = strip_unreviewable(&with_lock, 0, &GeneratedFilter::default());
+        assert_eq!(
+            filtered.generated,
+            vec!["dist/bundle.min.js".to_string(), "Cargo.lock".to_string()]
+        );
+        assert_eq!(filtered.files, 2);
+        assert!(!filtered.diff.contains("Cargo.lock"));
+    }
+    #[test]
+    fn test_fit_to_budget_prioritizes_source_files() {
+        let small = file_diff("src/lib.rs", 5);
+        let fitted = fit_to_budget(&small, 10_000, &GeneratedFilter::default());
+        assert_eq!(fitted.diff, small);
+        assert!(!fitted.is_truncated());
+        assert_eq!(fitted.full_files(), 1);
+
+        let lib = file_diff("src/lib.rs", 20);
+        let readme = file_diff("README.md", 20);
+        let diff = format!(
+            "## 已暂存的变更:\n{lib}{readme}{}{}",
+            file_diff("Cargo.lock", 200),
+            file_diff("src/big.rs", 300)
+        );
+        let budget = estimate_tokens(&lib) + estimate_tokens(&readme) + 150;
+        let fitted = fit_to_budget(&diff, budget, &GeneratedFilter::default());
+        assert!(estimate_tokens(&fitted.diff) <= budget);
+        assert!(fitted.diff.starts_with("## 已暂存的变更:\n"));
+        assert!(fitted.diff.contains(&lib));
+        // 源码优先：较大的源文件截断保留，文档与锁文件只保留摘要
+        assert_eq!(fitted.truncated, vec!["src/big.rs".to_string()]);
+        assert_eq!(
+            fitted.summarized,
+            vec!["README.md".to_string(), "Cargo.lock".to_string()]
+        );
+        assert!(fitted.diff.contains("- Cargo.lock (+200 -0)"));
+        assert_eq!(fitted.full_files(), 1);
+        assert!(fitted.is_truncated());
+    }
+}
@@ -48,6 +48,7 @@ impl From<crate::scan::Finding> for super::types::Finding {
             message: f.message,
             rule_id: f.rule_id,
             recommendation: f.remediation,
+            pre_existing: false,
         }
     }
 }
@@ -1,66 +1,167 @@
 // review 执行器模块
 // 负责执行评审流程的核心逻辑
 
-use super::types::{ReviewConfig, ReviewResult};
+use super::chunking::DiffPlan;
+use super::types::{DiffSelection, ReviewConfig, ReviewResult};
 use crate::config::Config;
 
-/// 执行评审流程（控制台输出）
+/// 执行评审流程（控制台输出），返回结构化结果供调用方做门禁判断
 pub async fn execute_review(
     config: &Config,
     review_config: ReviewConfig,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<ReviewResult, Box<dyn std::error::Error + Send + Sync>> {
+    use crate::utils::report_output;
+
+    let format = report_output::normalize_format(&review_config.format)
+        .filter(|format| *format == "json" || *format == "markdown");
+    // 在评审前校验输出目标；文本格式写入文件时使用 Markdown
+    let targets = report_output::resolve_targets(
+        &review_config.output,
+        format.unwrap_or("markdown"),
+        &["json", "markdown"],
+    )?;
     let result = execute_review_with_result(config, review_config).await?;
 
+    let render = |format: &str| -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match format {
+            "json" => serde_json::to_string_pretty(&result)?,
+            _ => result.to_markdown(),
+        })
+    };
+    report_output::write_targets(&targets, render)?;
+    for target in &targets {
+        crate::progress!(
+            "{}",
+            crate::tr!("review.saved", path = target.path.display())
+        );
+    }
+
+    // 结构化输出：json / markdown（未指定输出文件时打印到标准输出）
+    if let Some(format) = format {
+        if targets.is_empty() {
+            println!("{}", render(format)?);
+        }
+        return Ok(result);
+    }
+
     // 打印结果到控制台
-    println!("\n🤖 AI 代码评审结果:");
+    println!("{}", crate::tr!("review.result_header"));
     println!("{}", "=".repeat(80));
     println!("{}", result.summary);
 
-    if !result.findings.is_empty() {
-        println!("\n🔒 发现的问题:");
-        for finding in &result.findings {
+    if let Some(note) = result.summarization_note() {
+        println!("\nℹ️  {note}");
+    }
+
+    if let Some(note) = result.skipped_files_note() {
+        println!("\nℹ️  {note}");
+    }
+
+    if let Some(note) = result.quality_note() {
+        println!("\n📐 {note}");
+    }
+
+    if let Some(deps) = result.details.get("new_dependencies") {
+        println!("{}", crate::tr!("review.new_dependencies"));
+        for dep in deps.split("; ") {
+            println!("  ⚠️  New dependency introduced: {dep}");
+        }
+    }
+
+    let (pre_existing, changed): (Vec<_>, Vec<_>) =
+        result.findings.iter().partition(|f| f.pre_existing);
+    if !changed.is_empty() {
+        println!("{}", crate::tr!("review.findings"));
+        for finding in &changed {
             println!("  ⚠️  {}", finding.title);
         }
     }
+    if !pre_existing.is_empty() {
+        println!(
+            "{}",
+            crate::tr!("review.pre_existing_findings", count = pre_existing.len())
+        );
+        for finding in &pre_existing {
+            println!("  ·  {}", finding.title);
+        }
+    }
 
     if !result.recommendations.is_empty() {
-        println!("\n💡 改进建议:");
+        println!("{}", crate::tr!("review.recommendations"));
         for rec in &result.recommendations {
             println!("  • {rec}");
         }
     }
 
+    if let Some(bump) = result.details.get("semver_bump") {
+        println!("{}", crate::tr!("review.semver", bump = bump));
+        if let Some(reasons) = result.details.get("semver_reasons") {
+            for reason in reasons.split("; ") {
+                println!("  • {reason}");
+            }
+        }
+    }
+
     // 依赖分析洞察（若有）
     if result.details.contains_key("dep_nodes") {
-        println!("\n🔗 依赖分析洞察:");
+        println!("{}", crate::tr!("review.dep_insights"));
         if let (Some(nodes), Some(edges)) = (
             result.details.get("dep_nodes"),
             result.details.get("dep_edges"),
         ) {
-            println!("  图规模: {nodes} 节点 / {edges} 边");
+            println!(
+                "{}",
+                crate::tr!("review.dep_graph_size", nodes = nodes, edges = edges)
+            );
         }
         if let Some(avg) = result.details.get("dep_avg_degree") {
-            println!("  平均度: {avg}");
+            println!("{}", crate::tr!("review.dep_avg_degree", avg = avg));
         }
         if let Some(cn) = result.details.get("dep_critical_nodes") {
-            println!("  关键节点数: {cn}");
+            println!("{}", crate::tr!("review.dep_critical_nodes", count = cn));
         }
         if let Some(mapped) = result.details.get("dep_changed_nodes_mapped") {
-            println!("  映射变更节点: {mapped}");
+            println!("{}", crate::tr!("review.dep_mapped_nodes", count = mapped));
         }
         if let Some(scope) = result.details.get("dep_impact_scope_count") {
-            println!("  影响范围节点: {scope}");
+            println!("{}", crate::tr!("review.dep_scope_nodes", count = scope));
         }
         if let Some(top_pr) = result.details.get("dep_top_pagerank") {
             println!("  PageRank Top: {top_pr}");
         }
         if let Some(top_imp) = result.details.get("dep_top_impacted") {
-            println!("  影响度 Top: {top_imp}");
+            println!("{}", crate::tr!("review.dep_top_impacted", top = top_imp));
         }
     }
 
     println!("{}", "=".repeat(80));
-    Ok(())
+    Ok(result)
+}
+
+/// 跳过的二进制/超大/生成文件写入 details（路径以 `; ` 分隔）
+fn skipped_file_details(
+    reviewable: &super::chunking::ReviewableDiff,
+) -> std::collections::HashMap<String, String> {
+    let mut details = std::collections::HashMap::new();
+    if !reviewable.binary.is_empty() {
+        details.insert(
+            "skipped_binary_files".to_string(),
+            reviewable.binary.join("; "),
+        );
+    }
+    if !reviewable.oversized.is_empty() {
+        details.insert(
+            "skipped_oversized_files".to_string(),
+            reviewable.oversized.join("; "),
+        );
+    }
+    if !reviewable.generated.is_empty() {
+        details.insert(
+            "skipped_generated_files".to_string(),
+            reviewable.generated.join("; "),
+        );
+    }
+    details
 }
 
 /// 执行评审流程并返回结构化结果
@@ -69,39 +170,51 @@ pub async fn execute_review_with_result(
     review_config: ReviewConfig,
 ) -> Result<ReviewResult, Box<dyn std::error::Error + Send + Sync>> {
     // 获取代码变更
-    // 优先获取当前变更，如果没有则尝试获取最后一次提交
+    // 指定了远程 PR 时直接从 DevOps 平台拉取 diff；
+    // 否则优先获取当前变更，如果没有则尝试获取最后一次提交
     // 这样 MCP 调用时即使没有新变更也可以分析最近的提交
-    let diff = match crate::git::get_all_diff() {
-        Ok(d) => d,
-        Err(_) => {
-            // 如果没有当前变更，尝试获取最后一次提交
-            match crate::git::get_last_commit_diff() {
-                Ok(last_diff) if !last_diff.trim().is_empty() => {
-                    format!("## 最后一次提交的变更 (Last Commit):\n{last_diff}")
-                }
-                Ok(_) => {
-                    // 最后一次提交为空
-                    return Ok(ReviewResult {
-                        success: true,
-                        message: "没有检测到代码变更".to_string(),
-                        summary: "没有需要评审的代码变更".to_string(),
-                        details: std::collections::HashMap::new(),
-                        findings: Vec::new(),
-                        score: Some(100),
-                        recommendations: Vec::new(),
-                    });
-                }
-                Err(e) => {
-                    // 无法获取任何 diff，可能是新仓库或空仓库，或 MCP 服务未在仓库目录执行
-                    log::warn!("无法获取代码变更: {e}");
-
-                    // 在 details 中附带当前工作目录，方便排查
-                    let mut details = std::collections::HashMap::new();
-                    if let Ok(cwd) = std::env::current_dir() {
-                        details.insert("cwd".to_string(), cwd.display().to_string());
+    let diff = if let Some(ref pr_url) = review_config.pr_url {
+        crate::progress!("{}", crate::tr!("review.fetching_pr", url = pr_url));
+        fetch_pull_request_diff(config, pr_url).await?
+    } else if let Some(selected) = review_config
+        .diff_selection
+        .read_diff_in(review_config.repo_dir())?
+    {
+        // 显式指定了 --staged / --working / --stash / --blob：不回退到其他来源
+        selected
+    } else {
+        match crate::git::get_all_diff_in(review_config.repo_dir()) {
+            Ok(d) => d,
+            Err(_) => {
+                // 如果没有当前变更，尝试获取最后一次提交
+                match crate::git::get_last_commit_diff_in(review_config.repo_dir()) {
+                    Ok(last_diff) if !last_diff.trim().is_empty() => {
+                        format!("## 最后一次提交的变更 (Last Commit):\n{last_diff}")
+                    }
+                    Ok(_) => {
+                        // 最后一次提交为空
+                        return Ok(ReviewResult {
+                            success: true,
+                            message: "没有检测到代码变更".to_string(),
+                            summary: "没有需要评审的代码变更".to_string(),
+                            details: std::collections::HashMap::new(),
+                            findings: Vec::new(),
+                            score: Some(100),
+                            recommendations: Vec::new(),
+                        });
                     }
+                    Err(e) => {
+                        // 无法获取任何 diff，可能是新仓库或空仓库，或 MCP 服务未在仓库目录执行
+                        log::warn!("无法获取代码变更: {e}");
 
-                    return Ok(ReviewResult {
+                        // 在 details 中附带当前工作目录，方便排查
+                        let mut details = std::collections::HashMap::new();
+                        let repo = review_config.repo_dir();
+                        if let Ok(cwd) = std::env::current_dir().map(|cwd| cwd.join(repo)) {
+                            details.insert("cwd".to_string(), cwd.display().to_string());
+                        }
+
+                        return Ok(ReviewResult {
                         success: true,
                         message: "无法获取代码变更，可能是新仓库、空仓库，或 MCP 服务不在 Git 仓库目录中运行".to_string(),
                         summary: "没有可用的代码变更进行评审".to_string(),
@@ -114,6 +227,7 @@ pub async fn execute_review_with_result(
                             "如果通过 MCP 调用，请在参数中设置 path 指向仓库根目录，或在仓库根目录启动 MCP 服务".to_string(),
                         ],
                     });
+                    }
                 }
             }
         }
@@ -132,93 +246,219 @@ pub async fn execute_review_with_result(
         });
     }
 
+    // 二进制文件、超大文件与生成文件不发送给 AI，只在报告中注明
+    let reviewable = super::chunking::strip_unreviewable(
+        &diff,
+        super::chunking::MAX_FILE_DIFF_BYTES,
+        &crate::utils::generated::active(),
+    );
+    let skipped_details = skipped_file_details(&reviewable);
+    if reviewable.has_skipped() {
+        let note = crate::tr!(
+            "review.skipped_files",
+            binary = reviewable.binary.len(),
+            oversized = reviewable.oversized.len(),
+            generated = reviewable.generated.len()
+        );
+        crate::progress!("{note}");
+        if reviewable.files == 0 {
+            return Ok(ReviewResult {
+                success: true,
+                message: "没有可评审的代码变更".to_string(),
+                summary: note,
+                details: skipped_details,
+                findings: Vec::new(),
+                score: None,
+                recommendations: Vec::new(),
+            });
+        }
+    }
+    let diff = reviewable.diff;
+
     // 生成缓存键
     let cache_key = super::cache::build_cache_key(&diff, &review_config);
 
     // 检查缓存
     if let Some(cached_result) = super::cache::check_cache(&cache_key)? {
-        println!("📦 使用缓存的评审结果");
+        crate::progress!("{}", crate::tr!("review.cached"));
         return Ok(ReviewResult {
             success: true,
             message: "代码评审完成（缓存）".to_string(),
+            // 缓存的是 AI 回复原文，重新解析其中标注的问题以保持门禁结果一致
+            findings: super::rubric::parse_ai_findings(&cached_result),
             summary: cached_result,
             details: std::collections::HashMap::new(),
-            findings: Vec::new(),
             score: Some(85),
             recommendations: Vec::new(),
         });
     }
 
-    // 检查暂存状态与未跟踪文件、提交基线
-    let has_unstaged = crate::git::has_unstaged_changes().unwrap_or(false);
-    let has_staged = crate::git::has_staged_changes().unwrap_or(false);
-    let has_untracked = crate::git::has_untracked_changes().unwrap_or(false);
-    let has_commits = crate::git::has_any_commit();
+    // 检查暂存状态与未跟踪文件、提交基线（远程 PR 评审时不涉及本地状态）
+    let repo = review_config.repo_dir();
+    let has_unstaged = crate::git::has_unstaged_changes_in(repo).unwrap_or(false);
+    let has_staged = crate::git::has_staged_changes_in(repo).unwrap_or(false);
+    let has_untracked = crate::git::has_untracked_changes_in(repo).unwrap_or(false);
+    let has_commits = crate::git::has_any_commit_in(repo);
 
-    if has_unstaged || has_untracked {
+    if review_config.pr_url.is_some() {
+        crate::progress!("{}", crate::tr!("review.scope_pr"));
+    } else if review_config.diff_selection == DiffSelection::Staged {
+        crate::progress!("{}", crate::tr!("review.scope_staged_only"));
+    } else if review_config.diff_selection == DiffSelection::Working {
+        crate::progress!("{}", crate::tr!("review.scope_uncommitted"));
+    } else if let DiffSelection::Stash(index) = review_config.diff_selection {
+        crate::progress!(
+            "{}",
+            crate::tr!("review.scope_stash", stash = format!("stash@{{{index}}}"))
+        );
+    } else if let DiffSelection::Blob(spec) = &review_config.diff_selection {
+        crate::progress!("{}", crate::tr!("review.scope_blob", spec = spec));
+    } else if has_unstaged || has_untracked {
         if has_unstaged {
-            println!("💡 提示：检测到未暂存的代码变更");
-            println!("   使用 `git add .` 暂存所有变更，或使用 `git add <file>` 暂存特定文件");
+            crate::progress!("{}", crate::tr!("review.hint_unstaged"));
+            crate::progress!("{}", crate::tr!("review.hint_git_add"));
         }
         if has_untracked {
-            println!("💡 提示：检测到未跟踪的新文件");
-            println!("   使用 `git add <file>` 开始跟踪这些文件");
+            crate::progress!("{}", crate::tr!("review.hint_untracked"));
+            crate::progress!("{}", crate::tr!("review.hint_track"));
         }
         if has_staged {
-            println!("   当前已暂存的变更也会被评审");
+            crate::progress!("{}", crate::tr!("review.staged_included"));
         }
         if !has_commits {
-            println!("   ⚠️ 当前仓库还没有任何提交（建议尽快 `git commit -m \"<msg>\"`）");
+            crate::progress!("{}", crate::tr!("review.no_commits_warning"));
         }
-        println!("   📝 GitAI将分析所有变更（已暂存 + 未暂存 + 未跟踪）");
-        println!();
+        crate::progress!("{}", crate::tr!("review.scope_all"));
+        crate::progress!();
     } else if has_staged {
-        println!("✅ 已暂存的代码准备就绪");
-        println!("   📝 GitAI将分析已暂存的变更");
+        crate::progress!("{}", crate::tr!("review.staged_ready"));
+        crate::progress!("{}", crate::tr!("review.scope_staged"));
     } else if !has_commits {
-        println!("💡 提示：仓库没有任何提交。请先进行一次提交以建立基线：");
-        println!("   git add -A && git commit -m \"init\"");
+        crate::progress!("{}", crate::tr!("review.hint_no_commits"));
+        crate::progress!("   git add -A && git commit -m \"init\"");
     } else {
-        println!("🔍 检查未推送的提交...");
-        println!("   📝 GitAI将分析最近的提交变更");
+        crate::progress!("{}", crate::tr!("review.checking_unpushed"));
+        crate::progress!("{}", crate::tr!("review.scope_last_commit"));
+    }
+
+    // diff 超过 token 上限时按文件分段评审，分段过多则退化为仅结构分析（此时强制执行结构分析）
+    let max_diff_tokens = review_config
+        .max_diff_tokens
+        .unwrap_or(config.ai.max_diff_tokens);
+    let diff_tokens = super::chunking::estimate_tokens(&diff);
+    let diff_plan = super::chunking::plan_diff(&diff, max_diff_tokens);
+    match &diff_plan {
+        DiffPlan::Full => {}
+        DiffPlan::Chunked(chunks) => crate::progress!(
+            "{}",
+            crate::tr!(
+                "review.diff_chunked",
+                tokens = diff_tokens,
+                limit = max_diff_tokens,
+                chunks = chunks.len()
+            )
+        ),
+        DiffPlan::StructuralOnly => crate::progress!(
+            "{}",
+            crate::tr!(
+                "review.diff_structural_only",
+                tokens = diff_tokens,
+                limit = max_diff_tokens
+            )
+        ),
     }
 
     // 如果启用了 tree-sitter 分析
     let mut structural_summary = None;
-    if review_config.tree_sitter {
-        println!("🌳 使用 Tree-sitter 进行结构分析...");
+    if review_config.tree_sitter || diff_plan == DiffPlan::StructuralOnly {
+        crate::progress!("{}", crate::tr!("review.tree_sitter"));
         structural_summary =
             super::analyzer::perform_structural_analysis(&diff, &review_config.language).await?;
 
         if let Some(ref summary) = structural_summary {
             // 根据是否为多语言模式显示不同的统计信息
             if summary.is_multi_language() {
-                println!("  ✅ 多语言结构分析完成");
+                crate::progress!("{}", crate::tr!("review.multi_language_done"));
                 for (lang, lang_summary) in &summary.language_summaries {
-                    println!(
-                        "    🗺️ {}: {} 函数, {} 类, {} 注释",
-                        lang,
-                        lang_summary.functions.len(),
-                        lang_summary.classes.len(),
-                        lang_summary.comments.len()
+                    crate::progress!(
+                        "    🗺️ {}",
+                        crate::tr!(
+                            "review.language_stats",
+                            lang = lang,
+                            functions = lang_summary.functions.len(),
+                            classes = lang_summary.classes.len(),
+                            comments = lang_summary.comments.len(),
+                        )
                     );
                 }
             } else {
-                println!("  ✅ 结构分析完成");
-                println!(
-                    "    📋 {}: {} 函数, {} 类, {} 注释",
-                    summary.language,
-                    summary.functions.len(),
-                    summary.classes.len(),
-                    summary.comments.len()
+                crate::progress!("{}", crate::tr!("structure.done"));
+                crate::progress!(
+                    "    📋 {}",
+                    crate::tr!(
+                        "review.language_stats",
+                        lang = summary.language,
+                        functions = summary.functions.len(),
+                        classes = summary.classes.len(),
+                        comments = summary.comments.len(),
+                    )
                 );
             }
         }
     }
 
+    // 执行已注册的自定义分析 Pass
+    let mut pass_findings: Vec<super::types::Finding> = Vec::new();
+    if let Some(ref summary) = structural_summary {
+        pass_findings =
+            super::passes::run_registered_passes_with_config(summary, &config.analysis.lints);
+        if !pass_findings.is_empty() {
+            crate::progress!(
+                "{}",
+                crate::tr!("review.pass_findings", count = pass_findings.len())
+            );
+        }
+    }
+
     // 执行架构影响分析
     let architectural_impact =
-        super::analyzer::perform_architectural_impact_analysis(&diff).await?;
+        super::analyzer::perform_architectural_impact_analysis(&diff, review_config.repo_dir())
+            .await?;
+
+    // 基于公共 API 变化给出语义化版本建议
+    let version_suggestion = crate::architectural_impact::version_bump::suggest_from_diff(&diff);
+
+    // 检测本次变更新引入的第三方依赖（远程 PR 没有本地基线，跳过）
+    let new_dependencies = if review_config.pr_url.is_none() {
+        let base_ref = dependency_base_ref(
+            review_config.repo_dir(),
+            &diff,
+            &review_config.diff_selection,
+        );
+        match crate::architectural_impact::dependency_report::detect_new_dependencies_in(
+            review_config.repo_dir(),
+            &diff,
+            &base_ref,
+        )
+        .await
+        {
+            Ok(deps) => {
+                if !deps.is_empty() {
+                    crate::progress!(
+                        "{}",
+                        crate::tr!("review.new_dependencies_detected", count = deps.len())
+                    );
+                }
+                deps
+            }
+            Err(e) => {
+                log::warn!("新依赖检测失败: {e}");
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
     // 依赖分析与 PageRank（受 deviation_analysis 控制）
     let mut dep_details: Vec<(String, String)> = Vec::new();
@@ -226,9 +466,9 @@ pub async fn execute_review_with_result(
     let mut extra_findings: Vec<super::types::Finding> = Vec::new();
     let mut dep_prompt: Option<String> = None;
     if review_config.full || review_config.deviation_analysis {
-        println!("🔗 正在进行依赖图与 PageRank 分析...");
+        crate::progress!("{}", crate::tr!("review.dependency_graph"));
         match crate::architectural_impact::graph_export::build_global_dependency_graph(
-            std::path::Path::new("."),
+            review_config.repo_dir(),
         )
         .await
         {
@@ -296,6 +536,7 @@ pub async fn execute_review_with_result(
                                     message: format!("变更影响关键节点，中心性 {cent:.3}"),
                                     rule_id: None,
                                     recommendation: Some("考虑回归测试与影响面评估".to_string()),
+                                    pre_existing: false,
                                 });
                             }
                         }
@@ -374,7 +615,10 @@ pub async fn execute_review_with_result(
                 }
             }
             Err(e) => {
-                println!("  ⚠️ 依赖图构建失败: {e}");
+                crate::progress!(
+                    "{}",
+                    crate::tr!("review.dependency_graph_failed", error = e)
+                );
             }
         }
     }
@@ -386,30 +630,51 @@ pub async fn execute_review_with_result(
     let security_findings: Vec<super::types::Finding> = Vec::new();
     #[cfg(feature = "security")]
     if review_config.security_scan {
-        println!("🔒 正在进行安全扫描...");
+        crate::progress!("{}", crate::tr!("review.security_scan"));
         let scan_result = crate::scan::run_opengrep_scan(
             config,
-            std::path::Path::new("."),
+            review_config.repo_dir(),
             None,
             Some(60),
             false,
         )?;
 
         if !scan_result.findings.is_empty() {
-            println!("  ⚠️  发现 {} 个安全问题", scan_result.findings.len());
+            crate::progress!(
+                "{}",
+                crate::tr!(
+                    "review.security_findings",
+                    count = scan_result.findings.len()
+                )
+            );
             security_findings.extend(scan_result.findings.into_iter().map(Into::into));
+            // 全量扫描会带出历史问题：按变更行标记，变更行上的问题排在前面
+            let changed = super::changed_lines::ChangedLines::from_diff(&diff);
+            super::changed_lines::prioritize_findings(&mut security_findings, &changed);
         } else {
-            println!("  ✅ 未发现安全问题");
+            crate::progress!("{}", crate::tr!("review.security_clean"));
         }
     }
 
     // 调用 AI 进行评审
     #[cfg(feature = "ai")]
-    println!("🤖 正在调用 AI 进行代码评审...");
+    if diff_plan != DiffPlan::StructuralOnly {
+        crate::progress!("{}", crate::tr!("review.calling_ai"));
+    }
     #[cfg(not(feature = "ai"))]
-    println!("🤖 AI 功能未启用，使用基础规则生成结果...");
+    crate::progress!("{}", crate::tr!("review.ai_disabled"));
 
-    let mut prompt = format!("请对以下代码变更进行详细评审：\n\n{diff}\n\n");
+    let mut prompt = match &diff_plan {
+        DiffPlan::Chunked(chunks) => {
+            let chunk_reviews = review_chunks(config, chunks).await?;
+            format!(
+                "以下代码变更较大（约 {diff_tokens} tokens），已按文件分 {} 段分别评审，\
+                 请综合各段结论给出完整评审：\n\n{chunk_reviews}\n\n",
+                chunks.len()
+            )
+        }
+        _ => format!("请对以下代码变更进行详细评审：\n\n{diff}\n\n"),
+    };
 
     if let Some(ref summary) = structural_summary {
         prompt.push_str(&format!("\n结构分析结果：\n{summary:#?}\n"));
@@ -421,11 +686,21 @@ pub async fn execute_review_with_result(
         prompt.push('\n');
     }
 
+    if !new_dependencies.is_empty() {
+        prompt.push_str("\n本次变更新引入的第三方依赖（请评估其必要性与供应链风险）：\n");
+        for dep in &new_dependencies {
+            prompt.push_str(&format!("- {dep}，引入文件: {}\n", dep.files.join(", ")));
+        }
+    }
+
     prompt.push_str("请提供：\n");
     prompt.push_str("1. 代码质量评估\n");
     prompt.push_str("2. 潜在问题和风险\n");
     prompt.push_str("3. 改进建议\n");
     prompt.push_str("4. 总体评分（1-100）\n");
+    prompt.push_str(&super::rubric::rubric_prompt(
+        &config.review.severity_rubric,
+    ));
 
     // 在存在 Issue 或启用偏离度分析时，注入 DevOps Issue 上下文
     let devops_issue_context = {
@@ -495,7 +770,16 @@ pub async fn execute_review_with_result(
         prompt.push_str("3. 给出偏离项清单与建议收敛方案；\n");
     }
 
-    let ai_response = {
+    let ai_response = if diff_plan == DiffPlan::StructuralOnly {
+        basic_summary(
+            &format!(
+                "变更过大（约 {diff_tokens} tokens，上限 {max_diff_tokens}），未调用 AI 评审。以下为基础分析汇总：\n"
+            ),
+            structural_summary.as_ref(),
+            architectural_impact.as_ref(),
+            &devops_issue_context,
+        )
+    } else {
         #[cfg(feature = "ai")]
         {
             crate::ai::call_ai(config, &prompt).await?
@@ -503,28 +787,22 @@ pub async fn execute_review_with_result(
         #[cfg(not(feature = "ai"))]
         {
             // 退化为基础总结
-            let mut summary_text = String::new();
-            summary_text.push_str("AI 功能未启用。以下为基础分析汇总：\n");
-            if let Some(ref summary_struct) = structural_summary {
-                summary_text.push_str(&format!("结构分析结果：\n{summary_struct:#?}\n"));
-            }
-            if let Some(ref impact) = architectural_impact {
-                summary_text.push_str(&format!(
-                    "架构影响：风险={}\n受影响模块：{}\n破坏性变更数：{}\n",
-                    impact.impact_summary.risk_level,
-                    impact.impact_summary.affected_modules.join(", "),
-                    impact.impact_summary.breaking_changes.len()
-                ));
-            }
-            if !devops_issue_context.is_empty() {
-                summary_text.push_str("相关 Issue 上下文：\n");
-                summary_text.push_str(&devops_issue_context);
-                summary_text.push('\n');
-            }
-            summary_text
+            basic_summary(
+                "AI 功能未启用。以下为基础分析汇总：\n",
+                structural_summary.as_ref(),
+                architectural_impact.as_ref(),
+                &devops_issue_context,
+            )
         }
     };
 
+    // 按评级标准解析 AI 标注的问题，严重程度归一化后参与 --fail-on 门禁
+    let ai_findings = if cfg!(feature = "ai") && diff_plan != DiffPlan::StructuralOnly {
+        super::rubric::parse_ai_findings(&ai_response)
+    } else {
+        Vec::new()
+    };
+
     // 解析 AI 响应并构建结果
     let mut details = std::collections::HashMap::new();
     details.insert("review_result".to_string(), ai_response.clone());
@@ -532,6 +810,44 @@ pub async fn execute_review_with_result(
     if review_config.tree_sitter {
         details.insert("tree_sitter".to_string(), "true".to_string());
     }
+    details.extend(skipped_details);
+
+    // 变更代码的复杂度与可维护性指数
+    if let Some(summary) = structural_summary
+        .as_ref()
+        .filter(|summary| !summary.functions.is_empty())
+    {
+        let quality = crate::evaluation::QualityMetrics::from_summary(
+            summary,
+            &crate::evaluation::QualityMetricsOptions::default(),
+        );
+        details.insert(
+            "maintainability_index".to_string(),
+            format!("{:.1}", quality.maintainability_index),
+        );
+        details.insert(
+            "max_cyclomatic_complexity".to_string(),
+            quality.max_cyclomatic_complexity.to_string(),
+        );
+        details.insert(
+            "max_cognitive_complexity".to_string(),
+            quality.max_cognitive_complexity.to_string(),
+        );
+        details.insert(
+            "cognitive_complexity_level".to_string(),
+            quality.cognitive_level().as_str().to_string(),
+        );
+    }
+
+    // 记录大变更的分段/降级方式，便于调用方识别结果并非基于完整 diff
+    if let Some(label) = diff_plan.label() {
+        details.insert("diff_summarization".to_string(), label.to_string());
+        details.insert("diff_tokens".to_string(), diff_tokens.to_string());
+        details.insert("max_diff_tokens".to_string(), max_diff_tokens.to_string());
+        if let DiffPlan::Chunked(chunks) = &diff_plan {
+            details.insert("diff_chunks".to_string(), chunks.len().to_string());
+        }
+    }
 
     // 添加架构影响分析结果
     if let Some(ref impact) = architectural_impact {
@@ -555,6 +871,29 @@ pub async fn execute_review_with_result(
         );
     }
 
+    // 只在建议提升 minor 及以上版本时写入，没有公共 API 变化的评审不显示版本建议
+    if version_suggestion.is_suggested() {
+        details.insert(
+            "semver_bump".to_string(),
+            version_suggestion.bump.to_string(),
+        );
+        details.insert(
+            "semver_reasons".to_string(),
+            version_suggestion.reasons_text(),
+        );
+    }
+
+    if !new_dependencies.is_empty() {
+        details.insert(
+            "new_dependencies".to_string(),
+            new_dependencies
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        );
+    }
+
     // 合并依赖分析详情
     for (k, v) in dep_details {
         details.insert(k, v);
@@ -566,20 +905,28 @@ pub async fn execute_review_with_result(
             "security_findings_count".to_string(),
             security_findings.len().to_string(),
         );
+        let pre_existing = security_findings.iter().filter(|f| f.pre_existing).count();
+        if pre_existing > 0 {
+            details.insert(
+                "pre_existing_findings_count".to_string(),
+                pre_existing.to_string(),
+            );
+        }
     }
 
     // 简单的评分提取（尝试从 AI 响应中找到数字）
     let mut score = extract_score_from_response(&ai_response).unwrap_or(85);
 
-    // 根据安全问题调整评分
+    // 根据安全问题调整评分（既有问题不扣分）
     if !security_findings.is_empty() {
         let critical_count = security_findings
             .iter()
             .filter(|f| {
-                matches!(
-                    f.severity,
-                    super::types::Severity::Critical | super::types::Severity::High
-                )
+                !f.pre_existing
+                    && matches!(
+                        f.severity,
+                        super::types::Severity::Critical | super::types::Severity::High
+                    )
             })
             .count();
         score = score.saturating_sub((critical_count * 10) as u8);
@@ -592,11 +939,37 @@ pub async fn execute_review_with_result(
     // 保存缓存
     super::cache::save_cache(&cache_key, &ai_response, &review_config.language)?;
 
-    // 合并发现（安全 + 依赖分析）
-    let mut combined_findings = security_findings;
+    // 合并发现（新依赖 + 安全 + 依赖分析 + AI 评审 + 自定义 Pass）
+    let mut combined_findings: Vec<super::types::Finding> = new_dependencies
+        .iter()
+        .map(|dep| super::types::Finding {
+            title: format!("引入新的第三方依赖: {}", dep.package),
+            severity: super::types::Severity::Medium,
+            file_path: dep.files.first().cloned(),
+            line: None,
+            column: None,
+            code_snippet: None,
+            message: format!(
+                "{} 依赖 {} 在本次变更中首次出现（{}）",
+                dep.language,
+                dep.package,
+                dep.files.join(", ")
+            ),
+            rule_id: Some("dependency/new".to_string()),
+            recommendation: Some(
+                "确认该依赖的必要性、来源、许可证与维护状况，并锁定版本".to_string(),
+            ),
+            pre_existing: false,
+        })
+        .collect();
+    combined_findings.extend(security_findings);
     combined_findings.extend(extra_findings);
+    combined_findings.extend(ai_findings);
+    combined_findings.extend(pass_findings);
+    // 既有问题统一放在最后
+    combined_findings.sort_by_key(|f| f.pre_existing);
 
-    Ok(ReviewResult {
+    let mut result = ReviewResult {
         success: true,
         message: "代码评审完成".to_string(),
         summary: ai_response,
@@ -604,7 +977,173 @@ pub async fn execute_review_with_result(
         findings: combined_findings,
         score: Some(score),
         recommendations: Vec::new(),
-    })
+    };
+
+    // 回帖到远程 PR（失败不影响评审结果）
+    if review_config.post_comment {
+        if let Some(ref pr_url) = review_config.pr_url {
+            let comment = result.to_markdown();
+            let posted = match post_pull_request_comment(config, pr_url, &comment).await {
+                Ok(()) => {
+                    crate::progress!("{}", crate::tr!("review.comment_posted"));
+                    true
+                }
+                Err(e) => {
+                    log::warn!("回帖到 PR 失败: {e}");
+                    false
+                }
+            };
+            result
+                .details
+                .insert("pr_comment_posted".to_string(), posted.to_string());
+        }
+    }
+
+    Ok(result)
+}
+
+/// 逐段评审超过 token 上限的 diff，返回各段评审结论（供汇总评审使用）
+#[cfg(feature = "ai")]
+async fn review_chunks(
+    config: &Config,
+    chunks: &[String],
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut reviews = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        crate::progress!(
+            "{}",
+            crate::tr!(
+                "review.reviewing_chunk",
+                index = i + 1,
+                total = chunks.len()
+            )
+        );
+        let prompt = format!(
+            "以下是一次较大代码变更的第 {}/{} 段（按文件切分）。请列出这部分变更中的潜在问题、风险与改进建议，保持简洁：\n\n{chunk}",
+            i + 1,
+            chunks.len()
+        );
+        let review = crate::ai::call_ai(config, &prompt).await?;
+        reviews.push_str(&format!("### 第 {} 段\n{}\n\n", i + 1, review.trim()));
+    }
+    Ok(reviews)
+}
+
+#[cfg(not(feature = "ai"))]
+async fn review_chunks(
+    _config: &Config,
+    _chunks: &[String],
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(String::new())
+}
+
+/// 不调用 AI 时基于结构分析、架构影响与 Issue 上下文生成的基础总结
+fn basic_summary(
+    header: &str,
+    structural_summary: Option<&crate::tree_sitter::StructuralSummary>,
+    architectural_impact: Option<&crate::architectural_impact::ArchitecturalImpact>,
+    devops_issue_context: &str,
+) -> String {
+    let mut summary_text = header.to_string();
+    if let Some(summary_struct) = structural_summary {
+        summary_text.push_str(&format!("结构分析结果：\n{summary_struct:#?}\n"));
+    }
+    if let Some(impact) = architectural_impact {
+        summary_text.push_str(&format!(
+            "架构影响：风险={}\n受影响模块：{}\n破坏性变更数：{}\n",
+            impact.impact_summary.risk_level,
+            impact.impact_summary.affected_modules.join(", "),
+            impact.impact_summary.breaking_changes.len()
+        ));
+    }
+    if !devops_issue_context.is_empty() {
+        summary_text.push_str("相关 Issue 上下文：\n");
+        summary_text.push_str(devops_issue_context);
+        summary_text.push('\n');
+    }
+    summary_text
+}
+
+/// 推断本地 diff 的基线引用，用于对比变更前后的依赖
+///
+/// 贮藏以其基线提交为基线，其他显式选择的范围以 HEAD 为基线；自动推断时依据 diff 中的段落标记
+fn dependency_base_ref(repo: &std::path::Path, diff: &str, selection: &DiffSelection) -> String {
+    match selection {
+        DiffSelection::Auto => {}
+        DiffSelection::Stash(index) => return format!("stash@{{{index}}}^1"),
+        _ => return "HEAD".to_string(),
+    }
+    if diff.starts_with("## 最后一次提交的变更") {
+        return "HEAD~1".to_string();
+    }
+    if diff.contains("## 未推送的提交变更") {
+        if let Ok(upstream) = crate::git::get_upstream_branch_in(repo) {
+            return upstream;
+        }
+    }
+    "HEAD".to_string()
+}
+
+/// 为远程 PR 构建 DevOps 客户端（未配置 devops 时以匿名方式访问公开仓库）
+#[cfg(feature = "devops")]
+fn pull_request_client(
+    config: &Config,
+    pr: &crate::devops::PullRequestRef,
+) -> crate::devops::DevOpsClient {
+    let devops_cfg = config
+        .devops
+        .clone()
+        .unwrap_or_else(|| crate::config::DevOpsConfig {
+            platform: pr.platform.clone(),
+            base_url: format!("https://{}", pr.host),
+            token: String::new(),
+            project: Some(pr.project.clone()),
+            space_id: None,
+            timeout: 30,
+            retry_count: 0,
+        });
+    crate::devops::DevOpsClient::new(devops_cfg)
+}
+
+/// 从 DevOps 平台拉取远程 PR 的 diff
+async fn fetch_pull_request_diff(
+    config: &Config,
+    pr_url: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(feature = "devops")]
+    {
+        let pr = crate::devops::PullRequestRef::parse(pr_url)?;
+        let client = pull_request_client(config, &pr);
+        let diff = client.get_pull_request_diff(&pr).await?;
+        Ok(format!(
+            "## 远程 PR 变更 ({} {}#{}):\n{diff}",
+            pr.platform, pr.project, pr.number
+        ))
+    }
+    #[cfg(not(feature = "devops"))]
+    {
+        let _ = (config, pr_url);
+        Err("远程 PR 评审需要启用 'devops' 功能".into())
+    }
+}
+
+/// 将评审结果回帖到远程 PR
+async fn post_pull_request_comment(
+    config: &Config,
+    pr_url: &str,
+    body: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(feature = "devops")]
+    {
+        let pr = crate::devops::PullRequestRef::parse(pr_url)?;
+        let client = pull_request_client(config, &pr);
+        client.post_pull_request_comment(&pr, body).await
+    }
+    #[cfg(not(feature = "devops"))]
+    {
+        let _ = (config, pr_url, body);
+        Err("PR 回帖需要启用 'devops' 功能".into())
+    }
 }
 
 /// 从 AI 响应中提取评分
@@ -643,7 +1182,9 @@ impl ReviewExecutor {
         &self,
         review_config: ReviewConfig,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        execute_review(&self.config, review_config).await
+        execute_review(&self.config, review_config)
+            .await
+            .map(|_| ())
     }
 
     #[deprecated(note = "Use execute_review_with_result static function instead")]
@@ -3,8 +3,13 @@
 
 pub mod analyzer;
 pub mod cache;
+pub mod changed_lines;
+pub mod chunking;
 pub mod converter;
 pub mod executor;
+pub mod passes;
+pub mod plan;
+pub mod rubric;
 pub mod types;
 
 // 重新导出核心类型和函数
@@ -12,7 +17,11 @@ pub use analyzer::{perform_architectural_impact_analysis, perform_structural_ana
 pub use cache::{build_cache_key, check_cache, save_cache};
 pub use converter::{convert_analysis_result, convert_analysis_result_with_critical_check};
 pub use executor::{execute_review, execute_review_with_result};
-pub use types::{Finding, ReviewCache, ReviewConfig, ReviewResult, Severity};
+pub use passes::{register_pass, registered_passes, AnalysisPass, PassRegistry};
+pub use plan::{plan_review, ReviewPlan};
+pub use types::{
+    DiffSelection, Finding, ReviewCache, ReviewConfig, ReviewConfigBuilder, ReviewResult, Severity,
+};
 
 // 保持向后兼容
 #[deprecated(
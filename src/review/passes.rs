@@ -0,0 +1,391 @@
+// review 分析 Pass 模块
+// 提供可插拔的自定义分析扩展点：基于 Tree-sitter 结构摘要产出评审发现
+
+use super::types::{Finding, Severity};
+use crate::config::LintsConfig;
+use crate::tree_sitter::StructuralSummary;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// 自定义分析 Pass
+///
+/// 实现该 trait 并通过 [`register_pass`] 注册后，评审流程在完成结构分析时
+/// 会依次调用所有已注册 Pass，并将其产出的发现合并进评审结果。
+pub trait AnalysisPass: Send + Sync {
+    /// Pass 名称（用于日志与规则 ID 前缀）
+    fn name(&self) -> &str;
+
+    /// 基于结构摘要执行分析
+    fn run(&self, summary: &StructuralSummary) -> Vec<Finding>;
+}
+
+/// 分析 Pass 注册表
+#[derive(Clone, Default)]
+pub struct PassRegistry {
+    passes: Vec<Arc<dyn AnalysisPass>>,
+}
+
+impl PassRegistry {
+    /// 创建空注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建包含内置 Pass 的注册表
+    pub fn with_builtin_passes() -> Self {
+        let mut registry = Self::new();
+        registry.register(TodoCommentPass);
+        registry.register(LongFunctionPass::default());
+        registry
+    }
+
+    /// 注册 Pass；同名 Pass 会被替换
+    pub fn register<P: AnalysisPass + 'static>(&mut self, pass: P) {
+        self.register_arc(Arc::new(pass));
+    }
+
+    /// 注册已共享的 Pass；同名 Pass 会被替换
+    pub fn register_arc(&mut self, pass: Arc<dyn AnalysisPass>) {
+        self.passes.retain(|p| p.name() != pass.name());
+        self.passes.push(pass);
+    }
+
+    /// 按名称移除 Pass，返回是否存在
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let before = self.passes.len();
+        self.passes.retain(|p| p.name() != name);
+        before != self.passes.len()
+    }
+
+    /// 是否已注册指定名称的 Pass
+    pub fn contains(&self, name: &str) -> bool {
+        self.passes.iter().any(|p| p.name() == name)
+    }
+
+    /// 已注册 Pass 名称（按注册顺序）
+    pub fn names(&self) -> Vec<String> {
+        self.passes.iter().map(|p| p.name().to_string()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// 依次执行所有 Pass 并汇总发现
+    pub fn run(&self, summary: &StructuralSummary) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for pass in &self.passes {
+            let produced = pass.run(summary);
+            log::debug!("分析 Pass '{}' 产出 {} 个发现", pass.name(), produced.len());
+            findings.extend(produced);
+        }
+        findings
+    }
+}
+
+// 全局 Pass 注册表，评审流程从这里读取
+lazy_static::lazy_static! {
+    static ref GLOBAL_PASSES: RwLock<PassRegistry> = RwLock::new(PassRegistry::with_builtin_passes());
+}
+
+/// 向全局注册表注册 Pass
+pub fn register_pass<P: AnalysisPass + 'static>(pass: P) {
+    GLOBAL_PASSES.write().register(pass);
+}
+
+/// 从全局注册表移除 Pass
+pub fn unregister_pass(name: &str) -> bool {
+    GLOBAL_PASSES.write().unregister(name)
+}
+
+/// 全局已注册的 Pass 名称
+pub fn registered_passes() -> Vec<String> {
+    GLOBAL_PASSES.read().names()
+}
+
+/// 执行全局注册表中的所有 Pass
+pub fn run_registered_passes(summary: &StructuralSummary) -> Vec<Finding> {
+    // 先克隆注册表再执行，避免 Pass 内部注册时死锁
+    let registry = GLOBAL_PASSES.read().clone();
+    registry.run(summary)
+}
+
+/// 按配置执行全局注册表中的所有 Pass
+///
+/// 内置 lint（仍在注册表中时）会按 `analysis.lints` 的阈值重新配置；
+/// `enabled = false` 时跳过内置 lint。
+pub fn run_registered_passes_with_config(
+    summary: &StructuralSummary,
+    lints: &LintsConfig,
+) -> Vec<Finding> {
+    let mut registry = GLOBAL_PASSES.read().clone();
+    if registry.contains(LongFunctionPass::NAME) {
+        if lints.enabled {
+            registry.register(LongFunctionPass::from_config(lints));
+        } else {
+            registry.unregister(LongFunctionPass::NAME);
+        }
+    }
+    registry.run(summary)
+}
+
+/// 内置 lint：函数过长 / 参数过多
+#[derive(Debug, Clone)]
+pub struct LongFunctionPass {
+    /// 函数最大行数
+    pub max_lines: usize,
+    /// 函数最大参数个数
+    pub max_parameters: usize,
+}
+
+impl LongFunctionPass {
+    pub const NAME: &'static str = "long-function";
+
+    pub fn from_config(lints: &LintsConfig) -> Self {
+        Self {
+            max_lines: lints.max_function_lines,
+            max_parameters: lints.max_parameters,
+        }
+    }
+}
+
+impl Default for LongFunctionPass {
+    fn default() -> Self {
+        Self::from_config(&LintsConfig::default())
+    }
+}
+
+impl AnalysisPass for LongFunctionPass {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn run(&self, summary: &StructuralSummary) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for func in &summary.functions {
+            let lines = func.line_end.saturating_sub(func.line_start) + 1;
+            if lines > self.max_lines {
+                findings.push(Finding {
+                    title: format!("函数过长: {}", func.name),
+                    severity: Severity::Medium,
+                    file_path: None,
+                    line: Some(func.line_start),
+                    column: None,
+                    code_snippet: None,
+                    message: format!(
+                        "函数 {} 共 {lines} 行（第 {}-{} 行），超过阈值 {}",
+                        func.name, func.line_start, func.line_end, self.max_lines
+                    ),
+                    rule_id: Some(format!("{}/lines", Self::NAME)),
+                    recommendation: Some("拆分为多个职责单一的小函数".to_string()),
+                    pre_existing: false,
+                });
+            }
+
+            let params = func.parameters.len();
+            if params > self.max_parameters {
+                findings.push(Finding {
+                    title: format!("参数过多: {}", func.name),
+                    severity: Severity::Low,
+                    file_path: None,
+                    line: Some(func.line_start),
+                    column: None,
+                    code_snippet: None,
+                    message: format!(
+                        "函数 {} 有 {params} 个参数，超过阈值 {}",
+                        func.name, self.max_parameters
+                    ),
+                    rule_id: Some(format!("{}/parameters", Self::NAME)),
+                    recommendation: Some("将相关参数合并为配置结构体或使用构建器".to_string()),
+                    pre_existing: false,
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+/// 内置示例 Pass：标记 TODO/FIXME/HACK 注释
+pub struct TodoCommentPass;
+
+impl AnalysisPass for TodoCommentPass {
+    fn name(&self) -> &str {
+        "todo-comment"
+    }
+
+    fn run(&self, summary: &StructuralSummary) -> Vec<Finding> {
+        const MARKERS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+
+        summary
+            .comments
+            .iter()
+            .filter_map(|comment| {
+                let marker = MARKERS.iter().find(|m| comment.text.contains(*m))?;
+                Some(Finding {
+                    title: format!("遗留 {marker} 注释"),
+                    severity: Severity::Info,
+                    file_path: None,
+                    line: Some(comment.line),
+                    column: None,
+                    code_snippet: Some(comment.text.trim().to_string()),
+                    message: format!("第 {} 行存在未完成事项标记 {marker}", comment.line),
+                    rule_id: Some(format!("{}/{}", self.name(), marker.to_lowercase())),
+                    recommendation: Some("在合并前处理该事项，或将其转为 Issue 跟踪".to_string()),
+                    pre_existing: false,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree_sitter::{CommentInfo, FunctionInfo};
+
+    struct CountingPass(&'static str);
+
+    impl AnalysisPass for CountingPass {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn run(&self, summary: &StructuralSummary) -> Vec<Finding> {
+            summary
+                .functions
+                .iter()
+                .map(|f| Finding {
+                    title: f.name.clone(),
+                    severity: Severity::Low,
+                    file_path: None,
+                    line: Some(f.line_start),
+                    column: None,
+                    code_snippet: None,
+                    message: String::new(),
+                    rule_id: Some(self.0.to_string()),
+                    recommendation: None,
+                    pre_existing: false,
+                })
+                .collect()
+        }
+    }
+
+    fn summary_with_comments(texts: &[&str]) -> StructuralSummary {
+        StructuralSummary {
+            comments: texts
+                .iter()
+                .enumerate()
+                .map(|(i, t)| CommentInfo {
+                    text: t.to_string(),
+                    line: i + 1,
+                    is_doc_comment: false,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn function(name: &str, lines: usize, params: usize) -> FunctionInfo {
+        FunctionInfo {
+            name: name.to_string(),
+            parameters: (0..params).map(|i| format!("p{i}")).collect(),
+            params: Vec::new(),
+            return_type: None,
+            line_start: 10,
+            line_end: 10 + lines - 1,
+            is_async: false,
+            visibility: None,
+            generics: Vec::new(),
+            annotations: Vec::new(),
+            cyclomatic_complexity: 0,
+            cognitive_complexity: 0,
+            halstead_volume: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_long_function_pass_thresholds() {
+        let pass = LongFunctionPass {
+            max_lines: 80,
+            max_parameters: 6,
+        };
+        let summary = StructuralSummary {
+            functions: vec![
+                function("at_limit", 80, 6),
+                function("too_long", 81, 0),
+                function("too_many_params", 5, 7),
+            ],
+            ..Default::default()
+        };
+
+        let findings = pass.run(&summary);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].title, "函数过长: too_long");
+        assert_eq!(findings[0].line, Some(10));
+        assert_eq!(findings[0].rule_id.as_deref(), Some("long-function/lines"));
+        assert_eq!(findings[1].title, "参数过多: too_many_params");
+        assert!(findings.iter().all(|f| f.recommendation.is_some()));
+    }
+
+    #[test]
+    fn test_run_with_config_applies_thresholds() {
+        let summary = StructuralSummary {
+            functions: vec![function("medium", 30, 2)],
+            ..Default::default()
+        };
+        let strict = LintsConfig {
+            enabled: true,
+            max_function_lines: 20,
+            max_parameters: 6,
+        };
+        let disabled = LintsConfig {
+            enabled: false,
+            ..strict.clone()
+        };
+
+        let count = |lints: &LintsConfig| {
+            run_registered_passes_with_config(&summary, lints)
+                .iter()
+                .filter(|f| f.rule_id.as_deref() == Some("long-function/lines"))
+                .count()
+        };
+        assert_eq!(count(&strict), 1);
+        assert_eq!(count(&disabled), 0);
+        assert_eq!(count(&LintsConfig::default()), 0);
+    }
+
+    #[test]
+    fn test_todo_comment_pass() {
+        let summary = summary_with_comments(&["// TODO: refactor", "// plain", "/* FIXME */"]);
+        let findings = TodoCommentPass.run(&summary);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].line, Some(1));
+        assert_eq!(findings[1].rule_id.as_deref(), Some("todo-comment/fixme"));
+    }
+
+    #[test]
+    fn test_registry_replaces_same_name() {
+        let mut registry = PassRegistry::new();
+        registry.register(CountingPass("a"));
+        registry.register(CountingPass("b"));
+        registry.register(CountingPass("a"));
+        assert_eq!(registry.names(), vec!["b".to_string(), "a".to_string()]);
+        assert!(registry.unregister("b"));
+        assert!(!registry.unregister("b"));
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_builtin_registry_runs() {
+        let registry = PassRegistry::with_builtin_passes();
+        assert!(registry.names().contains(&"todo-comment".to_string()));
+        let findings = registry.run(&summary_with_comments(&["# HACK around"]));
+        assert_eq!(findings.len(), 1);
+    }
+}
@@ -0,0 +1,450 @@
+// review 执行计划模块
+// `gitai review --plan`：根据配置与参数推算将执行的阶段、变更规模与网络需求，不实际执行
+
+use super::types::{DiffSelection, ReviewConfig};
+use crate::config::Config;
+use serde::Serialize;
+
+/// 变更来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffSource {
+    /// 工作区变更（已暂存 + 未暂存 + 未跟踪 + 未推送的提交）
+    WorkingTree,
+    /// 仅已暂存的变更（`--staged`）
+    Staged,
+    /// 全部未提交的变更（`--working`）
+    Uncommitted,
+    /// 贮藏中的变更（`--stash`）
+    Stash,
+    /// 指定版本的单个文件（`--blob`）
+    Blob,
+    /// 没有当前变更时回退到最后一次提交
+    LastCommit,
+    /// 远程 PR/MR（规划阶段不拉取）
+    PullRequest,
+    /// 无法获取任何变更
+    None,
+}
+
+impl DiffSource {
+    fn label(self) -> &'static str {
+        match self {
+            DiffSource::WorkingTree => "工作区变更",
+            DiffSource::Staged => "已暂存的变更",
+            DiffSource::Uncommitted => "未提交的变更",
+            DiffSource::Stash => "贮藏的变更",
+            DiffSource::Blob => "指定版本的文件",
+            DiffSource::LastCommit => "最后一次提交",
+            DiffSource::PullRequest => "远程 PR/MR",
+            DiffSource::None => "无",
+        }
+    }
+}
+
+/// 变更规模
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffScope {
+    /// 变更文件路径（按出现顺序去重）
+    pub files: Vec<String>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+impl DiffScope {
+    /// 从 unified diff 统计变更文件与增删行数
+    pub fn from_diff(diff: &str) -> Self {
+        let mut scope = Self::default();
+        for line in diff.lines() {
+            if let Some(rest) = line.strip_prefix("diff --git ") {
+                if let Some(b_part) = rest.split_whitespace().nth(1) {
+                    // 已暂存与未暂存的 diff 会拼接在一起，同一文件可能出现两次
+                    let path = b_part.strip_prefix("b/").unwrap_or(b_part);
+                    if !scope.files.iter().any(|f| f == path) {
+                        scope.files.push(path.to_string());
+                    }
+                }
+            } else if line.starts_with("+++") || line.starts_with("---") {
+                continue;
+            } else if line.starts_with('+') {
+                scope.lines_added += 1;
+            } else if line.starts_with('-') {
+                scope.lines_removed += 1;
+            }
+        }
+        scope
+    }
+
+    /// 受 Tree-sitter 支持的变更文件数
+    fn supported_files(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|f| {
+                std::path::Path::new(f)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .and_then(crate::tree_sitter::SupportedLanguage::from_extension)
+                    .is_some()
+            })
+            .count()
+    }
+}
+
+/// 计划中的单个阶段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedStage {
+    /// 稳定的阶段代码（如 `diff`、`tree_sitter`、`ai`）
+    pub name: String,
+    pub description: String,
+    /// 是否会执行
+    pub enabled: bool,
+    /// 是否需要网络
+    pub network: bool,
+    /// 启用/跳过原因或规模说明
+    pub note: Option<String>,
+}
+
+/// review 执行计划
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewPlan {
+    pub diff_source: DiffSource,
+    /// 变更规模；远程 PR 在规划阶段未知
+    pub scope: Option<DiffScope>,
+    /// 命中评审缓存时不会调用 AI
+    pub cache_hit: bool,
+    /// 任一将执行的阶段需要网络
+    pub requires_network: bool,
+    pub stages: Vec<PlannedStage>,
+    /// 可能的配置问题（如变更为空）
+    pub warnings: Vec<String>,
+}
+
+impl ReviewPlan {
+    /// 根据配置、参数与已获取的变更推算计划
+    pub fn build(
+        config: &Config,
+        review_config: &ReviewConfig,
+        diff_source: DiffSource,
+        scope: Option<DiffScope>,
+        cache_hit: bool,
+    ) -> Self {
+        let mut warnings = Vec::new();
+        let empty =
+            matches!(&scope, Some(s) if s.files.is_empty()) || diff_source == DiffSource::None;
+        if empty {
+            warnings.push("没有检测到代码变更，评审将直接返回空结果".to_string());
+        }
+        let remote = diff_source == DiffSource::PullRequest;
+        // 变更为空时后续阶段都不会执行
+        let proceed = !empty;
+        let reason = |text: &str| Some(text.to_string());
+        let mut stages = Vec::new();
+
+        stages.push(PlannedStage {
+            name: "diff".to_string(),
+            description: "获取代码变更".to_string(),
+            enabled: true,
+            network: remote,
+            note: Some(match &scope {
+                Some(s) => format!(
+                    "{}：{} 个文件，+{} -{} 行",
+                    diff_source.label(),
+                    s.files.len(),
+                    s.lines_added,
+                    s.lines_removed
+                ),
+                None => format!("{}：规模在拉取后确定", diff_source.label()),
+            }),
+        });
+
+        stages.push(PlannedStage {
+            name: "tree_sitter".to_string(),
+            description: "Tree-sitter 结构分析与分析 Pass".to_string(),
+            enabled: proceed && !cache_hit && review_config.tree_sitter,
+            network: false,
+            note: if !review_config.tree_sitter {
+                reason("未指定 --tree-sitter")
+            } else {
+                scope
+                    .as_ref()
+                    .map(|s| format!("{} 个受支持语言的文件", s.supported_files()))
+            },
+        });
+
+        stages.push(PlannedStage {
+            name: "architectural_impact".to_string(),
+            description: "架构影响与语义化版本建议".to_string(),
+            enabled: proceed && !cache_hit,
+            network: false,
+            note: None,
+        });
+
+        let dependency_graph = review_config.full || review_config.deviation_analysis;
+        stages.push(PlannedStage {
+            name: "dependency_graph".to_string(),
+            description: "全仓库依赖图与 PageRank 分析".to_string(),
+            enabled: proceed && !cache_hit && dependency_graph,
+            network: false,
+            note: if dependency_graph {
+                reason("扫描整个仓库，耗时随仓库规模增长")
+            } else {
+                reason("未指定 --full 或 Issue")
+            },
+        });
+
+        stages.push(PlannedStage {
+            name: "new_dependencies".to_string(),
+            description: "新引入第三方依赖检测".to_string(),
+            enabled: proceed && !cache_hit && !remote,
+            network: false,
+            note: if remote {
+                reason("远程 PR 没有本地基线")
+            } else {
+                None
+            },
+        });
+
+        let security_available = cfg!(feature = "security");
+        stages.push(PlannedStage {
+            name: "security_scan".to_string(),
+            description: "OpenGrep 安全扫描".to_string(),
+            enabled: proceed && !cache_hit && review_config.security_scan && security_available,
+            network: false,
+            note: if !review_config.security_scan {
+                reason("未指定 --security-scan")
+            } else if !security_available {
+                reason("当前构建未包含 'security' 功能")
+            } else {
+                None
+            },
+        });
+
+        let wants_issues = review_config.needs_issue_context();
+        let devops_available = cfg!(feature = "devops") && config.devops.is_some();
+        stages.push(PlannedStage {
+            name: "devops_issues".to_string(),
+            description: "拉取 DevOps Issue 上下文".to_string(),
+            enabled: proceed && !cache_hit && wants_issues && devops_available,
+            network: true,
+            note: if !wants_issues {
+                reason("未指定 --issue-id")
+            } else if !devops_available {
+                reason("未配置 DevOps 或当前构建未包含 'devops' 功能")
+            } else {
+                Some(review_config.issue_ids.join(", "))
+            },
+        });
+
+        let ai_available = cfg!(feature = "ai");
+        let ai_local = is_local_url(&config.ai.api_url);
+        stages.push(PlannedStage {
+            name: "ai".to_string(),
+            description: "调用 AI 进行评审".to_string(),
+            enabled: proceed && !cache_hit && ai_available,
+            network: ai_available && !ai_local,
+            note: if cache_hit {
+                reason("命中评审缓存，直接使用缓存结果")
+            } else if !ai_available {
+                reason("当前构建未包含 'ai' 功能，使用基础规则汇总")
+            } else {
+                Some(format!("{}（{}）", config.ai.model, config.ai.api_url))
+            },
+        });
+
+        if review_config.post_comment {
+            stages.push(PlannedStage {
+                name: "post_comment".to_string(),
+                description: "将评审结果回帖到 PR/MR".to_string(),
+                enabled: proceed,
+                network: true,
+                note: None,
+            });
+        }
+
+        let requires_network = stages.iter().any(|s| s.enabled && s.network);
+        Self {
+            diff_source,
+            scope,
+            cache_hit,
+            requires_network,
+            stages,
+            warnings,
+        }
+    }
+
+    /// 渲染为终端文本
+    pub fn to_text(&self) -> String {
+        let mut out = String::from("🗺️  评审计划（未实际执行）\n");
+        match &self.scope {
+            Some(scope) => out.push_str(&format!(
+                "   变更来源: {}，{} 个文件，+{} -{} 行\n",
+                self.diff_source.label(),
+                scope.files.len(),
+                scope.lines_added,
+                scope.lines_removed
+            )),
+            None => out.push_str(&format!(
+                "   变更来源: {}（规模在拉取后确定）\n",
+                self.diff_source.label()
+            )),
+        }
+        out.push_str(&format!(
+            "   需要网络: {}\n\n",
+            if self.requires_network { "是" } else { "否" }
+        ));
+        for stage in &self.stages {
+            let mark = if stage.enabled { "▶" } else { "⏭" };
+            let network = if stage.enabled && stage.network {
+                " 🌐"
+            } else {
+                ""
+            };
+            out.push_str(&format!("  {mark} {}{network}", stage.description));
+            if let Some(note) = &stage.note {
+                out.push_str(&format!(" — {note}"));
+            }
+            out.push('\n');
+        }
+        for warning in &self.warnings {
+            out.push_str(&format!("\n⚠️  {warning}\n"));
+        }
+        out
+    }
+}
+
+/// 获取变更（与实际评审相同的选择与回退顺序，远程 PR 不拉取）并推算计划
+pub async fn plan_review(
+    config: &Config,
+    review_config: &ReviewConfig,
+) -> Result<ReviewPlan, Box<dyn std::error::Error + Send + Sync>> {
+    if review_config.pr_url.is_some() {
+        return Ok(ReviewPlan::build(
+            config,
+            review_config,
+            DiffSource::PullRequest,
+            None,
+            false,
+        ));
+    }
+
+    let repo = review_config.repo_dir();
+    let selected = review_config.diff_selection.read_diff_in(repo)?;
+    let (source, diff) = match (&review_config.diff_selection, selected) {
+        (DiffSelection::Staged, Some(diff)) => (DiffSource::Staged, diff),
+        (DiffSelection::Working, Some(diff)) => (DiffSource::Uncommitted, diff),
+        (DiffSelection::Stash(_), Some(diff)) => (DiffSource::Stash, diff),
+        (DiffSelection::Blob(_), Some(diff)) => (DiffSource::Blob, diff),
+        _ => match crate::git::get_all_diff_in(repo) {
+            Ok(d) => (DiffSource::WorkingTree, d),
+            Err(_) => match crate::git::get_last_commit_diff_in(repo) {
+                Ok(d) => (DiffSource::LastCommit, d),
+                Err(e) => {
+                    log::debug!("无法获取代码变更: {e}");
+                    (DiffSource::None, String::new())
+                }
+            },
+        },
+    };
+    let cache_hit = !diff.trim().is_empty()
+        && super::cache::check_cache(&super::cache::build_cache_key(&diff, review_config))
+            .ok()
+            .flatten()
+            .is_some();
+    Ok(ReviewPlan::build(
+        config,
+        review_config,
+        source,
+        Some(DiffScope::from_diff(&diff)),
+        cache_hit,
+    ))
+}
+
+/// AI 接口是否指向本机（如本地 Ollama），此时不需要外部网络
+fn is_local_url(url: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .unwrap_or(url)
+        .split(['/', '?'])
+        .next()
+        .unwrap_or_default();
+    let host = host.rsplit_once('@').map_or(host, |(_, h)| h);
+    let host = if host.starts_with('[') {
+        host.split(']')
+            .next()
+            .unwrap_or(host)
+            .trim_start_matches('[')
+    } else {
+        host.split(':').next().unwrap_or(host)
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review_config(tree_sitter: bool, security_scan: bool) -> ReviewConfig {
+        ReviewConfig::builder()
+            .tree_sitter(tree_sitter)
+            .security_scan(security_scan)
+            .build()
+    }
+
+    fn stage<'a>(plan: &'a ReviewPlan, name: &str) -> &'a PlannedStage {
+        plan.stages.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_plan_counts_scope_and_enabled_stages() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n-fn a() {}\n+fn b() {}\n+fn c() {}\ndiff --git a/README.md b/README.md\n--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-old\n+new\n";
+        let scope = DiffScope::from_diff(diff);
+        assert_eq!(scope.files, vec!["src/lib.rs", "README.md"]);
+        assert_eq!((scope.lines_added, scope.lines_removed), (3, 2));
+
+        let mut config = Config::default();
+        config.ai.api_url = "https://api.example.com/v1/chat/completions".to_string();
+        let plan = ReviewPlan::build(
+            &config,
+            &review_config(true, false),
+            DiffSource::WorkingTree,
+            Some(scope),
+            false,
+        );
+        assert!(plan.warnings.is_empty());
+        assert!(stage(&plan, "tree_sitter").enabled);
+        assert_eq!(
+            stage(&plan, "tree_sitter").note.as_deref(),
+            Some("1 个受支持语言的文件")
+        );
+        assert!(!stage(&plan, "security_scan").enabled);
+        assert!(!stage(&plan, "dependency_graph").enabled);
+        assert_eq!(stage(&plan, "ai").enabled, cfg!(feature = "ai"));
+        assert_eq!(plan.requires_network, cfg!(feature = "ai"));
+    }
+
+    #[test]
+    fn test_plan_for_empty_diff_warns_and_skips_stages() {
+        let config = Config::default();
+        let plan = ReviewPlan::build(
+            &config,
+            &review_config(true, true),
+            DiffSource::WorkingTree,
+            Some(DiffScope::from_diff("")),
+            false,
+        );
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(!plan.requires_network);
+        assert!(plan.stages.iter().skip(1).all(|s| !s.enabled));
+    }
+
+    #[test]
+    fn test_is_local_url() {
+        assert!(is_local_url("http://localhost:11434/v1/chat/completions"));
+        assert!(is_local_url("http://127.0.0.1/v1"));
+        assert!(is_local_url("http://[::1]:8080/v1"));
+        assert!(!is_local_url(
+            "https://api.deepseek.com/v1/chat/completions"
+        ));
+    }
+}
@@ -0,0 +1,128 @@
+// review 严重程度评级模块
+// 把配置中的评级标准注入评审提示词，并把 AI 回复中标注的问题解析为统一的 Severity
+
+use super::types::{Finding, Severity};
+use crate::config::SeverityRubric;
+
+/// AI 评审发现的规则 ID
+pub const AI_REVIEW_RULE_ID: &str = "ai-review";
+
+/// 生成注入评审提示词的评级标准与问题输出格式说明
+pub fn rubric_prompt(rubric: &SeverityRubric) -> String {
+    format!(
+        "\n严重程度评级标准：\n\
+         - critical: {}\n\
+         - major: {}\n\
+         - minor: {}\n\
+         请在“潜在问题和风险”中每个问题单独一行，格式为 \
+         `- [critical|major|minor] 问题描述 (文件路径:行号)`，\
+         没有明确位置时省略括号部分；严重程度只能使用上述三个标签。\n",
+        rubric.critical.trim(),
+        rubric.major.trim(),
+        rubric.minor.trim()
+    )
+}
+
+/// 把 AI 给出的严重程度标签归一化为 Severity；无法识别的标签视为 Info
+pub fn normalize_severity(label: &str) -> Severity {
+    match label.trim().to_lowercase().as_str() {
+        "critical" | "blocker" | "严重" | "致命" => Severity::Critical,
+        "major" | "high" | "error" | "重要" | "高" => Severity::High,
+        "medium" | "warning" | "moderate" | "中" | "中等" => Severity::Medium,
+        "minor" | "low" | "trivial" | "nit" | "一般" | "轻微" | "低" => Severity::Low,
+        "info" | "suggestion" | "提示" | "建议" => Severity::Info,
+        other => {
+            log::debug!("无法识别的严重程度标签 {other}，按 Info 处理");
+            Severity::Info
+        }
+    }
+}
+
+/// 从 AI 评审回复中解析按 [`rubric_prompt`] 格式标注的问题
+pub fn parse_ai_findings(response: &str) -> Vec<Finding> {
+    lazy_static::lazy_static! {
+        static ref LINE_RE: regex::Regex = regex::Regex::new(
+            r"^\s*(?:[-*+]|\d+[.)])?\s*(?:\*\*)?\[([^\]\s]{2,20})\](?:\*\*)?\s*(.+?)\s*$"
+        )
+        .expect("valid regex");
+        static ref LOCATION_RE: regex::Regex =
+            regex::Regex::new(r"\s*[(（]`?([^\s()（）`]+?):(\d+)`?[)）]$").expect("valid regex");
+    }
+
+    response
+        .lines()
+        .filter_map(|line| {
+            let caps = LINE_RE.captures(line)?;
+            let mut title = caps[2].to_string();
+            let mut location = None;
+            if let Some(loc) = LOCATION_RE.captures(&title) {
+                location = Some((loc[1].to_string(), loc[2].parse::<usize>().ok()));
+                let start = loc.get(0).map_or(title.len(), |m| m.start());
+                title.truncate(start);
+            }
+            let title = title.trim().to_string();
+            if title.is_empty() {
+                return None;
+            }
+            let (file_path, line) = match location {
+                Some((path, line)) => (Some(path), line),
+                None => (None, None),
+            };
+            Some(Finding {
+                message: title.clone(),
+                title,
+                severity: normalize_severity(&caps[1]),
+                file_path,
+                line,
+                column: None,
+                code_snippet: None,
+                rule_id: Some(AI_REVIEW_RULE_ID.to_string()),
+                recommendation: None,
+                pre_existing: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ai_findings_normalizes_severity() {
+        let response = "## 潜在问题和风险\n\
+            - [critical] SQL 拼接存在注入风险 (src/db.rs:42)\n\
+            - **[Major]** 未处理的 unwrap（`src/main.rs:7`）\n\
+            1. [minor] 变量命名不清晰\n\
+            - [urgent] 未知标签\n\
+            - [ ] 普通复选框\n\
+            总体评分：80";
+        let findings = parse_ai_findings(response);
+        assert_eq!(findings.len(), 4);
+
+        assert!(matches!(findings[0].severity, Severity::Critical));
+        assert_eq!(findings[0].title, "SQL 拼接存在注入风险");
+        assert_eq!(findings[0].file_path.as_deref(), Some("src/db.rs"));
+        assert_eq!(findings[0].line, Some(42));
+
+        assert!(matches!(findings[1].severity, Severity::High));
+        assert_eq!(findings[1].title, "未处理的 unwrap");
+        assert_eq!(findings[1].file_path.as_deref(), Some("src/main.rs"));
+
+        assert!(matches!(findings[2].severity, Severity::Low));
+        assert_eq!(findings[2].file_path, None);
+
+        assert!(matches!(findings[3].severity, Severity::Info));
+        assert!(findings
+            .iter()
+            .all(|f| f.rule_id.as_deref() == Some(AI_REVIEW_RULE_ID)));
+    }
+
+    #[test]
+    fn test_rubric_prompt_contains_levels() {
+        let prompt = rubric_prompt(&SeverityRubric::default());
+        for level in ["critical", "major", "minor"] {
+            assert!(prompt.contains(&format!("- {level}: ")));
+        }
+    }
+}
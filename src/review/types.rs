@@ -3,7 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// 评审结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +24,165 @@ pub struct ReviewResult {
     pub recommendations: Vec<String>,
 }
 
+impl ReviewResult {
+    /// 严重程度不低于 threshold 的问题
+    pub fn findings_at_or_above(&self, threshold: &Severity) -> Vec<&Finding> {
+        self.findings
+            .iter()
+            .filter(|f| f.severity.rank() >= threshold.rank())
+            .collect()
+    }
+
+    /// diff 超过 token 上限时的评审方式说明，整体评审时为 None
+    pub fn summarization_note(&self) -> Option<String> {
+        let tokens = self
+            .details
+            .get("diff_tokens")
+            .map(String::as_str)
+            .unwrap_or("?");
+        match self.details.get("diff_summarization")?.as_str() {
+            "chunked" => Some(crate::tr!(
+                "review.summarized_chunked",
+                tokens = tokens,
+                chunks = self
+                    .details
+                    .get("diff_chunks")
+                    .map(String::as_str)
+                    .unwrap_or("?")
+            )),
+            "structural_only" => Some(crate::tr!("review.summarized_structural", tokens = tokens)),
+            _ => None,
+        }
+    }
+
+    /// 未参与评审的二进制/超大/生成文件说明，没有跳过文件时为 None
+    pub fn skipped_files_note(&self) -> Option<String> {
+        let count = |key: &str| {
+            self.details.get(key).map_or(0, |files| {
+                files.split("; ").filter(|f| !f.is_empty()).count()
+            })
+        };
+        let (binary, oversized, generated) = (
+            count("skipped_binary_files"),
+            count("skipped_oversized_files"),
+            count("skipped_generated_files"),
+        );
+        (binary + oversized + generated > 0).then(|| {
+            crate::tr!(
+                "review.skipped_files",
+                binary = binary,
+                oversized = oversized,
+                generated = generated
+            )
+        })
+    }
+
+    /// 变更代码的质量指标说明（启用结构分析时才有）
+    pub fn quality_note(&self) -> Option<String> {
+        use crate::evaluation::ComplexityLevel;
+
+        let metric = |key: &str| self.details.get(key).map(String::as_str).unwrap_or("?");
+        // 旧结果没有记录级别时按最大认知复杂度推导
+        let level = self
+            .details
+            .get("cognitive_complexity_level")
+            .and_then(|name| ComplexityLevel::parse(name))
+            .or_else(|| {
+                metric("max_cognitive_complexity")
+                    .parse()
+                    .ok()
+                    .map(ComplexityLevel::from_cognitive)
+            })
+            .map_or("?", |level| level.label());
+        self.details.get("maintainability_index").map(|index| {
+            crate::tr!(
+                "review.quality_metrics",
+                index = index,
+                cyclomatic = metric("max_cyclomatic_complexity"),
+                cognitive = metric("max_cognitive_complexity"),
+                level = level
+            )
+        })
+    }
+
+    /// 渲染为 Markdown（用于 `--format markdown` 与 PR 回帖）
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## 🤖 GitAI 代码评审\n\n");
+        if let Some(score) = self.score {
+            out.push_str(&format!("评分: {score}/100\n\n"));
+        }
+        out.push_str(self.summary.trim_end());
+        out.push('\n');
+
+        if let Some(deps) = self.details.get("new_dependencies") {
+            out.push_str("\n### 📦 新引入的依赖\n\n");
+            for dep in deps.split("; ") {
+                out.push_str(&format!("- ⚠️ New dependency introduced: `{dep}`\n"));
+            }
+        }
+
+        let (pre_existing, changed): (Vec<_>, Vec<_>) =
+            self.findings.iter().partition(|f| f.pre_existing);
+        if !changed.is_empty() {
+            out.push_str("\n### 🔒 发现的问题\n\n");
+            for finding in changed {
+                push_finding_line(&mut out, finding);
+            }
+        }
+        // 既有问题折叠显示，避免分散对本次变更的注意力
+        if !pre_existing.is_empty() {
+            out.push_str(&format!(
+                "\n<details>\n<summary>既有问题（{} 个，不在本次变更行上）</summary>\n\n",
+                pre_existing.len()
+            ));
+            for finding in pre_existing {
+                push_finding_line(&mut out, finding);
+            }
+            out.push_str("\n</details>\n");
+        }
+
+        if let Some(note) = self.summarization_note() {
+            out.push_str(&format!("\n> {note}\n"));
+        }
+
+        if let Some(note) = self.skipped_files_note() {
+            out.push_str(&format!("\n> {note}\n"));
+        }
+
+        if let Some(note) = self.quality_note() {
+            out.push_str(&format!("\n📐 {note}\n"));
+        }
+
+        if let Some(bump) = self.details.get("semver_bump") {
+            out.push_str(&format!("\n### 🏷️ 版本建议: {bump}\n\n"));
+            if let Some(reasons) = self.details.get("semver_reasons") {
+                for reason in reasons.split("; ").filter(|r| !r.is_empty()) {
+                    out.push_str(&format!("- {reason}\n"));
+                }
+            }
+        }
+
+        if !self.recommendations.is_empty() {
+            out.push_str("\n### 💡 改进建议\n\n");
+            for rec in &self.recommendations {
+                out.push_str(&format!("- {rec}\n"));
+            }
+        }
+
+        out
+    }
+}
+
+fn push_finding_line(out: &mut String, finding: &Finding) {
+    out.push_str(&format!("- **[{:?}]** {}", finding.severity, finding.title));
+    match (&finding.file_path, finding.line) {
+        (Some(path), Some(line)) => out.push_str(&format!(" (`{path}:{line}`)")),
+        (Some(path), None) => out.push_str(&format!(" (`{path}`)")),
+        _ => {}
+    }
+    out.push('\n');
+}
+
 /// 发现的问题
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
@@ -45,6 +204,9 @@ pub struct Finding {
     pub rule_id: Option<String>,
     /// 修复建议
     pub recommendation: Option<String>,
+    /// 既有问题：不在本次变更的行上（如全量安全扫描发现的历史问题）
+    #[serde(default)]
+    pub pre_existing: bool,
 }
 
 /// 严重程度
@@ -59,6 +221,26 @@ pub enum Severity {
     Warning,
 }
 
+impl Severity {
+    /// 严重程度排序值，越大越严重（Error/Warning 分别与 High/Medium 同级）
+    pub fn rank(&self) -> u8 {
+        match self {
+            Self::Critical => 4,
+            Self::High | Self::Error => 3,
+            Self::Medium | Self::Warning => 2,
+            Self::Low => 1,
+            Self::Info => 0,
+        }
+    }
+}
+
+impl crate::domain::entities::Scorable for Severity {
+    /// 严重 1.0、高/错误 0.8、中/警告 0.6、低 0.4、提示 0.2
+    fn score(&self) -> f64 {
+        f64::from(self.rank() + 1) / 5.0
+    }
+}
+
 impl std::str::FromStr for Severity {
     type Err = ();
 
@@ -81,7 +263,8 @@ impl std::str::FromStr for Severity {
 pub struct ReviewConfig {
     pub language: Option<String>,
     pub format: String,
-    pub output: Option<PathBuf>,
+    /// 结果文件（可有多个，格式按扩展名推断）
+    pub output: Vec<PathBuf>,
     pub tree_sitter: bool,
     pub security_scan: bool,
     pub scan_tool: Option<String>,
@@ -93,6 +276,62 @@ pub struct ReviewConfig {
     pub full: bool,
     /// 是否启用“偏离度分析”（DevOps 需求级偏离分析，保留该命名供 Issue 相关分析使用）
     pub deviation_analysis: bool,
+    /// 远程 PR/MR 链接；设置后从 DevOps 平台拉取 diff 而非读取本地变更
+    pub pr_url: Option<String>,
+    /// 是否将评审结果回帖到远程 PR/MR
+    pub post_comment: bool,
+    /// 本地评审的变更范围
+    pub diff_selection: DiffSelection,
+    /// 评审的仓库目录，未指定时为当前工作目录（不切换进程工作目录）
+    pub repo_path: Option<PathBuf>,
+    /// AI 评审的 diff token 上限，覆盖配置中的 `ai.max_diff_tokens`（0 表示不限制）
+    pub max_diff_tokens: Option<usize>,
+}
+
+/// 本地评审读取哪部分变更
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DiffSelection {
+    /// 自动推断：全部变更（含未推送的提交），没有时回退到最后一次提交
+    #[default]
+    Auto,
+    /// 仅已暂存的变更（`--staged`，适用于 pre-commit 钩子）
+    Staged,
+    /// 全部未提交的变更：已暂存 + 未暂存 + 未跟踪（`--working`）
+    Working,
+    /// 第 n 个贮藏中的变更（`--stash <n>`）
+    Stash(usize),
+    /// 指定版本的单个文件，`<rev>:<path>`（`--blob`）
+    Blob(String),
+}
+
+impl DiffSelection {
+    /// 按命令行开关选择，两者都未指定时为 Auto
+    pub fn from_flags(staged: bool, working: bool) -> Self {
+        match (staged, working) {
+            (true, _) => Self::Staged,
+            (false, true) => Self::Working,
+            (false, false) => Self::Auto,
+        }
+    }
+
+    /// 读取显式选择的变更；Auto 返回 None，由调用方按原有顺序推断
+    pub fn read_diff(&self) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.read_diff_in(Path::new("."))
+    }
+
+    /// 在 dir 所在仓库中读取显式选择的变更
+    pub fn read_diff_in(
+        &self,
+        dir: &Path,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Self::Auto => Ok(None),
+            Self::Staged => crate::git::get_diff_in(dir).map(Some),
+            Self::Working => crate::git::get_working_diff_in(dir).map(Some),
+            Self::Stash(index) => crate::git::get_stash_diff_in(dir, *index).map(Some),
+            Self::Blob(spec) => crate::git::get_blob_diff_in(dir, spec).map(Some),
+        }
+    }
 }
 
 impl ReviewConfig {
@@ -100,7 +339,7 @@ impl ReviewConfig {
     pub fn from_args(
         language: Option<String>,
         format: String,
-        output: Option<PathBuf>,
+        output: Vec<PathBuf>,
         tree_sitter: bool,
         security_scan: bool,
         scan_tool: Option<String>,
@@ -108,30 +347,66 @@ impl ReviewConfig {
         issue_id: Option<String>,
         space_id: Option<u64>,
         full: bool,
+        pr_url: Option<String>,
+        post_comment: bool,
     ) -> Self {
         let issue_ids: Vec<String> = issue_id
             .map(|ids| ids.split(',').map(|s| s.trim().to_string()).collect())
             .unwrap_or_default();
 
-        // 当指定了 scan_tool 时自动启用 security_scan
-        let security_scan = security_scan || scan_tool.is_some();
+        let mut builder = Self::builder()
+            .format(format)
+            .tree_sitter(tree_sitter)
+            .security_scan(security_scan)
+            .block_on_critical(block_on_critical)
+            .issue_ids(issue_ids)
+            .full(full)
+            .post_comment(post_comment);
+        builder.config.language = language;
+        builder.config.output = output;
+        builder.config.scan_tool = scan_tool;
+        builder.config.space_id = space_id;
+        builder.config.pr_url = pr_url;
+        builder.build()
+    }
 
-        // 偏离度分析隐式启用：当存在 Issue ID 时自动启用，否则禁用
-        let deviation_analysis = !issue_ids.is_empty();
+    /// 以编程方式构建评审配置（供库调用方与服务层使用，不依赖命令行参数顺序）
+    ///
+    /// # Examples
+    /// ```
+    /// use gitai::review::{DiffSelection, ReviewConfig};
+    ///
+    /// let config = ReviewConfig::builder()
+    ///     .language("rust")
+    ///     .format("json")
+    ///     .scan_tool("opengrep")
+    ///     .issue_ids(["#12", "#34"])
+    ///     .diff_selection(DiffSelection::Staged)
+    ///     .build();
+    /// assert_eq!(config.format, "json");
+    /// // 指定扫描工具时自动启用安全扫描，存在 Issue 时自动启用偏离度分析
+    /// assert!(config.security_scan);
+    /// assert!(config.deviation_analysis());
+    /// ```
+    pub fn builder() -> ReviewConfigBuilder {
+        ReviewConfigBuilder::default()
+    }
 
-        Self {
-            language,
-            format,
-            output,
-            tree_sitter,
-            security_scan,
-            scan_tool,
-            block_on_critical,
-            issue_ids,
-            space_id,
-            full,
-            deviation_analysis,
-        }
+    /// 指定本地评审的变更范围
+    pub fn with_diff_selection(mut self, diff_selection: DiffSelection) -> Self {
+        self.diff_selection = diff_selection;
+        self
+    }
+
+    /// 覆盖 AI 评审的 diff token 上限
+    pub fn with_max_diff_tokens(mut self, max_diff_tokens: Option<usize>) -> Self {
+        self.max_diff_tokens = max_diff_tokens;
+        self
+    }
+
+    /// 评审的仓库目录，未指定时为当前目录
+    pub fn repo_dir(&self) -> &Path {
+        self.repo_path.as_deref().unwrap_or(Path::new("."))
     }
 
     pub fn needs_issue_context(&self) -> bool {
@@ -143,6 +418,141 @@ impl ReviewConfig {
     }
 }
 
+/// [`ReviewConfig`] 构建器，未设置的选项取默认值（文本输出、不启用任何附加分析）
+#[derive(Debug, Clone)]
+pub struct ReviewConfigBuilder {
+    config: ReviewConfig,
+}
+
+impl Default for ReviewConfigBuilder {
+    fn default() -> Self {
+        Self {
+            config: ReviewConfig {
+                language: None,
+                format: "text".to_string(),
+                output: Vec::new(),
+                tree_sitter: false,
+                security_scan: false,
+                scan_tool: None,
+                block_on_critical: false,
+                issue_ids: Vec::new(),
+                space_id: None,
+                full: false,
+                deviation_analysis: false,
+                pr_url: None,
+                post_comment: false,
+                diff_selection: DiffSelection::Auto,
+                repo_path: None,
+                max_diff_tokens: None,
+            },
+        }
+    }
+}
+
+impl ReviewConfigBuilder {
+    /// 输出语言
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.config.language = Some(language.into());
+        self
+    }
+
+    /// 输出格式（text|json|markdown）
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.config.format = format.into();
+        self
+    }
+
+    /// 增加一个结果文件（可多次调用，格式按扩展名推断）
+    pub fn output(mut self, output: impl Into<PathBuf>) -> Self {
+        self.config.output.push(output.into());
+        self
+    }
+
+    /// 启用 Tree-sitter 结构分析
+    pub fn tree_sitter(mut self, enabled: bool) -> Self {
+        self.config.tree_sitter = enabled;
+        self
+    }
+
+    /// 启用安全扫描
+    pub fn security_scan(mut self, enabled: bool) -> Self {
+        self.config.security_scan = enabled;
+        self
+    }
+
+    /// 安全扫描工具（设置后自动启用安全扫描）
+    pub fn scan_tool(mut self, tool: impl Into<String>) -> Self {
+        self.config.scan_tool = Some(tool.into());
+        self
+    }
+
+    /// 发现严重问题时阻止提交
+    pub fn block_on_critical(mut self, enabled: bool) -> Self {
+        self.config.block_on_critical = enabled;
+        self
+    }
+
+    /// 关联的 Issue（存在时自动启用偏离度分析）
+    pub fn issue_ids<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.issue_ids = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Coding 空间（项目）ID
+    pub fn space_id(mut self, space_id: u64) -> Self {
+        self.config.space_id = Some(space_id);
+        self
+    }
+
+    /// 启用完整模式（依赖图、PageRank 等深入分析）
+    pub fn full(mut self, enabled: bool) -> Self {
+        self.config.full = enabled;
+        self
+    }
+
+    /// 评审远程 PR/MR
+    pub fn pr_url(mut self, pr_url: impl Into<String>) -> Self {
+        self.config.pr_url = Some(pr_url.into());
+        self
+    }
+
+    /// 将评审结果回帖到远程 PR/MR
+    pub fn post_comment(mut self, enabled: bool) -> Self {
+        self.config.post_comment = enabled;
+        self
+    }
+
+    /// 本地评审的变更范围
+    pub fn diff_selection(mut self, diff_selection: DiffSelection) -> Self {
+        self.config.diff_selection = diff_selection;
+        self
+    }
+
+    /// 评审的仓库目录
+    pub fn repo_path(mut self, repo_path: impl Into<PathBuf>) -> Self {
+        self.config.repo_path = Some(repo_path.into());
+        self
+    }
+
+    /// AI 评审的 diff token 上限（0 表示不限制）
+    pub fn max_diff_tokens(mut self, max_diff_tokens: usize) -> Self {
+        self.config.max_diff_tokens = Some(max_diff_tokens);
+        self
+    }
+
+    /// 生成配置：指定扫描工具时启用安全扫描，存在 Issue 时启用偏离度分析
+    pub fn build(self) -> ReviewConfig {
+        let mut config = self.config;
+        config.security_scan = config.security_scan || config.scan_tool.is_some();
+        config.deviation_analysis = !config.issue_ids.is_empty();
+        config
+    }
+}
+
 /// 简化的Review缓存
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewCache {
@@ -173,3 +583,179 @@ impl ReviewCache {
         now.saturating_sub(self.timestamp) > max_age_seconds
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_from_args() {
+        let from_args = ReviewConfig::from_args(
+            Some("zh".to_string()),
+            "json".to_string(),
+            vec![PathBuf::from("review.json")],
+            true,
+            false,
+            Some("opengrep".to_string()),
+            true,
+            Some("#1, #2".to_string()),
+            Some(7),
+            true,
+            None,
+            false,
+        );
+        let built = ReviewConfig::builder()
+            .language("zh")
+            .format("json")
+            .output("review.json")
+            .tree_sitter(true)
+            .scan_tool("opengrep")
+            .block_on_critical(true)
+            .issue_ids(["#1", "#2"])
+            .space_id(7)
+            .full(true)
+            .build();
+        assert_eq!(format!("{from_args:?}"), format!("{built:?}"));
+        assert!(built.security_scan && built.deviation_analysis());
+
+        let default = ReviewConfig::builder().build();
+        assert_eq!(default.format, "text");
+        assert!(!default.needs_issue_context());
+    }
+
+    #[test]
+    fn test_markdown_lists_new_dependencies() {
+        let mut details = HashMap::new();
+        details.insert(
+            "new_dependencies".to_string(),
+            "reqwest (rust); left-pad (javascript)".to_string(),
+        );
+        let result = ReviewResult {
+            success: true,
+            message: String::new(),
+            summary: "LGTM".to_string(),
+            details,
+            findings: vec![Finding {
+                title: "引入新的第三方依赖: reqwest".to_string(),
+                severity: Severity::Medium,
+                file_path: Some("src/http.rs".to_string()),
+                line: None,
+                column: None,
+                code_snippet: None,
+                message: String::new(),
+                rule_id: Some("dependency/new".to_string()),
+                recommendation: None,
+                pre_existing: false,
+            }],
+            score: Some(90),
+            recommendations: Vec::new(),
+        };
+
+        let markdown = result.to_markdown();
+        assert!(markdown.contains("评分: 90/100"));
+        assert!(markdown.contains("### 📦 新引入的依赖"));
+        assert!(markdown.contains("New dependency introduced: `reqwest (rust)`"));
+        assert!(markdown.contains("New dependency introduced: `left-pad (javascript)`"));
+        assert!(markdown.contains("(`src/http.rs`)"));
+        assert!(result.quality_note().is_none());
+    }
+
+    #[test]
+    fn test_quality_note_from_details() {
+        let details: HashMap<String, String> = [
+            ("maintainability_index", "72.5"),
+            ("max_cyclomatic_complexity", "9"),
+            ("max_cognitive_complexity", "14"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let result = ReviewResult {
+            success: true,
+            message: String::new(),
+            summary: "LGTM".to_string(),
+            details,
+            findings: Vec::new(),
+            score: None,
+            recommendations: Vec::new(),
+        };
+
+        let note = result.quality_note().unwrap();
+        assert!(note.contains("72.5"));
+        assert!(note.contains('9'));
+        assert!(note.contains("14"));
+        assert!(note.contains(crate::evaluation::ComplexityLevel::Medium.label()));
+        assert!(result.to_markdown().contains(&format!("📐 {note}")));
+    }
+
+    #[test]
+    fn test_skipped_files_note() {
+        let mut details = HashMap::new();
+        details.insert(
+            "skipped_binary_files".to_string(),
+            "a.png; b.woff; c.jar".to_string(),
+        );
+        let result = ReviewResult {
+            success: true,
+            message: String::new(),
+            summary: "LGTM".to_string(),
+            details,
+            findings: Vec::new(),
+            score: None,
+            recommendations: Vec::new(),
+        };
+        let note = result.skipped_files_note().unwrap();
+        assert!(note.contains('3'));
+        assert!(note.contains('0'));
+        assert!(result.to_markdown().contains(&format!("> {note}")));
+
+        let plain = ReviewResult {
+            details: HashMap::new(),
+            ..result
+        };
+        assert!(plain.skipped_files_note().is_none());
+    }
+
+    #[test]
+    fn test_markdown_collapses_pre_existing_findings() {
+        let finding = |title: &str, line: usize, pre_existing: bool| Finding {
+            title: title.to_string(),
+            severity: Severity::High,
+            file_path: Some("src/db.rs".to_string()),
+            line: Some(line),
+            column: None,
+            code_snippet: None,
+            message: String::new(),
+            rule_id: None,
+            recommendation: None,
+            pre_existing,
+        };
+        let result = ReviewResult {
+            success: true,
+            message: String::new(),
+            summary: "LGTM".to_string(),
+            details: HashMap::new(),
+            findings: vec![
+                finding("SQL 注入", 12, false),
+                finding("硬编码密钥", 80, true),
+            ],
+            score: None,
+            recommendations: Vec::new(),
+        };
+
+        let markdown = result.to_markdown();
+        let main = markdown.find("SQL 注入").unwrap();
+        let details = markdown.find("<details>").unwrap();
+        let old = markdown.find("硬编码密钥").unwrap();
+        assert!(main < details && details < old);
+        assert!(markdown.contains("既有问题（1 个"));
+        assert!(markdown.contains("(`src/db.rs:80`)"));
+
+        // 只有既有问题时不输出主问题列表
+        let only_old = ReviewResult {
+            findings: vec![finding("硬编码密钥", 80, true)],
+            ..result
+        };
+        assert!(!only_old.to_markdown().contains("### 🔒 发现的问题"));
+    }
+}
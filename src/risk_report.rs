@@ -0,0 +1,761 @@
+// 综合风险报告
+// `gitai report` 汇总安全扫描、质量指标与公共 API 影响，各部分给出 0-100 的风险分
+// （`Scorable::score` 为其 0-1 的归一化值），整体风险取各部分最高分并映射到 `RiskLevel`
+
+use crate::architectural_impact::api_diff::{ApiChange, ApiChangeKind, ApiDiffReport};
+use crate::architectural_impact::RiskLevel;
+use crate::domain::entities::Scorable;
+use crate::review::Severity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 风险分到风险级别的映射
+pub fn level_for_score(score: f64) -> RiskLevel {
+    if score >= 80.0 {
+        RiskLevel::Critical
+    } else if score >= 60.0 {
+        RiskLevel::High
+    } else if score >= 30.0 {
+        RiskLevel::Medium
+    } else if score > 0.0 {
+        RiskLevel::Low
+    } else {
+        RiskLevel::None
+    }
+}
+
+/// 一个严重问题的权重
+const CRITICAL_WEIGHT: f64 = 60.0;
+
+/// 单个问题的权重：由 [`Scorable::score`] 的四次方放大，`60 × score⁴`，
+/// 即严重 60、高/错误约 24.6、中/警告约 7.8、低约 1.5、提示约 0.1。
+///
+/// 风险分由多个问题累加得出，线性评分（1.0/0.8/0.6/0.4/0.2）下五个提示就与一个严重问题等分；
+/// 取四次方后每升一级权重至少翻倍，配合 [`severity_ceiling`] 的分级封顶，低级别问题的数量不会压过一个高级别问题
+fn severity_weight(severity: &Severity) -> f64 {
+    CRITICAL_WEIGHT * severity.score().powi(4)
+}
+
+/// 每个严重程度累计得分的上限：低级别问题再多也不会把风险推到更高的级别
+/// （只有提示最多 10 分，只有中/警告最多 50 分，只有高/错误最多 80 分）
+fn severity_ceiling(rank: u8) -> f64 {
+    match rank {
+        4 => 100.0,
+        3 => 80.0,
+        2 => 50.0,
+        1 => 20.0,
+        _ => 10.0,
+    }
+}
+
+/// 把一组问题的严重程度加权为 0-100 的风险分：同级问题的权重相加并按级别封顶，各级之和再封顶 100。
+/// 一个严重问题（60，High）远高于任意数量的提示（至多 10，Low）
+pub fn severity_score<I>(severities: I) -> f64
+where
+    I: IntoIterator,
+    I::Item: Borrow<Severity>,
+{
+    let mut by_rank = [0.0_f64; 5];
+    for severity in severities {
+        let severity = severity.borrow();
+        by_rank[usize::from(severity.rank())] += severity_weight(severity);
+    }
+    let score: f64 = by_rank
+        .iter()
+        .enumerate()
+        .map(|(rank, total)| total.min(severity_ceiling(rank as u8)))
+        .sum();
+    score.min(100.0)
+}
+
+fn level_label(level: &RiskLevel) -> &'static str {
+    match level {
+        RiskLevel::Critical => "严重",
+        RiskLevel::High => "高",
+        RiskLevel::Medium => "中",
+        RiskLevel::Low => "低",
+        RiskLevel::None => "无",
+    }
+}
+
+/// 报告中展示的单个安全问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopFinding {
+    pub severity: String,
+    pub file_path: PathBuf,
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    pub title: String,
+}
+
+/// 安全扫描部分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecuritySection {
+    pub total: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    /// 按严重程度排序的前若干个问题
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_findings: Vec<TopFinding>,
+    /// 扫描器报告的错误（扫描仍有部分结果）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "security")]
+impl SecuritySection {
+    /// 从扫描结果汇总，保留最严重的 top 个问题（无法识别的严重程度按 Warning 处理）
+    pub fn from_scan(result: &crate::scan::ScanResult, top: usize) -> Self {
+        use crate::scan::Severity;
+
+        let mut section = Self {
+            total: result.findings.len(),
+            error: result.error.clone(),
+            ..Self::default()
+        };
+        let mut ranked: Vec<(u8, &crate::scan::Finding)> = Vec::new();
+        for finding in &result.findings {
+            let rank = match Severity::parse(&finding.severity).unwrap_or(Severity::Warning) {
+                Severity::Error => {
+                    section.errors += 1;
+                    3
+                }
+                Severity::Warning => {
+                    section.warnings += 1;
+                    2
+                }
+                Severity::Info => {
+                    section.infos += 1;
+                    1
+                }
+            };
+            ranked.push((rank, finding));
+        }
+        // 稳定排序：同级问题保持扫描器输出顺序
+        ranked.sort_by_key(|(rank, _)| std::cmp::Reverse(*rank));
+        section.top_findings = ranked
+            .into_iter()
+            .take(top)
+            .map(|(_, f)| TopFinding {
+                severity: f.severity.clone(),
+                file_path: f.file_path.clone(),
+                line: f.line,
+                rule_id: f.rule_id.clone(),
+                title: f.title.clone(),
+            })
+            .collect();
+        section
+    }
+}
+
+impl SecuritySection {
+    /// 0-100 的风险分：按严重程度加权（见 [`severity_score`]）
+    pub fn risk_score(&self) -> f64 {
+        let repeat = |severity: Severity, count: usize| std::iter::repeat_n(severity, count);
+        severity_score(
+            repeat(Severity::Error, self.errors)
+                .chain(repeat(Severity::Warning, self.warnings))
+                .chain(repeat(Severity::Info, self.infos)),
+        )
+    }
+}
+
+/// 与上一个质量快照相比的变化（正数表示增加）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityTrend {
+    /// 上一个快照的提交与时间
+    pub since_commit: String,
+    pub since: DateTime<Utc>,
+    pub debt_score: f64,
+    pub maintainability_index: f64,
+    pub avg_complexity: f64,
+    pub lines_of_code: i64,
+}
+
+/// 质量指标部分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualitySection {
+    pub lines_of_code: usize,
+    pub debt_score: f64,
+    pub maintainability_index: f64,
+    pub avg_complexity: f64,
+    /// 没有历史快照时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trend: Option<QualityTrend>,
+}
+
+#[cfg(feature = "metrics")]
+impl QualitySection {
+    /// 由当前度量与上一个已记录的快照（若有）构造
+    pub fn from_snapshots(
+        current: &crate::metrics::QualitySnapshot,
+        previous: Option<&crate::metrics::QualitySnapshot>,
+    ) -> Self {
+        let complexity = &current.complexity_metrics;
+        Self {
+            lines_of_code: current.lines_of_code,
+            debt_score: current.technical_debt.debt_score,
+            maintainability_index: complexity.maintainability_index,
+            avg_complexity: complexity.avg_cyclomatic_complexity,
+            trend: previous.map(|prev| QualityTrend {
+                since_commit: prev.commit_hash.clone(),
+                since: prev.timestamp,
+                debt_score: current.technical_debt.debt_score - prev.technical_debt.debt_score,
+                maintainability_index: complexity.maintainability_index
+                    - prev.complexity_metrics.maintainability_index,
+                avg_complexity: complexity.avg_cyclomatic_complexity
+                    - prev.complexity_metrics.avg_cyclomatic_complexity,
+                lines_of_code: current.lines_of_code as i64 - prev.lines_of_code as i64,
+            }),
+        }
+    }
+}
+
+impl QualitySection {
+    /// 0-100 的风险分：技术债务分与（100 - 可维护性指数）的均值；可维护性指数缺失（为 0）时只看债务分。
+    /// 相比上一个快照变差的部分（债务上升、可维护性下降）额外计入
+    pub fn risk_score(&self) -> f64 {
+        let mut score = if self.maintainability_index > 0.0 {
+            (self.debt_score + (100.0 - self.maintainability_index)) / 2.0
+        } else {
+            self.debt_score
+        };
+        if let Some(trend) = &self.trend {
+            score += trend.debt_score.max(0.0) + (-trend.maintainability_index).max(0.0);
+        }
+        score.clamp(0.0, 100.0)
+    }
+}
+
+/// 公共 API 影响部分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImpactSection {
+    pub base: String,
+    pub head: String,
+    pub added: usize,
+    pub removed: usize,
+    pub signature_changed: usize,
+    /// 破坏性变更（删除或签名变化）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaking: Vec<ApiChange>,
+}
+
+impl ImpactSection {
+    pub fn from_api_diff(report: &ApiDiffReport) -> Self {
+        Self {
+            base: report.base.clone(),
+            head: report.head.clone(),
+            added: report.count(ApiChangeKind::Added),
+            removed: report.count(ApiChangeKind::Removed),
+            signature_changed: report.count(ApiChangeKind::SignatureChanged),
+            breaking: report
+                .changes
+                .iter()
+                .filter(|c| c.breaking)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl ImpactSection {
+    /// 0-100 的风险分：每个破坏性变更 20 分
+    pub fn risk_score(&self) -> f64 {
+        ((self.breaking.len() * 20) as f64).min(100.0)
+    }
+}
+
+// 各部分的评分为 0-100 风险分的归一化值
+
+impl Scorable for SecuritySection {
+    fn score(&self) -> f64 {
+        self.risk_score() / 100.0
+    }
+}
+
+impl Scorable for QualitySection {
+    fn score(&self) -> f64 {
+        self.risk_score() / 100.0
+    }
+}
+
+impl Scorable for ImpactSection {
+    fn score(&self) -> f64 {
+        self.risk_score() / 100.0
+    }
+}
+
+impl Scorable for RiskReport {
+    fn score(&self) -> f64 {
+        self.risk_score() / 100.0
+    }
+}
+
+/// 综合风险报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskReport {
+    pub generated_at: DateTime<Utc>,
+    pub path: PathBuf,
+    pub risk_score: f64,
+    pub risk_level: RiskLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecuritySection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<QualitySection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<ImpactSection>,
+    /// 未生成的部分及原因（被参数关闭、功能未启用或执行失败）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub skipped: BTreeMap<String, String>,
+}
+
+impl RiskReport {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            generated_at: Utc::now(),
+            path: path.into(),
+            risk_score: 0.0,
+            risk_level: RiskLevel::None,
+            security: None,
+            quality: None,
+            impact: None,
+            skipped: BTreeMap::new(),
+        }
+    }
+
+    /// 记录未生成的部分
+    pub fn skip(&mut self, section: &str, reason: impl Into<String>) {
+        self.skipped.insert(section.to_string(), reason.into());
+    }
+
+    /// 0-100 的整体风险分：各部分的最高分
+    pub fn risk_score(&self) -> f64 {
+        self.sections()
+            .into_iter()
+            .map(|(_, score)| score)
+            .fold(0.0, f64::max)
+    }
+
+    /// 根据已生成的部分计算整体风险分与级别
+    pub fn finalize(mut self) -> Self {
+        self.risk_score = self.risk_score();
+        self.risk_level = level_for_score(self.risk_score);
+        self
+    }
+
+    /// 已生成的部分及其风险分
+    fn sections(&self) -> Vec<(&'static str, f64)> {
+        let mut sections = Vec::new();
+        if let Some(s) = &self.security {
+            sections.push(("安全扫描", s.risk_score()));
+        }
+        if let Some(s) = &self.quality {
+            sections.push(("质量指标", s.risk_score()));
+        }
+        if let Some(s) = &self.impact {
+            sections.push(("API 影响", s.risk_score()));
+        }
+        sections
+    }
+
+    /// Markdown 报告
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# 综合风险报告\n\n- 路径：`{}`\n- 生成时间：{}\n- 整体风险：**{}**（{:.0}/100）\n",
+            self.path.display(),
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            level_label(&self.risk_level),
+            self.risk_score
+        );
+        let sections = self.sections();
+        if !sections.is_empty() {
+            out.push_str("\n| 部分 | 风险分 | 风险级别 |\n|---|---|---|\n");
+            for (name, score) in sections {
+                out.push_str(&format!(
+                    "| {} | {:.0} | {} |\n",
+                    name,
+                    score,
+                    level_label(&level_for_score(score))
+                ));
+            }
+        }
+
+        if let Some(s) = &self.security {
+            out.push_str(&format!(
+                "\n## 安全扫描\n\n- 问题数：{}（错误 {} / 警告 {} / 提示 {}）\n",
+                s.total, s.errors, s.warnings, s.infos
+            ));
+            if let Some(error) = &s.error {
+                out.push_str(&format!("- 扫描错误：{}\n", error));
+            }
+            if !s.top_findings.is_empty() {
+                out.push_str("\n| 严重程度 | 位置 | 规则 | 问题 |\n|---|---|---|---|\n");
+                for f in &s.top_findings {
+                    out.push_str(&format!(
+                        "| {} | `{}:{}` | {} | {} |\n",
+                        f.severity,
+                        f.file_path.display(),
+                        f.line,
+                        f.rule_id.as_deref().unwrap_or("-"),
+                        f.title.replace('|', "\\|")
+                    ));
+                }
+            }
+        }
+
+        if let Some(q) = &self.quality {
+            out.push_str(&format!(
+                "\n## 质量指标\n\n- 代码行数：{}\n- 技术债务：{:.1}\n- 可维护性指数：{:.1}\n- 平均复杂度：{:.1}\n",
+                q.lines_of_code, q.debt_score, q.maintainability_index, q.avg_complexity
+            ));
+            match &q.trend {
+                Some(t) => out.push_str(&format!(
+                    "\n相比上一个快照（{}，{}）：技术债务 {:+.1}，可维护性指数 {:+.1}，平均复杂度 {:+.1}，代码行数 {:+}\n",
+                    short_commit(&t.since_commit),
+                    t.since.format("%Y-%m-%d"),
+                    t.debt_score,
+                    t.maintainability_index,
+                    t.avg_complexity,
+                    t.lines_of_code
+                )),
+                None => out.push_str("\n暂无历史快照，可用 `gitai metrics record` 记录\n"),
+            }
+        }
+
+        if let Some(i) = &self.impact {
+            out.push_str(&format!(
+                "\n## API 影响（{} → {}）\n\n- 新增 {} / 删除 {} / 签名变化 {}（破坏性 {}）\n",
+                i.base,
+                i.head,
+                i.added,
+                i.removed,
+                i.signature_changed,
+                i.breaking.len()
+            ));
+            if !i.breaking.is_empty() {
+                out.push_str("\n| 变化 | 名称 | 文件 |\n|---|---|---|\n");
+                for c in &i.breaking {
+                    out.push_str(&format!(
+                        "| {} | `{}` | `{}` |\n",
+                        change_label(&c.change),
+                        c.name,
+                        c.file_path
+                    ));
+                }
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            out.push_str("\n## 未生成的部分\n\n");
+            for (section, reason) in &self.skipped {
+                out.push_str(&format!("- {}：{}\n", section, reason));
+            }
+        }
+        out
+    }
+
+    /// 独立的 HTML 报告
+    pub fn to_html(&self) -> String {
+        let mut body = format!(
+            "<h1>综合风险报告</h1>\n<p>路径 <code>{}</code>，生成于 {}，整体风险 <strong>{}</strong>（{:.0}/100）</p>\n",
+            escape_html(&self.path.display().to_string()),
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            level_label(&self.risk_level),
+            self.risk_score
+        );
+        let sections = self.sections();
+        if !sections.is_empty() {
+            body.push_str("<table><tr><th>部分</th><th>风险分</th><th>风险级别</th></tr>\n");
+            for (name, score) in sections {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td>{:.0}</td><td>{}</td></tr>\n",
+                    name,
+                    score,
+                    level_label(&level_for_score(score))
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        if let Some(s) = &self.security {
+            body.push_str(&format!(
+                "<h2>安全扫描</h2>\n<p>问题数 {}（错误 {} / 警告 {} / 提示 {}）</p>\n",
+                s.total, s.errors, s.warnings, s.infos
+            ));
+            if !s.top_findings.is_empty() {
+                body.push_str(
+                    "<table><tr><th>严重程度</th><th>位置</th><th>规则</th><th>问题</th></tr>\n",
+                );
+                for f in &s.top_findings {
+                    body.push_str(&format!(
+                        "<tr><td>{}</td><td><code>{}:{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&f.severity),
+                        escape_html(&f.file_path.display().to_string()),
+                        f.line,
+                        escape_html(f.rule_id.as_deref().unwrap_or("-")),
+                        escape_html(&f.title)
+                    ));
+                }
+                body.push_str("</table>\n");
+            }
+        }
+
+        if let Some(q) = &self.quality {
+            body.push_str(&format!(
+                "<h2>质量指标</h2>\n<p>代码行数 {}，技术债务 {:.1}，可维护性指数 {:.1}，平均复杂度 {:.1}</p>\n",
+                q.lines_of_code, q.debt_score, q.maintainability_index, q.avg_complexity
+            ));
+            if let Some(t) = &q.trend {
+                body.push_str(&format!(
+                    "<p>相比上一个快照（{}）：技术债务 {:+.1}，可维护性指数 {:+.1}，平均复杂度 {:+.1}，代码行数 {:+}</p>\n",
+                    escape_html(short_commit(&t.since_commit)),
+                    t.debt_score,
+                    t.maintainability_index,
+                    t.avg_complexity,
+                    t.lines_of_code
+                ));
+            }
+        }
+
+        if let Some(i) = &self.impact {
+            body.push_str(&format!(
+                "<h2>API 影响（{} → {}）</h2>\n<p>新增 {} / 删除 {} / 签名变化 {}（破坏性 {}）</p>\n",
+                escape_html(&i.base),
+                escape_html(&i.head),
+                i.added,
+                i.removed,
+                i.signature_changed,
+                i.breaking.len()
+            ));
+            if !i.breaking.is_empty() {
+                body.push_str("<table><tr><th>变化</th><th>名称</th><th>文件</th></tr>\n");
+                for c in &i.breaking {
+                    body.push_str(&format!(
+                        "<tr><td>{}</td><td><code>{}</code></td><td><code>{}</code></td></tr>\n",
+                        change_label(&c.change),
+                        escape_html(&c.name),
+                        escape_html(&c.file_path)
+                    ));
+                }
+                body.push_str("</table>\n");
+            }
+        }
+
+        if !self.skipped.is_empty() {
+            body.push_str("<h2>未生成的部分</h2>\n<ul>\n");
+            for (section, reason) in &self.skipped {
+                body.push_str(&format!(
+                    "<li>{}：{}</li>\n",
+                    escape_html(section),
+                    escape_html(reason)
+                ));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>综合风险报告</title>\n\
+             <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+             td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style></head>\n\
+             <body>{body}</body></html>\n"
+        )
+    }
+
+    /// 按格式渲染（json|markdown|html），供多目标输出使用
+    pub fn render(&self, format: &str) -> Result<String> {
+        Ok(match format {
+            "json" => serde_json::to_string_pretty(self)?,
+            "html" => self.to_html(),
+            _ => self.to_markdown(),
+        })
+    }
+}
+
+fn change_label(change: &ApiChangeKind) -> &'static str {
+    match change {
+        ApiChangeKind::Added => "新增",
+        ApiChangeKind::Removed => "删除",
+        ApiChangeKind::SignatureChanged => "签名变化",
+    }
+}
+
+fn short_commit(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architectural_impact::api_diff::ApiItemKind;
+
+    fn removed(name: &str) -> ApiChange {
+        ApiChange {
+            change: ApiChangeKind::Removed,
+            kind: ApiItemKind::Function,
+            name: name.to_string(),
+            file_path: "src/lib.rs".to_string(),
+            before: Some(format!("fn {name}()")),
+            after: None,
+            breaking: true,
+        }
+    }
+
+    #[test]
+    fn test_level_for_score_thresholds() {
+        assert_eq!(level_for_score(0.0), RiskLevel::None);
+        assert_eq!(level_for_score(0.5), RiskLevel::Low);
+        assert_eq!(level_for_score(30.0), RiskLevel::Medium);
+        assert_eq!(level_for_score(60.0), RiskLevel::High);
+        assert_eq!(level_for_score(80.0), RiskLevel::Critical);
+    }
+
+    fn assert_score(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_severity_score_weighting() {
+        assert_eq!(severity_score(Vec::<Severity>::new()), 0.0);
+
+        // 再多的提示也只是低风险，一个严重问题即为高风险
+        let infos = vec![Severity::Info; 1000];
+        assert_eq!(severity_score(&infos), 10.0);
+        assert_eq!(level_for_score(severity_score(&infos)), RiskLevel::Low);
+        assert_eq!(severity_score([Severity::Critical]), 60.0);
+        assert_eq!(
+            level_for_score(severity_score([Severity::Critical])),
+            RiskLevel::High
+        );
+
+        // 同级封顶：警告至多 50，错误/高至多 80；Error 与 High 同级
+        assert_score(severity_score(vec![Severity::Warning; 6]), 6.0 * 7.776);
+        assert_eq!(severity_score(vec![Severity::Warning; 7]), 50.0);
+        assert_score(
+            severity_score([Severity::Error, Severity::High, Severity::Error]),
+            3.0 * 24.576,
+        );
+        assert_eq!(severity_score(vec![Severity::Error; 4]), 80.0);
+
+        // 各级之和封顶 100
+        let mixed = [Severity::Critical, Severity::Critical, Severity::Info];
+        assert_eq!(severity_score(mixed), 100.0);
+        assert_score(
+            severity_score([Severity::Critical, Severity::Low, Severity::Info]),
+            60.0 + 1.536 + 0.096,
+        );
+    }
+
+    #[test]
+    fn test_severity_weight_follows_score_order() {
+        let ordered = [
+            Severity::Info,
+            Severity::Low,
+            Severity::Medium,
+            Severity::High,
+            Severity::Critical,
+        ];
+        for severity in &ordered {
+            assert_score(
+                severity_weight(severity),
+                CRITICAL_WEIGHT * severity.score().powi(4),
+            );
+        }
+        for pair in ordered.windows(2) {
+            assert!(pair[0].score() < pair[1].score());
+            assert!(severity_weight(&pair[0]) < severity_weight(&pair[1]));
+            // 权重逐级至少翻倍
+            assert!(severity_weight(&pair[1]) >= 2.0 * severity_weight(&pair[0]));
+        }
+        // 线性评分下 5 个提示即等于 1 个严重问题，加权后远低于它
+        assert!((5.0 * Severity::Info.score() - Severity::Critical.score()).abs() < 1e-9);
+        assert!(severity_score(vec![Severity::Info; 5]) < severity_score([Severity::Critical]));
+        // Error/Warning 与 High/Medium 同级
+        assert_eq!(
+            severity_weight(&Severity::Error),
+            severity_weight(&Severity::High)
+        );
+        assert_eq!(Severity::Warning.score(), Severity::Medium.score());
+    }
+
+    #[test]
+    fn test_section_scores() {
+        let security = SecuritySection {
+            total: 3,
+            errors: 1,
+            warnings: 2,
+            ..SecuritySection::default()
+        };
+        assert_score(security.risk_score(), 24.576 + 2.0 * 7.776);
+
+        let quality = QualitySection {
+            debt_score: 20.0,
+            maintainability_index: 80.0,
+            trend: Some(QualityTrend {
+                since_commit: "abc".to_string(),
+                since: Utc::now(),
+                debt_score: 5.0,
+                maintainability_index: 2.0,
+                avg_complexity: 0.0,
+                lines_of_code: 10,
+            }),
+            ..QualitySection::default()
+        };
+        // (20 + 20) / 2 + 债务上升 5；可维护性上升不减分
+        assert_eq!(quality.risk_score(), 25.0);
+
+        let impact = ImpactSection {
+            breaking: (0..6).map(|i| removed(&format!("f{i}"))).collect(),
+            ..ImpactSection::default()
+        };
+        assert_eq!(impact.risk_score(), 100.0);
+        assert_eq!(level_for_score(impact.risk_score()), RiskLevel::Critical);
+        assert_eq!(impact.score(), 1.0);
+        assert_eq!(security.score(), security.risk_score() / 100.0);
+    }
+
+    #[test]
+    fn test_report_takes_worst_section_and_renders() {
+        let mut report = RiskReport::new(".");
+        report.impact = Some(ImpactSection::from_api_diff(&ApiDiffReport {
+            base: "HEAD~1".to_string(),
+            head: "HEAD".to_string(),
+            base_api_count: 3,
+            head_api_count: 1,
+            changes: vec![removed("parse"), removed("render")],
+        }));
+        report.security = Some(SecuritySection::default());
+        report.skip("quality", "已通过 --no-metrics 关闭");
+        let report = report.finalize();
+
+        assert_eq!(report.risk_score, 40.0);
+        assert_eq!(report.risk_level, RiskLevel::Medium);
+
+        let markdown = report.render("markdown").unwrap();
+        assert!(markdown.contains("整体风险：**中**"));
+        assert!(markdown.contains("| 删除 | `parse` |"));
+        assert!(markdown.contains("- quality：已通过 --no-metrics 关闭"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.render("json").unwrap()).unwrap();
+        assert_eq!(json["risk_level"], "Medium");
+        assert!(json.get("quality").is_none());
+        assert!(report
+            .render("html")
+            .unwrap()
+            .contains("<code>render</code>"));
+    }
+}
@@ -0,0 +1,400 @@
+// 多来源规则组合
+// 按 `[[scan.sources]]` 的顺序解析托管规则、本地目录与额外的 Git 规则仓库，并按规则 ID 合并：
+// 后面的来源覆盖前面同 ID 的规则，便于在组织基线规则之上叠加团队规则。
+// 每个 Git 来源单独缓存在 `~/.cache/gitai/rule-sources/<名称>`，并在 `.source.meta` 中记录各自的版本
+
+use crate::config::{Config, RuleSourceConfig, RuleSourceKind};
+use crate::scan::RulesInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+/// Git 来源的版本元信息文件
+const SOURCE_META: &str = ".source.meta";
+
+/// 合并结果所在的子目录
+const MERGED_DIR: &str = "merged";
+
+/// `--rules` / `custom_rules_dir` 作为最后一个来源参与合并时的名称
+const CUSTOM_SOURCE_NAME: &str = "custom";
+
+/// 单个来源的解析结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSourceSummary {
+    /// 来源名称
+    pub name: String,
+    /// 来源类型（managed / local / git）
+    pub kind: String,
+    /// 规则所在位置（目录或仓库地址）
+    pub location: String,
+    /// 来源版本：托管规则为更新时间，Git 来源为提交哈希，本地目录为空
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    /// 合并后由该来源提供的规则数
+    pub rules: usize,
+    /// 覆盖了前面来源中同 ID 规则的条数
+    pub overrides: usize,
+}
+
+/// 合并后的规则集
+#[derive(Debug, Clone)]
+pub struct ResolvedRules {
+    /// 合并后的规则目录，直接作为 OpenGrep 的 `--config`
+    pub dir: PathBuf,
+    /// 按合并顺序排列的来源
+    pub sources: Vec<RuleSourceSummary>,
+}
+
+impl ResolvedRules {
+    /// 合并后的规则总数
+    pub fn total_rules(&self) -> usize {
+        self.sources.iter().map(|s| s.rules).sum()
+    }
+
+    /// 转换为扫描结果中的规则元信息
+    pub fn rules_info(&self) -> RulesInfo {
+        RulesInfo {
+            dir: self.dir.display().to_string(),
+            sources: self.sources.iter().map(|s| s.location.clone()).collect(),
+            total_rules: self.total_rules(),
+            updated_at: None,
+            layers: self.sources.clone(),
+        }
+    }
+}
+
+/// Git 来源的版本记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SourceMeta {
+    url: String,
+    #[serde(default)]
+    rev: Option<String>,
+    commit: String,
+    updated_at: String,
+}
+
+/// 规则来源管理：解析、缓存并合并各个来源
+pub struct RuleManager {
+    sources: Vec<RuleSourceConfig>,
+    managed_dir: PathBuf,
+    cache_dir: PathBuf,
+}
+
+impl RuleManager {
+    /// 按配置创建；设置了 `custom_rules_dir` 时作为最后一个本地来源
+    pub fn new(config: &Config) -> Self {
+        let mut sources = config.scan.sources.clone();
+        if let Some(custom) = config.scan.custom_rules_dir.as_deref() {
+            sources.push(RuleSourceConfig {
+                name: CUSTOM_SOURCE_NAME.to_string(),
+                kind: RuleSourceKind::Local {
+                    path: custom.to_string(),
+                },
+            });
+        }
+        Self {
+            sources,
+            managed_dir: crate::utils::paths::resolve_rules_dir(config.scan.rules_dir.as_deref()),
+            cache_dir: crate::utils::paths::rule_sources_dir(),
+        }
+    }
+
+    /// 使用指定的缓存目录（Git 来源与合并结果都写在这里）
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    /// 重新拉取全部 Git 来源，返回更新后的来源版本
+    pub fn refresh(&self) -> Result<Vec<RuleSourceSummary>> {
+        let mut updated = Vec::new();
+        for source in &self.sources {
+            if let RuleSourceKind::Git { url, rev } = &source.kind {
+                let meta = self.fetch_git(&source.name, url, rev.as_deref())?;
+                updated.push(RuleSourceSummary {
+                    name: source.name.clone(),
+                    kind: source.kind.label().to_string(),
+                    location: url.clone(),
+                    version: Some(meta.commit),
+                    rules: 0,
+                    overrides: 0,
+                });
+            }
+        }
+        Ok(updated)
+    }
+
+    /// 解析全部来源并按规则 ID 合并，结果写入缓存目录下的 `merged`
+    pub fn resolve(&self, lang: Option<&str>) -> Result<ResolvedRules> {
+        // 规则 ID -> (来源序号, 规则原文)；后出现的覆盖先出现的
+        let mut winners: HashMap<String, (usize, String)> = HashMap::new();
+        let mut order: Vec<String> = Vec::new();
+        let mut summaries = Vec::new();
+
+        for (index, source) in self.sources.iter().enumerate() {
+            let (dirs, location, version) = self.locate(source, lang)?;
+            let mut overrides = 0;
+            for dir in &dirs {
+                for file in crate::rules::rule_files(dir) {
+                    let Some(content) = read_rule_file(&file) else {
+                        continue;
+                    };
+                    for block in crate::rules::rule_blocks(&content) {
+                        match winners.insert(block.id.clone(), (index, block.text)) {
+                            Some((previous, _)) if previous != index => overrides += 1,
+                            Some(_) => {}
+                            None => order.push(block.id),
+                        }
+                    }
+                }
+            }
+            summaries.push(RuleSourceSummary {
+                name: source.name.clone(),
+                kind: source.kind.label().to_string(),
+                location,
+                version,
+                rules: 0,
+                overrides,
+            });
+        }
+
+        // 每个来源写一个文件，只包含合并后由它提供的规则
+        let mut per_source: Vec<Vec<&str>> = vec![Vec::new(); self.sources.len()];
+        for id in &order {
+            let (index, text) = &winners[id];
+            per_source[*index].push(text);
+        }
+        let merged_dir = self.cache_dir.join(MERGED_DIR);
+        if merged_dir.exists() {
+            std::fs::remove_dir_all(&merged_dir)?;
+        }
+        std::fs::create_dir_all(&merged_dir)?;
+        for (index, blocks) in per_source.iter().enumerate() {
+            summaries[index].rules = blocks.len();
+            if blocks.is_empty() {
+                continue;
+            }
+            let file = merged_dir.join(format!("{:02}-{}.yml", index, self.sources[index].name));
+            std::fs::write(file, format!("rules:\n{}\n", blocks.join("\n")))?;
+        }
+
+        let resolved = ResolvedRules {
+            dir: merged_dir,
+            sources: summaries,
+        };
+        if resolved.total_rules() == 0 {
+            return Err("规则来源合并后没有可用的规则，请检查 [[scan.sources]] 配置".into());
+        }
+        log::info!(
+            "规则来源合并完成: {} 条规则，目录: {}",
+            resolved.total_rules(),
+            resolved.dir.display()
+        );
+        Ok(resolved)
+    }
+
+    /// 来源的规则目录、位置描述与版本
+    fn locate(
+        &self,
+        source: &RuleSourceConfig,
+        lang: Option<&str>,
+    ) -> Result<(Vec<PathBuf>, String, Option<String>)> {
+        match &source.kind {
+            RuleSourceKind::Managed => {
+                let dirs = crate::scan::managed_rule_dirs(&self.managed_dir, lang);
+                if dirs.is_empty() {
+                    log::warn!(
+                        "托管规则来源 {} 没有可用规则，可运行 `gitai scan --update-rules` 下载: {}",
+                        source.name,
+                        self.managed_dir.display()
+                    );
+                }
+                let version =
+                    crate::scan::read_rules_info(&self.managed_dir).and_then(|i| i.updated_at);
+                Ok((dirs, self.managed_dir.display().to_string(), version))
+            }
+            RuleSourceKind::Local { path } => {
+                let dir =
+                    crate::scan::validate_custom_rules(&crate::utils::paths::expand_user(path))
+                        .map_err(|e| format!("规则来源 {}: {e}", source.name))?;
+                let location = dir.display().to_string();
+                Ok((vec![dir], location, None))
+            }
+            RuleSourceKind::Git { url, rev } => {
+                let dir = self.cache_dir.join(&source.name);
+                let meta = match read_meta(&dir) {
+                    Some(meta) if meta.url == *url && meta.rev == *rev => meta,
+                    // 未缓存或配置的地址/版本已变化时重新拉取
+                    _ => self.fetch_git(&source.name, url, rev.as_deref())?,
+                };
+                Ok((vec![dir], url.clone(), Some(meta.commit)))
+            }
+        }
+    }
+
+    /// 浅克隆 Git 来源到缓存目录并记录版本；先克隆到临时目录，成功后再替换旧缓存
+    fn fetch_git(&self, name: &str, url: &str, rev: Option<&str>) -> Result<SourceMeta> {
+        std::fs::create_dir_all(&self.cache_dir)?;
+        let target = self.cache_dir.join(name);
+        let staging = self.cache_dir.join(format!(".{name}.tmp"));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+
+        crate::progress!("⬇️  正在拉取规则来源 {name}: {url}");
+        let mut cmd = Command::new("git");
+        cmd.args(["clone", "--depth", "1", "--quiet"]);
+        if let Some(rev) = rev {
+            cmd.args(["--branch", rev]);
+        }
+        let output = cmd.arg("--").arg(url).arg(&staging).output()?;
+        if !output.status.success() {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(format!(
+                "拉取规则来源 {name} 失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+
+        let head = Command::new("git")
+            .arg("-C")
+            .arg(&staging)
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()?;
+        let meta = SourceMeta {
+            url: url.to_string(),
+            rev: rev.map(str::to_string),
+            commit: String::from_utf8_lossy(&head.stdout).trim().to_string(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        std::fs::write(
+            staging.join(SOURCE_META),
+            serde_json::to_string_pretty(&meta)?,
+        )?;
+
+        if target.exists() {
+            std::fs::remove_dir_all(&target)?;
+        }
+        std::fs::rename(&staging, &target)?;
+        Ok(meta)
+    }
+}
+
+fn read_meta(dir: &Path) -> Option<SourceMeta> {
+    let content = std::fs::read_to_string(dir.join(SOURCE_META)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 读取规则文件；跳过 pre-commit 等不含 `rules:` 的 YAML
+fn read_rule_file(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    if name.contains("pre-commit") {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .take(200)
+        .any(|line| line.trim_start().starts_with("rules:"))
+        .then_some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_rules(dir: &Path, file: &str, rules: &[(&str, &str)]) {
+        let mut content = String::from("rules:\n");
+        for (id, message) in rules {
+            content.push_str(&format!(
+                "  - id: {id}\n    message: {message}\n    languages: [python]\n    severity: WARNING\n    pattern: {id}(...)\n"
+            ));
+        }
+        std::fs::write(dir.join(file), content).unwrap();
+    }
+
+    fn local(name: &str, dir: &Path) -> RuleSourceConfig {
+        RuleSourceConfig {
+            name: name.to_string(),
+            kind: RuleSourceKind::Local {
+                path: dir.display().to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_later_sources_override_by_rule_id() {
+        let baseline = tempfile::tempdir().unwrap();
+        let team = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_rules(
+            baseline.path(),
+            "base.yml",
+            &[("no-eval", "baseline eval"), ("no-exec", "baseline exec")],
+        );
+        write_rules(
+            team.path(),
+            "team.yaml",
+            &[("no-eval", "team eval"), ("no-print", "team print")],
+        );
+
+        let mut config = Config::default();
+        config.scan.sources = vec![
+            local("baseline", baseline.path()),
+            local("team", team.path()),
+        ];
+        let resolved = RuleManager::new(&config)
+            .with_cache_dir(cache.path())
+            .resolve(None)
+            .unwrap();
+
+        assert_eq!(resolved.total_rules(), 3);
+        assert_eq!(resolved.sources[0].rules, 1);
+        assert_eq!(resolved.sources[0].overrides, 0);
+        assert_eq!(resolved.sources[1].rules, 2);
+        assert_eq!(resolved.sources[1].overrides, 1);
+
+        let team_file = std::fs::read_to_string(resolved.dir.join("01-team.yml")).unwrap();
+        assert!(team_file.contains("team eval"));
+        let base_file = std::fs::read_to_string(resolved.dir.join("00-baseline.yml")).unwrap();
+        assert!(!base_file.contains("no-eval"));
+        assert!(base_file.contains("no-exec"));
+
+        let info = resolved.rules_info();
+        assert_eq!(info.total_rules, 3);
+        assert_eq!(info.layers.len(), 2);
+    }
+
+    #[test]
+    fn test_custom_rules_dir_is_last_source() {
+        let team = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        write_rules(team.path(), "team.yml", &[("no-eval", "team eval")]);
+
+        let mut config = Config::default();
+        config.scan.sources = vec![local("team", team.path())];
+        config.scan.custom_rules_dir = Some(team.path().display().to_string());
+        let resolved = RuleManager::new(&config)
+            .with_cache_dir(cache.path())
+            .resolve(None)
+            .unwrap();
+        assert_eq!(resolved.sources.len(), 2);
+        assert_eq!(resolved.sources[1].name, CUSTOM_SOURCE_NAME);
+        assert_eq!(resolved.sources[1].rules, 1);
+        assert_eq!(resolved.sources[0].rules, 0);
+
+        // 没有任何可用规则时报错
+        let empty = tempfile::tempdir().unwrap();
+        std::fs::write(empty.path().join("notes.yml"), "title: x\n").unwrap();
+        config.scan.sources = vec![local("empty", empty.path())];
+        config.scan.custom_rules_dir = None;
+        assert!(RuleManager::new(&config)
+            .with_cache_dir(cache.path())
+            .resolve(None)
+            .is_err());
+    }
+}
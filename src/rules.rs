@@ -0,0 +1,434 @@
+// 扫描规则查询模块
+// 在本地规则缓存中按规则 ID 查找 OpenGrep 规则，供 `gitai explain` 展示说明、严重程度与示例。
+// 规则文件是 YAML，这里只解析需要的字段，不引入完整的 YAML 解析器
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 遍历规则目录时的最大深度，避免误指向大目录时扫描过久
+const MAX_DEPTH: usize = 8;
+
+/// 示例代码最多展示的行数
+const MAX_EXAMPLE_LINES: usize = 8;
+
+/// 规则元数据中的本地化说明字段
+const LOCALIZED_KEYS: &[&str] = &["message-zh", "message_zh", "message-zh-cn", "message_zh_cn"];
+
+/// 一条扫描规则的说明
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RuleInfo {
+    /// 规则 ID（规则文件中的 `id`）
+    pub id: String,
+    /// 规则文件路径
+    pub path: PathBuf,
+    /// 规则说明（`message`）
+    pub message: String,
+    /// 严重程度（ERROR / WARNING / INFO）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// 适用语言
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// 元数据中的标量字段（cwe、category、references 等，列表以 `, ` 连接）
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// 规则测试文件中标注 `ruleid: <id>` 的示例代码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+    /// 本地化说明（元数据中的 `message-zh` 等字段，或 `--translate` 的翻译结果）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localized_message: Option<String>,
+}
+
+impl RuleInfo {
+    /// 规则 ID 是否与查询匹配
+    ///
+    /// OpenGrep 报告的 check_id 带有规则文件的目录前缀（如 `java.lang.security.foo`），
+    /// 因此查询以 `.<id>` 结尾时也视为匹配
+    pub fn matches(&self, query: &str) -> bool {
+        query == self.id || query.ends_with(&format!(".{}", self.id))
+    }
+
+    /// 人类可读的说明
+    pub fn to_text(&self) -> String {
+        let mut out = format!("📘 {}\n", self.id);
+        if let Some(severity) = &self.severity {
+            out.push_str(&format!(
+                "{}\n",
+                crate::tr!("explain.severity", severity = severity)
+            ));
+        }
+        if !self.languages.is_empty() {
+            out.push_str(&format!(
+                "{}\n",
+                crate::tr!("explain.languages", languages = self.languages.join(", "))
+            ));
+        }
+        out.push_str(&format!(
+            "{}\n",
+            crate::tr!("explain.file", path = self.path.display())
+        ));
+        out.push_str(&format!("\n{}\n", crate::tr!("explain.description")));
+        for line in self.message.lines() {
+            out.push_str(&format!("  {line}\n"));
+        }
+        if let Some(localized) = &self.localized_message {
+            out.push_str(&format!("\n{}\n", crate::tr!("explain.localized")));
+            for line in localized.lines() {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+        if !self.metadata.is_empty() {
+            out.push_str(&format!("\n{}\n", crate::tr!("explain.metadata")));
+            for (key, value) in &self.metadata {
+                out.push_str(&format!("  {key}: {value}\n"));
+            }
+        }
+        if let Some(example) = &self.example {
+            out.push_str(&format!("\n{}\n", crate::tr!("explain.example")));
+            for line in example.lines() {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// 在规则目录中查找规则；找不到返回 None
+pub fn find_rule(rules_dir: &Path, query: &str) -> Option<RuleInfo> {
+    let short_id = query.rsplit('.').next().unwrap_or(query);
+    let mut files = Vec::new();
+    collect_rule_files(rules_dir, 0, &mut files);
+    files.sort();
+    files.into_iter().find_map(|path| {
+        let content = std::fs::read_to_string(&path).ok()?;
+        // 先做廉价的文本过滤，只解析可能包含该规则的文件
+        if !content.contains(short_id) {
+            return None;
+        }
+        let mut rule = parse_rules(&content, &path)
+            .into_iter()
+            .find(|rule| rule.matches(query))?;
+        rule.example = find_example(&path, &rule.id);
+        Some(rule)
+    })
+}
+
+/// 规则目录下的全部 YAML 文件（跳过隐藏文件与目录），按路径排序
+pub fn rule_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_rule_files(dir, 0, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_rule_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_rule_files(&path, depth + 1, files);
+        } else if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yml" | "yaml")
+        ) {
+            files.push(path);
+        }
+    }
+}
+
+/// 规则文件中单条规则的原文，用于多来源合并时按规则 ID 覆盖
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleBlock {
+    /// 规则 ID
+    pub id: String,
+    /// 规则原文，已统一为两个空格缩进的 `- id:` 列表项，可直接拼接在 `rules:` 之下
+    pub text: String,
+}
+
+/// 按 `- id:` 列表项切分规则文件，返回 (ID, 起始行, 结束行)
+fn rule_spans(lines: &[&str]) -> Vec<(String, usize, usize)> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let Some(id) = line.trim_start().strip_prefix("- id:") else {
+            i += 1;
+            continue;
+        };
+        let item_indent = indent_of(line);
+        let end = (i + 1..lines.len())
+            .find(|&j| is_content(lines[j]) && indent_of(lines[j]) <= item_indent)
+            .unwrap_or(lines.len());
+        spans.push((unquote(id.trim()), i, end));
+        i = end;
+    }
+    spans
+}
+
+/// 提取规则文件中每条规则的原文
+pub fn rule_blocks(content: &str) -> Vec<RuleBlock> {
+    let lines: Vec<&str> = content.lines().collect();
+    rule_spans(&lines)
+        .into_iter()
+        .map(|(id, start, end)| {
+            let item_indent = indent_of(lines[start]);
+            let body: Vec<String> = lines[start..end]
+                .iter()
+                .map(|line| {
+                    let strip = indent_of(line).min(item_indent);
+                    format!("  {}", &line[strip..])
+                })
+                .collect();
+            let text = body.join("\n").trim_end().to_string();
+            RuleBlock { id, text }
+        })
+        .collect()
+}
+
+/// 解析规则文件中的全部规则
+pub fn parse_rules(content: &str, path: &Path) -> Vec<RuleInfo> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut rules = Vec::new();
+    for (id, i, end) in rule_spans(&lines) {
+        let mut rule = RuleInfo {
+            id,
+            path: path.to_path_buf(),
+            ..Default::default()
+        };
+        for (key, inline, children) in mapping(&lines[i + 1..end]) {
+            match key {
+                "message" => rule.message = scalar(inline, &children),
+                "severity" => rule.severity = Some(scalar(inline, &children)),
+                "languages" => rule.languages = list(inline, &children),
+                "metadata" => {
+                    for (meta_key, meta_inline, meta_children) in mapping(&children) {
+                        let value = if meta_inline.is_empty()
+                            && meta_children
+                                .iter()
+                                .any(|l| l.trim_start().starts_with("- "))
+                        {
+                            list(meta_inline, &meta_children).join(", ")
+                        } else {
+                            scalar(meta_inline, &meta_children)
+                        };
+                        if LOCALIZED_KEYS.contains(&meta_key) {
+                            rule.localized_message = Some(value);
+                        } else if !value.is_empty() {
+                            rule.metadata.insert(meta_key.to_string(), value);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        rules.push(rule);
+    }
+    rules
+}
+
+/// 在规则文件旁的测试文件（同名、非 YAML 扩展名）中查找 `ruleid: <id>` 标注后的示例代码
+fn find_example(rule_path: &Path, id: &str) -> Option<String> {
+    let stem = rule_path.file_stem()?.to_str()?;
+    let dir = rule_path.parent()?;
+    let marker = format!("ruleid: {id}");
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_stem().and_then(|s| s.to_str()) == Some(stem)
+                && !matches!(p.extension().and_then(|e| e.to_str()), Some("yml" | "yaml"))
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().find_map(|path| {
+        let content = std::fs::read_to_string(path).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        let start = lines.iter().position(|l| l.contains(&marker))? + 1;
+        let snippet: Vec<&str> = lines[start..]
+            .iter()
+            .take_while(|l| !l.trim().is_empty() && !l.contains("ruleid:") && !l.contains("ok:"))
+            .take(MAX_EXAMPLE_LINES)
+            .copied()
+            .collect();
+        (!snippet.is_empty()).then(|| snippet.join("\n"))
+    })
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// 把一段同缩进的 YAML 映射拆成 (键, 行内值, 子行)
+fn mapping<'a>(lines: &[&'a str]) -> Vec<(&'a str, &'a str, Vec<&'a str>)> {
+    let Some(base) = lines.iter().find(|l| is_content(l)).map(|l| indent_of(l)) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<(&str, &str, Vec<&str>)> = Vec::new();
+    for line in lines {
+        if is_content(line) && indent_of(line) == base {
+            if let Some((key, value)) = line.trim().split_once(':') {
+                entries.push((key.trim(), value.trim(), Vec::new()));
+                continue;
+            }
+        }
+        if let Some(last) = entries.last_mut() {
+            last.2.push(line);
+        }
+    }
+    entries
+}
+
+/// 解析标量：行内值、`|` / `>` 块或跨行的普通标量
+fn scalar(inline: &str, children: &[&str]) -> String {
+    let content: Vec<&str> = children.iter().copied().filter(|l| is_content(l)).collect();
+    let base = content.iter().map(|l| indent_of(l)).min().unwrap_or(0);
+    if inline.starts_with('|') {
+        let text: Vec<&str> = children
+            .iter()
+            .map(|l| l.get(base..).unwrap_or("").trim_end())
+            .collect();
+        return text.join("\n").trim().to_string();
+    }
+    let mut parts: Vec<String> = Vec::new();
+    if !inline.starts_with('>') && !inline.is_empty() {
+        parts.push(inline.to_string());
+    }
+    parts.extend(content.iter().map(|l| l.trim().to_string()));
+    unquote(&parts.join(" "))
+}
+
+/// 解析列表：`[a, b]` 或 `- a` 形式
+fn list(inline: &str, children: &[&str]) -> Vec<String> {
+    if let Some(inner) = inline.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        return inner
+            .split(',')
+            .map(|s| unquote(s.trim()))
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    if !inline.is_empty() {
+        return vec![unquote(inline)];
+    }
+    children
+        .iter()
+        .filter_map(|l| l.trim_start().strip_prefix("- "))
+        .map(|s| unquote(s.trim()))
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return value[1..value.len() - 1].to_string();
+        }
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"rules:
+  - id: avoid-eval
+    languages: [python]
+    severity: ERROR
+    message: >-
+      Detected use of eval().
+      Avoid evaluating dynamic content.
+    metadata:
+      cwe:
+        - "CWE-95: Eval Injection"
+      category: security
+      message-zh: 检测到 eval()，避免执行动态内容
+    pattern: eval(...)
+  - id: print-debug
+    languages:
+      - python
+    severity: INFO
+    message: "Remove debug print"
+    pattern: print(...)
+"#;
+
+    #[test]
+    fn test_rule_blocks_normalize_indent() {
+        let blocks = rule_blocks(RULES);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].id, "avoid-eval");
+        assert!(blocks[0]
+            .text
+            .starts_with("  - id: avoid-eval\n    languages: [python]"));
+        assert!(blocks[1].text.ends_with("    pattern: print(...)"));
+
+        // 顶格书写的列表项统一缩进后仍可解析
+        let flat = "rules:\n- id: flat\n  message: m\n  pattern: x\n";
+        let blocks = rule_blocks(flat);
+        let rebuilt = format!("rules:\n{}\n", blocks[0].text);
+        let rules = parse_rules(&rebuilt, Path::new("flat.yml"));
+        assert_eq!(rules[0].id, "flat");
+        assert_eq!(rules[0].message, "m");
+    }
+
+    #[test]
+    fn test_parse_rules_extracts_fields() {
+        let rules = parse_rules(RULES, Path::new("python/eval.yaml"));
+        assert_eq!(rules.len(), 2);
+        let eval = &rules[0];
+        assert_eq!(eval.id, "avoid-eval");
+        assert_eq!(eval.severity.as_deref(), Some("ERROR"));
+        assert_eq!(eval.languages, vec!["python"]);
+        assert_eq!(
+            eval.message,
+            "Detected use of eval(). Avoid evaluating dynamic content."
+        );
+        assert_eq!(eval.metadata["cwe"], "CWE-95: Eval Injection");
+        assert_eq!(eval.metadata["category"], "security");
+        assert_eq!(
+            eval.localized_message.as_deref(),
+            Some("检测到 eval()，避免执行动态内容")
+        );
+        assert_eq!(rules[1].message, "Remove debug print");
+        assert_eq!(rules[1].languages, vec!["python"]);
+        assert!(eval.matches("python.lang.security.avoid-eval"));
+        assert!(!eval.matches("python.avoid-eval-2"));
+    }
+
+    #[test]
+    fn test_find_rule_with_example() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("python").join("security");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(sub.join("eval.yaml"), RULES).unwrap();
+        std::fs::write(
+            sub.join("eval.py"),
+            "# ruleid: avoid-eval\neval(user_input)\n\n# ok: avoid-eval\nint(x)\n",
+        )
+        .unwrap();
+
+        let rule = find_rule(dir.path(), "python.security.avoid-eval").unwrap();
+        assert_eq!(rule.id, "avoid-eval");
+        assert_eq!(rule.example.as_deref(), Some("eval(user_input)"));
+        assert!(find_rule(dir.path(), "missing-rule").is_none());
+    }
+}
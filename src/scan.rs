@@ -23,6 +23,26 @@ pub struct ScanResult {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub rules_info: Option<RulesInfo>,
+    /// 命中生成文件模式而被丢弃问题的文件
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub skipped_generated: Vec<String>,
+    /// 多路径扫描时每个路径的问题数与耗时
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub paths: Vec<PathSummary>,
+    /// 使用基线（`--baseline`）时被抑制与逾期的问题统计
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub baseline: Option<crate::scan_baseline::BaselineSummary>,
+}
+
+/// 多路径扫描中单个路径的概况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathSummary {
+    pub path: std::path::PathBuf,
+    /// 去重前该路径的问题数
+    pub findings: usize,
+    pub execution_time: f64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +53,9 @@ pub struct RulesInfo {
     pub total_rules: usize,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub updated_at: Option<String>,
+    /// 多来源组合时每个来源解析出的规则数（`[[scan.sources]]`）
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub layers: Vec<crate::rule_sources::RuleSourceSummary>,
 }
 
 /// 安全问题发现
@@ -47,6 +70,31 @@ pub struct Finding {
     pub code_snippet: Option<String>,
     pub message: String,
     pub remediation: Option<String>,
+    /// 问题指纹（规则 + 文件 + 代码片段），代码行号变化时保持不变，用于 `scan --explain`
+    #[serde(default)]
+    pub fingerprint: String,
+    /// 报告该问题的扫描器（`--tool auto` 运行多个扫描器时填写）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scanners: Vec<String>,
+}
+
+impl Finding {
+    /// 计算问题指纹：规则 ID、`/` 分隔的文件路径与去除首尾空白的代码片段的 MD5 前 12 位，
+    /// 无代码片段时退化为使用行号
+    pub fn compute_fingerprint(&self) -> String {
+        let location = match &self.code_snippet {
+            Some(snippet) if !snippet.trim().is_empty() => snippet.trim().to_string(),
+            _ => self.line.to_string(),
+        };
+        let payload = format!(
+            "{}\n{}\n{}",
+            self.rule_id.as_deref().unwrap_or(&self.title),
+            crate::utils::paths::to_slash(&self.file_path).trim_start_matches("./"),
+            location
+        );
+        let hash = format!("{:x}", md5::compute(payload.as_bytes()));
+        hash[..12].to_string()
+    }
 }
 
 /// 严重程度
@@ -57,6 +105,615 @@ pub enum Severity {
     Info,
 }
 
+impl Severity {
+    /// 解析严重程度，兼容 OpenGrep 的 ERROR/WARNING/INFO 与 high/medium/low 写法
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" | "critical" | "high" => Some(Severity::Error),
+            "warning" | "warn" | "medium" => Some(Severity::Warning),
+            "info" | "low" | "note" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Severity::Error => 3,
+            Severity::Warning => 2,
+            Severity::Info => 1,
+        }
+    }
+
+    /// 是否不低于给定的严重程度
+    pub fn is_at_least(&self, threshold: &Severity) -> bool {
+        self.rank() >= threshold.rank()
+    }
+}
+
+/// 多个扫描器结果的一致性统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScannerAgreement {
+    /// 参与比较的扫描器
+    pub scanners: Vec<String>,
+    /// 所有扫描器都报告的问题数
+    pub agreed: usize,
+    /// 只有单个扫描器报告的问题数（按扫描器）
+    pub exclusive: std::collections::BTreeMap<String, usize>,
+}
+
+impl ScanResult {
+    /// 多个扫描器的结果一致性；问题未标注扫描器（单扫描器）时返回 None
+    pub fn scanner_agreement(&self) -> Option<ScannerAgreement> {
+        let mut scanners: Vec<String> = self
+            .findings
+            .iter()
+            .flat_map(|f| f.scanners.iter().cloned())
+            .collect();
+        scanners.sort();
+        scanners.dedup();
+        if scanners.len() < 2 {
+            return None;
+        }
+        let mut agreement = ScannerAgreement {
+            scanners,
+            ..Default::default()
+        };
+        for finding in &self.findings {
+            if finding.scanners.len() == agreement.scanners.len() {
+                agreement.agreed += 1;
+            } else if let [only] = finding.scanners.as_slice() {
+                *agreement.exclusive.entry(only.clone()).or_default() += 1;
+            }
+        }
+        Some(agreement)
+    }
+
+    /// 统计严重程度不低于阈值的问题数（无法识别的严重程度按 Warning 处理）
+    pub fn count_at_or_above(&self, threshold: &Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| {
+                Severity::parse(&f.severity)
+                    .unwrap_or(Severity::Warning)
+                    .is_at_least(threshold)
+            })
+            .count()
+    }
+
+    /// 按严重程度加权的 0-100 风险分（见 `risk_report::severity_score`）
+    pub fn risk_score(&self) -> f64 {
+        crate::risk_report::severity_score(
+            self.findings
+                .iter()
+                .map(|f| f.severity.parse().unwrap_or(crate::review::Severity::Info)),
+        )
+    }
+
+    /// 纯文本：每行一个问题 `路径:行: 标题`
+    pub fn to_text(&self) -> String {
+        self.findings
+            .iter()
+            .map(|f| format!("{}:{}: {}\n", f.file_path.display(), f.line, f.title))
+            .collect()
+    }
+
+    /// Markdown 报告
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# 安全扫描结果\n\n- 工具：{} {}\n- 耗时：{:.2}s\n- 问题数：{}\n- 风险分：{:.0}/100\n",
+            self.tool,
+            self.version,
+            self.execution_time,
+            self.findings.len(),
+            self.risk_score()
+        );
+        if !self.paths.is_empty() {
+            out.push_str("\n| 路径 | 问题数 | 耗时 |\n|---|---|---|\n");
+            for p in &self.paths {
+                out.push_str(&format!(
+                    "| `{}` | {} | {:.2}s |\n",
+                    p.path.display(),
+                    p.findings,
+                    p.execution_time
+                ));
+            }
+        }
+        if !self.findings.is_empty() {
+            out.push_str("\n| 严重程度 | 位置 | 规则 | 问题 |\n|---|---|---|---|\n");
+            for f in &self.findings {
+                out.push_str(&format!(
+                    "| {} | `{}:{}` | {} | {} |\n",
+                    f.severity,
+                    f.file_path.display(),
+                    f.line,
+                    f.rule_id.as_deref().unwrap_or("-"),
+                    f.title.replace('|', "\\|")
+                ));
+            }
+        }
+        out
+    }
+
+    /// 独立的 HTML 报告
+    pub fn to_html(&self) -> String {
+        let mut rows = String::new();
+        for f in &self.findings {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td><code>{}:{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&f.severity),
+                escape_html(&f.file_path.display().to_string()),
+                f.line,
+                escape_html(f.rule_id.as_deref().unwrap_or("-")),
+                escape_html(&f.title)
+            ));
+        }
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>安全扫描结果</title>\n\
+             <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+             td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style></head>\n\
+             <body><h1>安全扫描结果</h1>\n<p>工具：{} {}，耗时 {:.2}s，问题数 {}</p>\n\
+             <table><tr><th>严重程度</th><th>位置</th><th>规则</th><th>问题</th></tr>\n{rows}</table>\n\
+             </body></html>\n",
+            escape_html(&self.tool),
+            escape_html(&self.version),
+            self.execution_time,
+            self.findings.len()
+        )
+    }
+
+    /// 按格式渲染（json|markdown|html|text），供多目标输出使用
+    pub fn render(&self, format: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match format {
+            "json" => serde_json::to_string_pretty(self)?,
+            "markdown" => self.to_markdown(),
+            "html" => self.to_html(),
+            _ => self.to_text(),
+        })
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 检测目录是否包含有效规则（任意 .yml/.yaml 且包含 `rules:` 键），跳过隐藏文件与 pre-commit 配置
+pub fn dir_contains_valid_rules(dir: &Path) -> bool {
+    use std::fs;
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(d) = stack.pop() {
+        if let Ok(entries) = fs::read_dir(&d) {
+            for entry in entries.flatten() {
+                let p = entry.path();
+                if p.is_dir() {
+                    // 跳过隐藏目录
+                    if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
+                        if name.starts_with('.') {
+                            continue;
+                        }
+                    }
+                    stack.push(p);
+                } else if let Some(ext) = p.extension().and_then(|s| s.to_str()) {
+                    if ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml") {
+                        // 排除常见的非规则配置文件
+                        if let Some(fname) = p.file_name().and_then(|s| s.to_str()) {
+                            if fname.starts_with('.') {
+                                continue;
+                            }
+                            if fname.contains("pre-commit") {
+                                continue;
+                            }
+                        }
+                        if let Ok(content) = fs::read_to_string(&p) {
+                            for line in content.lines().take(200) {
+                                let t = line.trim_start();
+                                if t.starts_with("rules:") || t.starts_with("rules :") {
+                                    return true;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+/// 校验本地自定义规则目录（`--rules` / `[scan] custom_rules_dir`），返回规范化后的路径
+pub fn validate_custom_rules(
+    dir: &Path,
+) -> Result<std::path::PathBuf, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if !dir.is_dir() {
+        return Err(format!("自定义规则目录不存在或不是目录: {}", dir.display()).into());
+    }
+    if !dir_contains_valid_rules(dir) {
+        return Err(format!(
+            "自定义规则目录中没有可用的规则文件（需要包含 `rules:` 的 .yml/.yaml）: {}",
+            dir.display()
+        )
+        .into());
+    }
+    Ok(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()))
+}
+
+/// 托管规则目录中参与扫描的语言子目录
+///
+/// 语言已指定：仅使用该子目录（且包含有效规则）；
+/// 未指定：包含所有存在且包含有效规则的语言子目录，避免根目录中的非规则 YAML 被解析
+pub fn managed_rule_dirs(rules_dir: &Path, lang: Option<&str>) -> Vec<std::path::PathBuf> {
+    let mut used_config_paths: Vec<std::path::PathBuf> = Vec::new();
+    let has_entries = std::fs::read_dir(rules_dir)
+        .map(|mut iter| iter.next().is_some())
+        .unwrap_or(false);
+    if !has_entries {
+        return used_config_paths;
+    }
+    let known_langs = [
+        "java",
+        "python",
+        "javascript",
+        "typescript",
+        "go",
+        "rust",
+        "c",
+        "cpp",
+        "ruby",
+        "php",
+        "kotlin",
+        "scala",
+        "swift",
+    ];
+
+    // 构造候选根目录：rules_dir 以及其一级子目录（兼容 opengrep-rules-main/java 结构）
+    let mut candidate_roots: Vec<std::path::PathBuf> = vec![rules_dir.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(rules_dir) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if p.is_dir() {
+                candidate_roots.push(p);
+            }
+        }
+    }
+
+    if let Some(l) = lang {
+        let mut found_any = false;
+        for root in &candidate_roots {
+            let candidate = root.join(l);
+            if candidate.exists() && candidate.is_dir() {
+                if dir_contains_valid_rules(&candidate) {
+                    if !used_config_paths.iter().any(|x| x == &candidate) {
+                        used_config_paths.push(candidate.clone());
+                    }
+                    found_any = true;
+                } else {
+                    log::warn!(
+                        "指定语言 '{}' 的规则目录存在但未检测到有效规则: {}",
+                        l,
+                        candidate.display()
+                    );
+                }
+            }
+        }
+        if !found_any {
+            log::warn!(
+                "未找到指定语言 '{}' 的有效规则目录（已检查候选根目录下的子目录）: {}",
+                l,
+                rules_dir.display()
+            );
+        }
+    } else {
+        for root in &candidate_roots {
+            for l in known_langs {
+                let p = root.join(l);
+                if p.exists()
+                    && p.is_dir()
+                    && dir_contains_valid_rules(&p)
+                    && !used_config_paths.iter().any(|x| x == &p)
+                {
+                    used_config_paths.push(p);
+                }
+            }
+        }
+        if used_config_paths.is_empty() {
+            log::warn!(
+                "未在规则目录及其一级子目录下找到任何包含有效规则的语言子目录: {}",
+                rules_dir.display()
+            );
+        }
+    }
+
+    used_config_paths
+}
+
+/// 单次扫描的参数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanOptions<'a> {
+    /// 规则语言
+    pub lang: Option<&'a str>,
+    /// 覆盖配置中的超时时间（秒）
+    pub timeout: Option<u64>,
+    /// 是否查询扫描器版本
+    pub include_version: bool,
+}
+
+/// 可插拔的扫描器：`--tool auto` 会运行所有可用的扫描器并按指纹合并结果
+pub trait Scanner: Send + Sync {
+    /// 扫描器名称（与 `--tool` 的取值一致）
+    fn name(&self) -> &'static str;
+
+    /// 扫描器是否已安装可用
+    fn is_available(&self) -> bool;
+
+    /// 执行扫描
+    fn scan(
+        &self,
+        config: &Config,
+        path: &Path,
+        options: &ScanOptions<'_>,
+        cancel: &crate::utils::cancel::CancellationToken,
+    ) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// OpenGrep 扫描器
+pub struct OpenGrepScanner;
+
+impl Scanner for OpenGrepScanner {
+    fn name(&self) -> &'static str {
+        "opengrep"
+    }
+
+    fn is_available(&self) -> bool {
+        is_opengrep_installed()
+    }
+
+    fn scan(
+        &self,
+        config: &Config,
+        path: &Path,
+        options: &ScanOptions<'_>,
+        cancel: &crate::utils::cancel::CancellationToken,
+    ) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        run_opengrep_scan_cancellable(
+            config,
+            path,
+            options.lang,
+            options.timeout,
+            options.include_version,
+            cancel,
+        )
+    }
+}
+
+/// 内置的扫描器（新增扫描器时在这里注册）
+pub fn builtin_scanners() -> Vec<Box<dyn Scanner>> {
+    vec![Box::new(OpenGrepScanner)]
+}
+
+/// `--tool auto`：运行所有可用的扫描器；只有一个可用时与直接使用该扫描器相同，
+/// 多个可用时按指纹合并问题，并在每个问题上标注报告它的扫描器
+pub fn run_auto_scan(
+    config: &Config,
+    path: &Path,
+    options: &ScanOptions<'_>,
+    scanners: &[Box<dyn Scanner>],
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    run_auto_scan_cancellable(
+        config,
+        path,
+        options,
+        scanners,
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+}
+
+/// 可取消的 `--tool auto` 扫描，令牌传给每个扫描器
+pub fn run_auto_scan_cancellable(
+    config: &Config,
+    path: &Path,
+    options: &ScanOptions<'_>,
+    scanners: &[Box<dyn Scanner>],
+    cancel: &crate::utils::cancel::CancellationToken,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let available: Vec<&dyn Scanner> = scanners
+        .iter()
+        .map(|s| s.as_ref())
+        .filter(|s| s.is_available())
+        .collect();
+    match available.as_slice() {
+        [] => Err("未检测到可用的扫描工具".into()),
+        [only] => only.scan(config, path, options, cancel),
+        _ => {
+            let mut results = Vec::new();
+            for scanner in &available {
+                log::info!("运行扫描器: {}", scanner.name());
+                results.push((scanner.name(), scanner.scan(config, path, options, cancel)?));
+            }
+            Ok(merge_scan_results(results))
+        }
+    }
+}
+
+/// 按指纹合并多个扫描器的结果：同一指纹只保留首次出现的问题，并记录所有报告它的扫描器
+pub fn merge_scan_results(results: Vec<(&str, ScanResult)>) -> ScanResult {
+    let mut merged = ScanResult {
+        tool: results
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join("+"),
+        version: results
+            .iter()
+            .map(|(name, r)| format!("{name} {}", r.version))
+            .collect::<Vec<_>>()
+            .join(", "),
+        execution_time: 0.0,
+        findings: Vec::new(),
+        error: None,
+        rules_info: None,
+        skipped_generated: Vec::new(),
+        paths: Vec::new(),
+        baseline: None,
+    };
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut errors = Vec::new();
+    for (name, result) in results {
+        // 扫描器依次运行，总耗时为各自耗时之和
+        merged.execution_time += result.execution_time;
+        if let Some(error) = result.error {
+            errors.push(format!("{name}: {error}"));
+        }
+        merged.rules_info = merged.rules_info.or(result.rules_info);
+        for path in result.skipped_generated {
+            if !merged.skipped_generated.contains(&path) {
+                merged.skipped_generated.push(path);
+            }
+        }
+        for mut finding in result.findings {
+            let fingerprint = if finding.fingerprint.is_empty() {
+                finding.compute_fingerprint()
+            } else {
+                finding.fingerprint.clone()
+            };
+            match index.get(&fingerprint) {
+                Some(&i) => {
+                    let scanners = &mut merged.findings[i].scanners;
+                    if !scanners.iter().any(|s| s == name) {
+                        scanners.push(name.to_string());
+                    }
+                }
+                None => {
+                    finding.scanners = vec![name.to_string()];
+                    index.insert(fingerprint, merged.findings.len());
+                    merged.findings.push(finding);
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        merged.error = Some(errors.join("; "));
+    }
+    merged
+}
+
+/// 单个路径的扫描结果（`gitai scan <路径>...`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathScanResult {
+    pub path: std::path::PathBuf,
+    #[serde(flatten)]
+    pub result: ScanResult,
+}
+
+/// 并发扫描多个路径，结果与输入顺序一致；任一路径失败时取消其余扫描并返回该错误
+pub fn scan_paths<F>(
+    paths: &[std::path::PathBuf],
+    scan: F,
+) -> Result<Vec<PathScanResult>, Box<dyn std::error::Error + Send + Sync + 'static>>
+where
+    F: Fn(
+            &Path,
+            &crate::utils::cancel::CancellationToken,
+        ) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>>
+        + Sync,
+{
+    let cancel = crate::utils::cancel::CancellationToken::new();
+    let outcomes: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                let (scan, cancel) = (&scan, &cancel);
+                scope.spawn(move || {
+                    let outcome = scan(path, cancel);
+                    if outcome.is_err() {
+                        cancel.cancel();
+                    }
+                    outcome
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err("扫描线程异常退出".into()))
+            })
+            .collect()
+    });
+
+    let mut results = Vec::with_capacity(paths.len());
+    let mut cancelled = None;
+    for (path, outcome) in paths.iter().zip(outcomes) {
+        match outcome {
+            Ok(result) => results.push(PathScanResult {
+                path: path.clone(),
+                result,
+            }),
+            // 被其他路径的失败取消的扫描不是根本原因，优先报告真正的错误
+            Err(e) if crate::utils::cancel::is_cancelled_error(e.as_ref()) => {
+                cancelled.get_or_insert(e);
+            }
+            Err(e) => return Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+    match cancelled {
+        Some(e) => Err(e),
+        None => Ok(results),
+    }
+}
+
+/// 合并多个路径的扫描结果：同一指纹（重叠路径中的同一问题）只保留一次；
+/// 各路径并发执行，总耗时取最慢的路径，并在 `paths` 中记录每个路径的概况
+pub fn merge_path_results(results: &[PathScanResult]) -> ScanResult {
+    let first = results.first().map(|r| &r.result);
+    let mut merged = ScanResult {
+        tool: first.map(|r| r.tool.clone()).unwrap_or_default(),
+        version: first.map(|r| r.version.clone()).unwrap_or_default(),
+        execution_time: 0.0,
+        findings: Vec::new(),
+        error: None,
+        rules_info: first.and_then(|r| r.rules_info.clone()),
+        skipped_generated: Vec::new(),
+        paths: Vec::new(),
+        baseline: None,
+    };
+    let mut seen = std::collections::HashSet::new();
+    let mut errors = Vec::new();
+    for PathScanResult { path, result } in results {
+        merged.execution_time = merged.execution_time.max(result.execution_time);
+        if let Some(error) = &result.error {
+            errors.push(format!("{}: {error}", path.display()));
+        }
+        for skipped in &result.skipped_generated {
+            if !merged.skipped_generated.contains(skipped) {
+                merged.skipped_generated.push(skipped.clone());
+            }
+        }
+        for finding in &result.findings {
+            let fingerprint = if finding.fingerprint.is_empty() {
+                finding.compute_fingerprint()
+            } else {
+                finding.fingerprint.clone()
+            };
+            if seen.insert(fingerprint) {
+                merged.findings.push(finding.clone());
+            }
+        }
+        merged.paths.push(PathSummary {
+            path: path.clone(),
+            findings: result.findings.len(),
+            execution_time: result.execution_time,
+            error: result.error.clone(),
+        });
+    }
+    if !errors.is_empty() {
+        merged.error = Some(errors.join("; "));
+    }
+    merged
+}
+
 /// 运行OpenGrep扫描
 pub fn run_opengrep_scan(
     config: &Config,
@@ -64,6 +721,26 @@ pub fn run_opengrep_scan(
     lang: Option<&str>,
     timeout_override: Option<u64>,
     include_version: bool,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    run_opengrep_scan_cancellable(
+        config,
+        path,
+        lang,
+        timeout_override,
+        include_version,
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+}
+
+/// 可取消的 OpenGrep 扫描：令牌被取消时终止 OpenGrep 子进程并返回
+/// [`Cancelled`](crate::utils::cancel::Cancelled) 错误
+pub fn run_opengrep_scan_cancellable(
+    config: &Config,
+    path: &Path,
+    lang: Option<&str>,
+    timeout_override: Option<u64>,
+    include_version: bool,
+    cancel: &crate::utils::cancel::CancellationToken,
 ) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync + 'static>> {
     let start_time = std::time::Instant::now();
 
@@ -93,170 +770,61 @@ pub fn run_opengrep_scan(
     args.push("--use-git-ignore".to_string());
 
     // 规则目录
-    let rules_dir = config
-        .scan
-        .rules_dir
-        .clone()
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|| {
-            dirs::home_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
-                .join(".cache")
-                .join("gitai")
-                .join("rules")
-        });
+    let rules_dir = crate::utils::paths::resolve_rules_dir(config.scan.rules_dir.as_deref());
     let mut rules_info: Option<RulesInfo> = None;
     let mut used_config_paths: Vec<std::path::PathBuf> = Vec::new();
-    if rules_dir.exists() {
-        if let Ok(mut iter) = std::fs::read_dir(&rules_dir) {
-            if iter.next().is_some() {
-                // 语言已指定：仅使用该子目录（且包含有效规则）
-                // 未指定：包含所有存在且包含有效规则的语言子目录，避免根目录中的非规则 YAML 被解析
-                let known_langs = [
-                    "java",
-                    "python",
-                    "javascript",
-                    "typescript",
-                    "go",
-                    "rust",
-                    "c",
-                    "cpp",
-                    "ruby",
-                    "php",
-                    "kotlin",
-                    "scala",
-                    "swift",
-                ];
-
-                // 辅助：检测目录是否包含有效规则（任意 .yml/.yaml 且包含 'rules:' 键）
-                fn dir_contains_valid_rules(dir: &std::path::Path) -> bool {
-                    use std::fs;
-                    let mut stack = vec![dir.to_path_buf()];
-                    while let Some(d) = stack.pop() {
-                        if let Ok(entries) = fs::read_dir(&d) {
-                            for entry in entries.flatten() {
-                                let p = entry.path();
-                                if p.is_dir() {
-                                    // 跳过隐藏目录
-                                    if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
-                                        if name.starts_with('.') {
-                                            continue;
-                                        }
-                                    }
-                                    stack.push(p);
-                                } else if let Some(ext) = p.extension().and_then(|s| s.to_str()) {
-                                    if ext.eq_ignore_ascii_case("yml")
-                                        || ext.eq_ignore_ascii_case("yaml")
-                                    {
-                                        // 排除常见的非规则配置文件
-                                        if let Some(fname) = p.file_name().and_then(|s| s.to_str())
-                                        {
-                                            if fname.starts_with('.') {
-                                                continue;
-                                            }
-                                            if fname.contains("pre-commit") {
-                                                continue;
-                                            }
-                                        }
-                                        if let Ok(content) = fs::read_to_string(&p) {
-                                            for line in content.lines().take(200) {
-                                                let t = line.trim_start();
-                                                if t.starts_with("rules:")
-                                                    || t.starts_with("rules :")
-                                                {
-                                                    return true;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    false
-                }
-
-                // 构造候选根目录：rules_dir 以及其一级子目录（兼容 opengrep-rules-main/java 结构）
-                let mut candidate_roots: Vec<std::path::PathBuf> = vec![rules_dir.clone()];
-                if let Ok(entries) = std::fs::read_dir(&rules_dir) {
-                    for entry in entries.flatten() {
-                        let p = entry.path();
-                        if p.is_dir() {
-                            candidate_roots.push(p);
-                        }
-                    }
-                }
-
-                if let Some(l) = lang {
-                    let mut found_any = false;
-                    for root in &candidate_roots {
-                        let candidate = root.join(l);
-                        if candidate.exists() && candidate.is_dir() {
-                            if dir_contains_valid_rules(&candidate) {
-                                if !used_config_paths.iter().any(|x| x == &candidate) {
-                                    used_config_paths.push(candidate.clone());
-                                }
-                                found_any = true;
-                            } else {
-                                log::warn!(
-                                    "指定语言 '{}' 的规则目录存在但未检测到有效规则: {}",
-                                    l,
-                                    candidate.display()
-                                );
-                            }
-                        }
-                    }
-                    if !found_any {
-                        log::warn!(
-                            "未找到指定语言 '{}' 的有效规则目录（已检查候选根目录下的子目录）: {}",
-                            l,
-                            rules_dir.display()
-                        );
-                    }
-                } else {
-                    for root in &candidate_roots {
-                        for l in known_langs {
-                            let p = root.join(l);
-                            if p.exists()
-                                && p.is_dir()
-                                && dir_contains_valid_rules(&p)
-                                && !used_config_paths.iter().any(|x| x == &p)
-                            {
-                                used_config_paths.push(p);
-                            }
-                        }
-                    }
-                    if used_config_paths.is_empty() {
-                        log::warn!(
-                            "未在规则目录及其一级子目录下找到任何包含有效规则的语言子目录: {}",
-                            rules_dir.display()
-                        );
-                    }
-                }
 
-                // 添加所有配置目录
-                for p in &used_config_paths {
-                    args.push(format!("--config={}", p.display()));
-                }
+    // 本地自定义规则：先校验，避免 OpenGrep 因规则不可用而静默无发现
+    let custom_rules = match config.scan.custom_rules_dir.as_deref() {
+        Some(dir) => Some(validate_custom_rules(Path::new(dir))?),
+        None if config.scan.rules_only => {
+            return Err(
+                "--rules-only 需要通过 --rules 或 [scan] custom_rules_dir 指定自定义规则目录"
+                    .into(),
+            );
+        }
+        None => None,
+    };
 
-                // 读取元信息：优先使用第一个有效目录；如果没有，则尝试根目录
-                if let Some(first) = used_config_paths.first() {
-                    rules_info = read_rules_info(first).or_else(|| read_rules_info(&rules_dir));
-                }
+    if !config.scan.rules_only && !config.scan.sources.is_empty() {
+        // 多来源组合：按规则 ID 合并到单个目录，自定义规则作为最后一个来源参与合并
+        let resolved = crate::rule_sources::RuleManager::new(config).resolve(lang)?;
+        used_config_paths.push(resolved.dir.clone());
+        rules_info = Some(resolved.rules_info());
+    } else {
+        if !config.scan.rules_only {
+            used_config_paths = managed_rule_dirs(&rules_dir, lang);
+            // 读取元信息：优先使用第一个有效目录；如果没有，则尝试根目录
+            if let Some(first) = used_config_paths.first() {
+                rules_info = read_rules_info(first).or_else(|| read_rules_info(&rules_dir));
+            }
+        }
+        if let Some(custom) = custom_rules {
+            log::info!("使用自定义规则目录: {}", custom.display());
+            if !used_config_paths.iter().any(|x| x == &custom) {
+                used_config_paths.push(custom);
             }
         }
     }
 
-    // 执行命令
+    // 添加所有配置目录
+    for p in &used_config_paths {
+        args.push(format!("--config={}", p.display()));
+    }
+
+    // 执行命令（OpenGrep 自行遍历目录，不进入符号链接指向的目录，与分析的默认策略一致）
     log::debug!("执行命令: opengrep {} {}", args.join(" "), path.display());
-    let output = Command::new("opengrep")
-        .args(&args)
-        .arg(path)
-        .output()
-        .map_err(|e| {
-            log::error!("执行 OpenGrep 失败: {e}");
-            format!("执行 OpenGrep 失败: {e}\n💡 请确保 OpenGrep 已安装并在 PATH 中")
-        })?;
+    let output = crate::utils::cancel::output_cancellable(
+        Command::new("opengrep").args(&args).arg(path),
+        cancel,
+    )
+    .map_err(|e| {
+        if crate::utils::cancel::is_cancelled_error(e.as_ref()) {
+            return e;
+        }
+        log::error!("执行 OpenGrep 失败: {e}");
+        format!("执行 OpenGrep 失败: {e}\n💡 请确保 OpenGrep 已安装并在 PATH 中").into()
+    })?;
 
     let execution_time = start_time.elapsed().as_secs_f64();
 
@@ -298,6 +866,9 @@ pub fn run_opengrep_scan(
                     findings: vec![],
                     error: Some(err_msg),
                     rules_info,
+                    skipped_generated: Vec::new(),
+                    paths: Vec::new(),
+                    baseline: None,
                 });
             }
         } else {
@@ -332,6 +903,9 @@ pub fn run_opengrep_scan(
                 findings: vec![],
                 error: Some(err_msg),
                 rules_info,
+                skipped_generated: Vec::new(),
+                paths: Vec::new(),
+                baseline: None,
             });
         }
     }
@@ -348,7 +922,7 @@ pub fn run_opengrep_scan(
         debug!("📦 使用规则目录: {}", joined);
     }
 
-    let findings = match parse_opengrep_output(&stdout) {
+    let mut findings = match parse_opengrep_output(&stdout) {
         Ok(f) => f,
         Err(e) => {
             debug!("❌ JSON 解析失败: {e}");
@@ -363,10 +937,19 @@ pub fn run_opengrep_scan(
                 findings: vec![],
                 error: Some(format!("JSON 解析失败: {e}")),
                 rules_info,
+                skipped_generated: Vec::new(),
+                paths: Vec::new(),
+                baseline: None,
             });
         }
     };
 
+    let skipped_generated =
+        drop_generated_findings(&mut findings, &crate::utils::generated::active());
+    if !skipped_generated.is_empty() {
+        log::info!("跳过 {} 个生成文件中的安全问题", skipped_generated.len());
+    }
+
     Ok(ScanResult {
         tool: "opengrep".to_string(),
         version: if include_version {
@@ -378,9 +961,32 @@ pub fn run_opengrep_scan(
         findings,
         error: None,
         rules_info,
+        skipped_generated,
+        paths: Vec::new(),
+        baseline: None,
     })
 }
 
+/// 丢弃位于生成文件中的问题，返回涉及的文件（去重、排序）
+fn drop_generated_findings(
+    findings: &mut Vec<Finding>,
+    filter: &crate::utils::generated::GeneratedFilter,
+) -> Vec<String> {
+    let mut skipped = Vec::new();
+    findings.retain(|finding| {
+        let path = crate::utils::paths::to_slash(&finding.file_path);
+        if filter.is_generated(&path) {
+            skipped.push(path);
+            false
+        } else {
+            true
+        }
+    });
+    skipped.sort();
+    skipped.dedup();
+    skipped
+}
+
 /// 获取OpenGrep版本（使用缓存）
 fn get_opengrep_version() -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
     // 先检查缓存
@@ -485,7 +1091,7 @@ fn create_finding_from_result(
 
     let remediation = item["extra"]["fix"].as_str().map(|s| s.to_string());
 
-    Ok(Finding {
+    let mut finding = Finding {
         title,
         file_path: std::path::PathBuf::from(file_path),
         line,
@@ -495,7 +1101,11 @@ fn create_finding_from_result(
         code_snippet,
         message,
         remediation,
-    })
+        fingerprint: String::new(),
+        scanners: Vec::new(),
+    };
+    finding.fingerprint = finding.compute_fingerprint();
+    Ok(finding)
 }
 
 /// 检查OpenGrep是否已安装
@@ -536,6 +1146,7 @@ pub fn read_rules_info(rules_dir: &std::path::Path) -> Option<RulesInfo> {
                 sources,
                 total_rules: total,
                 updated_at,
+                layers: Vec::new(),
             })
         } else {
             // 回退：仅提供目录
@@ -544,6 +1155,7 @@ pub fn read_rules_info(rules_dir: &std::path::Path) -> Option<RulesInfo> {
                 sources: Vec::new(),
                 total_rules: 0,
                 updated_at: None,
+                layers: Vec::new(),
             })
         }
     } else {
@@ -553,6 +1165,7 @@ pub fn read_rules_info(rules_dir: &std::path::Path) -> Option<RulesInfo> {
             sources: Vec::new(),
             total_rules: 0,
             updated_at: None,
+            layers: Vec::new(),
         })
     };
 
@@ -567,7 +1180,7 @@ pub fn read_rules_info(rules_dir: &std::path::Path) -> Option<RulesInfo> {
 
 /// 安装OpenGrep（优先使用 cargo；若不可用则给出明确指引）
 pub fn install_opengrep() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    println!("🔧 正在安装OpenGrep...");
+    crate::progress!("🔧 正在安装OpenGrep...");
 
     // 先检测 cargo 是否可用
     let cargo_available = Command::new("cargo")
@@ -584,10 +1197,10 @@ pub fn install_opengrep() -> Result<(), Box<dyn std::error::Error + Send + Sync
         if output.status.success() {
             // 提示 PATH 配置（如未生效）
             if !is_opengrep_installed() {
-                println!("ℹ️ 已通过 cargo 安装，但未检测到 opengrep 在 PATH。若使用 rustup 默认目录，请添加到 PATH:");
-                println!("   export PATH=\"$HOME/.cargo/bin:$PATH\"");
+                crate::progress!("ℹ️ 已通过 cargo 安装，但未检测到 opengrep 在 PATH。若使用 rustup 默认目录，请添加到 PATH:");
+                crate::progress!("   export PATH=\"$HOME/.cargo/bin:$PATH\"");
             }
-            println!("✅ OpenGrep 安装完成");
+            crate::progress!("✅ OpenGrep 安装完成");
             return Ok(());
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -601,3 +1214,244 @@ pub fn install_opengrep() -> Result<(), Box<dyn std::error::Error + Send + Sync
     let guide = "未检测到 cargo。请先安装 Rust 工具链，然后使用 cargo 安装 OpenGrep:\n\n1) 安装 Rust（推荐 rustup）: https://rustup.rs\n2) 安装 OpenGrep: cargo install opengrep\n3) 将 cargo 的 bin 目录加入 PATH: export PATH=\"$HOME/.cargo/bin:$PATH\"";
     Err(guide.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_custom_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(validate_custom_rules(&dir.path().join("missing")).is_err());
+        // 只有非规则 YAML 时视为不可用
+        std::fs::write(dir.path().join(".pre-commit-config.yaml"), "rules:\n").unwrap();
+        std::fs::write(dir.path().join("notes.yml"), "title: x\n").unwrap();
+        assert!(validate_custom_rules(dir.path()).is_err());
+
+        let nested = dir.path().join("team");
+        std::fs::create_dir(&nested).unwrap();
+        std::fs::write(
+            nested.join("no-println.yml"),
+            "rules:\n  - id: no-println\n    pattern: println!(...)\n",
+        )
+        .unwrap();
+        let resolved = validate_custom_rules(dir.path()).unwrap();
+        assert!(resolved.is_absolute());
+    }
+
+    fn finding(severity: &str) -> Finding {
+        Finding {
+            title: "issue".to_string(),
+            file_path: std::path::PathBuf::from("src/lib.rs"),
+            line: 1,
+            column: 1,
+            severity: severity.to_string(),
+            rule_id: None,
+            code_snippet: None,
+            message: "issue".to_string(),
+            remediation: None,
+            fingerprint: String::new(),
+            scanners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_merge_scan_results_by_fingerprint() {
+        let at = |rule: &str| {
+            let mut f = Finding {
+                rule_id: Some(rule.to_string()),
+                ..finding("ERROR")
+            };
+            f.fingerprint = f.compute_fingerprint();
+            f
+        };
+        let result = |tool: &str, findings: Vec<Finding>| ScanResult {
+            tool: tool.to_string(),
+            version: "1.0".to_string(),
+            execution_time: 1.0,
+            findings,
+            error: None,
+            rules_info: None,
+            skipped_generated: Vec::new(),
+            paths: Vec::new(),
+            baseline: None,
+        };
+        let merged = merge_scan_results(vec![
+            ("a", result("a", vec![at("shared"), at("only-a")])),
+            (
+                "b",
+                result("b", vec![at("shared"), at("only-b"), at("only-b-2")]),
+            ),
+        ]);
+        assert_eq!(merged.tool, "a+b");
+        assert_eq!(merged.execution_time, 2.0);
+        assert_eq!(merged.findings.len(), 4);
+        assert_eq!(merged.findings[0].scanners, vec!["a", "b"]);
+
+        let agreement = merged.scanner_agreement().unwrap();
+        assert_eq!(agreement.scanners, vec!["a", "b"]);
+        assert_eq!(agreement.agreed, 1);
+        assert_eq!(agreement.exclusive["a"], 1);
+        assert_eq!(agreement.exclusive["b"], 2);
+
+        // 单扫描器结果不做一致性统计
+        assert!(result("a", vec![at("x")]).scanner_agreement().is_none());
+    }
+
+    #[test]
+    fn test_scan_paths_merges_and_dedups_overlapping_paths() {
+        let at = |file: &str, rule: &str| {
+            let mut f = Finding {
+                file_path: std::path::PathBuf::from(file),
+                rule_id: Some(rule.to_string()),
+                ..finding("ERROR")
+            };
+            f.fingerprint = f.compute_fingerprint();
+            f
+        };
+        let paths: Vec<std::path::PathBuf> = ["svc-a", "svc-a/api", "svc-b"]
+            .iter()
+            .map(std::path::PathBuf::from)
+            .collect();
+        let results = scan_paths(&paths, |path, _| {
+            let findings = match path.to_str().unwrap() {
+                "svc-a" => vec![at("svc-a/api/x.py", "sqli"), at("svc-a/y.py", "xss")],
+                "svc-a/api" => vec![at("./svc-a/api/x.py", "sqli")],
+                _ => vec![at("svc-b/z.py", "sqli")],
+            };
+            Ok(ScanResult {
+                tool: "opengrep".to_string(),
+                version: "1.0".to_string(),
+                execution_time: findings.len() as f64,
+                findings,
+                error: (path == Path::new("svc-b")).then(|| "partial".to_string()),
+                rules_info: None,
+                skipped_generated: Vec::new(),
+                paths: Vec::new(),
+                baseline: None,
+            })
+        })
+        .unwrap();
+        assert_eq!(
+            results.iter().map(|r| r.path.clone()).collect::<Vec<_>>(),
+            paths
+        );
+
+        let merged = merge_path_results(&results);
+        assert_eq!(merged.findings.len(), 3);
+        // 并发执行，总耗时取最慢的路径
+        assert_eq!(merged.execution_time, 2.0);
+        assert_eq!(merged.paths.len(), 3);
+        assert_eq!(merged.paths[1].findings, 1);
+        assert_eq!(merged.error.as_deref(), Some("svc-b: partial"));
+        assert!(merged.to_markdown().contains("| `svc-a/api` | 1 | 1.00s |"));
+    }
+
+    #[test]
+    fn test_scan_paths_cancels_remaining_on_failure() {
+        let paths: Vec<std::path::PathBuf> = ["ok", "missing"]
+            .iter()
+            .map(std::path::PathBuf::from)
+            .collect();
+        let err = scan_paths(&paths, |path, cancel| {
+            if path == Path::new("missing") {
+                return Err("扫描路径不存在".into());
+            }
+            // 等待失败的路径取消本次扫描
+            while !cancel.is_cancelled() {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+            Err(crate::utils::cancel::Cancelled.into())
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "missing: 扫描路径不存在");
+    }
+
+    #[test]
+    fn test_count_at_or_above() {
+        let result = ScanResult {
+            tool: "opengrep".to_string(),
+            version: "test".to_string(),
+            execution_time: 0.0,
+            findings: vec![finding("ERROR"), finding("WARNING"), finding("INFO")],
+            error: None,
+            rules_info: None,
+            skipped_generated: Vec::new(),
+            paths: Vec::new(),
+            baseline: None,
+        };
+
+        assert_eq!(result.count_at_or_above(&Severity::Error), 1);
+        assert_eq!(result.count_at_or_above(&Severity::Warning), 2);
+        assert_eq!(result.count_at_or_above(&Severity::Info), 3);
+        assert!(Severity::parse("bogus").is_none());
+        assert!(matches!(Severity::parse("High"), Some(Severity::Error)));
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let mut escaped = finding("ERROR");
+        escaped.title = "<script>".to_string();
+        let result = ScanResult {
+            tool: "opengrep".to_string(),
+            version: "1.0".to_string(),
+            execution_time: 0.5,
+            findings: vec![escaped, finding("INFO")],
+            error: None,
+            rules_info: None,
+            skipped_generated: Vec::new(),
+            paths: Vec::new(),
+            baseline: None,
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&result.render("json").unwrap()).unwrap();
+        assert_eq!(json["findings"].as_array().unwrap().len(), 2);
+        let html = result.render("html").unwrap();
+        assert!(html.contains("&lt;script&gt;") && !html.contains("<script>"));
+        assert!(result
+            .render("markdown")
+            .unwrap()
+            .contains("| INFO | `src/lib.rs:1` |"));
+        assert_eq!(
+            result.render("text").unwrap(),
+            "src/lib.rs:1: <script>\nsrc/lib.rs:1: issue\n"
+        );
+    }
+
+    #[test]
+    fn test_drop_generated_findings() {
+        let at = |path: &str| Finding {
+            file_path: std::path::PathBuf::from(path),
+            ..finding("ERROR")
+        };
+        let mut findings = vec![
+            at("src/lib.rs"),
+            at("./web/dist/app.min.js"),
+            at("api/user.pb.go"),
+            at("api/user.pb.go"),
+        ];
+        let filter = crate::utils::generated::GeneratedFilter::default();
+        let skipped = drop_generated_findings(&mut findings, &filter);
+        assert_eq!(skipped, vec!["./web/dist/app.min.js", "api/user.pb.go"]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].file_path,
+            std::path::PathBuf::from("src/lib.rs")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_shift() {
+        let mut a = finding("ERROR");
+        a.rule_id = Some("python.avoid-eval".to_string());
+        a.code_snippet = Some("  eval(x)\n".to_string());
+        let mut b = a.clone();
+        b.line = 42;
+        b.file_path = std::path::PathBuf::from("./src/lib.rs");
+        assert_eq!(a.compute_fingerprint(), b.compute_fingerprint());
+        assert_eq!(a.compute_fingerprint().len(), 12);
+
+        b.code_snippet = Some("eval(y)".to_string());
+        assert_ne!(a.compute_fingerprint(), b.compute_fingerprint());
+    }
+}
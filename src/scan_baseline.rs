@@ -0,0 +1,256 @@
+// 扫描基线与老化策略
+// 基线文件记录已接受（暂不修复）的问题指纹及其首次加入基线的时间；`gitai scan --baseline <文件>`
+// 抑制基线中的问题，加入基线超过 `scan.baseline_max_age_days` 天的条目重新报告为逾期，避免技术债被永久隐藏
+
+use crate::scan::{Finding, ScanResult};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 基线文件格式版本
+const BASELINE_VERSION: u32 = 1;
+
+/// 基线中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// 首次加入基线的时间；更新基线时保持不变，老化从这里开始计算
+    pub added_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    pub file_path: PathBuf,
+    pub title: String,
+}
+
+/// 扫描基线：问题指纹 → 记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub entries: BTreeMap<String, BaselineEntry>,
+}
+
+fn default_version() -> u32 {
+    BASELINE_VERSION
+}
+
+/// 应用基线后的统计（扫描结果中的 `baseline`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BaselineSummary {
+    /// 基线文件
+    pub file: PathBuf,
+    /// 被抑制的问题数
+    pub suppressed: usize,
+    /// 超过老化期限、重新报告的问题指纹
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overdue: Vec<String>,
+    /// 老化期限（天），未设置时基线条目永不过期
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+}
+
+impl BaselineSummary {
+    /// 问题是否因超过老化期限而被重新报告
+    pub fn is_overdue(&self, finding: &Finding) -> bool {
+        self.overdue.contains(&fingerprint(finding))
+    }
+}
+
+fn fingerprint(finding: &Finding) -> String {
+    if finding.fingerprint.is_empty() {
+        finding.compute_fingerprint()
+    } else {
+        finding.fingerprint.clone()
+    }
+}
+
+impl Baseline {
+    /// 读取基线文件；文件不存在时返回空基线
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let baseline: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("基线文件 {} 解析失败: {e}", path.display()))?;
+        if baseline.version > BASELINE_VERSION {
+            return Err(format!(
+                "基线文件 {} 的版本 {} 不受支持（当前支持 {BASELINE_VERSION}）",
+                path.display(),
+                baseline.version
+            )
+            .into());
+        }
+        Ok(baseline)
+    }
+
+    /// 原子地写入基线文件
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        crate::utils::atomic_write::write_atomic(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// 用本次扫描的问题更新基线：新问题以 now 加入，已有条目保留原加入时间（重新生成基线不会重置老化），
+    /// 已不再出现的问题（已修复）被移除
+    pub fn update(&mut self, findings: &[Finding], now: DateTime<Utc>) {
+        let mut entries = BTreeMap::new();
+        for finding in findings {
+            let fingerprint = fingerprint(finding);
+            let entry = self
+                .entries
+                .remove(&fingerprint)
+                .unwrap_or_else(|| BaselineEntry {
+                    added_at: now,
+                    rule_id: finding.rule_id.clone(),
+                    file_path: finding.file_path.clone(),
+                    title: finding.title.clone(),
+                });
+            entries.insert(fingerprint, entry);
+        }
+        self.entries = entries;
+    }
+
+    /// 基线条目在 now 时是否已超过老化期限（加入时间早于 now 减去期限）
+    pub fn is_expired(
+        entry: &BaselineEntry,
+        max_age_days: Option<u32>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        max_age_days.is_some_and(|days| now - entry.added_at > Duration::days(i64::from(days)))
+    }
+
+    /// 对扫描结果应用基线，并在结果中记录抑制与逾期的统计
+    pub fn apply_to(
+        &self,
+        result: &mut ScanResult,
+        file: &Path,
+        max_age_days: Option<u32>,
+        now: DateTime<Utc>,
+    ) {
+        let (suppressed, overdue) = self.apply(&mut result.findings, max_age_days, now);
+        result.baseline = Some(BaselineSummary {
+            file: file.to_path_buf(),
+            suppressed,
+            overdue,
+            max_age_days,
+        });
+    }
+
+    /// 过滤掉基线中未过期的问题，保留新问题与逾期问题；返回被抑制的问题数与逾期问题的指纹
+    pub fn apply(
+        &self,
+        findings: &mut Vec<Finding>,
+        max_age_days: Option<u32>,
+        now: DateTime<Utc>,
+    ) -> (usize, Vec<String>) {
+        let mut suppressed = 0;
+        let mut overdue = Vec::new();
+        findings.retain(|finding| {
+            let fingerprint = fingerprint(finding);
+            match self.entries.get(&fingerprint) {
+                None => true,
+                Some(entry) if Self::is_expired(entry, max_age_days, now) => {
+                    overdue.push(fingerprint);
+                    true
+                }
+                Some(_) => {
+                    suppressed += 1;
+                    false
+                }
+            }
+        });
+        (suppressed, overdue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule: &str) -> Finding {
+        let mut finding = Finding {
+            title: format!("{rule} issue"),
+            file_path: PathBuf::from("src/app.py"),
+            line: 3,
+            column: 1,
+            severity: "ERROR".to_string(),
+            rule_id: Some(rule.to_string()),
+            code_snippet: Some(format!("{rule}(input)")),
+            message: String::new(),
+            remediation: None,
+            fingerprint: String::new(),
+            scanners: Vec::new(),
+        };
+        finding.fingerprint = finding.compute_fingerprint();
+        finding
+    }
+
+    #[test]
+    fn test_update_keeps_added_at_and_drops_fixed() {
+        let start = Utc::now();
+        let mut baseline = Baseline::default();
+        baseline.update(&[finding("sqli"), finding("xss")], start);
+        assert_eq!(baseline.entries.len(), 2);
+
+        let later = start + Duration::days(10);
+        baseline.update(&[finding("sqli"), finding("eval")], later);
+        let added: Vec<DateTime<Utc>> = baseline.entries.values().map(|e| e.added_at).collect();
+        assert_eq!(baseline.entries.len(), 2);
+        assert!(added.contains(&start) && added.contains(&later));
+        assert!(!baseline.entries.contains_key(&finding("xss").fingerprint));
+    }
+
+    #[test]
+    fn test_aging_window_boundaries() {
+        let added = Utc::now();
+        let mut baseline = Baseline::default();
+        baseline.update(&[finding("sqli")], added);
+        let window = Duration::days(30);
+
+        // 刚好未到期限：仍被抑制
+        let mut findings = vec![finding("sqli"), finding("new")];
+        let now = added + window - Duration::seconds(1);
+        let (suppressed, overdue) = baseline.apply(&mut findings, Some(30), now);
+        assert_eq!((suppressed, overdue.len()), (1, 0));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule_id.as_deref(), Some("new"));
+
+        // 正好等于期限时尚未“超过”
+        let mut findings = vec![finding("sqli")];
+        let (suppressed, _) = baseline.apply(&mut findings, Some(30), added + window);
+        assert_eq!(suppressed, 1);
+
+        // 刚超过期限：重新报告为逾期
+        let mut findings = vec![finding("sqli"), finding("new")];
+        let now = added + window + Duration::seconds(1);
+        let (suppressed, overdue) = baseline.apply(&mut findings, Some(30), now);
+        assert_eq!(suppressed, 0);
+        assert_eq!(overdue, vec![finding("sqli").fingerprint]);
+        assert_eq!(findings.len(), 2);
+
+        // 未设置期限时永不过期
+        let mut findings = vec![finding("sqli")];
+        let (suppressed, overdue) =
+            baseline.apply(&mut findings, None, added + Duration::days(3650));
+        assert_eq!((suppressed, overdue.len()), (1, 0));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitai-baseline.json");
+        assert_eq!(Baseline::load(&path).unwrap(), Baseline::default());
+
+        let mut baseline = Baseline::default();
+        baseline.update(&[finding("sqli")], Utc::now());
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+
+        std::fs::write(&path, r#"{"version": 99, "entries": {}}"#).unwrap();
+        assert!(Baseline::load(&path).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+// 单个扫描问题的 AI 解释
+// `gitai scan --explain <编号|指纹>` 将问题及其周围代码发送给 AI，输出针对性的修复建议；
+// 解释按指纹缓存，AI 不可用时退化为规则自带的说明
+
+use crate::config::Config;
+use crate::i18n::Lang;
+use crate::scan::Finding;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 问题行前后展示的代码行数
+const CONTEXT_LINES: usize = 8;
+
+/// 解释来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplanationSource {
+    /// 本次调用 AI 生成
+    Ai,
+    /// 来自缓存
+    Cache,
+    /// AI 不可用，使用规则自带的说明与修复建议
+    Rule,
+}
+
+/// 单个问题的解释
+#[derive(Debug, Clone, Serialize)]
+pub struct FindingExplanation {
+    pub finding: Finding,
+    /// 问题行周围的代码（带行号，问题行以 `>` 标记）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    pub explanation: String,
+    pub source: ExplanationSource,
+}
+
+impl FindingExplanation {
+    /// 人类可读的说明
+    pub fn to_text(&self) -> String {
+        let finding = &self.finding;
+        let mut out = format!(
+            "🔎 {} ({}:{})\n",
+            finding.title,
+            finding.file_path.display(),
+            finding.line
+        );
+        if let Some(rule_id) = &finding.rule_id {
+            out.push_str(&format!(
+                "{}\n",
+                crate::tr!("scan.explain_rule", rule = rule_id)
+            ));
+        }
+        out.push_str(&format!(
+            "{}\n",
+            crate::tr!(
+                "scan.explain_fingerprint",
+                fingerprint = finding.fingerprint
+            )
+        ));
+        if let Some(context) = &self.context {
+            out.push_str(&format!("\n{context}\n"));
+        }
+        let header = match self.source {
+            ExplanationSource::Ai => crate::tr!("scan.explain_ai"),
+            ExplanationSource::Cache => crate::tr!("scan.explain_cached"),
+            ExplanationSource::Rule => crate::tr!("scan.explain_rule_only"),
+        };
+        out.push_str(&format!("\n{header}\n{}\n", self.explanation.trim_end()));
+        out
+    }
+}
+
+/// 缓存的解释
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedExplanation {
+    fingerprint: String,
+    language: String,
+    explanation: String,
+    created_at: String,
+}
+
+/// 按 1 起始的编号（与 `gitai scan` 的列表一致）或指纹前缀选择问题
+pub fn select_finding<'a>(findings: &'a [Finding], selector: &str) -> Option<&'a Finding> {
+    let selector = selector.trim();
+    if let Ok(index) = selector.parse::<usize>() {
+        if let Some(finding) = index.checked_sub(1).and_then(|i| findings.get(i)) {
+            return Some(finding);
+        }
+    }
+    let mut matches = findings.iter().filter(|f| {
+        let fingerprint = if f.fingerprint.is_empty() {
+            f.compute_fingerprint()
+        } else {
+            f.fingerprint.clone()
+        };
+        !selector.is_empty() && fingerprint.starts_with(selector)
+    });
+    // 指纹前缀不唯一时不做猜测
+    match (matches.next(), matches.next()) {
+        (Some(finding), None) => Some(finding),
+        _ => None,
+    }
+}
+
+/// 读取问题行前后的代码；路径先按当前目录解析，再按扫描路径解析
+pub fn code_context(finding: &Finding, scan_root: &Path) -> Option<String> {
+    let path = [
+        finding.file_path.clone(),
+        scan_root.join(&finding.file_path),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    if finding.line == 0 || finding.line > lines.len() {
+        return None;
+    }
+    let start = finding.line.saturating_sub(CONTEXT_LINES).max(1);
+    let end = (finding.line + CONTEXT_LINES).min(lines.len());
+    let width = end.to_string().len();
+    let context = (start..=end)
+        .map(|n| {
+            let marker = if n == finding.line { '>' } else { ' ' };
+            format!("{marker} {n:>width$} | {}", lines[n - 1])
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(context)
+}
+
+/// 构建发送给 AI 的提示词
+pub fn build_prompt(finding: &Finding, context: Option<&str>, lang: Lang) -> String {
+    let language = match lang {
+        Lang::Zh => "简体中文",
+        Lang::En => "English",
+    };
+    let mut prompt = format!(
+        "You are a security-focused code reviewer. Explain the following static analysis \
+         finding and how to fix it. Answer in {language} with three short sections: \
+         why it is a problem, whether it is likely a false positive in this context, \
+         and a concrete fix (with a minimal code change).\n\n\
+         Rule: {}\nSeverity: {}\nFile: {}:{}\nMessage: {}\n",
+        finding.rule_id.as_deref().unwrap_or("-"),
+        finding.severity,
+        finding.file_path.display(),
+        finding.line,
+        finding.message
+    );
+    if let Some(fix) = &finding.remediation {
+        prompt.push_str(&format!("Suggested autofix from the rule: {fix}\n"));
+    }
+    match (context, &finding.code_snippet) {
+        (Some(context), _) => prompt.push_str(&format!(
+            "\nCode context (the finding line is marked with '>'):\n```\n{context}\n```\n"
+        )),
+        (None, Some(snippet)) => prompt.push_str(&format!(
+            "\nMatched code:\n```\n{}\n```\n",
+            snippet.trim_end()
+        )),
+        (None, None) => {}
+    }
+    prompt
+}
+
+/// AI 不可用时的说明：规则消息加上规则自带的修复建议
+pub fn rule_explanation(finding: &Finding) -> String {
+    let mut text = finding.message.clone();
+    if let Some(fix) = &finding.remediation {
+        text.push_str(&format!(
+            "\n\n{}\n{fix}",
+            crate::tr!("scan.explain_rule_fix")
+        ));
+    }
+    text
+}
+
+fn cache_path(fingerprint: &str, lang: Lang) -> PathBuf {
+    crate::utils::paths::explain_cache_dir().join(format!("{fingerprint}_{}.json", lang.code()))
+}
+
+/// 读取缓存的解释
+pub fn load_cached(fingerprint: &str, lang: Lang) -> Option<String> {
+    let content = std::fs::read_to_string(cache_path(fingerprint, lang)).ok()?;
+    let cached: CachedExplanation = serde_json::from_str(&content).ok()?;
+    Some(cached.explanation)
+}
+
+/// 保存解释到缓存
+pub fn save_cached(
+    fingerprint: &str,
+    lang: Lang,
+    explanation: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = cache_path(fingerprint, lang);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let cached = CachedExplanation {
+        fingerprint: fingerprint.to_string(),
+        language: lang.code().to_string(),
+        explanation: explanation.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&cached)?)?;
+    Ok(())
+}
+
+/// 解释单个问题：优先使用缓存，其次调用 AI；`use_ai` 为 false 或 AI 调用失败时使用规则说明
+pub async fn explain_finding(
+    config: &Config,
+    finding: &Finding,
+    scan_root: &Path,
+    use_ai: bool,
+) -> FindingExplanation {
+    let lang = crate::i18n::current();
+    let context = code_context(finding, scan_root);
+    let fingerprint = if finding.fingerprint.is_empty() {
+        finding.compute_fingerprint()
+    } else {
+        finding.fingerprint.clone()
+    };
+    let mut finding = finding.clone();
+    finding.fingerprint = fingerprint.clone();
+
+    let (explanation, source) = if let Some(cached) = load_cached(&fingerprint, lang) {
+        (cached, ExplanationSource::Cache)
+    } else if use_ai {
+        let prompt = build_prompt(&finding, context.as_deref(), lang);
+        match ask_ai(config, &prompt).await {
+            Ok(text) => {
+                if let Err(e) = save_cached(&fingerprint, lang, &text) {
+                    log::warn!("保存问题解释缓存失败: {e}");
+                }
+                (text, ExplanationSource::Ai)
+            }
+            Err(e) => {
+                eprintln!("{}", crate::tr!("scan.explain_ai_failed", error = e));
+                (rule_explanation(&finding), ExplanationSource::Rule)
+            }
+        }
+    } else {
+        (rule_explanation(&finding), ExplanationSource::Rule)
+    };
+
+    FindingExplanation {
+        finding,
+        context,
+        explanation,
+        source,
+    }
+}
+
+#[cfg(feature = "ai")]
+async fn ask_ai(
+    config: &Config,
+    prompt: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    crate::progress!("{}", crate::tr!("scan.explain_calling_ai"));
+    let text = crate::ai::call_ai(config, prompt).await?;
+    Ok(text.trim().to_string())
+}
+
+#[cfg(not(feature = "ai"))]
+async fn ask_ai(
+    _config: &Config,
+    _prompt: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    Err(crate::tr!("scan.explain_ai_disabled").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(line: usize, snippet: &str) -> Finding {
+        let mut finding = Finding {
+            title: "Detected eval".to_string(),
+            file_path: PathBuf::from("app.py"),
+            line,
+            column: 1,
+            severity: "ERROR".to_string(),
+            rule_id: Some("python.avoid-eval".to_string()),
+            code_snippet: Some(snippet.to_string()),
+            message: "Avoid eval".to_string(),
+            remediation: Some("ast.literal_eval(x)".to_string()),
+            fingerprint: String::new(),
+            scanners: Vec::new(),
+        };
+        finding.fingerprint = finding.compute_fingerprint();
+        finding
+    }
+
+    #[test]
+    fn test_select_by_index_and_fingerprint() {
+        let findings = vec![finding(3, "eval(a)"), finding(9, "eval(b)")];
+        assert_eq!(select_finding(&findings, "2").unwrap().line, 9);
+        let fingerprint = &findings[0].fingerprint;
+        assert_eq!(
+            select_finding(&findings, &fingerprint[..8]).unwrap().line,
+            3
+        );
+        assert!(select_finding(&findings, "not-a-fingerprint").is_none());
+        assert!(select_finding(&findings, "").is_none());
+    }
+
+    #[test]
+    fn test_context_marks_finding_line_and_prompt_includes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let source: String = (1..=20).map(|n| format!("line{n}\n")).collect();
+        std::fs::write(dir.path().join("app.py"), source).unwrap();
+
+        let finding = finding(12, "line12");
+        let context = code_context(&finding, dir.path()).unwrap();
+        assert!(context.contains("> 12 | line12"));
+        assert!(context.contains("   4 | line4"));
+        assert!(!context.contains("| line3"));
+
+        let prompt = build_prompt(&finding, Some(&context), Lang::En);
+        assert!(prompt.contains("Rule: python.avoid-eval"));
+        assert!(prompt.contains("> 12 | line12"));
+        assert!(prompt.contains("ast.literal_eval(x)"));
+        assert!(rule_explanation(&finding).contains("ast.literal_eval(x)"));
+    }
+}
@@ -61,7 +61,7 @@ impl SecurityReviewer {
         issue_ids: &[String],
         security_scan: bool,
     ) -> Result<SecurityReviewResult, Box<dyn std::error::Error + Send + Sync>> {
-        println!("🔍 正在进行AI时代安全评审...");
+        crate::progress!("🔍 正在进行AI时代安全评审...");
 
         let mut all_insights = Vec::new();
 
@@ -77,7 +77,7 @@ impl SecurityReviewer {
 
         // 3. 对每个变更文件进行安全分析
         for (file_path, _file_diff) in changed_files {
-            println!("📁 分析文件: {}", file_path);
+            crate::progress!("📁 分析文件: {}", file_path);
 
             // 检测文件语言
             let language = self.detect_language(&file_path);
@@ -323,10 +323,10 @@ impl SecurityReviewer {
                         .collect();
                     Ok(insights)
                 }
-                Err(_) => Ok(Vec::new())
+                Err(_) => Ok(Vec::new()),
             }
         }
-        
+
         #[cfg(not(feature = "security"))]
         {
             log::debug!("Security scan feature is not enabled");
@@ -1,7 +1,7 @@
 #![allow(clippy::uninlined_format_args)]
 
 use crate::tree_sitter::{
-    queries::QueriesManager, ClassInfo, CommentInfo, FunctionInfo, StructuralSummary,
+    complexity, queries::QueriesManager, ClassInfo, CommentInfo, FunctionInfo, StructuralSummary,
     SupportedLanguage,
 };
 use tree_sitter::{Node, Query, QueryCursor, Tree};
@@ -72,7 +72,7 @@ impl StructureAnalyzer {
                 (class_declaration
                   name: (identifier) @class.name
                   superclass: (superclass (type_identifier) @class.extends)?
-                  interfaces: (super_interfaces (interface_type_list (type_identifier) @class.implements))?
+                  interfaces: (super_interfaces (type_list (type_identifier) @class.implements))?
                 ) @class.definition
 
                 (interface_declaration
@@ -152,12 +152,6 @@ impl StructureAnalyzer {
                   parameters: (parameters) @function.parameters
                   return_type: (type)? @function.return_type
                 ) @function.definition
-
-                (async_function_definition
-                  name: (identifier) @function.name
-                  parameters: (parameters) @function.parameters
-                  return_type: (type)? @function.return_type
-                ) @function.definition
                 "#;
 
                 // Python类查询
@@ -202,9 +196,7 @@ impl StructureAnalyzer {
 
                 (method_definition
                   name: (property_identifier) @function.name
-                  value: (function_expression
-                    parameters: (formal_parameters) @function.parameters
-                  )
+                  parameters: (formal_parameters) @function.parameters
                 ) @function.definition
                 "#;
 
@@ -434,6 +426,7 @@ impl StructureAnalyzer {
             comments: Vec::with_capacity(self.comment_buffer.capacity()),
             complexity_hints: Vec::new(),
             calls: Vec::new(),
+            truncated: false,
         };
 
         let root_node = tree.root_node();
@@ -522,11 +515,17 @@ impl StructureAnalyzer {
             let mut function = FunctionInfo {
                 name: String::new(),
                 parameters: Vec::new(),
+                params: Vec::new(),
                 return_type: None,
                 line_start: 0,
                 line_end: 0,
                 is_async: false,
                 visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
+                cyclomatic_complexity: 0,
+                cognitive_complexity: 0,
+                halstead_volume: 0.0,
             };
 
             for capture in m.captures {
@@ -539,7 +538,7 @@ impl StructureAnalyzer {
                             function.name = text;
                         }
                         "function.parameters" => {
-                            function.parameters = self.parse_parameters(&text);
+                            function.set_parameters(self.parse_parameters(&text), self.language);
                         }
                         "function.return_type" => {
                             function.return_type = Some(text);
@@ -547,6 +546,10 @@ impl StructureAnalyzer {
                         "function.definition" => {
                             function.line_start = captured_node.start_position().row + 1;
                             function.line_end = captured_node.end_position().row + 1;
+                            let complexity = complexity::measure(captured_node, source);
+                            function.cyclomatic_complexity = complexity.cyclomatic;
+                            function.cognitive_complexity = complexity.cognitive;
+                            function.halstead_volume = complexity.halstead_volume;
                         }
                         _ => {}
                     }
@@ -584,6 +587,9 @@ impl StructureAnalyzer {
                 is_abstract: false,
                 extends: None,
                 implements: Vec::new(),
+                visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
             };
 
             for capture in m.captures {
@@ -731,11 +737,17 @@ impl StructureAnalyzer {
             let mut function = FunctionInfo {
                 name: String::with_capacity(32),   // 预分配
                 parameters: Vec::with_capacity(5), // 预分配
+                params: Vec::new(),
                 return_type: None,
                 line_start: 0,
                 line_end: 0,
                 is_async: false,
                 visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
+                cyclomatic_complexity: 0,
+                cognitive_complexity: 0,
+                halstead_volume: 0.0,
             };
 
             for capture in m.captures {
@@ -758,6 +770,10 @@ impl StructureAnalyzer {
                             "function.definition" => {
                                 function.line_start = captured_node.start_position().row + 1;
                                 function.line_end = captured_node.end_position().row + 1;
+                                let complexity = complexity::measure(captured_node, source);
+                                function.cyclomatic_complexity = complexity.cyclomatic;
+                                function.cognitive_complexity = complexity.cognitive;
+                                function.halstead_volume = complexity.halstead_volume;
                             }
                             _ => {}
                         }
@@ -775,10 +791,8 @@ impl StructureAnalyzer {
         for function in &mut functions_to_process {
             if function.parameters.len() == 1 && function.parameters[0].contains('(') {
                 let param_text = function.parameters[0].clone();
-                function.parameters.clear();
                 function
-                    .parameters
-                    .extend(self.parse_parameters_optimized(&param_text));
+                    .set_parameters(self.parse_parameters_optimized(&param_text), self.language);
             }
         }
         self.function_buffer = functions_to_process;
@@ -809,6 +823,9 @@ impl StructureAnalyzer {
                 is_abstract: false,
                 extends: None,
                 implements: Vec::with_capacity(3), // 预分配
+                visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
             };
 
             for capture in m.captures {
@@ -969,6 +986,12 @@ impl StructureAnalyzer {
 mod tests {
     use super::*;
     use crate::tree_sitter::queries::QueriesManager;
+    #[cfg(any(
+        feature = "tree-sitter-java",
+        feature = "tree-sitter-c",
+        feature = "tree-sitter-cpp",
+        feature = "tree-sitter-go"
+    ))]
     use tree_sitter::Parser;
 
     #[test]
@@ -1058,11 +1081,17 @@ mod tests {
                     FunctionInfo {
                         name: "shortFunction".to_string(),
                         parameters: vec!["param1".to_string(), "param2".to_string()],
+                        params: Vec::new(),
                         return_type: Some("String".to_string()),
                         line_start: 1,
                         line_end: 10,
                         is_async: false,
                         visibility: Some("public".to_string()),
+                        generics: Vec::new(),
+                        annotations: Vec::new(),
+                        cyclomatic_complexity: 0,
+                        cognitive_complexity: 0,
+                        halstead_volume: 0.0,
                     },
                     FunctionInfo {
                         name: "longFunction".to_string(),
@@ -1074,11 +1103,17 @@ mod tests {
                             "param5".to_string(),
                             "param6".to_string(),
                         ],
+                        params: Vec::new(),
                         return_type: Some("void".to_string()),
                         line_start: 20,
                         line_end: 150, // 130行，超过100行限制
                         is_async: false,
                         visibility: Some("private".to_string()),
+                        generics: Vec::new(),
+                        annotations: Vec::new(),
+                        cyclomatic_complexity: 0,
+                        cognitive_complexity: 0,
+                        halstead_volume: 0.0,
                     },
                 ],
                 classes: Vec::new(),
@@ -1087,6 +1122,7 @@ mod tests {
                 comments: Vec::new(),
                 complexity_hints: Vec::new(),
                 calls: Vec::new(),
+                truncated: false,
             };
 
             // 添加更多的函数以达到数量限制
@@ -1094,11 +1130,17 @@ mod tests {
                 summary.functions.push(FunctionInfo {
                     name: format!("function{i}"),
                     parameters: vec!["param".to_string()],
+                    params: Vec::new(),
                     return_type: None,
                     line_start: i * 10,
                     line_end: i * 10 + 5,
                     is_async: false,
                     visibility: None,
+                    generics: Vec::new(),
+                    annotations: Vec::new(),
+                    cyclomatic_complexity: 0,
+                    cognitive_complexity: 0,
+                    halstead_volume: 0.0,
                 });
             }
 
@@ -1127,11 +1169,17 @@ mod tests {
                 functions: vec![FunctionInfo {
                     name: "test_function".to_string(),
                     parameters: vec!["param1".to_string()],
+                    params: Vec::new(),
                     return_type: Some("String".to_string()),
                     line_start: 1,
                     line_end: 10,
                     is_async: false,
                     visibility: Some("pub".to_string()),
+                    generics: Vec::new(),
+                    annotations: Vec::new(),
+                    cyclomatic_complexity: 0,
+                    cognitive_complexity: 0,
+                    halstead_volume: 0.0,
                 }],
                 classes: Vec::new(),
                 imports: Vec::new(),
@@ -1139,6 +1187,7 @@ mod tests {
                 comments: Vec::new(),
                 complexity_hints: Vec::new(),
                 calls: Vec::new(),
+                truncated: false,
             };
 
             let hints = analyzer.calculate_complexity_hints(&summary);
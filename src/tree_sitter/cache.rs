@@ -27,6 +27,9 @@ impl CacheKey {
     }
 }
 
+/// 缓存格式版本；分析器输出的结构发生变化时递增，使旧的磁盘缓存失效
+pub const CACHE_VERSION: u32 = 4;
+
 /// 缓存项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry {
@@ -36,6 +39,9 @@ pub struct CacheEntry {
     pub timestamp: u64,
     /// 访问次数
     pub access_count: u32,
+    /// 写入时的缓存格式版本（旧缓存缺省为 0）
+    #[serde(default)]
+    pub version: u32,
 }
 
 impl CacheEntry {
@@ -48,11 +54,15 @@ impl CacheEntry {
                 .unwrap()
                 .as_secs(),
             access_count: 1,
+            version: CACHE_VERSION,
         }
     }
 
-    /// 检查缓存是否过期
+    /// 检查缓存是否过期（格式版本不一致也视为过期）
     pub fn is_expired(&self, max_age_seconds: u64) -> bool {
+        if self.version != CACHE_VERSION {
+            return true;
+        }
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -383,6 +393,7 @@ mod tests {
             comments: vec![],
             complexity_hints: vec![],
             calls: vec![],
+            truncated: false,
         };
 
         let mut entry = CacheEntry::new(summary);
@@ -390,6 +401,11 @@ mod tests {
 
         assert!(entry.is_expired(10));
         assert!(!CacheEntry::new(StructuralSummary::default()).is_expired(3600));
+
+        // 旧格式的缓存（无版本字段）视为过期
+        let mut stale = CacheEntry::new(StructuralSummary::default());
+        stale.version = 0;
+        assert!(stale.is_expired(3600));
     }
 
     #[test]
@@ -422,6 +438,7 @@ mod tests {
             comments: vec![],
             complexity_hints: vec![],
             calls: vec![],
+            truncated: false,
         };
 
         // 测试缓存未命中
@@ -0,0 +1,312 @@
+// 函数复杂度计算
+// 按语法树节点类型统计圈复杂度与认知复杂度，节点名覆盖已支持语言的常见分支结构；
+// 同时按语法树叶子节点近似 Halstead 体积，供可维护性指数使用
+
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// if 节点
+const IF_KINDS: &[&str] = &["if_expression", "if_statement"];
+
+/// 循环节点
+const LOOP_KINDS: &[&str] = &[
+    "while_expression",
+    "while_statement",
+    "loop_expression",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "do_statement",
+];
+
+/// 多路分支整体（认知复杂度只计一次）
+const SWITCH_KINDS: &[&str] = &[
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+];
+
+/// 多路分支中的单个分支（只计入圈复杂度）
+const CASE_KINDS: &[&str] = &[
+    "match_arm",
+    "case_clause",
+    "switch_case",
+    "switch_label",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "case_statement",
+];
+
+/// 异常捕获
+const CATCH_KINDS: &[&str] = &["catch_clause", "except_clause"];
+
+/// 条件表达式（三元运算符）
+const CONDITIONAL_KINDS: &[&str] = &["conditional_expression", "ternary_expression"];
+
+/// 嵌套的闭包/匿名函数：自身不计分，但加深其中结构的嵌套层级
+const NESTED_FUNCTION_KINDS: &[&str] = &[
+    "closure_expression",
+    "lambda",
+    "lambda_expression",
+    "arrow_function",
+    "function_expression",
+    "func_literal",
+];
+
+/// 单个函数的复杂度
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complexity {
+    /// 圈复杂度：1 + 分支点数（if/循环/case/catch/三元/布尔运算符）
+    pub cyclomatic: usize,
+    /// 认知复杂度：结构每出现一次 +1，并按所在嵌套深度额外加分；
+    /// else/else if 与连续的同种布尔运算符各 +1，不受嵌套影响
+    pub cognitive: usize,
+    /// Halstead 体积 `N × log2(n)`：匿名叶子节点（关键字、运算符、标点）视为运算符，
+    /// 具名叶子节点（标识符、字面量）视为操作数，N 为两者总数，n 为不同词法单元数；注释不计
+    pub halstead_volume: f64,
+}
+
+/// 计算函数定义节点的复杂度
+pub fn measure(definition: Node, source: &[u8]) -> Complexity {
+    let mut complexity = Complexity {
+        cyclomatic: 1,
+        cognitive: 0,
+        halstead_volume: 0.0,
+    };
+    let mut cursor = definition.walk();
+    for child in definition.children(&mut cursor) {
+        visit(child, 0, source, &mut complexity);
+    }
+    complexity.halstead_volume = halstead_volume(definition, source);
+    complexity
+}
+
+fn halstead_volume(definition: Node, source: &[u8]) -> f64 {
+    let mut total = 0usize;
+    let mut vocabulary: HashSet<(bool, &[u8])> = HashSet::new();
+    let mut stack = vec![definition];
+    while let Some(node) = stack.pop() {
+        if node.kind().contains("comment") {
+            continue;
+        }
+        if node.child_count() == 0 {
+            // 运算符按节点类型区分，操作数按文本区分
+            let token = if node.is_named() {
+                &source[node.byte_range()]
+            } else {
+                node.kind().as_bytes()
+            };
+            total += 1;
+            vocabulary.insert((node.is_named(), token));
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    total as f64 * (vocabulary.len().max(2) as f64).log2()
+}
+
+fn visit(node: Node, nesting: usize, source: &[u8], complexity: &mut Complexity) {
+    let kind = node.kind();
+    let mut child_nesting = nesting;
+
+    if IF_KINDS.contains(&kind) {
+        complexity.cyclomatic += 1;
+        if is_else_if(node) {
+            complexity.cognitive += 1;
+        } else {
+            complexity.cognitive += 1 + nesting;
+            child_nesting = nesting + 1;
+        }
+        // 没有 else_clause 包裹的 else 分支（Java、Go 等）
+        if let Some(alternative) = node.child_by_field_name("alternative") {
+            let alt_kind = alternative.kind();
+            if !IF_KINDS.contains(&alt_kind) && !is_else_kind(alt_kind) {
+                complexity.cognitive += 1;
+            }
+        }
+    } else if kind == "elif_clause" {
+        complexity.cyclomatic += 1;
+        complexity.cognitive += 1;
+    } else if kind == "else_clause" {
+        let mut cursor = node.walk();
+        let has_if = node
+            .named_children(&mut cursor)
+            .any(|child| IF_KINDS.contains(&child.kind()));
+        if !has_if {
+            complexity.cognitive += 1;
+        }
+    } else if LOOP_KINDS.contains(&kind)
+        || CATCH_KINDS.contains(&kind)
+        || CONDITIONAL_KINDS.contains(&kind)
+    {
+        complexity.cyclomatic += 1;
+        complexity.cognitive += 1 + nesting;
+        child_nesting = nesting + 1;
+    } else if SWITCH_KINDS.contains(&kind) {
+        complexity.cognitive += 1 + nesting;
+        child_nesting = nesting + 1;
+    } else if CASE_KINDS.contains(&kind) {
+        if !is_default_case(node, source) {
+            complexity.cyclomatic += 1;
+        }
+    } else if NESTED_FUNCTION_KINDS.contains(&kind) {
+        child_nesting = nesting + 1;
+    } else if let Some(operator) = boolean_operator(node, source) {
+        complexity.cyclomatic += 1;
+        // 连续的同种运算符（a && b && c）只计一次认知复杂度
+        let continues_sequence = node
+            .parent()
+            .and_then(|parent| boolean_operator(parent, source))
+            .is_some_and(|parent_operator| parent_operator == operator);
+        if !continues_sequence {
+            complexity.cognitive += 1;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        visit(child, child_nesting, source, complexity);
+    }
+}
+
+fn is_else_kind(kind: &str) -> bool {
+    kind == "else_clause" || kind == "elif_clause"
+}
+
+/// `else if`：if 位于 else 分支内，或直接作为上一个 if 的 alternative
+fn is_else_if(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    parent.kind() == "else_clause"
+        || (IF_KINDS.contains(&parent.kind())
+            && parent.child_by_field_name("alternative") == Some(node))
+}
+
+/// `default:` 分支不增加圈复杂度
+fn is_default_case(node: Node, source: &[u8]) -> bool {
+    node.utf8_text(source)
+        .is_ok_and(|text| text.trim_start().starts_with("default"))
+}
+
+/// 短路布尔运算符（`&&`、`||`、`and`、`or`）
+fn boolean_operator<'a>(node: Node, source: &'a [u8]) -> Option<&'a str> {
+    if node.kind() != "binary_expression" && node.kind() != "boolean_operator" {
+        return None;
+    }
+    let operator = node
+        .child_by_field_name("operator")?
+        .utf8_text(source)
+        .ok()?;
+    matches!(operator, "&&" | "||" | "and" | "or").then_some(operator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree_sitter::SupportedLanguage;
+
+    /// 解析代码并计算第一个顶层函数定义的复杂度
+    fn measure_first(language: SupportedLanguage, code: &str, function_kind: &str) -> Complexity {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(language.language().unwrap()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let definition = root
+            .named_children(&mut cursor)
+            .find(|node| node.kind() == function_kind)
+            .expect("function definition");
+        measure(definition, code.as_bytes())
+    }
+
+    #[cfg(feature = "tree-sitter-rust")]
+    #[test]
+    fn test_rust_complexity() {
+        let straight = measure_first(
+            SupportedLanguage::Rust,
+            "fn add(a: i32, b: i32) -> i32 { a + b }",
+            "function_item",
+        );
+        assert_eq!((straight.cyclomatic, straight.cognitive), (1, 0));
+        // 18 个词法单元：运算符 fn ( : , ) -> { + } 共 9 种，操作数 add a b i32 共 4 种
+        assert!((straight.halstead_volume - 18.0 * 13f64.log2()).abs() < 1e-9);
+
+        let code = r#"
+fn classify(items: &[i32], strict: bool) -> i32 {
+    let mut total = 0;
+    for item in items {                  // +1
+        if *item > 0 && strict {         // +2（嵌套 1），&& +1
+            total += 1;
+        } else if *item < 0 {            // +1
+            total -= 1;
+        } else {                         // +1
+            match item {                 // +3（嵌套 2）
+                0 => {}
+                _ => total += 2,
+            }
+        }
+    }
+    total
+}
+"#;
+        let complexity = measure_first(SupportedLanguage::Rust, code, "function_item");
+        // 1 + for + if + && + else if + 两个 match 分支
+        assert_eq!(complexity.cyclomatic, 7);
+        assert_eq!(complexity.cognitive, 9);
+    }
+
+    #[cfg(feature = "tree-sitter-python")]
+    #[test]
+    fn test_python_complexity() {
+        let code = r#"
+def check(values, limit):
+    if not values:                       # +1
+        return None
+    elif len(values) > limit or limit < 0 and values:  # +1，or +1，and +1
+        return False
+    try:
+        while values:                    # +1
+            values.pop()
+    except IndexError:                   # +1
+        pass
+    return True
+"#;
+        let complexity = measure_first(SupportedLanguage::Python, code, "function_definition");
+        assert_eq!(complexity.cyclomatic, 7);
+        assert_eq!(complexity.cognitive, 6);
+    }
+
+    #[cfg(feature = "tree-sitter-javascript")]
+    #[test]
+    fn test_javascript_nested_callback() {
+        let code = r#"
+function run(items) {
+    items.forEach((item) => {
+        if (item.ok) {                   // +2（闭包内嵌套 1）
+            return item.ok ? 1 : 0;      // +3（嵌套 2）
+        }
+    });
+    switch (items.length) {              // +1
+        case 0: return 0;
+        case 1: return 1;
+        default: return 2;
+    }
+}
+"#;
+        let complexity = measure_first(SupportedLanguage::JavaScript, code, "function_declaration");
+        // 1 + if + 三元 + 两个 case（default 不计）
+        assert_eq!(complexity.cyclomatic, 5);
+        assert_eq!(complexity.cognitive, 6);
+    }
+}
@@ -279,6 +279,10 @@ comment_query = """
                 .call_query
                 .clone()
                 .or_else(|| default.call_query.clone()),
+            import_query: custom
+                .import_query
+                .clone()
+                .or_else(|| default.import_query.clone()),
         }
     }
 
@@ -367,6 +371,7 @@ mod tests {
             class_query: "default_class".to_string(),
             comment_query: "default_comment".to_string(),
             call_query: None,
+            import_query: None,
         };
 
         let custom = LanguageQueries {
@@ -374,6 +379,7 @@ mod tests {
             class_query: String::new(),
             comment_query: "custom_comment".to_string(),
             call_query: None,
+            import_query: None,
         };
 
         let merged = manager.merge_queries(&default, &custom);
@@ -401,6 +407,7 @@ mod tests {
                 class_query: String::new(),
                 comment_query: String::new(),
                 call_query: None,
+                import_query: None,
             },
         };
 
@@ -420,6 +427,7 @@ mod tests {
                 class_query: String::new(),
                 comment_query: String::new(),
                 call_query: None,
+                import_query: None,
             },
         };
 
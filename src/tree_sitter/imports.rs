@@ -0,0 +1,607 @@
+// 导入分类模块
+// 将结构摘要中的导入路径归类为标准库 / 第三方 / 本地模块，并提取外部包名
+
+use super::SupportedLanguage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 导入类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// 语言标准库 / 运行时内置模块
+    Std,
+    /// 第三方依赖
+    ThirdParty,
+    /// 项目内部模块
+    Local,
+}
+
+impl ImportKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportKind::Std => "std",
+            ImportKind::ThirdParty => "third-party",
+            ImportKind::Local => "local",
+        }
+    }
+}
+
+impl std::fmt::Display for ImportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+const RUST_STD: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+const PYTHON_STDLIB: &[&str] = &[
+    "__future__",
+    "abc",
+    "argparse",
+    "array",
+    "ast",
+    "asyncio",
+    "base64",
+    "bisect",
+    "builtins",
+    "bz2",
+    "calendar",
+    "cmath",
+    "collections",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "csv",
+    "ctypes",
+    "dataclasses",
+    "datetime",
+    "decimal",
+    "difflib",
+    "dis",
+    "email",
+    "enum",
+    "errno",
+    "faulthandler",
+    "fcntl",
+    "filecmp",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "getopt",
+    "getpass",
+    "gettext",
+    "glob",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "imaplib",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "linecache",
+    "locale",
+    "logging",
+    "lzma",
+    "math",
+    "mimetypes",
+    "mmap",
+    "multiprocessing",
+    "numbers",
+    "operator",
+    "os",
+    "pathlib",
+    "pickle",
+    "pkgutil",
+    "platform",
+    "plistlib",
+    "pprint",
+    "profile",
+    "queue",
+    "random",
+    "re",
+    "sched",
+    "secrets",
+    "select",
+    "selectors",
+    "shelve",
+    "shlex",
+    "shutil",
+    "signal",
+    "smtplib",
+    "socket",
+    "socketserver",
+    "sqlite3",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "struct",
+    "subprocess",
+    "sys",
+    "sysconfig",
+    "tarfile",
+    "tempfile",
+    "textwrap",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "tomllib",
+    "trace",
+    "traceback",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uuid",
+    "venv",
+    "warnings",
+    "weakref",
+    "webbrowser",
+    "xml",
+    "zipfile",
+    "zlib",
+    "zoneinfo",
+];
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "crypto",
+    "dgram",
+    "dns",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "querystring",
+    "readline",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+const JAVA_STD_PREFIXES: &[&str] = &["java.", "javax.", "jdk.", "sun.", "com.sun."];
+
+const C_STD_HEADERS: &[&str] = &[
+    "assert.h",
+    "complex.h",
+    "ctype.h",
+    "errno.h",
+    "fenv.h",
+    "float.h",
+    "inttypes.h",
+    "iso646.h",
+    "limits.h",
+    "locale.h",
+    "math.h",
+    "setjmp.h",
+    "signal.h",
+    "stdalign.h",
+    "stdarg.h",
+    "stdatomic.h",
+    "stdbool.h",
+    "stddef.h",
+    "stdint.h",
+    "stdio.h",
+    "stdlib.h",
+    "stdnoreturn.h",
+    "string.h",
+    "tgmath.h",
+    "threads.h",
+    "time.h",
+    "uchar.h",
+    "wchar.h",
+    "wctype.h",
+    "unistd.h",
+    "pthread.h",
+    "fcntl.h",
+    "dirent.h",
+    "dlfcn.h",
+    "poll.h",
+    "termios.h",
+];
+
+/// 导入分类器
+///
+/// 默认只依据语言惯例分类；通过 [`ImportClassifier::for_project`] 可额外识别
+/// 项目自身的包名（Cargo 包名、Go module 路径、Python 顶层包）为本地模块，
+/// 并以 Cargo.toml 中声明的依赖区分 Rust 的第三方 crate 与本地模块。
+#[derive(Debug, Clone, Default)]
+pub struct ImportClassifier {
+    local_roots: Vec<String>,
+    /// Cargo.toml 声明的依赖 crate 名（`-` 已替换为 `_`）；`None` 表示无清单
+    rust_dependencies: Option<Vec<String>>,
+}
+
+impl ImportClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定额外视为本地模块的包名 / 路径前缀
+    pub fn with_local_roots(roots: Vec<String>) -> Self {
+        Self {
+            local_roots: roots,
+            rust_dependencies: None,
+        }
+    }
+
+    /// 指定 Rust 项目声明的依赖 crate；未声明的路径根将视为本地模块
+    pub fn with_rust_dependencies(mut self, crates: Vec<String>) -> Self {
+        self.rust_dependencies = Some(crates.into_iter().map(|c| c.replace('-', "_")).collect());
+        self
+    }
+
+    /// 从项目根目录的清单文件推断本地包名
+    pub fn for_project(root: &Path) -> Self {
+        let mut roots = Vec::new();
+        let mut rust_dependencies = None;
+
+        if let Ok(content) = std::fs::read_to_string(root.join("Cargo.toml")) {
+            if let Ok(manifest) = toml::from_str::<toml::Value>(&content) {
+                for table in ["package", "lib"] {
+                    if let Some(name) = manifest
+                        .get(table)
+                        .and_then(|t| t.get("name"))
+                        .and_then(|n| n.as_str())
+                    {
+                        roots.push(name.replace('-', "_"));
+                    }
+                }
+                rust_dependencies = Some(cargo_dependency_names(&manifest));
+            }
+        }
+
+        if let Ok(content) = std::fs::read_to_string(root.join("go.mod")) {
+            if let Some(module) = content
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("module "))
+            {
+                roots.push(module.trim().to_string());
+            }
+        }
+
+        // Python 顶层包：根目录或 src/ 下包含 __init__.py 的目录
+        for dir in [root.to_path_buf(), root.join("src")] {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() && path.join("__init__.py").exists() {
+                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                        roots.push(name.to_string());
+                    }
+                }
+            }
+        }
+
+        roots.sort();
+        roots.dedup();
+        let classifier = Self::with_local_roots(roots);
+        match rust_dependencies {
+            Some(crates) => classifier.with_rust_dependencies(crates),
+            None => classifier,
+        }
+    }
+
+    fn is_local_root(&self, package: &str) -> bool {
+        self.local_roots.iter().any(|root| {
+            package == root
+                || package
+                    .strip_prefix(root.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// 对单个导入路径分类
+    pub fn classify(&self, import: &str, language: SupportedLanguage) -> ImportKind {
+        let import = import.trim();
+        let package = package_name(import, language);
+
+        match language {
+            SupportedLanguage::Rust => {
+                if matches!(package.as_str(), "crate" | "self" | "super")
+                    || self.is_local_root(&package)
+                {
+                    ImportKind::Local
+                } else if RUST_STD.contains(&package.as_str()) {
+                    ImportKind::Std
+                } else if let Some(crates) = &self.rust_dependencies {
+                    // 有清单时，未声明为依赖的路径根是本地模块（2018 版相对路径）
+                    if crates.contains(&package) {
+                        ImportKind::ThirdParty
+                    } else {
+                        ImportKind::Local
+                    }
+                } else if package.chars().next().is_some_and(char::is_uppercase) {
+                    // crate 名不会以大写开头，多为本地类型/枚举路径（如 `Ordering::*`）
+                    ImportKind::Local
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+            SupportedLanguage::Python => {
+                if import.starts_with('.') || self.is_local_root(&package) {
+                    ImportKind::Local
+                } else if PYTHON_STDLIB.contains(&package.as_str()) {
+                    ImportKind::Std
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+            SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+                // 相对路径与常见的路径别名（@/、~/）均指向项目内部
+                if import.starts_with('.')
+                    || import.starts_with('/')
+                    || import.starts_with("@/")
+                    || import.starts_with("~/")
+                    || self.is_local_root(&package)
+                {
+                    ImportKind::Local
+                } else if import.starts_with("node:") || NODE_BUILTINS.contains(&package.as_str()) {
+                    ImportKind::Std
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+            SupportedLanguage::Java => {
+                if self
+                    .local_roots
+                    .iter()
+                    .any(|root| import == root || import.starts_with(&format!("{root}.")))
+                {
+                    ImportKind::Local
+                } else if JAVA_STD_PREFIXES.iter().any(|p| import.starts_with(p)) {
+                    ImportKind::Std
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+            SupportedLanguage::Go => {
+                // 本地 module 路径可能不含域名，需先于标准库判断
+                if self.is_local_root(import) {
+                    ImportKind::Local
+                } else if !import.split('/').next().unwrap_or("").contains('.') {
+                    ImportKind::Std
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+            SupportedLanguage::C | SupportedLanguage::Cpp => {
+                if import.starts_with('"') {
+                    ImportKind::Local
+                } else if C_STD_HEADERS.contains(&package.as_str())
+                    || package.starts_with("sys/")
+                    || (language == SupportedLanguage::Cpp && !package.contains('.'))
+                {
+                    // C++ 标准库头文件没有扩展名（<vector>、<cstdio>）
+                    ImportKind::Std
+                } else {
+                    ImportKind::ThirdParty
+                }
+            }
+        }
+    }
+}
+
+/// 收集 Cargo 清单中各依赖表（含 target 专属依赖）的 crate 名
+fn cargo_dependency_names(manifest: &toml::Value) -> Vec<String> {
+    const TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+    let mut tables: Vec<&toml::Value> = TABLES.iter().filter_map(|t| manifest.get(*t)).collect();
+    if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values() {
+            tables.extend(TABLES.iter().filter_map(|t| target.get(*t)));
+        }
+    }
+
+    let mut names: Vec<String> = tables
+        .iter()
+        .filter_map(|t| t.as_table())
+        .flat_map(|t| t.keys())
+        .map(|name| name.replace('-', "_"))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// 提取导入对应的包名（用于按依赖聚合）
+///
+/// Rust 取 crate 名，Python 取顶层包，npm 取（带 scope 的）包名，
+/// Java 取前三段小写包路径，Go 取 `域名/组织/仓库`，C/C++ 取头文件路径。
+pub fn package_name(import: &str, language: SupportedLanguage) -> String {
+    let import = import.trim();
+    match language {
+        SupportedLanguage::Rust => import
+            .trim_start_matches("::")
+            .split(['{', ':', ' '])
+            .next()
+            .unwrap_or(import)
+            .to_string(),
+        SupportedLanguage::Python => {
+            if import.starts_with('.') {
+                import.to_string()
+            } else {
+                import.split('.').next().unwrap_or(import).to_string()
+            }
+        }
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+            let import = import.strip_prefix("node:").unwrap_or(import);
+            let take = if import.starts_with('@') { 2 } else { 1 };
+            import.split('/').take(take).collect::<Vec<_>>().join("/")
+        }
+        SupportedLanguage::Java => {
+            let segments: Vec<&str> = import
+                .split('.')
+                .take_while(|s| s.chars().next().is_some_and(|c| c.is_ascii_lowercase()))
+                .take(3)
+                .collect();
+            if segments.is_empty() {
+                import.to_string()
+            } else {
+                segments.join(".")
+            }
+        }
+        SupportedLanguage::Go => {
+            if import.split('/').next().unwrap_or("").contains('.') {
+                import.split('/').take(3).collect::<Vec<_>>().join("/")
+            } else {
+                import.to_string()
+            }
+        }
+        SupportedLanguage::C | SupportedLanguage::Cpp => import
+            .trim_matches(|c| matches!(c, '<' | '>' | '"'))
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_by_language() {
+        use ImportKind::*;
+        use SupportedLanguage as L;
+        let classifier = ImportClassifier::new();
+        let cases = [
+            ("std::collections::HashMap", L::Rust, Std),
+            ("crate::config::Config", L::Rust, Local),
+            ("serde::{Deserialize, Serialize}", L::Rust, ThirdParty),
+            ("os.path", L::Python, Std),
+            (".models", L::Python, Local),
+            ("requests.adapters", L::Python, ThirdParty),
+            ("node:fs", L::JavaScript, Std),
+            ("path", L::JavaScript, Std),
+            ("./utils", L::TypeScript, Local),
+            ("@angular/core", L::TypeScript, ThirdParty),
+            ("java.util.List", L::Java, Std),
+            ("org.junit.Test", L::Java, ThirdParty),
+            ("net/http", L::Go, Std),
+            ("github.com/spf13/cobra", L::Go, ThirdParty),
+            ("<stdio.h>", L::C, Std),
+            ("\"util.h\"", L::C, Local),
+            ("<curl/curl.h>", L::C, ThirdParty),
+            ("<vector>", L::Cpp, Std),
+        ];
+        for (import, language, expected) in cases {
+            assert_eq!(
+                classifier.classify(import, language),
+                expected,
+                "{import} ({language:?})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_package_name() {
+        use SupportedLanguage as L;
+        assert_eq!(package_name("serde::de::Error", L::Rust), "serde");
+        assert_eq!(package_name("tokio", L::Rust), "tokio");
+        assert_eq!(package_name("numpy.linalg", L::Python), "numpy");
+        assert_eq!(
+            package_name("@babel/core/lib/x", L::JavaScript),
+            "@babel/core"
+        );
+        assert_eq!(package_name("lodash/fp", L::JavaScript), "lodash");
+        assert_eq!(
+            package_name("com.fasterxml.jackson.databind.ObjectMapper", L::Java),
+            "com.fasterxml.jackson"
+        );
+        assert_eq!(
+            package_name("github.com/spf13/cobra/doc", L::Go),
+            "github.com/spf13/cobra"
+        );
+        assert_eq!(package_name("<openssl/ssl.h>", L::C), "openssl/ssl.h");
+    }
+
+    #[test]
+    fn test_local_roots() {
+        let classifier = ImportClassifier::with_local_roots(vec![
+            "gitai".to_string(),
+            "example.com/app".to_string(),
+        ]);
+        assert_eq!(
+            classifier.classify("gitai::config", SupportedLanguage::Rust),
+            ImportKind::Local
+        );
+        assert_eq!(
+            classifier.classify("example.com/app/internal/db", SupportedLanguage::Go),
+            ImportKind::Local
+        );
+        assert_eq!(
+            classifier.classify("example.com/application", SupportedLanguage::Go),
+            ImportKind::ThirdParty
+        );
+    }
+
+    #[test]
+    fn test_for_project_reads_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"my-crate\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde-json = \"1\"\n\n[target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("pkg")).unwrap();
+        std::fs::write(dir.path().join("pkg/__init__.py"), "").unwrap();
+
+        let classifier = ImportClassifier::for_project(dir.path());
+        assert_eq!(
+            classifier.classify("my_crate::api", SupportedLanguage::Rust),
+            ImportKind::Local
+        );
+        assert_eq!(
+            classifier.classify("pkg.sub", SupportedLanguage::Python),
+            ImportKind::Local
+        );
+        for (import, expected) in [
+            ("serde_json::Value", ImportKind::ThirdParty),
+            ("libc::c_int", ImportKind::ThirdParty),
+            ("utils::paths", ImportKind::Local),
+        ] {
+            assert_eq!(
+                classifier.classify(import, SupportedLanguage::Rust),
+                expected,
+                "{import}"
+            );
+        }
+    }
+}
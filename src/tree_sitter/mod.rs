@@ -1,11 +1,15 @@
 pub mod analyzer;
 pub mod cache;
+pub mod complexity;
 pub mod custom_queries;
+pub mod imports;
 pub mod queries;
+pub mod results_store;
 pub mod unified_analyzer;
 
 use cache::{CacheKey, TreeSitterCache};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tree_sitter::{Language, Parser};
 
 /// 支持的编程语言
@@ -111,6 +115,26 @@ impl SupportedLanguage {
         }
     }
 
+    /// 从脚本首行的 shebang 推断语言（如 `#!/usr/bin/env python3`）
+    pub fn from_shebang(first_line: &str) -> Option<Self> {
+        let interpreter = first_line.strip_prefix("#!")?;
+        let program = interpreter
+            .split_whitespace()
+            .filter(|part| *part != "-S")
+            .map(|part| part.rsplit('/').next().unwrap_or(part))
+            .find(|part| *part != "env")?;
+
+        if program.starts_with("python") {
+            Some(Self::Python)
+        } else if matches!(program, "node" | "nodejs" | "deno" | "bun") {
+            Some(Self::JavaScript)
+        } else if program.starts_with("ts-node") {
+            Some(Self::TypeScript)
+        } else {
+            None
+        }
+    }
+
     /// 获取所有支持的语言
     pub fn all() -> Vec<Self> {
         vec![
@@ -126,16 +150,64 @@ impl SupportedLanguage {
     }
 }
 
+/// 单文件结构分析的默认大小上限（字节），超出部分不参与分析
+pub const DEFAULT_MAX_FILE_BYTES: usize = 1024 * 1024;
+
+/// 新建的 `TreeSitterManager` 是否启用分析缓存与按提交持久化的结果（进程级设置，
+/// `--no-analysis-cache` 关闭）
+static CACHE_ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+/// 设置之后创建的 `TreeSitterManager` 是否使用分析缓存（`gitai bench` 默认关闭以测量冷启动耗时）
+pub fn set_cache_enabled(enabled: bool) {
+    CACHE_ENABLED.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// 分析缓存是否启用
+pub fn cache_enabled() -> bool {
+    CACHE_ENABLED.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 /// Tree-sitter管理器
 pub struct TreeSitterManager {
     parsers: HashMap<SupportedLanguage, Parser>,
     queries_manager: queries::QueriesManager,
     cache: Option<TreeSitterCache>,
+    /// 按提交持久化的文件分析结果（`analyze_path` 使用）
+    store: Option<results_store::ResultsStore>,
+    max_file_bytes: usize,
+}
+
+/// 大小上限：环境变量 GITAI_MAX_ANALYSIS_FILE_BYTES 优先，否则取默认值（0 表示不限制）
+fn max_file_bytes_from_env() -> usize {
+    std::env::var("GITAI_MAX_ANALYSIS_FILE_BYTES")
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_FILE_BYTES)
+}
+
+/// 截取不超过 max_bytes 的代码前缀，尽量在行尾处截断
+fn truncate_code(code: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(code.len());
+    while !code.is_char_boundary(end) {
+        end -= 1;
+    }
+    match code[..end].rfind('\n') {
+        Some(pos) => &code[..=pos],
+        None => &code[..end],
+    }
 }
 
 impl TreeSitterManager {
-    /// 创建新的管理器
+    /// 创建新的管理器；启用缓存时复用被分析文件所在仓库当前提交在用户缓存中保存的分析结果
     pub async fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let store = cache_enabled().then(results_store::ResultsStore::in_user_cache);
+        Self::with_results_store(store).await
+    }
+
+    /// 创建使用指定结果目录的管理器，store 为 None 时不读写按提交保存的分析结果
+    pub async fn with_results_store(
+        store: Option<results_store::ResultsStore>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let mut parsers = HashMap::new();
         let queries_manager = queries::QueriesManager::new()?;
 
@@ -152,15 +224,26 @@ impl TreeSitterManager {
         queries_manager.ensure_queries_downloaded().await?;
 
         // 初始化缓存 (100项，1小时过期)
-        let cache = TreeSitterCache::new(100, 3600).ok();
+        let cache = if cache_enabled() {
+            TreeSitterCache::new(100, 3600).ok()
+        } else {
+            None
+        };
 
         Ok(Self {
             parsers,
             queries_manager,
             cache,
+            store,
+            max_file_bytes: max_file_bytes_from_env(),
         })
     }
 
+    /// 设置单文件结构分析的大小上限（字节，0 表示不限制）
+    pub fn set_max_file_bytes(&mut self, max_file_bytes: usize) {
+        self.max_file_bytes = max_file_bytes;
+    }
+
     /// 获取指定语言的解析器
     pub fn get_parser(&mut self, language: SupportedLanguage) -> Option<&mut Parser> {
         self.parsers.get_mut(&language)
@@ -172,11 +255,27 @@ impl TreeSitterManager {
     }
 
     /// 分析代码结构（支持缓存）
+    ///
+    /// 代码超过大小上限时只分析上限内的前缀，结果标记 `truncated: true`，
+    /// 避免单个生成/第三方大文件在目录分析中占用过多内存。
     pub fn analyze_structure(
         &mut self,
         code: &str,
         language: SupportedLanguage,
     ) -> Result<StructuralSummary, Box<dyn std::error::Error + Send + Sync>> {
+        if self.max_file_bytes > 0 && code.len() > self.max_file_bytes {
+            log::warn!(
+                "代码过大（{} 字节，上限 {} 字节），仅分析前 {} 字节",
+                code.len(),
+                self.max_file_bytes,
+                self.max_file_bytes
+            );
+            let mut summary =
+                self.analyze_structure(truncate_code(code, self.max_file_bytes), language)?;
+            summary.truncated = true;
+            return Ok(summary);
+        }
+
         log::debug!(
             "开始分析 {:?} 语言代码，代码长度: {} 字符",
             language,
@@ -235,6 +334,448 @@ impl TreeSitterManager {
 
         Ok(result)
     }
+
+    /// 分析单个文件：读取内容、推断语言并执行结构分析
+    ///
+    /// 优先按扩展名推断语言，失败时回退到 shebang；不支持的语言、
+    /// 当前构建未启用的语言或非 UTF-8 文件返回 `Ok(None)`。
+    pub fn analyze_path(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<FileAnalysisResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                log::debug!("跳过非文本文件: {}", path.display());
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let language = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(SupportedLanguage::from_extension)
+            .or_else(|| {
+                content
+                    .lines()
+                    .next()
+                    .and_then(SupportedLanguage::from_shebang)
+            });
+        let Some(language) = language.filter(|lang| self.parsers.contains_key(lang)) else {
+            log::debug!("不支持的文件类型: {}", path.display());
+            return Ok(None);
+        };
+
+        // 同一提交下内容未变的文件直接复用持久化的结果
+        let stored = self.store.clone().map(|store| {
+            let key = results_store::ResultsStore::key(&content, language, self.max_file_bytes);
+            (store, key)
+        });
+        if let Some(summary) = stored
+            .as_ref()
+            .and_then(|(store, key)| store.get(path, key))
+        {
+            log::debug!("复用已保存的分析结果: {}", path.display());
+            return Ok(Some(FileAnalysisResult {
+                path: path.to_path_buf(),
+                language,
+                summary,
+            }));
+        }
+
+        let summary = self.analyze_structure(&content, language)?;
+        if let Some((store, key)) = stored {
+            store.put(path, &key, &summary);
+        }
+        Ok(Some(FileAnalysisResult {
+            path: path.to_path_buf(),
+            language,
+            summary,
+        }))
+    }
+}
+
+/// 从 reader 读取全部源码并按指定语言分析结构（供 `gitai analyze --stdin` 使用）
+pub async fn analyze_reader<R: std::io::Read>(
+    mut reader: R,
+    language: SupportedLanguage,
+) -> Result<StructuralSummary, Box<dyn std::error::Error + Send + Sync>> {
+    let mut code = String::new();
+    reader.read_to_string(&mut code)?;
+    let mut manager = TreeSitterManager::new().await?;
+    manager.analyze_structure(&code, language)
+}
+
+/// 从 `GITAI_ANALYSIS_CONCURRENCY` 环境变量读取并发分析的工作数
+pub fn analysis_concurrency_from_env() -> Option<usize> {
+    std::env::var("GITAI_ANALYSIS_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+}
+
+/// 并发分析的工作数：显式设置（大于 0）时直接采用，否则取 CPU 可用并行度，
+/// 且不超过待分析的文件数（至少为 1）
+pub fn analysis_pool_size(file_count: usize, explicit: Option<usize>) -> usize {
+    let limit = explicit.filter(|&n| n > 0).unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+    limit.min(file_count).max(1)
+}
+
+/// 并发分析多个文件，结果与输入顺序一一对应
+///
+/// 启动至多 `max_concurrent` 个工作线程（未指定时依次取 `GITAI_ANALYSIS_CONCURRENCY`
+/// 与 CPU 可用并行度），每个工作线程持有独立的 `TreeSitterManager`：解析器与内存缓存
+/// 互不共享，磁盘上的分析缓存仍然共用。不支持或分析失败的文件对应 `None`。
+pub async fn analyze_paths_concurrent(
+    paths: &[PathBuf],
+    max_concurrent: Option<usize>,
+) -> Result<Vec<Option<FileAnalysisResult>>, Box<dyn std::error::Error + Send + Sync>> {
+    analyze_paths_concurrent_cancellable(
+        paths,
+        max_concurrent,
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+    .await
+}
+
+/// 可取消的并发分析：工作线程在分析每个文件前检查令牌，取消后返回
+/// [`Cancelled`](crate::utils::cancel::Cancelled) 错误
+pub async fn analyze_paths_concurrent_cancellable(
+    paths: &[PathBuf],
+    max_concurrent: Option<usize>,
+    cancel: &crate::utils::cancel::CancellationToken,
+) -> Result<Vec<Option<FileAnalysisResult>>, Box<dyn std::error::Error + Send + Sync>> {
+    let outcomes = analyze_paths_with_outcomes(paths, max_concurrent, cancel).await?;
+    Ok(outcomes
+        .into_iter()
+        .map(FileAnalysisOutcome::into_analyzed)
+        .collect())
+}
+
+/// 单个文件的并发分析结果
+#[derive(Debug, Clone)]
+pub enum FileAnalysisOutcome {
+    /// 分析成功
+    Analyzed(Box<FileAnalysisResult>),
+    /// 不支持的语言、当前构建未启用的语言或非文本文件
+    Unsupported,
+    /// 读取或解析失败（如解析器初始化失败）
+    Failed(String),
+}
+
+impl FileAnalysisOutcome {
+    /// 只保留分析成功的结果
+    pub fn into_analyzed(self) -> Option<FileAnalysisResult> {
+        match self {
+            Self::Analyzed(result) => Some(*result),
+            Self::Unsupported | Self::Failed(_) => None,
+        }
+    }
+}
+
+/// 同 [`analyze_paths_concurrent_cancellable`]，但区分不支持与分析失败的文件，
+/// 失败原因保留在结果中供调用方报告
+pub async fn analyze_paths_with_outcomes(
+    paths: &[PathBuf],
+    max_concurrent: Option<usize>,
+    cancel: &crate::utils::cancel::CancellationToken,
+) -> Result<Vec<FileAnalysisOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+    use std::sync::{Arc, Mutex};
+
+    // 没有文件时不启动工作线程，也就不会创建管理器（可能触发查询文件下载）
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let workers = analysis_pool_size(
+        paths.len(),
+        max_concurrent.or_else(analysis_concurrency_from_env),
+    );
+    log::debug!("并发分析 {} 个文件，工作线程数: {workers}", paths.len());
+    let queue: Arc<Mutex<std::collections::VecDeque<(usize, PathBuf)>>> =
+        Arc::new(Mutex::new(paths.iter().cloned().enumerate().collect()));
+
+    let mut handles = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        cancel.check()?;
+        let mut manager = TreeSitterManager::new().await?;
+        let queue = Arc::clone(&queue);
+        let cancel = cancel.clone();
+        handles.push(tokio::task::spawn_blocking(move || {
+            let mut done = Vec::new();
+            loop {
+                if cancel.is_cancelled() {
+                    break;
+                }
+                let next = queue.lock().map(|mut q| q.pop_front()).unwrap_or(None);
+                let Some((index, path)) = next else {
+                    break;
+                };
+                let outcome = match manager.analyze_path(&path) {
+                    Ok(Some(result)) => FileAnalysisOutcome::Analyzed(Box::new(result)),
+                    Ok(None) => FileAnalysisOutcome::Unsupported,
+                    Err(e) => {
+                        log::warn!("分析文件失败 {}: {e}", path.display());
+                        FileAnalysisOutcome::Failed(e.to_string())
+                    }
+                };
+                done.push((index, outcome));
+            }
+            done
+        }));
+    }
+
+    let mut results: Vec<FileAnalysisOutcome> = paths
+        .iter()
+        .map(|_| FileAnalysisOutcome::Unsupported)
+        .collect();
+    for handle in handles {
+        for (index, outcome) in handle.await? {
+            results[index] = outcome;
+        }
+    }
+    cancel.check()?;
+    Ok(results)
+}
+
+/// 目录分析结果
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryAnalysisResult {
+    /// 分析成功的文件（按路径排序）
+    pub files: Vec<FileAnalysisResult>,
+    /// 不支持的文件数
+    pub unsupported: usize,
+    /// 命中生成文件模式而跳过的文件数
+    pub generated: usize,
+    /// 分析失败的文件及原因
+    pub failed: Vec<SkippedFile>,
+}
+
+impl DirectoryAnalysisResult {
+    /// 由路径与对应的分析结果汇总
+    pub fn from_outcomes(paths: &[PathBuf], outcomes: Vec<FileAnalysisOutcome>) -> Self {
+        let mut result = Self::default();
+        for (path, outcome) in paths.iter().zip(outcomes) {
+            match outcome {
+                FileAnalysisOutcome::Analyzed(analysis) => result.files.push(*analysis),
+                FileAnalysisOutcome::Unsupported => result.unsupported += 1,
+                FileAnalysisOutcome::Failed(reason) => result.failed.push(SkippedFile {
+                    path: path.display().to_string(),
+                    reason,
+                }),
+            }
+        }
+        result
+    }
+
+    /// 分析失败的文件数
+    pub fn failed_count(&self) -> usize {
+        self.failed.len()
+    }
+}
+
+/// 并发分析目录下的全部代码文件（目录遍历规则与依赖图一致，遍历深度使用全局遍历选项），
+/// 分析失败的文件记录在结果中
+pub async fn analyze_directory_concurrent(
+    root: &Path,
+    max_concurrent: Option<usize>,
+) -> Result<DirectoryAnalysisResult, Box<dyn std::error::Error + Send + Sync>> {
+    analyze_directory_concurrent_cancellable(
+        root,
+        max_concurrent,
+        &crate::utils::walk::active(),
+        &crate::utils::cancel::CancellationToken::new(),
+    )
+    .await
+}
+
+/// 可取消的 [`analyze_directory_concurrent`]，walk 指定遍历选项（如最大深度）
+pub async fn analyze_directory_concurrent_cancellable(
+    root: &Path,
+    max_concurrent: Option<usize>,
+    walk: &crate::utils::walk::WalkOptions,
+    cancel: &crate::utils::cancel::CancellationToken,
+) -> Result<DirectoryAnalysisResult, Box<dyn std::error::Error + Send + Sync>> {
+    let mut paths = Vec::new();
+    let generated =
+        crate::architectural_impact::graph_export::collect_files_with(root, &mut paths, walk);
+    paths.sort();
+    let outcomes = analyze_paths_with_outcomes(&paths, max_concurrent, cancel).await?;
+    Ok(DirectoryAnalysisResult {
+        generated,
+        ..DirectoryAnalysisResult::from_outcomes(&paths, outcomes)
+    })
+}
+
+/// 读取文件清单：每行一个路径，忽略空行与 `#` 开头的注释行
+pub fn read_file_list(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// 并发分析清单中的文件（供 `gitai analyze --files-from` 使用），结果按清单顺序合并
+///
+/// 同时至多分析 `jobs` 个文件（未指定时依次取 `GITAI_ANALYSIS_CONCURRENCY` 与 CPU 可用并行度），
+/// 每个并发槽位持有独立的 `TreeSitterManager`。
+pub async fn analyze_files(
+    paths: &[PathBuf],
+    jobs: Option<usize>,
+) -> Result<BatchAnalysis, Box<dyn std::error::Error + Send + Sync>> {
+    use futures_util::stream::{self, StreamExt};
+    use std::sync::{Arc, Mutex};
+
+    if paths.is_empty() {
+        return Ok(BatchAnalysis::from_outcomes(paths, Vec::new()));
+    }
+    let workers = analysis_pool_size(paths.len(), jobs.or_else(analysis_concurrency_from_env));
+    log::debug!("并发分析 {} 个清单文件，并发数: {workers}", paths.len());
+    let mut managers = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        managers.push(TreeSitterManager::new().await?);
+    }
+    let pool = Arc::new(Mutex::new(managers));
+
+    let results: Vec<
+        Result<(usize, FileAnalysisOutcome), Box<dyn std::error::Error + Send + Sync>>,
+    > = stream::iter(paths.iter().cloned().enumerate())
+        .map(|(index, path)| {
+            let pool = Arc::clone(&pool);
+            async move {
+                // buffer_unordered 限制了同时进行的分析数，池中总有空闲的管理器
+                let mut manager = pool
+                    .lock()
+                    .ok()
+                    .and_then(|mut pool| pool.pop())
+                    .ok_or("没有空闲的 Tree-sitter 管理器")?;
+                let (manager, outcome) = tokio::task::spawn_blocking(move || {
+                    let outcome = manager.analyze_listed(&path);
+                    (manager, outcome)
+                })
+                .await?;
+                if let Ok(mut pool) = pool.lock() {
+                    pool.push(manager);
+                }
+                Ok((index, outcome))
+            }
+        })
+        .buffer_unordered(workers)
+        .collect()
+        .await;
+
+    let mut outcomes = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+    outcomes.sort_by_key(|(index, _)| *index);
+    Ok(BatchAnalysis::from_outcomes(
+        paths,
+        outcomes.into_iter().map(|(_, outcome)| outcome),
+    ))
+}
+
+/// 跳过的文件
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// 文件列表的批量分析结果
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct BatchAnalysis {
+    /// 已分析的文件
+    pub files: Vec<String>,
+    /// 跳过的文件（不存在、类型不支持或读取失败）
+    pub skipped: Vec<SkippedFile>,
+    /// 按语言合并的结构摘要
+    pub summary: StructuralSummary,
+}
+
+impl BatchAnalysis {
+    /// 按清单顺序合并各文件的分析结果，无法分析的文件记录为跳过并输出警告
+    fn from_outcomes(
+        paths: &[PathBuf],
+        outcomes: impl IntoIterator<Item = FileAnalysisOutcome>,
+    ) -> Self {
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        let mut by_language: HashMap<String, LanguageSummary> = HashMap::new();
+        let mut truncated = false;
+
+        for (path, outcome) in paths.iter().zip(outcomes) {
+            let display = path.display().to_string();
+            let reason = match outcome {
+                FileAnalysisOutcome::Analyzed(result) => {
+                    let summary = result.summary;
+                    truncated |= summary.truncated;
+                    let entry = by_language
+                        .entry(result.language.name().to_string())
+                        .or_insert_with(|| LanguageSummary {
+                            language: result.language.name().to_string(),
+                            ..Default::default()
+                        });
+                    entry.functions.extend(summary.functions);
+                    entry.classes.extend(summary.classes);
+                    entry.imports.extend(summary.imports);
+                    entry.exports.extend(summary.exports);
+                    entry.comments.extend(summary.comments);
+                    entry.complexity_hints.extend(summary.complexity_hints);
+                    entry.calls.extend(summary.calls);
+                    entry.file_count += 1;
+                    files.push(display);
+                    continue;
+                }
+                FileAnalysisOutcome::Unsupported => "不支持的文件类型".to_string(),
+                FileAnalysisOutcome::Failed(reason) => reason,
+            };
+            log::warn!("跳过文件 {display}: {reason}");
+            skipped.push(SkippedFile {
+                path: display,
+                reason,
+            });
+        }
+
+        let mut summary = StructuralSummary::multi_language(by_language);
+        summary.truncated = truncated;
+        BatchAnalysis {
+            files,
+            skipped,
+            summary,
+        }
+    }
+}
+
+impl TreeSitterManager {
+    /// 逐个分析给定文件并按语言合并结果，无法分析的文件记录为跳过并输出警告
+    pub fn analyze_files(&mut self, paths: &[PathBuf]) -> BatchAnalysis {
+        let outcomes: Vec<FileAnalysisOutcome> =
+            paths.iter().map(|path| self.analyze_listed(path)).collect();
+        BatchAnalysis::from_outcomes(paths, outcomes)
+    }
+
+    /// 分析清单中的单个文件，清单中的文件可能已不存在
+    fn analyze_listed(&mut self, path: &Path) -> FileAnalysisOutcome {
+        if !path.is_file() {
+            return FileAnalysisOutcome::Failed("文件不存在".to_string());
+        }
+        match self.analyze_path(path) {
+            Ok(Some(result)) => FileAnalysisOutcome::Analyzed(Box::new(result)),
+            Ok(None) => FileAnalysisOutcome::Unsupported,
+            Err(e) => FileAnalysisOutcome::Failed(e.to_string()),
+        }
+    }
+}
+
+/// 单文件分析结果
+#[derive(Debug, Clone)]
+pub struct FileAnalysisResult {
+    pub path: PathBuf,
+    pub language: SupportedLanguage,
+    pub summary: StructuralSummary,
 }
 
 /// 代码结构摘要
@@ -252,6 +793,9 @@ pub struct StructuralSummary {
     pub comments: Vec<CommentInfo>,
     pub complexity_hints: Vec<String>,
     pub calls: Vec<FunctionCallInfo>,
+    /// 源码超过大小上限，仅分析了前缀部分
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 /// 单个语言的分析结果
@@ -282,6 +826,7 @@ impl StructuralSummary {
             comments: summary.comments.clone(),
             complexity_hints: summary.complexity_hints.clone(),
             calls: summary.calls.clone(),
+            truncated: false,
         };
         result.language_summaries.insert(language, summary);
         result
@@ -348,12 +893,167 @@ impl LanguageSummary {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FunctionInfo {
     pub name: String,
+    /// 参数原始文本（如 `a: i32`、`String name`）
     pub parameters: Vec<String>,
+    /// 结构化参数（名称与类型），与 `parameters` 一一对应；旧缓存中没有该字段时为空
+    #[serde(default)]
+    pub params: Vec<Param>,
     pub return_type: Option<String>,
     pub line_start: usize,
     pub line_end: usize,
     pub is_async: bool,
     pub visibility: Option<String>,
+    /// 泛型/类型参数（如 `T: Clone`、`T extends Comparable<T>`）
+    #[serde(default)]
+    pub generics: Vec<String>,
+    /// 注解/装饰器/属性（去掉 `@`、`#[...]` 包裹，如 `deprecated`、`Override`）
+    #[serde(default)]
+    pub annotations: Vec<String>,
+    /// 圈复杂度（见 [`complexity::Complexity`]，0 表示未计算）
+    #[serde(default)]
+    pub cyclomatic_complexity: usize,
+    /// 认知复杂度
+    #[serde(default)]
+    pub cognitive_complexity: usize,
+    /// Halstead 体积（0 表示未计算）
+    #[serde(default)]
+    pub halstead_volume: f64,
+}
+
+impl FunctionInfo {
+    /// 同时设置参数原始文本与按语言解析出的结构化参数
+    pub fn set_parameters(&mut self, raw: Vec<String>, language: SupportedLanguage) {
+        self.params = Param::parse_list(&raw, language);
+        self.parameters = raw;
+    }
+
+    /// 是否带有弃用注解
+    pub fn is_deprecated(&self) -> bool {
+        has_deprecation_annotation(&self.annotations)
+    }
+}
+
+/// 单个注解是否为弃用标记
+///
+/// 识别 `#[deprecated]`、`@Deprecated`、`@deprecated(...)`、`@typing_extensions.deprecated` 等形式。
+pub fn is_deprecation_annotation(annotation: &str) -> bool {
+    let name = annotation.split('(').next().unwrap_or(annotation).trim();
+    let last = name.rsplit(['.', ':']).next().unwrap_or(name);
+    last.eq_ignore_ascii_case("deprecated")
+}
+
+/// 注解列表中是否包含弃用标记
+pub fn has_deprecation_annotation(annotations: &[String]) -> bool {
+    annotations.iter().any(|a| is_deprecation_annotation(a))
+}
+
+/// 函数参数（名称与可选类型）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Param {
+    pub name: String,
+    pub ty: Option<String>,
+}
+
+impl Param {
+    /// 无类型参数
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ty: None,
+        }
+    }
+
+    /// 带类型参数
+    pub fn typed(name: impl Into<String>, ty: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ty: Some(ty.into()),
+        }
+    }
+
+    /// 解析参数列表；Go 的 `a, b int` 共享类型，向前回填
+    pub fn parse_list(raw: &[String], language: SupportedLanguage) -> Vec<Param> {
+        let mut params: Vec<Param> = raw.iter().map(|p| Param::parse(p, language)).collect();
+        if language == SupportedLanguage::Go {
+            let mut shared_ty: Option<String> = None;
+            for param in params.iter_mut().rev() {
+                match &param.ty {
+                    Some(ty) => shared_ty = Some(ty.clone()),
+                    None => param.ty = shared_ty.clone(),
+                }
+            }
+        }
+        params
+    }
+
+    /// 按语言语法解析单个参数文本
+    ///
+    /// Rust/TS/Python 为 `name: Type`，Java/C/C++ 为 `Type name`，Go 为 `name Type`；
+    /// 默认值、`mut`/`final` 修饰与注解会被去除。
+    pub fn parse(raw: &str, language: SupportedLanguage) -> Self {
+        let raw = raw.trim();
+        let strip_default = |text: &str| -> String {
+            match text.find('=') {
+                Some(pos) if !text[pos..].starts_with("=>") => text[..pos].trim().to_string(),
+                _ => text.trim().to_string(),
+            }
+        };
+
+        match language {
+            SupportedLanguage::Rust | SupportedLanguage::TypeScript | SupportedLanguage::Python => {
+                let text = if language == SupportedLanguage::Rust {
+                    raw.to_string()
+                } else {
+                    strip_default(raw)
+                };
+                match text.split_once(':') {
+                    Some((name, ty)) => {
+                        let name = name.trim();
+                        let name = name.strip_prefix("mut ").unwrap_or(name);
+                        Self::typed(name.trim_end_matches('?').trim(), ty.trim())
+                    }
+                    None => Self::named(text),
+                }
+            }
+            SupportedLanguage::Java | SupportedLanguage::C | SupportedLanguage::Cpp => {
+                let text = strip_default(raw);
+                let tokens: Vec<&str> = text
+                    .split_whitespace()
+                    .filter(|t| !t.starts_with('@') && *t != "final")
+                    .collect();
+                match tokens.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        // 指针/引用与数组标记归入类型
+                        let name = last.trim_start_matches(['*', '&']);
+                        let prefix = &last[..last.len() - name.len()];
+                        let (name, suffix) = match name.find('[') {
+                            Some(pos) => (&name[..pos], &name[pos..]),
+                            None => (name, ""),
+                        };
+                        Self::typed(name, format!("{}{prefix}{suffix}", rest.join(" ")))
+                    }
+                    // 仅有类型（如 C 的 `void` 或省略参数名）
+                    Some((only, _)) => Self::typed("", *only),
+                    None => Self::named(""),
+                }
+            }
+            SupportedLanguage::Go => match raw.split_once(char::is_whitespace) {
+                Some((name, ty)) => Self::typed(name, ty.trim()),
+                None => Self::named(raw),
+            },
+            SupportedLanguage::JavaScript => Self::named(strip_default(raw)),
+        }
+    }
+}
+
+impl std::fmt::Display for Param {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.ty, self.name.is_empty()) {
+            (Some(ty), true) => f.write_str(ty),
+            (Some(ty), false) => write!(f, "{}: {ty}", self.name),
+            (None, _) => f.write_str(&self.name),
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -366,6 +1066,21 @@ pub struct ClassInfo {
     pub is_abstract: bool,
     pub extends: Option<String>,
     pub implements: Vec<String>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// 泛型/类型参数
+    #[serde(default)]
+    pub generics: Vec<String>,
+    /// 注解/装饰器/属性
+    #[serde(default)]
+    pub annotations: Vec<String>,
+}
+
+impl ClassInfo {
+    /// 是否带有弃用注解
+    pub fn is_deprecated(&self) -> bool {
+        has_deprecation_annotation(&self.annotations)
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -598,4 +1313,367 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_param_parse_by_language() {
+        assert_eq!(
+            Param::parse("final String name", SupportedLanguage::Java),
+            Param::typed("name", "String")
+        );
+        assert_eq!(
+            Param::parse("const char *buf[]", SupportedLanguage::C),
+            Param::typed("buf", "const char*[]")
+        );
+        assert_eq!(
+            Param::parse("opts ...Option", SupportedLanguage::Go),
+            Param::typed("opts", "...Option")
+        );
+        assert_eq!(
+            Param::parse("retries: int = 3", SupportedLanguage::Python),
+            Param::typed("retries", "int")
+        );
+        assert_eq!(
+            Param::parse("label?: string", SupportedLanguage::TypeScript),
+            Param::typed("label", "string")
+        );
+        assert_eq!(
+            Param::parse("cb = () => {}", SupportedLanguage::JavaScript),
+            Param::named("cb")
+        );
+        assert_eq!(Param::typed("n", "usize").to_string(), "n: usize");
+    }
+
+    #[test]
+    fn test_function_info_keeps_raw_parameters() {
+        // 旧缓存中只有字符串形式的 parameters
+        let legacy = r#"{"name": "f", "parameters": ["a: i32"], "return_type": null,
+            "line_start": 1, "line_end": 2, "is_async": false, "visibility": null}"#;
+        let mut func: FunctionInfo = serde_json::from_str(legacy).unwrap();
+        assert_eq!(func.parameters, vec!["a: i32"]);
+        assert!(func.params.is_empty());
+
+        func.set_parameters(
+            vec!["a".to_string(), "b".to_string(), "c int".to_string()],
+            SupportedLanguage::Go,
+        );
+        assert_eq!(func.parameters, vec!["a", "b", "c int"]);
+        assert_eq!(
+            func.params,
+            vec![
+                Param::typed("a", "int"),
+                Param::typed("b", "int"),
+                Param::typed("c", "int"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_supported_language_from_shebang() {
+        assert_eq!(
+            SupportedLanguage::from_shebang("#!/usr/bin/env python3"),
+            Some(SupportedLanguage::Python)
+        );
+        assert_eq!(
+            SupportedLanguage::from_shebang("#!/usr/bin/node"),
+            Some(SupportedLanguage::JavaScript)
+        );
+        assert_eq!(SupportedLanguage::from_shebang("#!/bin/bash"), None);
+        assert_eq!(SupportedLanguage::from_shebang("print('hi')"), None);
+    }
+
+    #[cfg(all(feature = "tree-sitter-rust", feature = "tree-sitter-python"))]
+    #[tokio::test]
+    async fn test_analyze_path() {
+        let mut manager = TreeSitterManager::with_results_store(None)
+            .await
+            .expect("Failed to create manager");
+        let dir = tempfile::tempdir().unwrap();
+
+        let rust_file = dir.path().join("lib.rs");
+        std::fs::write(&rust_file, "pub fn hello() {}").unwrap();
+        let result = manager.analyze_path(&rust_file).unwrap().unwrap();
+        assert_eq!(result.language, SupportedLanguage::Rust);
+        assert_eq!(result.path, rust_file);
+        assert_eq!(result.summary.functions[0].name, "hello");
+
+        // 无扩展名脚本通过 shebang 推断
+        let script = dir.path().join("tool");
+        std::fs::write(&script, "#!/usr/bin/env python3\ndef main():\n    pass\n").unwrap();
+        let result = manager.analyze_path(&script).unwrap().unwrap();
+        assert_eq!(result.language, SupportedLanguage::Python);
+
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "hello").unwrap();
+        assert!(manager.analyze_path(&text).unwrap().is_none());
+
+        let binary = dir.path().join("blob.rs");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        assert!(manager.analyze_path(&binary).unwrap().is_none());
+
+        assert!(manager
+            .analyze_path(&dir.path().join("missing.rs"))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_path_reuses_stored_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = results_store::ResultsStore::at(dir.path().join("store"));
+        let mut manager = TreeSitterManager::with_results_store(Some(store.clone()))
+            .await
+            .expect("Failed to create manager");
+
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "pub fn hello() {}").unwrap();
+        let first = manager.analyze_path(&file).unwrap().unwrap();
+        assert_eq!(first.summary.functions[0].name, "hello");
+
+        // 内容未变时直接返回已保存的结果（篡改保存的结果以确认未重新分析）
+        let key = results_store::ResultsStore::key(
+            "pub fn hello() {}",
+            SupportedLanguage::Rust,
+            manager.max_file_bytes,
+        );
+        let mut stored = store.get(&file, &key).unwrap();
+        stored.imports.push("from_store".to_string());
+        store.put(&file, &key, &stored);
+        let second = manager.analyze_path(&file).unwrap().unwrap();
+        assert_eq!(second.summary.imports, vec!["from_store"]);
+
+        // 内容变化后仅该文件重新分析
+        std::fs::write(&file, "pub fn world() {}").unwrap();
+        let third = manager.analyze_path(&file).unwrap().unwrap();
+        assert_eq!(third.summary.functions[0].name, "world");
+        assert!(third.summary.imports.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_reader() {
+        let input = std::io::Cursor::new("fn main() {\n    helper();\n}\nfn helper() {}\n");
+        let summary = analyze_reader(input, SupportedLanguage::Rust)
+            .await
+            .unwrap();
+        let names: Vec<&str> = summary.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "helper"]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_files_skips_missing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib.rs");
+        let script = dir.path().join("tool.py");
+        std::fs::write(&lib, "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(&script, "def main():\n    pass\n").unwrap();
+        let list = format!(
+            "# changed files\n{}\n\n{}\n{}\n",
+            lib.display(),
+            dir.path().join("missing.rs").display(),
+            script.display()
+        );
+
+        let paths = read_file_list(&list);
+        assert_eq!(paths.len(), 3);
+        let batch = analyze_files(&paths, Some(2)).await.unwrap();
+        // 并发完成的顺序不影响结果顺序
+        assert_eq!(
+            batch.files,
+            vec![lib.display().to_string(), script.display().to_string()]
+        );
+        assert_eq!(batch.skipped.len(), 1);
+        assert!(batch.skipped[0].path.ends_with("missing.rs"));
+        assert_eq!(batch.summary.functions.len(), 3);
+        assert_eq!(batch.summary.language_summaries["rust"].file_count, 1);
+
+        let sequential = TreeSitterManager::new()
+            .await
+            .unwrap()
+            .analyze_files(&paths);
+        assert_eq!(sequential.files, batch.files);
+        assert_eq!(sequential.summary.functions.len(), 3);
+    }
+
+    #[test]
+    fn test_analysis_pool_size() {
+        let parallelism = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        assert_eq!(analysis_pool_size(1000, None), parallelism);
+        assert_eq!(analysis_pool_size(1000, Some(0)), parallelism);
+        assert_eq!(analysis_pool_size(1000, Some(64)), 64);
+        assert_eq!(analysis_pool_size(3, Some(64)), 3);
+        assert_eq!(analysis_pool_size(0, None), 1);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_paths_concurrent_preserves_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for i in 0..6 {
+            let path = dir.path().join(format!("m{i}.rs"));
+            std::fs::write(&path, format!("fn f{i}() {{}}\n")).unwrap();
+            paths.push(path);
+        }
+        paths.insert(2, dir.path().join("notes.txt"));
+        std::fs::write(&paths[2], "plain text\n").unwrap();
+
+        let results = analyze_paths_concurrent(&paths, Some(3)).await.unwrap();
+        assert_eq!(results.len(), 7);
+        assert!(results[2].is_none());
+        let names: Vec<String> = results
+            .iter()
+            .flatten()
+            .map(|r| r.summary.functions[0].name.clone())
+            .collect();
+        assert_eq!(names, vec!["f0", "f1", "f2", "f3", "f4", "f5"]);
+    }
+
+    #[tokio::test]
+    async fn test_directory_analysis_reports_failed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("good.rs");
+        std::fs::write(&good, "fn ok() {}\n").unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "plain text\n").unwrap();
+        // 无法读取的文件（这里是与源码同名的目录）计为失败而不是被静默丢弃
+        let broken = dir.path().join("broken.rs");
+        std::fs::create_dir(&broken).unwrap();
+
+        let paths = vec![good, notes, broken.clone()];
+        let outcomes = analyze_paths_with_outcomes(
+            &paths,
+            Some(2),
+            &crate::utils::cancel::CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(outcomes[1], FileAnalysisOutcome::Unsupported));
+        assert!(matches!(outcomes[2], FileAnalysisOutcome::Failed(_)));
+
+        let result = DirectoryAnalysisResult::from_outcomes(&paths, outcomes);
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.unsupported, 1);
+        assert_eq!(result.failed_count(), 1);
+        assert_eq!(result.failed[0].path, broken.display().to_string());
+        assert!(!result.failed[0].reason.is_empty());
+
+        // 目录分析同时统计跳过的生成文件
+        std::fs::write(dir.path().join("schema_generated.rs"), "fn g() {}\n").unwrap();
+        let result = analyze_directory_concurrent(dir.path(), Some(1))
+            .await
+            .unwrap();
+        assert_eq!(result.generated, 1);
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.files[0].summary.functions[0].name, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_empty_paths_start_no_workers() {
+        // 已取消的令牌会让任何工作线程的启动失败；没有文件时直接返回空结果
+        let cancel = crate::utils::cancel::CancellationToken::new();
+        cancel.cancel();
+        let outcomes = analyze_paths_with_outcomes(&[], Some(4), &cancel)
+            .await
+            .unwrap();
+        assert!(outcomes.is_empty());
+
+        let batch = analyze_files(&[], Some(4)).await.unwrap();
+        assert!(batch.files.is_empty());
+        assert!(batch.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_directory_analysis_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut nested = dir.path().to_path_buf();
+        for level in 1..=4 {
+            std::fs::write(nested.join(format!("level{level}.rs")), "fn f() {}\n").unwrap();
+            nested = nested.join(format!("d{level}"));
+            std::fs::create_dir(&nested).unwrap();
+        }
+
+        let names = |result: &DirectoryAnalysisResult| -> Vec<String> {
+            result
+                .files
+                .iter()
+                .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+        let cancel = crate::utils::cancel::CancellationToken::new();
+        let unlimited = crate::utils::walk::WalkOptions::default();
+        let result =
+            analyze_directory_concurrent_cancellable(dir.path(), Some(2), &unlimited, &cancel)
+                .await
+                .unwrap();
+        assert_eq!(result.files.len(), 4);
+
+        let limited = crate::utils::walk::WalkOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let result =
+            analyze_directory_concurrent_cancellable(dir.path(), Some(2), &limited, &cancel)
+                .await
+                .unwrap();
+        assert_eq!(names(&result), vec!["level2.rs", "level1.rs"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_directory_analysis_symlink_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(sub.join("lib.rs"), "fn f() {}\n").unwrap();
+        // sub/loop -> ..，构成环路；linked.rs 指向同一文件
+        std::os::unix::fs::symlink("..", sub.join("loop")).unwrap();
+        std::os::unix::fs::symlink(sub.join("lib.rs"), dir.path().join("linked.rs")).unwrap();
+
+        let cancel = crate::utils::cancel::CancellationToken::new();
+        let skip = crate::utils::walk::WalkOptions::default();
+        let result = analyze_directory_concurrent_cancellable(dir.path(), Some(2), &skip, &cancel)
+            .await
+            .unwrap();
+        assert_eq!(result.files.len(), 1);
+        assert!(result.files[0].path.ends_with("sub/lib.rs"));
+
+        // 跟随符号链接时每个目录只进入一次，不会无限递归
+        let follow = crate::utils::walk::WalkOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let result =
+            analyze_directory_concurrent_cancellable(dir.path(), Some(2), &follow, &cancel)
+                .await
+                .unwrap();
+        assert_eq!(result.files.len(), 2);
+    }
+
+    #[test]
+    fn test_truncate_code_cuts_at_line_boundary() {
+        assert_eq!(truncate_code("fn a() {}\nfn b() {}\n", 14), "fn a() {}\n");
+        assert_eq!(truncate_code("short", 100), "short");
+        assert_eq!(truncate_code("中文注释", 4), "中");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_code_is_truncated() {
+        let mut manager = TreeSitterManager::new()
+            .await
+            .expect("Failed to create manager");
+        let code: String = (0..200).map(|i| format!("fn f{i}() {{}}\n")).collect();
+
+        let full = manager
+            .analyze_structure(&code, SupportedLanguage::Rust)
+            .unwrap();
+        assert!(!full.truncated);
+        assert_eq!(full.functions.len(), 200);
+
+        manager.set_max_file_bytes(code.len() / 2);
+        let partial = manager
+            .analyze_structure(&code, SupportedLanguage::Rust)
+            .unwrap();
+        assert!(partial.truncated);
+        assert!(!partial.functions.is_empty());
+        assert!(partial.functions.len() < 200);
+    }
 }
@@ -3,6 +3,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::tree_sitter::SupportedLanguage;
+use crate::utils::download::{download_resumable, is_checksum_mismatch, ChecksumManifest};
 
 // Default Tree-sitter queries URL (fallback when config is not available)
 const DEFAULT_NVIM_TREESITTER_BASE: &str =
@@ -137,21 +138,27 @@ impl QueriesManager {
 
         log::info!("Downloading queries for {}", language.name());
 
+        // 基础 URL 下提供 `SHA256SUMS` 清单时校验每个查询文件；下载中断的文件下次从断点继续
+        let client = crate::http::client()?;
+        let manifest = ChecksumManifest::fetch(
+            &client,
+            &format!("{}/SHA256SUMS", self.tree_sitter_base_url),
+        )
+        .await;
+
         // 下载每个查询文件
         for query_file in QUERY_FILES {
-            let url = format!(
-                "{}/{}/{}",
-                self.tree_sitter_base_url,
-                language.name(),
-                query_file
-            );
+            let name = format!("{}/{}", language.name(), query_file);
+            let url = format!("{}/{}", self.tree_sitter_base_url, name);
             let file_path = lang_dir.join(query_file);
+            let expected = manifest.as_ref().and_then(|m| m.sha256_for(&name));
 
-            match self.download_query_file(&url).await {
-                Ok(content) => {
-                    fs::write(&file_path, content)?;
+            match download_resumable(&client, &url, &file_path, expected).await {
+                Ok(()) => {
                     log::debug!("Downloaded {} for {}", query_file, language.name());
                 }
+                // 校验失败说明内容损坏，不能当作缺失文件忽略
+                Err(e) if is_checksum_mismatch(e.as_ref()) => return Err(e),
                 Err(e) => {
                     // 某些语言可能没有所有的查询文件，这是正常的
                     log::debug!(
@@ -167,21 +174,6 @@ impl QueriesManager {
         Ok(())
     }
 
-    /// 下载单个查询文件
-    async fn download_query_file(
-        &self,
-        url: &str,
-    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
-        let response = client.get(url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.text().await?)
-        } else {
-            Err(format!("Failed to download from {}: {}", url, response.status()).into())
-        }
-    }
-
     /// 检查查询文件是否完整（至少有highlights.scm）
     fn is_queries_complete(&self, lang_dir: &Path) -> bool {
         lang_dir.join("highlights.scm").exists()
@@ -0,0 +1,213 @@
+// 按提交持久化的结构分析结果
+// 与内存 LRU 缓存不同，结果按 `<仓库>-<提交>/<文件哈希>` 存放在磁盘上，仓库与提交取自被分析文件
+// 所在的检出而不是进程的工作目录；同一次 CI 中的多个命令
+// 以及同一提交的重跑都可复用；文件内容变化时哈希随之变化，只有该文件会被重新分析
+
+use super::cache::CACHE_VERSION;
+use super::{StructuralSummary, SupportedLanguage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 最多保留的仓库提交目录数，超出时删除最久未写入的目录
+const MAX_COMMITS: usize = 20;
+
+lazy_static::lazy_static! {
+    // 文件所在目录 -> (仓库根目录, HEAD 提交)；同一进程内每个目录只解析一次
+    static ref REPO_BY_DIR: Mutex<HashMap<PathBuf, Option<(PathBuf, String)>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// 持久化的分析结果目录
+#[derive(Debug, Clone)]
+pub struct ResultsStore {
+    location: Location,
+}
+
+#[derive(Debug, Clone)]
+enum Location {
+    /// 所有结果保存在同一目录
+    Fixed(PathBuf),
+    /// 按被分析文件所在仓库的根目录与 HEAD 分目录保存（`<根目录>/v<版本>/<仓库哈希>-<提交>`）
+    PerRepo(PathBuf),
+}
+
+impl ResultsStore {
+    /// 用户缓存中的结果目录，按被分析文件所在的仓库与其 HEAD 区分
+    pub fn in_user_cache() -> Self {
+        Self::under(&crate::utils::paths::analysis_store_dir())
+    }
+
+    /// 同 [`in_user_cache`](Self::in_user_cache)，结果保存在 store_root 下
+    pub fn under(store_root: &Path) -> Self {
+        Self {
+            location: Location::PerRepo(store_root.join(format!("v{CACHE_VERSION}"))),
+        }
+    }
+
+    /// 使用指定目录，不区分仓库与提交
+    pub fn at(dir: PathBuf) -> Self {
+        Self {
+            location: Location::Fixed(dir),
+        }
+    }
+
+    /// 结果文件的键：内容、语言与分析大小上限共同决定分析结果
+    pub fn key(content: &str, language: SupportedLanguage, max_file_bytes: usize) -> String {
+        let digest = md5::compute(content.as_bytes());
+        format!("{digest:x}-{}-{max_file_bytes}", language.name())
+    }
+
+    /// 读取文件 path 已保存的结果；path 不在 Git 仓库中时没有可用的结果
+    pub fn get(&self, path: &Path, key: &str) -> Option<StructuralSummary> {
+        let file = self.dir_for(path)?.join(format!("{key}.json"));
+        let text = std::fs::read_to_string(file).ok()?;
+        match serde_json::from_str(&text) {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                log::debug!("忽略损坏的分析结果 {key}: {e}");
+                None
+            }
+        }
+    }
+
+    /// 保存文件 path 的结果；原子写入，并发工作线程不会读到半个文件
+    pub fn put(&self, path: &Path, key: &str, summary: &StructuralSummary) {
+        let Some(dir) = self.dir_for(path) else {
+            return;
+        };
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&dir)?;
+            let json = serde_json::to_string(summary).map_err(std::io::Error::other)?;
+            crate::utils::atomic_write::write_atomic(dir.join(format!("{key}.json")), json)
+        };
+        if let Err(e) = write() {
+            log::debug!("保存分析结果失败 {key}: {e}");
+        }
+    }
+
+    /// 文件 path 的结果目录
+    fn dir_for(&self, path: &Path) -> Option<PathBuf> {
+        match &self.location {
+            Location::Fixed(dir) => Some(dir.clone()),
+            Location::PerRepo(root) => {
+                let (repo, commit) = repo_head(path)?;
+                let repo_hash = md5::compute(repo.to_string_lossy().as_bytes());
+                let dir = root.join(format!("{:.16}-{commit}", format!("{repo_hash:x}")));
+                if !dir.exists() {
+                    prune(root, MAX_COMMITS.saturating_sub(1));
+                }
+                Some(dir)
+            }
+        }
+    }
+}
+
+/// 文件所在仓库的根目录与 HEAD 提交；不在 Git 仓库中或仓库还没有提交时为 None
+fn repo_head(path: &Path) -> Option<(PathBuf, String)> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dir = std::fs::canonicalize(parent).ok()?;
+    let mut repos = REPO_BY_DIR.lock().ok()?;
+    repos
+        .entry(dir)
+        .or_insert_with_key(|dir| {
+            let out = crate::git::run_git_in(
+                dir,
+                &[
+                    "rev-parse".to_string(),
+                    "--show-toplevel".to_string(),
+                    "HEAD".to_string(),
+                ],
+            )
+            .ok()?;
+            let mut lines = out.lines().map(str::trim);
+            let repo = PathBuf::from(lines.next().filter(|l| !l.is_empty())?);
+            let commit = lines.next().filter(|l| !l.is_empty())?.to_string();
+            Some((repo, commit))
+        })
+        .clone()
+}
+
+/// 只保留最近写入的 `keep` 个提交目录
+fn prune(root: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    let mut dirs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    if dirs.len() <= keep {
+        return;
+    }
+    dirs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, dir) in dirs.into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::debug!("清理分析结果目录失败 {}: {e}", dir.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_and_prune() {
+        let root = tempfile::tempdir().unwrap();
+        let store = ResultsStore::at(root.path().join("abc123"));
+        let file = Path::new("lib.rs");
+        let key = ResultsStore::key("fn a() {}", SupportedLanguage::Rust, 1024);
+        assert_ne!(
+            key,
+            ResultsStore::key("fn b() {}", SupportedLanguage::Rust, 1024)
+        );
+        assert!(store.get(file, &key).is_none());
+
+        let summary = StructuralSummary {
+            language: "rust".to_string(),
+            imports: vec!["std::fs".to_string()],
+            ..Default::default()
+        };
+        store.put(file, &key, &summary);
+        assert_eq!(store.get(file, &key).unwrap().imports, vec!["std::fs"]);
+
+        for (i, name) in ["c1", "c2", "c3"].iter().enumerate() {
+            let dir = root.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            let mtime = std::time::SystemTime::UNIX_EPOCH
+                + std::time::Duration::from_secs(1_000 + i as u64);
+            std::fs::File::open(&dir)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        }
+        prune(root.path(), 2);
+        // abc123 刚写入，c3 次之，其余被删除
+        assert!(root.path().join("abc123").exists());
+        assert!(root.path().join("c3").exists());
+        assert!(!root.path().join("c1").exists());
+        assert!(!root.path().join("c2").exists());
+    }
+
+    #[test]
+    fn test_per_repo_store_skips_files_outside_git() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let file = outside.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}").unwrap();
+        // 临时目录不在 Git 仓库中时既不读也不写
+        if repo_head(&file).is_some() {
+            return;
+        }
+        let store = ResultsStore::under(root.path());
+        let key = ResultsStore::key("fn a() {}", SupportedLanguage::Rust, 1024);
+        store.put(&file, &key, &StructuralSummary::default());
+        assert!(store.get(&file, &key).is_none());
+        assert!(!root.path().join(format!("v{CACHE_VERSION}")).exists());
+    }
+}
@@ -3,8 +3,8 @@
 // 支持用户自定义查询
 
 use crate::tree_sitter::{
-    custom_queries::CustomQueryManager, ClassInfo, CommentInfo, FunctionInfo, StructuralSummary,
-    SupportedLanguage,
+    complexity, custom_queries::CustomQueryManager, ClassInfo, CommentInfo, FunctionInfo,
+    StructuralSummary, SupportedLanguage,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -17,6 +17,7 @@ pub struct LanguageQueries {
     pub class_query: String,
     pub comment_query: String,
     pub call_query: Option<String>,
+    pub import_query: Option<String>,
 }
 
 /// 统一的结构分析器
@@ -28,6 +29,7 @@ pub struct UnifiedAnalyzer {
     class_query: Option<Query>,
     comment_query: Option<Query>,
     call_query: Option<Query>,
+    import_query: Option<Query>,
 }
 
 impl UnifiedAnalyzer {
@@ -50,6 +52,10 @@ impl UnifiedAnalyzer {
             Some(q) => Query::new(lang, q).ok(),
             None => None,
         };
+        let import_query = match &queries.import_query {
+            Some(q) => Query::new(lang, q).ok(),
+            None => None,
+        };
 
         // 记录加载情况
         if function_query.is_none() {
@@ -66,6 +72,11 @@ impl UnifiedAnalyzer {
         } else if call_query.is_none() {
             log::warn!("无法编译 {language:?} 的调用查询");
         }
+        if queries.import_query.is_none() {
+            log::info!("未定义 {language:?} 的导入查询，将跳过导入提取");
+        } else if import_query.is_none() {
+            log::warn!("无法编译 {language:?} 的导入查询");
+        }
 
         Ok(Self {
             language,
@@ -74,6 +85,7 @@ impl UnifiedAnalyzer {
             class_query,
             comment_query,
             call_query,
+            import_query,
         })
     }
 
@@ -172,6 +184,7 @@ impl UnifiedAnalyzer {
             comments: Vec::new(),
             complexity_hints: Vec::new(),
             calls: Vec::new(),
+            truncated: false,
         };
 
         let root_node = tree.root_node();
@@ -200,6 +213,12 @@ impl UnifiedAnalyzer {
             log::debug!("提取到 {} 个调用", summary.calls.len());
         }
 
+        // 提取导入
+        if let Some(ref query) = self.import_query {
+            summary.imports = self.extract_imports(query, root_node, source);
+            log::debug!("提取到 {} 个导入", summary.imports.len());
+        }
+
         // 计算复杂度提示
         summary.complexity_hints = self.calculate_complexity_hints(&summary);
 
@@ -222,12 +241,19 @@ impl UnifiedAnalyzer {
             let mut function = FunctionInfo {
                 name: String::new(),
                 parameters: Vec::new(),
+                params: Vec::new(),
                 return_type: None,
                 line_start: 0,
                 line_end: 0,
                 is_async: false,
                 visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
+                cyclomatic_complexity: 0,
+                cognitive_complexity: 0,
+                halstead_volume: 0.0,
             };
+            let mut definition = None;
 
             for capture in m.captures {
                 let captured_node = capture.node;
@@ -239,7 +265,7 @@ impl UnifiedAnalyzer {
                             function.name = text;
                         }
                         "function.parameters" => {
-                            function.parameters = self.parse_parameters(&text);
+                            function.set_parameters(self.parse_parameters(&text), self.language);
                         }
                         "function.return_type" => {
                             function.return_type = Some(text);
@@ -247,6 +273,7 @@ impl UnifiedAnalyzer {
                         "function.definition" => {
                             function.line_start = captured_node.start_position().row + 1;
                             function.line_end = captured_node.end_position().row + 1;
+                            definition = Some(captured_node);
                         }
                         _ => {}
                     }
@@ -254,6 +281,15 @@ impl UnifiedAnalyzer {
             }
 
             if !function.name.is_empty() {
+                if let Some(def) = definition {
+                    function.visibility = self.infer_visibility(def, &function.name, source);
+                    function.generics = Self::extract_generics(def, source);
+                    function.annotations = self.extract_annotations(def, source);
+                    let complexity = complexity::measure(def, source);
+                    function.cyclomatic_complexity = complexity.cyclomatic;
+                    function.cognitive_complexity = complexity.cognitive;
+                    function.halstead_volume = complexity.halstead_volume;
+                }
                 functions.push(function);
             }
         }
@@ -283,7 +319,11 @@ impl UnifiedAnalyzer {
                 is_abstract: false,
                 extends: None,
                 implements: Vec::new(),
+                visibility: None,
+                generics: Vec::new(),
+                annotations: Vec::new(),
             };
+            let mut definition = None;
 
             for capture in m.captures {
                 let captured_node = capture.node;
@@ -303,6 +343,7 @@ impl UnifiedAnalyzer {
                         "class.definition" => {
                             class.line_start = captured_node.start_position().row + 1;
                             class.line_end = captured_node.end_position().row + 1;
+                            definition = Some(captured_node);
                         }
                         _ => {}
                     }
@@ -310,6 +351,11 @@ impl UnifiedAnalyzer {
             }
 
             if !class.name.is_empty() {
+                if let Some(def) = definition {
+                    class.visibility = self.infer_visibility(def, &class.name, source);
+                    class.generics = Self::extract_generics(def, source);
+                    class.annotations = self.extract_annotations(def, source);
+                }
                 classes.push(class);
             }
         }
@@ -393,14 +439,249 @@ impl UnifiedAnalyzer {
 
     /// 解析参数列表
     fn parse_parameters(&self, params_text: &str) -> Vec<String> {
-        params_text
-            .trim_matches(|c| c == '(' || c == ')')
-            .split(',')
-            .map(|p| p.trim().to_string())
-            .filter(|p| !p.is_empty())
+        let inner = params_text.trim();
+        let inner = inner.strip_prefix('(').unwrap_or(inner);
+        let inner = inner.strip_suffix(')').unwrap_or(inner);
+
+        // 仅在顶层逗号处切分，避免拆开 `HashMap<K, V>` 之类的泛型
+        let mut raw_params = Vec::new();
+        let mut depth = 0usize;
+        let mut current = String::new();
+        for c in inner.chars() {
+            match c {
+                '<' | '(' | '[' | '{' => depth += 1,
+                '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    raw_params.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        raw_params.push(current);
+
+        raw_params
+            .iter()
+            .map(|p| p.trim())
+            // C 的 `(void)` 表示没有参数
+            .filter(|p| !p.is_empty() && *p != "void")
+            .map(str::to_string)
             .collect()
     }
 
+    /// 提取导入路径（按出现顺序去重）
+    fn extract_imports(&self, query: &Query, node: Node, source: &[u8]) -> Vec<String> {
+        let mut cursor = QueryCursor::new();
+        let mut imports: Vec<String> = Vec::new();
+
+        for m in cursor.matches(query, node, source) {
+            for capture in m.captures {
+                if query.capture_names()[capture.index as usize] != "import.path" {
+                    continue;
+                }
+                let Ok(text) = capture.node.utf8_text(source) else {
+                    continue;
+                };
+                let import = self.normalize_import(text);
+                if !import.is_empty() && !imports.contains(&import) {
+                    imports.push(import);
+                }
+            }
+        }
+
+        imports
+    }
+
+    /// 归一化导入文本：去掉字符串引号与 Rust 的 `as` 别名，合并空白
+    ///
+    /// C/C++ 保留 `<...>` / `"..."` 包裹，用于区分系统头文件与本地头文件。
+    fn normalize_import(&self, text: &str) -> String {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        match self.language {
+            SupportedLanguage::JavaScript
+            | SupportedLanguage::TypeScript
+            | SupportedLanguage::Go => text
+                .trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+                .to_string(),
+            SupportedLanguage::Rust => match text.split_once(" as ") {
+                Some((path, _)) if !path.contains('{') => path.to_string(),
+                _ => text,
+            },
+            _ => text,
+        }
+    }
+
+    /// 提取定义上的泛型参数（Rust/Java/TS 的 `type_parameters` 字段）
+    fn extract_generics(definition: Node, source: &[u8]) -> Vec<String> {
+        let Some(params) = definition.child_by_field_name("type_parameters") else {
+            return Vec::new();
+        };
+
+        let mut cursor = params.walk();
+        let generics = params
+            .named_children(&mut cursor)
+            .filter(|n| !n.kind().ends_with("comment"))
+            .filter_map(|n| n.utf8_text(source).ok())
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect();
+        generics
+    }
+
+    /// 提取定义上的注解/装饰器/属性，去掉 `@` 与 `#[...]` 包裹
+    ///
+    /// Rust 取紧邻的 `attribute_item` 兄弟节点，Java 取 `modifiers` 中的注解，
+    /// Python 取外层 `decorated_definition` 的装饰器，JS/TS 取 `decorator` 节点。
+    fn extract_annotations(&self, definition: Node, source: &[u8]) -> Vec<String> {
+        fn children_of_kind<'a>(node: Node<'a>, kinds: &[&str], out: &mut Vec<Node<'a>>) {
+            let mut cursor = node.walk();
+            out.extend(
+                node.children(&mut cursor)
+                    .filter(|c| kinds.contains(&c.kind())),
+            );
+        }
+
+        let mut nodes = Vec::new();
+
+        match self.language {
+            SupportedLanguage::Rust => {
+                // 属性位于定义之前，中间可能夹着文档注释
+                let mut current = definition.prev_sibling();
+                while let Some(node) = current {
+                    match node.kind() {
+                        "attribute_item" => nodes.push(node),
+                        "line_comment" | "block_comment" => {}
+                        _ => break,
+                    }
+                    current = node.prev_sibling();
+                }
+                nodes.reverse();
+            }
+            SupportedLanguage::Java => {
+                let mut cursor = definition.walk();
+                let modifiers = definition
+                    .children(&mut cursor)
+                    .find(|c| c.kind() == "modifiers");
+                if let Some(modifiers) = modifiers {
+                    children_of_kind(modifiers, &["marker_annotation", "annotation"], &mut nodes);
+                }
+            }
+            SupportedLanguage::Python => {
+                if let Some(parent) = definition
+                    .parent()
+                    .filter(|p| p.kind() == "decorated_definition")
+                {
+                    children_of_kind(parent, &["decorator"], &mut nodes);
+                }
+            }
+            SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+                // 类装饰器是定义的子节点，方法装饰器是类体中的前置兄弟节点
+                let mut current = definition.prev_sibling();
+                while let Some(node) = current.filter(|n| n.kind() == "decorator") {
+                    nodes.push(node);
+                    current = node.prev_sibling();
+                }
+                nodes.reverse();
+                children_of_kind(definition, &["decorator"], &mut nodes);
+            }
+            _ => {}
+        }
+
+        nodes
+            .iter()
+            .filter_map(|n| n.utf8_text(source).ok())
+            .map(|text| {
+                let text = text.trim();
+                let inner = text
+                    .strip_prefix("#[")
+                    .and_then(|t| t.strip_suffix(']'))
+                    .or_else(|| text.strip_prefix('@'))
+                    .unwrap_or(text);
+                inner.split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .collect()
+    }
+
+    /// 推断定义的可见性（public / private / crate / protected / package）
+    ///
+    /// 依据各语言惯例：Rust 的 `pub`、Java 修饰符、Go 首字母大写、
+    /// Python 下划线前缀、JS/TS 的 `export`；无法判断时返回 `None`。
+    fn infer_visibility(&self, definition: Node, name: &str, source: &[u8]) -> Option<String> {
+        let child_text = |kind: &str| {
+            let mut cursor = definition.walk();
+            let found = definition
+                .children(&mut cursor)
+                .find(|c| c.kind() == kind)
+                .and_then(|c| c.utf8_text(source).ok())
+                .map(str::to_string);
+            found
+        };
+
+        let visibility = match self.language {
+            SupportedLanguage::Rust => match child_text("visibility_modifier") {
+                Some(v) if v == "pub" => "public",
+                Some(_) => "crate",
+                None => "private",
+            },
+            SupportedLanguage::Java => {
+                let in_interface = definition
+                    .parent()
+                    .is_some_and(|p| p.kind() == "interface_body");
+                let modifiers = child_text("modifiers").unwrap_or_default();
+                let has = |m: &str| modifiers.split_whitespace().any(|w| w == m);
+                if has("public") || in_interface {
+                    "public"
+                } else if has("protected") {
+                    "protected"
+                } else if has("private") {
+                    "private"
+                } else {
+                    "package"
+                }
+            }
+            SupportedLanguage::Go => {
+                if name.chars().next().is_some_and(char::is_uppercase) {
+                    "public"
+                } else {
+                    "private"
+                }
+            }
+            SupportedLanguage::Python => {
+                let dunder = name.starts_with("__") && name.ends_with("__");
+                if name.starts_with('_') && !dunder {
+                    "private"
+                } else {
+                    "public"
+                }
+            }
+            SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+                match child_text("accessibility_modifier").as_deref() {
+                    Some("private") => "private",
+                    Some("protected") => "protected",
+                    _ => {
+                        let mut current = definition.parent();
+                        let mut exported = false;
+                        while let Some(parent) = current {
+                            if parent.kind() == "export_statement" {
+                                exported = true;
+                                break;
+                            }
+                            current = parent.parent();
+                        }
+                        if exported {
+                            "public"
+                        } else {
+                            "private"
+                        }
+                    }
+                }
+            }
+            SupportedLanguage::C | SupportedLanguage::Cpp => return None,
+        };
+
+        Some(visibility.to_string())
+    }
+
     /// 判断是否为文档注释
     fn is_doc_comment(&self, text: &str) -> bool {
         match self.language {
@@ -529,6 +810,332 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "tree-sitter-rust")]
+    #[test]
+    fn test_rust_visibility() {
+        let analyzer = UnifiedAnalyzer::new(SupportedLanguage::Rust).unwrap();
+        let rust_code = r#"
+        pub fn exported() {}
+        pub(crate) fn internal() {}
+        fn hidden() {}
+        pub struct Public;
+        struct Private;
+        "#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(SupportedLanguage::Rust.language().unwrap())
+            .unwrap();
+        let tree = parser.parse(rust_code, None).unwrap();
+        let summary = analyzer.analyze(&tree, rust_code.as_bytes()).unwrap();
+
+        let function_vis = |name: &str| {
+            summary
+                .functions
+                .iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.visibility.clone())
+        };
+        assert_eq!(function_vis("exported").as_deref(), Some("public"));
+        assert_eq!(function_vis("internal").as_deref(), Some("crate"));
+        assert_eq!(function_vis("hidden").as_deref(), Some("private"));
+
+        let class_vis = |name: &str| {
+            summary
+                .classes
+                .iter()
+                .find(|c| c.name == name)
+                .and_then(|c| c.visibility.clone())
+        };
+        assert_eq!(class_vis("Public").as_deref(), Some("public"));
+        assert_eq!(class_vis("Private").as_deref(), Some("private"));
+    }
+
+    #[cfg(feature = "tree-sitter-rust")]
+    #[test]
+    fn test_rust_generics() {
+        let analyzer = UnifiedAnalyzer::new(SupportedLanguage::Rust).unwrap();
+        let rust_code = r#"
+        pub fn pick<'a, T: Clone + Send, const N: usize>(items: &'a [T; N]) -> T {
+            items[0].clone()
+        }
+        pub struct Wrapper<K, V: Default> { k: K, v: V }
+        fn plain() {}
+        "#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(SupportedLanguage::Rust.language().unwrap())
+            .unwrap();
+        let tree = parser.parse(rust_code, None).unwrap();
+        let summary = analyzer.analyze(&tree, rust_code.as_bytes()).unwrap();
+
+        let pick = summary.functions.iter().find(|f| f.name == "pick").unwrap();
+        assert_eq!(
+            pick.generics,
+            vec!["'a", "T: Clone + Send", "const N: usize"]
+        );
+        let plain = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "plain")
+            .unwrap();
+        assert!(plain.generics.is_empty());
+
+        let wrapper = summary
+            .classes
+            .iter()
+            .find(|c| c.name == "Wrapper")
+            .unwrap();
+        assert_eq!(wrapper.generics, vec!["K", "V: Default"]);
+    }
+
+    #[cfg(feature = "tree-sitter-java")]
+    #[test]
+    fn test_java_generics() {
+        let analyzer = UnifiedAnalyzer::new(SupportedLanguage::Java).unwrap();
+        let java_code = r#"
+        public class Box<T extends Comparable<T>> {
+            public <R> R map(Function<T, R> f) { return f.apply(value); }
+        }
+        "#;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(SupportedLanguage::Java.language().unwrap())
+            .unwrap();
+        let tree = parser.parse(java_code, None).unwrap();
+        let summary = analyzer.analyze(&tree, java_code.as_bytes()).unwrap();
+
+        let class = summary.classes.iter().find(|c| c.name == "Box").unwrap();
+        assert_eq!(class.generics, vec!["T extends Comparable<T>"]);
+        let method = summary.functions.iter().find(|f| f.name == "map").unwrap();
+        assert_eq!(method.generics, vec!["R"]);
+        assert_eq!(method.visibility.as_deref(), Some("public"));
+    }
+
+    fn analyze_source(language: SupportedLanguage, code: &str) -> StructuralSummary {
+        let analyzer = UnifiedAnalyzer::new(language).unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(language.language().unwrap()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        analyzer.analyze(&tree, code.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_rust_attributes() {
+        let summary = analyze_source(
+            SupportedLanguage::Rust,
+            r#"
+        #[deprecated(since = "1.2", note = "use new_api")]
+        /// 旧接口
+        #[inline]
+        pub fn old_api() {}
+        pub fn new_api() {}
+        #[derive(Debug, Clone)]
+        pub struct Config { name: String }
+        "#,
+        );
+
+        let old = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "old_api")
+            .unwrap();
+        assert_eq!(
+            old.annotations,
+            vec![
+                "deprecated(since = \"1.2\", note = \"use new_api\")",
+                "inline"
+            ]
+        );
+        assert!(old.is_deprecated());
+        let new = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "new_api")
+            .unwrap();
+        assert!(new.annotations.is_empty());
+        assert!(!new.is_deprecated());
+
+        let config = summary.classes.iter().find(|c| c.name == "Config").unwrap();
+        assert_eq!(config.annotations, vec!["derive(Debug, Clone)"]);
+        assert!(!config.is_deprecated());
+    }
+
+    #[test]
+    fn test_python_decorators() {
+        let summary = analyze_source(
+            SupportedLanguage::Python,
+            r#"
+@dataclass
+class Point:
+    x: int
+
+@app.route("/users")
+@typing_extensions.deprecated("use list_accounts")
+def list_users():
+    pass
+
+def plain():
+    pass
+"#,
+        );
+
+        let point = summary.classes.iter().find(|c| c.name == "Point").unwrap();
+        assert_eq!(point.annotations, vec!["dataclass"]);
+        let users = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "list_users")
+            .unwrap();
+        assert_eq!(
+            users.annotations,
+            vec![
+                "app.route(\"/users\")",
+                "typing_extensions.deprecated(\"use list_accounts\")"
+            ]
+        );
+        assert!(users.is_deprecated());
+        let plain = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "plain")
+            .unwrap();
+        assert!(plain.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_extract_imports() {
+        let rust = analyze_source(
+            SupportedLanguage::Rust,
+            r#"
+        use std::collections::HashMap;
+        use serde::{Deserialize, Serialize};
+        use crate::config::Config as Cfg;
+        extern crate log;
+        "#,
+        );
+        assert_eq!(
+            rust.imports,
+            vec![
+                "std::collections::HashMap",
+                "serde::{Deserialize, Serialize}",
+                "crate::config::Config",
+                "log"
+            ]
+        );
+
+        let python = analyze_source(
+            SupportedLanguage::Python,
+            "import os.path\nimport numpy as np\nfrom .models import User\nfrom requests import get\n",
+        );
+        assert_eq!(
+            python.imports,
+            vec!["os.path", "numpy", ".models", "requests"]
+        );
+
+        let js = analyze_source(
+            SupportedLanguage::JavaScript,
+            "import React from 'react';\nconst fs = require(\"fs\");\nexport { x } from './x';\nconst other = load('nope');\n",
+        );
+        assert_eq!(js.imports, vec!["react", "fs", "./x"]);
+
+        #[cfg(feature = "tree-sitter-go")]
+        {
+            let go = analyze_source(
+                SupportedLanguage::Go,
+                "package main\nimport (\n  \"fmt\"\n  cobra \"github.com/spf13/cobra\"\n)\n",
+            );
+            assert_eq!(go.imports, vec!["fmt", "github.com/spf13/cobra"]);
+        }
+
+        #[cfg(feature = "tree-sitter-java")]
+        {
+            let java = analyze_source(
+                SupportedLanguage::Java,
+                "import java.util.List;\nimport static org.junit.Assert.assertEquals;\nclass A {}\n",
+            );
+            assert_eq!(
+                java.imports,
+                vec!["java.util.List", "org.junit.Assert.assertEquals"]
+            );
+        }
+
+        #[cfg(feature = "tree-sitter-c")]
+        {
+            let c = analyze_source(
+                SupportedLanguage::C,
+                "#include <stdio.h>\n#include \"util.h\"\nint main() { return 0; }\n",
+            );
+            assert_eq!(c.imports, vec!["<stdio.h>", "\"util.h\""]);
+        }
+    }
+
+    #[cfg(feature = "tree-sitter-java")]
+    #[test]
+    fn test_java_annotations() {
+        let summary = analyze_source(
+            SupportedLanguage::Java,
+            r#"
+        @RestController
+        public class UserController {
+            @Deprecated
+            @GetMapping("/users")
+            public List<User> users() { return list; }
+            public void keep() {}
+        }
+        "#,
+        );
+
+        let class = summary
+            .classes
+            .iter()
+            .find(|c| c.name == "UserController")
+            .unwrap();
+        assert_eq!(class.annotations, vec!["RestController"]);
+        let users = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "users")
+            .unwrap();
+        assert_eq!(
+            users.annotations,
+            vec!["Deprecated", "GetMapping(\"/users\")"]
+        );
+        assert!(users.is_deprecated());
+        let keep = summary.functions.iter().find(|f| f.name == "keep").unwrap();
+        assert!(!keep.is_deprecated());
+    }
+
+    #[cfg(feature = "tree-sitter-typescript")]
+    #[test]
+    fn test_typescript_decorators() {
+        let summary = analyze_source(
+            SupportedLanguage::TypeScript,
+            r#"
+@Component({ selector: "app" })
+class AppComponent {
+    @deprecated
+    render() {}
+}
+"#,
+        );
+
+        let class = summary
+            .classes
+            .iter()
+            .find(|c| c.name == "AppComponent")
+            .unwrap();
+        assert_eq!(class.annotations, vec!["Component({ selector: \"app\" })"]);
+        let render = summary
+            .functions
+            .iter()
+            .find(|f| f.name == "render")
+            .unwrap();
+        assert!(render.is_deprecated());
+    }
+
     #[test]
     fn test_load_queries_from_config() {
         // 测试从配置文件加载查询（优先测试已启用语言）
@@ -564,20 +1171,33 @@ mod tests {
             if let Ok(analyzer) = UnifiedAnalyzer::new(lang) {
                 // 测试各种参数格式
                 assert_eq!(analyzer.parse_parameters("()"), Vec::<String>::new());
-                assert_eq!(
-                    analyzer.parse_parameters("(String name)"),
-                    vec!["String name"]
-                );
-                assert_eq!(
-                    analyzer.parse_parameters("(int a, String b, boolean c)"),
-                    vec!["int a", "String b", "boolean c"]
-                );
+                let params = analyzer.parse_parameters("(a, b, c)");
+                assert_eq!(params.len(), 3);
+                assert_eq!(params[1], "b");
             }
         } else {
             println!("跳过参数解析测试 - 没有启用的语言");
         }
     }
 
+    #[cfg(feature = "tree-sitter-rust")]
+    #[test]
+    fn test_parse_typed_parameters() {
+        use crate::tree_sitter::Param;
+
+        let analyzer = UnifiedAnalyzer::new(SupportedLanguage::Rust).unwrap();
+        let raw = analyzer.parse_parameters("(&self, map: HashMap<K, V>, mut n: usize)");
+        assert_eq!(raw, vec!["&self", "map: HashMap<K, V>", "mut n: usize"]);
+        assert_eq!(
+            Param::parse_list(&raw, SupportedLanguage::Rust),
+            vec![
+                Param::named("&self"),
+                Param::typed("map", "HashMap<K, V>"),
+                Param::typed("n", "usize"),
+            ]
+        );
+    }
+
     #[test]
     fn test_is_doc_comment() {
         // 测试 Java （如果启用）
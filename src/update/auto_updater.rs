@@ -286,14 +286,7 @@ impl AutoUpdater {
     }
 
     pub fn get_rules_dir(&self) -> std::path::PathBuf {
-        if let Some(dir) = &self.config.scan.rules_dir {
-            return std::path::PathBuf::from(dir);
-        }
-        dirs::home_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join(".cache")
-            .join("gitai")
-            .join("rules")
+        crate::utils::paths::resolve_rules_dir(self.config.scan.rules_dir.as_deref())
     }
 
     fn normalize_rules_layout(dir: &std::path::Path) -> Result<(), UpdateError> {
@@ -353,7 +346,8 @@ impl AutoUpdater {
         // 带重试机制的版本检查
         self.retry_async(
             || async {
-                let client = reqwest::Client::new();
+                let client =
+                    crate::http::client().map_err(|e| UpdateError::Config(e.to_string()))?;
                 let response = client
                     .get("https://api.github.com/repos/nehcuh/gitai/releases/latest")
                     .header("User-Agent", "gitai")
@@ -36,3 +36,18 @@ impl From<std::io::Error> for UpdateError {
         UpdateError::Io(e)
     }
 }
+
+impl From<UpdateError> for crate::error::GitAIError {
+    fn from(e: UpdateError) -> Self {
+        use crate::error::UpdateError as Unified;
+        let unified = match e {
+            UpdateError::Network(e) => Unified::Network(e.to_string()),
+            UpdateError::Io(e) => Unified::Io(e.to_string()),
+            UpdateError::Config(msg) => Unified::Config(msg),
+            UpdateError::Download(msg) => Unified::Download(msg),
+            UpdateError::Parse(msg) => Unified::Parse(msg),
+            UpdateError::Version(msg) => Unified::Version(msg),
+        };
+        crate::error::GitAIError::Update(unified)
+    }
+}
@@ -0,0 +1,184 @@
+//! 原子写入
+//!
+//! 结果文件（扫描 JSON、评审报告、质量指标导出等）先写入同目录下的临时文件，成功后再重命名为
+//! 目标文件；进程在写入途中被终止时目标文件保持原样，读取方不会看到写了一半的产物。
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 原子地写入文件内容
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents.as_ref()))
+}
+
+/// 由 write 向临时文件写入内容，成功后重命名为 path；write 出错时删除临时文件，目标文件不受影响
+///
+/// path 是符号链接时写入其指向的文件，链接本身保留；目标文件已存在时沿用其权限，
+/// 新建的文件与普通写入一样使用 umask 决定的默认权限
+pub fn write_atomic_with<F>(path: impl AsRef<Path>, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let path = resolve_symlinks(path.as_ref())?;
+    // 临时文件与目标文件位于同一目录，保证重命名不跨文件系统
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let existing = match std::fs::metadata(&path) {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".gitai-").suffix(".tmp");
+    // 临时文件默认仅所有者可读写；新文件按 0o666 创建，由 umask 收窄
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+    write(temp.as_file_mut())?;
+    temp.as_file_mut().flush()?;
+    if let Some(permissions) = existing {
+        temp.as_file().set_permissions(permissions)?;
+    }
+    temp.as_file().sync_all()?;
+    temp.persist(&path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// 沿符号链接找到最终的目标路径（目标可以尚不存在）；不是符号链接时原样返回
+fn resolve_symlinks(path: &Path) -> io::Result<PathBuf> {
+    // 与系统的符号链接解析深度上限一致，避免链接成环时死循环
+    const MAX_LINKS: usize = 40;
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_LINKS {
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let link = std::fs::read_link(&path)?;
+                path = match path.parent() {
+                    Some(parent) if link.is_relative() => parent.join(link),
+                    _ => link,
+                };
+            }
+            _ => return Ok(path),
+        }
+    }
+    Err(io::Error::other(format!(
+        "符号链接层数过多: {}",
+        path.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        write_atomic(&path, "{\"v\": 1}").unwrap();
+        write_atomic(&path, "{\"v\": 2}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"v\": 2}");
+        assert_eq!(entries(dir.path()), vec!["report.json"]);
+    }
+
+    #[test]
+    fn test_interrupted_write_keeps_previous_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        std::fs::write(&path, "complete").unwrap();
+
+        // 写入一部分后失败，模拟写入途中被中断
+        let err = write_atomic_with(&path, |file| {
+            file.write_all(b"{\"findings\": [")?;
+            Err(io::Error::new(io::ErrorKind::Interrupted, "killed"))
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "complete");
+        // 临时文件已清理
+        assert_eq!(entries(dir.path()), vec!["results.json"]);
+
+        // 目标文件原本不存在时，中断后也不会留下文件
+        let missing = dir.path().join("new.md");
+        assert!(write_atomic_with(&missing, |_| Err(io::Error::other("killed"))).is_err());
+        assert!(!missing.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_through_symlink_keeps_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("dotfiles").join("config.toml");
+        std::fs::create_dir_all(real.parent().unwrap()).unwrap();
+        std::fs::write(&real, "old").unwrap();
+        let link = dir.path().join("config.toml");
+        std::os::unix::fs::symlink("dotfiles/config.toml", &link).unwrap();
+
+        write_atomic(&link, "new").unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "new");
+        assert_eq!(entries(real.parent().unwrap()), vec!["config.toml"]);
+
+        // 指向尚不存在的文件的链接：创建链接目标
+        let dangling = dir.path().join("report.md");
+        std::os::unix::fs::symlink(dir.path().join("out.md"), &dangling).unwrap();
+        write_atomic(&dangling, "report").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out.md")).unwrap(),
+            "report"
+        );
+        assert!(std::fs::symlink_metadata(&dangling)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_existing_file_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_atomic(&path, "new").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o640);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_new_file_uses_umask_default() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        // 与普通写入新建的文件比较，不依赖测试环境的 umask
+        let plain = dir.path().join("plain.json");
+        std::fs::write(&plain, "{}").unwrap();
+        let atomic = dir.path().join("report.json");
+        write_atomic(&atomic, "{}").unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&atomic), mode(&plain));
+    }
+}
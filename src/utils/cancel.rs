@@ -0,0 +1,177 @@
+//! 取消令牌
+//!
+//! 供嵌入方与 MCP 中止长时间运行的操作（评审、扫描、目录分析）。令牌可克隆，
+//! 任一副本调用 [`CancellationToken::cancel`] 后所有副本都会观察到取消。
+//!
+//! 取消点：
+//! - [`crate::tree_sitter::analyze_paths_concurrent_cancellable`]：工作线程在分析每个文件前检查，
+//!   已在分析中的文件会完成，剩余文件不再处理
+//! - [`crate::scan::run_opengrep_scan_cancellable`]：终止 OpenGrep 子进程
+//! - [`crate::domain::services::ReviewService::run_with_cancel`]：在评审流程的异步等待点
+//!   （读取远程 diff、AI 调用等）中止，并恢复工作目录
+
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 等待子进程时检查取消的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// 取消令牌
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+impl CancellationToken {
+    /// 创建未取消的令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消；重复调用无副作用
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已取消时返回 [`Cancelled`]
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 等待直到被取消
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // 先登记等待再检查标志，避免错过检查与等待之间发出的通知
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 操作被取消
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "操作已取消")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// 错误是否由取消引起
+pub fn is_cancelled_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<Cancelled>().is_some()
+}
+
+/// 执行命令并收集输出；令牌被取消时终止子进程并返回 [`Cancelled`]
+pub fn output_cancellable(
+    command: &mut Command,
+    cancel: &CancellationToken,
+) -> Result<Output, Box<dyn std::error::Error + Send + Sync>> {
+    cancel.check()?;
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // 在独立线程中读取输出，避免管道写满导致子进程阻塞
+    let stdout = spawn_reader(child.stdout.take());
+    let stderr = spawn_reader(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            // 子进程派生的进程可能仍持有管道，不等待读取线程
+            return Err(Cancelled.into());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_waiters_and_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(clone.check().is_ok());
+
+        let waiter = tokio::spawn(async move { clone.cancelled().await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        token.cancel();
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("waiter not woken")
+            .unwrap();
+        assert_eq!(token.check(), Err(Cancelled));
+        // 已取消后再等待立即返回
+        token.cancelled().await;
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_cancellable_kills_child() {
+        let token = CancellationToken::new();
+        let output = output_cancellable(Command::new("echo").arg("hi"), &token).unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hi");
+
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+        let start = std::time::Instant::now();
+        let err = output_cancellable(Command::new("sleep").arg("30"), &token).unwrap_err();
+        assert!(is_cancelled_error(err.as_ref()));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
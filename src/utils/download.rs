@@ -0,0 +1,477 @@
+//! 可续传、可校验的资源下载
+//!
+//! 下载先写入 `<目标>.part`，响应的 `ETag`（没有时为 `Last-Modified`）记录在 `<目标>.part.validator`。
+//! 中断后再次下载时用 `Range` 加 `If-Range` 请求从已有长度继续：远端资源已变化或服务器不支持范围请求
+//! （返回 200）时从头下载；没有记录校验标识时无法确认部分文件仍有效，同样从头下载。
+//! 给出 SHA-256 时在下载完成后校验，不一致则删除部分文件并返回 [`ChecksumMismatch`]，
+//! 不会留下损坏的目标文件。
+//!
+//! 校验值来自资源旁的 `sha256sum` 格式清单（如查询文件的 `SHA256SUMS`、归档的 `<归档>.sha256`），
+//! 镜像没有提供清单时跳过校验。
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// 下载内容的 SHA-256 与清单不一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub url: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} 校验失败：期望 SHA-256 {}，实际为 {}（已丢弃下载的内容）",
+            self.url, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// 错误是否由校验失败引起（校验失败不应重试）
+pub fn is_checksum_mismatch(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<ChecksumMismatch>().is_some()
+}
+
+/// `sha256sum` 格式的校验清单：每行 `<hex>  <路径>`，路径前的 `*`（二进制模式标记）会被忽略
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChecksumManifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl ChecksumManifest {
+    /// 解析清单；跳过空行、注释与不是 64 位十六进制的记录
+    pub fn parse(text: &str) -> Self {
+        let mut entries = BTreeMap::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (hash, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                continue;
+            }
+            let name = name.trim().trim_start_matches('*');
+            entries.insert(name.to_string(), hash.to_ascii_lowercase());
+        }
+        Self { entries }
+    }
+
+    /// 获取 url 处的清单；清单不存在或无法读取时返回 None（并非所有镜像都提供清单）
+    pub async fn fetch(client: &reqwest::Client, url: &str) -> Option<Self> {
+        let response = client.get(url).send().await.ok()?;
+        if !response.status().is_success() {
+            log::debug!("未找到校验清单 {}: HTTP {}", url, response.status());
+            return None;
+        }
+        let manifest = Self::parse(&response.text().await.ok()?);
+        (!manifest.is_empty()).then_some(manifest)
+    }
+
+    /// 查找 name 的校验值；清单只有一条不带文件名的记录时（单个文件的 `.sha256`）对任意文件生效
+    pub fn sha256_for(&self, name: &str) -> Option<&str> {
+        self.entries
+            .get(name)
+            .or_else(|| match self.entries.len() {
+                1 => self.entries.get(""),
+                _ => None,
+            })
+            .map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 计算文件的 SHA-256（小写十六进制）
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 下载中的部分文件：`<目标>.part`
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// 部分文件对应的校验标识：`<目标>.part.validator`
+fn validator_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part.validator");
+    dest.with_file_name(name)
+}
+
+/// 响应的校验标识，用于续传时的 `If-Range`；弱 ETag 不能用于范围请求，退而使用 `Last-Modified`
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    header(reqwest::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(reqwest::header::LAST_MODIFIED))
+}
+
+/// 删除部分文件及其校验标识
+async fn discard_part(dest: &Path) {
+    tokio::fs::remove_file(part_path(dest)).await.ok();
+    tokio::fs::remove_file(validator_path(dest)).await.ok();
+}
+
+/// 下载 url 到 dest，已有部分文件且记录了校验标识时从断点继续；给出 expected_sha256 时校验完整内容。
+/// 传输中断时保留部分文件供下次续传；校验失败时删除部分文件并返回 [`ChecksumMismatch`]
+pub async fn download_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<()> {
+    use reqwest::StatusCode;
+
+    let part = part_path(dest);
+    let validator_file = validator_path(dest);
+    if let Some(parent) = part.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    loop {
+        let offset = tokio::fs::metadata(&part).await.map_or(0, |m| m.len());
+        let validator = tokio::fs::read_to_string(&validator_file)
+            .await
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        let mut request = client.get(url);
+        let resuming = offset > 0 && validator.is_some();
+        if let (true, Some(validator)) = (resuming, &validator) {
+            log::info!("从 {offset} 字节处继续下载 {url}");
+            request = request
+                .header(reqwest::header::RANGE, format!("bytes={offset}-"))
+                .header(reqwest::header::IF_RANGE, validator.trim());
+        } else if offset > 0 {
+            log::info!("部分文件缺少校验标识，从头下载 {url}");
+        }
+        let mut response = request.send().await?;
+        let status = response.status();
+
+        // 416：部分文件已是完整内容。有校验值时交给校验判断，否则无法确认内容，丢弃后从头下载
+        if resuming && status == StatusCode::RANGE_NOT_SATISFIABLE {
+            if expected_sha256.is_none() {
+                log::warn!("无法确认 {url} 的部分文件是否完整，从头下载");
+                discard_part(dest).await;
+                continue;
+            }
+        } else {
+            if !status.is_success() {
+                return Err(format!("下载 {url} 失败: HTTP {status}").into());
+            }
+            let mut file = if resuming && status == StatusCode::PARTIAL_CONTENT {
+                tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(&part)
+                    .await?
+            } else {
+                // 首次下载，或远端资源已变化、服务器忽略了 Range，返回完整内容
+                if resuming {
+                    log::info!("{url} 已变化或不支持续传，从头下载");
+                }
+                match response_validator(&response) {
+                    Some(v) => tokio::fs::write(&validator_file, v).await?,
+                    None => {
+                        tokio::fs::remove_file(&validator_file).await.ok();
+                    }
+                }
+                tokio::fs::File::create(&part).await?
+            };
+            // 传输出错时也先把已收到的内容落盘，供下次续传
+            let streamed: Result<()> = async {
+                while let Some(chunk) = response.chunk().await? {
+                    file.write_all(&chunk).await?;
+                }
+                Ok(())
+            }
+            .await;
+            file.flush().await?;
+            streamed?;
+        }
+        break;
+    }
+
+    if let Some(expected) = expected_sha256 {
+        let actual = sha256_file(&part)?;
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            discard_part(dest).await;
+            return Err(Box::new(ChecksumMismatch {
+                url: url.to_string(),
+                expected: expected.trim().to_ascii_lowercase(),
+                actual,
+            }));
+        }
+    }
+    tokio::fs::rename(&part, dest).await?;
+    tokio::fs::remove_file(&validator_file).await.ok();
+    Ok(())
+}
+
+/// 最多尝试 attempts 次的 [`download_resumable`]：每次重试都从上一次中断处继续，校验失败不重试
+pub async fn download_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    attempts: u32,
+) -> Result<()> {
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match download_resumable(client, url, dest, expected_sha256).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < attempts && !is_checksum_mismatch(e.as_ref()) => {
+                log::warn!("下载 {url} 失败（第 {attempt}/{attempts} 次）: {e}，将继续下载");
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    fn body() -> Vec<u8> {
+        (0..64 * 1024).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn sha256(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    /// 请求中的 `Range` 与 `If-Range` 头
+    type Seen = Arc<Mutex<Vec<(Option<String>, Option<String>)>>>;
+
+    /// 支持 `Range: bytes=N-` 与 `If-Range` 的最小 HTTP 服务。第 i 个请求由 `versions` 中第 i 个
+    /// （超出时为最后一个）版本 `(内容, ETag)` 应答，`If-Range` 与当前 ETag 不一致时返回完整内容；
+    /// `cut_first` 时第一次应答只发送一半内容后断开。返回地址与每个请求的 Range、If-Range 头
+    fn serve(versions: Vec<(Vec<u8>, &'static str)>, cut_first: bool) -> (String, Seen) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen: Seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let header = |name: &str| {
+                    request.lines().find_map(|l| {
+                        let (key, value) = l.split_once(": ")?;
+                        key.eq_ignore_ascii_case(name).then(|| value.to_string())
+                    })
+                };
+                let (range, if_range) = (header("range"), header("if-range"));
+                log.lock().unwrap().push((range.clone(), if_range.clone()));
+
+                let (content, etag) = &versions[i.min(versions.len() - 1)];
+                let start = range
+                    .as_deref()
+                    .filter(|_| if_range.as_deref().is_none_or(|v| v == *etag))
+                    .and_then(|r| r.strip_prefix("bytes="))
+                    .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok())
+                    .unwrap_or(0);
+                if start >= content.len() && start > 0 {
+                    let head = format!(
+                        "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        content.len()
+                    );
+                    stream.write_all(head.as_bytes()).unwrap();
+                    continue;
+                }
+                let rest = &content[start..];
+                let head = if start > 0 {
+                    format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nETag: {etag}\r\nConnection: close\r\n\r\n",
+                        rest.len(),
+                        start,
+                        content.len() - 1,
+                        content.len()
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: {etag}\r\nConnection: close\r\n\r\n",
+                        rest.len()
+                    )
+                };
+                stream.write_all(head.as_bytes()).unwrap();
+                let sent = if cut_first && i == 0 {
+                    &rest[..rest.len() / 2]
+                } else {
+                    rest
+                };
+                stream.write_all(sent).unwrap();
+                stream.flush().unwrap();
+            }
+        });
+        (format!("http://{addr}/queries.tar.gz"), seen)
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_download_resumes_and_verifies() {
+        let content = body();
+        let (url, seen) = serve(vec![(content.clone(), "\"v1\"")], true);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("queries.tar.gz");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        // 第一次传输中途断开：保留部分文件与校验标识，目标文件不存在
+        let first = download_resumable(&client, &url, &dest, Some(&sha256(&content))).await;
+        assert!(first.is_err());
+        assert!(!dest.exists());
+        let partial = std::fs::metadata(part_path(&dest)).unwrap().len() as usize;
+        assert!(partial > 0 && partial < content.len());
+
+        // 重试带 If-Range 从断点继续
+        download_with_retry(&client, &url, &dest, Some(&sha256(&content)), 2)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+        assert!(!part_path(&dest).exists());
+        assert!(!validator_path(&dest).exists());
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(
+            seen,
+            vec![
+                (None, None),
+                (
+                    Some(format!("bytes={partial}-")),
+                    Some("\"v1\"".to_string())
+                )
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_changed_resource_restarts_from_zero() {
+        let old = body();
+        let new: Vec<u8> = old.iter().rev().copied().collect();
+        let (url, seen) = serve(vec![(old, "\"v1\""), (new.clone(), "\"v2\"")], true);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("queries.tar.gz");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        // 中断后远端资源已更新：If-Range 不匹配，服务器返回 200，不能拼接到旧的部分文件后
+        download_with_retry(&client, &url, &dest, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), new);
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen[1].1.as_deref(), Some("\"v1\""));
+    }
+
+    #[tokio::test]
+    async fn test_unverifiable_complete_part_is_downloaded_again() {
+        let content = body();
+        let (url, seen) = serve(vec![(content.clone(), "\"v1\"")], false);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("queries.tar.gz");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        // 部分文件长度已达到远端大小（416），但没有校验值无法确认内容，丢弃后重新下载
+        let stale = vec![0u8; content.len()];
+        std::fs::write(part_path(&dest), &stale).unwrap();
+        std::fs::write(validator_path(&dest), "\"v1\"").unwrap();
+        download_resumable(&client, &url, &dest, None)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1], (None, None));
+    }
+
+    #[tokio::test]
+    async fn test_part_without_validator_is_not_resumed() {
+        let content = body();
+        let (url, seen) = serve(vec![(content.clone(), "\"v1\"")], false);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("queries.tar.gz");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        std::fs::write(part_path(&dest), b"left over").unwrap();
+        download_resumable(&client, &url, &dest, None)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), content);
+        assert_eq!(seen.lock().unwrap().clone(), vec![(None, None)]);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_download_fails_checksum() {
+        let content = body();
+        let mut corrupted = content.clone();
+        corrupted[1000] ^= 0xff;
+        let (url, seen) = serve(vec![(corrupted, "\"v1\"")], false);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("queries.tar.gz");
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        let err = download_with_retry(&client, &url, &dest, Some(&sha256(&content)), 3)
+            .await
+            .unwrap_err();
+        assert!(is_checksum_mismatch(err.as_ref()));
+        assert!(err.to_string().contains(&sha256(&content)));
+        // 不留下损坏的文件，也不重复下载
+        assert!(!dest.exists());
+        assert!(!part_path(&dest).exists());
+        assert!(!validator_path(&dest).exists());
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_manifest_parse_and_lookup() {
+        let a = "a".repeat(64);
+        let b = "B".repeat(64);
+        let manifest = ChecksumManifest::parse(&format!(
+            "# queries\n{a}  java/highlights.scm\n{b} *python/locals.scm\nnot-a-hash  x.scm\n"
+        ));
+        assert_eq!(manifest.sha256_for("java/highlights.scm"), Some(a.as_str()));
+        assert_eq!(
+            manifest.sha256_for("python/locals.scm"),
+            Some("b".repeat(64).as_str())
+        );
+        assert_eq!(manifest.sha256_for("x.scm"), None);
+
+        // 单文件的 .sha256 只有校验值
+        let single = ChecksumManifest::parse(&format!("{a}\n"));
+        assert_eq!(single.sha256_for("main.zip"), Some(a.as_str()));
+        assert!(ChecksumManifest::parse("").is_empty());
+    }
+}
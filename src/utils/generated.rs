@@ -0,0 +1,118 @@
+//! 生成文件过滤模块
+//!
+//! 生成代码（`*.pb.go`、`*_generated.rs`）、锁文件与压缩后的前端产物会干扰结构分析、
+//! 质量指标、安全扫描与评审。这里维护一份全局的生成文件模式（配置 `[analysis]` 的
+//! `generated_patterns`），命中的文件默认被排除，`include_generated` 中的模式可显式保留。
+//! 模式语法与 [`crate::utils::glob`] 一致。
+
+use super::glob;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// 默认的生成文件模式
+pub const DEFAULT_GENERATED_PATTERNS: &[&str] = &[
+    "**/*.pb.go",
+    "**/*.pb.cc",
+    "**/*.pb.h",
+    "**/*_pb2.py",
+    "**/*_pb2_grpc.py",
+    "**/*_generated.rs",
+    "**/*_generated.go",
+    "**/*.generated.*",
+    "**/*.min.js",
+    "**/*.min.css",
+    "**/*.bundle.js",
+    "**/Cargo.lock",
+    "**/package-lock.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/go.sum",
+    "**/poetry.lock",
+    "**/Pipfile.lock",
+    "**/Gemfile.lock",
+    "**/composer.lock",
+];
+
+lazy_static::lazy_static! {
+    static ref ACTIVE: RwLock<GeneratedFilter> = RwLock::new(GeneratedFilter::default());
+}
+
+/// 生成文件过滤器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFilter {
+    patterns: Vec<String>,
+    include: Vec<String>,
+}
+
+impl Default for GeneratedFilter {
+    fn default() -> Self {
+        Self::new(default_patterns(), Vec::new())
+    }
+}
+
+impl GeneratedFilter {
+    /// `patterns` 命中即视为生成文件，同时命中 `include` 的文件除外
+    pub fn new(patterns: Vec<String>, include: Vec<String>) -> Self {
+        Self { patterns, include }
+    }
+
+    /// 不过滤任何文件
+    pub fn disabled() -> Self {
+        Self::new(Vec::new(), Vec::new())
+    }
+
+    /// 路径是否为需要跳过的生成文件
+    pub fn is_generated(&self, path: &str) -> bool {
+        glob::matches_any(&self.patterns, path) && !glob::matches_any(&self.include, path)
+    }
+}
+
+/// 默认模式的字符串列表（供配置默认值使用）
+pub fn default_patterns() -> Vec<String> {
+    DEFAULT_GENERATED_PATTERNS
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// 设置全局过滤器（通常在加载配置后调用一次）
+pub fn configure(filter: GeneratedFilter) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = filter;
+    }
+}
+
+/// 当前的全局过滤器
+pub fn active() -> GeneratedFilter {
+    ACTIVE.read().map(|f| f.clone()).unwrap_or_default()
+}
+
+/// 路径是否命中全局过滤器
+pub fn is_generated_path(path: &Path) -> bool {
+    ACTIVE
+        .read()
+        .is_ok_and(|filter| filter.is_generated(&path.to_string_lossy()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_patterns_and_include() {
+        let filter = GeneratedFilter::default();
+        assert!(filter.is_generated("api/v1/user.pb.go"));
+        assert!(filter.is_generated("src/schema_generated.rs"));
+        assert!(filter.is_generated("./web/dist/app.min.js"));
+        assert!(filter.is_generated("Cargo.lock"));
+        assert!(filter.is_generated("/work/repo/frontend/package-lock.json"));
+        assert!(!filter.is_generated("src/main.rs"));
+        assert!(!filter.is_generated("web/app.js"));
+
+        let filter = GeneratedFilter::new(default_patterns(), vec!["api/**".to_string()]);
+        assert!(!filter.is_generated("api/v1/user.pb.go"));
+        assert!(filter.is_generated("internal/user.pb.go"));
+
+        assert!(!GeneratedFilter::disabled().is_generated("Cargo.lock"));
+    }
+}
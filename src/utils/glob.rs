@@ -0,0 +1,122 @@
+//! 通配符路径匹配工具模块
+//!
+//! 供忽略列表、扫描 include/exclude 与分析过滤器共用，支持：
+//! - `*`：匹配单个路径段内的任意字符（不跨越 `/`）
+//! - `?`：匹配单个路径段内的任意一个字符
+//! - `**`：作为独立路径段时匹配零个或多个路径段（如 `**/*.rs`、`src/**`）
+//!
+//! 路径与模式中的 `\` 均按 `/` 处理，因此 Windows 风格路径可以直接匹配。
+
+/// 模式中是否包含通配符
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// 判断 path 是否完整匹配 pattern
+///
+/// # Examples
+/// ```
+/// use gitai::utils::glob::glob_match;
+///
+/// assert!(glob_match("**/*.rs", "src/utils/glob.rs"));
+/// assert!(glob_match("src/*.rs", "src/lib.rs"));
+/// assert!(!glob_match("src/*.rs", "src/utils/glob.rs"));
+/// ```
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.replace('\\', "/");
+    let path = path.replace('\\', "/");
+    let pattern: Vec<&str> = segments(&pattern).collect();
+    let path: Vec<&str> = segments(&path).collect();
+    match_segments(&pattern, &path)
+}
+
+/// path 是否匹配任意一个模式
+pub fn matches_any<S: AsRef<str>>(patterns: &[S], path: &str) -> bool {
+    patterns.iter().any(|p| glob_match(p.as_ref(), path))
+}
+
+/// 按 `/` 切分路径段，忽略空段与 `.`
+fn segments(s: &str) -> impl Iterator<Item = &str> {
+    s.split('/').filter(|seg| !seg.is_empty() && *seg != ".")
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((seg, rest)) => match path.split_first() {
+            Some((name, remaining)) => match_segment(seg, name) && match_segments(rest, remaining),
+            None => false,
+        },
+    }
+}
+
+/// 单个路径段内的 `*` / `?` 匹配（贪婪回溯，线性回退）
+fn match_segment(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    p = sp + 1;
+                    n = sn + 1;
+                    star = Some((sp, sn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_segment_wildcards() {
+        assert!(glob_match("*.rs", "lib.rs"));
+        assert!(glob_match("*", "Cargo.toml"));
+        assert!(glob_match("ma?n.rs", "main.rs"));
+        assert!(glob_match("*_test*.rs", "scan_test_utils.rs"));
+        assert!(!glob_match("*.rs", "lib.rs.bak"));
+        assert!(!glob_match("ma?n.rs", "mn.rs"));
+        assert!(!glob_match("*.rs", "src/lib.rs"));
+    }
+
+    #[test]
+    fn test_recursive_wildcard() {
+        assert!(glob_match("**/*.rs", "lib.rs"));
+        assert!(glob_match("**/*.rs", "src/utils/glob.rs"));
+        assert!(glob_match("src/**", "src/a/b/c.rs"));
+        assert!(glob_match("src/**", "src"));
+        assert!(glob_match("src/**/mod.rs", "src/mod.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/a/b/mod.rs"));
+        assert!(glob_match("**/target/**", "crates/core/target/debug/x"));
+        assert!(!glob_match("src/**/mod.rs", "tests/a/mod.rs"));
+        assert!(!glob_match("**/*.rs", "src/main.py"));
+    }
+
+    #[test]
+    fn test_path_separators() {
+        assert!(glob_match("src/**/*.rs", "src\\utils\\glob.rs"));
+        assert!(glob_match("src\\*.rs", "src/lib.rs"));
+        assert!(glob_match("./src/*.rs", "src/lib.rs"));
+        assert!(glob_match("src//*.rs", "src/lib.rs"));
+        assert!(matches_any(&["*.md", "**/*.rs"], "a/b.rs"));
+        assert!(!matches_any::<&str>(&[], "a/b.rs"));
+        assert!(is_glob("**/*.rs"));
+        assert!(!is_glob("src/lib.rs"));
+    }
+}
@@ -2,5 +2,13 @@
 //!
 //! 提供跨模块共用的工具函数和类型
 
+pub mod atomic_write;
+pub mod cancel;
+pub mod download;
 pub mod error_handling;
+pub mod generated;
+pub mod glob;
+pub mod output;
 pub mod paths;
+pub mod report_output;
+pub mod walk;
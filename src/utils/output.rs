@@ -0,0 +1,150 @@
+//! 终端输出样式
+//!
+//! 集中决定是否输出 ANSI 颜色与 emoji：`--no-color` 或非空的 `NO_COLOR` 环境变量关闭颜色
+//! （见 <https://no-color.org>），`--no-emoji` 去掉日志与错误信息中的 emoji。
+//! 所有带颜色的输出都应通过 [`paint`] 生成。
+//!
+//! `--quiet` 关闭进度与状态信息，只保留最终结果；进度信息统一通过 [`progress!`](crate::progress)
+//! 输出，以便管道只拿到结果。
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_COLOR_FLAG: AtomicBool = AtomicBool::new(false);
+static NO_EMOJI_FLAG: AtomicBool = AtomicBool::new(false);
+static QUIET_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// 输出一行进度/状态信息；静默模式下不输出
+///
+/// 用法与 `println!` 相同，文本会按 [`plain`] 去掉颜色或 emoji
+#[macro_export]
+macro_rules! progress {
+    () => {
+        if !$crate::utils::output::quiet() {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        if !$crate::utils::output::quiet() {
+            println!("{}", $crate::utils::output::plain(&format!($($arg)*)));
+        }
+    };
+}
+
+/// 按命令行开关设置输出样式（进程启动时调用一次）
+pub fn init(no_color: bool, no_emoji: bool) {
+    NO_COLOR_FLAG.store(no_color, Ordering::Relaxed);
+    NO_EMOJI_FLAG.store(no_emoji, Ordering::Relaxed);
+}
+
+/// 开启或关闭静默模式（`--quiet`，或结构化输出时由命令自行开启）
+pub fn set_quiet(quiet: bool) {
+    QUIET_FLAG.store(quiet, Ordering::Relaxed);
+}
+
+/// 是否处于静默模式
+pub fn quiet() -> bool {
+    QUIET_FLAG.load(Ordering::Relaxed)
+}
+
+/// 环境变量 `NO_COLOR` 是否要求关闭颜色（空值不生效）
+pub fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+/// 是否输出 ANSI 颜色
+pub fn color_enabled() -> bool {
+    !NO_COLOR_FLAG.load(Ordering::Relaxed) && !no_color_env()
+}
+
+/// 是否保留 emoji
+pub fn emoji_enabled() -> bool {
+    !NO_EMOJI_FLAG.load(Ordering::Relaxed)
+}
+
+/// 用 ANSI 颜色码包裹文本；关闭颜色时原样返回
+pub fn paint<'a>(text: &'a str, ansi: &str) -> Cow<'a, str> {
+    if color_enabled() {
+        Cow::Owned(format!("{ansi}{text}\x1b[0m"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// 按当前设置清理文本：关闭颜色时去掉 ANSI 转义序列，关闭 emoji 时去掉 emoji
+pub fn plain(text: &str) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(text);
+    if !color_enabled() && out.contains('\x1b') {
+        out = Cow::Owned(strip_ansi(&out));
+    }
+    if !emoji_enabled() {
+        out = Cow::Owned(strip_emoji(&out));
+    }
+    out
+}
+
+/// 去掉 ANSI CSI 转义序列（如 `\x1b[31m`）
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            // 参数与中间字节之后以 0x40-0x7E 范围内的字节结束
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// 去掉 emoji 及其后紧跟的空格，保留 CJK 文字与常规标点
+pub fn strip_emoji(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut skip_space = false;
+    for c in text.chars() {
+        if is_emoji(c) {
+            skip_space = true;
+            continue;
+        }
+        if skip_space && c == ' ' {
+            continue;
+        }
+        skip_space = false;
+        out.push(c);
+    }
+    out
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // 表情、符号与象形文字
+            | 0x2600..=0x27BF // 杂项符号与装饰符号（⚠ ✅ ❌ 等）
+            | 0x2B00..=0x2BFF // 箭头与几何符号（⭐ 等）
+            | 0xFE0F // 变体选择符
+            | 0x200D // 零宽连接符
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi_and_emoji() {
+        assert_eq!(strip_ansi("\x1b[32m启用\x1b[0m ok"), "启用 ok");
+        assert_eq!(strip_ansi("no escapes"), "no escapes");
+        assert_eq!(
+            strip_emoji("✅ 已安装 pre-commit 钩子"),
+            "已安装 pre-commit 钩子"
+        );
+        assert_eq!(strip_emoji("⚠️  警告: 中文保留"), "警告: 中文保留");
+        assert_eq!(strip_emoji("💡 提示: a → b"), "提示: a → b");
+    }
+}
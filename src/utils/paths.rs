@@ -98,32 +98,102 @@ pub fn prompts_dir() -> PathBuf {
 
 /// 获取 OpenGrep 规则目录
 pub fn rules_dir() -> PathBuf {
-    cache_dir().join("rules")
+    rules_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的 OpenGrep 规则目录
+pub fn rules_dir_in(cache: &Path) -> PathBuf {
+    cache.join("rules")
+}
+
+/// 获取多来源规则的缓存目录（每个 Git 来源一个子目录，合并结果在 `merged`）
+pub fn rule_sources_dir() -> PathBuf {
+    rule_sources_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的多来源规则目录
+pub fn rule_sources_dir_in(cache: &Path) -> PathBuf {
+    cache.join("rule-sources")
+}
+
+/// 获取依赖图增量构建快照目录
+pub fn graph_cache_dir() -> PathBuf {
+    graph_cache_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的依赖图快照目录
+pub fn graph_cache_dir_in(cache: &Path) -> PathBuf {
+    cache.join("graph")
 }
 
 /// 获取代码评审缓存目录
 pub fn review_cache_dir() -> PathBuf {
-    cache_dir().join("review_cache")
+    review_cache_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的代码评审缓存目录
+pub fn review_cache_dir_in(cache: &Path) -> PathBuf {
+    cache.join("review_cache")
 }
 
 /// 获取扫描历史目录
 pub fn scan_history_dir() -> PathBuf {
-    cache_dir().join("scan_history")
+    scan_history_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的扫描历史目录
+pub fn scan_history_dir_in(cache: &Path) -> PathBuf {
+    cache.join("scan_history")
+}
+
+/// 获取 AI 规则解释缓存目录
+pub fn explain_cache_dir() -> PathBuf {
+    explain_cache_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的 AI 规则解释缓存目录
+pub fn explain_cache_dir_in(cache: &Path) -> PathBuf {
+    cache.join("explain_cache")
 }
 
 /// 获取 Tree-sitter 相关目录
 pub fn tree_sitter_dir() -> PathBuf {
-    cache_dir().join("tree-sitter")
+    tree_sitter_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的 Tree-sitter 相关目录
+pub fn tree_sitter_dir_in(cache: &Path) -> PathBuf {
+    cache.join("tree-sitter")
 }
 
 /// 获取 Tree-sitter 查询文件目录
 pub fn tree_sitter_queries_dir() -> PathBuf {
-    cache_dir().join("tree-sitter-queries")
+    tree_sitter_queries_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的 Tree-sitter 查询文件目录
+pub fn tree_sitter_queries_dir_in(cache: &Path) -> PathBuf {
+    cache.join("tree-sitter-queries")
 }
 
 /// 获取 Tree-sitter 缓存目录
 pub fn tree_sitter_cache_dir() -> PathBuf {
-    cache_dir().join("tree_sitter_cache")
+    tree_sitter_cache_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的 Tree-sitter 缓存目录
+pub fn tree_sitter_cache_dir_in(cache: &Path) -> PathBuf {
+    cache.join("tree_sitter_cache")
+}
+
+/// 获取按提交保存的结构分析结果目录
+pub fn analysis_store_dir() -> PathBuf {
+    analysis_store_dir_in(&cache_dir())
+}
+
+/// 缓存目录 cache 下的结构分析结果目录
+pub fn analysis_store_dir_in(cache: &Path) -> PathBuf {
+    cache.join("analysis_store")
 }
 
 /// 获取默认配置文件路径
@@ -144,6 +214,38 @@ pub fn resolve_config_path(path: &str) -> PathBuf {
     expand_user(path)
 }
 
+/// 解析扫描规则目录：优先使用配置值（支持 ~ 展开），否则使用默认规则目录
+pub fn resolve_rules_dir(configured: Option<&str>) -> PathBuf {
+    match configured.map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => expand_user(dir),
+        None => rules_dir(),
+    }
+}
+
+/// 将路径转换为统一使用 `/` 分隔的字符串
+///
+/// Windows 下的 `\` 分隔符被替换为 `/`，便于与 git 输出、通配符模式及报告中的路径比较。
+///
+/// # Examples
+/// ```
+/// use gitai::utils::paths::to_slash;
+/// use std::path::Path;
+///
+/// assert_eq!(to_slash(Path::new("src\\utils\\paths.rs")), "src/utils/paths.rs");
+/// ```
+pub fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// 相对 root 的 `/` 分隔路径；path 不在 root 下时保留原路径
+pub fn relative_slash(root: &Path, path: &Path) -> String {
+    let relative = to_slash(path.strip_prefix(root).unwrap_or(path));
+    match relative.strip_prefix("./") {
+        Some(stripped) => stripped.to_string(),
+        None => relative,
+    }
+}
+
 /// MCP服务通用路径解析函数
 ///
 /// 支持：
@@ -235,9 +337,49 @@ fn resolve_relative_path(relative_path: &Path, service_name: &str) -> Result<Pat
     ))
 }
 
+/// 在 base 目录下解析 MCP 工具的路径参数：相对路径相对于 base 解析并要求存在，
+/// 绝对路径与 `~` 照常处理；没有 base 时等同于 [`resolve_mcp_path`]
+pub fn resolve_mcp_path_in(
+    base: Option<&Path>,
+    input_path: &str,
+    service_name: &str,
+) -> Result<PathBuf, String> {
+    let Some(base) = base else {
+        return resolve_mcp_path(input_path, service_name);
+    };
+    let expanded = expand_user(input_path);
+    if expanded.is_absolute() {
+        return resolve_mcp_path(input_path, service_name);
+    }
+    let joined = base.join(expanded);
+    if joined.exists() {
+        Ok(joined)
+    } else {
+        Err(format!(
+            "{}: 路径不存在: {}",
+            service_name,
+            joined.display()
+        ))
+    }
+}
+
+/// 路径是否位于 allowed 中的某个目录之下（按规范化后的路径比较，`..` 与符号链接无法绕过）；
+/// allowed 为空时不限制
+pub fn is_path_allowed(path: &Path, allowed: &[String]) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let Ok(path) = std::fs::canonicalize(path) else {
+        return false;
+    };
+    allowed.iter().any(|root| {
+        std::fs::canonicalize(expand_user(root)).is_ok_and(|root| path.starts_with(root))
+    })
+}
+
 /// 获取路径来源描述（用于日志调试）
 pub fn get_path_source(path: &Path) -> &'static str {
-    let path_str = path.to_string_lossy();
+    let path_str = to_slash(path);
 
     if env::var("GITAI_CONFIG_DIR").is_ok() || env::var("GITAI_CACHE_DIR").is_ok() {
         "environment variable"
@@ -254,6 +396,41 @@ mod tests {
     use std::env;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_slash_normalization() {
+        assert_eq!(to_slash(Path::new("src\\a\\b.rs")), "src/a/b.rs");
+        assert_eq!(to_slash(Path::new("src/a/b.rs")), "src/a/b.rs");
+
+        let root = PathBuf::from("project");
+        assert_eq!(
+            relative_slash(&root, &root.join("src").join("lib.rs")),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            relative_slash(&root, Path::new("other/lib.rs")),
+            "other/lib.rs"
+        );
+        assert_eq!(
+            relative_slash(Path::new("."), Path::new("./src/lib.rs")),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            relative_slash(Path::new("x"), Path::new("./src\\lib.rs")),
+            "src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rules_dir() {
+        assert_eq!(
+            resolve_rules_dir(Some("/opt/rules")),
+            PathBuf::from("/opt/rules")
+        );
+        assert!(resolve_rules_dir(Some("~/rules")).is_absolute());
+        assert!(resolve_rules_dir(Some("  ")).ends_with("rules"));
+        assert!(resolve_rules_dir(None).ends_with("rules"));
+    }
+
     #[test]
     fn test_expand_user() {
         // 测试 ~ 单独展开
@@ -313,6 +490,9 @@ mod tests {
         assert_eq!(tree_sitter_dir(), cache.join("tree-sitter"));
         assert_eq!(tree_sitter_queries_dir(), cache.join("tree-sitter-queries"));
         assert_eq!(tree_sitter_cache_dir(), cache.join("tree_sitter_cache"));
+        assert_eq!(graph_cache_dir(), cache.join("graph"));
+        assert_eq!(analysis_store_dir(), cache.join("analysis_store"));
+        assert_eq!(explain_cache_dir(), cache.join("explain_cache"));
         assert_eq!(default_config_file(), config.join("config.toml"));
 
         // 清理环境变量
@@ -368,6 +548,30 @@ mod tests {
         assert_eq!(get_path_source(&legacy_cache), "legacy path");
     }
 
+    #[test]
+    fn test_resolve_mcp_path_in_and_allowlist() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let other = temp_dir.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+
+        let resolved = resolve_mcp_path_in(Some(&repo), "src", "Test").unwrap();
+        assert_eq!(resolved, repo.join("src"));
+        assert!(resolve_mcp_path_in(Some(&repo), "missing", "Test").is_err());
+        let absolute = other.to_string_lossy().to_string();
+        assert_eq!(
+            resolve_mcp_path_in(Some(&repo), &absolute, "Test").unwrap(),
+            other
+        );
+
+        let allowed = vec![repo.to_string_lossy().to_string()];
+        assert!(is_path_allowed(&repo.join("src"), &allowed));
+        assert!(!is_path_allowed(&other, &allowed));
+        assert!(!is_path_allowed(&repo.join("../other"), &allowed));
+        assert!(is_path_allowed(&other, &[]));
+    }
+
     #[test]
     fn test_resolve_mcp_path_absolute() {
         let temp_dir = TempDir::new().unwrap();
@@ -451,8 +655,7 @@ mod tests {
         // 但我们可以测试逻辑结构
         let result = resolve_mcp_path("./test", "Test");
         // 如果当前目录下没有test文件，应该尝试其他策略
-        if result.is_err() {
-            let error = result.unwrap_err();
+        if let Err(error) = result {
             assert!(error.contains("无法解析相对路径"));
         }
     }
@@ -0,0 +1,128 @@
+//! 多目标结果输出
+//!
+//! `--output` 可重复指定，每个文件的格式按扩展名推断（`.json`、`.md`、`.html`、`.txt`），
+//! 无法推断时使用 `--format`。同一次分析的结果按格式各渲染一次后原子写入全部目标，
+//! CI 中可以同时得到用于门禁的 JSON 与给人看的报告而无需重复执行分析。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+type DynError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 输出目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTarget {
+    pub path: PathBuf,
+    pub format: &'static str,
+}
+
+/// 规范化格式名（`md` 视为 `markdown`），未知格式返回 None
+pub fn normalize_format(format: &str) -> Option<&'static str> {
+    match format.to_lowercase().as_str() {
+        "json" => Some("json"),
+        "markdown" | "md" => Some("markdown"),
+        "html" | "htm" => Some("html"),
+        "text" | "txt" => Some("text"),
+        _ => None,
+    }
+}
+
+/// 按扩展名推断格式
+pub fn format_for_path(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?;
+    match extension.to_lowercase().as_str() {
+        "log" => Some("text"),
+        other => normalize_format(other),
+    }
+}
+
+/// 解析输出目标并校验格式；在执行分析之前调用，避免分析完成后才发现格式不受支持
+pub fn resolve_targets(
+    outputs: &[PathBuf],
+    fallback: &str,
+    supported: &[&str],
+) -> Result<Vec<OutputTarget>, DynError> {
+    outputs
+        .iter()
+        .map(|path| {
+            let format = format_for_path(path)
+                .or_else(|| normalize_format(fallback))
+                .filter(|format| supported.contains(format))
+                .ok_or_else(|| {
+                    format!(
+                        "无法确定输出文件 {} 的格式（支持：{}）",
+                        path.display(),
+                        supported.join("、")
+                    )
+                })?;
+            Ok(OutputTarget {
+                path: path.clone(),
+                format,
+            })
+        })
+        .collect()
+}
+
+/// 按格式渲染（每种格式只渲染一次）并原子写入全部目标；render 可返回任意可转换为
+/// 动态错误的错误类型（如 `GitAIError`）
+pub fn write_targets<F, E>(targets: &[OutputTarget], mut render: F) -> Result<(), DynError>
+where
+    F: FnMut(&str) -> Result<String, E>,
+    E: Into<DynError>,
+{
+    let mut rendered: HashMap<&str, String> = HashMap::new();
+    for target in targets {
+        if !rendered.contains_key(target.format) {
+            rendered.insert(target.format, render(target.format).map_err(Into::into)?);
+        }
+        super::atomic_write::write_atomic(&target.path, &rendered[target.format])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_targets_by_extension() {
+        let outputs = vec![
+            PathBuf::from("report.json"),
+            PathBuf::from("report.HTML"),
+            PathBuf::from("summary"),
+        ];
+        let targets = resolve_targets(&outputs, "md", &["json", "markdown", "html"]).unwrap();
+        let formats: Vec<&str> = targets.iter().map(|t| t.format).collect();
+        assert_eq!(formats, vec!["json", "html", "markdown"]);
+
+        let err = resolve_targets(&outputs, "text", &["json", "markdown"]).unwrap_err();
+        assert!(err.to_string().contains("report.HTML"));
+        assert!(resolve_targets(&[PathBuf::from("out")], "yaml", &["json"]).is_err());
+    }
+
+    #[test]
+    fn test_write_targets_renders_each_format_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let outputs = vec![
+            dir.path().join("a.json"),
+            dir.path().join("b.json"),
+            dir.path().join("c.md"),
+        ];
+        let targets = resolve_targets(&outputs, "json", &["json", "markdown"]).unwrap();
+        let mut calls = Vec::new();
+        write_targets(&targets, |format| {
+            calls.push(format.to_string());
+            Ok::<_, crate::error::GitAIError>(format!("rendered {format}"))
+        })
+        .unwrap();
+        assert_eq!(calls, vec!["json", "markdown"]);
+        assert_eq!(
+            std::fs::read_to_string(&outputs[1]).unwrap(),
+            "rendered json"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&outputs[2]).unwrap(),
+            "rendered markdown"
+        );
+    }
+}
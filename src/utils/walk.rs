@@ -0,0 +1,40 @@
+//! 目录遍历选项
+//!
+//! 结构分析、依赖图、质量指标与工程评估在遍历目录时共用这里的全局选项（配置 `[analysis]`
+//! 的 `max_depth`，或全局参数 `--max-depth`），用于限制深层嵌套的 vendored 目录等
+//! 病态仓库的遍历范围；`follow_symlinks` 控制是否跟随符号链接（默认不跟随，
+//! 跟随时按目录的规范路径检测环路）。
+
+use std::sync::RwLock;
+
+lazy_static::lazy_static! {
+    static ref ACTIVE: RwLock<WalkOptions> = RwLock::new(WalkOptions::default());
+}
+
+/// 目录遍历选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// 最大遍历深度：起始目录下的文件深度为 1，每进入一层子目录加 1；None 表示不限制
+    pub max_depth: Option<usize>,
+    /// 是否跟随符号链接；不跟随时符号链接（无论指向文件还是目录）被跳过
+    pub follow_symlinks: bool,
+}
+
+impl WalkOptions {
+    /// 深度为 depth 的条目是否在限制范围内
+    pub fn allows_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+/// 设置全局遍历选项（通常在加载配置后调用一次）
+pub fn configure(options: WalkOptions) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = options;
+    }
+}
+
+/// 当前的全局遍历选项
+pub fn active() -> WalkOptions {
+    ACTIVE.read().map(|o| *o).unwrap_or_default()
+}
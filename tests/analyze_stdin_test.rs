@@ -0,0 +1,53 @@
+//! `gitai analyze --stdin` 集成测试
+//!
+//! 通过标准输入传入代码，验证输出的结构摘要 JSON
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn gitai(home: &std::path::Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gitai"));
+    cmd.env("HOME", home).env("RUST_LOG", "off");
+    cmd
+}
+
+#[test]
+fn test_analyze_stdin_outputs_structural_summary() {
+    let home = tempfile::tempdir().unwrap();
+    let mut child = gitai(home.path())
+        .args(["analyze", "--stdin", "--lang", "rust"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"fn main() {\n    helper();\n}\n\nfn helper() {}\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["language"], "rust");
+    let names: Vec<&str> = summary["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|f| f["name"].as_str())
+        .collect();
+    assert_eq!(names, vec!["main", "helper"]);
+}
+
+#[test]
+fn test_analyze_stdin_requires_lang() {
+    let home = tempfile::tempdir().unwrap();
+    let output = gitai(home.path())
+        .args(["analyze", "--stdin"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
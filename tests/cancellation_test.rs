@@ -0,0 +1,108 @@
+//! 取消令牌集成测试
+//!
+//! 在评审等待 AI 应答、OpenGrep 扫描运行期间取消，操作应及时返回并恢复现场
+
+mod common;
+
+use common::{git, spawn_hanging_ai};
+use gitai::config::Config;
+use gitai::domain::errors::DomainError;
+use gitai::domain::services::{ReviewOptions, ReviewService};
+use gitai::review::DiffSelection;
+use gitai::utils::cancel::{is_cancelled_error, CancellationToken};
+use std::time::{Duration, Instant};
+
+fn cancel_after(token: &CancellationToken, delay: Duration) {
+    let token = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        token.cancel();
+    });
+}
+
+#[tokio::test]
+async fn test_cancel_review_while_waiting_for_ai() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() -> u8 {\n    1\n}\n").unwrap();
+    git(dir, &["add", "lib.rs"]);
+
+    let mut config = Config::default();
+    config.ai.api_url = spawn_hanging_ai();
+    let cwd = std::env::current_dir().unwrap();
+    let token = CancellationToken::new();
+    cancel_after(&token, Duration::from_millis(500));
+
+    let start = Instant::now();
+    let result = ReviewService::new(config)
+        .run_with_cancel(
+            ReviewOptions {
+                repo_path: Some(dir.to_path_buf()),
+                selection: DiffSelection::Staged,
+                ..Default::default()
+            },
+            &token,
+        )
+        .await;
+
+    if cfg!(feature = "ai") {
+        assert!(matches!(result, Err(DomainError::Cancelled)), "{result:?}");
+        assert!(start.elapsed() < Duration::from_secs(20));
+    }
+    assert_eq!(std::env::current_dir().unwrap(), cwd);
+    assert!(!gitai::utils::output::quiet());
+}
+
+#[tokio::test]
+async fn test_cancelled_token_stops_concurrent_analysis() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let paths = vec![std::path::PathBuf::from("src/lib.rs")];
+    let err = gitai::tree_sitter::analyze_paths_concurrent_cancellable(&paths, Some(1), &token)
+        .await
+        .unwrap_err();
+    assert!(is_cancelled_error(err.as_ref()));
+}
+
+#[cfg(all(unix, feature = "security"))]
+#[test]
+fn test_cancel_scan_kills_opengrep() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = tempfile::tempdir().unwrap();
+    let bin = root.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::write(bin.join("opengrep"), "#!/bin/sh\nexec sleep 30\n").unwrap();
+    std::fs::set_permissions(bin.join("opengrep"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    std::env::set_var("PATH", path);
+    std::env::set_var("GITAI_CACHE_DIR", root.path().join("cache"));
+
+    let token = CancellationToken::new();
+    cancel_after(&token, Duration::from_millis(300));
+    let start = Instant::now();
+    let err = gitai::scan::run_opengrep_scan_cancellable(
+        &Config::default(),
+        root.path(),
+        None,
+        None,
+        false,
+        &token,
+    )
+    .unwrap_err();
+    assert!(is_cancelled_error(err.as_ref()), "{err}");
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
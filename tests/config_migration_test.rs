@@ -0,0 +1,89 @@
+//! 配置文件版本迁移测试
+
+use gitai::config::Config;
+use gitai::config_migrate::{self, CURRENT_VERSION};
+
+const V1: &str = include_str!("fixtures/config_v1.toml");
+
+#[test]
+fn test_migrates_v1_fixture() {
+    let migration = config_migrate::migrate(V1).unwrap().expect("v1 需要迁移");
+    assert_eq!(migration.from, 1);
+    assert_eq!(migration.to, CURRENT_VERSION);
+    assert_eq!(
+        migration.changes,
+        vec![
+            "scan.rules_path 更名为 scan.rules_dir".to_string(),
+            "删除空的 ai.api_key".to_string(),
+            "network.proxy 复制到 http.proxy".to_string(),
+        ]
+    );
+
+    let config: Config = toml::from_str(&migration.content).unwrap();
+    assert_eq!(config.version, CURRENT_VERSION);
+    assert_eq!(config.scan.rules_dir.as_deref(), Some("~/rules/opengrep"));
+    assert_eq!(config.ai.api_key, None);
+    assert_eq!(
+        config.http.proxy.as_deref(),
+        Some("http://proxy.company.com:8080")
+    );
+    assert_eq!(config.ai.model, "qwen2.5:32b");
+    config.validate().unwrap();
+
+    // 注释与其余配置保持不变
+    assert!(migration
+        .content
+        .starts_with("# GitAI Configuration File\n"));
+    assert!(migration
+        .content
+        .contains("# OpenGrep rules configuration\n"));
+    assert!(migration
+        .content
+        .contains("[network]\nproxy = \"http://proxy.company.com:8080\""));
+
+    // 迁移后的内容已是当前版本
+    assert!(config_migrate::migrate(&migration.content)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_write_migration_backs_up_original() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, V1).unwrap();
+
+    let migration = config_migrate::migrate(V1).unwrap().unwrap();
+    let backup = config_migrate::write_migration(&path, V1, &migration).unwrap();
+    assert_eq!(backup, dir.path().join("config.toml.v1.backup"));
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), V1);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), migration.content);
+
+    // 再次迁移（如恢复了旧文件）时不覆盖已有备份
+    let restored = format!("{V1}# restored\n");
+    std::fs::write(&path, &restored).unwrap();
+    let (_, second) = config_migrate::migrate_file(&path).unwrap().unwrap();
+    assert_eq!(second, dir.path().join("config.toml.v1.backup.1"));
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), restored);
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), V1);
+    assert!(config_migrate::migrate_file(&path).unwrap().is_none());
+}
+
+#[test]
+fn test_version_detection() {
+    // 没有 version 的旧文件视为版本 1
+    let unversioned = "[ai]\napi_url = \"x\"\nmodel = \"m\"\ntemperature = 0.3\n";
+    let migration = config_migrate::migrate(unversioned).unwrap().unwrap();
+    assert_eq!(migration.from, 1);
+    assert!(migration.changes.is_empty());
+    assert!(migration.content.starts_with("version = 2\n"));
+
+    // 已是当前版本或更新的版本时不迁移
+    assert!(config_migrate::migrate("version = 2\n").unwrap().is_none());
+    assert!(config_migrate::migrate("version = 99\n").unwrap().is_none());
+    assert!(config_migrate::migrate("version = \"banana\"\n").is_err());
+
+    // 内置模板已是当前版本
+    let template = include_str!("../assets/config.enhanced.toml");
+    assert!(config_migrate::migrate(template).unwrap().is_none());
+}
@@ -0,0 +1,57 @@
+//! `gitai explain` 集成测试
+//!
+//! 从 GITAI_CACHE_DIR 下的本地规则缓存中查找规则，找不到时提示更新规则
+
+use std::process::{Command, Output};
+
+const RULE: &str = r#"rules:
+  - id: avoid-eval
+    languages: [python]
+    severity: ERROR
+    message: Detected use of eval()
+    metadata:
+      category: security
+    pattern: eval(...)
+"#;
+
+fn explain(cache: &std::path::Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .env("HOME", cache)
+        .env("GITAI_CACHE_DIR", cache)
+        .env("RUST_LOG", "off")
+        .args(["--lang", "en", "explain"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_explain_prints_local_rule() {
+    let cache = tempfile::tempdir().unwrap();
+    let dir = cache.path().join("rules").join("python").join("security");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("eval.yaml"), RULE).unwrap();
+    std::fs::write(dir.join("eval.py"), "# ruleid: avoid-eval\neval(data)\n").unwrap();
+
+    let output = explain(cache.path(), &["python.security.avoid-eval"]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Severity: ERROR"));
+    assert!(text.contains("Detected use of eval()"));
+    assert!(text.contains("eval(data)"));
+
+    let output = explain(cache.path(), &["avoid-eval", "--format", "json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["id"], "avoid-eval");
+    assert_eq!(json["metadata"]["category"], "security");
+}
+
+#[test]
+fn test_explain_unknown_rule_suggests_update() {
+    let cache = tempfile::tempdir().unwrap();
+    let output = explain(cache.path(), &["no-such-rule"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("gitai update"), "{stderr}");
+}
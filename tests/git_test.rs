@@ -8,6 +8,9 @@
 
 #![allow(clippy::uninlined_format_args, clippy::print_stdout)]
 
+mod common;
+
+use common::git as git_in;
 use gitai::git;
 use std::path::Path;
 
@@ -340,3 +343,319 @@ fn test_git_commit_and_add_operations() {
     // 注意：不测试 git_commit 以避免创建实际的提交
     // 如果需要测试提交功能，应该使用测试仓库
 }
+
+/// 在临时目录中初始化一个带初始提交的仓库
+fn init_temp_repo() -> tempfile::TempDir {
+    let dir = tempfile::TempDir::new().unwrap();
+    git_in(dir.path(), &["init", "-q", "-b", "main"]);
+    git_in(dir.path(), &["config", "user.email", "test@example.com"]);
+    git_in(dir.path(), &["config", "user.name", "test"]);
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        std::fs::write(dir.path().join(name), format!("{name}\n")).unwrap();
+    }
+    git_in(dir.path(), &["add", "."]);
+    git_in(dir.path(), &["commit", "-q", "-m", "init"]);
+    dir
+}
+
+#[test]
+fn test_status_in_temp_repo() {
+    let repo = init_temp_repo();
+    assert!(git::status_in(repo.path()).unwrap().is_clean());
+
+    std::fs::write(repo.path().join("a.txt"), "changed\n").unwrap();
+    std::fs::write(repo.path().join("new file.txt"), "new\n").unwrap();
+    git_in(repo.path(), &["mv", "b.txt", "renamed.txt"]);
+
+    let status = git::status_in(repo.path()).unwrap();
+    assert!(!status.is_clean());
+
+    let unstaged: Vec<_> = status.unstaged().map(|e| e.path.as_str()).collect();
+    assert_eq!(unstaged, vec!["a.txt"]);
+    let untracked: Vec<_> = status.untracked().map(|e| e.path.as_str()).collect();
+    assert_eq!(untracked, vec!["new file.txt"]);
+    let staged: Vec<_> = status.staged().collect();
+    assert_eq!(staged.len(), 1);
+    assert_eq!(staged[0].path, "renamed.txt");
+    assert_eq!(staged[0].orig_path.as_deref(), Some("b.txt"));
+    assert_eq!(staged[0].index, 'R');
+}
+
+#[test]
+fn test_diff_changed_files_in_temp_repo() {
+    let repo = init_temp_repo();
+    assert!(git::diff_changed_files_in(repo.path()).unwrap().is_empty());
+
+    std::fs::write(repo.path().join("a.txt"), "changed\n").unwrap();
+    git_in(repo.path(), &["mv", "b.txt", "renamed.txt"]);
+    git_in(repo.path(), &["rm", "-q", "c.txt"]);
+    std::fs::write(repo.path().join("untracked.txt"), "u\n").unwrap();
+
+    let mut changes = git::diff_changed_files_in(repo.path()).unwrap();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(
+        changes,
+        vec![
+            git::FileChange {
+                path: "a.txt".to_string(),
+                old_path: None,
+                kind: git::ChangeKind::Modified,
+            },
+            git::FileChange {
+                path: "c.txt".to_string(),
+                old_path: None,
+                kind: git::ChangeKind::Deleted,
+            },
+            git::FileChange {
+                path: "renamed.txt".to_string(),
+                old_path: Some("b.txt".to_string()),
+                kind: git::ChangeKind::Renamed,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_git_info_in_temp_repo() {
+    let repo = init_temp_repo();
+    // 只有一个提交时没有 HEAD~1
+    assert!(git::git_info_in(repo.path(), "HEAD~1").is_err());
+
+    std::fs::write(repo.path().join("a.txt"), "second\n").unwrap();
+    git_in(repo.path(), &["commit", "-q", "-am", "second"]);
+
+    let info = git::git_info_in(repo.path(), "HEAD~1").unwrap();
+    assert_eq!(info.current_commit.len(), 40);
+    assert_eq!(info.base_commit.len(), 40);
+    assert_ne!(info.current_commit, info.base_commit);
+    assert_eq!(info.branch.as_deref(), Some("main"));
+
+    let empty = tempfile::TempDir::new().unwrap();
+    git_in(empty.path(), &["init", "-q"]);
+    assert!(git::git_info_in(empty.path(), "HEAD").is_err());
+    assert!(git::diff_changed_files_in(empty.path()).unwrap().is_empty());
+}
+
+fn range(kind: git::LineChangeKind, start: usize, end: usize) -> git::LineRange {
+    git::LineRange { kind, start, end }
+}
+
+#[test]
+fn test_parse_diff_hunks_multi_hunk() {
+    use git::LineChangeKind::{Added, Removed};
+    let diff = "diff --git a/src/lib.rs b/src/lib.rs\n\
+        index 1111111..2222222 100644\n\
+        --- a/src/lib.rs\n\
+        +++ b/src/lib.rs\n\
+        @@ -1,4 +1,5 @@\n \
+        fn a() {}\n\
+        -fn b() {}\n\
+        +fn b(x: u8) {}\n\
+        +fn c() {}\n \
+        fn d() {}\n \
+        fn e() {}\n\
+        @@ -20,3 +21,2 @@ impl Foo {\n \
+        let x = 1;\n\
+        --- not a header\n\
+        -let y = 2;\n\
+        +let y = 3;\n\
+        \\ No newline at end of file\n";
+    let hunks = git::parse_diff_hunks(diff);
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(
+        hunks[Path::new("src/lib.rs")],
+        vec![
+            range(Removed, 2, 2),
+            range(Added, 2, 3),
+            range(Removed, 21, 22),
+            range(Added, 22, 22),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_diff_hunks_additions_and_deletions() {
+    use git::LineChangeKind::{Added, Removed};
+    let diff = "diff --git a/new.rs b/new.rs\n\
+        new file mode 100644\n\
+        --- /dev/null\n\
+        +++ b/new.rs\n\
+        @@ -0,0 +1,3 @@\n\
+        +fn a() {}\n\
+        +fn b() {}\n\
+        +fn c() {}\n\
+        diff --git a/only_add.rs b/only_add.rs\n\
+        --- a/only_add.rs\n\
+        +++ b/only_add.rs\n\
+        @@ -5,0 +6,2 @@\n\
+        +// one\n\
+        +// two\n\
+        diff --git a/old.rs b/old.rs\n\
+        deleted file mode 100644\n\
+        --- a/old.rs\n\
+        +++ /dev/null\n\
+        @@ -1,2 +0,0 @@\n\
+        -fn gone() {}\n\
+        -fn also_gone() {}\n";
+    let hunks = git::parse_diff_hunks(diff);
+    assert_eq!(hunks[Path::new("new.rs")], vec![range(Added, 1, 3)]);
+    assert_eq!(hunks[Path::new("only_add.rs")], vec![range(Added, 6, 7)]);
+    assert_eq!(hunks[Path::new("old.rs")], vec![range(Removed, 1, 2)]);
+}
+
+#[test]
+fn test_parse_diff_hunks_renames_and_binary() {
+    use git::LineChangeKind::{Added, Removed};
+    let diff = "diff --git a/src/old name.rs b/src/new name.rs\n\
+        similarity index 90%\n\
+        rename from src/old name.rs\n\
+        rename to src/new name.rs\n\
+        --- a/src/old name.rs\t\n\
+        +++ b/src/new name.rs\t\n\
+        @@ -3 +3 @@\n\
+        -old\n\
+        +new\n\
+        diff --git a/a.rs b/b.rs\n\
+        similarity index 100%\n\
+        rename from a.rs\n\
+        rename to b.rs\n\
+        diff --git a/logo.png b/logo.png\n\
+        index 1111111..2222222 100644\n\
+        Binary files a/logo.png and b/logo.png differ\n";
+    let hunks = git::parse_diff_hunks(diff);
+    assert_eq!(hunks.len(), 3);
+    assert_eq!(
+        hunks[Path::new("src/new name.rs")],
+        vec![range(Removed, 3, 3), range(Added, 3, 3)]
+    );
+    assert!(hunks[Path::new("b.rs")].is_empty());
+    assert!(!hunks.contains_key(Path::new("a.rs")));
+    assert!(hunks[Path::new("logo.png")].is_empty());
+}
+
+#[test]
+fn test_diff_hunks_in_temp_repo() {
+    use git::LineChangeKind::{Added, Removed};
+    let repo = init_temp_repo();
+    std::fs::write(
+        repo.path().join("long.txt"),
+        (1..=20).map(|i| format!("line {i}\n")).collect::<String>(),
+    )
+    .unwrap();
+    git_in(repo.path(), &["add", "."]);
+    git_in(repo.path(), &["commit", "-q", "-m", "long"]);
+
+    let edited: String = (1..=20)
+        .map(|i| match i {
+            2 => "changed 2\n".to_string(),
+            15 => format!("line {i}\nextra\n"),
+            _ => format!("line {i}\n"),
+        })
+        .collect();
+    std::fs::write(repo.path().join("long.txt"), edited).unwrap();
+    git_in(repo.path(), &["mv", "a.txt", "moved.txt"]);
+    git_in(repo.path(), &["commit", "-q", "-am", "edit"]);
+
+    let hunks = git::diff_hunks_in(repo.path(), "HEAD~1", Some("HEAD")).unwrap();
+    assert_eq!(
+        hunks[Path::new("long.txt")],
+        vec![
+            range(Removed, 2, 2),
+            range(Added, 2, 2),
+            range(Added, 16, 16)
+        ]
+    );
+    assert!(hunks[Path::new("moved.txt")].is_empty());
+    assert!(!hunks.contains_key(Path::new("a.txt")));
+
+    // head 为 None 时对比工作区
+    std::fs::write(repo.path().join("c.txt"), "c.txt\nmore\n").unwrap();
+    let working = git::diff_hunks_in(repo.path(), "HEAD", None).unwrap();
+    assert_eq!(working.len(), 1);
+    assert_eq!(working[Path::new("c.txt")], vec![range(Added, 2, 2)]);
+}
+
+#[test]
+fn test_linked_worktree() {
+    let repo = init_temp_repo();
+    let wt_parent = tempfile::TempDir::new().unwrap();
+    let wt = wt_parent.path().join("feature");
+    git_in(
+        repo.path(),
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            wt.to_str().unwrap(),
+        ],
+    );
+
+    assert_eq!(
+        git::repo_layout_in(repo.path()).unwrap(),
+        git::RepoLayout::WorkTree
+    );
+    assert_eq!(
+        git::repo_layout_in(&wt).unwrap(),
+        git::RepoLayout::LinkedWorktree
+    );
+
+    // 链接工作区中的变更不影响主工作区
+    std::fs::write(wt.join("a.txt"), "changed in worktree\n").unwrap();
+    std::fs::write(wt.join("wt.txt"), "new\n").unwrap();
+    let status = git::status_in(&wt).unwrap();
+    assert_eq!(status.unstaged().count(), 1);
+    assert_eq!(status.untracked().count(), 1);
+    assert!(git::status_in(repo.path()).unwrap().is_clean());
+
+    let changes = git::diff_changed_files_in(&wt).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "a.txt");
+    let hunks = git::diff_hunks_in(&wt, "HEAD", None).unwrap();
+    assert!(hunks.contains_key(Path::new("a.txt")));
+    let mut changed = git::get_changed_files_in(&wt).unwrap();
+    changed.sort();
+    assert_eq!(changed, vec!["a.txt", "wt.txt"]);
+
+    git_in(&wt, &["commit", "-q", "-am", "feature"]);
+    let info = git::git_info_in(&wt, "HEAD~1").unwrap();
+    assert_eq!(info.branch.as_deref(), Some("feature"));
+}
+
+#[test]
+fn test_bare_repo() {
+    let repo = init_temp_repo();
+    std::fs::write(repo.path().join("a.txt"), "second\n").unwrap();
+    git_in(repo.path(), &["commit", "-q", "-am", "second"]);
+    let bare_parent = tempfile::TempDir::new().unwrap();
+    let bare = bare_parent.path().join("repo.git");
+    git_in(
+        bare_parent.path(),
+        &[
+            "clone",
+            "-q",
+            "--bare",
+            repo.path().to_str().unwrap(),
+            bare.to_str().unwrap(),
+        ],
+    );
+
+    assert_eq!(git::repo_layout_in(&bare).unwrap(), git::RepoLayout::Bare);
+    assert!(!git::RepoLayout::Bare.has_work_tree());
+
+    // 需要工作区的操作给出明确错误
+    let err = git::status_in(&bare).unwrap_err().to_string();
+    assert!(err.contains("裸仓库"), "{err}");
+    assert!(git::diff_changed_files_in(&bare).is_err());
+    assert!(git::get_changed_files_in(&bare).is_err());
+    assert!(git::diff_hunks_in(&bare, "HEAD", None).is_err());
+
+    // 提交之间的比较与提交信息不需要工作区
+    let hunks = git::diff_hunks_in(&bare, "HEAD~1", Some("HEAD")).unwrap();
+    assert!(hunks.contains_key(Path::new("a.txt")));
+    assert!(git::git_info_in(&bare, "HEAD~1").is_ok());
+
+    let not_repo = tempfile::TempDir::new().unwrap();
+    assert!(git::repo_layout_in(not_repo.path()).is_err());
+}
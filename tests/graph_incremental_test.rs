@@ -0,0 +1,70 @@
+use gitai::architectural_impact::dependency_graph::DependencyGraph;
+use gitai::architectural_impact::graph_cache::{
+    build_incremental_dependency_graph, IncrementalStats,
+};
+use gitai::architectural_impact::graph_export::build_global_dependency_graph;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// 与顺序无关的图指纹：排序后的节点 ID 与边
+fn shape(graph: &DependencyGraph) -> (Vec<String>, Vec<String>) {
+    let mut nodes: Vec<String> = graph.nodes.keys().cloned().collect();
+    nodes.sort();
+    let mut edges: Vec<String> = graph
+        .edges
+        .iter()
+        .map(|e| format!("{} -{:?}/{}-> {}", e.from, e.edge_type, e.weight, e.to))
+        .collect();
+    edges.sort();
+    (nodes, edges)
+}
+
+#[tokio::test]
+async fn test_incremental_graph_matches_full_rebuild() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let cache = tempfile::tempdir()?;
+    let snapshot = cache.path().join("graph.json");
+    fs::write(dir.path().join("a.rs"), "fn a() { b(); }\n")?;
+    fs::write(dir.path().join("b.rs"), "fn b() {}\n")?;
+    fs::write(dir.path().join("c.rs"), "fn c() { a(); }\n")?;
+
+    // 首次构建：没有快照，全部解析
+    let (graph, stats) = build_incremental_dependency_graph(dir.path(), None, &snapshot).await?;
+    assert_eq!(
+        stats,
+        IncrementalStats {
+            reused: 0,
+            reparsed: 3,
+            removed: 0
+        }
+    );
+    assert_eq!(
+        shape(&graph),
+        shape(&build_global_dependency_graph(dir.path()).await?)
+    );
+
+    // 修改一个文件、删除一个文件、新增一个文件后只重新解析变化的部分
+    fs::write(dir.path().join("b.rs"), "fn b() { d(); }\nfn helper() {}\n")?;
+    fs::remove_file(dir.path().join("c.rs"))?;
+    fs::write(dir.path().join("d.rs"), "fn d() {}\n")?;
+    let (graph, stats) = build_incremental_dependency_graph(dir.path(), None, &snapshot).await?;
+    assert_eq!(
+        stats,
+        IncrementalStats {
+            reused: 1,
+            reparsed: 2,
+            removed: 1
+        }
+    );
+    assert_eq!(
+        shape(&graph),
+        shape(&build_global_dependency_graph(dir.path()).await?)
+    );
+
+    // 没有变化时全部复用
+    let (_, stats) = build_incremental_dependency_graph(dir.path(), None, &snapshot).await?;
+    assert_eq!(stats.reparsed, 0);
+    assert_eq!(stats.reused, 3);
+    Ok(())
+}
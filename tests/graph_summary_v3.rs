@@ -19,19 +19,21 @@ async fn test_budget_truncation_sets_flag() -> Result<(), Box<dyn std::error::Er
     std::env::set_var("GITAI_GRAPH_SUMMARY_MIN_CHAR_BUDGET", "0");
 
     let json = gitai::architectural_impact::graph_export::export_summary_string(
-        dir.path(),  // scan_dir
-        2,           // radius (允许被降级)
-        300,         // top_k (较大，便于降级策略命中)
-        false,       // seeds_from_diff
-        "json",      // format
-        10,          // budget_tokens（极小预算，强制触发降级）
-        false,       // with_communities
-        "labelprop", // comm_alg（无关，本测试禁用社区）
-        50,          // max_communities
-        10,          // max_nodes_per_community
-        false,       // with_paths
-        5,           // path_samples
-        5,           // path_max_hops
+        dir.path(),          // scan_dir
+        2,                   // radius (允许被降级)
+        300,                 // top_k (较大，便于降级策略命中)
+        false,               // seeds_from_diff
+        "json",              // format
+        10,                  // budget_tokens（极小预算，强制触发降级）
+        false,               // with_communities
+        "labelprop",         // comm_alg（无关，本测试禁用社区）
+        50,                  // max_communities
+        10,                  // max_nodes_per_community
+        false,               // with_paths
+        5,                   // path_samples
+        5,                   // path_max_hops
+        &Default::default(), // filter
+        None,                // max_concurrent
     )
     .await?;
 
@@ -45,3 +47,107 @@ async fn test_budget_truncation_sets_flag() -> Result<(), Box<dyn std::error::Er
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_bundle_contains_graph_summary_and_metadata(
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("a.rs"),
+        r#"
+        fn f2() {}
+        fn f1() { f2(); }
+        "#,
+    )?;
+
+    let json = gitai::architectural_impact::graph_export::export_bundle_string(
+        dir.path(),          // scan_dir
+        0.15,                // threshold
+        1,                   // radius
+        50,                  // top_k
+        false,               // seeds_from_diff
+        3000,                // budget_tokens
+        false,               // with_communities
+        "labelprop",         // comm_alg
+        50,                  // max_communities
+        10,                  // max_nodes_per_community
+        false,               // with_paths
+        5,                   // path_samples
+        5,                   // path_max_hops
+        &Default::default(), // filter
+        Some(2),             // max_concurrent
+    )
+    .await?;
+
+    let bundle: gitai::architectural_impact::graph_export::GraphBundle =
+        serde_json::from_str(&json)?;
+    assert_eq!(bundle.metadata.schema_version, 1);
+    assert_eq!(bundle.metadata.threshold, 0.15);
+    assert!(!bundle.metadata.seeds.is_empty());
+    assert!(bundle.graph.nodes.len() >= 2);
+    assert!(bundle
+        .graph
+        .nodes
+        .windows(2)
+        .all(|pair| pair[0].id <= pair[1].id));
+    assert!(bundle.summary.get("graph_stats").is_some());
+    assert_eq!(bundle.summary["radius"], 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repeated_calls_produce_heavier_edge(
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use gitai::architectural_impact::dependency_graph::EdgeType;
+    use gitai::architectural_impact::graph_export::{
+        build_global_dependency_graph, export_dot_string,
+    };
+
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("a.rs"),
+        r#"
+fn hot() {}
+fn cold() {}
+fn main() {
+    hot();
+    hot();
+    hot();
+    cold();
+}
+"#,
+    )?;
+
+    let mut graph = build_global_dependency_graph(dir.path()).await?;
+    let call_weight = |to: &str| {
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Calls && e.to.ends_with(to))
+            .map(|e| e.weight)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(call_weight("::hot"), vec![3.0]);
+    assert_eq!(call_weight("::cold"), vec![1.0]);
+
+    // 加权 PageRank：被频繁调用的节点更重要
+    let pr = graph.calculate_pagerank(0.85, 20, 1e-6);
+    let score = |name: &str| {
+        pr.iter()
+            .find(|(id, _)| id.ends_with(name))
+            .map(|(_, s)| *s)
+            .unwrap()
+    };
+    assert!(score("::hot") > score("::cold"));
+
+    // 阈值过滤弱调用边，保留热点边并按权重加粗
+    let dot = export_dot_string(dir.path(), 0.5, &Default::default(), None).await?;
+    assert!(dot.contains("::hot\" [color=\"#1b4332\", label=\"Calls (w=3.00)\", penwidth=2.58]"));
+    assert!(!dot.contains("::cold\" [color=\"#1b4332\""));
+
+    let dot = export_dot_string(dir.path(), 0.1, &Default::default(), None).await?;
+    assert!(dot.contains("::cold\" [color=\"#1b4332\", label=\"Calls (w=1.00)\", penwidth=1.00]"));
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! 输出语言切换集成测试
+//!
+//! `--lang` 优先于 LANG 环境变量，未指定且无法识别时保持中文
+
+use std::process::Command;
+
+fn config_check(home: &std::path::Path, lang_arg: Option<&str>, lang_env: Option<&str>) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gitai"));
+    cmd.env("HOME", home)
+        .env("RUST_LOG", "off")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("LANG");
+    if let Some(value) = lang_env {
+        cmd.env("LANG", value);
+    }
+    if let Some(lang) = lang_arg {
+        cmd.args(["--lang", lang]);
+    }
+    let output = cmd.args(["config", "check"]).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_lang_flag_and_env_select_output_language() {
+    let home = tempfile::tempdir().unwrap();
+    let home = home.path();
+
+    assert!(config_check(home, None, None).contains("检查配置状态"));
+    assert!(config_check(home, None, Some("C.UTF-8")).contains("检查配置状态"));
+    assert!(config_check(home, None, Some("en_US.UTF-8")).contains("Checking configuration"));
+    assert!(config_check(home, Some("en"), None).contains("Checking configuration"));
+    assert!(config_check(home, Some("zh"), Some("en_US.UTF-8")).contains("检查配置状态"));
+}
@@ -0,0 +1,25 @@
+//! `--json-errors` 输出中的请求 ID 集成测试
+//!
+//! 同一次调用的错误报告与日志共用一个请求 ID
+
+use std::process::Command;
+
+#[test]
+fn test_json_error_includes_request_id() {
+    let home = tempfile::tempdir().unwrap();
+    let missing = home.path().join("missing.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .env("HOME", home.path())
+        .env("RUST_LOG", "off")
+        .args(["--json-errors", "analyze", "--files-from"])
+        .arg(&missing)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let report: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    let request_id = report["request_id"].as_str().unwrap();
+    assert_eq!(request_id.len(), 12);
+    assert!(request_id.chars().all(|c| c.is_ascii_hexdigit()));
+}
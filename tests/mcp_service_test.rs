@@ -10,30 +10,33 @@ mod tests {
 
     /// 创建测试用的 MCP 配置
     fn create_test_mcp_config() -> Config {
-        let mut config = Config::default();
-        config.mcp = Some(McpConfig {
-            enabled: true,
-            server: McpServerConfig {
-                transport: "stdio".to_string(),
-                listen_addr: None,
-                name: "GitAI Test".to_string(),
-                version: "0.1.0".to_string(),
-            },
-            services: McpServicesConfig {
-                enabled: vec![
-                    "analysis".to_string(),
-                    "scan".to_string(),
-                    "review".to_string(),
-                    "commit".to_string(),
-                ],
-                review: None,
-                commit: None,
-                scan: None,
-                analysis: None,
-                dependency: None,
-            },
-        });
-        config
+        Config {
+            mcp: Some(McpConfig {
+                enabled: true,
+                server: McpServerConfig {
+                    transport: "stdio".to_string(),
+                    listen_addr: None,
+                    name: "GitAI Test".to_string(),
+                    version: "0.1.0".to_string(),
+                    allowed_paths: Vec::new(),
+                },
+                services: McpServicesConfig {
+                    enabled: vec![
+                        "analysis".to_string(),
+                        "scan".to_string(),
+                        "review".to_string(),
+                        "commit".to_string(),
+                    ],
+                    review: None,
+                    commit: None,
+                    scan: None,
+                    analysis: None,
+                    dependency: None,
+                },
+                logging: Default::default(),
+            }),
+            ..Default::default()
+        }
     }
 
     #[tokio::test]
@@ -154,24 +157,28 @@ mod tests {
     #[tokio::test]
     async fn test_mcp_disabled_config() {
         // 测试禁用 MCP 时的行为
-        let mut config = Config::default();
-        config.mcp = Some(McpConfig {
-            enabled: false,
-            server: McpServerConfig {
-                transport: "stdio".to_string(),
-                listen_addr: None,
-                name: "GitAI Test".to_string(),
-                version: "0.1.0".to_string(),
-            },
-            services: McpServicesConfig {
-                enabled: vec![],
-                review: None,
-                commit: None,
-                scan: None,
-                analysis: None,
-                dependency: None,
-            },
-        });
+        let config = Config {
+            mcp: Some(McpConfig {
+                enabled: false,
+                server: McpServerConfig {
+                    transport: "stdio".to_string(),
+                    listen_addr: None,
+                    name: "GitAI Test".to_string(),
+                    version: "0.1.0".to_string(),
+                    allowed_paths: Vec::new(),
+                },
+                services: McpServicesConfig {
+                    enabled: vec![],
+                    review: None,
+                    commit: None,
+                    scan: None,
+                    analysis: None,
+                    dependency: None,
+                },
+                logging: Default::default(),
+            }),
+            ..Default::default()
+        };
 
         let manager = GitAiMcpManager::new(config)
             .await
@@ -188,8 +195,10 @@ mod tests {
     #[tokio::test]
     async fn test_mcp_no_config() {
         // 测试没有 MCP 配置时的行为
-        let mut config = Config::default();
-        config.mcp = None;
+        let config = Config {
+            mcp: None,
+            ..Default::default()
+        };
 
         let manager = GitAiMcpManager::new(config)
             .await
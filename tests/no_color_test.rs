@@ -0,0 +1,50 @@
+//! `--no-color` / `NO_COLOR` 集成测试
+//!
+//! 验证关闭颜色后输出中不再包含 ANSI 转义序列
+
+use std::process::Command;
+
+fn features_table(home: &std::path::Path, extra_args: &[&str], no_color_env: bool) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gitai"));
+    cmd.env("HOME", home)
+        .env("RUST_LOG", "off")
+        .env_remove("NO_COLOR")
+        .args(extra_args)
+        .args(["features", "--format", "table"]);
+    if no_color_env {
+        cmd.env("NO_COLOR", "1");
+    }
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_no_color_suppresses_ansi_output() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(features_table(home.path(), &[], false).contains('\x1b'));
+
+    let plain = features_table(home.path(), &["--no-color"], false);
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.contains("启用"));
+    assert!(!features_table(home.path(), &[], true).contains('\x1b'));
+}
+
+#[test]
+fn test_no_emoji_strips_error_output() {
+    let home = tempfile::tempdir().unwrap();
+    let message = home.path().join("msg");
+    std::fs::write(&message, "\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .env("HOME", home.path())
+        .env("RUST_LOG", "off")
+        .args(["--no-emoji", "hooks", "check-message"])
+        .arg(&message)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(10));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("提交信息标题为空"));
+    assert!(!stderr.contains('❌'));
+    assert!(!stderr.contains('💡'));
+}
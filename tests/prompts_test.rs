@@ -0,0 +1,67 @@
+//! `gitai prompts` 集成测试
+//!
+//! 用户提示词目录位于 HOME 下，测试中指向临时目录
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn prompts(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .env("HOME", home)
+        .env("RUST_LOG", "off")
+        .arg("prompts")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_prompts_validate_reports_bad_placeholders() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(prompts(home.path(), &["validate"]).status.success());
+
+    let dir = home.path().join(".config").join("gitai").join("prompts");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("commit.md"), "生成提交信息：{changes}\n").unwrap();
+    std::fs::write(dir.join("custom.md"), "{whatever}\n").unwrap();
+
+    let output = prompts(home.path(), &["validate"]);
+    assert!(!output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("❌ commit"), "{text}");
+    assert!(text.contains("{changes}"));
+    assert!(text.contains("{diff}"));
+    assert!(text.contains("✅ review"));
+    assert!(text.contains("➖ custom"));
+}
+
+#[test]
+fn test_prompts_update_keeps_user_changes() {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join(".config").join("gitai").join("prompts");
+
+    let output = prompts(home.path(), &["update"]);
+    assert!(output.status.success());
+    assert!(dir.join("commit.md").exists());
+    assert!(dir.join("review.md").exists());
+
+    std::fs::write(dir.join("review.md"), "本地评审模板 {diff}\n").unwrap();
+    let output = prompts(home.path(), &["update"]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("commit: 已是最新"), "{text}");
+    assert!(text.contains("review: 已跳过"), "{text}");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("review.md")).unwrap(),
+        "本地评审模板 {diff}\n"
+    );
+    assert!(dir.join("review.md.new").exists());
+
+    let output = prompts(home.path(), &["update", "--force"]);
+    assert!(output.status.success());
+    assert!(dir.join("review.md.bak").exists());
+    assert_ne!(
+        std::fs::read_to_string(dir.join("review.md")).unwrap(),
+        "本地评审模板 {diff}\n"
+    );
+}
@@ -0,0 +1,44 @@
+//! `--quiet` 集成测试
+//!
+//! 静默模式下不输出进度信息，只输出最终结果
+
+mod common;
+
+use common::git;
+use std::path::Path;
+use std::process::Command;
+
+fn dry_run_commit(dir: &Path, home: &Path, quiet: bool) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_gitai"));
+    cmd.current_dir(dir)
+        .env("HOME", home)
+        .env("RUST_LOG", "off")
+        .args(["--lang", "zh"]);
+    if quiet {
+        cmd.arg("--quiet");
+    }
+    let output = cmd
+        .args(["commit", "--dry-run", "-m", "fix: tweak"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_quiet_commit_dry_run_prints_only_message() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    std::fs::write(dir.join("a.txt"), "a\nb\n").unwrap();
+
+    let verbose = dry_run_commit(dir, home.path(), false);
+    assert!(verbose.contains("干运行模式"));
+    assert_eq!(dry_run_commit(dir, home.path(), true), "fix: tweak\n");
+}
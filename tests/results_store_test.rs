@@ -0,0 +1,66 @@
+//! 持久化分析结果按被分析文件所在的仓库与提交区分，而不是进程的工作目录
+
+mod common;
+
+use common::git;
+use gitai::tree_sitter::results_store::ResultsStore;
+use gitai::tree_sitter::{StructuralSummary, SupportedLanguage};
+
+/// 含一次提交的临时仓库，lib.rs 的内容在各仓库中相同
+fn repo(message: &str) -> tempfile::TempDir {
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", message]);
+    repo
+}
+
+#[test]
+fn test_results_are_keyed_by_the_analyzed_repo() {
+    let root = tempfile::tempdir().unwrap();
+    let store = ResultsStore::under(root.path());
+    let first = repo("first");
+    let second = repo("second");
+    let cwd = std::env::current_dir().unwrap();
+    assert!(!first.path().starts_with(&cwd));
+
+    let key = ResultsStore::key("pub fn a() {}\n", SupportedLanguage::Rust, 1024);
+    let summary = StructuralSummary {
+        language: "rust".to_string(),
+        imports: vec!["from_first".to_string()],
+        ..Default::default()
+    };
+    let first_file = first.path().join("lib.rs");
+    store.put(&first_file, &key, &summary);
+    assert_eq!(
+        store.get(&first_file, &key).unwrap().imports,
+        vec!["from_first"]
+    );
+    // 另一个检出中内容相同的文件不会读到第一个仓库的结果
+    assert!(store.get(&second.path().join("lib.rs"), &key).is_none());
+
+    // 结果保存在第一个仓库 HEAD 对应的目录下
+    let head = std::process::Command::new("git")
+        .current_dir(first.path())
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .unwrap();
+    let head = String::from_utf8(head.stdout).unwrap();
+    let names = |dir: &std::path::Path| -> Vec<std::path::PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect()
+    };
+    let versions = names(root.path());
+    assert_eq!(versions.len(), 1);
+    let dirs = names(&versions[0]);
+    assert_eq!(dirs.len(), 1);
+    assert!(dirs[0]
+        .to_string_lossy()
+        .ends_with(&format!("-{}", head.trim())));
+}
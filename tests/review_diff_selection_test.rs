@@ -0,0 +1,93 @@
+//! `gitai review --staged / --working / --stash / --blob` 集成测试
+//!
+//! 在临时仓库中通过 `--plan` 验证显式选择的变更范围，不调用 AI
+
+mod common;
+
+use common::git;
+use std::path::Path;
+use std::process::Command;
+
+fn plan_review(dir: &Path, home: &Path, flags: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .current_dir(dir)
+        .env("HOME", home)
+        .env("RUST_LOG", "off")
+        .arg("review")
+        .args(flags)
+        .args(["--plan", "--format", "json"])
+        .output()
+        .unwrap()
+}
+
+fn planned_files(dir: &Path, home: &Path, flags: &[&str]) -> Vec<String> {
+    let output = plan_review(dir, home, flags);
+    assert!(output.status.success());
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    plan["scope"]["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f.as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_review_staged_and_working_select_diff() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("a.rs"), "fn a() {}\n").unwrap();
+    std::fs::write(dir.join("b.rs"), "fn b() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+
+    // a.rs 已暂存，b.rs 仅修改，c.rs 未跟踪
+    std::fs::write(dir.join("a.rs"), "fn a() { 1; }\n").unwrap();
+    git(dir, &["add", "a.rs"]);
+    std::fs::write(dir.join("b.rs"), "fn b() { 2; }\n").unwrap();
+    std::fs::write(dir.join("c.rs"), "fn c() {}\n").unwrap();
+
+    assert_eq!(planned_files(dir, home.path(), &["--staged"]), vec!["a.rs"]);
+    assert_eq!(
+        planned_files(dir, home.path(), &["--working"]),
+        vec!["a.rs", "b.rs", "c.rs"]
+    );
+}
+
+#[test]
+fn test_review_stash_and_blob() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("a.rs"), "fn a() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+
+    // 贮藏 a.rs 的修改与未跟踪的 c.rs，工作区恢复干净
+    std::fs::write(dir.join("a.rs"), "fn a() { 1; }\n").unwrap();
+    std::fs::write(dir.join("c.rs"), "fn c() {}\n").unwrap();
+    git(dir, &["stash", "-u", "-q"]);
+
+    assert_eq!(
+        planned_files(dir, home.path(), &["--stash", "0"]),
+        vec!["a.rs", "c.rs"]
+    );
+    assert_eq!(
+        planned_files(dir, home.path(), &["--blob", "HEAD:a.rs"]),
+        vec!["a.rs"]
+    );
+
+    let missing = plan_review(dir, home.path(), &["--stash", "1"]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("stash@{1}"));
+    let missing = plan_review(dir, home.path(), &["--blob", "HEAD:b.rs"]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("b.rs"));
+}
@@ -0,0 +1,246 @@
+//! `ReviewService::run` 集成测试
+//!
+//! 在临时仓库中评审已暂存的变更，AI 请求由本地的 OpenAI 兼容桩服务应答
+
+mod common;
+
+use common::{git, spawn_stub};
+use gitai::config::Config;
+use gitai::domain::services::{ReviewOptions, ReviewService};
+use gitai::review::DiffSelection;
+
+const AI_REPLY: &str = "评分: 92\n变更很小，未发现问题";
+
+/// 启动只返回固定内容的 chat/completions 桩服务，返回其地址
+fn spawn_ai_stub() -> String {
+    spawn_stub(|_| AI_REPLY.to_string())
+}
+
+#[tokio::test]
+async fn test_review_service_runs_full_review() {
+    let home = tempfile::tempdir().unwrap();
+    // 评审缓存位于 HOME 下，隔离后每次都会真正调用 AI
+    std::env::set_var("HOME", home.path());
+
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() -> u8 {\n    1\n}\n").unwrap();
+    git(dir, &["add", "lib.rs"]);
+
+    let mut config = Config::default();
+    config.ai.api_url = spawn_ai_stub();
+    let cwd = std::env::current_dir().unwrap();
+
+    let report = ReviewService::new(config)
+        .run(ReviewOptions {
+            repo_path: Some(dir.to_path_buf()),
+            selection: DiffSelection::Staged,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(std::env::current_dir().unwrap(), cwd);
+    assert!(report.result.success);
+    assert_eq!(report.ai_review, cfg!(feature = "ai"));
+    assert!(!report.security_scan);
+    if report.ai_review {
+        assert_eq!(report.result.details["review_result"], AI_REPLY);
+        assert_eq!(report.result.score, Some(92));
+    }
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["result"]["details"]["semver_bump"], "major");
+}
+
+#[tokio::test]
+async fn test_review_service_chunks_large_diff() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("README"), "init\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    for n in 0..10 {
+        let name = format!("m{n}.rs");
+        let body: String = (0..30)
+            .map(|i| format!("pub const V{i}: u32 = {i};\n"))
+            .collect();
+        std::fs::write(dir.join(name), body).unwrap();
+    }
+    git(dir, &["add", "."]);
+
+    let mut config = Config::default();
+    config.ai.api_url = spawn_ai_stub();
+
+    let report = ReviewService::new(config)
+        .run(ReviewOptions {
+            repo_path: Some(dir.to_path_buf()),
+            selection: DiffSelection::Staged,
+            max_diff_tokens: Some(1000),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let details = &report.result.details;
+    assert_eq!(details["diff_summarization"], "chunked");
+    let chunks: usize = details["diff_chunks"].parse().unwrap();
+    assert!((2..=gitai::review::chunking::MAX_CHUNKS).contains(&chunks));
+    assert!(report.result.summarization_note().is_some());
+    assert!(report.result.to_markdown().contains("> "));
+
+    // 上限过小时分段过多，退化为仅结构分析（使用默认配置，若调用 AI 会因无服务而失败）
+    let report = ReviewService::new(Config::default())
+        .run(ReviewOptions {
+            repo_path: Some(dir.to_path_buf()),
+            selection: DiffSelection::Staged,
+            max_diff_tokens: Some(10),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        report.result.details["diff_summarization"],
+        "structural_only"
+    );
+    assert!(report.result.summary.contains("未调用 AI 评审"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_reviews_use_their_own_repo() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+
+    let repos: Vec<_> = ["alpha", "beta"]
+        .into_iter()
+        .map(|name| {
+            let repo = tempfile::tempdir().unwrap();
+            let dir = repo.path();
+            git(dir, &["init", "-q"]);
+            git(dir, &["config", "user.email", "test@example.com"]);
+            git(dir, &["config", "user.name", "test"]);
+            std::fs::write(dir.join("README"), "init\n").unwrap();
+            git(dir, &["add", "."]);
+            git(dir, &["commit", "-q", "-m", "init"]);
+            std::fs::write(dir.join(format!("{name}.rs")), "pub fn f() {}\n").unwrap();
+            git(dir, &["add", "."]);
+            (name, repo)
+        })
+        .collect();
+
+    // 回显请求体，评审结果中因此包含发送给 AI 的 diff
+    let mut config = Config::default();
+    config.ai.api_url = spawn_stub(str::to_string);
+    let cwd = std::env::current_dir().unwrap();
+
+    let handles: Vec<_> = repos
+        .iter()
+        .map(|(name, repo)| {
+            let config = config.clone();
+            let review_config = gitai::review::ReviewConfig::builder()
+                .format("json")
+                .diff_selection(DiffSelection::Staged)
+                .repo_path(repo.path())
+                .build();
+            let name = name.to_string();
+            tokio::spawn(async move {
+                let result = gitai::review::execute_review_with_result(&config, review_config)
+                    .await
+                    .unwrap();
+                (name, result)
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let (name, result) = handle.await.unwrap();
+        assert!(result.success);
+        if cfg!(feature = "ai") {
+            let echoed = &result.details["review_result"];
+            let other = if name == "alpha" { "beta" } else { "alpha" };
+            assert!(echoed.contains(&format!("{name}.rs")), "{name}");
+            assert!(!echoed.contains(&format!("{other}.rs")), "{name}");
+        }
+    }
+    assert_eq!(std::env::current_dir().unwrap(), cwd);
+}
+
+/// 创建带一次提交的仓库，并暂存对 lib.rs 的同一处修改
+fn repo_with_staged_change() -> tempfile::TempDir {
+    let repo = tempfile::tempdir().unwrap();
+    let dir = repo.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "test"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() {}\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    std::fs::write(dir.join("lib.rs"), "pub fn a() -> u8 {\n    1\n}\n").unwrap();
+    git(dir, &["add", "lib.rs"]);
+    repo
+}
+
+#[tokio::test]
+async fn test_review_of_other_repo_does_not_reuse_results() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+
+    // 两个仓库的 diff 完全相同，且都不是测试进程的当前目录
+    let first = repo_with_staged_change();
+    let second = repo_with_staged_change();
+    let cwd = std::env::current_dir().unwrap();
+    assert!(!first.path().starts_with(&cwd));
+
+    let mut config = Config::default();
+    config.ai.api_url = spawn_ai_stub();
+    for repo in [&first, &second] {
+        let review_config = gitai::review::ReviewConfig::builder()
+            .format("json")
+            .diff_selection(DiffSelection::Staged)
+            .repo_path(repo.path())
+            .build();
+        let result = gitai::review::execute_review_with_result(&config, review_config)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(!result.message.contains("缓存"), "{}", result.message);
+    }
+}
+
+#[test]
+fn test_git_state_analyzer_reads_the_given_repo() {
+    let repo = repo_with_staged_change();
+    let dir = repo.path();
+    git(dir, &["commit", "-q", "-m", "change"]);
+
+    let analyzer = gitai::architectural_impact::GitStateAnalyzer::in_repo(dir);
+    assert_eq!(analyzer.get_changed_files().unwrap(), vec!["lib.rs"]);
+    assert_eq!(
+        analyzer
+            .get_file_content_at_commit("lib.rs", "HEAD~1")
+            .unwrap(),
+        "pub fn a() {}\n"
+    );
+    let info = analyzer.get_git_info().unwrap();
+    let head = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        info.current_commit,
+        String::from_utf8_lossy(&head.stdout).trim()
+    );
+}
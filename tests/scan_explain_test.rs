@@ -0,0 +1,98 @@
+//! `gitai scan --explain` 集成测试
+//!
+//! 用输出固定结果的 opengrep 替身执行扫描：离线时退化为规则说明，已缓存的解释直接复用
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const FAKE_OPENGREP: &str = r#"#!/bin/sh
+if [ "$1" = "--version" ]; then echo 1.0.0; exit 0; fi
+cat <<'JSON'
+{"results":[{"check_id":"python.avoid-eval","path":"app.py","start":{"line":3,"col":1},"severity":"ERROR","lines":"eval(data)","extra":{"message":"Detected eval","fix":"ast.literal_eval(data)"}}],"errors":[]}
+JSON
+"#;
+
+fn scan(root: &Path, args: &[&str]) -> Output {
+    let bin = root.join("bin");
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    Command::new(env!("CARGO_BIN_EXE_gitai"))
+        .current_dir(root.join("src"))
+        .env("PATH", path)
+        .env("HOME", root)
+        .env("GITAI_CACHE_DIR", root.join("cache"))
+        .env("RUST_LOG", "off")
+        .args(["--lang", "en", "--offline", "scan", "--no-history"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn setup() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    let bin = root.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::write(bin.join("opengrep"), FAKE_OPENGREP).unwrap();
+    std::fs::set_permissions(bin.join("opengrep"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let src = root.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("app.py"), "import os\n\neval(data)\n").unwrap();
+    root
+}
+
+#[test]
+fn test_explain_offline_falls_back_to_rule() {
+    let root = setup();
+    let output = scan(root.path(), &["--explain", "1", "--format", "json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["source"], "rule");
+    assert!(json["explanation"]
+        .as_str()
+        .unwrap()
+        .contains("ast.literal_eval(data)"));
+    assert!(json["context"]
+        .as_str()
+        .unwrap()
+        .contains("> 3 | eval(data)"));
+
+    let output = scan(root.path(), &["--explain", "ffffffffffff"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_explain_reuses_cached_explanation() {
+    let root = setup();
+    let listing = scan(root.path(), &["--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&listing.stdout).unwrap();
+    let fingerprint = json["findings"][0]["fingerprint"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(fingerprint.len(), 12);
+
+    let cache = root.path().join("cache").join("explain_cache");
+    std::fs::create_dir_all(&cache).unwrap();
+    std::fs::write(
+        cache.join(format!("{fingerprint}_en.json")),
+        serde_json::json!({
+            "fingerprint": fingerprint,
+            "language": "en",
+            "explanation": "cached advice",
+            "created_at": "2026-01-01T00:00:00Z",
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = scan(root.path(), &["--explain", &fingerprint[..6]]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("(cached)"), "{text}");
+    assert!(text.contains("cached advice"));
+}